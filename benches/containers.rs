@@ -0,0 +1,294 @@
+//! 有序容器和标准库 BTreeMap 的性能对比
+//!
+//! 分别使用均匀分布和 Zipf 分布(大量重复的热点 key)的 key 测试插入、查找、删除和遍历
+//!
+//! `bptree_build` 分组对比 B+ 树逐个插入有序 key 和 [`BPlusTree::from_sorted_iter`] 批量构建
+//!
+//! `arena/*` 分组在相同的插入、查找、删除负载下对比 Arena 版本的 AVL 树和指针版本的 AVL 树
+//!
+//! ```text
+//! cargo bench --bench containers
+//! ```
+
+use std::collections::BTreeMap;
+
+use criterion::measurement::WallTime;
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkGroup, Criterion};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use impx::arc_cache::zipf_trace;
+use impx::arena::ArenaAVLTree;
+use impx::avl_tree::AVLTree;
+use impx::b_plus_tree::BPlusTree;
+use impx::rb_tree::RBTree;
+use impx::skip_list::SkipList;
+use impx::treap::Treap;
+
+const N: usize = 10_000;
+
+/// 统一各个容器的接口
+trait Set {
+    const NAME: &'static str;
+
+    fn new() -> Self;
+    fn insert(&mut self, key: u64);
+    fn contains(&self, key: &u64) -> bool;
+    fn delete(&mut self, key: &u64);
+    fn sum(&self) -> u64;
+}
+
+impl Set for RBTree<u64> {
+    const NAME: &'static str = "RBTree";
+
+    fn new() -> Self {
+        RBTree::new()
+    }
+
+    fn insert(&mut self, key: u64) {
+        RBTree::insert(self, key);
+    }
+
+    fn contains(&self, key: &u64) -> bool {
+        RBTree::contains(self, key)
+    }
+
+    fn delete(&mut self, key: &u64) {
+        RBTree::delete(self, key);
+    }
+
+    fn sum(&self) -> u64 {
+        self.iter().sum()
+    }
+}
+
+impl Set for AVLTree<u64> {
+    const NAME: &'static str = "AVLTree";
+
+    fn new() -> Self {
+        AVLTree::new()
+    }
+
+    fn insert(&mut self, key: u64) {
+        AVLTree::insert(self, key);
+    }
+
+    fn contains(&self, key: &u64) -> bool {
+        AVLTree::contains(self, key)
+    }
+
+    fn delete(&mut self, key: &u64) {
+        AVLTree::delete(self, key);
+    }
+
+    fn sum(&self) -> u64 {
+        self.iter().sum()
+    }
+}
+
+impl Set for ArenaAVLTree<u64> {
+    const NAME: &'static str = "ArenaAVLTree";
+
+    fn new() -> Self {
+        ArenaAVLTree::new()
+    }
+
+    fn insert(&mut self, key: u64) {
+        ArenaAVLTree::insert(self, key);
+    }
+
+    fn contains(&self, key: &u64) -> bool {
+        ArenaAVLTree::contains(self, key)
+    }
+
+    fn delete(&mut self, key: &u64) {
+        ArenaAVLTree::delete(self, key);
+    }
+
+    fn sum(&self) -> u64 {
+        self.iter().sum()
+    }
+}
+
+impl Set for Treap<u64> {
+    const NAME: &'static str = "Treap";
+
+    fn new() -> Self {
+        Treap::new()
+    }
+
+    fn insert(&mut self, key: u64) {
+        Treap::insert(self, key);
+    }
+
+    fn contains(&self, key: &u64) -> bool {
+        Treap::contains(self, key)
+    }
+
+    fn delete(&mut self, key: &u64) {
+        Treap::delete(self, key);
+    }
+
+    fn sum(&self) -> u64 {
+        self.iter().sum()
+    }
+}
+
+impl Set for SkipList<16, u64, ()> {
+    const NAME: &'static str = "SkipList";
+
+    fn new() -> Self {
+        SkipList::new()
+    }
+
+    fn insert(&mut self, key: u64) {
+        SkipList::insert(self, key, ());
+    }
+
+    fn contains(&self, key: &u64) -> bool {
+        self.find(key).is_some()
+    }
+
+    fn delete(&mut self, key: &u64) {
+        SkipList::delete(self, key);
+    }
+
+    fn sum(&self) -> u64 {
+        self.iter().map(|(k, _)| k).sum()
+    }
+}
+
+impl Set for BTreeMap<u64, ()> {
+    const NAME: &'static str = "BTreeMap";
+
+    fn new() -> Self {
+        BTreeMap::new()
+    }
+
+    fn insert(&mut self, key: u64) {
+        BTreeMap::insert(self, key, ());
+    }
+
+    fn contains(&self, key: &u64) -> bool {
+        self.contains_key(key)
+    }
+
+    fn delete(&mut self, key: &u64) {
+        self.remove(key);
+    }
+
+    fn sum(&self) -> u64 {
+        self.keys().sum()
+    }
+}
+
+fn build<S: Set>(keys: &[u64]) -> S {
+    let mut set = S::new();
+    for &key in keys {
+        set.insert(key);
+    }
+    set
+}
+
+#[derive(Clone, Copy)]
+enum Op {
+    Insert,
+    Lookup,
+    Delete,
+    Iterate,
+}
+
+fn run<S: Set>(group: &mut BenchmarkGroup<WallTime>, op: Op, keys: &[u64]) {
+    match op {
+        Op::Insert => {
+            group.bench_function(S::NAME, |b| b.iter(|| build::<S>(black_box(keys))));
+        }
+        Op::Lookup => {
+            let set = build::<S>(keys);
+            group.bench_function(S::NAME, |b| {
+                b.iter(|| keys.iter().filter(|k| set.contains(k)).count())
+            });
+        }
+        Op::Delete => {
+            group.bench_function(S::NAME, |b| {
+                b.iter_batched(
+                    || build::<S>(keys),
+                    |mut set| {
+                        for key in keys {
+                            set.delete(key);
+                        }
+                        set
+                    },
+                    BatchSize::LargeInput,
+                )
+            });
+        }
+        Op::Iterate => {
+            let set = build::<S>(keys);
+            group.bench_function(S::NAME, |b| b.iter(|| black_box(set.sum())));
+        }
+    }
+}
+
+fn bench_containers(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(42);
+    let uniform: Vec<u64> = (0..N).map(|_| rng.gen_range(0..N as u64 * 4)).collect();
+    let skewed = zipf_trace(N, 1.0, N, 42);
+
+    let ops = [
+        ("insert", Op::Insert),
+        ("lookup", Op::Lookup),
+        ("delete", Op::Delete),
+        ("iterate", Op::Iterate),
+    ];
+    for (dist, keys) in [("uniform", &uniform), ("zipf", &skewed)] {
+        for (name, op) in ops {
+            let mut group = c.benchmark_group(format!("{}/{}", name, dist));
+            group.sample_size(20);
+            run::<RBTree<u64>>(&mut group, op, keys);
+            run::<AVLTree<u64>>(&mut group, op, keys);
+            run::<Treap<u64>>(&mut group, op, keys);
+            run::<SkipList<16, u64, ()>>(&mut group, op, keys);
+            run::<BTreeMap<u64, ()>>(&mut group, op, keys);
+            group.finish();
+        }
+    }
+}
+
+fn bench_arena(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(42);
+    let keys: Vec<u64> = (0..N).map(|_| rng.gen_range(0..N as u64 * 4)).collect();
+
+    let ops = [
+        ("insert", Op::Insert),
+        ("lookup", Op::Lookup),
+        ("delete", Op::Delete),
+    ];
+    for (name, op) in ops {
+        let mut group = c.benchmark_group(format!("arena/{}", name));
+        group.sample_size(20);
+        run::<AVLTree<u64>>(&mut group, op, &keys);
+        run::<ArenaAVLTree<u64>>(&mut group, op, &keys);
+        group.finish();
+    }
+}
+
+fn bench_bptree_build(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bptree_build");
+    group.bench_function("insert_loop", |b| {
+        b.iter(|| {
+            let mut t = BPlusTree::new(16);
+            for i in 0..N as u64 {
+                t.insert((i, i));
+            }
+            t
+        })
+    });
+    group.bench_function("from_sorted_iter", |b| {
+        b.iter(|| BPlusTree::from_sorted_iter((0..N as u64).map(|i| (i, i)), 16))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_containers, bench_arena, bench_bptree_build);
+criterion_main!(benches);
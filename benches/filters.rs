@@ -0,0 +1,72 @@
+//! 布隆过滤器、计数布隆过滤器和布谷鸟过滤器在相同空间下的对比
+//!
+//! 先打印每种过滤器实际测得的假阳性率, 再测量插入和查询的吞吐
+//!
+//! ```text
+//! cargo bench --bench filters
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+
+use impx::bloom_filter::{BloomFilter, CountingBloomFilter};
+use impx::cuckoo_filter::CuckooFilter;
+use impx::filter::{false_positive_rate, Filter};
+
+const N: usize = 2000;
+
+/// 创建一个空的过滤器
+type FilterFactory = fn() -> Box<dyn Filter>;
+
+/// 三种过滤器都占用 4096 字节
+fn filters() -> Vec<(&'static str, FilterFactory)> {
+    vec![
+        ("bloom", || Box::new(BloomFilter::new(4096 * 8, 7))),
+        ("counting", || Box::new(CountingBloomFilter::new(4096, 2))),
+        ("cuckoo", || Box::new(CuckooFilter::new())),
+    ]
+}
+
+fn bench_filters(c: &mut Criterion) {
+    for (name, new) in filters() {
+        let report = false_positive_rate(new().as_mut(), N, 100_000, 0x5eed);
+        println!("{name}: false positive rate {:.4}%", report.rate() * 100.0);
+    }
+
+    let keys: Vec<[u8; 8]> = (0..N as u64).map(|i| i.to_le_bytes()).collect();
+
+    let mut group = c.benchmark_group("filter_insert");
+    for (name, new) in filters() {
+        group.bench_function(name, |b| {
+            b.iter_batched(
+                new,
+                |mut filter| {
+                    for key in &keys {
+                        filter.insert(key);
+                    }
+                    filter
+                },
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("filter_contains");
+    for (name, new) in filters() {
+        let mut filter = new();
+        for key in &keys[..N / 2] {
+            filter.insert(key);
+        }
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                keys.iter()
+                    .filter(|key| filter.contains(black_box(&key[..])))
+                    .count()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_filters);
+criterion_main!(benches);
@@ -0,0 +1,37 @@
+//! 前缀函数的 O(n) 算法和朴素算法的性能对比
+//!
+//! 全部相同的字符串是朴素算法的最坏情况, 每个位置都要比较 O(n) 长度的前后缀
+//!
+//! ```text
+//! cargo bench --bench kmp
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use impx::kmp::{kmp_find_all_bytes, prefix_n, prefix_n_naive};
+
+fn bench_prefix_n(c: &mut Criterion) {
+    let mut group = c.benchmark_group("prefix_n");
+    group.sample_size(10);
+    for n in [1_000, 100_000] {
+        let s = vec![b'a'; n];
+        group.bench_with_input(BenchmarkId::new("optimized", n), &s, |b, s| {
+            b.iter(|| prefix_n(black_box(s)))
+        });
+        group.bench_with_input(BenchmarkId::new("naive", n), &s, |b, s| {
+            b.iter(|| prefix_n_naive(black_box(s)))
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("kmp_find_all");
+    let s = vec![b'a'; 100_000];
+    let t = vec![b'a'; 1_000];
+    group.bench_function("100000", |b| {
+        b.iter(|| kmp_find_all_bytes(black_box(&s), black_box(&t)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_prefix_n);
+criterion_main!(benches);
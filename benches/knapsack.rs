@@ -0,0 +1,29 @@
+//! 子集和的朴素 bool 数组 DP 和位图移位 DP 的性能对比
+//!
+//! ```text
+//! cargo bench --bench knapsack
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use impx::knapsack::{subset_sum_naive, subset_sums};
+
+fn bench_subset_sum(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(0x5eed);
+    let mut group = c.benchmark_group("subset_sum");
+    group.sample_size(10);
+    for limit in [10_000, 100_000] {
+        let items: Vec<usize> = (0..200).map(|_| rng.gen_range(1..limit / 10)).collect();
+        group.bench_with_input(BenchmarkId::new("bitset", limit), &items, |b, items| {
+            b.iter(|| subset_sums(black_box(items), limit))
+        });
+        group.bench_with_input(BenchmarkId::new("naive", limit), &items, |b, items| {
+            b.iter(|| subset_sum_naive(black_box(items), limit))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_subset_sum);
+criterion_main!(benches);
@@ -0,0 +1,71 @@
+//! 递归的懒标记线段树和非递归(zkw)线段树的性能对比
+//!
+//! 只比较两者都支持的单点修改和区间求和, 递归版本的单点修改就是长度为 1 的区间修改
+//!
+//! ```text
+//! cargo bench --bench segment_tree
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use impx::segment_tree::{sum_tree, SegmentTree};
+
+const OPS: usize = 10_000;
+
+/// 随机生成修改和查询, 修改为 (i, diff) 查询为 [l, r]
+fn gen_ops(n: usize, rng: &mut StdRng) -> Vec<(bool, usize, usize, isize)> {
+    (0..OPS)
+        .map(|_| {
+            let l = rng.gen_range(0..n);
+            let r = rng.gen_range(l..n);
+            (rng.gen_bool(0.5), l, r, rng.gen_range(-100..100))
+        })
+        .collect()
+}
+
+fn bench_segment_tree(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut group = c.benchmark_group("segment_tree");
+    group.sample_size(20);
+    for n in [1_000, 100_000] {
+        let data: Vec<isize> = (0..n).map(|_| rng.gen_range(-100..100)).collect();
+        let ops = gen_ops(n, &mut rng);
+
+        group.bench_with_input(BenchmarkId::new("recursive", n), &ops, |b, ops| {
+            let mut t = SegmentTree::new(&data);
+            b.iter(|| {
+                let mut acc = 0;
+                for &(update, l, r, diff) in ops {
+                    if update {
+                        t.update(l, l, diff);
+                    } else {
+                        acc += t.sum(l, r);
+                    }
+                }
+                black_box(acc)
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("zkw", n), &ops, |b, ops| {
+            let mut t = sum_tree(&data);
+            b.iter(|| {
+                let mut acc = 0;
+                for &(update, l, r, diff) in ops {
+                    if update {
+                        let x = t.get(l) + diff;
+                        t.set(l, x);
+                    } else {
+                        acc += t.query(l, r).unwrap();
+                    }
+                }
+                black_box(acc)
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_segment_tree);
+criterion_main!(benches);
@@ -0,0 +1,163 @@
+//! 跳表节点池和标准库 BTreeMap, HashMap 的性能对比
+//!
+//! 测试三种负载:
+//!
+//! - 插入删除交替: 先插入 n 个 key, 再反复删除一个 key 并插入一个新的 key, 节点池可以复用刚删除的节点
+//! - 查找: 在 n 个 key 中查找随机 key
+//! - 清空后重建: 插入 n 个 key, 清空, 再插入 n 个 key
+//!
+//! n = 1e4 时节点池在插入删除交替的负载下只快 5% 左右, 清空后重建没有明显差别:
+//! 跳表的瓶颈是沿链表查找时的缓存未命中, 而不是分配器, 整体比 BTreeMap 慢 3~4 倍
+//!
+//! ```text
+//! cargo bench --bench skip_list
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use impx::skip_list::SkipList;
+
+const N: usize = 10_000;
+
+/// 统一各个容器的接口
+trait Map {
+    fn insert(&mut self, key: u64, value: u64);
+    fn find(&self, key: &u64) -> Option<&u64>;
+    fn delete(&mut self, key: &u64) -> Option<u64>;
+    fn clear(&mut self);
+}
+
+impl Map for SkipList<16, u64, u64> {
+    fn insert(&mut self, key: u64, value: u64) {
+        SkipList::insert(self, key, value);
+    }
+
+    fn find(&self, key: &u64) -> Option<&u64> {
+        SkipList::find(self, key)
+    }
+
+    fn delete(&mut self, key: &u64) -> Option<u64> {
+        SkipList::delete(self, key)
+    }
+
+    fn clear(&mut self) {
+        SkipList::clear(self)
+    }
+}
+
+impl Map for BTreeMap<u64, u64> {
+    fn insert(&mut self, key: u64, value: u64) {
+        BTreeMap::insert(self, key, value);
+    }
+
+    fn find(&self, key: &u64) -> Option<&u64> {
+        self.get(key)
+    }
+
+    fn delete(&mut self, key: &u64) -> Option<u64> {
+        self.remove(key)
+    }
+
+    fn clear(&mut self) {
+        BTreeMap::clear(self)
+    }
+}
+
+impl Map for HashMap<u64, u64> {
+    fn insert(&mut self, key: u64, value: u64) {
+        HashMap::insert(self, key, value);
+    }
+
+    fn find(&self, key: &u64) -> Option<&u64> {
+        self.get(key)
+    }
+
+    fn delete(&mut self, key: &u64) -> Option<u64> {
+        self.remove(key)
+    }
+
+    fn clear(&mut self) {
+        HashMap::clear(self)
+    }
+}
+
+/// 创建一个空的容器
+type MapFactory = fn() -> Box<dyn Map>;
+
+/// 参与对比的容器, 跳表分别关闭和开启节点池
+fn maps() -> Vec<(&'static str, MapFactory)> {
+    vec![
+        ("SkipList", || Box::new(SkipList::<16, u64, u64>::new())),
+        ("SkipList/pool", || {
+            Box::new(SkipList::<16, u64, u64>::with_pool_capacity(N))
+        }),
+        ("BTreeMap", || Box::new(BTreeMap::new())),
+        ("HashMap", || Box::new(HashMap::new())),
+    ]
+}
+
+fn bench_skip_list(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(0x5eed);
+    let keys: Vec<u64> = (0..N).map(|_| rng.gen()).collect();
+    let churn: Vec<u64> = (0..N).map(|_| rng.gen()).collect();
+    let queries: Vec<u64> = (0..N).map(|_| keys[rng.gen_range(0..N)]).collect();
+
+    let mut group = c.benchmark_group("skip_list_churn");
+    for (name, new) in maps() {
+        let mut map = new();
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                map.clear();
+                for &key in &keys {
+                    map.insert(key, key);
+                }
+                // 每次删除最早插入的 key, 再插入一个新的 key
+                for (&old, &new) in keys.iter().zip(&churn) {
+                    map.delete(&old);
+                    map.insert(new, new);
+                }
+            })
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("skip_list_find");
+    for (name, new) in maps() {
+        let mut map = new();
+        for &key in &keys {
+            map.insert(key, key);
+        }
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                queries
+                    .iter()
+                    .filter_map(|key| map.find(black_box(key)))
+                    .fold(0u64, |acc, &v| acc.wrapping_add(v))
+            })
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("skip_list_rebuild");
+    for (name, new) in maps() {
+        let mut map = new();
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                for _ in 0..2 {
+                    map.clear();
+                    for &key in &keys {
+                        map.insert(key, key);
+                    }
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_skip_list);
+criterion_main!(benches);
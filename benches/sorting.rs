@@ -0,0 +1,53 @@
+//! 排序算法和标准库 `slice::sort`/`slice::sort_unstable` 的性能对比
+//!
+//! O(n²) 的排序只在较小的规模上测试
+//!
+//! ```text
+//! cargo bench --bench sorting
+//! ```
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use impx::sorting;
+
+type SortFn = fn(&mut [usize]);
+
+fn bench_sorting(c: &mut Criterion) {
+    let quadratic: [(&str, SortFn); 4] = [
+        ("bubble_sort", sorting::bubble_sort),
+        ("selection_sort", sorting::selection_sort),
+        ("insertion_sort", sorting::insertion_sort),
+        ("cocktail_sort", sorting::cocktail_sort),
+    ];
+    let fast: [(&str, SortFn); 8] = [
+        ("shell_sort", sorting::shell_sort),
+        ("merge_sort", sorting::merge_sort),
+        ("tim_sort", sorting::tim_sort),
+        ("heap_sort", sorting::heap_sort),
+        ("quick_sort", sorting::quick_sort),
+        ("radix_sort", sorting::radix_sort),
+        ("slice::sort", |v| v.sort()),
+        ("slice::sort_unstable", |v| v.sort_unstable()),
+    ];
+
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut group = c.benchmark_group("sorting");
+    group.sample_size(20);
+    for n in [1_000, 100_000] {
+        let data: Vec<usize> = (0..n).map(|_| rng.gen_range(0..n * 10)).collect();
+        let sorts = fast
+            .iter()
+            .chain(if n <= 1_000 { &quadratic[..] } else { &[] });
+        for &(name, sort) in sorts {
+            group.bench_with_input(BenchmarkId::new(name, n), &data, |b, data| {
+                b.iter_batched_ref(|| data.clone(), |v| sort(v), BatchSize::LargeInput)
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_sorting);
+criterion_main!(benches);
@@ -0,0 +1,79 @@
+//! van Emde Boas 树和标准库 BTreeSet 的性能对比
+//!
+//! 在整个 u32 范围内随机生成 key, 测试插入、后继查询和删除
+//!
+//! 虽然每次操作只有 loglogU = 5 层, 但每层都要查一次哈希表, 插入时还要分配新的 cluster,
+//! n = 1e5 时常数远大于 BTreeSet, 插入和删除大约慢 6~25 倍, 后继查询大约慢 2 倍
+//!
+//! ```text
+//! cargo bench --bench veb_tree
+//! ```
+
+use std::collections::BTreeSet;
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use impx::veb_tree::VebTree;
+
+const N: usize = 100_000;
+
+fn bench_veb_tree(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(0x5eed);
+    let keys: Vec<u32> = (0..N).map(|_| rng.gen()).collect();
+    let queries: Vec<u32> = (0..N).map(|_| rng.gen()).collect();
+
+    let mut group = c.benchmark_group("veb_insert");
+    group.sample_size(10);
+    group.bench_function("VebTree", |b| {
+        b.iter(|| keys.iter().copied().collect::<VebTree>())
+    });
+    group.bench_function("BTreeSet", |b| {
+        b.iter(|| keys.iter().copied().collect::<BTreeSet<_>>())
+    });
+    group.finish();
+
+    let veb: VebTree = keys.iter().copied().collect();
+    let btree: BTreeSet<u32> = keys.iter().copied().collect();
+
+    let mut group = c.benchmark_group("veb_successor");
+    group.bench_function("VebTree", |b| {
+        b.iter(|| {
+            queries
+                .iter()
+                .filter_map(|&x| veb.successor(black_box(x)))
+                .fold(0u64, |acc, y| acc + y as u64)
+        })
+    });
+    group.bench_function("BTreeSet", |b| {
+        b.iter(|| {
+            queries
+                .iter()
+                .filter_map(|&x| btree.range(black_box(x) + 1..).next().copied())
+                .fold(0u64, |acc, y| acc + y as u64)
+        })
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("veb_delete");
+    group.sample_size(10);
+    group.bench_function("VebTree", |b| {
+        b.iter_batched(
+            || keys.iter().copied().collect::<VebTree>(),
+            |mut t| keys.iter().filter(|&&x| t.delete(x)).count(),
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("BTreeSet", |b| {
+        b.iter_batched(
+            || keys.iter().copied().collect::<BTreeSet<_>>(),
+            |mut t| keys.iter().filter(|&x| t.remove(x)).count(),
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_veb_tree);
+criterion_main!(benches);
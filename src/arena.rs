@@ -0,0 +1,469 @@
+//! Arena 分配器
+//!
+//! - [Rust Design Patterns - Arena](https://rust-unofficial.github.io/patterns/patterns/structural/arena.html)
+//! - [generational-arena](https://github.com/fitzgen/generational-arena)
+//! - [Handles are the better pointers](https://floooh.github.io/2018/06/17/handles-vs-pointers.html)
+//!
+//! 链表和各种树在 Rust 中通常用 `Box` 或者 `NonNull` 裸指针实现, 前者无法表示父指针等多个指向同一节点的引用,
+//! 后者需要大量 unsafe 并且要自己保证在 Drop 时释放所有节点, 稍不注意就会内存泄漏或者悬垂指针
+//!
+//! Arena 把所有节点放在一个 Vec 中, 用下标代替指针, 节点之间的引用不再涉及所有权, 整个 Arena 被 drop 时所有节点一起释放  
+//! 删除的位置串成一个空闲链表(slab), 下次插入时复用, 所以插入删除都是 O(1)
+//!
+//! 只用下标的问题是一个位置被复用之后, 之前保存的旧下标会指向新的数据(类似 use-after-free 但是是内存安全的)  
+//! 给每个位置加一个代数(generation), 每次删除时加一, 下标 [`Index`] 同时保存位置和代数, 代数不匹配时就说明是过期的下标
+//!
+//! [`ArenaAVLTree`] 是 [`crate::avl_tree`] 的 Arena 版本, 全部使用安全代码, 节点之间通过 [`Index`] 连接
+
+use std::cmp::Ordering;
+
+/// Arena 中的下标, 包含位置和代数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Index {
+    slot: usize,
+    generation: u64,
+}
+
+enum Entry<T> {
+    Occupied {
+        generation: u64,
+        value: T,
+    },
+    Free {
+        generation: u64,
+        next_free: Option<usize>,
+    },
+}
+
+/// 使用代数下标的 slab 分配器
+pub struct Arena<T> {
+    entries: Vec<Entry<T>>,
+    free_head: Option<usize>,
+    len: usize,
+}
+
+impl<T> Arena<T> {
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    pub fn with_capacity(n: usize) -> Self {
+        Self {
+            entries: Vec::with_capacity(n),
+            free_head: None,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// 已经分配的位置数量, 包括空闲的位置
+    pub fn capacity(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// 插入一个值并返回它的下标, 优先复用空闲的位置
+    pub fn insert(&mut self, value: T) -> Index {
+        self.len += 1;
+        match self.free_head {
+            Some(slot) => {
+                let Entry::Free {
+                    generation,
+                    next_free,
+                } = self.entries[slot]
+                else {
+                    unreachable!("free list points to an occupied entry");
+                };
+                self.free_head = next_free;
+                self.entries[slot] = Entry::Occupied { generation, value };
+                Index { slot, generation }
+            }
+            None => {
+                self.entries.push(Entry::Occupied {
+                    generation: 0,
+                    value,
+                });
+                Index {
+                    slot: self.entries.len() - 1,
+                    generation: 0,
+                }
+            }
+        }
+    }
+
+    /// 删除下标对应的值, 下标已经过期时返回 None
+    pub fn remove(&mut self, index: Index) -> Option<T> {
+        if !self.contains(index) {
+            return None;
+        }
+
+        let free = Entry::Free {
+            generation: index.generation + 1,
+            next_free: self.free_head,
+        };
+        let Entry::Occupied { value, .. } = std::mem::replace(&mut self.entries[index.slot], free)
+        else {
+            unreachable!();
+        };
+        self.free_head = Some(index.slot);
+        self.len -= 1;
+
+        Some(value)
+    }
+
+    /// 下标是否有效
+    pub fn contains(&self, index: Index) -> bool {
+        self.get(index).is_some()
+    }
+
+    pub fn get(&self, index: Index) -> Option<&T> {
+        match self.entries.get(index.slot) {
+            Some(Entry::Occupied { generation, value }) if *generation == index.generation => {
+                Some(value)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, index: Index) -> Option<&mut T> {
+        match self.entries.get_mut(index.slot) {
+            Some(Entry::Occupied { generation, value }) if *generation == index.generation => {
+                Some(value)
+            }
+            _ => None,
+        }
+    }
+
+    /// 遍历所有值, 按位置顺序
+    pub fn iter(&self) -> impl Iterator<Item = (Index, &T)> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, e)| match e {
+                Entry::Occupied { generation, value } => Some((
+                    Index {
+                        slot,
+                        generation: *generation,
+                    },
+                    value,
+                )),
+                Entry::Free { .. } => None,
+            })
+    }
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> std::ops::Index<Index> for Arena<T> {
+    type Output = T;
+
+    fn index(&self, index: Index) -> &T {
+        self.get(index).expect("invalid arena index")
+    }
+}
+
+impl<T> std::ops::IndexMut<Index> for Arena<T> {
+    fn index_mut(&mut self, index: Index) -> &mut T {
+        self.get_mut(index).expect("invalid arena index")
+    }
+}
+
+struct AVLNode<T> {
+    value: T,
+    height: usize,
+    left: Option<Index>,
+    right: Option<Index>,
+}
+
+/// 使用 Arena 保存节点的 AVL 树
+pub struct ArenaAVLTree<T> {
+    nodes: Arena<AVLNode<T>>,
+    root: Option<Index>,
+}
+
+impl<T: Ord> ArenaAVLTree<T> {
+    pub fn new() -> Self {
+        Self {
+            nodes: Arena::new(),
+            root: None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// 树的高度, 空树为 0
+    pub fn height(&self) -> usize {
+        self.height_of(self.root)
+    }
+
+    fn height_of(&self, node: Option<Index>) -> usize {
+        node.map_or(0, |i| self.nodes[i].height)
+    }
+
+    /// 平衡因子: 左子树高度减去右子树高度
+    fn balance_factor(&self, i: Index) -> isize {
+        let node = &self.nodes[i];
+        self.height_of(node.left) as isize - self.height_of(node.right) as isize
+    }
+
+    fn reset_height(&mut self, i: Index) {
+        let (l, r) = (self.nodes[i].left, self.nodes[i].right);
+        self.nodes[i].height = self.height_of(l).max(self.height_of(r)) + 1;
+    }
+
+    /// 左旋, 返回新的子树根
+    fn rotate_left(&mut self, i: Index) -> Index {
+        let r = self.nodes[i].right.expect("right child must exist");
+        self.nodes[i].right = self.nodes[r].left;
+        self.nodes[r].left = Some(i);
+        self.reset_height(i);
+        self.reset_height(r);
+        r
+    }
+
+    /// 右旋, 返回新的子树根
+    fn rotate_right(&mut self, i: Index) -> Index {
+        let l = self.nodes[i].left.expect("left child must exist");
+        self.nodes[i].left = self.nodes[l].right;
+        self.nodes[l].right = Some(i);
+        self.reset_height(i);
+        self.reset_height(l);
+        l
+    }
+
+    /// 重新平衡 i 为根的子树并返回新的根, 四种情况和 [`crate::avl_tree::rebalance`] 相同
+    fn rebalance(&mut self, i: Index) -> Index {
+        self.reset_height(i);
+        let factor = self.balance_factor(i);
+        if factor > 1 {
+            let l = self.nodes[i].left.unwrap();
+            if self.balance_factor(l) < 0 {
+                // LR
+                self.nodes[i].left = Some(self.rotate_left(l));
+            }
+            return self.rotate_right(i);
+        }
+        if factor < -1 {
+            let r = self.nodes[i].right.unwrap();
+            if self.balance_factor(r) > 0 {
+                // RL
+                self.nodes[i].right = Some(self.rotate_right(r));
+            }
+            return self.rotate_left(i);
+        }
+
+        i
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        let mut curr = self.root;
+        while let Some(i) = curr {
+            let node = &self.nodes[i];
+            match node.value.cmp(value) {
+                Ordering::Greater => curr = node.left,
+                Ordering::Less => curr = node.right,
+                Ordering::Equal => return true,
+            }
+        }
+
+        false
+    }
+
+    /// 插入值, 已经存在时返回 false
+    pub fn insert(&mut self, value: T) -> bool {
+        let (root, inserted) = self.insert_at(self.root, value);
+        self.root = Some(root);
+        inserted
+    }
+
+    fn insert_at(&mut self, node: Option<Index>, value: T) -> (Index, bool) {
+        let Some(i) = node else {
+            let i = self.nodes.insert(AVLNode {
+                value,
+                height: 1,
+                left: None,
+                right: None,
+            });
+            return (i, true);
+        };
+
+        let inserted = match self.nodes[i].value.cmp(&value) {
+            Ordering::Equal => return (i, false),
+            Ordering::Greater => {
+                let (l, inserted) = self.insert_at(self.nodes[i].left, value);
+                self.nodes[i].left = Some(l);
+                inserted
+            }
+            Ordering::Less => {
+                let (r, inserted) = self.insert_at(self.nodes[i].right, value);
+                self.nodes[i].right = Some(r);
+                inserted
+            }
+        };
+
+        (self.rebalance(i), inserted)
+    }
+
+    /// 删除值并返回, 不存在时返回 None
+    pub fn delete(&mut self, value: &T) -> Option<T> {
+        let (root, removed) = self.delete_at(self.root, value);
+        self.root = root;
+        removed.map(|i| self.nodes.remove(i).unwrap().value)
+    }
+
+    /// 从子树中摘下值为 value 的节点, 返回新的子树根和被摘下的节点
+    fn delete_at(&mut self, node: Option<Index>, value: &T) -> (Option<Index>, Option<Index>) {
+        let Some(i) = node else {
+            return (None, None);
+        };
+
+        let removed = match self.nodes[i].value.cmp(value) {
+            Ordering::Greater => {
+                let (l, removed) = self.delete_at(self.nodes[i].left, value);
+                self.nodes[i].left = l;
+                removed
+            }
+            Ordering::Less => {
+                let (r, removed) = self.delete_at(self.nodes[i].right, value);
+                self.nodes[i].right = r;
+                removed
+            }
+            Ordering::Equal => {
+                let (l, r) = (self.nodes[i].left, self.nodes[i].right);
+                let Some(r) = r else {
+                    return (l, Some(i));
+                };
+
+                // 把右子树的最小节点整个移动到当前位置, 不需要移动或复制 value
+                let (r, min) = self.take_min(r);
+                self.nodes[min].left = l;
+                self.nodes[min].right = r;
+                return (Some(self.rebalance(min)), Some(i));
+            }
+        };
+
+        (Some(self.rebalance(i)), removed)
+    }
+
+    /// 从子树中摘下最小的节点, 返回新的子树根和最小节点
+    fn take_min(&mut self, i: Index) -> (Option<Index>, Index) {
+        match self.nodes[i].left {
+            None => (self.nodes[i].right, i),
+            Some(l) => {
+                let (l, min) = self.take_min(l);
+                self.nodes[i].left = l;
+                (Some(self.rebalance(i)), min)
+            }
+        }
+    }
+
+    /// 按从小到大的顺序遍历
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        let mut stack = vec![];
+        let mut curr = self.root;
+        std::iter::from_fn(move || {
+            while let Some(i) = curr {
+                stack.push(i);
+                curr = self.nodes[i].left;
+            }
+            let i = stack.pop()?;
+            curr = self.nodes[i].right;
+            Some(&self.nodes[i].value)
+        })
+    }
+}
+
+impl<T: Ord> Default for ArenaAVLTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_arena() {
+        let mut arena = Arena::new();
+        let a = arena.insert("a");
+        let b = arena.insert("b");
+        assert_eq!(arena.len(), 2);
+        assert_eq!(arena[a], "a");
+
+        assert_eq!(arena.remove(a), Some("a"));
+        assert_eq!(arena.remove(a), None);
+        assert!(!arena.contains(a));
+
+        // 复用 a 的位置, 但是旧的下标已经过期
+        let c = arena.insert("c");
+        assert_eq!(arena.capacity(), 2);
+        assert_eq!(arena.get(a), None);
+        assert_eq!(arena.get(c), Some(&"c"));
+
+        *arena.get_mut(b).unwrap() = "B";
+        let mut values: Vec<_> = arena.iter().map(|(_, &v)| v).collect();
+        values.sort();
+        assert_eq!(values, vec!["B", "c"]);
+    }
+
+    /// 检查 AVL 的高度和平衡性质
+    fn check<T: Ord>(tree: &ArenaAVLTree<T>, node: Option<Index>) -> usize {
+        let Some(i) = node else {
+            return 0;
+        };
+        let (l, r) = (tree.nodes[i].left, tree.nodes[i].right);
+        let (hl, hr) = (check(tree, l), check(tree, r));
+        assert!(hl.abs_diff(hr) <= 1);
+        assert_eq!(tree.nodes[i].height, hl.max(hr) + 1);
+        hl.max(hr) + 1
+    }
+
+    #[test]
+    fn test_arena_avl_tree() {
+        let mut rng = rand::thread_rng();
+        let mut tree = ArenaAVLTree::new();
+        let mut set = BTreeSet::new();
+
+        for _ in 0..5000 {
+            let v = rng.gen_range(0..500);
+            if rng.gen_bool(0.6) {
+                assert_eq!(tree.insert(v), set.insert(v));
+            } else {
+                assert_eq!(tree.delete(&v), set.take(&v));
+            }
+            assert_eq!(tree.len(), set.len());
+            assert_eq!(tree.contains(&v), set.contains(&v));
+        }
+
+        check(&tree, tree.root);
+        assert!(tree.iter().eq(set.iter()));
+        // 节点数不超过同时存在的最大值个数, 删除的位置会被复用
+        assert!(tree.nodes.capacity() <= 500);
+        assert!(tree.height() as f64 <= 1.45 * (tree.len() as f64 + 2.0).log2());
+
+        for v in 0..500 {
+            tree.delete(&v);
+        }
+        assert!(tree.is_empty());
+        assert_eq!(tree.height(), 0);
+    }
+}
@@ -1,4 +1,245 @@
 //! 数组相关算法
+//!
+//! - [OI Wiki - 前缀和 & 差分](https://oi-wiki.org/basic/prefix-sum/)
+//! - [OI Wiki - 双指针](https://oi-wiki.org/misc/two-pointer/)
+//! - [wikipedia - Maximum subarray problem](https://en.wikipedia.org/wiki/Maximum_subarray_problem)
+//! - [wikipedia - Longest increasing subsequence](https://en.wikipedia.org/wiki/Longest_increasing_subsequence)
+//!
+//! 前缀和 `s[i] = a[0] + ... + a[i-1]` 预处理之后区间和就是 `s[r+1] - s[l]`,
+//! 二维的情况下 `s[i][j]` 是左上角 i 行 j 列的和, 矩形的和用容斥原理计算
+//!
+//! 差分是前缀和的逆运算 `d[i] = a[i] - a[i-1]`, 对区间 `[l, r]` 整体加 v 只需要 `d[l] += v` `d[r+1] -= v`,
+//! 所有修改完成后再求一次前缀和就得到了修改后的数组
+
+use std::ops::{Add, Sub};
+
+use crate::binary_search::binary_search_first;
+
+/// 一维前缀和, 结果的长度是 `n + 1` 且 `s[0]` 为零值
+///
+/// ```
+/// use impx::array::prefix_sum;
+///
+/// let s = prefix_sum(&[1, 2, 3, 4]);
+/// assert_eq!(s, vec![0, 1, 3, 6, 10]);
+/// // 区间 [1, 2] 的和
+/// assert_eq!(s[3] - s[1], 5);
+/// ```
+pub fn prefix_sum<T: Copy + Default + Add<Output = T>>(v: &[T]) -> Vec<T> {
+    let mut s = Vec::with_capacity(v.len() + 1);
+    s.push(T::default());
+    for &x in v {
+        s.push(*s.last().unwrap() + x);
+    }
+
+    s
+}
+
+/// 二维前缀和
+///
+/// ```
+/// use impx::array::PrefixSum2D;
+///
+/// let s = PrefixSum2D::new(&[vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]]);
+/// assert_eq!(s.query(0, 0, 2, 2), 45);
+/// assert_eq!(s.query(1, 1, 2, 2), 28);
+/// assert_eq!(s.query(0, 2, 1, 2), 9);
+/// ```
+pub struct PrefixSum2D<T> {
+    /// `sum[i][j]` 是前 i 行前 j 列的和
+    sum: Vec<Vec<T>>,
+}
+
+impl<T> PrefixSum2D<T>
+where
+    T: Copy + Default + Add<Output = T> + Sub<Output = T>,
+{
+    pub fn new(grid: &[Vec<T>]) -> Self {
+        let m = grid.first().map_or(0, |row| row.len());
+        let mut sum = vec![vec![T::default(); m + 1]; grid.len() + 1];
+        for (i, row) in grid.iter().enumerate() {
+            assert_eq!(row.len(), m, "all rows must have the same length");
+            for (j, &x) in row.iter().enumerate() {
+                sum[i + 1][j + 1] = sum[i][j + 1] + sum[i + 1][j] - sum[i][j] + x;
+            }
+        }
+
+        Self { sum }
+    }
+
+    /// 左上角 `(r1, c1)` 到右下角 `(r2, c2)` 的矩形(闭区间)之和
+    pub fn query(&self, r1: usize, c1: usize, r2: usize, c2: usize) -> T {
+        assert!(r1 <= r2 && c1 <= c2, "invalid rectangle");
+        let s = &self.sum;
+        s[r2 + 1][c2 + 1] - s[r1][c2 + 1] - s[r2 + 1][c1] + s[r1][c1]
+    }
+}
+
+/// 差分数组, 支持多次区间加之后一次性求出结果
+///
+/// ```
+/// use impx::array::DifferenceArray;
+///
+/// let mut d = DifferenceArray::from(&[1, 1, 1, 1, 1][..]);
+/// d.add(1, 3, 2);
+/// d.add(0, 1, -1);
+/// assert_eq!(d.materialize(), vec![0, 2, 3, 3, 1]);
+/// ```
+pub struct DifferenceArray<T> {
+    diff: Vec<T>,
+}
+
+impl<T> DifferenceArray<T>
+where
+    T: Copy + Default + Add<Output = T> + Sub<Output = T>,
+{
+    /// 长度为 n 的全零数组
+    pub fn new(n: usize) -> Self {
+        Self {
+            diff: vec![T::default(); n],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.diff.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.diff.is_empty()
+    }
+
+    /// 区间 `[l, r]` 的每个元素加上 v
+    pub fn add(&mut self, l: usize, r: usize, v: T) {
+        assert!(l <= r && r < self.len(), "range out of bounds");
+        self.diff[l] = self.diff[l] + v;
+        if r + 1 < self.len() {
+            self.diff[r + 1] = self.diff[r + 1] - v;
+        }
+    }
+
+    /// 求前缀和还原出修改后的数组
+    pub fn materialize(&self) -> Vec<T> {
+        prefix_sum(&self.diff)[1..].to_vec()
+    }
+}
+
+impl<T> From<&[T]> for DifferenceArray<T>
+where
+    T: Copy + Default + Sub<Output = T>,
+{
+    fn from(v: &[T]) -> Self {
+        let mut prev = T::default();
+        let diff = v
+            .iter()
+            .map(|&x| {
+                let d = x - prev;
+                prev = x;
+                d
+            })
+            .collect();
+
+        Self { diff }
+    }
+}
+
+/// Kadane 算法求和最大的非空子数组, 返回最大和以及子数组的区间 `[l, r]`
+///
+/// 以 i 结尾的最大子数组要么只有 `a[i]` 自己, 要么是以 i-1 结尾的最大子数组再加上 `a[i]`,
+/// 所以只要以 i-1 结尾的最大和不大于零就从 i 重新开始
+///
+/// ```
+/// use impx::array::max_subarray;
+///
+/// assert_eq!(max_subarray(&[-2, 1, -3, 4, -1, 2, 1, -5, 4]), Some((6, 3, 6)));
+/// assert_eq!(max_subarray(&[-3, -1, -2]), Some((-1, 1, 1)));
+/// assert_eq!(max_subarray::<i32>(&[]), None);
+/// ```
+pub fn max_subarray<T>(v: &[T]) -> Option<(T, usize, usize)>
+where
+    T: Copy + Default + Ord + Add<Output = T>,
+{
+    let mut best = (*v.first()?, 0, 0);
+    let (mut cur, mut start) = (v[0], 0);
+    for (i, &x) in v.iter().enumerate().skip(1) {
+        if cur <= T::default() {
+            (cur, start) = (x, i);
+        } else {
+            cur = cur + x;
+        }
+
+        if cur > best.0 {
+            best = (cur, start, i);
+        }
+    }
+
+    Some(best)
+}
+
+/// 最长严格递增子序列, 返回子序列在原数组中的下标
+///
+/// `tails[k]` 保存长度为 k+1 的递增子序列中结尾最小的那个的结尾下标, 它对应的值是严格递增的  
+/// 所以对每个元素二分查找第一个不小于它的结尾并替换, 找不到说明可以接在最长的序列后面  
+/// 同时记录每个元素接在哪个元素后面, 最后从最长序列的结尾往回找就得到了整个子序列, 时间复杂度 O(nlogn)
+///
+/// ```
+/// use impx::array::longest_increasing_subsequence;
+///
+/// let v = [10, 9, 2, 5, 3, 7, 101, 18];
+/// let lis = longest_increasing_subsequence(&v);
+/// assert_eq!(lis.len(), 4);
+/// assert!(lis.windows(2).all(|w| w[0] < w[1] && v[w[0]] < v[w[1]]));
+/// ```
+pub fn longest_increasing_subsequence<T: Ord>(v: &[T]) -> Vec<usize> {
+    let mut tails: Vec<usize> = vec![];
+    let mut prev = vec![None; v.len()];
+    for (i, x) in v.iter().enumerate() {
+        let k = if tails.last().is_none_or(|&t| v[t] < *x) {
+            tails.push(i);
+            tails.len() - 1
+        } else {
+            let k = binary_search_first(&tails, |&t| v[t] >= *x).unwrap();
+            tails[k] = i;
+            k
+        };
+        prev[i] = k.checked_sub(1).map(|k| tails[k]);
+    }
+
+    let mut res = vec![];
+    let mut cur = tails.last().copied();
+    while let Some(i) = cur {
+        res.push(i);
+        cur = prev[i];
+    }
+    res.reverse();
+
+    res
+}
+
+/// 双指针在有序数组中查找两个和为 target 的元素的下标
+///
+/// 左右指针从两端出发, 和太小时只能移动左指针, 和太大时只能移动右指针
+///
+/// ```
+/// use impx::array::two_sum_sorted;
+///
+/// assert_eq!(two_sum_sorted(&[1, 2, 4, 7, 11, 15], 15), Some((2, 4)));
+/// assert_eq!(two_sum_sorted(&[1, 2, 4], 8), None);
+/// ```
+pub fn two_sum_sorted<T>(v: &[T], target: T) -> Option<(usize, usize)>
+where
+    T: Copy + Ord + Add<Output = T>,
+{
+    let (mut l, mut r) = (0, v.len().checked_sub(1)?);
+    while l < r {
+        match (v[l] + v[r]).cmp(&target) {
+            std::cmp::Ordering::Less => l += 1,
+            std::cmp::Ordering::Equal => return Some((l, r)),
+            std::cmp::Ordering::Greater => r -= 1,
+        }
+    }
+
+    None
+}
 
 /// Boyer–Moore 多数投票算法
 ///
@@ -7,6 +248,14 @@
 /// - 设置一个计数器 count 和当前多数元素 e
 /// - 遍历元素如果当前 count 为 0 则将多数元素设置为当前元素
 /// - 否则如果当前元素等于 e 则 count+1 否则 count-1
+///
+/// 只有在多数元素一定存在时结果才有意义, 不确定时使用 [`majority_element`]
+///
+/// ```
+/// use impx::array::majority_vote;
+///
+/// assert_eq!(majority_vote(&[2, 2, 1, 1, 1, 2, 2]), &2);
+/// ```
 pub fn majority_vote<T: std::cmp::Eq>(arr: &[T]) -> &T {
     let (mut count, mut e) = (0, &arr[0]);
     for v in arr {
@@ -25,6 +274,24 @@ pub fn majority_vote<T: std::cmp::Eq>(arr: &[T]) -> &T {
     e
 }
 
+/// 查找多数元素, 投票之后再遍历一次确认候选元素的数量确实超过一半
+///
+/// ```
+/// use impx::array::majority_element;
+///
+/// assert_eq!(majority_element(&[3, 2, 3]), Some(&3));
+/// assert_eq!(majority_element(&[1, 2, 3]), None);
+/// assert_eq!(majority_element::<i32>(&[]), None);
+/// ```
+pub fn majority_element<T: Eq>(arr: &[T]) -> Option<&T> {
+    if arr.is_empty() {
+        return None;
+    }
+
+    let e = majority_vote(arr);
+    (arr.iter().filter(|&v| v == e).count() * 2 > arr.len()).then_some(e)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -36,5 +303,74 @@ mod tests {
         assert_eq!(majority_vote(&[0, 1, 1]), &1);
         assert_eq!(majority_vote(&[1, 2, 2, 3]), &2);
         assert_eq!(majority_vote(&[1, 3, 1, 2, 3, 3, 3, 4, 3]), &3);
+        assert_eq!(majority_element(&[1, 2, 2, 3]), None);
+        assert_eq!(majority_element(&[1, 3, 1, 2, 3, 3, 3, 4, 3]), Some(&3));
+    }
+
+    #[test]
+    fn test_prefix_and_difference() {
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        let grid: Vec<Vec<i64>> = (0..8)
+            .map(|_| (0..6).map(|_| rng.gen_range(-10..10)).collect())
+            .collect();
+        let s = PrefixSum2D::new(&grid);
+        for r1 in 0..8 {
+            for r2 in r1..8 {
+                for c1 in 0..6 {
+                    for c2 in c1..6 {
+                        let expect: i64 = grid[r1..=r2]
+                            .iter()
+                            .map(|row| row[c1..=c2].iter().sum::<i64>())
+                            .sum();
+                        assert_eq!(s.query(r1, c1, r2, c2), expect);
+                    }
+                }
+            }
+        }
+
+        let mut a: Vec<i64> = (0..50).map(|_| rng.gen_range(-10..10)).collect();
+        let mut d = DifferenceArray::from(&a[..]);
+        for _ in 0..100 {
+            let l = rng.gen_range(0..a.len());
+            let r = rng.gen_range(l..a.len());
+            let v = rng.gen_range(-5..5);
+            d.add(l, r, v);
+            a[l..=r].iter_mut().for_each(|x| *x += v);
+        }
+        assert_eq!(d.materialize(), a);
+    }
+
+    #[test]
+    fn test_max_subarray_and_lis() {
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        for n in 1..40 {
+            let a: Vec<i64> = (0..n).map(|_| rng.gen_range(-20..20)).collect();
+
+            let (sum, l, r) = max_subarray(&a).unwrap();
+            assert_eq!(a[l..=r].iter().sum::<i64>(), sum);
+            let best = (0..n)
+                .flat_map(|l| (l..n).map(move |r| (l, r)))
+                .map(|(l, r)| a[l..=r].iter().sum::<i64>())
+                .max();
+            assert_eq!(Some(sum), best);
+
+            // O(n^2) 动态规划求 LIS 的长度
+            let mut dp = vec![1; n];
+            for i in 0..n {
+                for j in 0..i {
+                    if a[j] < a[i] {
+                        dp[i] = dp[i].max(dp[j] + 1);
+                    }
+                }
+            }
+            let lis = longest_increasing_subsequence(&a);
+            assert_eq!(Some(&lis.len()), dp.iter().max());
+            assert!(lis.windows(2).all(|w| w[0] < w[1] && a[w[0]] < a[w[1]]));
+        }
+        assert!(longest_increasing_subsequence::<i32>(&[]).is_empty());
     }
 }
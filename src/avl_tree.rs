@@ -8,9 +8,12 @@
 //!
 //! AVL 树的性质很简单: 左右子树的高度差不超过 1 因此在插入和删除的过程中需要重新平衡
 //!
-//! 插入: 按照二叉搜索树的查找顺序, 找到合适的节点直接插入接着向上递归重新平衡  
+//! 插入: 按照二叉搜索树的查找顺序, 找到合适的节点直接插入接着向上重新平衡  
 //! 删除: 找到对应节点, 从节点右子树中找到最小值替换到当前被删掉的节点重新平衡子树  
 //!
+//! 插入和删除都不使用递归: 向下查找时把经过的节点从树上摘下来压进路径栈,
+//! 修改完成后再从栈顶开始把子树挂回父节点并逐个重新平衡, 节点始终在同一个 `Box` 里移动
+//!
 //! 定义平衡因子factor: 左子树高度减去右子树高度, 则有:
 //!
 //! - root.factor > 0: 左子树比右子树高
@@ -18,13 +21,13 @@
 //!
 //! root.factor > 1: 左子树比右子树高两层需要平衡:
 //!
-//! - root.left.factor > 0: 左子树的左子树更高 (LL)
-//! - root.left.factor <= 0: 左子树的右子树更高 (LR)
+//! - root.left.factor >= 0: 左子树的左子树不低于右子树 (LL), 删除时可能出现两者等高
+//! - root.left.factor < 0: 左子树的右子树更高 (LR)
 //!
 //! root.factor < -1: 右子树比左子树高两层需要平衡:
 //!
-//! - root.right.factor < 0: 右子树的右子树更高 (RR)
-//! - root.right.factor >= 0: 右子树的左子树更高 (RL)
+//! - root.right.factor <= 0: 右子树的右子树不低于左子树 (RR)
+//! - root.right.factor > 0: 右子树的左子树更高 (RL)
 //!
 //! 各种情况的平衡方式:
 //!
@@ -49,13 +52,14 @@
 //!   / \                              /  \
 //! T2   T3                           T3   T4
 //! ```
-//!
-//! TODO: 在插入删除过程中来来回回有很多 `Box` 的 wrap 考虑怎么处理
 use std::{cmp::Ordering, fmt::Debug};
 
+use crate::visualize::{binary_tree, Dot, ToDot};
+
 /// AVL 树
 pub struct AVLTree<T> {
-    root: Option<AVLNode<T>>,
+    root: Option<Box<AVLNode<T>>>,
+    length: usize,
 }
 
 /// AVL 树节点
@@ -70,7 +74,7 @@ impl<T: Ord> AVLNode<T> {
     fn new(value: T) -> Self {
         Self {
             value,
-            height: 0,
+            height: 1,
             left: None,
             right: None,
         }
@@ -92,78 +96,89 @@ impl<T: Ord> AVLNode<T> {
     }
 }
 
-/// AVL 树中插入值
-pub fn insert<T: Ord>(mut root: AVLNode<T>, value: T) -> AVLNode<T> {
-    match root.value.cmp(&value) {
-        Ordering::Equal => return root,
-        Ordering::Greater => match root.left.take() {
-            None => {
-                root.left = Some(Box::new(AVLNode::new(value)));
-            }
-            Some(node) => {
-                root.left = Some(Box::new(insert(*node, value)));
-            }
-        },
-        Ordering::Less => match root.right.take() {
-            None => {
-                root.right = Some(Box::new(AVLNode::new(value)));
-            }
-            Some(node) => {
-                root.right = Some(Box::new(insert(*node, value)));
-            }
-        },
-    };
-
-    root = rebalance(root);
-    if let Some(left) = &mut root.left {
-        left.reset_height();
+/// 查找路径: 经过的节点以及从该节点往下走的方向, `Greater` 表示走向左子树
+type Path<T> = Vec<(Box<AVLNode<T>>, Ordering)>;
+
+/// 沿查找路径自底向上把子树挂回父节点, 同时重新平衡每一个祖先
+fn rebuild<T: Ord>(
+    mut path: Path<T>,
+    mut child: Option<Box<AVLNode<T>>>,
+) -> Option<Box<AVLNode<T>>> {
+    while let Some((mut parent, dir)) = path.pop() {
+        match dir {
+            Ordering::Greater => parent.left = child,
+            _ => parent.right = child,
+        }
+        child = Some(rebalance_and_reset(parent));
     }
-    if let Some(right) = &mut root.right {
-        right.reset_height();
+
+    child
+}
+
+/// AVL 树中插入值, 同时返回是否插入了新节点, 值已经存在时树保持不变
+///
+/// 向下查找时把经过的节点从树上摘下来放进路径栈, 插入新节点后再自底向上挂回去,
+/// 全程只移动 `Box` 指针, 除了新节点之外不会重新分配
+pub fn insert<T: Ord>(root: Option<Box<AVLNode<T>>>, value: T) -> (Box<AVLNode<T>>, bool) {
+    let mut path = vec![];
+    let mut curr = root;
+    while let Some(mut node) = curr {
+        let dir = node.value.cmp(&value);
+        curr = match dir {
+            Ordering::Greater => node.left.take(),
+            Ordering::Less => node.right.take(),
+            // 值已经存在, 原样挂回去
+            Ordering::Equal => return (rebuild(path, Some(node)).unwrap(), false),
+        };
+        path.push((node, dir));
     }
-    root.reset_height();
 
-    root
+    (
+        rebuild(path, Some(Box::new(AVLNode::new(value)))).unwrap(),
+        true,
+    )
 }
 
-/// AVL 树中删除值
-pub fn delete<T: Ord>(mut root: AVLNode<T>, value: &T) -> Option<Box<AVLNode<T>>> {
-    match root.value.cmp(value) {
-        Ordering::Equal => {
-            if let Some(right) = root.right {
+/// AVL 树中删除值, 同时返回被删除的值
+pub fn delete<T: Ord>(
+    root: Option<Box<AVLNode<T>>>,
+    value: &T,
+) -> (Option<Box<AVLNode<T>>>, Option<T>) {
+    let mut path = vec![];
+    let mut curr = root;
+    let (replacement, removed) = loop {
+        let Some(mut node) = curr else {
+            // 没有找到, 路径原样挂回去
+            break (None, None);
+        };
+
+        let dir = node.value.cmp(value);
+        curr = match dir {
+            Ordering::Greater => node.left.take(),
+            Ordering::Less => node.right.take(),
+            Ordering::Equal => match node.right.take() {
                 // 如果右子树存在, 从右子树中找到一个最小值替换到当前节点
-                let (value, right) = take_min(*right);
-                root.value = value;
-                root.right = right;
-                root = rebalance(root);
-                root.reset_height();
-                Some(Box::new(root))
-            } else {
+                Some(right) => {
+                    let (min, right) = take_min(right);
+                    let removed = std::mem::replace(&mut node.value, min);
+                    node.right = right;
+                    break (Some(rebalance_and_reset(node)), Some(removed));
+                }
                 // 否则直接返回左节点即可
-                root.left
-            }
-        }
-        Ordering::Less => {
-            if let Some(right) = root.right {
-                root.right = delete(*right, value);
-                root.reset_height();
-            }
-
-            Some(Box::new(root))
-        }
-        Ordering::Greater => {
-            if let Some(left) = root.left {
-                root.left = delete(*left, value);
-                root.reset_height();
-            }
+                None => {
+                    let AVLNode { value, left, .. } = *node;
+                    break (left, Some(value));
+                }
+            },
+        };
+        path.push((node, dir));
+    };
 
-            Some(Box::new(root))
-        }
-    }
+    (rebuild(path, replacement), removed)
 }
 
 /// AVL 树重新平衡
-pub fn rebalance<T: Ord>(mut root: AVLNode<T>) -> AVLNode<T> {
+pub fn rebalance<T: Ord>(mut root: Box<AVLNode<T>>) -> Box<AVLNode<T>> {
     let factor = root.balance_factor();
 
     if factor > 1 {
@@ -173,12 +188,12 @@ pub fn rebalance<T: Ord>(mut root: AVLNode<T>) -> AVLNode<T> {
             .expect("left subtree must exist when factor > 1")
             .balance_factor();
 
-        if left_factor > 0 {
+        if left_factor >= 0 {
             // LL
             root = rotate_right(root);
         } else {
             // LR
-            root.left = root.left.map(|node| Box::new(rotate_left(*node)));
+            root.left = root.left.take().map(rotate_left);
             root = rotate_right(root);
         }
 
@@ -192,12 +207,12 @@ pub fn rebalance<T: Ord>(mut root: AVLNode<T>) -> AVLNode<T> {
             .expect("right subtree must exist when factor < -1")
             .balance_factor();
 
-        if right_factor < 0 {
+        if right_factor <= 0 {
             // RR
             root = rotate_left(root);
         } else {
             // RL
-            root.right = root.right.map(|node| Box::new(rotate_right(*node)));
+            root.right = root.right.take().map(rotate_right);
             root = rotate_left(root);
         }
 
@@ -207,16 +222,34 @@ pub fn rebalance<T: Ord>(mut root: AVLNode<T>) -> AVLNode<T> {
     root
 }
 
-fn take_min<T: Ord>(mut root: AVLNode<T>) -> (T, Option<Box<AVLNode<T>>>) {
-    if let Some(left) = root.left {
-        let (value, right) = take_min(*left);
-        root.left = right;
-        root = rebalance(root);
-        root.reset_height();
-        (value, Some(Box::new(root)))
-    } else {
-        (root.value, root.right.take())
+/// 重新平衡并重置高度
+///
+/// 旋转只会改变新的根节点和它的两个子节点的高度, 所以先重置子节点再重置根节点
+fn rebalance_and_reset<T: Ord>(root: Box<AVLNode<T>>) -> Box<AVLNode<T>> {
+    let mut root = rebalance(root);
+    if let Some(left) = &mut root.left {
+        left.reset_height();
     }
+    if let Some(right) = &mut root.right {
+        right.reset_height();
+    }
+    root.reset_height();
+
+    root
+}
+
+/// 取出子树中的最小值, 返回剩下的子树
+fn take_min<T: Ord>(root: Box<AVLNode<T>>) -> (T, Option<Box<AVLNode<T>>>) {
+    let mut path = vec![];
+    let mut node = root;
+    while let Some(left) = node.left.take() {
+        path.push((node, Ordering::Greater));
+        node = left;
+    }
+
+    // 最小节点没有左子树, 用它的右子树代替它
+    let AVLNode { value, right, .. } = *node;
+    (value, rebuild(path, right))
 }
 
 /// 左旋
@@ -230,12 +263,11 @@ fn take_min<T: Ord>(mut root: AVLNode<T>) -> (T, Option<Box<AVLNode<T>>>) {
 ///        / \
 ///      T3  T4
 /// ```
-pub fn rotate_left<T>(mut node: AVLNode<T>) -> AVLNode<T> {
-    let right = node.right.take();
-    let mut right = *right.unwrap();
+pub fn rotate_left<T>(mut node: Box<AVLNode<T>>) -> Box<AVLNode<T>> {
+    let mut right = node.right.take().unwrap();
 
     node.right = right.left.take();
-    right.left = Some(Box::new(node));
+    right.left = Some(node);
 
     right
 }
@@ -251,12 +283,11 @@ pub fn rotate_left<T>(mut node: AVLNode<T>) -> AVLNode<T> {
 ///   / \
 /// T1   T2
 /// ```
-pub fn rotate_right<T>(mut node: AVLNode<T>) -> AVLNode<T> {
-    let left = node.left.take();
-    let mut left = *left.unwrap();
+pub fn rotate_right<T>(mut node: Box<AVLNode<T>>) -> Box<AVLNode<T>> {
+    let mut left = node.left.take().unwrap();
 
     node.left = left.right.take();
-    left.right = Some(Box::new(node));
+    left.right = Some(node);
 
     left
 }
@@ -281,32 +312,174 @@ impl<T: Debug> AVLNode<T> {
 
 impl<T: Ord + Debug> AVLTree<T> {
     pub fn new() -> Self {
-        Self { root: None }
+        Self {
+            root: None,
+            length: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.length
     }
 
     pub fn is_empty(&self) -> bool {
         self.root.is_none()
     }
 
-    pub fn insert(&mut self, value: T) {
-        match self.root.take() {
-            None => {
-                self.root = Some(AVLNode::new(value));
-            }
-            Some(node) => {
-                let root = insert(node, value);
-                self.root = Some(root);
+    pub fn clear(&mut self) {
+        self.root = None;
+        self.length = 0;
+    }
+
+    /// 查找树中等于 value 的值
+    pub fn find(&self, value: &T) -> Option<&T> {
+        let mut curr = self.root.as_deref();
+        while let Some(node) = curr {
+            match node.value.cmp(value) {
+                Ordering::Greater => curr = node.left.as_deref(),
+                Ordering::Less => curr = node.right.as_deref(),
+                Ordering::Equal => return Some(&node.value),
             }
         }
+
+        None
+    }
+
+    /// 查找树中是否存在 value
+    pub fn contains(&self, value: &T) -> bool {
+        self.find(value).is_some()
+    }
+
+    /// 插入值, 值已经存在时不插入并返回 false
+    pub fn insert(&mut self, value: T) -> bool {
+        let (root, inserted) = insert(self.root.take(), value);
+        self.root = Some(root);
+        if inserted {
+            self.length += 1;
+        }
+        inserted
+    }
+
+    /// 删除值, 返回被删除的值, 不存在时返回 None
+    pub fn delete(&mut self, value: &T) -> Option<T> {
+        let (root, removed) = delete(self.root.take(), value);
+        self.root = root;
+        if removed.is_some() {
+            self.length -= 1;
+        }
+        removed
+    }
+}
+
+impl<T> AVLTree<T> {
+    /// 树的高度, 空树为 0
+    pub fn height(&self) -> usize {
+        self.root.as_ref().map_or(0, |root| root.height)
+    }
+
+    /// 中序遍历引用迭代器
+    pub fn iter(&self) -> Iter<'_, T> {
+        let mut iter = Iter { stack: vec![] };
+        iter.push_left(self.root.as_deref());
+        iter
     }
+}
+
+/// 中序遍历引用迭代器
+pub struct Iter<'a, T> {
+    stack: Vec<&'a AVLNode<T>>,
+}
 
-    pub fn delete(&mut self, value: &T) {
-        if let Some(node) = self.root.take() {
-            self.root = delete(node, value).map(|node| *node);
+impl<'a, T> Iter<'a, T> {
+    fn push_left(&mut self, mut link: Option<&'a AVLNode<T>>) {
+        while let Some(node) = link {
+            self.stack.push(node);
+            link = node.left.as_deref();
         }
     }
 }
 
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.push_left(node.right.as_deref());
+        Some(&node.value)
+    }
+}
+
+/// 中序遍历值迭代器, 栈中的节点已经摘下了左子树
+pub struct IntoIter<T> {
+    stack: Vec<Box<AVLNode<T>>>,
+    remain: usize,
+}
+
+impl<T> IntoIter<T> {
+    fn push_left(&mut self, mut link: Option<Box<AVLNode<T>>>) {
+        while let Some(mut node) = link {
+            link = node.left.take();
+            self.stack.push(node);
+        }
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut node = self.stack.pop()?;
+        self.push_left(node.right.take());
+        self.remain -= 1;
+        Some(node.value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remain, Some(self.remain))
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for AVLTree<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let mut iter = IntoIter {
+            stack: vec![],
+            remain: self.length,
+        };
+        iter.push_left(self.root);
+        iter
+    }
+}
+
+impl<'a, T> IntoIterator for &'a AVLTree<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: Ord + Debug> FromIterator<T> for AVLTree<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut tree = AVLTree::new();
+        tree.extend(iter);
+        tree
+    }
+}
+
+impl<T: Ord + Debug> Extend<T> for AVLTree<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|value| {
+            self.insert(value);
+        });
+    }
+}
+
 impl<T: Ord + Debug> Default for AVLTree<T> {
     fn default() -> Self {
         Self::new()
@@ -322,6 +495,45 @@ impl<T: Debug> Debug for AVLTree<T> {
     }
 }
 
+/// 按中序序列化所有值, 反序列化时逐个插入重新构建
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    impl<T: Serialize> Serialize for AVLTree<T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(self.iter())
+        }
+    }
+
+    impl<'de, T: Deserialize<'de> + Ord + Debug> Deserialize<'de> for AVLTree<T> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            Ok(Vec::<T>::deserialize(deserializer)?.into_iter().collect())
+        }
+    }
+}
+
+impl<T: Debug> ToDot for AVLTree<T> {
+    /// 节点旁边标注子树高度
+    fn to_dot(&self) -> String {
+        let mut dot = Dot::digraph("AVLTree");
+        dot.stmt("node [shape=circle]");
+        if let Some(root) = self.root.as_deref() {
+            binary_tree(
+                &mut dot,
+                root,
+                &|node: &AVLNode<T>| (node.left.as_deref(), node.right.as_deref()),
+                &|dot: &mut Dot, node: &AVLNode<T>| {
+                    let height = node.height.to_string();
+                    dot.node(&format!("{:?}", node.value), &[("xlabel", &height)])
+                },
+            );
+        }
+        dot.finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -349,4 +561,131 @@ mod tests {
         assert_eq!(t.root.as_ref().map(|node| node.value), Some(6));
         println!("{:?}", t);
     }
+
+    /// 检查高度和平衡因子, 返回子树高度
+    fn check<T: Ord>(node: Option<&AVLNode<T>>) -> usize {
+        let Some(node) = node else {
+            return 0;
+        };
+        let hl = check(node.left.as_deref());
+        let hr = check(node.right.as_deref());
+        assert!(hl.abs_diff(hr) <= 1);
+        assert_eq!(node.height, hl.max(hr) + 1);
+        node.height
+    }
+
+    #[test]
+    fn test_avl_tree_random() {
+        use rand::{Rng, SeedableRng};
+        use std::collections::BTreeSet;
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0x5eed);
+        let mut t = AVLTree::new();
+        let mut set = BTreeSet::new();
+        for _ in 0..5000 {
+            let v = rng.gen_range(0..500);
+            if rng.gen_bool(0.6) {
+                t.insert(v);
+                set.insert(v);
+            } else {
+                t.delete(&v);
+                set.remove(&v);
+            }
+            assert_eq!(t.len(), set.len());
+        }
+        check(t.root.as_deref());
+        assert!(t.iter().eq(set.iter()));
+
+        // 直接调用不存在的值和重复的值
+        let (root, removed) = delete(t.root.take(), &1000);
+        assert_eq!(removed, None);
+        let (root, inserted) = insert(root, *set.first().unwrap());
+        assert!(!inserted);
+        check(Some(&root));
+    }
+
+    /// 有序插入 1e6 个值的冒烟测试, 每次插入都要沿最右侧的路径走到底再逐层旋转
+    #[test]
+    fn test_avl_tree_large() {
+        let n = 1_000_000;
+        let mut t = AVLTree::new();
+        for i in 0..n {
+            t.insert(i);
+        }
+        assert_eq!(t.len(), n);
+        // AVL 树的高度不超过 1.44 log2(n + 2)
+        let height = t.root.as_ref().unwrap().height;
+        assert!(height as f64 <= 1.44 * ((n + 2) as f64).log2());
+
+        for i in (0..n).step_by(2) {
+            t.delete(&i);
+        }
+        assert_eq!(t.len(), n / 2);
+        assert!(t.iter().copied().eq((1..n).step_by(2)));
+        check(t.root.as_deref());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_avl_tree_serde() {
+        let mut t = AVLTree::new();
+        for v in [5, 3, 8, 1, 4, 7, 9, 2, 6] {
+            t.insert(v);
+        }
+
+        let json = serde_json::to_string(&t).unwrap();
+        assert_eq!(json, "[1,2,3,4,5,6,7,8,9]");
+
+        let t: AVLTree<i32> = serde_json::from_str(&json).unwrap();
+        assert!((1..=9).all(|v| t.contains(&v)));
+        assert_eq!(serde_json::to_string(&t).unwrap(), json);
+    }
+
+    #[test]
+    fn test_avl_tree_iter() {
+        let mut t = AVLTree::new();
+        for i in [5, 1, 4, 1, 3, 9, 2, 6] {
+            t.insert(i);
+        }
+        assert_eq!(t.len(), 7);
+        assert_eq!(t.iter().copied().collect::<Vec<_>>(), [1, 2, 3, 4, 5, 6, 9]);
+        assert!(!t.insert(9));
+        assert!(t.insert(0));
+        assert_eq!(t.len(), 8);
+
+        // 删除叶子, 只有一个子节点以及有两个子节点的节点
+        assert_eq!(t.delete(&4), Some(4));
+        assert_eq!(t.delete(&4), None);
+        assert_eq!(t.delete(&0), Some(0));
+        let root = t.root.as_ref().unwrap().value;
+        assert_eq!(t.delete(&root), Some(root));
+        assert_eq!(t.len(), 5);
+
+        t.clear();
+        assert!(t.is_empty());
+        assert_eq!(t.iter().next(), None);
+        assert_eq!(t.height(), 0);
+    }
+
+    #[test]
+    fn test_avl_tree_api() {
+        let t: AVLTree<_> = (0..100).rev().chain(0..50).collect();
+        assert_eq!(t.len(), 100);
+        // 100 个节点的 AVL 树高度在 7 到 9 之间
+        assert!((7..=9).contains(&t.height()));
+        assert_eq!(t.find(&42), Some(&42));
+        assert_eq!(t.find(&100), None);
+        assert!(t.contains(&0) && !t.contains(&-1));
+
+        let mut sum = 0;
+        for v in &t {
+            sum += v;
+        }
+        assert_eq!(sum, 4950);
+
+        let mut it = t.into_iter();
+        assert_eq!(it.len(), 100);
+        assert_eq!(it.next(), Some(0));
+        assert!(it.eq(1..100));
+    }
 }
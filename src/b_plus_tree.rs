@@ -12,9 +12,20 @@
 //! - 每个节点上 key 的数目最少为 [(M+1)/2]-1
 //! - 每个节点上 children 的数目最少为 [(M+1)/2]
 //! - 实际上对于中间节点 children 的数目总是 key 数目 +1
+//!
+//! 阶数 M 至少为 3: M = 2 时节点最多只有 1 个 key, 分裂时 `mid = len/2` 会产生空节点,
+//! 最少 key 数目 [(M+1)/2]-1 也会变成 0
+//!
+//! 批量构建: 已知数据有序时不需要逐个插入, 可以自底向上构建整棵树:
+//! 先从左到右把数据填满叶子节点并串起来, 再把每 M 个节点作为一组构建上一层中间节点,
+//! 中间节点的 key 是右侧子树中的最小 key, 直到只剩一个节点作为根节点  
+//! 每一层只有最后一个节点可能不满足最少数目的约束, 这时把它和前一个节点平分即可  
+//! 整个过程是 O(n) 的, 并且除最后一个节点外都是满的, 空间利用率也比逐个插入(分裂后只有半满)更高
 
 use std::{fmt::Debug, iter::Zip, ptr::NonNull, slice::Iter};
 
+use crate::visualize::{Dot, ToDot};
+
 pub struct BPlusTree<K, V> {
     order: usize,
     length: usize,
@@ -30,7 +41,9 @@ pub struct Node<K, V> {
 }
 
 impl<K: Ord + Copy + Debug, V> BPlusTree<K, V> {
+    /// 创建一个 M 阶 B+ 树, M 至少为 3
     pub fn new(order: usize) -> Self {
+        assert!(order >= 3, "order of B+ tree must be at least 3");
         Self {
             order,
             length: 0,
@@ -38,6 +51,107 @@ impl<K: Ord + Copy + Debug, V> BPlusTree<K, V> {
         }
     }
 
+    /// 从按 key 升序排列的数据批量构建 B+ 树, key 重复时保留最后一个值
+    ///
+    /// ```
+    /// use impx::b_plus_tree::BPlusTree;
+    ///
+    /// let t = BPlusTree::from_sorted_iter((0..100).map(|i| (i, i * i)), 4);
+    /// assert_eq!(t.len(), 100);
+    /// assert_eq!(t.find(&9), Some((&9, &81)));
+    /// ```
+    pub fn from_sorted_iter<I: IntoIterator<Item = (K, V)>>(iter: I, order: usize) -> Self {
+        let mut tree = Self::new(order);
+
+        // 填充叶子节点, 每个叶子节点最多 order-1 个 key
+        let mut leaves = vec![];
+        let mut leaf = Node::new(true);
+        for (key, value) in iter {
+            if let Some(&last) = leaf.keys.last() {
+                assert!(last <= key, "keys must be sorted in ascending order");
+                if last == key {
+                    *leaf.values.last_mut().unwrap() = value;
+                    continue;
+                }
+            }
+
+            if leaf.keys.len() == order - 1 {
+                leaves.push(std::mem::replace(&mut leaf, Node::new(true)));
+            }
+            leaf.keys.push(key);
+            leaf.values.push(value);
+            tree.length += 1;
+        }
+
+        if leaf.keys.is_empty() {
+            return tree;
+        }
+        leaves.push(leaf);
+
+        // 最后一个叶子节点不满足约束时和前一个节点平分
+        let min_keys = order.div_ceil(2) - 1;
+        if let [.., prev, last] = leaves.as_mut_slice() {
+            if last.keys.len() < min_keys {
+                let mid = (prev.keys.len() + last.keys.len()) / 2;
+                let mut keys = prev.keys.split_off(mid);
+                let mut values = prev.values.split_off(mid);
+                keys.append(&mut last.keys);
+                values.append(&mut last.values);
+                (last.keys, last.values) = (keys, values);
+            }
+        }
+
+        // 每一层保存节点和子树中的最小 key, 从后向前串起叶子节点
+        let mut level = vec![];
+        let mut next = None;
+        for mut leaf in leaves.into_iter().rev() {
+            leaf.next = next;
+            let min_key = leaf.keys[0];
+            let ptr = unsafe { NonNull::new_unchecked(Box::into_raw(Box::new(leaf))) };
+            next = Some(ptr);
+            level.push((min_key, ptr));
+        }
+        level.reverse();
+
+        // 每 order 个节点一组构建上一层, 最后一组不满足约束时和前一组平分
+        let min_children = order.div_ceil(2);
+        while level.len() > 1 {
+            let mut sizes = vec![order; level.len() / order];
+            let rest = level.len() % order;
+            if rest > 0 {
+                sizes.push(rest);
+            }
+            if let [.., prev, last] = sizes.as_mut_slice() {
+                if *last < min_children {
+                    let total = *prev + *last;
+                    (*prev, *last) = (total / 2, total - total / 2);
+                }
+            }
+
+            let mut children = level.into_iter();
+            level = sizes
+                .into_iter()
+                .map(|size| {
+                    let mut node = Node::new(false);
+                    let mut min_key = None;
+                    for (key, child) in children.by_ref().take(size) {
+                        match min_key {
+                            None => min_key = Some(key),
+                            Some(_) => node.keys.push(key),
+                        }
+                        node.children.push(child);
+                    }
+
+                    let ptr = unsafe { NonNull::new_unchecked(Box::into_raw(Box::new(node))) };
+                    (min_key.unwrap(), ptr)
+                })
+                .collect();
+        }
+
+        tree.root = level.pop().map(|(_, root)| root);
+        tree
+    }
+
     pub fn len(&self) -> usize {
         self.length
     }
@@ -246,43 +360,49 @@ impl<K: Ord + Copy + Debug, V> BPlusTree<K, V> {
             let parent_mut = unsafe { parent_ptr.as_mut() };
 
             // 尝试左兄弟节点
+            // 叶子节点直接借取最后一个 key value, 然后更新父节点对应索引的 key
+            // 中间节点需要旋转: 父节点的 key 下降到当前节点, 左兄弟节点最后一个 key 上升到父节点
             if index > 0 {
                 let mut left_sibling_ptr = parent_mut.children[index - 1];
                 let left_sibling_mut = unsafe { left_sibling_ptr.as_mut() };
                 if left_sibling_mut.keys.len() > min_count {
                     let left_last_key = left_sibling_mut.keys.pop().unwrap();
-                    node_mut.keys.insert(0, left_last_key);
-                    if let Some(left_last_value) = left_sibling_mut.values.pop() {
+                    if node_mut.is_leaf {
+                        let left_last_value = left_sibling_mut.values.pop().unwrap();
+                        node_mut.keys.insert(0, left_last_key);
                         node_mut.values.insert(0, left_last_value);
-                    }
-                    if let Some(left_last_child) = left_sibling_mut.children.pop() {
+                        parent_mut.keys[index - 1] = left_last_key;
+                    } else {
+                        let left_last_child = left_sibling_mut.children.pop().unwrap();
+                        let mid_key =
+                            std::mem::replace(&mut parent_mut.keys[index - 1], left_last_key);
+                        node_mut.keys.insert(0, mid_key);
                         node_mut.children.insert(0, left_last_child);
                     }
 
-                    // 修改父节点对应索引 key
-                    parent_mut.keys[index - 1] = node_mut.keys[0];
                     return Some(entry);
                 }
             }
 
-            // 尝试右兄弟节点
+            // 尝试右兄弟节点, 同理
             if index + 1 < parent_mut.children.len() {
                 let mut right_sibling_ptr = parent_mut.children[index + 1];
                 let right_sibling_mut = unsafe { right_sibling_ptr.as_mut() };
                 if right_sibling_mut.keys.len() > min_count {
                     let right_first_key = right_sibling_mut.keys.remove(0);
-                    node_mut.keys.push(right_first_key);
-                    if !right_sibling_mut.values.is_empty() {
+                    if node_mut.is_leaf {
                         let right_first_value = right_sibling_mut.values.remove(0);
+                        node_mut.keys.push(right_first_key);
                         node_mut.values.push(right_first_value);
-                    }
-                    if !right_sibling_mut.children.is_empty() {
+                        parent_mut.keys[index] = right_sibling_mut.keys[0];
+                    } else {
                         let right_first_child = right_sibling_mut.children.remove(0);
+                        let mid_key =
+                            std::mem::replace(&mut parent_mut.keys[index], right_first_key);
+                        node_mut.keys.push(mid_key);
                         node_mut.children.push(right_first_child);
                     }
 
-                    // 修改父节点对应索引 key
-                    parent_mut.keys[index] = right_sibling_mut.keys[0];
                     return Some(entry);
                 }
             }
@@ -438,6 +558,16 @@ impl<K: Ord + Copy, V> Node<K, V> {
     }
 }
 
+impl<K, V> Drop for BPlusTree<K, V> {
+    fn drop(&mut self) {
+        let mut stack: Vec<_> = self.root.take().into_iter().collect();
+        while let Some(ptr) = stack.pop() {
+            let mut node = unsafe { Box::from_raw(ptr.as_ptr()) };
+            stack.append(&mut node.children);
+        }
+    }
+}
+
 impl<K: Debug, V: Debug> Debug for Node<K, V> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -478,10 +608,218 @@ impl<K: Debug, V: Debug> Debug for BPlusTree<K, V> {
     }
 }
 
+/// 序列化为 `(阶数, 按 key 升序的 key-value 列表)`, 反序列化时使用批量构建
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+    use serde::{de::Error, ser::SerializeTuple, Deserialize, Deserializer, Serialize, Serializer};
+
+    struct Entries<'a, K, V>(&'a BPlusTree<K, V>);
+
+    impl<K: Ord + Copy + Debug + Serialize, V: Serialize> Serialize for Entries<'_, K, V> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(self.0.iter())
+        }
+    }
+
+    impl<K: Ord + Copy + Debug + Serialize, V: Serialize> Serialize for BPlusTree<K, V> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut tuple = serializer.serialize_tuple(2)?;
+            tuple.serialize_element(&self.order)?;
+            tuple.serialize_element(&Entries(self))?;
+            tuple.end()
+        }
+    }
+
+    impl<'de, K, V> Deserialize<'de> for BPlusTree<K, V>
+    where
+        K: Ord + Copy + Debug + Deserialize<'de>,
+        V: Deserialize<'de>,
+    {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let (order, entries) = <(usize, Vec<(K, V)>)>::deserialize(deserializer)?;
+            if order < 3 {
+                return Err(D::Error::custom("order of B+ tree must be at least 3"));
+            }
+            if !entries.windows(2).all(|w| w[0].0 < w[1].0) {
+                return Err(D::Error::custom("keys must be strictly increasing"));
+            }
+
+            Ok(BPlusTree::from_sorted_iter(entries, order))
+        }
+    }
+}
+
+impl<K: Debug, V> ToDot for BPlusTree<K, V> {
+    /// 每个节点输出为一个 record, 叶子节点放在同一层并用虚线连接 next 指针
+    fn to_dot(&self) -> String {
+        type Leaves<K, V> = Vec<(NonNull<Node<K, V>>, usize)>;
+
+        fn visit<K: Debug, V>(
+            dot: &mut Dot,
+            ptr: NonNull<Node<K, V>>,
+            leaves: &mut Leaves<K, V>,
+        ) -> usize {
+            let node = unsafe { ptr.as_ref() };
+            let keys: Vec<_> = node.keys.iter().map(|k| format!("{:?}", k)).collect();
+            let id = dot.record(&keys, &[]);
+            if node.is_leaf {
+                leaves.push((ptr, id));
+            }
+            for &child in &node.children {
+                let child = visit(dot, child, leaves);
+                dot.edge(id, child, &[]);
+            }
+            id
+        }
+
+        let mut dot = Dot::digraph("BPlusTree");
+        if let Some(root) = self.root {
+            let mut leaves = vec![];
+            visit(&mut dot, root, &mut leaves);
+
+            for &(ptr, id) in &leaves {
+                let next = unsafe { ptr.as_ref() }.next;
+                if let Some(&(_, next)) = leaves.iter().find(|(leaf, _)| Some(*leaf) == next) {
+                    dot.edge(id, next, &[("style", "dashed"), ("constraint", "false")]);
+                }
+            }
+
+            let ids: Vec<_> = leaves.iter().map(|(_, id)| format!("n{}", id)).collect();
+            dot.stmt(&format!("{{ rank=same; {} }}", ids.join("; ")));
+        }
+        dot.finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// 检查 B+ 树的所有约束, 返回树的高度
+    fn check_invariants<K: Ord + Copy + Debug, V>(t: &BPlusTree<K, V>) -> usize {
+        let root = match t.root {
+            None => {
+                assert_eq!(t.len(), 0);
+                return 0;
+            }
+            Some(root) => root,
+        };
+
+        let min_keys = t.order.div_ceil(2) - 1;
+        let mut leaves = vec![];
+
+        // 检查以 ptr 为根的子树, 所有 key 都在 [lo, hi) 中, 返回子树高度
+        fn check<K: Ord + Copy + Debug, V>(
+            ptr: NonNull<Node<K, V>>,
+            lo: Option<K>,
+            hi: Option<K>,
+            is_root: bool,
+            (order, min_keys): (usize, usize),
+            leaves: &mut Vec<NonNull<Node<K, V>>>,
+        ) -> usize {
+            let node = unsafe { ptr.as_ref() };
+            assert!(node.keys.len() < order, "too many keys: {:?}", node.keys);
+            assert!(
+                is_root || node.keys.len() >= min_keys,
+                "too few keys: {:?}",
+                node.keys
+            );
+            assert!(
+                node.keys.windows(2).all(|w| w[0] < w[1]),
+                "keys not sorted: {:?}",
+                node.keys
+            );
+            assert!(node
+                .keys
+                .iter()
+                .all(|k| lo.is_none_or(|lo| lo <= *k) && hi.is_none_or(|hi| *k < hi)));
+
+            if node.is_leaf {
+                assert_eq!(node.values.len(), node.keys.len());
+                assert!(node.children.is_empty());
+                leaves.push(ptr);
+                return 1;
+            }
+
+            assert!(node.values.is_empty());
+            assert_eq!(node.children.len(), node.keys.len() + 1);
+            assert!(is_root || !node.keys.is_empty());
+            let heights: Vec<_> = node
+                .children
+                .iter()
+                .enumerate()
+                .map(|(i, &child)| {
+                    let lo = if i == 0 { lo } else { Some(node.keys[i - 1]) };
+                    let hi = node.keys.get(i).copied().or(hi);
+                    check(child, lo, hi, false, (order, min_keys), leaves)
+                })
+                .collect();
+            assert!(
+                heights.windows(2).all(|w| w[0] == w[1]),
+                "leaves not on the same level"
+            );
+
+            heights[0] + 1
+        }
+
+        let height = check(root, None, None, true, (t.order, min_keys), &mut leaves);
+
+        // 叶子节点的 next 指针按顺序串起所有叶子节点
+        for w in leaves.windows(2) {
+            assert_eq!(unsafe { w[0].as_ref() }.next, Some(w[1]));
+        }
+        assert_eq!(unsafe { leaves.last().unwrap().as_ref() }.next, None);
+
+        let count: usize = leaves
+            .iter()
+            .map(|leaf| unsafe { leaf.as_ref() }.keys.len())
+            .sum();
+        assert_eq!(count, t.len());
+        assert_eq!(t.iter().count(), t.len());
+
+        height
+    }
+
+    #[test]
+    #[should_panic(expected = "at least 3")]
+    fn bptree_invalid_order() {
+        BPlusTree::<i32, ()>::new(2);
+    }
+
+    #[test]
+    fn bptree_random_invariants() {
+        use rand::Rng;
+        use std::collections::BTreeMap;
+
+        let mut rng = rand::thread_rng();
+        for order in 3..9 {
+            let mut t = BPlusTree::new(order);
+            let mut expect = BTreeMap::new();
+            for _ in 0..2000 {
+                let key = rng.gen_range(0..200);
+                if rng.gen_bool(0.55) {
+                    let value = rng.gen::<u32>();
+                    assert_eq!(
+                        t.insert((key, value)).map(|e| e.1),
+                        expect.insert(key, value)
+                    );
+                } else {
+                    assert_eq!(t.delete(&key).map(|e| e.1), expect.remove(&key));
+                }
+                check_invariants(&t);
+            }
+
+            let items: Vec<_> = t.iter().map(|(&k, &v)| (k, v)).collect();
+            assert_eq!(items, expect.into_iter().collect::<Vec<_>>());
+        }
+
+        for order in 3..8 {
+            let t = BPlusTree::from_sorted_iter((0..500).map(|i| (i, ())), order);
+            assert!(check_invariants(&t) > 1);
+        }
+    }
+
     #[test]
     fn bptree_insert() {
         let mut t = BPlusTree::new(3);
@@ -561,4 +899,62 @@ mod tests {
             index += 1;
         }
     }
+
+    #[test]
+    fn bptree_from_sorted_iter() {
+        assert!(BPlusTree::<i32, ()>::from_sorted_iter(vec![], 3).is_empty());
+
+        // 和逐个插入的结果一致
+        for order in 3..8 {
+            for n in 0..200 {
+                let t = BPlusTree::from_sorted_iter((0..n).map(|i| (i, i * 2)), order);
+                assert_eq!(t.len(), n);
+                let items: Vec<_> = t.iter().map(|(&k, &v)| (k, v)).collect();
+                assert_eq!(items, (0..n).map(|i| (i, i * 2)).collect::<Vec<_>>());
+                for i in 0..n {
+                    assert_eq!(t.find(&i), Some((&i, &(i * 2))));
+                }
+                assert_eq!(t.find(&n), None);
+            }
+        }
+
+        // 重复的 key 保留最后一个值
+        let t = BPlusTree::from_sorted_iter([(1, 'a'), (1, 'b'), (2, 'c')], 3);
+        assert_eq!(t.len(), 2);
+        assert_eq!(t.find(&1), Some((&1, &'b')));
+
+        // 批量构建之后还可以继续插入和删除
+        let mut t = BPlusTree::from_sorted_iter((0..100).map(|i| (i * 2, ())), 4);
+        for i in 0..100 {
+            assert!(t.insert((i * 2 + 1, ())).is_none());
+        }
+        for i in 0..100 {
+            assert!(t.delete(&(i * 2)).is_some());
+        }
+        assert_eq!(
+            t.iter().map(|(&k, _)| k).collect::<Vec<_>>(),
+            (0..100).map(|i| i * 2 + 1).collect::<Vec<_>>()
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn bptree_serde() {
+        let mut t = BPlusTree::new(4);
+        for i in (0..50).rev() {
+            t.insert((i, i.to_string()));
+        }
+
+        let json = serde_json::to_string(&t).unwrap();
+        assert!(json.starts_with(r#"[4,[[0,"0"],[1,"1"]"#));
+
+        let t: BPlusTree<i32, String> = serde_json::from_str(&json).unwrap();
+        check_invariants(&t);
+        assert_eq!(t.len(), 50);
+        assert_eq!(t.find(&42), Some((&42, &"42".to_string())));
+        assert_eq!(serde_json::to_string(&t).unwrap(), json);
+
+        assert!(serde_json::from_str::<BPlusTree<i32, ()>>("[2,[]]").is_err());
+        assert!(serde_json::from_str::<BPlusTree<i32, ()>>("[3,[[2,null],[1,null]]]").is_err());
+    }
 }
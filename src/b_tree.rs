@@ -6,16 +6,25 @@
 //!
 //! M 阶 `B-Tree` 规则:
 //!
-//! - 每个节点最多有 M 个子节点
-//! - 内部节点最少有 (M-1)/2 个 value 节点
+//! - 每个节点最多有 M 个子节点, 即最多 M-1 个键
+//! - 除根节点外每个节点最少有 ⌈M/2⌉-1 = (M-1)/2 个键
 //! - 如果根节点不是叶子节点, 则至少有两个子节点
 //! - 有 k 个子节点的非叶子节点有 k-1 个键
 //! - 所有的叶子节点在同一层
 //!
+//! 阶数 M 至少为 3, M = 2 时节点最多只有 1 个 key, 分裂后会产生空节点
+//!
+//! 插入分裂和删除再平衡都以最少键数 (M-1)/2 为准:
+//!
+//! - 分裂: 满节点有 M 个键, 取出一个上升后左边保留 M/2 个, 右边 (M-1)/2 个, 都不少于最少键数
+//! - 合并: 一个节点少于最少键数且兄弟恰好等于最少键数时, 合并后有 2*((M-1)/2) ≤ M-1 个键
+//!
 //! 具体实现详情见代码内注释
 
 use std::{fmt::Debug, ptr::NonNull};
 
+use crate::visualize::{Dot, ToDot};
+
 type Entry<K, V> = (K, V);
 
 /// B-Tree 节点
@@ -32,8 +41,9 @@ pub struct BTree<K, V> {
 }
 
 impl<K: Ord, V> BTree<K, V> {
-    /// 创建一个 M 阶 B-Tree
+    /// 创建一个 M 阶 B-Tree, M 至少为 3
     pub fn new(order: usize) -> Self {
+        assert!(order >= 3, "order of B-Tree must be at least 3");
         let root = BTreeNode::new(order);
         let root = unsafe { root.into_raw_ptr() };
 
@@ -66,6 +76,18 @@ impl<K: Ord, V> BTree<K, V> {
         root.max()
     }
 
+    /// 查找 key 是否存在
+    pub fn contains(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// 按 key 升序遍历的引用迭代器
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        let mut iter = Iter { stack: vec![] };
+        iter.push_left(unsafe { self.root.as_ref() });
+        iter
+    }
+
     /// 向 B-Tree 中插入 Entry 如果对应 key 已经存在则将旧值换出
     pub fn insert(&mut self, entry: Entry<K, V>) -> Option<Entry<K, V>> {
         let key = &entry.0;
@@ -111,7 +133,8 @@ impl<K: Ord, V> BTree<K, V> {
             // 取中间节点上升到上一级, 左右两边分裂作为新的子节点
             // 取出后半部分的值放到新的节点上
             // 对于非叶子节点同理取出后半部分的 children 放到新的节点上
-            let mid_idx = (node.order / 2) + 1;
+            // 新节点得到 order - mid_idx = min_len 个值, 原节点弹出中间节点后剩下 order/2 个值
+            let mid_idx = node.order - node.min_len();
             let mut new_node = BTreeNode::new(node.order);
             new_node.values = node.values.drain(mid_idx..).collect();
             if !node.is_leaf() {
@@ -205,22 +228,23 @@ impl<K: Ord, V> BTree<K, V> {
 
         self.length -= 1;
 
-        // 如果叶子节点上的值数量仍然大于等于阶数的一半则无需重新平衡
+        // 如果叶子节点上的值数量仍然不少于最少键数则无需重新平衡
         let mut node = unsafe { curr_node.as_mut() };
-        if node.values.len() >= node.order / 2 {
+        let limit = node.min_len();
+        if node.values.len() >= limit {
             return Some(old_val);
         }
 
-        let order = node.order;
-        let limit = (order - 1) / 2;
-        // 限制: 每个节点最少有 limit 个 value
+        // 限制: 除根节点外每个节点最少有 limit 个 value
         // 平衡流程, 如果当前节点元素 n0 < limit
         // 1. 如果右兄弟有多余元素(n1 > limit), 用其最小值交换间隔父节点, 把父节点交换下来
         // 2. 如果左兄弟有多余元素(n2 > limit), 用其最大值交换间隔父节点, 把父节点交换下来
         // 3. 如果左右兄弟都没有多余元素即 n1 <= limit && n2 <= limit
         //    则可以将当前节点与某个兄弟加上间隔的父节点合并, 比如合并右节点
-        //    n0 + n2 + 1< 2*limit + 1 = order
+        //    n0 + n1 + 1 = (limit - 1) + limit + 1 = 2*limit <= order - 1
         //    合并完的节点数量依然是合法的
+        // 借用兄弟节点不改变父节点的 value 数量, 借用之后直接结束
+        // 合并会从父节点中取走一个 value, 父节点少于 limit 时继续向上平衡
 
         while let Some((mut parent, index)) = parents.pop() {
             let par_node = unsafe { parent.as_mut() };
@@ -236,17 +260,12 @@ impl<K: Ord, V> BTree<K, V> {
                 if sib_left.values.len() > limit {
                     let sib_left_max = sib_left.values.pop().expect("");
                     let par_mid_val = std::mem::replace(par_mid_val, sib_left_max);
-                    node.values.push(par_mid_val);
+                    node.values.insert(0, par_mid_val);
                     if !node.is_leaf() {
                         node.children.insert(0, sib_left.children.pop().expect(""));
                     }
 
-                    node = par_node;
-                    if node.values.len() >= limit {
-                        return Some(old_val);
-                    }
-
-                    continue;
+                    return Some(old_val);
                 }
             }
 
@@ -263,12 +282,7 @@ impl<K: Ord, V> BTree<K, V> {
                         node.children.push(sib_right.children.remove(0));
                     }
 
-                    node = par_node;
-                    if node.values.len() >= limit {
-                        return Some(old_val);
-                    }
-
-                    continue; // 递归向上
+                    return Some(old_val);
                 }
             }
 
@@ -296,9 +310,13 @@ impl<K: Ord, V> BTree<K, V> {
             left_node.values.append(&mut right_node.values);
             left_node.children.append(&mut right_node.children);
 
+            // 右边节点已经被清空, 直接释放
+            drop(unsafe { Box::from_raw(right.as_ptr()) });
+
             // 如果当前父节点是根节点且是空节点则直接替换根节点
             if par_node.values.is_empty() && parents.is_empty() {
                 self.root = left;
+                drop(unsafe { Box::from_raw(parent.as_ptr()) });
                 return Some(old_val);
             }
 
@@ -313,6 +331,124 @@ impl<K: Ord, V> BTree<K, V> {
     }
 }
 
+impl<K: Ord + Debug, V> BTree<K, V> {
+    /// 检查 B-Tree 的所有性质, 不满足时 panic, 返回树的高度(只有根节点时为 1)
+    ///
+    /// - 每个节点的 value 数量不超过 order-1, 除根节点外不少于 (order-1)/2
+    /// - 非叶子节点的 children 数量等于 value 数量加一, 非叶子的根节点至少有一个 value
+    /// - 节点内的 key 严格递增, 且落在父节点对应的两个 key 之间
+    /// - 所有叶子节点在同一层, 节点总数等于 length
+    pub fn validate(&self) -> usize {
+        let mut count = 0;
+        let height = validate(self.root, None, None, true, &mut count);
+        assert_eq!(count, self.length, "length mismatch");
+        height
+    }
+}
+
+/// 检查以 node 为根的子树, 子树中的 key 都必须在 (lo, hi) 范围内, 返回子树高度
+fn validate<K: Ord + Debug, V>(
+    node_ptr: NonNull<BTreeNode<K, V>>,
+    lo: Option<&K>,
+    hi: Option<&K>,
+    is_root: bool,
+    count: &mut usize,
+) -> usize {
+    let node = unsafe { node_ptr.as_ref() };
+    let keys: Vec<_> = node.values.iter().map(|e| &e.0).collect();
+    *count += keys.len();
+
+    assert!(keys.len() < node.order, "too many keys: {:?}", keys);
+    assert!(
+        is_root || keys.len() >= node.min_len(),
+        "too few keys: {:?}",
+        keys
+    );
+    assert!(
+        keys.windows(2).all(|w| w[0] < w[1]),
+        "keys not sorted: {:?}",
+        keys
+    );
+    assert!(
+        keys.iter()
+            .all(|&k| lo.is_none_or(|lo| lo < k) && hi.is_none_or(|hi| k < hi)),
+        "keys out of range: {:?}",
+        keys
+    );
+
+    if node.is_leaf() {
+        return 1;
+    }
+
+    assert!(!keys.is_empty(), "internal node without keys");
+    assert_eq!(
+        node.children.len(),
+        keys.len() + 1,
+        "children count mismatch: {:?}",
+        keys
+    );
+
+    let mut heights = node.children.iter().enumerate().map(|(i, &child)| {
+        let lo = if i == 0 { lo } else { Some(keys[i - 1]) };
+        let hi = keys.get(i).copied().or(hi);
+        validate(child, lo, hi, false, count)
+    });
+    let height = heights.next().expect("internal node has children");
+    assert!(heights.all(|h| h == height), "leaves at different depths");
+    height + 1
+}
+
+impl<K, V> Drop for BTree<K, V> {
+    fn drop(&mut self) {
+        let mut stack = vec![self.root];
+        while let Some(ptr) = stack.pop() {
+            let mut node = unsafe { Box::from_raw(ptr.as_ptr()) };
+            stack.append(&mut node.children);
+        }
+    }
+}
+
+/// 中序遍历引用迭代器
+///
+/// 栈中保存节点和下一个要访问的 value 的下标, 访问完第 i 个 value 之后接着访问第 i+1 棵子树
+pub struct Iter<'a, K, V> {
+    stack: Vec<(&'a BTreeNode<K, V>, usize)>,
+}
+
+impl<'a, K, V> Iter<'a, K, V> {
+    fn push_left(&mut self, mut node: &'a BTreeNode<K, V>) {
+        loop {
+            self.stack.push((node, 0));
+            match node.children.first() {
+                None => break,
+                Some(child) => node = unsafe { child.as_ref() },
+            }
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (node, index) = self.stack.last_mut()?;
+            let node: &'a BTreeNode<K, V> = node;
+            if *index == node.values.len() {
+                self.stack.pop();
+                continue;
+            }
+
+            let entry = &node.values[*index];
+            *index += 1;
+            if let Some(child) = node.children.get(*index) {
+                self.push_left(unsafe { child.as_ref() });
+            }
+            return Some((&entry.0, &entry.1));
+        }
+    }
+}
+
 impl<K: Ord, V> BTreeNode<K, V> {
     pub fn new(order: usize) -> Self {
         Self {
@@ -322,6 +458,11 @@ impl<K: Ord, V> BTreeNode<K, V> {
         }
     }
 
+    /// 除根节点外每个节点最少的 value 数量 ⌈order/2⌉-1
+    fn min_len(&self) -> usize {
+        (self.order - 1) / 2
+    }
+
     /// 是否叶子节点
     pub fn is_leaf(&self) -> bool {
         self.children.is_empty()
@@ -401,6 +542,28 @@ impl<K: Debug, V: Debug> Debug for BTree<K, V> {
     }
 }
 
+impl<K: Debug, V> ToDot for BTree<K, V> {
+    /// 每个节点输出为一个 record, 字段为节点上的所有 key
+    fn to_dot(&self) -> String {
+        fn visit<K: Debug, V>(dot: &mut Dot, node: NonNull<BTreeNode<K, V>>) -> usize {
+            let node = unsafe { node.as_ref() };
+            let keys: Vec<_> = node.values.iter().map(|e| format!("{:?}", e.0)).collect();
+            let id = dot.record(&keys, &[]);
+            for &child in &node.children {
+                let child = visit(dot, child);
+                dot.edge(id, child, &[]);
+            }
+            id
+        }
+
+        let mut dot = Dot::digraph("BTree");
+        if self.length > 0 {
+            visit(&mut dot, self.root);
+        }
+        dot.finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -450,4 +613,70 @@ mod tests {
 
         assert!(t.is_empty());
     }
+
+    #[test]
+    fn test_btree_validate_all_orders() {
+        use rand::{Rng, SeedableRng};
+        use std::collections::BTreeMap;
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0x5eed);
+        for order in 3..=8 {
+            // 不同的 key 范围分别对应稀疏和密集的负载, 密集时删除命中率更高, 合并更频繁
+            for keys in [16, 64, 1024] {
+                let mut t = BTree::new(order);
+                let mut map = BTreeMap::new();
+                for _ in 0..4000 {
+                    let key = rng.gen_range(0..keys);
+                    if rng.gen_bool(0.55) {
+                        let value = rng.gen::<u32>();
+                        assert_eq!(t.insert((key, value)).map(|e| e.1), map.insert(key, value));
+                    } else {
+                        assert_eq!(t.delete(&key).map(|e| e.1), map.remove(&key));
+                    }
+                    t.validate();
+                }
+
+                assert!(t.iter().eq(map.iter()));
+                // 按随机顺序删除所有 key, 每一步都检查
+                let mut rest: Vec<_> = map.keys().copied().collect();
+                while !rest.is_empty() {
+                    let key = rest.swap_remove(rng.gen_range(0..rest.len()));
+                    assert!(t.delete(&key).is_some());
+                    t.validate();
+                }
+                assert!(t.is_empty());
+                assert_eq!(t.validate(), 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_btree_sequential_fill() {
+        // 顺序插入时每次分裂的都是最右边的节点, 顺序删除时每次都从最左边借用或合并
+        for order in 3..=8 {
+            let mut t = BTree::new(order);
+            for i in 0..500 {
+                t.insert((i, ()));
+                t.validate();
+            }
+            for i in 0..500 {
+                assert!(t.delete(&i).is_some());
+                t.validate();
+            }
+            for i in (0..500).rev() {
+                t.insert((i, ()));
+            }
+            t.validate();
+            for i in (0..500).rev() {
+                assert!(t.delete(&i).is_some());
+                t.validate();
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "at least 3")]
+    fn test_btree_invalid_order() {
+        BTree::<i32, ()>::new(2);
+    }
 }
@@ -0,0 +1,419 @@
+//! 括号序列表示的简洁树
+//!
+//! 参考:
+//!
+//! - [OI Wiki - 简洁数据结构](https://oi-wiki.org/ds/succinct/)
+//! - Navarro, Sadakane. Fully Functional Static and Dynamic Succinct Trees
+//!
+//! 深度优先遍历一棵有 n 个节点的有序树, 进入节点时写一个 `(`(1), 离开时写一个 `)`(0),
+//! 得到长度为 2n 的平衡括号序列, 节点用它的左括号在序列中的位置表示。
+//! 序列存放在 [`BitSet`] 中, 借助 [`RankSelect`] 可以在节点编号和位置之间转换:
+//!
+//! - 节点的先序编号是它之前左括号的个数 `rank1(v)`, 第 k 个节点的位置是 `select1(k)`
+//! - 节点的深度是它之前的超额(左括号数减右括号数) `rank1(v) - rank0(v)`
+//!
+//! 其余的导航操作都归结为在序列上查找超额变化为某个值的第一个位置:
+//!
+//! - `find_close(v)`: v 之后第一个使超额下降 1 的位置, 即匹配的右括号, 子树大小为两者距离的一半
+//! - `find_open(j)` / `enclose(v)`: 往前第一个使超额上升 1 的位置, 分别是匹配的左括号和父节点
+//!
+//! 对每个 64 位的字预处理总超额、最小前缀超额和最大后缀超额, 查找时可以整个字跳过,
+//! 完整的实现会在字之上再建一棵 range min-max 树把查找降到 O(logn), 这里省略
+//!
+//! 二叉树通过左孩子右兄弟的方式转成有序树: 左孩子是第一个孩子, 右孩子是下一个兄弟,
+//! 再加一个虚拟的根把原来的根和它的右链作为孩子, 所以 n 个节点的二叉树需要 2n + 2 位
+
+use std::fmt;
+
+use crate::binary_tree::BinaryTree;
+use crate::bitset::{BitSet, RankSelect};
+
+const WORD_SIZE: usize = 64;
+
+/// 一个字内的超额信息
+#[derive(Clone, Copy)]
+struct WordExcess {
+    /// 整个字的超额
+    excess: i8,
+    /// 从字开头开始的前缀超额的最小值
+    min_prefix: i8,
+    /// 到字结尾为止的后缀超额的最大值
+    max_suffix: i8,
+}
+
+/// 平衡括号序列表示的静态有序树
+pub struct BpTree {
+    /// 序列长度, 即节点数的两倍
+    len: usize,
+    bits: RankSelect,
+    words: Vec<WordExcess>,
+}
+
+impl BpTree {
+    fn from_parens(parens: &[bool]) -> Self {
+        let mut bs = BitSet::new(parens.len());
+        for (i, &open) in parens.iter().enumerate() {
+            if open {
+                bs.set(i);
+            }
+        }
+
+        let words = parens
+            .chunks(WORD_SIZE)
+            .map(|chunk| {
+                let steps: Vec<i8> = chunk
+                    .iter()
+                    .map(|&open| if open { 1 } else { -1 })
+                    .collect();
+                let (mut excess, mut min_prefix) = (0, i8::MAX);
+                for &s in &steps {
+                    excess += s;
+                    min_prefix = min_prefix.min(excess);
+                }
+                let (mut suffix, mut max_suffix) = (0, i8::MIN);
+                for &s in steps.iter().rev() {
+                    suffix += s;
+                    max_suffix = max_suffix.max(suffix);
+                }
+                WordExcess {
+                    excess,
+                    min_prefix,
+                    max_suffix,
+                }
+            })
+            .collect();
+
+        Self {
+            len: parens.len(),
+            bits: RankSelect::new(&bs),
+            words,
+        }
+    }
+
+    /// 用孩子列表表示的有序树构建, 节点的孩子按列表中的顺序排列
+    pub fn from_children(children: &[Vec<usize>], root: usize) -> Self {
+        let mut parens = Vec::with_capacity(children.len() * 2);
+        // 栈中保存节点和下一个要访问的孩子
+        let mut stack = vec![(root, 0)];
+        parens.push(true);
+        while let Some((u, i)) = stack.last_mut() {
+            match children[*u].get(*i) {
+                Some(&v) => {
+                    *i += 1;
+                    parens.push(true);
+                    stack.push((v, 0));
+                }
+                None => {
+                    parens.push(false);
+                    stack.pop();
+                }
+            }
+        }
+        Self::from_parens(&parens)
+    }
+
+    /// 用左孩子右兄弟的方式编码二叉树, 根是虚拟节点, 二叉树的根是 `node(1)`
+    ///
+    /// 二叉树的节点按先序编号为 1..=n, 左右孩子分别对应 [`first_child`](Self::first_child)
+    /// 和 [`next_sibling`](Self::next_sibling)
+    pub fn from_binary_tree<T>(tree: &BinaryTree<T>) -> Self {
+        fn encode<T>(tree: Option<&BinaryTree<T>>, parens: &mut Vec<bool>) {
+            // 依次输出 tree 和它的右链上的节点, 它们互为兄弟
+            let mut curr = tree;
+            while let Some(node) = curr {
+                parens.push(true);
+                encode(node.left.as_deref(), parens);
+                parens.push(false);
+                curr = node.right.as_deref();
+            }
+        }
+
+        let mut parens = vec![true];
+        encode(Some(tree), &mut parens);
+        parens.push(false);
+        Self::from_parens(&parens)
+    }
+
+    /// 节点数
+    pub fn len(&self) -> usize {
+        self.len / 2
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn is_open(&self, i: usize) -> bool {
+        i < self.len && self.bits.get(i)
+    }
+
+    fn step(&self, i: usize) -> i64 {
+        if self.bits.get(i) {
+            1
+        } else {
+            -1
+        }
+    }
+
+    /// 最小的 j > i 使得 `(i, j]` 的超额为 d, d 为负数
+    fn fwd_search(&self, i: usize, d: i64) -> Option<usize> {
+        let (mut cur, mut j) = (0, i + 1);
+        while j < self.len && j % WORD_SIZE != 0 {
+            cur += self.step(j);
+            if cur == d {
+                return Some(j);
+            }
+            j += 1;
+        }
+
+        // 超额每次只变化 1, 字内的最小前缀超额没有达到 d 就可以跳过整个字
+        while j < self.len {
+            let w = self.words[j / WORD_SIZE];
+            if cur + w.min_prefix as i64 <= d {
+                break;
+            }
+            cur += w.excess as i64;
+            j += WORD_SIZE;
+        }
+
+        while j < self.len {
+            cur += self.step(j);
+            if cur == d {
+                return Some(j);
+            }
+            j += 1;
+        }
+        None
+    }
+
+    /// 最大的 p < i 使得 `[p, i)` 的超额为 d, d 为正数
+    fn bwd_search(&self, i: usize, d: i64) -> Option<usize> {
+        let (mut cur, mut p) = (0, i);
+        while p > 0 && p % WORD_SIZE != 0 {
+            p -= 1;
+            cur += self.step(p);
+            if cur == d {
+                return Some(p);
+            }
+        }
+
+        while p >= WORD_SIZE {
+            let w = self.words[p / WORD_SIZE - 1];
+            if cur + w.max_suffix as i64 >= d {
+                break;
+            }
+            cur += w.excess as i64;
+            p -= WORD_SIZE;
+        }
+
+        while p > 0 {
+            p -= 1;
+            cur += self.step(p);
+            if cur == d {
+                return Some(p);
+            }
+        }
+        None
+    }
+
+    /// 位置 i 的左括号匹配的右括号
+    pub fn find_close(&self, i: usize) -> usize {
+        assert!(self.is_open(i), "not an open parenthesis");
+        self.fwd_search(i, -1).unwrap()
+    }
+
+    /// 位置 j 的右括号匹配的左括号
+    pub fn find_open(&self, j: usize) -> usize {
+        assert!(j < self.len && !self.is_open(j), "not a close parenthesis");
+        self.bwd_search(j, 1).unwrap()
+    }
+
+    /// 包住位置 i 的左括号的最近一对括号, 即父节点
+    pub fn enclose(&self, i: usize) -> Option<usize> {
+        assert!(self.is_open(i), "not an open parenthesis");
+        self.bwd_search(i, 1)
+    }
+
+    pub fn root(&self) -> usize {
+        0
+    }
+
+    /// 先序编号为 k 的节点, 根的编号为 0
+    pub fn node(&self, k: usize) -> Option<usize> {
+        self.bits.select1(k).filter(|&v| v < self.len)
+    }
+
+    /// 节点的先序编号
+    pub fn preorder(&self, v: usize) -> usize {
+        self.bits.rank1(v)
+    }
+
+    /// 节点的深度, 根的深度为 0
+    pub fn depth(&self, v: usize) -> usize {
+        self.bits.rank1(v) - self.bits.rank0(v)
+    }
+
+    pub fn parent(&self, v: usize) -> Option<usize> {
+        self.enclose(v)
+    }
+
+    pub fn first_child(&self, v: usize) -> Option<usize> {
+        assert!(self.is_open(v), "not an open parenthesis");
+        Some(v + 1).filter(|&c| self.is_open(c))
+    }
+
+    pub fn next_sibling(&self, v: usize) -> Option<usize> {
+        Some(self.find_close(v) + 1).filter(|&s| self.is_open(s))
+    }
+
+    pub fn is_leaf(&self, v: usize) -> bool {
+        self.first_child(v).is_none()
+    }
+
+    /// 子树中的节点数
+    pub fn subtree_size(&self, v: usize) -> usize {
+        (self.find_close(v) - v).div_ceil(2)
+    }
+}
+
+impl fmt::Display for BpTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for i in 0..self.len {
+            f.write_str(if self.is_open(i) { "(" } else { ")" })?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn test_from_children() {
+        //       0
+        //     / | \
+        //    1  2  3
+        //   / \    |
+        //  4   5   6
+        let children = vec![
+            vec![1, 2, 3],
+            vec![4, 5],
+            vec![],
+            vec![6],
+            vec![],
+            vec![],
+            vec![],
+        ];
+        let t = BpTree::from_children(&children, 0);
+        assert_eq!(t.to_string(), "((()())()(()))");
+        assert_eq!(t.len(), 7);
+        assert!(!t.is_empty());
+
+        let root = t.root();
+        assert_eq!(t.find_close(root), 13);
+        assert_eq!(t.find_open(13), root);
+        assert_eq!(t.parent(root), None);
+        assert_eq!(t.subtree_size(root), 7);
+
+        let a = t.first_child(root).unwrap();
+        let b = t.next_sibling(a).unwrap();
+        let c = t.next_sibling(b).unwrap();
+        assert_eq!((a, b, c), (1, 7, 9));
+        assert_eq!(t.next_sibling(c), None);
+        assert_eq!(t.subtree_size(a), 3);
+        assert!(t.is_leaf(b));
+        assert_eq!(t.parent(c), Some(root));
+        assert_eq!(t.depth(c), 1);
+
+        // 先序编号: 0 1 4 5 2 3 6
+        let order: Vec<_> = (0..7).map(|k| t.node(k).unwrap()).collect();
+        assert_eq!(order, [0, 1, 2, 4, 7, 9, 10]);
+        assert_eq!(t.node(7), None);
+        assert_eq!(t.preorder(10), 6);
+        assert_eq!(t.depth(10), 2);
+    }
+
+    #[test]
+    fn test_from_binary_tree() {
+        //     1
+        //    / \
+        //   2   5
+        //  / \
+        // 3   4
+        let tree = BinaryTree::with_children(
+            1,
+            Some(BinaryTree::with_children(
+                2,
+                Some(BinaryTree::new(3)),
+                Some(BinaryTree::new(4)),
+            )),
+            Some(BinaryTree::new(5)),
+        );
+        let t = BpTree::from_binary_tree(&tree);
+        assert_eq!(t.len(), 6);
+        assert_eq!(t.to_string(), "(((())())())");
+
+        // 按先序编号检查左右孩子
+        let left = |k: usize| t.first_child(t.node(k).unwrap()).map(|v| t.preorder(v));
+        let right = |k: usize| t.next_sibling(t.node(k).unwrap()).map(|v| t.preorder(v));
+        assert_eq!((left(1), right(1)), (Some(2), Some(5)));
+        assert_eq!((left(2), right(2)), (Some(3), Some(4)));
+        assert_eq!((left(3), right(3)), (None, None));
+        assert_eq!((left(4), right(4)), (None, None));
+        assert_eq!((left(5), right(5)), (None, None));
+    }
+
+    /// 随机生成父节点编号小于自己的树, 和直接用父数组计算的结果对比
+    #[test]
+    fn test_random() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0x5eed);
+        for n in [1, 2, 10, 100, 1000, 5000] {
+            // 深度较大的树让查找跨过多个字
+            let parent: Vec<usize> = (0..n)
+                .map(|i| match i {
+                    0 => 0,
+                    _ if rng.gen_bool(0.7) => i - 1,
+                    _ => rng.gen_range(0..i),
+                })
+                .collect();
+            let mut children = vec![vec![]; n];
+            for i in 1..n {
+                children[parent[i]].push(i);
+            }
+
+            // 按先序遍历的顺序给节点编号
+            let mut preorder = vec![];
+            let mut stack = vec![0];
+            while let Some(u) = stack.pop() {
+                preorder.push(u);
+                stack.extend(children[u].iter().rev());
+            }
+            let mut depth = vec![0; n];
+            let mut size = vec![1; n];
+            for i in 1..n {
+                depth[i] = depth[parent[i]] + 1;
+            }
+            for i in (1..n).rev() {
+                size[parent[i]] += size[i];
+            }
+
+            let t = BpTree::from_children(&children, 0);
+            assert_eq!(t.len(), n);
+            for (k, &u) in preorder.iter().enumerate() {
+                let v = t.node(k).unwrap();
+                assert_eq!(t.preorder(v), k);
+                assert_eq!(t.depth(v), depth[u]);
+                assert_eq!(t.subtree_size(v), size[u]);
+                assert_eq!(t.find_open(t.find_close(v)), v);
+                assert_eq!(t.is_leaf(v), children[u].is_empty());
+
+                let p = t.parent(v).map(|p| preorder[t.preorder(p)]);
+                assert_eq!(p, (u != 0).then_some(parent[u]));
+                let first = t.first_child(v).map(|c| preorder[t.preorder(c)]);
+                assert_eq!(first, children[u].first().copied());
+            }
+        }
+    }
+}
@@ -0,0 +1,632 @@
+//! 高精度无符号整数
+//!
+//! - [OI Wiki - 高精度计算](https://oi-wiki.org/math/bignum/)
+//! - [wikipedia - Karatsuba algorithm](https://en.wikipedia.org/wiki/Karatsuba_algorithm)
+//! - [The Art of Computer Programming Vol.2 - 4.3.1 Algorithm D](https://en.wikipedia.org/wiki/The_Art_of_Computer_Programming)
+//!
+//! 用 2^32 进制表示大整数, 每一位(limb)是一个 u32, 按从低位到高位的顺序保存并且去掉高位的 0, 零就是空数组  
+//! 两个 u32 的乘积加上进位不会超过 u64, 所以中间结果都用 u64 计算
+//!
+//! - 加减法: 逐位相加减并处理进位/借位, O(n)
+//! - 乘法: 竖式乘法 O(n²); Karatsuba 把两个数各拆成高低两半 `a = a1·B + a0` `b = b1·B + b0`,
+//!   利用 `a1·b0 + a0·b1 = (a0 + a1)(b0 + b1) - a0·b0 - a1·b1` 只需要三次递归乘法, 复杂度 O(n^1.585)  
+//!   位数更多时可以把每一位再拆成两个 16 位的数字看作多项式系数, 用 [`crate::fft`] 做卷积之后再处理进位
+//! - 除法: 除数只有一位时逐位做短除法; 否则使用 Knuth 的 Algorithm D, 每次用被除数的最高两位除以除数的最高位估计一位商,
+//!   先把除数左移使最高位的最高比特为 1, 这样估计的商最多比真实值大 2, 再用次高位修正并在减法出现借位时加回
+//! - 十进制转换: 每次乘以或除以 10^9 处理 9 个十进制位
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Rem, Sub};
+use std::str::FromStr;
+
+/// 长度小于这个值时使用竖式乘法
+const KARATSUBA_THRESHOLD: usize = 32;
+
+/// 十进制转换时每次处理的位数以及对应的基数
+const DECIMAL_DIGITS: usize = 9;
+const DECIMAL_BASE: u32 = 1_000_000_000;
+
+/// 高精度无符号整数
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    /// 从低位到高位, 最高位不为 0
+    limbs: Vec<u32>,
+}
+
+/// 去掉高位的 0
+fn trim(v: &mut Vec<u32>) {
+    while v.last() == Some(&0) {
+        v.pop();
+    }
+}
+
+fn cmp_slices(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+/// a + b
+fn add_slices(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut res = Vec::with_capacity(a.len() + 1);
+    let mut carry = 0u64;
+    for (i, &x) in a.iter().enumerate() {
+        let t = x as u64 + b.get(i).copied().unwrap_or(0) as u64 + carry;
+        res.push(t as u32);
+        carry = t >> 32;
+    }
+    if carry > 0 {
+        res.push(carry as u32);
+    }
+
+    res
+}
+
+/// a - b, 要求 a >= b
+fn sub_slices(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut res = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+    for (i, &x) in a.iter().enumerate() {
+        let mut t = x as i64 - b.get(i).copied().unwrap_or(0) as i64 - borrow;
+        borrow = 0;
+        if t < 0 {
+            t += 1 << 32;
+            borrow = 1;
+        }
+        res.push(t as u32);
+    }
+    assert_eq!(borrow, 0, "subtraction overflow");
+    trim(&mut res);
+
+    res
+}
+
+/// 把 x 加到 `res[offset..]` 上, res 要足够长
+fn add_at(res: &mut [u32], x: &[u32], offset: usize) {
+    let mut carry = 0u64;
+    let mut i = 0;
+    while i < x.len() || carry > 0 {
+        let t = res[offset + i] as u64 + x.get(i).copied().unwrap_or(0) as u64 + carry;
+        res[offset + i] = t as u32;
+        carry = t >> 32;
+        i += 1;
+    }
+}
+
+/// 竖式乘法
+fn mul_schoolbook(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut res = vec![0u32; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, &y) in b.iter().enumerate() {
+            // (2^32-1)² + 2 * (2^32-1) = 2^64 - 1 不会溢出
+            let t = res[i + j] as u64 + x as u64 * y as u64 + carry;
+            res[i + j] = t as u32;
+            carry = t >> 32;
+        }
+        res[i + b.len()] = carry as u32;
+    }
+    trim(&mut res);
+
+    res
+}
+
+/// Karatsuba 乘法
+fn mul_karatsuba(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.len().min(b.len()) < KARATSUBA_THRESHOLD {
+        return mul_schoolbook(a, b);
+    }
+
+    let half = a.len().max(b.len()) / 2;
+    let split = |v: &[u32]| -> (Vec<u32>, Vec<u32>) {
+        let (lo, hi) = v.split_at(half.min(v.len()));
+        let mut lo = lo.to_vec();
+        trim(&mut lo);
+        (lo, hi.to_vec())
+    };
+    let (a0, a1) = split(a);
+    let (b0, b1) = split(b);
+
+    let z0 = mul_karatsuba(&a0, &b0);
+    let z2 = mul_karatsuba(&a1, &b1);
+    let z1 = mul_karatsuba(&add_slices(&a0, &a1), &add_slices(&b0, &b1));
+    let z1 = sub_slices(&sub_slices(&z1, &z0), &z2);
+
+    let mut res = vec![0u32; a.len() + b.len() + 1];
+    add_at(&mut res, &z0, 0);
+    add_at(&mut res, &z1, half);
+    add_at(&mut res, &z2, half * 2);
+    trim(&mut res);
+
+    res
+}
+
+/// 左移 s (< 32) 位, 结果比原来多一位
+fn shl_bits(v: &[u32], s: u32) -> Vec<u32> {
+    let mut res = Vec::with_capacity(v.len() + 1);
+    let mut carry = 0u32;
+    for &x in v {
+        res.push(x << s | carry);
+        carry = if s == 0 { 0 } else { x >> (32 - s) };
+    }
+    res.push(carry);
+
+    res
+}
+
+/// 右移 s (< 32) 位
+fn shr_bits(v: &[u32], s: u32) -> Vec<u32> {
+    let mut res = vec![0u32; v.len()];
+    for i in 0..v.len() {
+        let high = if s == 0 {
+            0
+        } else {
+            v.get(i + 1).map_or(0, |&x| x << (32 - s))
+        };
+        res[i] = v[i] >> s | high;
+    }
+    trim(&mut res);
+
+    res
+}
+
+impl BigUint {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn one() -> Self {
+        Self::from(1u32)
+    }
+
+    fn from_limbs(mut limbs: Vec<u32>) -> Self {
+        trim(&mut limbs);
+        Self { limbs }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// 二进制位数, 零的位数为 0
+    pub fn bits(&self) -> usize {
+        self.limbs
+            .last()
+            .map_or(0, |x| self.limbs.len() * 32 - x.leading_zeros() as usize)
+    }
+
+    /// 能放进 u128 时转换为 u128
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.limbs.len() > 4 {
+            return None;
+        }
+        Some(
+            self.limbs
+                .iter()
+                .rev()
+                .fold(0u128, |acc, &x| acc << 32 | x as u128),
+        )
+    }
+
+    /// 减法, 结果为负时返回 None
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        match cmp_slices(&self.limbs, &other.limbs) {
+            Ordering::Less => None,
+            _ => Some(Self::from_limbs(sub_slices(&self.limbs, &other.limbs))),
+        }
+    }
+
+    /// 乘以一个小整数再加上一个小整数
+    fn mul_add_small(&mut self, m: u32, a: u32) {
+        let mut carry = a as u64;
+        for x in self.limbs.iter_mut() {
+            let t = *x as u64 * m as u64 + carry;
+            *x = t as u32;
+            carry = t >> 32;
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+        trim(&mut self.limbs);
+    }
+
+    /// 除以一个小整数, 返回余数
+    fn div_rem_small(&mut self, d: u32) -> u32 {
+        assert!(d != 0, "division by zero");
+        let mut rem = 0u64;
+        for x in self.limbs.iter_mut().rev() {
+            let t = rem << 32 | *x as u64;
+            *x = (t / d as u64) as u32;
+            rem = t % d as u64;
+        }
+        trim(&mut self.limbs);
+
+        rem as u32
+    }
+
+    /// 同时求商和余数
+    ///
+    /// ```
+    /// use impx::bigint::BigUint;
+    ///
+    /// let a: BigUint = "123456789012345678901234567890".parse().unwrap();
+    /// let b: BigUint = "9876543210987".parse().unwrap();
+    /// let (q, r) = a.div_rem(&b);
+    /// assert_eq!(q.to_string(), "12499999886094578");
+    /// assert_eq!(r.to_string(), "1249943839404");
+    /// ```
+    pub fn div_rem(&self, other: &Self) -> (Self, Self) {
+        assert!(!other.is_zero(), "division by zero");
+        if cmp_slices(&self.limbs, &other.limbs) == Ordering::Less {
+            return (Self::zero(), self.clone());
+        }
+
+        if other.limbs.len() == 1 {
+            let mut q = self.clone();
+            let r = q.div_rem_small(other.limbs[0]);
+            return (q, Self::from(r));
+        }
+
+        // 规格化: 左移使除数最高位的最高比特为 1
+        let s = other.limbs.last().unwrap().leading_zeros();
+        let mut v = shl_bits(&other.limbs, s);
+        // 除数左移后最高位不会溢出
+        v.pop();
+        let mut u = shl_bits(&self.limbs, s);
+        let n = v.len();
+        let m = u.len() - n;
+        let mut q = vec![0u32; m];
+
+        let b = 1u64 << 32;
+        for j in (0..m).rev() {
+            // 用最高两位估计商
+            let num = (u[j + n] as u64) << 32 | u[j + n - 1] as u64;
+            let mut qhat = num / v[n - 1] as u64;
+            let mut rhat = num % v[n - 1] as u64;
+            while qhat >= b || qhat * v[n - 2] as u64 > (rhat << 32 | u[j + n - 2] as u64) {
+                qhat -= 1;
+                rhat += v[n - 1] as u64;
+                if rhat >= b {
+                    break;
+                }
+            }
+
+            // u[j..=j+n] -= qhat * v
+            let mut borrow = 0i64;
+            for i in 0..n {
+                let p = qhat * v[i] as u64;
+                let t = u[i + j] as i64 - borrow - (p & 0xffff_ffff) as i64;
+                u[i + j] = t as u32;
+                borrow = (p >> 32) as i64 - (t >> 32);
+            }
+            let t = u[j + n] as i64 - borrow;
+            u[j + n] = t as u32;
+
+            q[j] = qhat as u32;
+            if t < 0 {
+                // 估计的商大了 1, 加回一个除数
+                q[j] -= 1;
+                let mut carry = 0u64;
+                for i in 0..n {
+                    let t = u[i + j] as u64 + v[i] as u64 + carry;
+                    u[i + j] = t as u32;
+                    carry = t >> 32;
+                }
+                u[j + n] = u[j + n].wrapping_add(carry as u32);
+            }
+        }
+
+        u.truncate(n);
+        (Self::from_limbs(q), Self::from_limbs(shr_bits(&u, s)))
+    }
+
+    /// 快速幂
+    ///
+    /// ```
+    /// use impx::bigint::BigUint;
+    ///
+    /// assert_eq!(BigUint::from(2u32).pow(100).to_string(), "1267650600228229401496703205376");
+    /// ```
+    pub fn pow(&self, mut n: u32) -> Self {
+        let mut base = self.clone();
+        let mut res = Self::one();
+        while n > 0 {
+            if n & 1 == 1 {
+                res = &res * &base;
+            }
+            n >>= 1;
+            if n > 0 {
+                base = &base * &base;
+            }
+        }
+
+        res
+    }
+
+    /// 使用 FFT 计算乘积
+    ///
+    /// 把每一位拆成两个 16 位的数字作为多项式系数, 每个系数的乘积小于 2^32,
+    /// 浮点 FFT 在卷积结果小于 2^50 左右时是精确的, 所以拆分后的长度不能超过 2^17
+    pub fn mul_fft(&self, other: &Self) -> Self {
+        if self.is_zero() || other.is_zero() {
+            return Self::zero();
+        }
+
+        let split = |v: &[u32]| -> Vec<u64> {
+            v.iter()
+                .flat_map(|&x| [(x & 0xffff) as u64, (x >> 16) as u64])
+                .collect()
+        };
+        let (a, b) = (split(&self.limbs), split(&other.limbs));
+        assert!(
+            a.len().max(b.len()) <= 1 << 17,
+            "operands too large for fft multiplication"
+        );
+
+        let conv = crate::fft::multiply(&a, &b);
+        let mut digits = Vec::with_capacity(conv.len() + 2);
+        let mut carry = 0u64;
+        for x in conv {
+            let t = x + carry;
+            digits.push((t & 0xffff) as u32);
+            carry = t >> 16;
+        }
+        while carry > 0 {
+            digits.push((carry & 0xffff) as u32);
+            carry >>= 16;
+        }
+
+        // 两个 16 位数字合并回一个 u32
+        let limbs = digits
+            .chunks(2)
+            .map(|c| c[0] | c.get(1).copied().unwrap_or(0) << 16)
+            .collect();
+        Self::from_limbs(limbs)
+    }
+}
+
+impl From<u32> for BigUint {
+    fn from(x: u32) -> Self {
+        Self::from_limbs(vec![x])
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(x: u64) -> Self {
+        Self::from_limbs(vec![x as u32, (x >> 32) as u32])
+    }
+}
+
+impl From<u128> for BigUint {
+    fn from(x: u128) -> Self {
+        Self::from_limbs((0..4).map(|i| (x >> (32 * i)) as u32).collect())
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_slices(&self.limbs, &other.limbs)
+    }
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, rhs: &BigUint) -> BigUint {
+        BigUint::from_limbs(add_slices(&self.limbs, &rhs.limbs))
+    }
+}
+
+impl Sub<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    /// 结果为负时 panic, 不希望 panic 时使用 [`BigUint::checked_sub`]
+    fn sub(self, rhs: &BigUint) -> BigUint {
+        self.checked_sub(rhs).expect("subtraction overflow")
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, rhs: &BigUint) -> BigUint {
+        if self.is_zero() || rhs.is_zero() {
+            return BigUint::zero();
+        }
+        BigUint::from_limbs(mul_karatsuba(&self.limbs, &rhs.limbs))
+    }
+}
+
+impl Div<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn div(self, rhs: &BigUint) -> BigUint {
+        self.div_rem(rhs).0
+    }
+}
+
+impl Rem<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn rem(self, rhs: &BigUint) -> BigUint {
+        self.div_rem(rhs).1
+    }
+}
+
+/// 为值类型实现运算符, 转发给引用类型的实现
+macro_rules! forward_binop {
+    ($($imp:ident $method:ident),*) => {
+        $(
+            impl $imp for BigUint {
+                type Output = BigUint;
+
+                fn $method(self, rhs: BigUint) -> BigUint {
+                    (&self).$method(&rhs)
+                }
+            }
+        )*
+    };
+}
+
+forward_binop!(Add add, Sub sub, Mul mul, Div div, Rem rem);
+
+/// 解析十进制字符串失败
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseBigUintError;
+
+impl fmt::Display for ParseBigUintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid decimal digit string")
+    }
+}
+
+impl std::error::Error for ParseBigUintError {}
+
+impl FromStr for BigUint {
+    type Err = ParseBigUintError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() || !s.bytes().all(|c| c.is_ascii_digit()) {
+            return Err(ParseBigUintError);
+        }
+
+        // 从高位开始每次读入 9 位, 第一段的长度可能不足 9 位
+        let mut res = Self::zero();
+        let first = match s.len() % DECIMAL_DIGITS {
+            0 => DECIMAL_DIGITS,
+            r => r,
+        };
+        let mut start = 0;
+        let mut end = first;
+        while start < s.len() {
+            let chunk: u32 = s[start..end].parse().unwrap();
+            res.mul_add_small(10u32.pow((end - start) as u32), chunk);
+            (start, end) = (end, end + DECIMAL_DIGITS);
+        }
+
+        Ok(res)
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return f.pad_integral(true, "", "0");
+        }
+
+        // 不断除以 10^9 得到从低到高的每 9 个十进制位
+        let mut chunks = vec![];
+        let mut x = self.clone();
+        while !x.is_zero() {
+            chunks.push(x.div_rem_small(DECIMAL_BASE));
+        }
+
+        let mut s = chunks.last().unwrap().to_string();
+        for c in chunks.iter().rev().skip(1) {
+            s.push_str(&format!("{c:09}"));
+        }
+        f.pad_integral(true, "", &s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    /// 随机生成长度在 limbs 范围内的大整数
+    fn random_big(rng: &mut impl Rng, limbs: std::ops::Range<usize>) -> BigUint {
+        let n = rng.gen_range(limbs);
+        BigUint::from_limbs((0..n).map(|_| rng.gen()).collect())
+    }
+
+    #[test]
+    fn test_against_u128() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..2000 {
+            // 随机位数使得各种长度都能覆盖到
+            let x: u128 = rng.gen::<u128>() >> rng.gen_range(0..128);
+            let y: u128 = rng.gen::<u128>() >> rng.gen_range(0..128);
+            let (a, b) = (BigUint::from(x), BigUint::from(y));
+
+            assert_eq!(a.to_u128(), Some(x));
+            assert_eq!(a.cmp(&b), x.cmp(&y));
+            assert_eq!(a.to_string(), x.to_string());
+            assert_eq!(x.to_string().parse::<BigUint>(), Ok(a.clone()));
+            if let Some(s) = x.checked_add(y) {
+                assert_eq!((&a + &b).to_u128(), Some(s));
+            }
+            assert_eq!(
+                a.checked_sub(&b).and_then(|d| d.to_u128()),
+                x.checked_sub(y)
+            );
+
+            let (x64, y64) = (x as u64, y as u64);
+            let p = BigUint::from(x64) * BigUint::from(y64);
+            assert_eq!(p.to_u128(), Some(x64 as u128 * y64 as u128));
+
+            if let Some(quotient) = x.checked_div(y) {
+                let (q, r) = a.div_rem(&b);
+                assert_eq!(q.to_u128(), Some(quotient));
+                assert_eq!(r.to_u128(), Some(x % y));
+            }
+        }
+    }
+
+    #[test]
+    fn test_large() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..20 {
+            let a = random_big(&mut rng, 1..200);
+            let b = random_big(&mut rng, 1..200);
+
+            // 三种乘法的结果相同
+            let p = &a * &b;
+            assert_eq!(p, BigUint::from_limbs(mul_schoolbook(&a.limbs, &b.limbs)));
+            assert_eq!(p, a.mul_fft(&b));
+
+            if !b.is_zero() {
+                let r = random_big(&mut rng, 0..b.limbs.len() + 1) % b.clone();
+                let (q, rem) = (&p + &r).div_rem(&b);
+                assert_eq!(q, a);
+                assert_eq!(rem, r);
+            }
+
+            assert_eq!(&(&a + &b) - &b, a);
+            let s = a.to_string();
+            assert_eq!(s.parse::<BigUint>().unwrap(), a);
+            assert_eq!(
+                crate::fft::multiply_decimal(&s, &b.to_string()),
+                p.to_string()
+            );
+        }
+    }
+
+    #[test]
+    fn test_bigint() {
+        let factorial = (1..=30u32).fold(BigUint::one(), |acc, i| acc * BigUint::from(i));
+        assert_eq!(factorial.to_string(), "265252859812191058636308480000000");
+
+        let x = BigUint::from(10u32).pow(50);
+        assert_eq!(x.to_string(), format!("1{}", "0".repeat(50)));
+        assert_eq!(x.bits(), 167);
+        assert_eq!(format!("{:>5}", BigUint::zero()), "    0");
+        assert_eq!(BigUint::zero().bits(), 0);
+        assert!(BigUint::zero().pow(0) == BigUint::one());
+
+        assert_eq!("".parse::<BigUint>(), Err(ParseBigUintError));
+        assert_eq!("12a".parse::<BigUint>(), Err(ParseBigUintError));
+        assert_eq!("000123".parse::<BigUint>().unwrap().to_string(), "123");
+        assert!(BigUint::from(1u32)
+            .checked_sub(&BigUint::from(2u32))
+            .is_none());
+    }
+}
@@ -1,20 +1,110 @@
 //! 二叉堆
+//!
+//! 默认是大根堆, 需要小根堆时可以把元素包在 [`std::cmp::Reverse`] 中
+//!
+//! [`k_way_merge`] 使用小根堆惰性地合并 k 个有序迭代器, 每次弹出堆顶后只需要从同一个迭代器中补充一个元素,
+//! 合并 n 个元素的复杂度为 O(nlogk), 可以用于外部排序和 B+ 树的批量构建
+//!
+//! ## 可索引堆
+//!
+//! [`IndexedBinaryHeap`] 在推入元素时返回一个句柄, 之后可以通过句柄修改或者删除堆中任意位置的元素,
+//! 这正是 Dijkstra, Prim 和 A* 需要的 decrease-key 操作, 不需要像普通的堆那样重复推入同一个节点再在弹出时跳过
+//!
+//! 实现上额外记录每个句柄在堆中的位置, 交换元素时同步更新, 修改元素后根据新的值向上或者向下调整  
+//! 堆的分叉数 D 可以指定, D 越大树越矮, 向上调整越快但向下调整每层要比较更多的子节点,
+//! decrease-key 远多于弹出的场景下 4 叉堆通常比二叉堆更快
+
+use std::cmp::{Ordering, Reverse};
 
 pub struct BinaryHeap<T> {
     nodes: Vec<T>,
 }
 
-/// 构建二叉堆
-pub fn build_heap<T: Copy + PartialOrd>(vs: &[T]) -> BinaryHeap<T> {
-    let mut nodes = vs.to_vec();
-    for i in (0..=nodes.len() / 2).rev() {
-        down(&mut nodes, i);
+/// 从切片复制元素构建二叉堆, 不需要复制时使用 [`BinaryHeap::from`] 或者 [`Iterator::collect`]
+pub fn build_heap<T: Clone + PartialOrd>(vs: &[T]) -> BinaryHeap<T> {
+    BinaryHeap::from(vs.to_vec())
+}
+
+/// 自底向上建堆, 从最后一个非叶子节点开始向下调整, 复杂度 O(n)
+impl<T: PartialOrd> From<Vec<T>> for BinaryHeap<T> {
+    fn from(mut nodes: Vec<T>) -> Self {
+        for i in (0..nodes.len() / 2).rev() {
+            down(&mut nodes, i);
+        }
+
+        BinaryHeap { nodes }
+    }
+}
+
+impl<T: PartialOrd> FromIterator<T> for BinaryHeap<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl<T: PartialOrd> Extend<T> for BinaryHeap<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for v in iter {
+            self.push(v);
+        }
+    }
+}
+
+/// 按弹出的顺序(从大到小)消费所有元素
+impl<T: PartialOrd> IntoIterator for BinaryHeap<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { heap: self }
+    }
+}
+
+/// 按从大到小的顺序消费元素的迭代器
+pub struct IntoIter<T> {
+    heap: BinaryHeap<T>,
+}
+
+impl<T: PartialOrd> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.heap.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.heap.len(), Some(self.heap.len()))
+    }
+}
+
+impl<T: PartialOrd> ExactSizeIterator for IntoIter<T> {}
+
+impl<T: PartialOrd> Default for BinaryHeap<T> {
+    fn default() -> Self {
+        Self::new()
     }
+}
 
-    BinaryHeap { nodes }
+impl<T> BinaryHeap<T> {
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// 当前最大元素
+    pub fn peek(&self) -> Option<&T> {
+        self.nodes.first()
+    }
 }
 
-impl<T: Copy + PartialOrd> BinaryHeap<T> {
+impl<T: PartialOrd> BinaryHeap<T> {
+    pub fn new() -> Self {
+        BinaryHeap { nodes: vec![] }
+    }
+
     /// 推入元素
     pub fn push(&mut self, v: T) {
         self.nodes.push(v);
@@ -22,18 +112,252 @@ impl<T: Copy + PartialOrd> BinaryHeap<T> {
         up(&mut self.nodes, idx);
     }
 
-    /// 弹出当前最大元素
+    /// 弹出当前最大元素, 先把堆顶和最后一个元素交换, 移出后再把新的堆顶向下调整
     pub fn pop(&mut self) -> Option<T> {
+        let value = self.nodes.pop()?;
         if self.nodes.is_empty() {
-            return None;
+            return Some(value);
         }
 
-        let last = self.nodes.len() - 1;
-        self.nodes.swap(0, last);
-        let value = self.nodes.pop();
+        let top = std::mem::replace(&mut self.nodes[0], value);
         down(&mut self.nodes, 0);
+        Some(top)
+    }
+}
+
+/// 合并中的堆元素, 值相同时来自靠前迭代器的元素优先, 保证合并是稳定的
+struct MergeItem<T> {
+    value: T,
+    source: usize,
+}
+
+impl<T: PartialOrd> PartialEq for MergeItem<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl<T: PartialOrd> PartialOrd for MergeItem<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.value.partial_cmp(&other.value)? {
+            Ordering::Equal => Some(self.source.cmp(&other.source)),
+            ord => Some(ord),
+        }
+    }
+}
+
+/// k 路归并迭代器, 由 [`k_way_merge`] 创建
+pub struct KWayMerge<I: Iterator> {
+    iters: Vec<I>,
+    heap: BinaryHeap<Reverse<MergeItem<I::Item>>>,
+}
+
+/// 惰性合并 k 个升序迭代器, 结果也是升序的
+///
+/// ```
+/// use impx::binary_heap::k_way_merge;
+///
+/// let merged: Vec<_> = k_way_merge(vec![
+///     vec![1, 4, 7].into_iter(),
+///     vec![2, 5, 8].into_iter(),
+///     vec![0, 3, 6, 9].into_iter(),
+/// ])
+/// .collect();
+/// assert_eq!(merged, (0..10).collect::<Vec<_>>());
+/// ```
+pub fn k_way_merge<I>(mut iters: Vec<I>) -> KWayMerge<I>
+where
+    I: Iterator,
+    I::Item: PartialOrd,
+{
+    let mut heap = BinaryHeap::new();
+    for (source, iter) in iters.iter_mut().enumerate() {
+        if let Some(value) = iter.next() {
+            heap.push(Reverse(MergeItem { value, source }));
+        }
+    }
+
+    KWayMerge { iters, heap }
+}
+
+impl<I> Iterator for KWayMerge<I>
+where
+    I: Iterator,
+    I::Item: PartialOrd,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse(MergeItem { value, source }) = self.heap.pop()?;
+        if let Some(next) = self.iters[source].next() {
+            self.heap.push(Reverse(MergeItem {
+                value: next,
+                source,
+            }));
+        }
+
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iters
+            .iter()
+            .fold((self.heap.len(), Some(self.heap.len())), |(lo, hi), it| {
+                let (l, h) = it.size_hint();
+                (
+                    lo.saturating_add(l),
+                    hi.zip(h).and_then(|(a, b)| a.checked_add(b)),
+                )
+            })
+    }
+}
+
+/// [`IndexedBinaryHeap`] 中元素的句柄, 在元素被弹出或删除之前一直有效
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Handle(usize);
+
+/// 可索引的 D 叉大根堆, 默认是二叉堆
+///
+/// ```
+/// use impx::binary_heap::IndexedBinaryHeap;
+///
+/// let mut h = IndexedBinaryHeap::<_, 4>::new();
+/// let a = h.push(1);
+/// let b = h.push(5);
+/// h.push(3);
+///
+/// assert_eq!(h.change_key(a, 9), Some(1));
+/// assert_eq!(h.remove(b), Some(5));
+/// assert_eq!(h.pop(), Some((a, 9)));
+/// assert_eq!(h.pop().map(|e| e.1), Some(3));
+/// assert_eq!(h.change_key(a, 0), None);
+/// ```
+pub struct IndexedBinaryHeap<T, const D: usize = 2> {
+    /// 堆中每个位置对应的句柄
+    heap: Vec<usize>,
+    /// 句柄对应的元素, 弹出或删除后为 None, 句柄不会被复用
+    values: Vec<Option<T>>,
+    /// 句柄对应的元素在堆中的位置
+    pos: Vec<usize>,
+}
+
+impl<T: PartialOrd, const D: usize> Default for IndexedBinaryHeap<T, D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PartialOrd, const D: usize> IndexedBinaryHeap<T, D> {
+    pub fn new() -> Self {
+        assert!(D >= 2, "heap arity must be at least 2");
+        Self {
+            heap: vec![],
+            values: vec![],
+            pos: vec![],
+        }
+    }
 
-        value
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// 推入元素, 返回它的句柄
+    pub fn push(&mut self, value: T) -> Handle {
+        let handle = self.values.len();
+        self.values.push(Some(value));
+        self.pos.push(self.heap.len());
+        self.heap.push(handle);
+        self.up(self.heap.len() - 1);
+        Handle(handle)
+    }
+
+    /// 当前最大元素
+    pub fn peek(&self) -> Option<(Handle, &T)> {
+        let &handle = self.heap.first()?;
+        Some((Handle(handle), self.values[handle].as_ref().unwrap()))
+    }
+
+    /// 弹出当前最大元素
+    pub fn pop(&mut self) -> Option<(Handle, T)> {
+        let handle = Handle(*self.heap.first()?);
+        self.remove(handle).map(|value| (handle, value))
+    }
+
+    /// 句柄对应的元素是否还在堆中
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.get(handle).is_some()
+    }
+
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        self.values.get(handle.0)?.as_ref()
+    }
+
+    /// 修改句柄对应的元素并调整位置, 返回原来的值, 元素已经不在堆中时返回 None
+    pub fn change_key(&mut self, handle: Handle, value: T) -> Option<T> {
+        let old = self.values.get_mut(handle.0)?.as_mut()?;
+        let old = std::mem::replace(old, value);
+
+        let i = self.up(self.pos[handle.0]);
+        self.down(i);
+        Some(old)
+    }
+
+    /// 删除句柄对应的元素, 元素已经不在堆中时返回 None
+    ///
+    /// 把最后一个元素换到被删除的位置上, 它可能比新的父节点大也可能比子节点小, 两个方向都要调整
+    pub fn remove(&mut self, handle: Handle) -> Option<T> {
+        let value = self.values.get_mut(handle.0)?.take()?;
+
+        let i = self.pos[handle.0];
+        let last = self.heap.len() - 1;
+        self.swap(i, last);
+        self.heap.pop();
+        if i < self.heap.len() {
+            let i = self.up(i);
+            self.down(i);
+        }
+        Some(value)
+    }
+
+    fn value(&self, i: usize) -> &T {
+        self.values[self.heap[i]].as_ref().unwrap()
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.heap.swap(i, j);
+        self.pos[self.heap[i]] = i;
+        self.pos[self.heap[j]] = j;
+    }
+
+    /// 向上调整, 返回最终的位置
+    fn up(&mut self, mut i: usize) -> usize {
+        while i > 0 && self.value(i) > self.value((i - 1) / D) {
+            self.swap(i, (i - 1) / D);
+            i = (i - 1) / D;
+        }
+        i
+    }
+
+    fn down(&mut self, mut i: usize) {
+        loop {
+            let first = i * D + 1;
+            let mut max_idx = i;
+            for child in first..(first + D).min(self.heap.len()) {
+                if self.value(child) > self.value(max_idx) {
+                    max_idx = child;
+                }
+            }
+
+            if max_idx == i {
+                break;
+            }
+            self.swap(i, max_idx);
+            i = max_idx;
+        }
     }
 }
 
@@ -90,4 +414,160 @@ mod tests {
         assert_eq!(h.pop(), Some(0));
         assert_eq!(h.pop(), None);
     }
+
+    #[test]
+    fn test_binary_heap_traits() {
+        // 不需要 Copy 或者 Clone
+        let words = ["pear", "apple", "fig", "kiwi", "banana"].map(String::from);
+        let mut h: BinaryHeap<String> = words.into_iter().collect();
+        assert_eq!(h.len(), 5);
+        assert_eq!(h.peek().map(String::as_str), Some("pear"));
+
+        h.extend(["zucchini", "cherry"].map(String::from));
+        assert_eq!(h.peek().map(String::as_str), Some("zucchini"));
+
+        let iter = h.into_iter();
+        assert_eq!(iter.len(), 7);
+        let drained: Vec<_> = iter.collect();
+        assert_eq!(
+            drained,
+            ["zucchini", "pear", "kiwi", "fig", "cherry", "banana", "apple"]
+        );
+
+        let mut h = BinaryHeap::<i32>::default();
+        assert!(h.is_empty());
+        assert_eq!(h.peek(), None);
+        assert_eq!(h.pop(), None);
+        h.extend([3]);
+        assert_eq!(h.pop(), Some(3));
+    }
+
+    #[test]
+    fn test_binary_heap_random() {
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        for n in [0, 1, 2, 3, 10, 100, 1000] {
+            let vs: Vec<u32> = (0..n).map(|_| rng.gen_range(0..100)).collect();
+            let mut expect = vs.clone();
+            expect.sort_by(|a, b| b.cmp(a));
+
+            let h = BinaryHeap::from(vs);
+            assert!(h.into_iter().eq(expect));
+        }
+    }
+
+    #[test]
+    fn test_k_way_merge() {
+        use rand::Rng;
+
+        assert_eq!(
+            k_way_merge(Vec::<std::vec::IntoIter<i32>>::new()).next(),
+            None
+        );
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..50 {
+            let lists: Vec<Vec<u32>> = (0..rng.gen_range(1..8))
+                .map(|_| {
+                    let mut v: Vec<_> = (0..rng.gen_range(0..30))
+                        .map(|_| rng.gen_range(0..50))
+                        .collect();
+                    v.sort();
+                    v
+                })
+                .collect();
+
+            let merge = k_way_merge(lists.iter().map(|v| v.iter().copied()).collect());
+            let total = lists.iter().map(Vec::len).sum();
+            assert_eq!(merge.size_hint(), (total, Some(total)));
+
+            let mut expect: Vec<_> = lists.concat();
+            expect.sort();
+            assert_eq!(merge.collect::<Vec<_>>(), expect);
+        }
+
+        // 不需要 Copy, 并且值相同时按迭代器的顺序输出
+        let a = vec![(1, "a0"), (2, "a1")];
+        let b = vec![(1, "b0"), (2, "b1")];
+        let key = |v: Vec<(i32, &'static str)>| v.into_iter().map(|(k, s)| Keyed(k, s.to_string()));
+        let merged: Vec<_> = k_way_merge(vec![key(b), key(a)]).map(|k| k.1).collect();
+        assert_eq!(merged, ["b0", "a0", "b1", "a1"]);
+    }
+
+    fn do_indexed_heap_test<const D: usize>() {
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        let mut h = IndexedBinaryHeap::<u32, D>::new();
+        // 参照模型: 所有还在堆中的句柄和值
+        let mut model: Vec<(Handle, u32)> = vec![];
+        let mut removed = vec![];
+        for _ in 0..5000 {
+            match rng.gen_range(0..5) {
+                0 | 1 => {
+                    let v = rng.gen_range(0..100);
+                    model.push((h.push(v), v));
+                }
+                2 if !model.is_empty() => {
+                    let i = rng.gen_range(0..model.len());
+                    let v = rng.gen_range(0..100);
+                    assert_eq!(h.change_key(model[i].0, v), Some(model[i].1));
+                    model[i].1 = v;
+                }
+                3 if !model.is_empty() => {
+                    let (handle, v) = model.swap_remove(rng.gen_range(0..model.len()));
+                    assert_eq!(h.remove(handle), Some(v));
+                    removed.push(handle);
+                }
+                _ => {
+                    let max = model.iter().map(|e| e.1).max();
+                    assert_eq!(h.peek().map(|e| *e.1), max);
+                    if let Some((handle, v)) = h.pop() {
+                        assert_eq!(Some(v), max);
+                        let i = model.iter().position(|e| e.0 == handle).unwrap();
+                        assert_eq!(model.swap_remove(i).1, v);
+                        removed.push(handle);
+                    }
+                }
+            }
+
+            assert_eq!(h.len(), model.len());
+        }
+
+        // 已经删除的句柄不再有效
+        for handle in removed {
+            assert!(!h.contains(handle));
+            assert_eq!(h.change_key(handle, 0), None);
+            assert_eq!(h.remove(handle), None);
+        }
+
+        let mut expect: Vec<_> = model.iter().map(|e| e.1).collect();
+        expect.sort_by(|a, b| b.cmp(a));
+        let popped: Vec<_> = std::iter::from_fn(|| h.pop().map(|e| e.1)).collect();
+        assert_eq!(popped, expect);
+        assert!(h.is_empty());
+    }
+
+    #[test]
+    fn test_indexed_binary_heap() {
+        do_indexed_heap_test::<2>();
+        do_indexed_heap_test::<3>();
+        do_indexed_heap_test::<4>();
+    }
+
+    /// 只按第一个字段比较
+    struct Keyed(i32, String);
+
+    impl PartialEq for Keyed {
+        fn eq(&self, other: &Self) -> bool {
+            self.0 == other.0
+        }
+    }
+
+    impl PartialOrd for Keyed {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            self.0.partial_cmp(&other.0)
+        }
+    }
 }
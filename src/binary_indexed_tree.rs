@@ -86,6 +86,25 @@ pub fn update(t: &mut [isize], mut i: usize, v: isize) {
     }
 }
 
+/// 在元素都非负的树状数组中查找前缀和第一次达到 k 的位置(从 0 开始)
+///
+/// 类似倍增, 从最高位开始尝试: 如果 `t[pos + step - 1]` 代表的 step 个元素之和仍然小于 k,
+/// 说明答案在这 step 个元素之后, 跳过它们并从 k 中减去这部分的和, 最终 pos 就是答案
+pub fn find_kth(t: &[isize], mut k: isize) -> Option<usize> {
+    let n = t.len();
+    let mut pos = 0;
+    let mut step = if n == 0 { 0 } else { 1 << n.ilog2() };
+    while step > 0 {
+        if pos + step <= n && t[pos + step - 1] < k {
+            pos += step;
+            k -= t[pos - 1];
+        }
+        step >>= 1;
+    }
+
+    (pos < n).then_some(pos)
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -125,18 +144,36 @@ mod tests {
         let a = rand_slice(128);
         let mut t = init(&a);
 
-        assert_eq!(sum(&t, 15), a[..15].iter().sum());
-        assert_eq!(sum(&t, 66), a[..66].iter().sum());
-        assert_eq!(sum(&t, 97), a[..97].iter().sum());
-        assert_eq!(sum(&t, 111), a[..111].iter().sum());
+        assert_eq!(sum(&t, 15), a[..15].iter().sum::<isize>());
+        assert_eq!(sum(&t, 66), a[..66].iter().sum::<isize>());
+        assert_eq!(sum(&t, 97), a[..97].iter().sum::<isize>());
+        assert_eq!(sum(&t, 111), a[..111].iter().sum::<isize>());
 
         update(&mut t, 32, -100);
         update(&mut t, 80, -100);
         update(&mut t, 120, -100);
 
-        assert_eq!(sum(&t, 15), a[..15].iter().sum());
+        assert_eq!(sum(&t, 15), a[..15].iter().sum::<isize>());
         assert_eq!(sum(&t, 66), a[..66].iter().sum::<isize>() - 100);
         assert_eq!(sum(&t, 90), a[..90].iter().sum::<isize>() - 200);
         assert_eq!(sum(&t, 121), a[..121].iter().sum::<isize>() - 300);
     }
+
+    #[test]
+    fn test_find_kth() {
+        use super::*;
+
+        let a = rand_slice(100);
+        let t = init(&a);
+        let mut prefix = 0;
+        for (i, &v) in a.iter().enumerate() {
+            if v > 0 {
+                assert_eq!(find_kth(&t, prefix + 1), Some(i));
+                assert_eq!(find_kth(&t, prefix + v), Some(i));
+            }
+            prefix += v;
+        }
+        assert_eq!(find_kth(&t, prefix + 1), None);
+        assert_eq!(find_kth(&[], 1), None);
+    }
 }
@@ -12,6 +12,11 @@
 //! 另一般比较容易理解的方式是, 上下界都使用具体的索引 [0, n-1] 终止条件变成是 `low <= high`  
 //! 这种写法的好处是在大于或者小于的情况下 `low = mid+1` `high = mid - 1` 都有一个加减一的逻辑且 `mid` 比较好计算  
 //! 具体的细节可以参考下面给出的使用二分搜索的算法实现源码
+//!
+//! 更一般地, 二分搜索只要求谓词在定义域上是单调的(前面一段为 false 后面一段为 true), 定义域可以是任意整数区间  
+//! 比如二分答案时直接在值域上搜索, [`binary_search_range`] 就是这种形式, [`lower_bound`] 和 [`upper_bound`] 也只是它的特例  
+//! 在实数上二分时无法用加减一来缩小区间, 需要按精度 eps 终止, 见 [`bisect`]  
+//! 对单峰函数求极值时可以使用三分搜索 [`ternary_search_max`], 每次比较两个三等分点并舍弃较差一侧的 1/3
 
 /// 二分查找目标值所在索引
 pub fn binary_search<T: Ord>(v: &[T], target: &T) -> Option<usize> {
@@ -110,6 +115,137 @@ where
     index
 }
 
+/// 在整数区间 `[lo, hi)` 中查找第一个使 pred 为 true 的值
+///
+/// pred 必须是单调的, 即存在某个分界点, 之前都是 false 之后都是 true, 区间中不存在这样的值时返回 None  
+/// 这里使用左闭右开的写法, 始终保持 `pred(lo-1) == false` 和 `pred(hi) == true`
+///
+/// ```
+/// use impx::binary_search::binary_search_range;
+///
+/// // 最小的平方不小于 1000 的整数
+/// assert_eq!(binary_search_range(0, 1000, |x| x * x >= 1000), Some(32));
+/// assert_eq!(binary_search_range(0, 10, |x| x > 100), None);
+/// ```
+pub fn binary_search_range<F>(mut lo: i64, mut hi: i64, pred: F) -> Option<i64>
+where
+    F: Fn(i64) -> bool,
+{
+    let end = hi;
+    while lo < hi {
+        // hi - lo 在区间跨越整个 i64 时会溢出, 用无符号的差值计算中点
+        let mid = lo + (hi.abs_diff(lo) / 2) as i64;
+        if pred(mid) {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+
+    (lo < end).then_some(lo)
+}
+
+/// 第一个不小于 target 的元素的索引, 所有元素都小于 target 时返回 `v.len()`, 和 C++ 的 `std::lower_bound` 相同
+///
+/// ```
+/// use impx::binary_search::lower_bound;
+///
+/// assert_eq!(lower_bound(&[1, 2, 2, 3], &2), 1);
+/// assert_eq!(lower_bound(&[1, 2, 2, 3], &4), 4);
+/// ```
+pub fn lower_bound<T: Ord>(v: &[T], target: &T) -> usize {
+    binary_search_range(0, v.len() as i64, |i| v[i as usize] >= *target)
+        .map_or(v.len(), |i| i as usize)
+}
+
+/// 第一个大于 target 的元素的索引, 所有元素都不大于 target 时返回 `v.len()`, 和 C++ 的 `std::upper_bound` 相同
+///
+/// ```
+/// use impx::binary_search::upper_bound;
+///
+/// assert_eq!(upper_bound(&[1, 2, 2, 3], &2), 3);
+/// assert_eq!(upper_bound(&[1, 2, 2, 3], &0), 0);
+/// ```
+pub fn upper_bound<T: Ord>(v: &[T], target: &T) -> usize {
+    binary_search_range(0, v.len() as i64, |i| v[i as usize] > *target)
+        .map_or(v.len(), |i| i as usize)
+}
+
+/// 实数二分, 在 `[lo, hi]` 上查找 pred 由 false 变成 true 的分界点, 区间长度小于 eps 时停止
+///
+/// 除了精度之外还限制了最大迭代次数, 防止 eps 小于浮点数精度时死循环
+///
+/// ```
+/// use impx::binary_search::bisect;
+///
+/// let sqrt2 = bisect(0.0, 2.0, 1e-9, |x| x * x >= 2.0);
+/// assert!((sqrt2 - 2f64.sqrt()).abs() < 1e-9);
+/// ```
+pub fn bisect<F>(mut lo: f64, mut hi: f64, eps: f64, pred: F) -> f64
+where
+    F: Fn(f64) -> bool,
+{
+    for _ in 0..200 {
+        if hi - lo <= eps {
+            break;
+        }
+
+        let mid = lo + (hi - lo) / 2.0;
+        if pred(mid) {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+
+    hi
+}
+
+/// 三分搜索单峰函数 f 在 `[lo, hi]` 上的最大值点, 区间长度小于 eps 时停止
+///
+/// ```
+/// use impx::binary_search::ternary_search_max;
+///
+/// let x = ternary_search_max(-10.0, 10.0, 1e-9, |x| -(x - 3.0) * (x - 3.0));
+/// assert!((x - 3.0).abs() < 1e-6);
+/// ```
+pub fn ternary_search_max<F>(mut lo: f64, mut hi: f64, eps: f64, f: F) -> f64
+where
+    F: Fn(f64) -> f64,
+{
+    for _ in 0..300 {
+        if hi - lo <= eps {
+            break;
+        }
+
+        let m1 = lo + (hi - lo) / 3.0;
+        let m2 = hi - (hi - lo) / 3.0;
+        if f(m1) < f(m2) {
+            // 最大值点一定不在 [lo, m1] 中
+            lo = m1;
+        } else {
+            hi = m2;
+        }
+    }
+
+    lo + (hi - lo) / 2.0
+}
+
+/// 三分搜索单峰函数 f 在 `[lo, hi]` 上的最小值点, 区间长度小于 eps 时停止
+///
+/// ```
+/// use impx::binary_search::ternary_search_min;
+///
+/// let x = ternary_search_min(0.0, 4.0, 1e-9, |x| (x - 1.5).abs());
+/// assert!((x - 1.5).abs() < 1e-6);
+/// ```
+pub fn ternary_search_min<F>(lo: f64, hi: f64, eps: f64, f: F) -> f64
+where
+    F: Fn(f64) -> f64,
+{
+    ternary_search_max(lo, hi, eps, |x| -f(x))
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -145,4 +281,57 @@ mod tests {
         assert_eq!(search(&[1, 1, 2, 2, 3, 3, 4], |&x| x < 1), None);
         assert_eq!(search(&[6, 5, 4, 3, 2, 1], |&x| x >= 4), Some(2));
     }
+
+    #[test]
+    fn test_bounds() {
+        use super::*;
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        for n in 0..30 {
+            let mut v: Vec<i32> = (0..n).map(|_| rng.gen_range(0..10)).collect();
+            v.sort();
+            for target in -1..11 {
+                assert_eq!(lower_bound(&v, &target), v.partition_point(|&x| x < target));
+                assert_eq!(
+                    upper_bound(&v, &target),
+                    v.partition_point(|&x| x <= target)
+                );
+            }
+        }
+
+        assert_eq!(binary_search_range(-100, 100, |x| x >= -7), Some(-7));
+        assert_eq!(binary_search_range(5, 5, |_| true), None);
+        assert_eq!(
+            binary_search_range(0, i64::MAX, |x| x >= 1 << 40),
+            Some(1 << 40)
+        );
+
+        // 区间跨越整个 i64
+        let (min, max) = (i64::MIN, i64::MAX);
+        assert_eq!(binary_search_range(min, max, |x| x >= -3), Some(-3));
+        assert_eq!(binary_search_range(min, max, |_| true), Some(min));
+        assert_eq!(
+            binary_search_range(min, max, |x| x >= max - 1),
+            Some(max - 1)
+        );
+        assert_eq!(binary_search_range(min, max, |_| false), None);
+        assert_eq!(binary_search_range(min, min + 1, |_| true), Some(min));
+    }
+
+    #[test]
+    fn test_bisect_and_ternary() {
+        use super::*;
+
+        let cbrt = bisect(0.0, 100.0, 1e-12, |x| x * x * x >= 27.0);
+        assert!((cbrt - 3.0).abs() < 1e-9);
+        // eps 过小时也能正常结束
+        let x = bisect(0.0, 1.0, 0.0, |x| x >= 0.25);
+        assert!((x - 0.25).abs() < 1e-12);
+
+        let x = ternary_search_max(0.0, std::f64::consts::PI, 1e-12, f64::sin);
+        assert!((x - std::f64::consts::FRAC_PI_2).abs() < 1e-6);
+        let x = ternary_search_min(-5.0, 5.0, 0.0, |x| (x + 2.0) * (x + 2.0) + 1.0);
+        assert!((x + 2.0).abs() < 1e-6);
+    }
 }
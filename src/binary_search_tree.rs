@@ -1,17 +1,34 @@
 //! 二叉搜索树
+//!
+//! <https://oi-wiki.org/ds/bst/>
+//!
+//! 不做任何平衡处理, 用来和 AVL 树, 红黑树等平衡树对比: 按顺序插入时会退化成链表, 高度等于元素个数  
+//! 相同的元素只保存一个节点, 出现次数记录在 `value.1` 中
+//!
+//! 退化成链表时树的高度可以达到 10⁵ 以上, 所以插入, 查找, 删除以及析构都使用循环实现, 避免递归导致栈溢出
+//!
+//! 每个节点额外维护子树中的元素个数 `size`(重复的元素计算多次), 由此可以在 O(h) 时间内求排名和第 k 小的元素
 
 use std::cmp::Ordering;
 
 /// 二叉搜索树节点
 pub struct BinarySearchNode<T> {
     pub value: (T, usize),
+    /// 子树中的元素个数
+    pub size: usize,
     pub left: Option<Box<BinarySearchNode<T>>>,
     pub right: Option<Box<BinarySearchNode<T>>>,
 }
 
+type Link<T> = Option<Box<BinarySearchNode<T>>>;
+
+fn size<T>(link: &Link<T>) -> usize {
+    link.as_ref().map_or(0, |node| node.size)
+}
+
 /// 二叉搜索树
 pub struct BinarySearchTree<T> {
-    pub root: Option<BinarySearchNode<T>>,
+    pub root: Link<T>,
 }
 
 impl<T: Ord> Default for BinarySearchTree<T> {
@@ -25,14 +42,68 @@ impl<T: Ord> BinarySearchTree<T> {
         BinarySearchTree { root: None }
     }
 
+    /// 元素个数, 重复的元素计算多次
+    pub fn len(&self) -> usize {
+        size(&self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// 树的高度, 空树为 0
+    pub fn height(&self) -> usize {
+        self.root.as_ref().map_or(0, |node| node.height())
+    }
+
     /// 插入元素
     pub fn insert(&mut self, value: T) {
         match &mut self.root {
-            None => self.root = Some(BinarySearchNode::new(value)),
+            None => self.root = Some(Box::new(BinarySearchNode::new(value))),
             Some(node) => node.insert(value),
         }
     }
 
+    /// 删除一个元素, 出现多次时只减少出现次数, 返回元素是否存在
+    ///
+    /// - 叶子节点直接删除
+    /// - 只有一个子节点时用子节点替换自己
+    /// - 有两个子节点时用后继(右子树的最小节点)替换自己, 再从右子树中删除后继
+    pub fn delete(&mut self, value: &T) -> bool {
+        // 先确认元素存在, 下降时才能直接更新路径上的子树大小
+        if !self.find(value) {
+            return false;
+        }
+
+        let mut link = &mut self.root;
+        loop {
+            let node = link.as_mut().unwrap();
+            node.size -= 1;
+            match node.value.0.cmp(value) {
+                Ordering::Equal => break,
+                Ordering::Less => link = &mut link.as_mut().unwrap().right,
+                Ordering::Greater => link = &mut link.as_mut().unwrap().left,
+            }
+        }
+
+        let node = link.as_mut().unwrap();
+        if node.value.1 > 1 {
+            node.value.1 -= 1;
+            return true;
+        }
+
+        match (node.left.take(), node.right.take()) {
+            (None, None) => *link = None,
+            (Some(child), None) | (None, Some(child)) => *link = Some(child),
+            (left, right) => {
+                node.left = left;
+                node.right = right;
+                node.value = take_min(&mut node.right);
+            }
+        }
+        true
+    }
+
     /// 查找元素是否存在
     pub fn find(&self, value: &T) -> bool {
         self.root
@@ -50,73 +121,258 @@ impl<T: Ord> BinarySearchTree<T> {
     pub fn min(&self) -> Option<&T> {
         self.root.as_ref().map(|node| node.min())
     }
+
+    /// 严格小于 value 的元素个数, 重复的元素计算多次
+    pub fn rank(&self, value: &T) -> usize {
+        let mut rank = 0;
+        let mut link = self.root.as_deref();
+        while let Some(node) = link {
+            match node.value.0.cmp(value) {
+                Ordering::Equal => return rank + size(&node.left),
+                Ordering::Less => {
+                    rank += size(&node.left) + node.value.1;
+                    link = node.right.as_deref();
+                }
+                Ordering::Greater => link = node.left.as_deref(),
+            }
+        }
+        rank
+    }
+
+    /// 第 k 小的元素, k 从 0 开始, 重复的元素计算多次
+    pub fn select(&self, mut k: usize) -> Option<&T> {
+        let mut link = self.root.as_deref();
+        while let Some(node) = link {
+            let left = size(&node.left);
+            if k < left {
+                link = node.left.as_deref();
+            } else if k < left + node.value.1 {
+                return Some(&node.value.0);
+            } else {
+                k -= left + node.value.1;
+                link = node.right.as_deref();
+            }
+        }
+        None
+    }
 }
 
 impl<T: Ord> BinarySearchNode<T> {
     pub fn new(value: T) -> Self {
         Self {
             value: (value, 1),
+            size: 1,
             left: None,
             right: None,
         }
     }
 
+    /// 逐层遍历, 层数就是高度
+    pub fn height(&self) -> usize {
+        let mut height = 0;
+        let mut level = vec![self];
+        while !level.is_empty() {
+            height += 1;
+            level = level
+                .into_iter()
+                .flat_map(|node| [node.left.as_deref(), node.right.as_deref()])
+                .flatten()
+                .collect();
+        }
+        height
+    }
+
     pub fn find(&self, v: &T) -> bool {
-        match self.value.0.cmp(v) {
-            Ordering::Equal => true,
-            Ordering::Less => self
-                .right
-                .as_ref()
-                .map(|node| node.find(v))
-                .unwrap_or_default(),
-            Ordering::Greater => self
-                .left
-                .as_ref()
-                .map(|node| node.find(v))
-                .unwrap_or_default(),
+        let mut node = self;
+        loop {
+            let next = match node.value.0.cmp(v) {
+                Ordering::Equal => return true,
+                Ordering::Less => &node.right,
+                Ordering::Greater => &node.left,
+            };
+            match next {
+                None => return false,
+                Some(next) => node = next,
+            }
         }
     }
 
     pub fn insert(&mut self, v: T) {
-        match self.value.0.cmp(&v) {
-            Ordering::Equal => {
-                self.value.1 += 1;
-            }
-            Ordering::Less => match &mut self.right {
-                None => {
-                    self.right = Some(Box::new(Self::new(v)));
-                }
-                Some(node) => {
-                    node.insert(v);
+        let mut node = self;
+        loop {
+            node.size += 1;
+            let next = match node.value.0.cmp(&v) {
+                Ordering::Equal => {
+                    node.value.1 += 1;
+                    return;
                 }
-            },
-            Ordering::Greater => match &mut self.left {
+                Ordering::Less => &mut node.right,
+                Ordering::Greater => &mut node.left,
+            };
+            match next {
                 None => {
-                    self.left = Some(Box::new(Self::new(v)));
-                }
-                Some(node) => {
-                    node.insert(v);
+                    *next = Some(Box::new(Self::new(v)));
+                    return;
                 }
-            },
+                Some(next) => node = next,
+            }
         }
     }
 
     pub fn min(&self) -> &T {
+        &self.min_node().value.0
+    }
+
+    pub fn max(&self) -> &T {
         let mut node = self;
-        while let Some(child) = &node.left {
+        while let Some(child) = &node.right {
             node = child.as_ref();
         }
 
         &node.value.0
     }
+}
 
-    pub fn max(&self) -> &T {
+/// 从非空子树中摘下最小的节点, 返回它的值, 路径上的子树大小都减去它的出现次数
+fn take_min<T>(mut link: &mut Link<T>) -> (T, usize) {
+    let count = link.as_deref().unwrap().min_node().value.1;
+    while link.as_ref().unwrap().left.is_some() {
+        let node = link.as_mut().unwrap();
+        node.size -= count;
+        link = &mut node.left;
+    }
+
+    let node = link.take().unwrap();
+    *link = node.right;
+    node.value
+}
+
+impl<T> BinarySearchNode<T> {
+    fn min_node(&self) -> &Self {
         let mut node = self;
-        while let Some(child) = &node.right {
+        while let Some(child) = &node.left {
             node = child.as_ref();
         }
+        node
+    }
+}
 
-        &node.value.0
+/// 默认的析构是递归的, 退化成链表时会栈溢出
+impl<T> Drop for BinarySearchTree<T> {
+    fn drop(&mut self) {
+        let mut stack: Vec<_> = self.root.take().into_iter().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.extend(node.left.take());
+            stack.extend(node.right.take());
+        }
+    }
+}
+
+impl<T> BinarySearchTree<T> {
+    /// 中序遍历引用迭代器, 重复的元素会返回多次
+    pub fn iter(&self) -> Iter<'_, T> {
+        let mut iter = Iter {
+            stack: vec![],
+            current: None,
+            remain: 0,
+        };
+        iter.push_left(self.root.as_deref());
+        iter
+    }
+}
+
+/// 中序遍历引用迭代器
+pub struct Iter<'a, T> {
+    stack: Vec<&'a BinarySearchNode<T>>,
+    current: Option<&'a T>,
+    /// 当前元素还需要返回的次数
+    remain: usize,
+}
+
+impl<'a, T> Iter<'a, T> {
+    fn push_left(&mut self, mut link: Option<&'a BinarySearchNode<T>>) {
+        while let Some(node) = link {
+            self.stack.push(node);
+            link = node.left.as_deref();
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remain == 0 {
+            let node = self.stack.pop()?;
+            self.push_left(node.right.as_deref());
+            self.current = Some(&node.value.0);
+            self.remain = node.value.1;
+        }
+
+        self.remain -= 1;
+        self.current
+    }
+}
+
+/// 按先序序列化所有值和出现次数, 按先序重新插入可以得到形状完全相同的树
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// 用显式的栈做先序遍历, 退化成链表时递归会栈溢出
+    fn preorder<T>(root: Option<&BinarySearchNode<T>>) -> Vec<(&T, usize)> {
+        let mut values = vec![];
+        let mut stack: Vec<_> = root.into_iter().collect();
+        while let Some(node) = stack.pop() {
+            values.push((&node.value.0, node.value.1));
+            stack.extend(node.right.as_deref());
+            stack.extend(node.left.as_deref());
+        }
+        values
+    }
+
+    impl<T: Serialize> Serialize for BinarySearchTree<T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(preorder(self.root.as_deref()))
+        }
+    }
+
+    impl<'de, T: Deserialize<'de> + Ord> Deserialize<'de> for BinarySearchTree<T> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let mut tree = BinarySearchTree::new();
+            for (value, count) in Vec::<(T, usize)>::deserialize(deserializer)? {
+                // 先序中每个值第一次出现时一定插入到叶子节点上, 直接设置出现次数
+                let mut leaf = BinarySearchNode::new(value);
+                leaf.value.1 = count;
+                leaf.size = count;
+
+                let mut node = match &mut tree.root {
+                    None => {
+                        tree.root = Some(Box::new(leaf));
+                        continue;
+                    }
+                    Some(root) => root,
+                };
+                loop {
+                    node.size += count;
+                    let child = match node.value.0.cmp(&leaf.value.0) {
+                        Ordering::Greater => &mut node.left,
+                        Ordering::Less => &mut node.right,
+                        Ordering::Equal => {
+                            node.value.1 += count;
+                            break;
+                        }
+                    };
+                    if child.is_none() {
+                        *child = Some(Box::new(leaf));
+                        break;
+                    }
+                    node = child.as_mut().unwrap();
+                }
+            }
+            Ok(tree)
+        }
     }
 }
 
@@ -139,4 +395,198 @@ mod tests {
         assert_eq!(t.max(), Some(&9));
         assert_eq!(t.min(), Some(&1));
     }
+
+    #[test]
+    fn test_binary_search_tree_delete() {
+        let mut t = BinarySearchTree::new();
+        assert!(!t.delete(&1));
+        assert_eq!((t.len(), t.height()), (0, 0));
+
+        //       5
+        //     /   \
+        //    3     8
+        //   / \   / \
+        //  1   4 7   9
+        //       /
+        //      6
+        for v in [5, 3, 8, 1, 4, 7, 9, 6, 3] {
+            t.insert(v);
+        }
+        assert_eq!(t.len(), 9);
+        assert_eq!(t.height(), 4);
+        assert!(t.iter().eq(&[1, 3, 3, 4, 5, 6, 7, 8, 9]));
+
+        // 重复的元素只减少次数
+        assert!(t.delete(&3));
+        assert!(t.find(&3));
+        assert!(t.iter().eq(&[1, 3, 4, 5, 6, 7, 8, 9]));
+
+        // 叶子节点, 只有一个子节点, 有两个子节点的根节点
+        assert!(t.delete(&1));
+        assert!(t.delete(&7));
+        assert!(t.delete(&5));
+        assert!(!t.delete(&5));
+        assert!(t.iter().eq(&[3, 4, 6, 8, 9]));
+        assert_eq!(t.root.as_ref().map(|n| n.value.0), Some(6));
+        assert_eq!(t.len(), 5);
+
+        for v in [3, 4, 6, 8, 9] {
+            assert!(t.delete(&v));
+        }
+        assert!(t.is_empty());
+        assert!(t.root.is_none());
+    }
+
+    #[test]
+    fn test_binary_search_tree_rank_select() {
+        let mut t = BinarySearchTree::new();
+        assert_eq!((t.rank(&1), t.select(0)), (0, None));
+
+        for v in [5, 3, 8, 3, 1, 8, 8] {
+            t.insert(v);
+        }
+        // 1 3 3 5 8 8 8
+        assert_eq!(t.rank(&0), 0);
+        assert_eq!(t.rank(&3), 1);
+        assert_eq!(t.rank(&4), 3);
+        assert_eq!(t.rank(&8), 4);
+        assert_eq!(t.rank(&9), 7);
+        let all: Vec<_> = (0..8).map(|k| t.select(k).copied()).collect();
+        assert_eq!(
+            all,
+            [1, 3, 3, 5, 8, 8, 8]
+                .map(Some)
+                .into_iter()
+                .chain([None])
+                .collect::<Vec<_>>()
+        );
+
+        // 删除有两个子节点的根节点后大小依然正确
+        assert!(t.delete(&5));
+        assert!(t.delete(&8));
+        assert_eq!(t.len(), 5);
+        assert_eq!(t.rank(&8), 3);
+        assert_eq!(t.select(4), Some(&8));
+    }
+
+    /// 按顺序插入时树退化成链表
+    fn do_degenerate_test(n: usize) {
+        let mut t = BinarySearchTree::new();
+        for v in 0..n {
+            t.insert(v);
+        }
+        assert_eq!(t.len(), n);
+        assert_eq!(t.height(), n);
+        assert!(t.find(&(n - 1)));
+        assert!(!t.find(&n));
+        assert_eq!(t.rank(&(n / 2)), n / 2);
+        assert_eq!(t.select(n - 1), Some(&(n - 1)));
+        assert!(t.iter().copied().eq(0..n));
+
+        for v in (0..n).step_by(2) {
+            assert!(t.delete(&v));
+        }
+        assert_eq!(t.len(), n / 2);
+        assert_eq!(t.select(0), Some(&1));
+    }
+
+    #[test]
+    fn test_binary_search_tree_degenerate() {
+        do_degenerate_test(20_000);
+    }
+
+    /// 直接从下往上构造一条向右的链 `0 -> 1 -> ... -> n-1`, 逐个插入需要 O(n²)
+    fn right_chain(n: usize) -> BinarySearchTree<usize> {
+        let mut root = None;
+        for v in (0..n).rev() {
+            let mut node = BinarySearchNode::new(v);
+            node.size = n - v;
+            node.right = root;
+            root = Some(Box::new(node));
+        }
+        BinarySearchTree { root }
+    }
+
+    #[test]
+    fn test_binary_search_tree_degenerate_large() {
+        let n = 100_000;
+        let mut t = right_chain(n);
+        assert_eq!(t.len(), n);
+        assert_eq!(t.height(), n);
+        assert!(t.find(&(n - 1)));
+        assert!(!t.find(&n));
+        assert_eq!((t.min(), t.max()), (Some(&0), Some(&(n - 1))));
+        assert_eq!(t.rank(&(n / 2)), n / 2);
+        assert_eq!(t.select(n - 1), Some(&(n - 1)));
+        assert!(t.iter().copied().eq(0..n));
+
+        t.insert(n);
+        assert_eq!(t.height(), n + 1);
+        for v in [n, n - 1, n / 2, 0] {
+            assert!(t.delete(&v));
+        }
+        assert_eq!(t.len(), n - 3);
+        assert_eq!(t.select(0), Some(&1));
+        assert_eq!(t.rank(&(n - 2)), n - 4);
+    }
+
+    #[test]
+    fn test_binary_search_tree_random() {
+        use rand::Rng;
+        use std::collections::BTreeMap;
+
+        let mut rng = rand::thread_rng();
+        let mut t = BinarySearchTree::new();
+        let mut model = BTreeMap::new();
+        for _ in 0..10000 {
+            let v = rng.gen_range(0..200);
+            if rng.gen_bool(0.5) {
+                t.insert(v);
+                *model.entry(v).or_insert(0) += 1;
+            } else {
+                let exist = model.contains_key(&v);
+                assert_eq!(t.delete(&v), exist);
+                if exist {
+                    *model.get_mut(&v).unwrap() -= 1;
+                    model.retain(|_, n| *n > 0);
+                }
+            }
+
+            assert_eq!(t.len(), model.values().sum::<usize>());
+        }
+
+        let all: Vec<_> = t.iter().copied().collect();
+        for (k, v) in all.iter().enumerate() {
+            assert_eq!(t.select(k), Some(v));
+            assert_eq!(t.rank(v), all.partition_point(|x| x < v));
+        }
+        assert_eq!(t.select(all.len()), None);
+        assert_eq!(t.rank(&200), all.len());
+
+        let expect = model.iter().flat_map(|(v, &n)| std::iter::repeat_n(v, n));
+        assert!(t.iter().eq(expect));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_binary_search_tree_serde() {
+        let mut t = BinarySearchTree::new();
+        for v in [5, 3, 8, 1, 4, 8, 3, 3] {
+            t.insert(v);
+        }
+
+        let json = serde_json::to_string(&t).unwrap();
+        assert_eq!(json, "[[5,1],[3,3],[1,1],[4,1],[8,2]]");
+
+        let t: BinarySearchTree<i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&t).unwrap(), json);
+        assert_eq!((t.min(), t.max()), (Some(&1), Some(&8)));
+        assert_eq!(t.len(), 8);
+
+        // 退化成链表时序列化不会栈溢出
+        let n = 100_000;
+        let json = serde_json::to_string(&right_chain(n)).unwrap();
+        let expect: Vec<(usize, usize)> = (0..n).map(|v| (v, 1)).collect();
+        assert_eq!(json, serde_json::to_string(&expect).unwrap());
+    }
 }
@@ -1,4 +1,26 @@
 //! 二叉树
+//!
+//! - [OI Wiki - 二叉树](https://oi-wiki.org/graph/tree-basic/#二叉树)
+//! - [wikipedia - Tree rotation](https://en.wikipedia.org/wiki/Tree_rotation)
+//!
+//! [`BinaryTree`] 是最朴素的写法, 每个节点通过 Box 拥有左右子树, 只能从上往下访问
+//!
+//! 很多树上的算法需要从子节点回到父节点(比如求 LCA、伸展树), 用 Box 和裸指针写父指针很容易出错,
+//! [`ArenaTree`] 把所有节点放在一个 Vec 里, 用下标代替指针, 父指针只是一个普通的下标  
+//! 每个节点还维护了子树大小和子树的权值之和, 修改结构时沿着父指针向上更新, 可以用来做按排名查找、霍夫曼树等
+//!
+//! 旋转是平衡树的基本操作, 以左旋为例, x 的右儿子 y 成为新的子树根, y 原来的左子树成为 x 的右子树:
+//!
+//! ```text
+//!     x                y
+//!    / \              / \
+//!   a   y    =>      x   c
+//!      / \          / \
+//!     b   c        a   b
+//! ```
+//!
+//! 旋转前后中序遍历的顺序不变, 只有 x 和 y 的子树信息需要重新计算
+
 pub struct BinaryTree<T> {
     pub value: T,
     pub left: Option<Box<BinaryTree<T>>>,
@@ -13,4 +35,477 @@ impl<T> BinaryTree<T> {
             right: None,
         }
     }
+
+    /// 使用给定的左右子树创建节点
+    pub fn with_children(
+        value: T,
+        left: Option<BinaryTree<T>>,
+        right: Option<BinaryTree<T>>,
+    ) -> Self {
+        Self {
+            value,
+            left: left.map(Box::new),
+            right: right.map(Box::new),
+        }
+    }
+
+    /// 子树中的节点数
+    pub fn size(&self) -> usize {
+        1 + self.left.as_ref().map_or(0, |n| n.size()) + self.right.as_ref().map_or(0, |n| n.size())
+    }
+
+    /// 树的高度, 只有一个节点时为 1
+    pub fn height(&self) -> usize {
+        let left = self.left.as_ref().map_or(0, |n| n.height());
+        let right = self.right.as_ref().map_or(0, |n| n.height());
+        1 + left.max(right)
+    }
+
+    /// 左旋, 没有右子树时不做任何操作
+    pub fn rotate_left(mut self: Box<Self>) -> Box<Self> {
+        match self.right.take() {
+            None => self,
+            Some(mut y) => {
+                self.right = y.left.take();
+                y.left = Some(self);
+                y
+            }
+        }
+    }
+
+    /// 右旋, 没有左子树时不做任何操作
+    pub fn rotate_right(mut self: Box<Self>) -> Box<Self> {
+        match self.left.take() {
+            None => self,
+            Some(mut y) => {
+                self.left = y.right.take();
+                y.right = Some(self);
+                y
+            }
+        }
+    }
+
+    /// 中序遍历
+    pub fn inorder(&self) -> Vec<&T> {
+        let mut res = vec![];
+        self.inorder_into(&mut res);
+        res
+    }
+
+    fn inorder_into<'a>(&'a self, res: &mut Vec<&'a T>) {
+        if let Some(left) = &self.left {
+            left.inorder_into(res);
+        }
+        res.push(&self.value);
+        if let Some(right) = &self.right {
+            right.inorder_into(res);
+        }
+    }
+}
+
+/// [`ArenaTree`] 中的节点编号
+pub type NodeId = usize;
+
+struct Node<T> {
+    value: T,
+    weight: u64,
+    parent: Option<NodeId>,
+    left: Option<NodeId>,
+    right: Option<NodeId>,
+    /// 子树中的节点数
+    size: usize,
+    /// 子树中所有节点的权值之和
+    subtree_weight: u64,
+}
+
+/// 使用下标代替指针、带有父指针和子树信息的二叉树
+///
+/// 一个 ArenaTree 中可以同时存在多棵树, 新创建的节点是一棵只有自己的树
+pub struct ArenaTree<T> {
+    nodes: Vec<Node<T>>,
+}
+
+impl<T> ArenaTree<T> {
+    pub fn new() -> Self {
+        Self { nodes: vec![] }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// 创建一个没有父节点和子节点的新节点
+    pub fn add_node(&mut self, value: T, weight: u64) -> NodeId {
+        self.nodes.push(Node {
+            value,
+            weight,
+            parent: None,
+            left: None,
+            right: None,
+            size: 1,
+            subtree_weight: weight,
+        });
+        self.nodes.len() - 1
+    }
+
+    pub fn value(&self, id: NodeId) -> &T {
+        &self.nodes[id].value
+    }
+
+    pub fn value_mut(&mut self, id: NodeId) -> &mut T {
+        &mut self.nodes[id].value
+    }
+
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.nodes[id].parent
+    }
+
+    pub fn left(&self, id: NodeId) -> Option<NodeId> {
+        self.nodes[id].left
+    }
+
+    pub fn right(&self, id: NodeId) -> Option<NodeId> {
+        self.nodes[id].right
+    }
+
+    pub fn size(&self, id: NodeId) -> usize {
+        self.nodes[id].size
+    }
+
+    pub fn weight(&self, id: NodeId) -> u64 {
+        self.nodes[id].weight
+    }
+
+    pub fn subtree_weight(&self, id: NodeId) -> u64 {
+        self.nodes[id].subtree_weight
+    }
+
+    /// 修改节点的权值并更新祖先的子树信息
+    pub fn set_weight(&mut self, id: NodeId, weight: u64) {
+        self.nodes[id].weight = weight;
+        self.pull_up(Some(id));
+    }
+
+    /// 根据子节点重新计算 id 的子树信息
+    fn pull(&mut self, id: NodeId) {
+        let (left, right) = (self.nodes[id].left, self.nodes[id].right);
+        let info = |c: Option<NodeId>| {
+            c.map_or((0, 0), |c| {
+                (self.nodes[c].size, self.nodes[c].subtree_weight)
+            })
+        };
+        let ((ls, lw), (rs, rw)) = (info(left), info(right));
+        let node = &mut self.nodes[id];
+        node.size = 1 + ls + rs;
+        node.subtree_weight = node.weight + lw + rw;
+    }
+
+    /// 从 id 开始沿着父指针一直更新到根
+    fn pull_up(&mut self, mut id: Option<NodeId>) {
+        while let Some(x) = id {
+            self.pull(x);
+            id = self.nodes[x].parent;
+        }
+    }
+
+    /// 设置 id 的左儿子, 原来的左儿子会和 id 断开, 新的左儿子必须没有父节点
+    pub fn set_left(&mut self, id: NodeId, child: Option<NodeId>) {
+        self.set_child(id, child, true);
+    }
+
+    /// 设置 id 的右儿子, 原来的右儿子会和 id 断开, 新的右儿子必须没有父节点
+    pub fn set_right(&mut self, id: NodeId, child: Option<NodeId>) {
+        self.set_child(id, child, false);
+    }
+
+    fn set_child(&mut self, id: NodeId, child: Option<NodeId>, left: bool) {
+        if let Some(c) = child {
+            assert!(self.nodes[c].parent.is_none(), "child already has a parent");
+            assert!(self.root(id) != c, "cannot attach an ancestor as a child");
+            self.nodes[c].parent = Some(id);
+        }
+
+        let slot = if left {
+            &mut self.nodes[id].left
+        } else {
+            &mut self.nodes[id].right
+        };
+        if let Some(old) = std::mem::replace(slot, child) {
+            self.nodes[old].parent = None;
+        }
+        self.pull_up(Some(id));
+    }
+
+    /// 把 old 在父节点中的位置替换成 new
+    fn replace_child(&mut self, parent: Option<NodeId>, old: NodeId, new: NodeId) {
+        self.nodes[new].parent = parent;
+        if let Some(p) = parent {
+            if self.nodes[p].left == Some(old) {
+                self.nodes[p].left = Some(new);
+            } else {
+                self.nodes[p].right = Some(new);
+            }
+        }
+    }
+
+    /// 左旋, 返回旋转后子树的根, 没有右儿子时不做任何操作
+    pub fn rotate_left(&mut self, x: NodeId) -> NodeId {
+        let Some(y) = self.nodes[x].right else {
+            return x;
+        };
+
+        let b = self.nodes[y].left;
+        self.replace_child(self.nodes[x].parent, x, y);
+        self.nodes[x].right = b;
+        if let Some(b) = b {
+            self.nodes[b].parent = Some(x);
+        }
+        self.nodes[y].left = Some(x);
+        self.nodes[x].parent = Some(y);
+
+        self.pull(x);
+        self.pull(y);
+        y
+    }
+
+    /// 右旋, 返回旋转后子树的根, 没有左儿子时不做任何操作
+    pub fn rotate_right(&mut self, x: NodeId) -> NodeId {
+        let Some(y) = self.nodes[x].left else {
+            return x;
+        };
+
+        let b = self.nodes[y].right;
+        self.replace_child(self.nodes[x].parent, x, y);
+        self.nodes[x].left = b;
+        if let Some(b) = b {
+            self.nodes[b].parent = Some(x);
+        }
+        self.nodes[y].right = Some(x);
+        self.nodes[x].parent = Some(y);
+
+        self.pull(x);
+        self.pull(y);
+        y
+    }
+
+    /// id 所在树的根
+    pub fn root(&self, mut id: NodeId) -> NodeId {
+        while let Some(p) = self.nodes[id].parent {
+            id = p;
+        }
+        id
+    }
+
+    /// 节点的深度, 根的深度为 0
+    pub fn depth(&self, mut id: NodeId) -> usize {
+        let mut depth = 0;
+        while let Some(p) = self.nodes[id].parent {
+            (id, depth) = (p, depth + 1);
+        }
+        depth
+    }
+
+    /// 最近公共祖先, 不在同一棵树中时返回 None
+    ///
+    /// 先把较深的节点向上移动到相同深度, 再同时向上移动直到相遇, 复杂度 O(h)
+    pub fn lca(&self, mut a: NodeId, mut b: NodeId) -> Option<NodeId> {
+        let (mut da, mut db) = (self.depth(a), self.depth(b));
+        while da > db {
+            (a, da) = (self.nodes[a].parent?, da - 1);
+        }
+        while db > da {
+            (b, db) = (self.nodes[b].parent?, db - 1);
+        }
+        while a != b {
+            a = self.nodes[a].parent?;
+            b = self.nodes[b].parent?;
+        }
+        Some(a)
+    }
+
+    /// 子树中按中序遍历的第 k 个节点(从 0 开始), 利用子树大小做到 O(h)
+    pub fn select(&self, mut id: NodeId, mut k: usize) -> Option<NodeId> {
+        if k >= self.nodes[id].size {
+            return None;
+        }
+
+        loop {
+            let ls = self.nodes[id].left.map_or(0, |l| self.nodes[l].size);
+            match k.cmp(&ls) {
+                std::cmp::Ordering::Less => id = self.nodes[id].left.unwrap(),
+                std::cmp::Ordering::Equal => return Some(id),
+                std::cmp::Ordering::Greater => {
+                    k -= ls + 1;
+                    id = self.nodes[id].right.unwrap();
+                }
+            }
+        }
+    }
+
+    /// 子树的中序遍历
+    pub fn inorder(&self, id: NodeId) -> Vec<NodeId> {
+        let mut res = Vec::with_capacity(self.nodes[id].size);
+        let mut stack = vec![];
+        let mut cur = Some(id);
+        while cur.is_some() || !stack.is_empty() {
+            while let Some(x) = cur {
+                stack.push(x);
+                cur = self.nodes[x].left;
+            }
+            let x = stack.pop().unwrap();
+            res.push(x);
+            cur = self.nodes[x].right;
+        }
+        res
+    }
+}
+
+impl<T> Default for ArenaTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> ArenaTree<T> {
+    /// 把 [`BinaryTree`] 转换到 arena 中, 每个节点的权值由 weight 计算, 返回根节点
+    pub fn insert_tree(&mut self, tree: BinaryTree<T>, weight: &impl Fn(&T) -> u64) -> NodeId {
+        let BinaryTree { value, left, right } = tree;
+        let w = weight(&value);
+        let id = self.add_node(value, w);
+        if let Some(left) = left {
+            let l = self.insert_tree(*left, weight);
+            self.set_left(id, Some(l));
+        }
+        if let Some(right) = right {
+            let r = self.insert_tree(*right, weight);
+            self.set_right(id, Some(r));
+        }
+        id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 检查所有节点的父指针和子树信息
+    fn check<T>(tree: &ArenaTree<T>) {
+        for id in 0..tree.len() {
+            let node = &tree.nodes[id];
+            let mut size = 1;
+            let mut weight = node.weight;
+            for c in [node.left, node.right].into_iter().flatten() {
+                assert_eq!(tree.parent(c), Some(id));
+                size += tree.size(c);
+                weight += tree.subtree_weight(c);
+            }
+            assert_eq!(node.size, size);
+            assert_eq!(node.subtree_weight, weight);
+        }
+    }
+
+    #[test]
+    fn test_binary_tree() {
+        let tree = BinaryTree::with_children(
+            2,
+            Some(BinaryTree::new(1)),
+            Some(BinaryTree::with_children(4, Some(BinaryTree::new(3)), None)),
+        );
+        assert_eq!(tree.size(), 4);
+        assert_eq!(tree.height(), 3);
+
+        let tree = Box::new(tree).rotate_left();
+        assert_eq!(tree.value, 4);
+        assert_eq!(tree.inorder(), vec![&1, &2, &3, &4]);
+        let tree = tree.rotate_right().rotate_right();
+        assert_eq!(tree.value, 1);
+        assert_eq!(tree.inorder(), vec![&1, &2, &3, &4]);
+    }
+
+    #[test]
+    fn test_arena_tree() {
+        use rand::Rng;
+
+        // 用一棵链状的树 0 - 1 - ... - 19 (都是右儿子) 反复随机旋转
+        let mut tree = ArenaTree::new();
+        let ids: Vec<_> = (0..20).map(|i| tree.add_node(i, i as u64)).collect();
+        for w in ids.windows(2) {
+            tree.set_right(w[0], Some(w[1]));
+        }
+        check(&tree);
+        assert_eq!(tree.size(0), 20);
+        assert_eq!(tree.subtree_weight(0), 190);
+        assert_eq!(tree.depth(19), 19);
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..1000 {
+            let x = rng.gen_range(0..20);
+            if rng.gen_bool(0.5) {
+                tree.rotate_left(x);
+            } else {
+                tree.rotate_right(x);
+            }
+            check(&tree);
+
+            let root = tree.root(0);
+            let order: Vec<_> = tree
+                .inorder(root)
+                .into_iter()
+                .map(|i| *tree.value(i))
+                .collect();
+            assert_eq!(order, (0..20).collect::<Vec<_>>());
+            assert_eq!(
+                (0..20).map(|k| tree.select(root, k)).collect::<Vec<_>>(),
+                ids.iter().map(|&i| Some(i)).collect::<Vec<_>>()
+            );
+        }
+
+        tree.set_weight(5, 100);
+        check(&tree);
+        assert_eq!(tree.subtree_weight(tree.root(0)), 285);
+
+        // 断开一棵子树
+        let root = tree.root(0);
+        let left = tree.left(root);
+        tree.set_left(root, None);
+        check(&tree);
+        if let Some(left) = left {
+            assert_eq!(tree.parent(left), None);
+            assert_eq!(tree.size(root) + tree.size(left), 20);
+            assert_eq!(tree.lca(left, root), None);
+        }
+    }
+
+    #[test]
+    fn test_arena_lca() {
+        //        0
+        //      /   \
+        //     1     2
+        //    / \     \
+        //   3   4     5
+        //      /
+        //     6
+        let tree = BinaryTree::with_children(
+            0,
+            Some(BinaryTree::with_children(
+                1,
+                Some(BinaryTree::new(3)),
+                Some(BinaryTree::with_children(4, Some(BinaryTree::new(6)), None)),
+            )),
+            Some(BinaryTree::with_children(2, None, Some(BinaryTree::new(5)))),
+        );
+        let mut arena = ArenaTree::new();
+        let root = arena.insert_tree(tree, &|_| 1);
+        let find = |v| (0..arena.len()).find(|&i| *arena.value(i) == v).unwrap();
+
+        assert_eq!(arena.size(root), 7);
+        assert_eq!(arena.lca(find(3), find(6)), Some(find(1)));
+        assert_eq!(arena.lca(find(6), find(5)), Some(root));
+        assert_eq!(arena.lca(find(4), find(6)), Some(find(4)));
+        assert_eq!(arena.depth(find(6)), 3);
+    }
 }
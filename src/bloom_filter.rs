@@ -3,11 +3,15 @@
 //! 参考:
 //!
 //! - [bloom](https://github.com/bits-and-blooms/bloom)
+//!
+//! 计数布隆过滤器把每一位换成一个计数器, 插入时加一删除时减一, 于是可以支持删除  
+//! 计数器溢出后就不再变化, 这个位置之后永远被认为存在
 
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 
 use crate::bitset::BitSet;
+use crate::filter::Filter;
 
 /// 参考 go-bloom 的实现, 这里偷懒直接把标准库哈希重新哈希了四次
 fn hash<T: Hash + ?Sized>(key: &T) -> [u64; 4] {
@@ -30,6 +34,13 @@ fn hash<T: Hash + ?Sized>(key: &T) -> [u64; 4] {
     [v1, v2, v3, v4]
 }
 
+/// 由 4 个基础哈希值组合出第 i 个哈希函数的位置
+fn location(hbase: &[u64; 4], i: usize, m: usize) -> usize {
+    let p1 = hbase[((i + i % 2) % 4) / 2 + 2];
+    let p0 = hbase[i % 2].wrapping_add((i as u64).wrapping_mul(p1));
+    (p0 as usize) % m
+}
+
 pub struct BloomFilter {
     k: usize,
     bits: BitSet,
@@ -44,9 +55,7 @@ impl BloomFilter {
     }
 
     fn location(&self, hbase: &[u64; 4], i: usize) -> usize {
-        let p1 = hbase[((i + i % 2) % 4) / 2 + 2];
-        let (p0, _) = hbase[i % 2].overflowing_add((i as u64) * p1);
-        (p0 as usize) % self.bits.len()
+        location(hbase, i, self.bits.len())
     }
 
     pub fn add<T: Hash + ?Sized>(&mut self, key: &T) {
@@ -68,6 +77,101 @@ impl BloomFilter {
 
         true
     }
+
+    /// 根据置位的数量 X 估计插入的元素数量 `n = -(m/k)·ln(1 - X/m)`
+    pub fn len_estimate(&self) -> usize {
+        let (m, k) = (self.bits.len() as f64, self.k as f64);
+        let x = (0..self.bits.len()).filter(|&i| self.bits.test(i)).count() as f64;
+        if x >= m {
+            return usize::MAX;
+        }
+
+        (-(m / k) * (1.0 - x / m).ln()).round() as usize
+    }
+}
+
+impl Filter for BloomFilter {
+    fn insert(&mut self, key: &[u8]) -> bool {
+        self.add(key);
+        true
+    }
+
+    fn contains(&self, key: &[u8]) -> bool {
+        self.test(key)
+    }
+
+    fn len_estimate(&self) -> usize {
+        BloomFilter::len_estimate(self)
+    }
+}
+
+/// 计数布隆过滤器
+pub struct CountingBloomFilter {
+    k: usize,
+    counters: Vec<u8>,
+}
+
+impl CountingBloomFilter {
+    pub fn new(m: usize, k: usize) -> Self {
+        assert!(m > 0, "counting bloom filter requires at least one counter");
+        Self {
+            k,
+            counters: vec![0; m],
+        }
+    }
+
+    fn location(&self, hbase: &[u64; 4], i: usize) -> usize {
+        location(hbase, i, self.counters.len())
+    }
+
+    pub fn add<T: Hash + ?Sized>(&mut self, key: &T) {
+        let hbase = hash(key);
+        for i in 0..self.k {
+            let pos = self.location(&hbase, i);
+            let c = &mut self.counters[pos];
+            *c = c.saturating_add(1);
+        }
+    }
+
+    pub fn test<T: Hash + ?Sized>(&self, key: &T) -> bool {
+        let hbase = hash(key);
+        (0..self.k).all(|i| self.counters[self.location(&hbase, i)] > 0)
+    }
+
+    /// 删除元素
+    /// 需要保证元素之前被插入过, 否则会把其他元素的计数器减掉导致假阴性
+    pub fn remove<T: Hash + ?Sized>(&mut self, key: &T) {
+        let hbase = hash(key);
+        for i in 0..self.k {
+            let pos = self.location(&hbase, i);
+            let c = &mut self.counters[pos];
+            // 溢出的计数器已经不知道真实的值, 只能保持不变
+            if *c != u8::MAX {
+                *c = c.saturating_sub(1);
+            }
+        }
+    }
+
+    /// 每个元素使计数器的总和增加 k, 计数器没有溢出时是准确值
+    pub fn len_estimate(&self) -> usize {
+        let total: usize = self.counters.iter().map(|&c| c as usize).sum();
+        total / self.k.max(1)
+    }
+}
+
+impl Filter for CountingBloomFilter {
+    fn insert(&mut self, key: &[u8]) -> bool {
+        self.add(key);
+        true
+    }
+
+    fn contains(&self, key: &[u8]) -> bool {
+        self.test(key)
+    }
+
+    fn len_estimate(&self) -> usize {
+        CountingBloomFilter::len_estimate(self)
+    }
 }
 
 #[cfg(test)]
@@ -87,5 +191,29 @@ mod tests {
         assert!(b.test(&2));
         assert!(b.test(&3));
         assert!(b.test(&4));
+
+        assert_eq!(b.len_estimate(), 4);
+    }
+
+    #[test]
+    fn test_counting_bloom_filter() {
+        let mut b = CountingBloomFilter::new(1024, 3);
+        for i in 0..100 {
+            b.add(&i);
+        }
+        assert_eq!(b.len_estimate(), 100);
+        assert!((0..100).all(|i| b.test(&i)));
+
+        for i in 0..50 {
+            b.remove(&i);
+        }
+        assert_eq!(b.len_estimate(), 50);
+        assert!((50..100).all(|i| b.test(&i)));
+
+        for i in 50..100 {
+            b.remove(&i);
+        }
+        assert_eq!(b.len_estimate(), 0);
+        assert!((0..100).all(|i| !b.test(&i)));
     }
 }
@@ -30,8 +30,21 @@
 //! x.left = n.right
 //! n.right = x
 //! ```
+//!
+//! ## O(n) 构建
+//!
+//! 直接用数组的下标作为 k 时序列已经有序, 不需要排序, 用一个栈保存右链:
+//! 加入新元素时把栈顶比它大的节点依次弹出, 弹出的这一段右链成为新节点的左子树, 再把新节点压入栈中  
+//! 每个元素最多入栈出栈一次, 所以是 O(n) 的, 这也就是单调栈
+//!
+//! ## RMQ
+//!
+//! 以小根堆构建时, 区间 `[l, r]` 的最小值就是笛卡尔树上 l 和 r 的最近公共祖先(LCA)  
+//! 而 LCA 又可以通过欧拉序转化成深度序列上的区间最小值, 欧拉序上相邻的深度恰好相差 1,
+//! 可以使用 [`PlusMinusOneRmq`] O(n) 预处理 O(1) 查询, 于是一般的 RMQ 也可以做到 O(n) 预处理 O(1) 查询,
+//! 见 [`CartesianRmq`]
 
-use std::ptr::NonNull;
+use crate::sparse_table::PlusMinusOneRmq;
 
 /// 笛卡尔树节点
 pub struct Node<K, W> {
@@ -46,6 +59,152 @@ pub struct CartesianTree<K, W> {
     pub root: Option<Box<Node<K, W>>>,
 }
 
+impl<K: Copy, W: Copy> CartesianTree<K, W> {
+    /// 中序遍历得到的 (k, w) 序列, 也就是按 k 排序后的原序列
+    pub fn in_order(&self) -> Vec<(K, W)> {
+        let mut res = vec![];
+        let mut stack = vec![];
+        let mut link = self.root.as_deref();
+        loop {
+            while let Some(node) = link {
+                stack.push(node);
+                link = node.left.as_deref();
+            }
+
+            let Some(node) = stack.pop() else {
+                break;
+            };
+            res.push((node.k, node.w));
+            link = node.right.as_deref();
+        }
+        res
+    }
+}
+
+/// 默认的析构是递归的, 有序的输入会退化成一条链, 很长时会栈溢出
+impl<K, W> Drop for CartesianTree<K, W> {
+    fn drop(&mut self) {
+        let mut stack: Vec<_> = self.root.take().into_iter().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.extend(node.left.take());
+            stack.extend(node.right.take());
+        }
+    }
+}
+
+/// 以下标作为 k 从数组构建小根堆笛卡尔树, 值相同时靠左的元素是祖先
+///
+/// ```
+/// use impx::cartesian_tree::from_slice;
+///
+/// let tree = from_slice(&[3, 1, 2]);
+/// let root = tree.root.as_ref().unwrap();
+/// assert_eq!((root.k, root.w), (1, 1));
+/// assert_eq!(root.left.as_ref().map(|n| n.w), Some(3));
+/// assert_eq!(root.right.as_ref().map(|n| n.w), Some(2));
+/// ```
+pub fn from_slice<W: Ord + Copy>(v: &[W]) -> CartesianTree<usize, W> {
+    // 栈中是右链上的节点, 每个节点的右子节点就是栈中的下一个节点, 暂时不连接
+    let mut stack: Vec<Box<Node<usize, W>>> = vec![];
+    for (k, &w) in v.iter().enumerate() {
+        let mut last = None;
+        while stack.last().is_some_and(|top| top.w > w) {
+            let mut top = stack.pop().unwrap();
+            top.right = last;
+            last = Some(top);
+        }
+
+        let mut node = new_node((k, w));
+        node.left = last;
+        stack.push(node);
+    }
+
+    // 最后把右链连接起来, 栈底就是根节点
+    let mut root = None;
+    while let Some(mut node) = stack.pop() {
+        node.right = root;
+        root = Some(node);
+    }
+
+    CartesianTree { root }
+}
+
+/// 通过笛卡尔树和欧拉序把一般的区间最小值查询转化为 ±1 RMQ, O(n) 预处理 O(1) 查询
+///
+/// ```
+/// use impx::cartesian_tree::CartesianRmq;
+///
+/// let rmq = CartesianRmq::new(&[5, 2, 4, 2, 3, 1]);
+/// assert_eq!(rmq.query(0, 4), Some(1));
+/// assert_eq!(rmq.query(2, 4), Some(3));
+/// assert_eq!(rmq.query(0, 5), Some(5));
+/// assert_eq!(rmq.query(3, 2), None);
+/// ```
+pub struct CartesianRmq {
+    /// 每个下标在欧拉序中第一次出现的位置
+    first: Vec<usize>,
+    /// 欧拉序经过的节点, 也就是数组下标
+    euler: Vec<usize>,
+    /// 欧拉序上的深度
+    depth: PlusMinusOneRmq,
+}
+
+impl CartesianRmq {
+    pub fn new<W: Ord + Copy>(v: &[W]) -> Self {
+        let tree = from_slice(v);
+        let mut first = vec![0; v.len()];
+        let mut euler = vec![];
+        let mut depths = vec![];
+
+        // 栈中保存 (节点, 深度, 已经访问过的子节点数), 每次回到节点时都记录一次
+        let mut stack: Vec<_> = tree
+            .root
+            .as_deref()
+            .map(|r| (r, 0, 0))
+            .into_iter()
+            .collect();
+        while let Some((node, depth, visited)) = stack.pop() {
+            if visited == 0 {
+                first[node.k] = euler.len();
+            }
+            euler.push(node.k);
+            depths.push(depth);
+
+            let children = [node.left.as_deref(), node.right.as_deref()];
+            if let Some((i, child)) = (visited..2).find_map(|i| children[i].map(|c| (i, c))) {
+                stack.push((node, depth, i + 1));
+                stack.push((child, depth + 1, 0));
+            }
+        }
+
+        Self {
+            first,
+            euler,
+            depth: PlusMinusOneRmq::new(&depths),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.first.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.first.is_empty()
+    }
+
+    /// 区间 `[l, r]` 最小值的下标, 有多个最小值时返回最靠左的下标, 区间不合法时返回 None
+    pub fn query(&self, l: usize, r: usize) -> Option<usize> {
+        if l > r || r >= self.len() {
+            return None;
+        }
+
+        // 欧拉序是先序的, r 可能是 l 的祖先, 第一次出现的位置不一定有序
+        let (a, b) = (self.first[l], self.first[r]);
+        let i = self.depth.query(a.min(b), a.max(b))?;
+        Some(self.euler[i])
+    }
+}
+
 /// 构建笛卡尔树
 pub fn build_cartesian_tree<K, W>(xs: &mut [(K, W)]) -> CartesianTree<K, W>
 where
@@ -69,48 +228,25 @@ where
 {
     match root {
         None => Some(new_node(x)),
-        Some(root) => {
-            if root.w > x.1 {
-                // 比根节点还要小直接替换成根节点
-                let mut node = new_node(x);
-                node.left = Some(root);
-                return Some(node);
+        Some(root) if root.w > x.1 => {
+            // 比根节点还要小直接替换成根节点
+            let mut node = new_node(x);
+            node.left = Some(root);
+            Some(node)
+        }
+        Some(mut root) => {
+            // 沿着右链向下, 找到第一个不比新节点小的右节点
+            // 直接通过 &mut Option<Box<_>> 在链上移动, 不需要把 Box 转成裸指针
+            let mut link = &mut root.right;
+            while link.as_ref().is_some_and(|right| right.w < x.1) {
+                link = &mut link.as_mut().unwrap().right;
             }
 
-            // 因为所有权和可变性的一些问题，需要用 unsafe 处理
-            let root_raw = Box::into_raw(root);
-            let mut curr_ptr = unsafe { NonNull::new_unchecked(root_raw) };
-            let mut curr_mut = unsafe { curr_ptr.as_mut() };
-
-            loop {
-                match curr_mut.right.take() {
-                    Some(right) => {
-                        let right_raw = Box::into_raw(right);
-                        let mut right_ptr = unsafe { NonNull::new_unchecked(right_raw) };
-                        let right_mut = unsafe { right_ptr.as_mut() };
-
-                        if right_mut.w < x.1 {
-                            // 如果右节点还比较小, 再赋值回去往下递归...
-                            let old_right = unsafe { Box::from_raw(right_raw) };
-                            curr_mut.right = Some(old_right);
-                            curr_mut = right_mut;
-                        } else {
-                            // 否则直接替换此处的右节点, 把旧的右子树替换成新节点的左子树
-                            let old_right = unsafe { Box::from_raw(right_raw) };
-                            let mut node = new_node(x);
-                            node.left = Some(old_right);
-                            curr_mut.right = Some(node);
-
-                            return Some(unsafe { Box::from_raw(root_raw) });
-                        }
-                    }
-                    None => {
-                        // 查到底了，直接添加一个空节点
-                        curr_mut.right = Some(new_node(x));
-                        return Some(unsafe { Box::from_raw(root_raw) });
-                    }
-                }
-            }
+            // 替换此处的右节点, 把旧的右子树替换成新节点的左子树, 查到底时旧的右子树为空
+            let mut node = new_node(x);
+            node.left = link.take();
+            *link = Some(node);
+            Some(root)
         }
     }
 }
@@ -136,7 +272,7 @@ mod tests {
             .map(|(i, v)| (i, v))
             .collect::<Vec<_>>();
 
-        let root = build_cartesian_tree(&mut xs).root.unwrap();
+        let root = build_cartesian_tree(&mut xs).root.take().unwrap();
 
         assert_eq!(root.w, 1);
 
@@ -156,4 +292,92 @@ mod tests {
         let l5 = l4.right.unwrap();
         assert_eq!(l5.w, 18);
     }
+
+    /// 先序遍历, 用来比较两棵树的形状
+    fn pre_order<W: Copy>(tree: &CartesianTree<usize, W>) -> Vec<(usize, W)> {
+        let mut res = vec![];
+        let mut stack: Vec<_> = tree.root.as_deref().into_iter().collect();
+        while let Some(node) = stack.pop() {
+            res.push((node.k, node.w));
+            stack.extend(node.right.as_deref());
+            stack.extend(node.left.as_deref());
+        }
+        res
+    }
+
+    #[test]
+    fn test_cartesian_tree_from_slice() {
+        use rand::seq::SliceRandom;
+
+        let mut rng = rand::thread_rng();
+        for n in [0, 1, 2, 10, 200] {
+            let mut v: Vec<u32> = (0..n).collect();
+            v.shuffle(&mut rng);
+
+            let tree = from_slice(&v);
+            let mut xs: Vec<_> = v.iter().copied().enumerate().collect();
+            let expect = build_cartesian_tree(&mut xs);
+            assert_eq!(pre_order(&tree), pre_order(&expect));
+            assert_eq!(tree.in_order(), xs);
+        }
+
+        // 值相同时靠左的元素是祖先
+        let tree = from_slice(&[2, 1, 1, 1]);
+        assert_eq!(pre_order(&tree), [(1, 1), (0, 2), (2, 1), (3, 1)]);
+    }
+
+    #[test]
+    fn test_cartesian_rmq() {
+        use crate::sparse_table::ArgSparseTable;
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        for n in [0, 1, 2, 3, 17, 100] {
+            // 值域很小, 有很多相同的值
+            let v: Vec<u8> = (0..n).map(|_| rng.gen_range(0..5)).collect();
+            let rmq = CartesianRmq::new(&v);
+            let st = ArgSparseTable::argmin(&v);
+            assert_eq!(rmq.len(), n);
+            for l in 0..n {
+                for r in l..n {
+                    assert_eq!(rmq.query(l, r), st.query(l, r), "{v:?} [{l}, {r}]");
+                }
+            }
+            assert_eq!(rmq.query(0, n), None);
+        }
+
+        // 有序的输入会得到一条很长的链
+        let v: Vec<u32> = (0..10_000).collect();
+        let rmq = CartesianRmq::new(&v);
+        assert_eq!(rmq.query(1234, 9999), Some(1234));
+    }
+
+    #[test]
+    fn test_cartesian_tree_degenerate_drop() {
+        // 升序时每个节点都是前一个节点的右子节点, 降序时都是左子节点
+        let n = 1_000_000;
+        let v: Vec<u32> = (0..n).collect();
+        let rmq = CartesianRmq::new(&v);
+        assert_eq!(rmq.len(), n as usize);
+        assert_eq!(rmq.query(12345, 999_999), Some(12345));
+
+        let v: Vec<u32> = (0..n).rev().collect();
+        let tree = from_slice(&v);
+        assert_eq!(tree.root.as_ref().map(|r| r.k), Some(n as usize - 1));
+    }
+
+    #[test]
+    fn test_cartesian_tree_in_order() {
+        let mut xs = [(3, 'd'), (0, 'a'), (4, 'b'), (1, 'e'), (2, 'c')];
+        let tree = build_cartesian_tree(&mut xs);
+        assert_eq!(
+            tree.in_order(),
+            [(0, 'a'), (1, 'e'), (2, 'c'), (3, 'd'), (4, 'b')]
+        );
+        assert_eq!(tree.root.as_ref().map(|n| n.w), Some('a'));
+
+        assert!(build_cartesian_tree::<i32, i32>(&mut [])
+            .in_order()
+            .is_empty());
+    }
 }
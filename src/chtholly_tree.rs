@@ -0,0 +1,233 @@
+//! 珂朵莉树(颜色段均摊)
+//!
+//! - [OI Wiki - 珂朵莉树](https://oi-wiki.org/misc/odt/)
+//! - [CF896C Willem, Chtholly and Seniorious](https://codeforces.com/problemset/problem/896/C)
+//!
+//! 把数组中连续相同的值合并成一个区间 `[l, r) -> v`, 所有区间按照左端点保存在有序表中
+//!
+//! 核心操作是 split(pos): 把包含 pos 的区间 `[l, r)` 拆分成 `[l, pos)` 和 `[pos, r)` 两个区间
+//! 任何区间操作 `[l, r)` 都先 split(r) 再 split(l), 这样操作范围恰好由若干个完整的区间组成
+//!
+//! - 区间赋值: 删除范围内的所有区间, 插入一个新区间, 区间数量会迅速减少
+//! - 其他操作(区间加、第 k 小、幂次和): 暴力遍历范围内的所有区间
+//!
+//! 在数据随机并且区间赋值操作占比较大时, 区间数量会保持在很小的规模, 期望复杂度 O(n loglogn)
+//! 但如果没有区间赋值操作, 区间数量不会减少, 会退化成暴力
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+/// 珂朵莉树, 每个区间只记录左端点, 右端点是下一个区间的左端点
+pub struct ChthollyTree {
+    n: usize,
+    map: BTreeMap<usize, i64>,
+}
+
+impl ChthollyTree {
+    pub fn new(values: &[i64]) -> Self {
+        let mut map = BTreeMap::new();
+        for (i, &v) in values.iter().enumerate() {
+            // 和前一个值相同时合并到同一个区间
+            if i == 0 || values[i - 1] != v {
+                map.insert(i, v);
+            }
+        }
+
+        Self {
+            n: values.len(),
+            map,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// 当前区间数量
+    pub fn intervals(&self) -> usize {
+        self.map.len()
+    }
+
+    /// 保证 pos 是一个区间的左端点
+    fn split(&mut self, pos: usize) {
+        if pos >= self.n || self.map.contains_key(&pos) {
+            return;
+        }
+
+        let (_, &v) = self
+            .map
+            .range(..pos)
+            .next_back()
+            .expect("pos must be covered");
+        self.map.insert(pos, v);
+    }
+
+    /// 拆分出 range 范围内的所有区间, 返回每个区间的 `(长度, 值)`
+    fn segments(&mut self, range: &Range<usize>) -> Vec<(usize, i64)> {
+        assert!(range.start <= range.end && range.end <= self.n);
+        self.split(range.end);
+        self.split(range.start);
+
+        let mut res = vec![];
+        let mut iter = self.map.range(range.clone()).peekable();
+        while let Some((&l, &v)) = iter.next() {
+            let r = iter.peek().map_or(range.end, |(&r, _)| r);
+            res.push((r - l, v));
+        }
+
+        res
+    }
+
+    /// 区间赋值
+    pub fn assign(&mut self, range: Range<usize>, v: i64) {
+        if range.is_empty() {
+            return;
+        }
+
+        self.segments(&range);
+        let keys: Vec<_> = self.map.range(range.clone()).map(|(&k, _)| k).collect();
+        for k in keys {
+            self.map.remove(&k);
+        }
+        self.map.insert(range.start, v);
+    }
+
+    /// 区间加
+    pub fn add(&mut self, range: Range<usize>, delta: i64) {
+        self.segments(&range);
+        for (_, v) in self.map.range_mut(range) {
+            *v += delta;
+        }
+    }
+
+    /// 区间和
+    pub fn sum(&mut self, range: Range<usize>) -> i64 {
+        self.segments(&range)
+            .iter()
+            .map(|&(len, v)| len as i64 * v)
+            .sum()
+    }
+
+    /// 区间内第 k 小的值, k 从 0 开始
+    pub fn kth(&mut self, range: Range<usize>, mut k: usize) -> Option<i64> {
+        let mut segments = self.segments(&range);
+        segments.sort_by_key(|&(_, v)| v);
+        for (len, v) in segments {
+            if k < len {
+                return Some(v);
+            }
+            k -= len;
+        }
+
+        None
+    }
+
+    /// 区间内每个数的 x 次方之和模 m, 两个小于 m 的数相乘可能超过 u64, 中间结果使用 u128
+    pub fn pow_sum(&mut self, range: Range<usize>, x: u64, m: u64) -> u64 {
+        let m = m as u128;
+        let pow = |base: i64| {
+            let (mut base, mut exp, mut res) =
+                ((base as i128).rem_euclid(m as i128) as u128, x, 1 % m);
+            while exp > 0 {
+                if exp & 1 == 1 {
+                    res = res * base % m;
+                }
+                base = base * base % m;
+                exp >>= 1;
+            }
+            res
+        };
+
+        let sum = self
+            .segments(&range)
+            .iter()
+            .fold(0, |acc, &(len, v)| (acc + len as u128 % m * pow(v)) % m);
+        sum as u64
+    }
+
+    /// 展开成数组
+    pub fn to_vec(&self) -> Vec<i64> {
+        let mut res = Vec::with_capacity(self.n);
+        let mut iter = self.map.iter().peekable();
+        while let Some((&l, &v)) = iter.next() {
+            let r = iter.peek().map_or(self.n, |(&r, _)| r);
+            res.extend(std::iter::repeat_n(v, r - l));
+        }
+
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_chtholly_tree() {
+        let mut rng = rand::thread_rng();
+        let n = 300;
+        let mut values: Vec<i64> = (0..n).map(|_| rng.gen_range(0..10)).collect();
+        let mut tree = ChthollyTree::new(&values);
+
+        for _ in 0..3000 {
+            let l = rng.gen_range(0..n);
+            let r = rng.gen_range(l + 1..=n);
+            match rng.gen_range(0..5) {
+                0 => {
+                    let v = rng.gen_range(-50..50);
+                    tree.assign(l..r, v);
+                    values[l..r].iter_mut().for_each(|x| *x = v);
+                }
+                1 => {
+                    let d = rng.gen_range(-5..5);
+                    tree.add(l..r, d);
+                    values[l..r].iter_mut().for_each(|x| *x += d);
+                }
+                2 => assert_eq!(tree.sum(l..r), values[l..r].iter().sum::<i64>()),
+                3 => {
+                    let k = rng.gen_range(0..r - l);
+                    let mut sorted = values[l..r].to_vec();
+                    sorted.sort();
+                    assert_eq!(tree.kth(l..r, k), Some(sorted[k]));
+                }
+                _ => {
+                    let m = 1_000_000_007;
+                    let expect = values[l..r].iter().fold(0, |acc, &v| {
+                        let v = v.rem_euclid(m as i64) as u64;
+                        (acc + v * v % m * v) % m
+                    });
+                    assert_eq!(tree.pow_sum(l..r, 3, m), expect);
+                }
+            }
+        }
+
+        assert_eq!(tree.to_vec(), values);
+        // 大量区间赋值之后区间数量远小于 n
+        tree.assign(0..n, 1);
+        assert_eq!(tree.intervals(), 1);
+        assert_eq!(tree.kth(0..n, n), None);
+    }
+
+    #[test]
+    fn test_pow_sum_large_modulus() {
+        // 小于 2^64 的最大素数, 以及超过 i64::MAX 的模数
+        for m in [u64::MAX - 58, i64::MAX as u64 + 2] {
+            let values = [i64::MIN, -1, 3, i64::MAX, 1 << 40];
+            let mut tree = ChthollyTree::new(&values);
+            let expect = values.iter().fold(0u128, |acc, &v| {
+                let v = (v as i128).rem_euclid(m as i128) as u128;
+                (acc + v * v % m as u128 * v) % m as u128
+            });
+            assert_eq!(tree.pow_sum(0..values.len(), 3, m), expect as u64);
+
+            // -1 的偶数次方是 1
+            assert_eq!(tree.pow_sum(1..2, 1 << 40, m), 1);
+        }
+        assert_eq!(ChthollyTree::new(&[5]).pow_sum(0..1, 0, 1), 0);
+    }
+}
@@ -0,0 +1,308 @@
+//! 组合数学
+//!
+//! - [OI Wiki - 排列组合](https://oi-wiki.org/math/combinatorics/combination/)
+//! - [OI Wiki - 卢卡斯定理](https://oi-wiki.org/math/number-theory/lucas/)
+//! - [OI Wiki - 卡特兰数](https://oi-wiki.org/math/combinatorics/catalan/)
+//! - [OI Wiki - 斯特林数](https://oi-wiki.org/math/combinatorics/stirling/)
+//!
+//! 模质数 p 意义下 `C(n, k) = n! / (k!·(n-k)!)`, 预处理阶乘和阶乘的逆元之后每次查询 O(1):
+//! 先用 [`ModInt::inv`] 求出 `n!` 的逆元, 再利用 `1/(i-1)! = i · 1/i!` 从大到小递推, 总共只需要一次求逆
+//!
+//! 这要求 n < p, 否则 n! 中含有因子 p 没有逆元; n 很大而 p 较小时使用卢卡斯定理:
+//!
+//! ```text
+//! C(n, k) ≡ C(n mod p, k mod p) · C(⌊n/p⌋, ⌊k/p⌋) (mod p)
+//! ```
+//!
+//! 即把 n 和 k 写成 p 进制后, 每一位分别求组合数再相乘, 每一位都小于 p 可以查表
+//!
+//! 卡特兰数 `Cat(n) = C(2n, n) - C(2n, n+1) = C(2n, n) / (n+1)`,
+//! 是 n 对括号的合法序列数, 也是 n 个节点的二叉树的形态数
+//!
+//! 斯特林数:
+//!
+//! - 第二类 `S(n, k)`: 把 n 个不同的元素划分为 k 个非空集合的方案数,
+//!   第 n 个元素单独成为一个集合或者放进已有的 k 个集合之一, `S(n, k) = S(n-1, k-1) + k·S(n-1, k)`
+//! - 第一类(无符号) `s(n, k)`: 把 n 个不同的元素排成 k 个非空圆排列的方案数,
+//!   第 n 个元素单独成为一个环或者插到前面 n-1 个元素中任意一个的后面, `s(n, k) = s(n-1, k-1) + (n-1)·s(n-1, k)`
+
+use crate::mod_int::ModInt;
+
+/// 阶乘和阶乘逆元表, M 必须是质数
+///
+/// ```
+/// use impx::combinatorics::Factorials;
+///
+/// let f = Factorials::<1_000_000_007>::new(100);
+/// assert_eq!(f.binomial(5, 2).value(), 10);
+/// assert_eq!(f.permutation(5, 2).value(), 20);
+/// assert_eq!(f.catalan(5).value(), 42);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Factorials<const M: u64> {
+    fact: Vec<ModInt<M>>,
+    inv_fact: Vec<ModInt<M>>,
+}
+
+impl<const M: u64> Factorials<M> {
+    /// 预处理 `0..=n` 的阶乘, 要求 n < M
+    pub fn new(n: usize) -> Self {
+        assert!((n as u64) < M, "n must be less than the modulus");
+
+        let mut fact = Vec::with_capacity(n + 1);
+        fact.push(ModInt::new(1));
+        for i in 1..=n {
+            fact.push(fact[i - 1] * ModInt::from(i));
+        }
+
+        let mut inv_fact = vec![ModInt::new(1); n + 1];
+        inv_fact[n] = fact[n].inv().expect("modulus must be a prime");
+        for i in (1..=n).rev() {
+            inv_fact[i - 1] = inv_fact[i] * ModInt::from(i);
+        }
+
+        Self { fact, inv_fact }
+    }
+
+    /// 预处理的上限 n
+    pub fn limit(&self) -> usize {
+        self.fact.len() - 1
+    }
+
+    /// n!
+    pub fn fact(&self, n: usize) -> ModInt<M> {
+        self.fact[n]
+    }
+
+    /// 1/n!
+    pub fn inv_fact(&self, n: usize) -> ModInt<M> {
+        self.inv_fact[n]
+    }
+
+    /// 1/n, n 不能为 0
+    pub fn inv(&self, n: usize) -> ModInt<M> {
+        assert!(n > 0, "0 has no inverse");
+        self.inv_fact[n] * self.fact[n - 1]
+    }
+
+    /// 组合数 C(n, k), k > n 时为 0
+    pub fn binomial(&self, n: usize, k: usize) -> ModInt<M> {
+        if k > n {
+            return ModInt::default();
+        }
+        self.fact[n] * self.inv_fact[k] * self.inv_fact[n - k]
+    }
+
+    /// 排列数 P(n, k) = n! / (n-k)!, k > n 时为 0
+    pub fn permutation(&self, n: usize, k: usize) -> ModInt<M> {
+        if k > n {
+            return ModInt::default();
+        }
+        self.fact[n] * self.inv_fact[n - k]
+    }
+
+    /// 多项式系数 `(Σk)! / ∏(k!)`, 把 Σk 个元素分成大小分别为 k 的若干组的方案数
+    pub fn multinomial(&self, ks: &[usize]) -> ModInt<M> {
+        let n: usize = ks.iter().sum();
+        ks.iter()
+            .fold(self.fact[n], |acc, &k| acc * self.inv_fact[k])
+    }
+
+    /// 卡特兰数 Cat(n), 需要预处理到 2n
+    pub fn catalan(&self, n: usize) -> ModInt<M> {
+        self.binomial(2 * n, n) - self.binomial(2 * n, n + 1)
+    }
+
+    /// 卢卡斯定理求 C(n, k) mod M, n 和 k 可以大于 M, 需要预处理到 M-1
+    ///
+    /// ```
+    /// use impx::combinatorics::Factorials;
+    ///
+    /// let f = Factorials::<7>::new(6);
+    /// // C(10, 3) = 120 ≡ 1 (mod 7)
+    /// assert_eq!(f.lucas(10, 3).value(), 1);
+    /// ```
+    pub fn lucas(&self, mut n: u64, mut k: u64) -> ModInt<M> {
+        assert!(
+            self.limit() as u64 >= M - 1,
+            "lucas requires factorials up to M-1"
+        );
+
+        let mut res = ModInt::new(1);
+        while k > 0 {
+            // 某一位上 k 比 n 大时这一位的组合数为 0, 整个结果为 0
+            res *= self.binomial((n % M) as usize, (k % M) as usize);
+            n /= M;
+            k /= M;
+        }
+        res
+    }
+
+    /// 第二类斯特林数 S(n, k), 使用容斥原理 `S(n, k) = 1/k! · Σ (-1)^j · C(k, j) · (k-j)^n`, O(k logn)
+    ///
+    /// 容斥的含义是: 把 n 个元素放进 k 个有编号的盒子, 减去至少有 j 个空盒子的方案, 最后除以盒子的排列数 k!
+    pub fn stirling2(&self, n: u64, k: usize) -> ModInt<M> {
+        let sum: ModInt<M> = (0..=k)
+            .map(|j| {
+                let term = self.binomial(k, j) * ModInt::from(k - j).pow(n);
+                if j % 2 == 0 {
+                    term
+                } else {
+                    -term
+                }
+            })
+            .sum();
+        sum * self.inv_fact[k]
+    }
+}
+
+/// 第二类斯特林数表, 第 n 行第 k 列为 S(n, k), O(n²)
+///
+/// ```
+/// use impx::combinatorics::stirling_second;
+///
+/// let s = stirling_second::<1_000_000_007>(4);
+/// assert_eq!(s[4][2].value(), 7);
+/// ```
+pub fn stirling_second<const M: u64>(n: usize) -> Vec<Vec<ModInt<M>>> {
+    let mut s = vec![vec![ModInt::default(); n + 1]; n + 1];
+    s[0][0] = ModInt::new(1);
+    for i in 1..=n {
+        for k in 1..=i {
+            s[i][k] = s[i - 1][k - 1] + ModInt::from(k) * s[i - 1][k];
+        }
+    }
+    s
+}
+
+/// 无符号第一类斯特林数表, 第 n 行第 k 列为 s(n, k), O(n²)
+///
+/// ```
+/// use impx::combinatorics::stirling_first;
+///
+/// let s = stirling_first::<1_000_000_007>(4);
+/// assert_eq!(s[4][2].value(), 11);
+/// ```
+pub fn stirling_first<const M: u64>(n: usize) -> Vec<Vec<ModInt<M>>> {
+    let mut s = vec![vec![ModInt::default(); n + 1]; n + 1];
+    s[0][0] = ModInt::new(1);
+    for i in 1..=n {
+        for k in 1..=i {
+            s[i][k] = s[i - 1][k - 1] + ModInt::from(i - 1) * s[i - 1][k];
+        }
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const P: u64 = 1_000_000_007;
+
+    /// 杨辉三角 C(n, k) 的精确值
+    fn pascal(n: usize) -> Vec<Vec<u128>> {
+        let mut c = vec![vec![0u128; n + 1]; n + 1];
+        for i in 0..=n {
+            c[i][0] = 1;
+            for k in 1..=i {
+                c[i][k] = c[i - 1][k - 1] + c[i - 1][k];
+            }
+        }
+        c
+    }
+
+    #[test]
+    fn test_binomial() {
+        let n = 100;
+        let f = Factorials::<P>::new(2 * n);
+        let c = pascal(n);
+        for (i, row) in c.iter().enumerate() {
+            for (k, &v) in row.iter().enumerate() {
+                let expect = if k <= i { v % P as u128 } else { 0 };
+                assert_eq!(f.binomial(i, k).value() as u128, expect);
+            }
+            assert_eq!(f.binomial(i, n + 1).value(), 0);
+            if i > 0 {
+                assert_eq!(f.inv(i) * ModInt::from(i), ModInt::new(1));
+            }
+        }
+
+        assert_eq!(f.permutation(10, 3).value(), 720);
+        assert_eq!(f.permutation(3, 4).value(), 0);
+        assert_eq!(f.fact(20).value(), (2432902008176640000u64 % P));
+        assert_eq!(f.fact(50) * f.inv_fact(50), ModInt::new(1));
+        assert_eq!(f.multinomial(&[2, 1, 1]).value(), 12);
+        assert_eq!(f.multinomial(&[]).value(), 1);
+
+        let catalan = [1, 1, 2, 5, 14, 42, 132, 429, 1430, 4862];
+        for (i, &v) in catalan.iter().enumerate() {
+            assert_eq!(f.catalan(i).value(), v);
+        }
+        // Cat(n) = C(2n, n) / (n+1)
+        assert_eq!(f.catalan(n), f.binomial(2 * n, n) * f.inv(n + 1));
+    }
+
+    #[test]
+    fn test_lucas() {
+        fn check<const M: u64>() {
+            let f = Factorials::<M>::new(M as usize - 1);
+            let c = pascal(120);
+            for (n, row) in c.iter().enumerate() {
+                for (k, &v) in row.iter().enumerate() {
+                    assert_eq!(f.lucas(n as u64, k as u64).value() as u128, v % M as u128);
+                }
+            }
+        }
+
+        check::<2>();
+        check::<3>();
+        check::<7>();
+        check::<13>();
+
+        // C(10^18, 10^9) 模 13, 两边的 13 进制各位比较
+        let f = Factorials::<13>::new(12);
+        let (n, k) = (1_000_000_000_000_000_000u64, 1_000_000_000u64);
+        let (mut x, mut y, mut expect) = (n, k, 1u64);
+        while y > 0 {
+            let (a, b) = ((x % 13) as usize, (y % 13) as usize);
+            expect = expect * pascal(12)[a][b] as u64 % 13;
+            x /= 13;
+            y /= 13;
+        }
+        assert_eq!(f.lucas(n, k).value(), expect);
+    }
+
+    #[test]
+    fn test_stirling() {
+        let n = 30;
+        let f = Factorials::<P>::new(n);
+        let s2 = stirling_second::<P>(n);
+        let s1 = stirling_first::<P>(n);
+        let c = pascal(n);
+
+        // 贝尔数 B(n) = Σ S(n, k), 满足 B(n+1) = Σ C(n, k)·B(k)
+        let bell: Vec<ModInt<P>> = s2.iter().map(|row| row.iter().copied().sum()).collect();
+        for i in 0..n {
+            let rhs: ModInt<P> = (0..=i)
+                .map(|k| ModInt::new((c[i][k] % P as u128) as u64) * bell[k])
+                .sum();
+            assert_eq!(bell[i + 1], rhs);
+        }
+
+        for (i, row) in s1.iter().enumerate() {
+            // 所有圆排列的方案数加起来就是全排列数
+            assert_eq!(row.iter().copied().sum::<ModInt<P>>(), f.fact(i));
+        }
+        for (i, row) in s2.iter().enumerate() {
+            for (k, &v) in row.iter().enumerate() {
+                assert_eq!(f.stirling2(i as u64, k), v);
+            }
+        }
+
+        assert_eq!(s2[10][3].value(), 9330);
+        assert_eq!(s1[10][3].value(), 1172700);
+        assert_eq!(f.stirling2(0, 0).value(), 1);
+        assert_eq!(f.stirling2(5, 0).value(), 0);
+    }
+}
@@ -0,0 +1,292 @@
+//! 并发跳跃表
+//!
+//! - [Java ConcurrentSkipListMap](https://docs.oracle.com/javase/8/docs/api/java/util/concurrent/ConcurrentSkipListMap.html)
+//! - [The Art of Multiprocessor Programming - Lock Striping](https://www.oreilly.com/library/view/the-art-of/9780123705914/)
+//!
+//! 在 [`crate::skip_list`] 的基础上比较两种让跳表可以被多个线程同时访问的做法:
+//!
+//! - [`RwLockSkipList`]: 粗粒度锁, 整个跳表由一把读写锁保护
+//!   读操作之间可以并发, 但任意一个写操作都会阻塞其他所有操作
+//! - [`StripedSkipList`]: 锁分段, 按 key 的哈希值把数据分散到多个独立加锁的跳表上
+//!   不同分段上的写操作可以并发执行, 锁竞争随分段数量下降
+//!   代价是失去了全局有序性, 需要有序遍历时要把所有分段的数据合并排序
+//!
+//! 真正的无锁跳表(比如 Java 中的实现)需要借助 CAS 逐层链接节点, 并且依赖基于纪元(epoch)
+//! 或者危险指针(hazard pointer)的内存回收机制, 这里只实现两种基于锁的版本
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+use std::sync::RwLock;
+
+use crate::skip_list::SkipList;
+
+/// 每个跳表的最大层数
+const LEVEL: usize = 16;
+
+/// 默认的分段数量
+const DEFAULT_SHARDS: usize = 16;
+
+/// 由一把读写锁保护的跳表
+pub struct RwLockSkipList<K, V> {
+    inner: RwLock<SkipList<LEVEL, K, V>>,
+}
+
+impl<K: Ord, V> RwLockSkipList<K, V> {
+    pub fn new() -> Self {
+        Self {
+            inner: RwLock::new(SkipList::new()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.read().unwrap().is_empty()
+    }
+
+    /// 插入 key-value 如果 key 已经存在则返回旧值
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        self.inner.write().unwrap().insert(key, value)
+    }
+
+    /// 返回 key 对应值的拷贝, 锁释放后无法再持有内部引用
+    pub fn get(&self, key: &K) -> Option<V>
+    where
+        V: Clone,
+    {
+        self.inner.read().unwrap().find(key).cloned()
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.inner.read().unwrap().find(key).is_some()
+    }
+
+    /// 删除 key 并返回对应的值
+    pub fn remove(&self, key: &K) -> Option<V> {
+        self.inner.write().unwrap().delete(key)
+    }
+
+    /// 按 key 有序复制出所有数据
+    pub fn to_vec(&self) -> Vec<(K, V)>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        let inner = self.inner.read().unwrap();
+        inner.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+    }
+}
+
+impl<K: Ord, V> Default for RwLockSkipList<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 锁分段跳表
+pub struct StripedSkipList<K, V, S = RandomState> {
+    shards: Vec<RwLock<SkipList<LEVEL, K, V>>>,
+    hasher: S,
+}
+
+impl<K: Ord + Hash, V> StripedSkipList<K, V> {
+    pub fn new() -> Self {
+        Self::with_shards(DEFAULT_SHARDS)
+    }
+
+    /// 创建包含 n 个分段的跳表
+    pub fn with_shards(n: usize) -> Self {
+        Self::with_shards_and_hasher(n, RandomState::new())
+    }
+}
+
+impl<K: Ord + Hash, V, S: BuildHasher> StripedSkipList<K, V, S> {
+    /// 使用指定的哈希函数创建包含 n 个分段的跳表
+    pub fn with_shards_and_hasher(n: usize, hasher: S) -> Self {
+        assert!(n > 0, "shards must be positive");
+        let shards = (0..n).map(|_| RwLock::new(SkipList::new())).collect();
+        Self { shards, hasher }
+    }
+
+    /// key 所在的分段
+    fn shard(&self, key: &K) -> &RwLock<SkipList<LEVEL, K, V>> {
+        let hash = self.hasher.hash_one(key);
+        &self.shards[hash as usize % self.shards.len()]
+    }
+
+    /// 总数需要依次读取每个分段, 并发修改时只是一个近似值
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|s| s.read().unwrap().len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shards.iter().all(|s| s.read().unwrap().is_empty())
+    }
+
+    /// 插入 key-value 如果 key 已经存在则返回旧值
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        self.shard(&key).write().unwrap().insert(key, value)
+    }
+
+    /// 返回 key 对应值的拷贝
+    pub fn get(&self, key: &K) -> Option<V>
+    where
+        V: Clone,
+    {
+        self.shard(key).read().unwrap().find(key).cloned()
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.shard(key).read().unwrap().find(key).is_some()
+    }
+
+    /// 删除 key 并返回对应的值
+    pub fn remove(&self, key: &K) -> Option<V> {
+        self.shard(key).write().unwrap().delete(key)
+    }
+
+    /// 复制出所有分段的数据并按 key 排序
+    pub fn to_vec(&self) -> Vec<(K, V)>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        let mut res = vec![];
+        for shard in &self.shards {
+            let shard = shard.read().unwrap();
+            res.extend(shard.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+
+        res.sort_by(|a, b| a.0.cmp(&b.0));
+        res
+    }
+}
+
+impl<K: Ord + Hash, V> Default for StripedSkipList<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    const THREADS: usize = 8;
+    const PER_THREAD: usize = 2000;
+
+    /// 每个线程写入互不相交的 key, 同时读取其他线程的 key
+    macro_rules! stress_disjoint {
+        ($list:expr) => {{
+            let list = $list;
+            thread::scope(|s| {
+                for t in 0..THREADS {
+                    let list = &list;
+                    s.spawn(move || {
+                        for i in 0..PER_THREAD {
+                            let key = i * THREADS + t;
+                            assert!(list.insert(key, key * 2).is_none());
+                            assert_eq!(list.get(&key), Some(key * 2));
+
+                            // 并发读取其他线程的 key, 结果可能存在也可能不存在但不会是错误值
+                            let other = i * THREADS + (t + 1) % THREADS;
+                            if let Some(v) = list.get(&other) {
+                                assert_eq!(v, other * 2);
+                            }
+                        }
+                    });
+                }
+            });
+
+            assert_eq!(list.len(), THREADS * PER_THREAD);
+            let all = list.to_vec();
+            assert!(all.iter().enumerate().all(|(i, &(k, v))| i == k && v == k * 2));
+
+            // 并发删除奇数 key
+            thread::scope(|s| {
+                for t in 0..THREADS {
+                    let list = &list;
+                    s.spawn(move || {
+                        for i in 0..PER_THREAD {
+                            let key = i * THREADS + t;
+                            if key % 2 == 1 {
+                                assert_eq!(list.remove(&key), Some(key * 2));
+                            }
+                        }
+                    });
+                }
+            });
+
+            assert_eq!(list.len(), THREADS * PER_THREAD / 2);
+            assert!(list.to_vec().iter().all(|&(k, _)| k % 2 == 0));
+        }};
+    }
+
+    /// 所有线程竞争同一小段 key, 最终每个 key 只能被成功删除一次
+    macro_rules! stress_contended {
+        ($list:expr) => {{
+            use std::sync::atomic::{AtomicUsize, Ordering};
+
+            let list = $list;
+            let inserted = AtomicUsize::new(0);
+            let removed = AtomicUsize::new(0);
+
+            thread::scope(|s| {
+                for t in 0..THREADS {
+                    let (list, inserted, removed) = (&list, &inserted, &removed);
+                    s.spawn(move || {
+                        for i in 0..PER_THREAD {
+                            let key = (i * 7 + t) % 64;
+                            if (i + t) % 2 == 0 {
+                                if list.insert(key, t).is_none() {
+                                    inserted.fetch_add(1, Ordering::Relaxed);
+                                }
+                            } else if list.remove(&key).is_some() {
+                                removed.fetch_add(1, Ordering::Relaxed);
+                            }
+                        }
+                    });
+                }
+            });
+
+            let inserted = inserted.load(Ordering::Relaxed);
+            let removed = removed.load(Ordering::Relaxed);
+            assert_eq!(list.len(), inserted - removed);
+        }};
+    }
+
+    #[test]
+    fn test_rwlock_skip_list() {
+        let list = RwLockSkipList::new();
+        assert!(list.is_empty());
+        assert_eq!(list.insert(1, "a"), None);
+        assert_eq!(list.insert(1, "b"), Some("a"));
+        assert_eq!(list.get(&1), Some("b"));
+        assert!(list.contains_key(&1));
+        assert_eq!(list.remove(&1), Some("b"));
+        assert!(list.is_empty());
+
+        stress_disjoint!(RwLockSkipList::new());
+        stress_contended!(RwLockSkipList::new());
+    }
+
+    #[test]
+    fn test_striped_skip_list() {
+        let list = StripedSkipList::with_shards(4);
+        assert!(list.is_empty());
+        for i in (0..100).rev() {
+            list.insert(i, i);
+        }
+
+        assert_eq!(list.len(), 100);
+        assert_eq!(list.to_vec(), (0..100).map(|i| (i, i)).collect::<Vec<_>>());
+        assert_eq!(list.remove(&50), Some(50));
+        assert!(!list.contains_key(&50));
+
+        stress_disjoint!(StripedSkipList::new());
+        stress_contended!(StripedSkipList::with_shards(3));
+    }
+}
@@ -1,10 +1,14 @@
 //! 一致哈希
 //!
 //! 参考: <https://github.com/buraksezer/consistent>
+//!
+//! 默认使用 [`DefaultHasher`] 并且不带随机种子, 所以同样的操作序列总是得到同样的分区结果  
+//! 也可以通过 [`ConsistentHashMap::with_hasher`] 传入其他 [`BuildHasher`], 例如更快的 FxHash
+//! 或者带固定种子的哈希函数
 
 use std::{
     collections::hash_map::DefaultHasher,
-    hash::{Hash, Hasher},
+    hash::{BuildHasher, BuildHasherDefault, Hash, Hasher},
 };
 
 /// 虚拟节点
@@ -14,18 +18,16 @@ struct VNode<T> {
     load: usize, // 负载
 }
 
-pub struct ConsistentHashMap<T> {
+/// 默认的哈希函数, 不带随机种子
+pub type DefaultState = BuildHasherDefault<DefaultHasher>;
+
+pub struct ConsistentHashMap<T, S = DefaultState> {
     partition_count: usize,           // 分区数量
     virtual_replication_count: usize, // 每个节点的虚拟节点副本数量
 
     vnodes: Vec<VNode<T>>, // 虚拟节点列表
     partitions: Vec<T>,    // 分区列表
-}
-
-fn hasher<T: Hash>(v: &T) -> u64 {
-    let mut state = DefaultHasher::new();
-    v.hash(&mut state);
-    state.finish()
+    hasher: S,             // 哈希函数
 }
 
 impl<T> ConsistentHashMap<T>
@@ -33,14 +35,38 @@ where
     T: Copy + Clone + Ord + Hash,
 {
     pub fn new(partition_count: usize, virtual_replication_count: usize) -> Self {
+        Self::with_hasher(
+            partition_count,
+            virtual_replication_count,
+            DefaultState::default(),
+        )
+    }
+}
+
+impl<T, S> ConsistentHashMap<T, S>
+where
+    T: Copy + Clone + Ord + Hash,
+    S: BuildHasher,
+{
+    pub fn with_hasher(
+        partition_count: usize,
+        virtual_replication_count: usize,
+        hasher: S,
+    ) -> Self {
         Self {
             partition_count,
             virtual_replication_count,
             vnodes: vec![],
             partitions: vec![],
+            hasher,
         }
     }
 
+    /// 每个分区对应的真实节点, 没有节点时为空
+    pub fn partitions(&self) -> &[T] {
+        &self.partitions
+    }
+
     fn balance_load(&mut self) {
         if self.vnodes.is_empty() {
             return;
@@ -55,7 +81,7 @@ where
 
         self.partitions.clear();
         for partiton_id in 0..self.partition_count {
-            let hash = hasher(&partiton_id.to_string());
+            let hash = self.hasher.hash_one(partiton_id.to_string());
 
             // 根据分区 ID 的哈希值找到对应的虚拟节点
             let mut index = match self
@@ -92,7 +118,7 @@ where
     pub fn add(&mut self, node: T) {
         // 创建虚拟节点
         for i in 0..self.virtual_replication_count {
-            let mut state = DefaultHasher::new();
+            let mut state = self.hasher.build_hasher();
             i.to_string().hash(&mut state);
             node.hash(&mut state);
             let hash = state.finish();
@@ -120,9 +146,7 @@ where
     }
 
     pub fn locate<K: Hash>(&self, key: &K) -> T {
-        let mut state = DefaultHasher::new();
-        key.hash(&mut state);
-        let hash = state.finish() as usize;
+        let hash = self.hasher.hash_one(key) as usize;
 
         // 定位到对应的分区
         let index = hash % self.partition_count;
@@ -130,6 +154,46 @@ where
     }
 }
 
+/// 节点变化前后的迁移统计
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RelocationStats {
+    pub total: usize, // 比较的分区或者 key 的数量
+    pub moved: usize, // 所在节点发生变化的数量
+}
+
+impl RelocationStats {
+    /// 发生迁移的比例
+    pub fn ratio(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.moved as f64 / self.total as f64
+        }
+    }
+}
+
+/// 比较两次分配结果, 可以是 [`ConsistentHashMap::partitions`] 也可以是一组 key 各自 locate 的结果
+///
+/// ```
+/// use impx::consistent_hashmap::{relocation_stats, ConsistentHashMap};
+///
+/// let mut h = ConsistentHashMap::new(71, 20);
+/// (0..4).for_each(|i| h.add(i));
+/// let before = h.partitions().to_vec();
+/// h.add(4);
+///
+/// let stats = relocation_stats(&before, h.partitions());
+/// assert_eq!(stats.total, 71);
+/// assert!(stats.ratio() < 0.5);
+/// ```
+pub fn relocation_stats<T: Eq>(before: &[T], after: &[T]) -> RelocationStats {
+    assert_eq!(before.len(), after.len(), "assignments length mismatch");
+    RelocationStats {
+        total: before.len(),
+        moved: before.iter().zip(after).filter(|(a, b)| a != b).count(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -147,7 +211,7 @@ mod tests {
         }
     }
 
-    fn test_add_relocated(partition_count: usize, virtual_replica: usize) {
+    fn add_relocated(partition_count: usize, virtual_replica: usize) -> RelocationStats {
         const TEST_NODE_COUNT: usize = 8;
         const TEST_KEY_COUNT: usize = 102400;
 
@@ -156,60 +220,93 @@ mod tests {
             h.add(i);
         }
 
-        let mut vs = vec![];
-        for i in 0..TEST_KEY_COUNT {
-            let node = h.locate(&i);
-            vs.push(node);
+        let before: Vec<_> = (0..TEST_KEY_COUNT).map(|i| h.locate(&i)).collect();
+        h.add(TEST_NODE_COUNT);
+        let after: Vec<_> = (0..TEST_KEY_COUNT).map(|i| h.locate(&i)).collect();
+
+        relocation_stats(&before, &after)
+    }
+
+    #[test]
+    fn test_consistent_hashmap() {
+        // 默认哈希函数没有随机种子, 结果是确定的
+        // 加入第 9 个节点后理想情况下迁移 1/9 ≈ 11.1%, 有界负载会额外迁移一部分
+        for (partition_count, virtual_replica) in [
+            (80, 20),
+            (80, 30),
+            (80, 50),
+            (80, 70),
+            (360, 40),
+            (360, 100),
+            (360, 180),
+            (360, 300),
+        ] {
+            let stats = add_relocated(partition_count, virtual_replica);
+            assert_eq!(stats, add_relocated(partition_count, virtual_replica));
+            // 新节点至少要分到接近平均的一份, 同时绝大部分 key 保持不动
+            let ratio = stats.ratio();
+            assert!(
+                (0.1..0.3).contains(&ratio),
+                "{partition_count}x{virtual_replica}: {ratio}"
+            );
         }
+    }
 
-        h.add(TEST_NODE_COUNT);
-        let mut chagned = 0;
-        for i in 0..TEST_KEY_COUNT {
-            let node = h.locate(&i);
-            if vs[i] != node {
-                chagned += 1;
+    /// FNV-1a, 作为注入的自定义哈希函数
+    struct Fnv(u64);
+
+    impl Hasher for Fnv {
+        fn finish(&self) -> u64 {
+            self.0
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            for &b in bytes {
+                self.0 = (self.0 ^ b as u64).wrapping_mul(0x100000001b3);
             }
         }
+    }
 
-        println!(
-            "{}x{}:\t{:.1}%\trelocated",
-            partition_count,
-            virtual_replica,
-            (chagned as f64 / TEST_KEY_COUNT as f64) * 100.0
-        );
+    /// 带种子的 FNV-1a
+    #[derive(Clone)]
+    struct FnvState(u64);
+
+    impl BuildHasher for FnvState {
+        type Hasher = Fnv;
+
+        fn build_hasher(&self) -> Fnv {
+            Fnv(0xcbf29ce484222325 ^ self.0)
+        }
     }
 
     #[test]
-    fn test_consistent_hashmap() {
-        test_add_relocated(80, 20);
-        test_add_relocated(80, 25);
-        test_add_relocated(80, 30);
-        test_add_relocated(80, 40);
-        test_add_relocated(80, 50);
-        test_add_relocated(80, 60);
-        test_add_relocated(80, 70);
-
-        test_add_relocated(360, 40);
-        test_add_relocated(360, 60);
-        test_add_relocated(360, 80);
-        test_add_relocated(360, 100);
-        test_add_relocated(360, 120);
-        test_add_relocated(360, 180);
-        test_add_relocated(360, 300);
-
-        // 80x20:   13.7%   relocated
-        // 80x25:   18.6%   relocated
-        // 80x30:   17.4%   relocated
-        // 80x40:   15.0%   relocated
-        // 80x50:   12.5%   relocated
-        // 80x60:   16.3%   relocated
-        // 80x70:   13.8%   relocated
-        // 360x40:  24.4%   relocated
-        // 360x60:  31.1%   relocated
-        // 360x80:  25.6%   relocated
-        // 360x100: 22.8%   relocated
-        // 360x120: 18.6%   relocated
-        // 360x180: 12.5%   relocated
-        // 360x300: 12.0%   relocated
+    fn test_consistent_hashmap_with_hasher() {
+        let build = |seed| {
+            let mut h = ConsistentHashMap::with_hasher(271, 40, FnvState(seed));
+            (0..10).for_each(|i| h.add(i));
+            h
+        };
+
+        // 同样的种子结果相同, 不同的种子一般得到不同的分区
+        let (a, b, c) = (build(1), build(1), build(2));
+        assert_eq!(a.partitions(), b.partitions());
+        assert_ne!(a.partitions(), c.partitions());
+        assert!((0..1000).all(|k| a.locate(&k) == b.locate(&k)));
+
+        // 每个节点都分到了分区, 并且不超过有界负载的上限
+        let mut load = [0; 10];
+        a.partitions().iter().for_each(|&n| load[n] += 1);
+        let vnodes = 10 * 40;
+        let max_load = 271 / vnodes + 1;
+        assert!(load.iter().all(|&l| l > 0 && l <= max_load * 40));
+
+        // 删除一个节点只会迁移原来属于它的分区以及有界负载导致的少量分区
+        let mut d = build(1);
+        d.remove(3);
+        let stats = relocation_stats(a.partitions(), d.partitions());
+        let owned = a.partitions().iter().filter(|&&n| n == 3).count();
+        assert!(stats.moved >= owned);
+        assert!(d.partitions().iter().all(|&n| n != 3));
+        assert_eq!(relocation_stats::<usize>(&[], &[]).ratio(), 0.0);
     }
 }
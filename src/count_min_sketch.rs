@@ -0,0 +1,169 @@
+//! Count-Min Sketch
+//!
+//! - [wikipedia - Count–min sketch](https://en.wikipedia.org/wiki/Count%E2%80%93min_sketch)
+//! - [An Improved Data Stream Summary: The Count-Min Sketch and its Applications](http://dimacs.rutgers.edu/~graham/pubs/papers/cm-full.pdf)
+//!
+//! 和布隆过滤器类似, 用 d 个哈希函数把 key 映射到一个 d 行 w 列的计数器矩阵中, 每行一个位置
+//!
+//! - 增加计数: 把 key 在每一行对应的计数器都加上 count
+//! - 估计频率: 取 key 在每一行对应计数器的最小值
+//!
+//! 因为哈希冲突只会让计数器变大, 所以估计值不会小于真实值
+//! 取 w = ⌈e / ε⌉, d = ⌈ln(1 / δ)⌉ 时, 估计值超过 真实值 + ε * N 的概率不超过 δ, 其中 N 是所有计数之和
+//!
+//! 保守更新(conservative update): 增加计数时先求出当前的估计值 m, 每个计数器只更新为 max(counter, m + count)
+//! 这样可以明显减少高估, 但不再支持减少计数, 也不能直接合并
+
+use std::hash::Hash;
+
+use crate::cuckoo_filter::hash;
+
+pub struct CountMinSketch {
+    width: usize,
+    depth: usize,
+    conservative: bool,
+    total: u64,
+    counters: Vec<u64>,
+}
+
+impl CountMinSketch {
+    /// 创建 depth 行 width 列的计数器矩阵
+    pub fn new(width: usize, depth: usize) -> Self {
+        assert!(width > 0 && depth > 0, "width and depth must be positive");
+        Self {
+            width,
+            depth,
+            conservative: false,
+            total: 0,
+            counters: vec![0; width * depth],
+        }
+    }
+
+    /// 根据误差 epsilon 和失败概率 delta 计算矩阵大小
+    pub fn with_error(epsilon: f64, delta: f64) -> Self {
+        assert!(epsilon > 0.0 && delta > 0.0 && delta < 1.0);
+        let width = (std::f64::consts::E / epsilon).ceil() as usize;
+        let depth = (1.0 / delta).ln().ceil().max(1.0) as usize;
+        Self::new(width, depth)
+    }
+
+    /// 开启或关闭保守更新
+    pub fn conservative(mut self, enable: bool) -> Self {
+        self.conservative = enable;
+        self
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// 所有计数之和
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// key 在每一行对应的计数器下标
+    fn indexes<T: Hash + ?Sized>(&self, key: &T) -> impl Iterator<Item = usize> + '_ {
+        let hashes: Vec<_> = (0..self.depth).map(|row| hash(key, row as u64)).collect();
+        hashes
+            .into_iter()
+            .enumerate()
+            .map(move |(row, h)| row * self.width + (h % self.width as u64) as usize)
+    }
+
+    /// 增加 key 的计数
+    pub fn add<T: Hash + ?Sized>(&mut self, key: &T, count: u64) {
+        self.total += count;
+        let indexes: Vec<_> = self.indexes(key).collect();
+
+        if self.conservative {
+            let min = indexes.iter().map(|&i| self.counters[i]).min().unwrap_or(0);
+            for i in indexes {
+                self.counters[i] = self.counters[i].max(min + count);
+            }
+        } else {
+            for i in indexes {
+                self.counters[i] += count;
+            }
+        }
+    }
+
+    /// 估计 key 的计数, 估计值不会小于真实值
+    pub fn estimate<T: Hash + ?Sized>(&self, key: &T) -> u64 {
+        self.indexes(key)
+            .map(|i| self.counters[i])
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// 合并另一个相同大小的 sketch, 相当于把两个数据流的计数相加
+    ///
+    /// 保守更新的 sketch 直接相加后依然不会低估, 但是失去了保守更新带来的精度优势
+    pub fn merge(&mut self, other: &CountMinSketch) {
+        assert_eq!(
+            (self.width, self.depth),
+            (other.width, other.depth),
+            "sketch size mismatch"
+        );
+
+        self.total += other.total;
+        for (a, b) in self.counters.iter_mut().zip(&other.counters) {
+            *a += b;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arc_cache::zipf_trace;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_count_min_sketch() {
+        let trace = zipf_trace(10000, 1.1, 100000, 7);
+        let mut counts = HashMap::new();
+        for &key in &trace {
+            *counts.entry(key).or_insert(0) += 1;
+        }
+
+        let (epsilon, delta) = (0.001, 0.01);
+        let mut plain = CountMinSketch::with_error(epsilon, delta);
+        let mut conservative = CountMinSketch::with_error(epsilon, delta).conservative(true);
+        for &key in &trace {
+            plain.add(&key, 1);
+            conservative.add(&key, 1);
+        }
+
+        assert_eq!(plain.total(), trace.len() as u64);
+        let bound = (epsilon * trace.len() as f64) as u64;
+        let (mut plain_err, mut conservative_err, mut over) = (0, 0, 0);
+        for (key, &count) in &counts {
+            let (p, c) = (plain.estimate(key), conservative.estimate(key));
+            assert!(p >= count && c >= count && c <= p);
+            plain_err += p - count;
+            conservative_err += c - count;
+            if p > count + bound {
+                over += 1;
+            }
+        }
+
+        // 超过误差上界的比例不应明显超过 delta
+        assert!(over as f64 <= counts.len() as f64 * delta * 2.0);
+        assert!(conservative_err <= plain_err);
+
+        let mut a = CountMinSketch::new(64, 4);
+        let mut b = CountMinSketch::new(64, 4);
+        a.add("x", 3);
+        b.add("x", 4);
+        b.add("y", 1);
+        a.merge(&b);
+        assert!(a.estimate("x") >= 7);
+        assert!(a.estimate("y") >= 1);
+        assert_eq!(a.total(), 8);
+    }
+}
@@ -3,12 +3,26 @@
 //! 参考:
 //!
 //! - [布谷鸟过滤器：实际上优于布隆过滤器](http://www.linvon.cn/posts/cuckoo/)
+//! - [Cuckoo Filter: Practically Better Than Bloom](https://www.cs.cmu.edu/~dga/papers/cuckoo-conext2014.pdf)
+//!
+//! ## 序列化
+//!
+//! 指纹取值为 `1..=255`, 0 表示空位, 于是每个桶可以序列化成固定的 4 个字节, 见 [`CuckooFilter::to_bytes`]
+//!
+//! 论文 5.2 节的半排序(semi-sorting)可以再节省每个指纹 1 位:
+//! 桶内的指纹顺序无关紧要, 把 4 个指纹排序之后它们的高 4 位组成一个非递减序列,
+//! 这样的序列只有 `C(16+4-1, 4) = 3876` 种, 用 12 位的编号就能表示, 而不排序需要 16 位  
+//! 每个桶编码为 12 位编号加上 4 个指纹的低 4 位共 28 位, 见 [`CuckooFilter::to_bytes_semi_sorted`]
+//!
+//! 两种格式都以 1 字节的模式和 4 字节小端序的桶数量开头, [`CuckooFilter::from_bytes`] 根据模式解码
 
 use std::{
     collections::hash_map::DefaultHasher,
     hash::{Hash, Hasher},
 };
 
+use crate::filter::Filter;
+
 /// 最大踢出次数
 const MAX_KICK: usize = 32;
 
@@ -18,6 +32,24 @@ const BUCKET_COUNT: usize = 1024;
 /// 每个桶指纹树
 const BUCKET_SIZE: usize = 4;
 
+/// 用 seed 区分的哈希函数, 同一个 seed 对同一个 key 总是得到相同的哈希值
+pub(crate) fn hash<T: Hash + ?Sized>(key: &T, seed: u64) -> u64 {
+    let mut h = DefaultHasher::new();
+    seed.hash(&mut h);
+    key.hash(&mut h);
+    h.finish()
+}
+
+/// 传入的是 key 的哈希值计算 key 的指纹, 取值为 `1..=255`, 0 留给序列化时表示空位
+pub(crate) fn fingerprint(hash: u64) -> u8 {
+    (hash % 255) as u8 + 1
+}
+
+/// 哈希值转换到桶索引, 简写直接用高 32 位取模作为桶索引
+pub(crate) fn hash2index(hash: u64, buckets: usize) -> usize {
+    ((hash >> 32) as usize) % buckets
+}
+
 /// 布谷鸟过滤器
 pub struct CuckooFilter {
     // 简单的布谷鸟哈希桶列表
@@ -31,16 +63,22 @@ impl CuckooFilter {
         }
     }
 
-    /// 传入的是 key 的哈希值计算 key 的指纹, 简写直接取低 8 位
+    /// 存储的指纹数量
+    pub fn len(&self) -> usize {
+        self.buckets.iter().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     fn fingerprint(&self, hash: u64) -> u8 {
-        hash as u8
+        fingerprint(hash)
     }
 
     /// 计算哈希值
     fn hash(&self, key: &[u8]) -> u64 {
-        let mut h = DefaultHasher::new();
-        key.hash(&mut h);
-        h.finish()
+        hash(key, 0)
     }
 
     /// 对指纹取哈希
@@ -48,9 +86,8 @@ impl CuckooFilter {
         self.hash(&[fp])
     }
 
-    /// 哈希值转换到桶索引, 简写直接用高 32 位取模作为桶索引
     fn hash2index(&self, hash: u64) -> usize {
-        ((hash >> 32) as usize) % self.buckets.len()
+        hash2index(hash, self.buckets.len())
     }
 
     /// 插入元素
@@ -146,6 +183,173 @@ impl CuckooFilter {
     }
 }
 
+/// 序列化格式: 每个桶固定 4 个字节
+const MODE_FIXED: u8 = 0;
+
+/// 序列化格式: 半排序压缩, 每个桶 28 位
+const MODE_SEMI_SORTED: u8 = 1;
+
+/// 半排序编码每个桶的位数
+const SEMI_SORTED_BITS: usize = 12 + 4 * BUCKET_SIZE;
+
+/// 所有由 0..16 组成的长度为 4 的非递减序列, 按字典序排列, 序列的下标就是它的编码
+fn semi_sorted_table() -> Vec<[u8; BUCKET_SIZE]> {
+    let mut table = vec![];
+    for a in 0..16 {
+        for b in a..16 {
+            for c in b..16 {
+                for d in c..16 {
+                    table.push([a, b, c, d]);
+                }
+            }
+        }
+    }
+    table
+}
+
+/// 解码失败: 长度不对, 未知的模式, 桶数量不是 2 的幂或者半排序编号越界
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeCuckooFilterError;
+
+impl std::fmt::Display for DecodeCuckooFilterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid cuckoo filter bytes")
+    }
+}
+
+impl std::error::Error for DecodeCuckooFilterError {}
+
+impl CuckooFilter {
+    /// 桶内的指纹补齐到 4 个, 空位为 0
+    fn slots(bucket: &[u8]) -> [u8; BUCKET_SIZE] {
+        let mut slots = [0; BUCKET_SIZE];
+        slots[..bucket.len()].copy_from_slice(bucket);
+        slots
+    }
+
+    fn header(&self, mode: u8) -> Vec<u8> {
+        let mut bytes = vec![mode];
+        bytes.extend_from_slice(&(self.buckets.len() as u32).to_le_bytes());
+        bytes
+    }
+
+    /// 序列化, 每个桶固定 4 个字节, 空位为 0
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.header(MODE_FIXED);
+        for bucket in &self.buckets {
+            bytes.extend_from_slice(&Self::slots(bucket));
+        }
+        bytes
+    }
+
+    /// 半排序压缩序列化, 每个桶 28 位, 按低位在前连续写入
+    pub fn to_bytes_semi_sorted(&self) -> Vec<u8> {
+        let table = semi_sorted_table();
+        let mut bytes = self.header(MODE_SEMI_SORTED);
+
+        let (mut acc, mut bits) = (0u64, 0);
+        for bucket in &self.buckets {
+            let mut slots = Self::slots(bucket);
+            slots.sort_unstable();
+
+            let high = slots.map(|fp| fp >> 4);
+            let index = table.binary_search(&high).unwrap() as u64;
+            let mut code = index;
+            for (i, fp) in slots.iter().enumerate() {
+                code |= ((fp & 0xF) as u64) << (12 + 4 * i);
+            }
+
+            acc |= code << bits;
+            bits += SEMI_SORTED_BITS;
+            while bits >= 8 {
+                bytes.push(acc as u8);
+                acc >>= 8;
+                bits -= 8;
+            }
+        }
+        if bits > 0 {
+            bytes.push(acc as u8);
+        }
+
+        bytes
+    }
+
+    /// 从 [`CuckooFilter::to_bytes`] 或者 [`CuckooFilter::to_bytes_semi_sorted`] 的结果恢复过滤器
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeCuckooFilterError> {
+        let (&mode, rest) = bytes.split_first().ok_or(DecodeCuckooFilterError)?;
+        let (count, data) = rest
+            .split_first_chunk::<4>()
+            .ok_or(DecodeCuckooFilterError)?;
+        let count = u32::from_le_bytes(*count) as usize;
+        // 另一个桶的索引通过哈希值异或得到, 要求桶数量是 2 的幂
+        if !count.is_power_of_two() {
+            return Err(DecodeCuckooFilterError);
+        }
+
+        let slots: Vec<[u8; BUCKET_SIZE]> = match mode {
+            MODE_FIXED => {
+                if data.len() != count * BUCKET_SIZE {
+                    return Err(DecodeCuckooFilterError);
+                }
+                data.chunks_exact(BUCKET_SIZE)
+                    .map(|chunk| chunk.try_into().unwrap())
+                    .collect()
+            }
+            MODE_SEMI_SORTED => {
+                if data.len() != (count * SEMI_SORTED_BITS).div_ceil(8) {
+                    return Err(DecodeCuckooFilterError);
+                }
+
+                let table = semi_sorted_table();
+                let mask = (1u64 << SEMI_SORTED_BITS) - 1;
+                let mut data = data.iter();
+                let (mut acc, mut bits) = (0u64, 0);
+                let mut slots = Vec::with_capacity(count);
+                for _ in 0..count {
+                    while bits < SEMI_SORTED_BITS {
+                        acc |= (*data.next().unwrap() as u64) << bits;
+                        bits += 8;
+                    }
+                    let code = acc & mask;
+                    acc >>= SEMI_SORTED_BITS;
+                    bits -= SEMI_SORTED_BITS;
+
+                    let high = table
+                        .get((code & 0xFFF) as usize)
+                        .ok_or(DecodeCuckooFilterError)?;
+                    let mut bucket = [0; BUCKET_SIZE];
+                    for (i, fp) in bucket.iter_mut().enumerate() {
+                        *fp = (high[i] << 4) | ((code >> (12 + 4 * i)) & 0xF) as u8;
+                    }
+                    slots.push(bucket);
+                }
+                slots
+            }
+            _ => return Err(DecodeCuckooFilterError),
+        };
+
+        let buckets = slots
+            .into_iter()
+            .map(|slots| slots.into_iter().filter(|&fp| fp != 0).collect())
+            .collect();
+        Ok(Self { buckets })
+    }
+}
+
+impl Filter for CuckooFilter {
+    fn insert(&mut self, key: &[u8]) -> bool {
+        CuckooFilter::insert(self, key)
+    }
+
+    fn contains(&self, key: &[u8]) -> bool {
+        self.lookup(key)
+    }
+
+    fn len_estimate(&self) -> usize {
+        self.len()
+    }
+}
+
 impl Default for CuckooFilter {
     fn default() -> Self {
         Self::new()
@@ -169,4 +373,75 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_semi_sorted_table() {
+        let table = semi_sorted_table();
+        assert_eq!(table.len(), 3876);
+        assert!(table.len() <= 1 << 12);
+        assert!(table.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_cuckoo_filter_bytes() {
+        let mut cf = CuckooFilter::new();
+        let keys: Vec<_> = (0..2000u32).map(|i| i.to_le_bytes()).collect();
+        let inserted: Vec<_> = keys.iter().filter(|key| cf.insert(&key[..])).collect();
+        assert_eq!(inserted.len(), 2000);
+        assert!(inserted.iter().all(|key| cf.lookup(&key[..])));
+
+        let fixed = cf.to_bytes();
+        let semi = cf.to_bytes_semi_sorted();
+        assert_eq!(fixed.len(), 5 + BUCKET_COUNT * 4);
+        assert_eq!(semi.len(), 5 + BUCKET_COUNT * 28 / 8);
+
+        for bytes in [&fixed, &semi] {
+            let decoded = CuckooFilter::from_bytes(bytes).unwrap();
+            assert_eq!(decoded.len(), cf.len());
+            for (a, b) in cf.buckets.iter().zip(&decoded.buckets) {
+                let (mut a, mut b) = (a.clone(), b.clone());
+                a.sort_unstable();
+                b.sort_unstable();
+                assert_eq!(a, b);
+            }
+            assert!(inserted.iter().all(|key| decoded.lookup(&key[..])));
+
+            // 半排序编码与桶内顺序无关, 两种格式解码后再压缩结果相同
+            assert_eq!(decoded.to_bytes_semi_sorted(), semi);
+        }
+
+        let empty = CuckooFilter::new();
+        let decoded = CuckooFilter::from_bytes(&empty.to_bytes_semi_sorted()).unwrap();
+        assert!(decoded.is_empty());
+    }
+
+    #[test]
+    fn test_cuckoo_filter_bytes_invalid() {
+        let cf = CuckooFilter::new();
+        let fixed = cf.to_bytes();
+
+        assert!(CuckooFilter::from_bytes(&[]).is_err());
+        assert!(CuckooFilter::from_bytes(&fixed[..3]).is_err());
+        assert!(CuckooFilter::from_bytes(&fixed[..fixed.len() - 1]).is_err());
+
+        // 未知模式
+        let mut bytes = fixed.clone();
+        bytes[0] = 7;
+        assert!(CuckooFilter::from_bytes(&bytes).is_err());
+
+        // 桶数量不是 2 的幂
+        let mut bytes = vec![MODE_FIXED];
+        bytes.extend_from_slice(&3u32.to_le_bytes());
+        bytes.extend_from_slice(&[0; 12]);
+        assert!(CuckooFilter::from_bytes(&bytes).is_err());
+
+        // 半排序编号越界
+        let mut bytes = vec![MODE_SEMI_SORTED];
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        bytes.extend_from_slice(&[0xFF; 4]);
+        assert_eq!(
+            CuckooFilter::from_bytes(&bytes).err(),
+            Some(DecodeCuckooFilterError)
+        );
+    }
 }
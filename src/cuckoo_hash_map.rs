@@ -0,0 +1,261 @@
+//! 布谷鸟哈希表
+//!
+//! - [wikipedia - Cuckoo hashing](https://en.wikipedia.org/wiki/Cuckoo_hashing)
+//! - [Cuckoo Hashing - Pagh & Rodler](https://www.brics.dk/RS/01/32/BRICS-RS-01-32.pdf)
+//!
+//! 和 [`crate::cuckoo_filter`] 只保存指纹不同, 布谷鸟哈希表保存完整的 key-value
+//!
+//! 使用两张表和两个哈希函数, 每个 key 只可能出现在 `T0[h0(key)]` 或 `T1[h1(key)]` 两个位置之一
+//! 所以查找和删除最多只需要访问两个位置, 是严格 O(1) 的
+//!
+//! 插入时先放到 `T0[h0(key)]`, 如果这个位置已经有数据就把它踢出来,
+//! 被踢出的数据换到另一张表中它的备用位置, 如此反复直到找到空位
+//! 如果踢出次数超过上限, 说明很可能出现了环, 这时更换哈希函数(换一组 seed)重建整个表
+//! 两张表的负载因子超过 1/2 之后插入失败的概率会急剧上升, 所以超过 1/2 时扩容
+
+use std::hash::Hash;
+
+use crate::cuckoo_filter::{hash, hash2index};
+
+/// 最大踢出次数
+const MAX_KICK: usize = 64;
+
+/// 每张表的初始大小
+const INITIAL_CAPACITY: usize = 8;
+
+/// 表的占用情况
+#[derive(Debug, Clone, PartialEq)]
+pub struct Occupancy {
+    /// 数据数量
+    pub len: usize,
+    /// 两张表的总容量
+    pub capacity: usize,
+    /// 每张表中的数据数量
+    pub tables: [usize; 2],
+    /// 负载因子
+    pub load_factor: f64,
+    /// 因为踢出失败导致的重建次数
+    pub rehashes: usize,
+}
+
+/// 布谷鸟哈希表
+pub struct CuckooHashMap<K, V> {
+    tables: [Vec<Option<(K, V)>>; 2],
+    seeds: [u64; 2],
+    len: usize,
+    rehashes: usize,
+}
+
+impl<K: Hash + Eq, V> CuckooHashMap<K, V> {
+    pub fn new() -> Self {
+        Self {
+            tables: [
+                Self::empty_table(INITIAL_CAPACITY),
+                Self::empty_table(INITIAL_CAPACITY),
+            ],
+            seeds: [0, 1],
+            len: 0,
+            rehashes: 0,
+        }
+    }
+
+    fn empty_table(n: usize) -> Vec<Option<(K, V)>> {
+        (0..n).map(|_| None).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// key 在第 t 张表中的位置
+    fn index(&self, t: usize, key: &K) -> usize {
+        hash2index(hash(key, self.seeds[t]), self.tables[t].len())
+    }
+
+    /// 查找 key 所在的表和位置
+    fn locate(&self, key: &K) -> Option<(usize, usize)> {
+        (0..2).find_map(|t| {
+            let i = self.index(t, key);
+            match &self.tables[t][i] {
+                Some((k, _)) if k == key => Some((t, i)),
+                _ => None,
+            }
+        })
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let (t, i) = self.locate(key)?;
+        self.tables[t][i].as_ref().map(|(_, v)| v)
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let (t, i) = self.locate(key)?;
+        self.tables[t][i].as_mut().map(|(_, v)| v)
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.locate(key).is_some()
+    }
+
+    /// 插入 key-value, 如果 key 已经存在则返回旧值
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(v) = self.get_mut(&key) {
+            return Some(std::mem::replace(v, value));
+        }
+
+        let capacity = self.tables[0].len() * 2;
+        if (self.len + 1) * 2 > capacity {
+            self.rehash(self.tables[0].len() * 2);
+        }
+
+        let mut entry = (key, value);
+        while let Err(kicked) = self.place(entry) {
+            // 出现了环, 换一组哈希函数重建后再插入被踢出的数据
+            self.rehashes += 1;
+            self.rehash(self.tables[0].len());
+            entry = kicked;
+        }
+
+        self.len += 1;
+        None
+    }
+
+    /// 放置一个新数据, 如果踢出次数超过上限则返回最后被踢出的数据
+    fn place(&mut self, mut entry: (K, V)) -> Result<(), (K, V)> {
+        let mut t = 0;
+        for _ in 0..MAX_KICK {
+            let i = self.index(t, &entry.0);
+            match self.tables[t][i].replace(entry) {
+                None => return Ok(()),
+                Some(kicked) => entry = kicked,
+            }
+
+            // 被踢出的数据原来在表 t 中, 它的备用位置在另一张表
+            t ^= 1;
+        }
+
+        Err(entry)
+    }
+
+    /// 使用新的 seed 和每张表 n 个位置重建整个表
+    fn rehash(&mut self, n: usize) {
+        let mut entries: Vec<_> = self
+            .tables
+            .iter_mut()
+            .flat_map(|table| table.drain(..))
+            .flatten()
+            .collect();
+
+        'retry: loop {
+            self.seeds = [rand::random(), rand::random()];
+            self.tables = [Self::empty_table(n), Self::empty_table(n)];
+
+            while let Some(entry) = entries.pop() {
+                if let Err(kicked) = self.place(entry) {
+                    // 新的哈希函数依然有环, 收回所有数据再换一组
+                    entries.push(kicked);
+                    entries.extend(self.tables.iter_mut().flat_map(|t| t.drain(..)).flatten());
+                    continue 'retry;
+                }
+            }
+
+            return;
+        }
+    }
+
+    /// 删除 key 并返回对应的值
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let (t, i) = self.locate(key)?;
+        self.len -= 1;
+        self.tables[t][i].take().map(|(_, v)| v)
+    }
+
+    /// 遍历所有数据, 顺序不确定
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.tables.iter().flatten().flatten().map(|(k, v)| (k, v))
+    }
+
+    /// 表的占用情况
+    pub fn occupancy(&self) -> Occupancy {
+        let count = |t: usize| self.tables[t].iter().filter(|s| s.is_some()).count();
+        let capacity = self.tables[0].len() + self.tables[1].len();
+        Occupancy {
+            len: self.len,
+            capacity,
+            tables: [count(0), count(1)],
+            load_factor: self.len as f64 / capacity as f64,
+            rehashes: self.rehashes,
+        }
+    }
+}
+
+impl<K: Hash + Eq, V> Default for CuckooHashMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_cuckoo_hash_map() {
+        let mut map = CuckooHashMap::new();
+        assert!(map.is_empty());
+        assert_eq!(map.insert("a", 1), None);
+        assert_eq!(map.insert("b", 2), None);
+        assert_eq!(map.insert("a", 3), Some(1));
+        assert_eq!(map.get(&"a"), Some(&3));
+        assert_eq!(map.remove(&"b"), Some(2));
+        assert_eq!(map.remove(&"b"), None);
+        assert!(!map.contains_key(&"b"));
+        assert_eq!(map.len(), 1);
+
+        for i in 0..10000 {
+            map.insert("x", i);
+        }
+        assert_eq!(map.len(), 2);
+
+        let mut map = CuckooHashMap::new();
+        for i in 0..10000 {
+            map.insert(i, i);
+        }
+
+        let occupancy = map.occupancy();
+        assert_eq!(occupancy.len, 10000);
+        assert_eq!(occupancy.tables[0] + occupancy.tables[1], 10000);
+        assert!(occupancy.load_factor <= 0.5);
+        assert!((0..10000).all(|i| map.get(&i) == Some(&i)));
+    }
+
+    #[test]
+    fn test_cuckoo_hash_map_random() {
+        let mut rng = rand::thread_rng();
+        let mut map = CuckooHashMap::new();
+        let mut std_map = HashMap::new();
+
+        for i in 0..20000 {
+            let key = rng.gen_range(0..1024);
+            match rng.gen_range(0..3) {
+                0 => assert_eq!(map.insert(key, i), std_map.insert(key, i)),
+                1 => assert_eq!(map.remove(&key), std_map.remove(&key)),
+                _ => assert_eq!(map.get(&key), std_map.get(&key)),
+            }
+
+            assert_eq!(map.len(), std_map.len());
+        }
+
+        let mut items: Vec<_> = map.iter().map(|(&k, &v)| (k, v)).collect();
+        let mut expect: Vec<_> = std_map.into_iter().collect();
+        items.sort();
+        expect.sort();
+        assert_eq!(items, expect);
+    }
+}
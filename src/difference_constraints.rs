@@ -0,0 +1,150 @@
+//! 差分约束系统
+//!
+//! - [OI Wiki - 差分约束](https://oi-wiki.org/graph/diff-constraints/)
+//!
+//! 差分约束系统是一组形如 `x[i] - x[j] <= c` 的不等式, 求一组满足所有不等式的解
+//!
+//! 最短路满足三角形不等式 `dist[i] <= dist[j] + w(j, i)`, 即 `dist[i] - dist[j] <= w(j, i)`, 和约束的形式完全相同  
+//! 所以对每个约束连一条 j -> i 权值为 c 的边, 再加一个超级源点向所有变量连权值为 0 的边,
+//! 求出的最短路 `x[i] = dist[i]` 就是一组解(并且是所有不大于 0 的解中每个变量都最大的一组)
+//!
+//! 如果图中存在负环, 沿着负环把约束加起来会得到 `0 <= 负数`, 说明无解  
+//! 边权可能为负, 所以使用 [`crate::graph_shortest_path::spfa`] 求最短路
+//!
+//! 其他形式的约束都可以转换成这种形式:
+//!
+//! - `x[i] - x[j] >= c` 等价于 `x[j] - x[i] <= -c`
+//! - `x[i] - x[j] == c` 等价于同时满足 `<= c` 和 `>= c`
+
+use crate::graph_shortest_path::spfa;
+
+/// 差分约束系统, 变量编号为 `0..n`
+///
+/// ```
+/// use impx::difference_constraints::DifferenceConstraints;
+///
+/// let mut dc = DifferenceConstraints::new(3);
+/// dc.add_le(1, 0, 3); // x1 - x0 <= 3
+/// dc.add_ge(2, 1, 2); // x2 - x1 >= 2
+/// dc.add_le(2, 0, 4); // x2 - x0 <= 4
+/// let x = dc.solve().unwrap();
+/// assert!(x[1] - x[0] <= 3 && x[2] - x[1] >= 2 && x[2] - x[0] <= 4);
+///
+/// dc.add_ge(1, 0, 3); // x1 - x0 >= 3 推出 x2 - x0 >= 5, 矛盾
+/// assert_eq!(dc.solve(), None);
+/// ```
+pub struct DifferenceConstraints {
+    n: usize,
+    /// 最短路模块中节点编号为 1..N, 变量 i 对应节点 i+1, 超级源点为 n+1
+    edges: Vec<(usize, usize, i64)>,
+}
+
+impl DifferenceConstraints {
+    /// n 个变量
+    pub fn new(n: usize) -> Self {
+        Self { n, edges: vec![] }
+    }
+
+    /// 变量个数
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// 约束个数(相等约束算两个)
+    pub fn constraints(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// `x[i] - x[j] <= c`
+    pub fn add_le(&mut self, i: usize, j: usize, c: i64) {
+        assert!(i < self.n && j < self.n, "variable out of range");
+        self.edges.push((j + 1, i + 1, c));
+    }
+
+    /// `x[i] - x[j] >= c`
+    pub fn add_ge(&mut self, i: usize, j: usize, c: i64) {
+        self.add_le(j, i, -c);
+    }
+
+    /// `x[i] - x[j] == c`
+    pub fn add_eq(&mut self, i: usize, j: usize, c: i64) {
+        self.add_le(i, j, c);
+        self.add_ge(i, j, c);
+    }
+
+    /// 求一组解, 无解时返回 None
+    pub fn solve(&self) -> Option<Vec<i64>> {
+        let src = self.n + 1;
+        let mut edges = self.edges.clone();
+        edges.extend((1..=self.n).map(|v| (src, v, 0)));
+
+        let dist = spfa(self.n + 1, &edges, src)?;
+        Some(dist[1..=self.n].iter().map(|d| d.unwrap()).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_difference_constraints_random() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..300 {
+            let n = rng.gen_range(1..5);
+            let mut dc = DifferenceConstraints::new(n);
+            let mut constraints = vec![];
+            for _ in 0..rng.gen_range(0..8) {
+                let (i, j, c) = (
+                    rng.gen_range(0..n),
+                    rng.gen_range(0..n),
+                    rng.gen_range(-3..4),
+                );
+                dc.add_le(i, j, c);
+                constraints.push((i, j, c));
+            }
+            let ok = |x: &[i64]| constraints.iter().all(|&(i, j, c)| x[i] - x[j] <= c);
+
+            // 如果有解, 一定存在所有变量都在 [-3n, 0] 中的解(最短路解的范围), 暴力枚举
+            let range = 3 * n as i64;
+            let brute = (0..(range as usize + 1).pow(n as u32)).any(|mut code| {
+                let x: Vec<i64> = (0..n)
+                    .map(|_| {
+                        let v = -((code % (range as usize + 1)) as i64);
+                        code /= range as usize + 1;
+                        v
+                    })
+                    .collect();
+                ok(&x)
+            });
+
+            match dc.solve() {
+                Some(x) => {
+                    assert!(ok(&x));
+                    assert!(x.iter().all(|&v| v <= 0));
+                }
+                None => assert!(!brute),
+            }
+        }
+    }
+
+    #[test]
+    fn test_difference_constraints_eq() {
+        // 排队问题: x1 = x0 + 2, x2 = x1 + 3, x2 - x0 <= 5
+        let mut dc = DifferenceConstraints::new(3);
+        dc.add_eq(1, 0, 2);
+        dc.add_eq(2, 1, 3);
+        dc.add_le(2, 0, 5);
+        let x = dc.solve().unwrap();
+        assert_eq!((x[1] - x[0], x[2] - x[1]), (2, 3));
+        assert_eq!(dc.constraints(), 5);
+
+        dc.add_le(2, 0, 4);
+        assert_eq!(dc.solve(), None);
+    }
+}
@@ -8,12 +8,20 @@
 //! 每棵树上有一个唯一的根节点, 具有相同根节点的元素在同一棵树中, 同属一个集合  
 //! 集合与集合之间也可以合并, 但一定要通过根节点合并, 将一棵树的根节点指向另外一棵树的根节点  
 //! 在图中使用并查集, 可以将同一个连通分量中的所有节点视为一个集合, 表示为一棵树
+//!
+//! 路径压缩只是修改父节点的指向, 不会改变任何元素所属的集合, 对外是不可见的  
+//! 所以父节点保存在 [`Cell`] 中, [`DisjointSet::find`] 只需要 `&self` 就可以压缩路径,
+//! 只读地共享并查集时(比如反复检查两个节点是否连通)也能享受路径压缩, 代价是 `DisjointSet` 不再是 `Sync` 的
+//!
+//! 元素是 `0..n` 的编号时使用 [`UnionFind`], 用数组代替哈希表, 按秩合并并且压缩路径,
+//! 单次操作的均摊复杂度为 O(α(n)), 其中 α 是反阿克曼函数, 实际使用中可以看作常数
 
+use std::cell::Cell;
 use std::{collections::HashMap, hash::Hash};
 
 /// 并查集
 pub struct DisjointSet<T> {
-    parent: HashMap<T, T>,
+    parent: HashMap<T, Cell<T>>,
     size: HashMap<T, usize>,
 }
 
@@ -24,7 +32,7 @@ impl<T: Eq + Hash + Copy> DisjointSet<T> {
         let mut size = HashMap::new();
         let mut parent = HashMap::new();
         for &v in total {
-            parent.insert(v, v);
+            parent.insert(v, Cell::new(v));
             size.insert(v, 1);
         }
 
@@ -46,33 +54,105 @@ impl<T: Eq + Hash + Copy> DisjointSet<T> {
             let bsize = self.size.get(&broot).expect("root must have size");
 
             if asize < bsize {
-                self.parent.insert(aroot, broot);
+                self.parent[&aroot].set(broot);
                 self.size.insert(broot, asize + bsize);
             } else {
-                self.parent.insert(broot, aroot);
+                self.parent[&broot].set(aroot);
                 self.size.insert(aroot, asize + bsize);
             }
         }
     }
 
     /// 查找节点对应的根节点, 期间可以执行路径压缩, 缩短当前节点和根结点之间的路径
-    pub fn find(&mut self, x: &T) -> T {
-        let parent = self.parent.get(x).expect("");
-        if parent == x {
+    pub fn find(&self, x: &T) -> T {
+        let parent = self.parent.get(x).expect("element not in the set");
+        if parent.get() == *x {
             // 如果自己的父节点等于自己
             // 那么自己就是根节点
-            return parent.to_owned();
+            return *x;
         }
 
         // 否则向上查询父节点的根节点
-        let x = x.to_owned();
-        let parent = parent.to_owned();
-        let root = self.find(&parent);
+        let root = self.find(&parent.get());
 
         // 找到根节点后, 直接将当前节点的父节点指向根节点压缩到根节点的路径
-        self.parent.insert(x, root);
+        parent.set(root);
         root
     }
+
+    /// 两个节点是否在同一个集合中
+    pub fn connected(&self, a: &T, b: &T) -> bool {
+        self.find(a) == self.find(b)
+    }
+}
+
+/// 元素为 `0..n` 的并查集
+pub struct UnionFind {
+    parent: Vec<Cell<usize>>,
+    /// 根节点的秩, 是树高的上界, 路径压缩之后不再更新
+    rank: Vec<u8>,
+    /// 集合的数量
+    count: usize,
+}
+
+impl UnionFind {
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).map(Cell::new).collect(),
+            rank: vec![0; n],
+            count: n,
+        }
+    }
+
+    /// 元素个数
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// 集合的数量
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// 查找根节点, 第一遍找到根节点, 第二遍把路径上的节点都直接指向根节点, 避免递归
+    pub fn find(&self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root].get() != root {
+            root = self.parent[root].get();
+        }
+
+        let mut x = x;
+        while x != root {
+            x = self.parent[x].replace(root);
+        }
+        root
+    }
+
+    /// 合并两个元素所在的集合, 秩较小的树挂到秩较大的树下, 返回是否真的发生了合并
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        if self.rank[a] < self.rank[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b].set(a);
+        if self.rank[a] == self.rank[b] {
+            self.rank[a] += 1;
+        }
+        self.count -= 1;
+        true
+    }
+
+    pub fn connected(&self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
 }
 
 #[cfg(test)]
@@ -94,4 +174,61 @@ mod tests {
         assert_ne!(set.find(&2), set.find(&4));
         assert_ne!(set.find(&3), set.find(&7));
     }
+
+    #[test]
+    fn test_disjoint_set_shared() {
+        use super::DisjointSet;
+
+        // 每次合并两个大小相同的集合, 得到一棵高度为 7 的二项树
+        let n = 128;
+        let mut set = DisjointSet::new(&(0..n).collect::<Vec<_>>());
+        let mut step = 1;
+        while step < n {
+            for i in (0..n).step_by(step * 2) {
+                set.union(&i, &(i + step));
+            }
+            step *= 2;
+        }
+
+        // 只通过共享引用查询, 查询后所有节点都直接指向根节点
+        let shared = &set;
+        assert_ne!(shared.parent[&(n - 1)].get(), 0);
+        let root = shared.find(&(n - 1));
+        assert_eq!(root, 0);
+        assert!((0..n).all(|i| shared.connected(&i, &root)));
+        assert!((0..n).all(|i| shared.parent[&i].get() == root));
+    }
+
+    #[test]
+    fn test_union_find() {
+        use super::{DisjointSet, UnionFind};
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        let n = 200;
+        let mut uf = UnionFind::new(n);
+        let mut set = DisjointSet::new(&(0..n).collect::<Vec<_>>());
+        assert_eq!((uf.len(), uf.count()), (n, n));
+        for _ in 0..300 {
+            let (a, b) = (rng.gen_range(0..n), rng.gen_range(0..n));
+            let merged = !set.connected(&a, &b);
+            assert_eq!(uf.union(a, b), merged);
+            set.union(&a, &b);
+
+            let (x, y) = (rng.gen_range(0..n), rng.gen_range(0..n));
+            assert_eq!(uf.connected(x, y), set.connected(&x, &y));
+        }
+
+        let roots: std::collections::HashSet<_> = (0..n).map(|i| uf.find(i)).collect();
+        assert_eq!(roots.len(), uf.count());
+        // 秩不超过 log2(n)
+        assert!(uf.rank.iter().all(|&r| 1 << r <= n));
+    }
+
+    #[test]
+    #[should_panic(expected = "not in the set")]
+    fn test_disjoint_set_missing() {
+        use super::DisjointSet;
+        DisjointSet::new(&[1, 2]).find(&3);
+    }
 }
@@ -0,0 +1,410 @@
+//! 动态规划
+//!
+//! - [OI Wiki - 动态规划基础](https://oi-wiki.org/dp/basic/)
+//! - [OI Wiki - 背包 DP](https://oi-wiki.org/dp/knapsack/)
+//! - [OI Wiki - 区间 DP](https://oi-wiki.org/dp/interval/)
+//!
+//! 动态规划适用于具有最优子结构和重叠子问题的问题:
+//! 原问题的最优解可以由子问题的最优解推出, 并且同一个子问题会被反复用到  
+//! 这时把每个子问题的答案保存下来就能避免重复计算, 有两种写法:
+//!
+//! - 记忆化搜索(自顶向下): 按递归的方式写状态转移, 第一次计算某个状态时把结果记到表里, 见 [`Memo`]
+//! - 递推(自底向上): 按依赖关系从小到大依次填表, 通常还可以用滚动数组压缩空间
+//!
+//! 写动态规划的关键是定义状态和状态转移方程, 下面的每个例子都在文档中给出了状态和转移方程
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// 记忆化搜索
+///
+/// f 的第一个参数用来递归地求解子问题, 已经计算过的状态直接从缓存中返回
+///
+/// ```
+/// use impx::dp::Memo;
+///
+/// let mut fib = Memo::new(|fib, n: u64| if n < 2 { n } else { fib(n - 1) + fib(n - 2) });
+/// assert_eq!(fib.get(90), 2880067194370816120);
+/// ```
+pub struct Memo<A, R, F> {
+    cache: HashMap<A, R>,
+    f: F,
+}
+
+impl<A, R, F> Memo<A, R, F>
+where
+    A: Hash + Eq + Clone,
+    R: Clone,
+    F: Fn(&mut dyn FnMut(A) -> R, A) -> R,
+{
+    pub fn new(f: F) -> Self {
+        Self {
+            cache: HashMap::new(),
+            f,
+        }
+    }
+
+    /// 已经缓存的状态数
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn get(&mut self, a: A) -> R {
+        Self::call(&mut self.cache, &self.f, a)
+    }
+
+    fn call(cache: &mut HashMap<A, R>, f: &F, a: A) -> R {
+        if let Some(r) = cache.get(&a) {
+            return r.clone();
+        }
+
+        let r = f(&mut |x| Self::call(cache, f, x), a.clone());
+        cache.insert(a, r.clone());
+        r
+    }
+}
+
+/// 把 f 包装成带缓存的函数, 参见 [`Memo`]
+///
+/// ```
+/// use impx::dp::memoize;
+///
+/// // 从左上角走到 (r, c) 的路径数
+/// let mut paths = memoize(|paths, (r, c): (u32, u32)| {
+///     if r == 0 || c == 0 { 1u64 } else { paths((r - 1, c)) + paths((r, c - 1)) }
+/// });
+/// assert_eq!(paths((16, 16)), 601080390);
+/// ```
+pub fn memoize<A, R, F>(f: F) -> impl FnMut(A) -> R
+where
+    A: Hash + Eq + Clone,
+    R: Clone,
+    F: Fn(&mut dyn FnMut(A) -> R, A) -> R,
+{
+    let mut memo = Memo::new(f);
+    move |a| memo.get(a)
+}
+
+/// 0/1 背包, 返回容量为 capacity 时能装下的最大价值以及选中的物品下标
+///
+/// 状态 `dp[i][w]` 表示只考虑前 i 个物品、容量为 w 时的最大价值, 第 i 个物品要么不选要么选:
+///
+/// `dp[i][w] = max(dp[i-1][w], dp[i-1][w - weight[i]] + value[i])`
+///
+/// 只求最大价值时可以只保留一行并让 w 从大到小遍历, 这里为了找回选中的物品保留了整张表
+///
+/// ```
+/// use impx::dp::knapsack_01;
+///
+/// let (best, items) = knapsack_01(&[1, 3, 4, 5], &[1, 4, 5, 7], 7);
+/// assert_eq!(best, 9);
+/// assert_eq!(items, vec![1, 2]);
+/// ```
+pub fn knapsack_01(weights: &[usize], values: &[u64], capacity: usize) -> (u64, Vec<usize>) {
+    assert_eq!(
+        weights.len(),
+        values.len(),
+        "weights and values must have the same length"
+    );
+
+    let n = weights.len();
+    let mut dp = vec![vec![0u64; capacity + 1]; n + 1];
+    for i in 1..=n {
+        let (w, v) = (weights[i - 1], values[i - 1]);
+        for c in 0..=capacity {
+            dp[i][c] = dp[i - 1][c];
+            if c >= w {
+                dp[i][c] = dp[i][c].max(dp[i - 1][c - w] + v);
+            }
+        }
+    }
+
+    // 从后往前找回选中的物品: 值发生了变化说明选了第 i 个物品
+    let mut items = vec![];
+    let mut c = capacity;
+    for i in (1..=n).rev() {
+        if dp[i][c] != dp[i - 1][c] {
+            items.push(i - 1);
+            c -= weights[i - 1];
+        }
+    }
+    items.reverse();
+
+    (dp[n][capacity], items)
+}
+
+/// 编辑距离(Levenshtein 距离), 把 a 变成 b 所需的最少插入、删除、替换次数
+///
+/// 状态 `dp[i][j]` 表示 a 的前 i 个字符变成 b 的前 j 个字符的编辑距离:
+///
+/// - `a[i] == b[j]` 时 `dp[i][j] = dp[i-1][j-1]`
+/// - 否则 `dp[i][j] = 1 + min(dp[i-1][j], dp[i][j-1], dp[i-1][j-1])` 分别对应删除、插入、替换
+///
+/// 每一行只依赖上一行, 所以只保存一行, 并且编辑距离是对称的,
+/// 交换 a 和 b 让一行的长度取较短的那个, 空间复杂度 O(min(m, n))
+///
+/// 需要具体的编辑步骤时可以使用 [`crate::string::edit_script`]
+///
+/// ```
+/// use impx::dp::edit_distance;
+///
+/// assert_eq!(edit_distance("kitten", "sitting"), 3);
+/// assert_eq!(edit_distance("", "abc"), 3);
+/// ```
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let (mut a, mut b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    if b.len() > a.len() {
+        std::mem::swap(&mut a, &mut b);
+    }
+    let mut dp: Vec<usize> = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        // prev 是 dp[i-1][j-1]
+        let mut prev = dp[0];
+        dp[0] = i;
+        for j in 1..=b.len() {
+            let cur = if a[i - 1] == b[j - 1] {
+                prev
+            } else {
+                1 + prev.min(dp[j]).min(dp[j - 1])
+            };
+            prev = dp[j];
+            dp[j] = cur;
+        }
+    }
+
+    dp[b.len()]
+}
+
+/// 最长公共子序列
+///
+/// 状态 `dp[i][j]` 表示 a 的前 i 个元素和 b 的前 j 个元素的最长公共子序列长度:
+///
+/// - `a[i] == b[j]` 时 `dp[i][j] = dp[i-1][j-1] + 1`
+/// - 否则 `dp[i][j] = max(dp[i-1][j], dp[i][j-1])`
+///
+/// ```
+/// use impx::dp::lcs;
+///
+/// let s = lcs(b"ABCBDAB", b"BDCABA");
+/// assert_eq!(s.len(), 4);
+/// ```
+pub fn lcs<T: Eq + Clone>(a: &[T], b: &[T]) -> Vec<T> {
+    let (n, m) = (a.len(), b.len());
+    let mut dp = vec![vec![0usize; m + 1]; n + 1];
+    for i in 1..=n {
+        for j in 1..=m {
+            dp[i][j] = if a[i - 1] == b[j - 1] {
+                dp[i - 1][j - 1] + 1
+            } else {
+                dp[i - 1][j].max(dp[i][j - 1])
+            };
+        }
+    }
+
+    // 从 dp[n][m] 沿着转移的方向往回走
+    let mut res = vec![];
+    let (mut i, mut j) = (n, m);
+    while i > 0 && j > 0 {
+        if a[i - 1] == b[j - 1] {
+            res.push(a[i - 1].clone());
+            (i, j) = (i - 1, j - 1);
+        } else if dp[i - 1][j] >= dp[i][j - 1] {
+            i -= 1;
+        } else {
+            j -= 1;
+        }
+    }
+    res.reverse();
+
+    res
+}
+
+/// 凑出 amount 所需的最少硬币数, 每种硬币数量不限, 凑不出时返回 None
+///
+/// 状态 `dp[x]` 表示凑出 x 的最少硬币数, `dp[x] = min(dp[x - coin] + 1)`
+///
+/// ```
+/// use impx::dp::coin_change_min;
+///
+/// assert_eq!(coin_change_min(&[1, 2, 5], 11), Some(3));
+/// assert_eq!(coin_change_min(&[2], 3), None);
+/// ```
+pub fn coin_change_min(coins: &[usize], amount: usize) -> Option<usize> {
+    let mut dp = vec![None; amount + 1];
+    dp[0] = Some(0);
+    for x in 1..=amount {
+        dp[x] = coins
+            .iter()
+            .filter(|&&c| c > 0 && c <= x)
+            .filter_map(|&c| dp[x - c])
+            .min()
+            .map(|k| k + 1);
+    }
+
+    dp[amount]
+}
+
+/// 凑出 amount 的方案数, 每种硬币数量不限, 只考虑硬币的组合而不考虑顺序
+///
+/// 这是一个完全背包: 外层遍历硬币, 内层从小到大遍历金额, `dp[x] += dp[x - coin]`  
+/// 先遍历硬币保证了每种组合只按硬币的顺序被计算一次, 如果交换两层循环求出的就是排列数
+///
+/// ```
+/// use impx::dp::coin_change_ways;
+///
+/// assert_eq!(coin_change_ways(&[1, 2, 5], 5), 4);
+/// ```
+pub fn coin_change_ways(coins: &[usize], amount: usize) -> u64 {
+    let mut dp = vec![0u64; amount + 1];
+    dp[0] = 1;
+    for &c in coins.iter().filter(|&&c| c > 0) {
+        for x in c..=amount {
+            dp[x] += dp[x - c];
+        }
+    }
+
+    dp[amount]
+}
+
+/// 矩阵链乘法, 第 i 个矩阵的大小是 `dims[i] x dims[i+1]`, 返回最少的标量乘法次数和对应的加括号方式
+///
+/// 这是一个区间 DP, 状态 `dp[i][j]` 表示计算第 i 到第 j 个矩阵的乘积的最小代价,
+/// 枚举最后一次乘法的分割点 k:
+///
+/// `dp[i][j] = min(dp[i][k] + dp[k+1][j] + dims[i] * dims[k+1] * dims[j+1])`
+///
+/// 按区间长度从小到大计算, 同时记录最优的分割点用来还原加括号的方式
+///
+/// ```
+/// use impx::dp::matrix_chain;
+///
+/// let (cost, order) = matrix_chain(&[10, 30, 5, 60]);
+/// assert_eq!(cost, 4500);
+/// assert_eq!(order, "((A1A2)A3)");
+/// ```
+pub fn matrix_chain(dims: &[usize]) -> (usize, String) {
+    assert!(dims.len() >= 2, "at least one matrix is required");
+
+    let n = dims.len() - 1;
+    let mut dp = vec![vec![0usize; n]; n];
+    let mut split = vec![vec![0usize; n]; n];
+    for len in 2..=n {
+        for i in 0..=n - len {
+            let j = i + len - 1;
+            dp[i][j] = usize::MAX;
+            for k in i..j {
+                let cost = dp[i][k] + dp[k + 1][j] + dims[i] * dims[k + 1] * dims[j + 1];
+                if cost < dp[i][j] {
+                    dp[i][j] = cost;
+                    split[i][j] = k;
+                }
+            }
+        }
+    }
+
+    fn order(split: &[Vec<usize>], i: usize, j: usize) -> String {
+        if i == j {
+            return format!("A{}", i + 1);
+        }
+        let k = split[i][j];
+        format!("({}{})", order(split, i, k), order(split, k + 1, j))
+    }
+
+    (dp[0][n - 1], order(&split, 0, n - 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_memo() {
+        let mut fib = Memo::new(|fib, n: u128| if n < 2 { n } else { fib(n - 1) + fib(n - 2) });
+        assert!(fib.is_empty());
+        assert_eq!(fib.get(186), crate::matrix_exp::fib(186));
+        assert_eq!(fib.len(), 187);
+
+        // 有缓存时不会再次计算
+        let calls = std::cell::Cell::new(0);
+        let mut square = memoize(|_, x: u64| {
+            calls.set(calls.get() + 1);
+            x * x
+        });
+        for _ in 0..3 {
+            assert_eq!(square(12), 144);
+        }
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn test_knapsack_01() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..50 {
+            let n = rng.gen_range(0..12);
+            let weights: Vec<usize> = (0..n).map(|_| rng.gen_range(1..20)).collect();
+            let values: Vec<u64> = (0..n).map(|_| rng.gen_range(0..50)).collect();
+            let capacity = rng.gen_range(0..60);
+
+            // 枚举所有子集
+            let best = (0..1usize << n)
+                .filter(|&s| {
+                    (0..n)
+                        .filter(|i| s >> i & 1 == 1)
+                        .map(|i| weights[i])
+                        .sum::<usize>()
+                        <= capacity
+                })
+                .map(|s| {
+                    (0..n)
+                        .filter(|i| s >> i & 1 == 1)
+                        .map(|i| values[i])
+                        .sum::<u64>()
+                })
+                .max()
+                .unwrap();
+
+            let (value, items) = knapsack_01(&weights, &values, capacity);
+            assert_eq!(value, best);
+            assert_eq!(items.iter().map(|&i| values[i]).sum::<u64>(), best);
+            assert!(items.iter().map(|&i| weights[i]).sum::<usize>() <= capacity);
+        }
+    }
+
+    #[test]
+    fn test_edit_distance_and_lcs() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("flaw", "lawn"), 2);
+        assert_eq!(edit_distance("intention", "execution"), 5);
+        assert_eq!(edit_distance("你好世界", "你们好"), 3);
+
+        assert_eq!(lcs(b"ABCBDAB", b"BDCABA"), b"BCBA");
+        assert_eq!(lcs(b"AGGTAB", b"GXTXAYB"), b"GTAB");
+        assert!(lcs::<u8>(b"", b"abc").is_empty());
+        assert!(lcs(b"abc", b"def").is_empty());
+    }
+
+    #[test]
+    fn test_coin_change() {
+        assert_eq!(coin_change_min(&[1, 5, 10, 25], 63), Some(6));
+        assert_eq!(coin_change_min(&[1, 3, 4], 6), Some(2));
+        assert_eq!(coin_change_min(&[], 0), Some(0));
+        assert_eq!(coin_change_min(&[], 1), None);
+
+        assert_eq!(coin_change_ways(&[2, 5, 3, 6], 10), 5);
+        assert_eq!(coin_change_ways(&[1, 5, 10, 25, 50], 100), 292);
+        assert_eq!(coin_change_ways(&[3], 2), 0);
+    }
+
+    #[test]
+    fn test_matrix_chain() {
+        let (cost, order) = matrix_chain(&[30, 35, 15, 5, 10, 20, 25]);
+        assert_eq!(cost, 15125);
+        assert_eq!(order, "((A1(A2A3))((A4A5)A6))");
+        assert_eq!(matrix_chain(&[3, 4]), (0, "A1".to_string()));
+    }
+}
@@ -0,0 +1,345 @@
+//! 四则运算表达式求值
+//!
+//! - [wikipedia - Shunting yard algorithm](https://en.wikipedia.org/wiki/Shunting_yard_algorithm)
+//! - [OI Wiki - 表达式求值](https://oi-wiki.org/misc/expression/)
+//!
+//! 分成四步:
+//!
+//! 1. [`tokenize`] 把字符串切分成数字、运算符和括号, 出现在开头、运算符或左括号之后的 `-` 是取负
+//! 2. [`to_postfix`] 使用调度场算法把中缀表达式转成后缀表达式(逆波兰表达式)
+//! 3. [`build_tree`] 用一个栈把后缀表达式还原成以 [`BinaryTree`] 表示的表达式树
+//! 4. [`evaluate`] 后序遍历表达式树求值
+//!
+//! 调度场算法从左往右扫描, 数字直接输出, 运算符入栈前先弹出栈顶所有应该先计算的运算符:
+//! 优先级更高的, 或者优先级相同并且是左结合的。取负是前缀运算符, 它的左边没有操作数, 入栈时不弹出任何运算符
+//!
+//! 优先级从低到高为 `+ -`, `* /`, 取负, `^`, 其中 `^` 和取负是右结合的, 所以 `-2^2 = -4`, `2^3^2 = 512`
+
+use std::fmt;
+
+use crate::binary_tree::BinaryTree;
+
+/// 运算符
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+    /// 取负
+    Neg,
+}
+
+impl Op {
+    fn precedence(self) -> u8 {
+        match self {
+            Op::Add | Op::Sub => 1,
+            Op::Mul | Op::Div => 2,
+            Op::Neg => 3,
+            Op::Pow => 4,
+        }
+    }
+
+    fn right_assoc(self) -> bool {
+        matches!(self, Op::Pow | Op::Neg)
+    }
+
+    fn symbol(self) -> char {
+        match self {
+            Op::Add => '+',
+            Op::Sub | Op::Neg => '-',
+            Op::Mul => '*',
+            Op::Div => '/',
+            Op::Pow => '^',
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Token {
+    Num(f64),
+    Op(Op),
+    LParen,
+    RParen,
+}
+
+/// 表达式树的节点, 运算符节点的操作数是它的子树, 取负只有左子树
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Expr {
+    Num(f64),
+    Op(Op),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParseExprError {
+    /// 无法识别的字符
+    InvalidChar(char),
+    /// 无法解析的数字, 比如 `1.2.3`
+    InvalidNumber,
+    /// 括号不匹配
+    UnbalancedParen,
+    /// 运算符缺少操作数或者操作数之间缺少运算符
+    InvalidSyntax,
+}
+
+impl fmt::Display for ParseExprError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseExprError::InvalidChar(c) => write!(f, "invalid character {c:?}"),
+            ParseExprError::InvalidNumber => write!(f, "invalid number"),
+            ParseExprError::UnbalancedParen => write!(f, "unbalanced parentheses"),
+            ParseExprError::InvalidSyntax => write!(f, "invalid expression"),
+        }
+    }
+}
+
+impl std::error::Error for ParseExprError {}
+
+/// 词法分析, 空白字符被忽略
+pub fn tokenize(s: &str) -> Result<Vec<Token>, ParseExprError> {
+    let mut tokens = vec![];
+    let mut chars = s.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '0'..='9' | '.' => {
+                let mut end = i + 1;
+                while let Some(&(j, c)) = chars.peek() {
+                    if !(c.is_ascii_digit() || c == '.') {
+                        break;
+                    }
+                    end = j + 1;
+                    chars.next();
+                }
+                let x = s[i..end]
+                    .parse()
+                    .map_err(|_| ParseExprError::InvalidNumber)?;
+                Token::Num(x)
+            }
+            '-' => match tokens.last() {
+                None | Some(Token::Op(_)) | Some(Token::LParen) => Token::Op(Op::Neg),
+                _ => Token::Op(Op::Sub),
+            },
+            '+' => Token::Op(Op::Add),
+            '*' => Token::Op(Op::Mul),
+            '/' => Token::Op(Op::Div),
+            '^' => Token::Op(Op::Pow),
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            c => return Err(ParseExprError::InvalidChar(c)),
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+/// 调度场算法, 中缀表达式转后缀表达式, 结果中不含括号
+pub fn to_postfix(tokens: &[Token]) -> Result<Vec<Token>, ParseExprError> {
+    let mut output = vec![];
+    let mut stack: Vec<Token> = vec![];
+    for &token in tokens {
+        match token {
+            Token::Num(_) => output.push(token),
+            Token::Op(Op::Neg) => stack.push(token),
+            Token::Op(op) => {
+                while let Some(&Token::Op(top)) = stack.last() {
+                    let first = top.precedence() > op.precedence()
+                        || (top.precedence() == op.precedence() && !op.right_assoc());
+                    if !first {
+                        break;
+                    }
+                    output.push(stack.pop().unwrap());
+                }
+                stack.push(token);
+            }
+            Token::LParen => stack.push(token),
+            Token::RParen => loop {
+                match stack.pop() {
+                    Some(Token::LParen) => break,
+                    Some(top) => output.push(top),
+                    None => return Err(ParseExprError::UnbalancedParen),
+                }
+            },
+        }
+    }
+
+    while let Some(top) = stack.pop() {
+        if top == Token::LParen {
+            return Err(ParseExprError::UnbalancedParen);
+        }
+        output.push(top);
+    }
+    Ok(output)
+}
+
+/// 从后缀表达式构建表达式树
+///
+/// 数字入栈, 运算符从栈中弹出它的操作数作为子树, 最后栈中必须恰好剩下一棵树
+pub fn build_tree(postfix: &[Token]) -> Result<BinaryTree<Expr>, ParseExprError> {
+    let mut stack = vec![];
+    for &token in postfix {
+        let node = match token {
+            Token::Num(x) => BinaryTree::new(Expr::Num(x)),
+            Token::Op(Op::Neg) => {
+                let operand = stack.pop().ok_or(ParseExprError::InvalidSyntax)?;
+                BinaryTree::with_children(Expr::Op(Op::Neg), Some(operand), None)
+            }
+            Token::Op(op) => {
+                let right = stack.pop().ok_or(ParseExprError::InvalidSyntax)?;
+                let left = stack.pop().ok_or(ParseExprError::InvalidSyntax)?;
+                BinaryTree::with_children(Expr::Op(op), Some(left), Some(right))
+            }
+            Token::LParen | Token::RParen => return Err(ParseExprError::UnbalancedParen),
+        };
+        stack.push(node);
+    }
+
+    match (stack.pop(), stack.is_empty()) {
+        (Some(tree), true) => Ok(tree),
+        _ => Err(ParseExprError::InvalidSyntax),
+    }
+}
+
+/// 后序遍历求值
+pub fn evaluate(tree: &BinaryTree<Expr>) -> f64 {
+    let op = match tree.value {
+        Expr::Num(x) => return x,
+        Expr::Op(op) => op,
+    };
+
+    let left = evaluate(tree.left.as_ref().unwrap());
+    if op == Op::Neg {
+        return -left;
+    }
+    let right = evaluate(tree.right.as_ref().unwrap());
+    match op {
+        Op::Add => left + right,
+        Op::Sub => left - right,
+        Op::Mul => left * right,
+        Op::Div => left / right,
+        Op::Pow => left.powf(right),
+        Op::Neg => unreachable!(),
+    }
+}
+
+/// 中序遍历输出中缀表达式, 每个运算都加上括号
+pub fn to_infix(tree: &BinaryTree<Expr>) -> String {
+    match tree.value {
+        Expr::Num(x) => x.to_string(),
+        Expr::Op(Op::Neg) => format!("(-{})", to_infix(tree.left.as_ref().unwrap())),
+        Expr::Op(op) => format!(
+            "({} {} {})",
+            to_infix(tree.left.as_ref().unwrap()),
+            op.symbol(),
+            to_infix(tree.right.as_ref().unwrap())
+        ),
+    }
+}
+
+/// 解析并计算表达式
+pub fn eval(s: &str) -> Result<f64, ParseExprError> {
+    let postfix = to_postfix(&tokenize(s)?)?;
+    Ok(evaluate(&build_tree(&postfix)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn postfix(s: &str) -> String {
+        to_postfix(&tokenize(s).unwrap())
+            .unwrap()
+            .iter()
+            .map(|token| match token {
+                Token::Num(x) => x.to_string(),
+                Token::Op(Op::Neg) => "neg".to_string(),
+                Token::Op(op) => op.symbol().to_string(),
+                _ => unreachable!(),
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn test_tokenize() {
+        use Token::*;
+        assert_eq!(
+            tokenize("-(1.5+ 2)*-3").unwrap(),
+            [
+                Op(super::Op::Neg),
+                LParen,
+                Num(1.5),
+                Op(super::Op::Add),
+                Num(2.0),
+                RParen,
+                Op(super::Op::Mul),
+                Op(super::Op::Neg),
+                Num(3.0),
+            ]
+        );
+        assert_eq!(tokenize("1 % 2"), Err(ParseExprError::InvalidChar('%')));
+        assert_eq!(tokenize("1.2.3"), Err(ParseExprError::InvalidNumber));
+    }
+
+    #[test]
+    fn test_to_postfix() {
+        assert_eq!(
+            postfix("3 + 4 * 2 / (1 - 5) ^ 2 ^ 3"),
+            "3 4 2 * 1 5 - 2 3 ^ ^ / +"
+        );
+        assert_eq!(postfix("1 - 2 - 3"), "1 2 - 3 -");
+        assert_eq!(postfix("-2 ^ 2"), "2 2 ^ neg");
+        assert_eq!(postfix("2 ^ -3"), "2 3 neg ^");
+        assert_eq!(postfix("--1 * 2"), "1 neg neg 2 *");
+    }
+
+    #[test]
+    fn test_eval() {
+        let cases = [
+            ("1 + 2 * 3", 7.0),
+            ("(1 + 2) * 3", 9.0),
+            ("10 / 4 - 1", 1.5),
+            ("1 - 2 - 3", -4.0),
+            ("2 ^ 3 ^ 2", 512.0),
+            ("-2 ^ 2", -4.0),
+            ("(-2) ^ 2", 4.0),
+            ("2 * -3", -6.0),
+            ("-(1 + 2) * 3", -9.0),
+            ("((7))", 7.0),
+            ("3 + 4 * 2 / (1 - 5) ^ 2 ^ 3", 3.0 + 8.0 / 65536.0),
+        ];
+        for (s, expected) in cases {
+            assert_eq!(eval(s), Ok(expected), "{s}");
+        }
+    }
+
+    #[test]
+    fn test_tree() {
+        let tokens = tokenize("1 - 2 * -(3 + 4)").unwrap();
+        let tree = build_tree(&to_postfix(&tokens).unwrap()).unwrap();
+        assert_eq!(tree.size(), 8);
+        assert_eq!(tree.height(), 5);
+        assert_eq!(tree.value, Expr::Op(Op::Sub));
+        assert_eq!(to_infix(&tree), "(1 - (2 * (-(3 + 4))))");
+        assert_eq!(evaluate(&tree), 15.0);
+        assert_eq!(eval(&to_infix(&tree)), Ok(15.0));
+
+        // 中序遍历去掉括号后就是原来的中缀表达式
+        let inorder: Vec<_> = tree.inorder().into_iter().copied().collect();
+        assert_eq!(inorder[0], Expr::Num(1.0));
+        assert_eq!(inorder[1], Expr::Op(Op::Sub));
+        assert_eq!(inorder.len(), 8);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(eval("(1 + 2"), Err(ParseExprError::UnbalancedParen));
+        assert_eq!(eval("1 + 2)"), Err(ParseExprError::UnbalancedParen));
+        assert_eq!(eval("1 +"), Err(ParseExprError::InvalidSyntax));
+        assert_eq!(eval("1 2"), Err(ParseExprError::InvalidSyntax));
+        assert_eq!(eval("()"), Err(ParseExprError::InvalidSyntax));
+        assert_eq!(eval(""), Err(ParseExprError::InvalidSyntax));
+        assert_eq!(eval("1 + a"), Err(ParseExprError::InvalidChar('a')));
+    }
+}
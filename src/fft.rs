@@ -0,0 +1,306 @@
+//! 快速傅里叶变换和快速数论变换
+//!
+//! - [OI Wiki - 快速傅里叶变换](https://oi-wiki.org/math/poly/fft/)
+//! - [OI Wiki - 快速数论变换](https://oi-wiki.org/math/poly/ntt/)
+//!
+//! 两个多项式相乘, 系数表示下需要 O(n²), 但是在点值表示下只需要把对应点的值相乘 O(n)
+//! FFT 选取 n 次单位根 ω⁰, ω¹ ... ωⁿ⁻¹ 作为求值点, 利用单位根的性质把求值和插值都加速到 O(nlogn):
+//!
+//! 把 A(x) 按奇偶系数拆成 A(x) = A₀(x²) + x·A₁(x²), 由于 ωₙ^(k + n/2) = -ωₙ^k, 有
+//!
+//! ```text
+//! A(ωₙ^k)         = A₀(ω_{n/2}^k) + ωₙ^k · A₁(ω_{n/2}^k)
+//! A(ωₙ^(k + n/2)) = A₀(ω_{n/2}^k) - ωₙ^k · A₁(ω_{n/2}^k)
+//! ```
+//!
+//! 这样一次求出两个点的值(蝴蝶操作), 递归的最底层的顺序恰好是下标二进制位翻转后的顺序,
+//! 所以可以先把数组按位翻转重排, 再自底向上迭代合并, 不需要递归
+//! 逆变换只需要把单位根换成 ω⁻¹ 最后除以 n
+//!
+//! 浮点 FFT 有精度误差, 在模 p = 998244353 = 119 × 2²³ + 1 意义下, 原根 g = 3 满足 g^((p-1)/n) 是 n 次单位根,
+//! 用它代替复数单位根就得到了没有精度问题的 NTT, 但结果是对 p 取模的
+
+use std::ops::{Add, Mul, Sub};
+
+use crate::number_theory::pow_mod;
+
+/// NTT 使用的模数
+pub const MOD: u64 = 998244353;
+
+/// MOD 的原根
+const G: u64 = 3;
+
+/// 复数
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Complex {
+    pub fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+
+    /// 模长为 1 辐角为 theta 的复数
+    fn from_angle(theta: f64) -> Self {
+        Self::new(theta.cos(), theta.sin())
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+
+    fn add(self, rhs: Complex) -> Complex {
+        Complex::new(self.re + rhs.re, self.im + rhs.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex;
+
+    fn sub(self, rhs: Complex) -> Complex {
+        Complex::new(self.re - rhs.re, self.im - rhs.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex;
+
+    fn mul(self, rhs: Complex) -> Complex {
+        Complex::new(
+            self.re * rhs.re - self.im * rhs.im,
+            self.re * rhs.im + self.im * rhs.re,
+        )
+    }
+}
+
+/// 按下标的二进制位翻转重排数组, 长度必须是 2 的幂
+fn bit_reverse<T>(a: &mut [T]) {
+    let n = a.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+
+        if i < j {
+            a.swap(i, j);
+        }
+    }
+}
+
+/// 原地进行 FFT, invert 为 true 时进行逆变换, 长度必须是 2 的幂
+pub fn fft(a: &mut [Complex], invert: bool) {
+    let n = a.len();
+    assert!(n.is_power_of_two(), "length must be a power of two");
+    bit_reverse(a);
+
+    let mut len = 2;
+    while len <= n {
+        let sign = if invert { -1.0 } else { 1.0 };
+        let w = Complex::from_angle(sign * 2.0 * std::f64::consts::PI / len as f64);
+        for chunk in a.chunks_mut(len) {
+            let mut wk = Complex::new(1.0, 0.0);
+            let (lo, hi) = chunk.split_at_mut(len / 2);
+            for (u, v) in lo.iter_mut().zip(hi) {
+                let t = wk * *v;
+                (*u, *v) = (*u + t, *u - t);
+                wk = wk * w;
+            }
+        }
+        len <<= 1;
+    }
+
+    if invert {
+        for x in a.iter_mut() {
+            x.re /= n as f64;
+            x.im /= n as f64;
+        }
+    }
+}
+
+/// 原地进行模 [`MOD`] 的 NTT, invert 为 true 时进行逆变换, 长度必须是 2 的幂
+pub fn ntt(a: &mut [u64], invert: bool) {
+    let n = a.len();
+    assert!(n.is_power_of_two(), "length must be a power of two");
+    assert!(
+        (MOD - 1).is_multiple_of(n as u64),
+        "length too large for NTT"
+    );
+    bit_reverse(a);
+
+    let mut len = 2;
+    while len <= n {
+        let mut w = pow_mod(G, (MOD - 1) / len as u64, MOD);
+        if invert {
+            w = pow_mod(w, MOD - 2, MOD);
+        }
+
+        for chunk in a.chunks_mut(len) {
+            let mut wk = 1;
+            let (lo, hi) = chunk.split_at_mut(len / 2);
+            for (u, v) in lo.iter_mut().zip(hi) {
+                let t = wk * *v % MOD;
+                (*u, *v) = ((*u + t) % MOD, (*u + MOD - t) % MOD);
+                wk = wk * w % MOD;
+            }
+        }
+        len <<= 1;
+    }
+
+    if invert {
+        let inv = pow_mod(n as u64, MOD - 2, MOD);
+        for x in a.iter_mut() {
+            *x = *x * inv % MOD;
+        }
+    }
+}
+
+/// 卷积结果的长度和需要补齐到的 2 的幂
+fn conv_size(a: usize, b: usize) -> (usize, usize) {
+    let len = a + b - 1;
+    (len, len.next_power_of_two())
+}
+
+/// 使用浮点 FFT 计算两个多项式的乘积(卷积)
+///
+/// 结果四舍五入为整数, 系数乘积之和在 2^50 左右以内时是精确的
+pub fn multiply(a: &[u64], b: &[u64]) -> Vec<u64> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+
+    let (len, n) = conv_size(a.len(), b.len());
+    let to_complex = |v: &[u64]| {
+        let mut res: Vec<_> = v.iter().map(|&x| Complex::new(x as f64, 0.0)).collect();
+        res.resize(n, Complex::default());
+        res
+    };
+
+    let (mut fa, mut fb) = (to_complex(a), to_complex(b));
+    fft(&mut fa, false);
+    fft(&mut fb, false);
+    for (x, y) in fa.iter_mut().zip(&fb) {
+        *x = *x * *y;
+    }
+    fft(&mut fa, true);
+
+    fa[..len].iter().map(|x| x.re.round() as u64).collect()
+}
+
+/// 使用 NTT 计算两个多项式在模 [`MOD`] 意义下的乘积
+pub fn multiply_mod(a: &[u64], b: &[u64]) -> Vec<u64> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+
+    let (len, n) = conv_size(a.len(), b.len());
+    let mut fa: Vec<_> = a.iter().map(|x| x % MOD).collect();
+    let mut fb: Vec<_> = b.iter().map(|x| x % MOD).collect();
+    fa.resize(n, 0);
+    fb.resize(n, 0);
+
+    ntt(&mut fa, false);
+    ntt(&mut fb, false);
+    for (x, y) in fa.iter_mut().zip(&fb) {
+        *x = *x * y % MOD;
+    }
+    ntt(&mut fa, true);
+
+    fa.truncate(len);
+    fa
+}
+
+/// 十进制大整数乘法, 把每一位数字看作多项式系数后做卷积再处理进位
+pub fn multiply_decimal(a: &str, b: &str) -> String {
+    let digits = |s: &str| -> Vec<u64> {
+        s.bytes()
+            .rev()
+            .map(|c| {
+                assert!(c.is_ascii_digit(), "invalid digit");
+                (c - b'0') as u64
+            })
+            .collect()
+    };
+
+    let mut res = multiply(&digits(a), &digits(b));
+    let mut carry = 0;
+    for x in res.iter_mut() {
+        *x += carry;
+        carry = *x / 10;
+        *x %= 10;
+    }
+    while carry > 0 {
+        res.push(carry % 10);
+        carry /= 10;
+    }
+    while res.len() > 1 && res.last() == Some(&0) {
+        res.pop();
+    }
+
+    if res.is_empty() {
+        return "0".to_string();
+    }
+    res.iter()
+        .rev()
+        .map(|d| char::from(b'0' + *d as u8))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    fn naive(a: &[u64], b: &[u64], m: Option<u64>) -> Vec<u64> {
+        let mut res = vec![0; a.len() + b.len() - 1];
+        for (i, x) in a.iter().enumerate() {
+            for (j, y) in b.iter().enumerate() {
+                res[i + j] = match m {
+                    Some(m) => (res[i + j] + x * y % m) % m,
+                    None => res[i + j] + x * y,
+                };
+            }
+        }
+        res
+    }
+
+    #[test]
+    fn test_multiply() {
+        assert_eq!(multiply(&[1, 2, 3], &[4, 5]), vec![4, 13, 22, 15]);
+        assert_eq!(multiply_mod(&[1, 2, 3], &[4, 5]), vec![4, 13, 22, 15]);
+        assert!(multiply(&[], &[1]).is_empty());
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..20 {
+            let a: Vec<u64> = (0..rng.gen_range(1..300))
+                .map(|_| rng.gen_range(0..10000))
+                .collect();
+            let b: Vec<u64> = (0..rng.gen_range(1..300))
+                .map(|_| rng.gen_range(0..10000))
+                .collect();
+            assert_eq!(multiply(&a, &b), naive(&a, &b, None));
+
+            let a: Vec<u64> = a.iter().map(|_| rng.gen_range(0..MOD)).collect();
+            assert_eq!(multiply_mod(&a, &b), naive(&a, &b, Some(MOD)));
+        }
+    }
+
+    #[test]
+    fn test_multiply_decimal() {
+        assert_eq!(multiply_decimal("12", "34"), "408");
+        assert_eq!(multiply_decimal("0", "12345"), "0");
+        assert_eq!(
+            multiply_decimal("123456789123456789", "987654321987654321"),
+            (123456789123456789u128 * 987654321987654321u128).to_string()
+        );
+
+        let nines = "9".repeat(1000);
+        let expect = format!("{}8{}1", "9".repeat(999), "0".repeat(999));
+        assert_eq!(multiply_decimal(&nines, &nines), expect);
+    }
+}
@@ -0,0 +1,149 @@
+//! 近似成员查询过滤器的统一接口
+//!
+//! 布隆过滤器、计数布隆过滤器和布谷鸟过滤器都只保证没有假阴性:
+//! 插入过的 key 一定能查到, 没有插入过的 key 以一定概率也能查到(假阳性)
+//!
+//! [`false_positive_rate`] 向过滤器插入 n 个随机 key, 再用另外一批没有插入过的 key 统计实际的假阳性率,
+//! 可以用来比较不同过滤器在相同空间下的表现
+//!
+//! ```text
+//! cargo bench --bench filters
+//! ```
+
+use std::collections::HashSet;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// 近似成员查询过滤器
+pub trait Filter {
+    /// 插入 key, 过滤器已满插入失败时返回 false
+    fn insert(&mut self, key: &[u8]) -> bool;
+
+    /// key 是否可能存在
+    fn contains(&self, key: &[u8]) -> bool;
+
+    /// 估计已经插入的元素数量
+    fn len_estimate(&self) -> usize;
+}
+
+/// 一次假阳性测量的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FalsePositiveReport {
+    pub inserted: usize,        // 成功插入的 key 数量
+    pub failed: usize,          // 插入失败的 key 数量
+    pub false_negatives: usize, // 插入成功却查不到的 key 数量, 正确的实现应该为 0
+    pub probes: usize,          // 用于测量的未插入 key 数量
+    pub false_positives: usize, // 未插入却查到的 key 数量
+}
+
+impl FalsePositiveReport {
+    /// 实际的假阳性率
+    pub fn rate(&self) -> f64 {
+        if self.probes == 0 {
+            0.0
+        } else {
+            self.false_positives as f64 / self.probes as f64
+        }
+    }
+}
+
+/// 用固定种子生成 n 个随机 key 插入过滤器, 再用 probes 个不同的随机 key 测量假阳性率
+pub fn false_positive_rate<F: Filter + ?Sized>(
+    filter: &mut F,
+    n: usize,
+    probes: usize,
+    seed: u64,
+) -> FalsePositiveReport {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut keys = HashSet::with_capacity(n);
+    while keys.len() < n {
+        keys.insert(rng.gen::<u64>());
+    }
+
+    let mut report = FalsePositiveReport {
+        inserted: 0,
+        failed: 0,
+        false_negatives: 0,
+        probes,
+        false_positives: 0,
+    };
+
+    let mut inserted = vec![];
+    for key in &keys {
+        if filter.insert(&key.to_le_bytes()) {
+            report.inserted += 1;
+            inserted.push(*key);
+        } else {
+            report.failed += 1;
+        }
+    }
+
+    report.false_negatives = inserted
+        .iter()
+        .filter(|key| !filter.contains(&key.to_le_bytes()))
+        .count();
+
+    let mut count = 0;
+    while count < probes {
+        let key = rng.gen::<u64>();
+        if keys.contains(&key) {
+            continue;
+        }
+
+        count += 1;
+        if filter.contains(&key.to_le_bytes()) {
+            report.false_positives += 1;
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bloom_filter::{BloomFilter, CountingBloomFilter};
+    use crate::cuckoo_filter::CuckooFilter;
+
+    /// 布隆过滤器的理论假阳性率 `(1 - e^(-kn/m))^k`
+    fn bloom_rate(m: usize, k: usize, n: usize) -> f64 {
+        (1.0 - (-(k as f64) * n as f64 / m as f64).exp()).powi(k as i32)
+    }
+
+    #[test]
+    fn test_false_positive_rate() {
+        // 三种过滤器都占用 4096 字节: 布谷鸟过滤器 1024 个桶每个 4 个 8 位指纹,
+        // 布隆过滤器 32768 位, 计数布隆过滤器 4096 个 8 位计数器
+        let n = 2000;
+        let filters: Vec<(&str, Box<dyn Filter>, f64)> = vec![
+            (
+                "bloom",
+                Box::new(BloomFilter::new(4096 * 8, 7)),
+                bloom_rate(4096 * 8, 7, n),
+            ),
+            (
+                "counting",
+                Box::new(CountingBloomFilter::new(4096, 2)),
+                bloom_rate(4096, 2, n),
+            ),
+            // 每次查询检查 2 个桶共 8 个指纹, 每个指纹碰撞的概率为 1/256
+            ("cuckoo", Box::new(CuckooFilter::new()), 8.0 / 256.0),
+        ];
+
+        for (name, mut filter, expect) in filters {
+            let report = false_positive_rate(filter.as_mut(), n, 100_000, 0x5eed);
+            assert_eq!(report.failed, 0, "{name}");
+            assert_eq!(report.false_negatives, 0, "{name}");
+            assert!(
+                report.rate() <= expect * 1.2 + 0.001,
+                "{name}: {} (expect {expect})",
+                report.rate()
+            );
+
+            // 估计的元素数量误差在 5% 以内
+            let len = filter.len_estimate() as f64;
+            assert!((len - n as f64).abs() / (n as f64) < 0.05, "{name} {len}");
+        }
+    }
+}
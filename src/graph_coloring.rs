@@ -0,0 +1,296 @@
+//! 图着色和独立集
+//!
+//! - [wikipedia - Greedy coloring](https://en.wikipedia.org/wiki/Greedy_coloring)
+//! - [wikipedia - DSatur](https://en.wikipedia.org/wiki/DSatur)
+//! - [wikipedia - Maximal independent set](https://en.wikipedia.org/wiki/Maximal_independent_set)
+//!
+//! 给无向图的每个节点染色, 使得相邻的节点颜色不同, 求最少颜色数(色数)是 NP 困难问题, 这里实现几种常用的启发式算法,
+//! 颜色用 `0..k` 编号, 自环无法合法染色所以忽略自环
+//!
+//! - 贪心着色: 按某个顺序依次给每个节点染上邻居没有用过的最小颜色, 节点的颜色不会超过它的度数,
+//!   所以最多使用 Δ+1 种颜色(Δ 为最大度数); 结果很依赖顺序, 总存在一个顺序能得到最优解, 也存在顺序让二分图用掉 n/2 种颜色
+//! - Welsh-Powell: 按度数从大到小的顺序贪心, 度数大的节点约束多, 尽早染色
+//! - DSATUR: 每次选择饱和度(邻居中已经出现的不同颜色数)最大的未染色节点, 相同时选择度数大的,
+//!   相当于动态地决定贪心顺序, 对二分图一定能得到最优解
+//!
+//! 独立集是两两不相邻的节点集合, 最大独立集同样是 NP 困难的;
+//! 贪心地每次选择剩余图中度数最小的节点加入独立集, 再删掉它和它的邻居, 得到的是一个极大独立集(不能再加入任何节点)
+//! 同一种颜色的节点就是一个独立集, 所以 k 染色可以把节点划分为 k 个独立集
+
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashSet};
+
+use crate::graph_traversal::Graph;
+
+/// 检查 color 是否为合法染色, 自环除外
+pub fn is_proper_coloring(g: &Graph, color: &[usize]) -> bool {
+    (0..g.len()).all(|u| {
+        g.neighbors(u)
+            .iter()
+            .all(|&v| u == v || color[u] != color[v])
+    })
+}
+
+/// 按 order 的顺序贪心着色, order 必须是所有节点的一个排列, 返回颜色数和每个节点的颜色
+pub fn greedy_coloring_with_order(g: &Graph, order: &[usize]) -> (usize, Vec<usize>) {
+    assert_eq!(order.len(), g.len(), "order must be a permutation of nodes");
+
+    let mut color = vec![usize::MAX; g.len()];
+    // used[c] == u 表示颜色 c 被 u 的邻居用过, 用节点编号做标记可以避免每次清空
+    let mut used = vec![usize::MAX; g.len() + 1];
+    let mut count = 0;
+    for &u in order {
+        for &v in g.neighbors(u) {
+            if color[v] != usize::MAX {
+                used[color[v]] = u;
+            }
+        }
+
+        let c = (0..).find(|&c| used[c] != u).expect("at most deg+1 colors");
+        color[u] = c;
+        count = count.max(c + 1);
+    }
+
+    (count, color)
+}
+
+/// 按节点编号的顺序贪心着色
+///
+/// ```
+/// use impx::graph_coloring::greedy_coloring;
+/// use impx::graph_traversal::Graph;
+///
+/// let g = Graph::from_edges(4, &[(0, 1), (1, 2), (2, 3)], false);
+/// assert_eq!(greedy_coloring(&g), (2, vec![0, 1, 0, 1]));
+/// ```
+pub fn greedy_coloring(g: &Graph) -> (usize, Vec<usize>) {
+    let order: Vec<_> = (0..g.len()).collect();
+    greedy_coloring_with_order(g, &order)
+}
+
+/// Welsh-Powell 算法, 按度数从大到小的顺序贪心着色
+///
+/// ```
+/// use impx::graph_coloring::welsh_powell;
+/// use impx::graph_traversal::Graph;
+///
+/// // 星形图的中心度数最大, 最先染色
+/// let g = Graph::from_edges(4, &[(1, 0), (1, 2), (1, 3)], false);
+/// assert_eq!(welsh_powell(&g), (2, vec![1, 0, 1, 1]));
+/// ```
+pub fn welsh_powell(g: &Graph) -> (usize, Vec<usize>) {
+    let mut order: Vec<_> = (0..g.len()).collect();
+    // 稳定排序, 度数相同时保持编号顺序
+    order.sort_by_key(|&u| Reverse(g.neighbors(u).len()));
+    greedy_coloring_with_order(g, &order)
+}
+
+/// DSATUR 算法, 返回颜色数和每个节点的颜色, O((n + m) logn)
+///
+/// ```
+/// use impx::graph_coloring::dsatur;
+/// use impx::graph_traversal::Graph;
+///
+/// // 奇环需要 3 种颜色
+/// let g = Graph::from_edges(5, &[(0, 1), (1, 2), (2, 3), (3, 4), (4, 0)], false);
+/// assert_eq!(dsatur(&g).0, 3);
+/// ```
+pub fn dsatur(g: &Graph) -> (usize, Vec<usize>) {
+    let n = g.len();
+    let degree: Vec<_> = (0..n).map(|u| g.neighbors(u).len()).collect();
+    // 每个节点的邻居中出现过的颜色, 集合大小就是饱和度
+    let mut adj_colors = vec![HashSet::new(); n];
+    // 按 (饱和度, 度数, 编号越小越优先) 排序的未染色节点, 最大的就是下一个要染色的节点
+    let mut queue: BTreeSet<_> = (0..n).map(|u| (0, degree[u], Reverse(u))).collect();
+
+    let mut color = vec![usize::MAX; n];
+    let mut count = 0;
+    while let Some((_, _, Reverse(u))) = queue.pop_last() {
+        let c = (0..).find(|c| !adj_colors[u].contains(c)).unwrap();
+        color[u] = c;
+        count = count.max(c + 1);
+
+        for &v in g.neighbors(u) {
+            if color[v] != usize::MAX || adj_colors[v].contains(&c) {
+                continue;
+            }
+
+            // 饱和度变化, 重新放入队列
+            queue.remove(&(adj_colors[v].len(), degree[v], Reverse(v)));
+            adj_colors[v].insert(c);
+            queue.insert((adj_colors[v].len(), degree[v], Reverse(v)));
+        }
+    }
+
+    (count, color)
+}
+
+/// 贪心求极大独立集, 每次选择剩余图中度数最小的节点, 返回升序排列的节点编号
+///
+/// ```
+/// use impx::graph_coloring::greedy_independent_set;
+/// use impx::graph_traversal::Graph;
+///
+/// // 星形图选择所有叶子比选择中心更好
+/// let g = Graph::from_edges(4, &[(0, 1), (0, 2), (0, 3)], false);
+/// assert_eq!(greedy_independent_set(&g), vec![1, 2, 3]);
+/// ```
+pub fn greedy_independent_set(g: &Graph) -> Vec<usize> {
+    let n = g.len();
+    let mut removed = vec![false; n];
+    let mut degree: Vec<_> = (0..n)
+        .map(|u| g.neighbors(u).iter().filter(|&&v| v != u).count())
+        .collect();
+    let mut queue: BTreeSet<_> = (0..n).map(|u| (degree[u], u)).collect();
+
+    let mut set = vec![];
+    while let Some((_, u)) = queue.pop_first() {
+        set.push(u);
+        removed[u] = true;
+
+        // 删除 u 的邻居, 邻居的邻居度数减少
+        for &v in g.neighbors(u) {
+            if removed[v] {
+                continue;
+            }
+
+            removed[v] = true;
+            queue.remove(&(degree[v], v));
+            for &w in g.neighbors(v) {
+                if !removed[w] {
+                    queue.remove(&(degree[w], w));
+                    degree[w] -= 1;
+                    queue.insert((degree[w], w));
+                }
+            }
+        }
+    }
+
+    set.sort_unstable();
+    set
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    /// 简单无向图, 没有自环和重边
+    fn random_graph(n: usize, p: f64) -> Graph {
+        let mut rng = rand::thread_rng();
+        let mut g = Graph::new(n);
+        for u in 0..n {
+            for v in u + 1..n {
+                if rng.gen_bool(p) {
+                    g.add_undirected_edge(u, v);
+                }
+            }
+        }
+        g
+    }
+
+    /// 枚举 k 从小到大检查是否存在 k 染色
+    fn chromatic_number(g: &Graph) -> usize {
+        fn search(g: &Graph, k: usize, color: &mut Vec<usize>) -> bool {
+            let u = color.len();
+            if u == g.len() {
+                return true;
+            }
+            for c in 0..k {
+                if g.neighbors(u).iter().all(|&v| v >= u || color[v] != c) {
+                    color.push(c);
+                    if search(g, k, color) {
+                        return true;
+                    }
+                    color.pop();
+                }
+            }
+            false
+        }
+
+        (0..=g.len()).find(|&k| search(g, k, &mut vec![])).unwrap()
+    }
+
+    #[test]
+    fn test_coloring_random() {
+        for _ in 0..100 {
+            let g = random_graph(9, 0.4);
+            let chi = chromatic_number(&g);
+            let max_degree = (0..g.len()).map(|u| g.neighbors(u).len()).max().unwrap();
+
+            for (count, color) in [greedy_coloring(&g), welsh_powell(&g), dsatur(&g)] {
+                assert!(is_proper_coloring(&g, &color));
+                assert_eq!(color.iter().max().map_or(0, |&c| c + 1), count);
+                assert!(chi <= count && count <= max_degree + 1);
+            }
+
+            // 二分图上 DSATUR 是最优的
+            if crate::graph_traversal::bipartite(&g).is_some() {
+                assert_eq!(dsatur(&g).0, chi);
+            }
+        }
+    }
+
+    #[test]
+    fn test_coloring_order() {
+        // 皇冠图: u_i 和 v_j (i != j) 相连, 是二分图
+        // 按 u0 v0 u1 v1 ... 的顺序贪心会用掉 n 种颜色
+        let n = 6;
+        let mut g = Graph::new(2 * n);
+        for i in 0..n {
+            for j in 0..n {
+                if i != j {
+                    g.add_undirected_edge(2 * i, 2 * j + 1);
+                }
+            }
+        }
+
+        assert_eq!(greedy_coloring(&g).0, n);
+        let order: Vec<_> = (0..n)
+            .map(|i| 2 * i)
+            .chain((0..n).map(|i| 2 * i + 1))
+            .collect();
+        assert_eq!(greedy_coloring_with_order(&g, &order).0, 2);
+        assert_eq!(dsatur(&g).0, 2);
+
+        // 自环被忽略
+        let mut g = Graph::from_edges(3, &[(0, 1), (1, 2)], false);
+        g.add_undirected_edge(1, 1);
+        for (count, color) in [greedy_coloring(&g), welsh_powell(&g), dsatur(&g)] {
+            assert_eq!(count, 2);
+            assert!(is_proper_coloring(&g, &color));
+        }
+        assert_eq!(greedy_independent_set(&g), vec![0, 2]);
+        assert_eq!(greedy_coloring(&Graph::new(0)), (0, vec![]));
+        assert_eq!(dsatur(&Graph::new(0)), (0, vec![]));
+    }
+
+    #[test]
+    fn test_independent_set() {
+        for _ in 0..100 {
+            let g = random_graph(12, 0.3);
+            let set = greedy_independent_set(&g);
+            let mut in_set = vec![false; g.len()];
+            for &u in &set {
+                in_set[u] = true;
+            }
+
+            // 独立: 集合内没有边; 极大: 集合外的节点都和集合内某个节点相邻
+            for u in 0..g.len() {
+                let adjacent = g.neighbors(u).iter().any(|&v| in_set[v]);
+                assert_eq!(in_set[u], !adjacent);
+            }
+
+            let best = (0..1u32 << 12)
+                .filter(|&mask| {
+                    (0..12).all(|u| {
+                        mask >> u & 1 == 0 || g.neighbors(u).iter().all(|&v| mask >> v & 1 == 0)
+                    })
+                })
+                .map(|mask| mask.count_ones() as usize)
+                .max()
+                .unwrap();
+            assert!(set.len() <= best);
+        }
+    }
+}
@@ -0,0 +1,381 @@
+//! 割点、桥和双连通分量
+//!
+//! - [OI Wiki - 割点和桥](https://oi-wiki.org/graph/cut/)
+//! - [OI Wiki - 双连通分量](https://oi-wiki.org/graph/bcc/)
+//!
+//! 对无向图做 DFS, 记 `dfn[u]` 为 u 第一次被访问的时间戳, `low[u]` 为 u 的子树中的节点
+//! 经过最多一条非树边(返祖边)能到达的最小时间戳
+//!
+//! - 桥: 树边 (u, v) 满足 `low[v] > dfn[u]`, 说明 v 的子树无论如何都回不到 u 及其祖先, 删掉这条边图就断开了
+//! - 割点: 非根节点 u 存在子节点 v 满足 `low[v] >= dfn[u]`, 说明 v 的子树只能通过 u 和外面连通;
+//!   根节点是割点当且仅当它在 DFS 树上有两个以上的子节点
+//!
+//! 点双连通分量(块): 不含割点的极大连通子图, 两个块之间最多共用一个节点(割点)  
+//! DFS 时把访问到的节点压栈, 发现 `low[v] >= dfn[u]` 时把栈中 v 及之后的节点弹出, 它们和 u 一起构成一个块
+//!
+//! 边双连通分量: 不含桥的极大连通子图, 删去所有的桥之后剩下的每个连通分量就是一个边双连通分量
+//!
+//! 有重边时判断桥要跳过的是来时的那条边而不是父节点, 所以这里按边的编号记录父边
+//!
+//! 有向图的强连通分量(SCC)也可以用同样的时间戳求出: 这时 `low[u]` 只能用还在栈中的节点更新,
+//! `low[u] == dfn[u]` 说明 u 是一个强连通分量中第一个被访问的节点, 把栈中 u 及之后的节点弹出就是这个分量  
+//! 一个分量被弹出时它能到达的分量都已经被弹出了, 所以分量的编号顺序是缩点后的 DAG 的逆拓扑序
+
+use crate::graph_traversal::Graph;
+
+/// 无向图的连通性分析结果, 节点编号为 `0..n`
+#[derive(Debug, Clone, Default)]
+pub struct Connectivity {
+    /// 割点, 从小到大
+    pub cut_vertices: Vec<usize>,
+    /// 桥在输入的边列表中的编号, 从小到大
+    pub bridges: Vec<usize>,
+    /// 点双连通分量, 每个分量中的节点从小到大, 孤立的节点单独构成一个分量
+    pub blocks: Vec<Vec<usize>>,
+    /// 边双连通分量, 每个分量中的节点从小到大
+    pub two_edge_components: Vec<Vec<usize>>,
+}
+
+struct Tarjan<'a> {
+    adj: &'a [Vec<(usize, usize)>],
+    timer: usize,
+    dfn: Vec<Option<usize>>,
+    low: Vec<usize>,
+    stack: Vec<usize>,
+    is_cut: Vec<bool>,
+    is_bridge: Vec<bool>,
+    blocks: Vec<Vec<usize>>,
+}
+
+impl Tarjan<'_> {
+    fn dfs(&mut self, u: usize, parent_edge: Option<usize>) {
+        self.dfn[u] = Some(self.timer);
+        self.low[u] = self.timer;
+        self.timer += 1;
+        self.stack.push(u);
+
+        let mut children = 0;
+        for &(v, id) in &self.adj[u] {
+            if Some(id) == parent_edge {
+                continue;
+            }
+
+            match self.dfn[v] {
+                Some(d) => self.low[u] = self.low[u].min(d),
+                None => {
+                    children += 1;
+                    self.dfs(v, Some(id));
+                    self.low[u] = self.low[u].min(self.low[v]);
+
+                    let du = self.dfn[u].unwrap();
+                    if self.low[v] > du {
+                        self.is_bridge[id] = true;
+                    }
+                    if self.low[v] >= du {
+                        if parent_edge.is_some() || children > 1 {
+                            self.is_cut[u] = true;
+                        }
+
+                        // 弹出 v 的子树中还没有归属的节点, 和 u 一起构成一个块
+                        let mut block = vec![u];
+                        while let Some(x) = self.stack.pop() {
+                            block.push(x);
+                            if x == v {
+                                break;
+                            }
+                        }
+                        block.sort();
+                        self.blocks.push(block);
+                    }
+                }
+            }
+        }
+
+        // 没有任何邻居的孤立节点
+        if parent_edge.is_none() && children == 0 {
+            self.stack.pop();
+            self.blocks.push(vec![u]);
+        }
+    }
+}
+
+/// 使用 Tarjan 算法求割点、桥和双连通分量
+///
+/// ```
+/// use impx::graph_connectivity::connectivity;
+///
+/// // 两个三角形 0-1-2 和 3-4-5 通过边 (2, 3) 相连
+/// let edges = [(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 5), (5, 3)];
+/// let c = connectivity(6, &edges);
+/// assert_eq!(c.cut_vertices, vec![2, 3]);
+/// assert_eq!(c.bridges, vec![3]);
+/// assert_eq!(c.blocks.len(), 3);
+/// assert_eq!(c.two_edge_components, vec![vec![0, 1, 2], vec![3, 4, 5]]);
+/// ```
+pub fn connectivity(n: usize, edges: &[(usize, usize)]) -> Connectivity {
+    let mut adj = vec![vec![]; n];
+    for (id, &(u, v)) in edges.iter().enumerate() {
+        adj[u].push((v, id));
+        if u != v {
+            adj[v].push((u, id));
+        }
+    }
+
+    let mut t = Tarjan {
+        adj: &adj,
+        timer: 0,
+        dfn: vec![None; n],
+        low: vec![0; n],
+        stack: vec![],
+        is_cut: vec![false; n],
+        is_bridge: vec![false; edges.len()],
+        blocks: vec![],
+    };
+    for u in 0..n {
+        if t.dfn[u].is_none() {
+            t.dfs(u, None);
+        }
+    }
+
+    // 删去所有桥之后求连通分量
+    let mut label = vec![None; n];
+    let mut two_edge_components = vec![];
+    for s in 0..n {
+        if label[s].is_some() {
+            continue;
+        }
+
+        label[s] = Some(two_edge_components.len());
+        let mut component = vec![];
+        let mut stack = vec![s];
+        while let Some(u) = stack.pop() {
+            component.push(u);
+            for &(v, id) in &adj[u] {
+                if !t.is_bridge[id] && label[v].is_none() {
+                    label[v] = label[s];
+                    stack.push(v);
+                }
+            }
+        }
+        component.sort();
+        two_edge_components.push(component);
+    }
+
+    Connectivity {
+        cut_vertices: (0..n).filter(|&u| t.is_cut[u]).collect(),
+        bridges: (0..edges.len()).filter(|&id| t.is_bridge[id]).collect(),
+        blocks: t.blocks,
+        two_edge_components,
+    }
+}
+
+struct TarjanScc<'a> {
+    g: &'a Graph,
+    timer: usize,
+    dfn: Vec<Option<usize>>,
+    low: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    comp: Vec<usize>,
+    count: usize,
+}
+
+impl TarjanScc<'_> {
+    fn dfs(&mut self, u: usize) {
+        self.dfn[u] = Some(self.timer);
+        self.low[u] = self.timer;
+        self.timer += 1;
+        self.stack.push(u);
+        self.on_stack[u] = true;
+
+        for &v in self.g.neighbors(u) {
+            match self.dfn[v] {
+                None => {
+                    self.dfs(v);
+                    self.low[u] = self.low[u].min(self.low[v]);
+                }
+                // 已经属于其他分量的节点不能用来更新
+                Some(d) if self.on_stack[v] => self.low[u] = self.low[u].min(d),
+                _ => {}
+            }
+        }
+
+        if Some(self.low[u]) == self.dfn[u] {
+            while let Some(x) = self.stack.pop() {
+                self.on_stack[x] = false;
+                self.comp[x] = self.count;
+                if x == u {
+                    break;
+                }
+            }
+            self.count += 1;
+        }
+    }
+}
+
+/// 有向图的强连通分量, 返回分量个数和每个节点所属分量的编号, 编号是缩点后 DAG 的逆拓扑序
+///
+/// ```
+/// use impx::graph_connectivity::strongly_connected_components;
+/// use impx::graph_traversal::Graph;
+///
+/// let g = Graph::from_edges(4, &[(0, 1), (1, 0), (1, 2), (2, 3), (3, 2)], true);
+/// assert_eq!(strongly_connected_components(&g), (2, vec![1, 1, 0, 0]));
+/// ```
+pub fn strongly_connected_components(g: &Graph) -> (usize, Vec<usize>) {
+    let n = g.len();
+    let mut t = TarjanScc {
+        g,
+        timer: 0,
+        dfn: vec![None; n],
+        low: vec![0; n],
+        on_stack: vec![false; n],
+        stack: vec![],
+        comp: vec![0; n],
+        count: 0,
+    };
+    for u in 0..n {
+        if t.dfn[u].is_none() {
+            t.dfs(u);
+        }
+    }
+
+    (t.count, t.comp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    /// 忽略被删除的节点和边之后的连通分量数量以及节点所属的分量
+    fn components(
+        n: usize,
+        edges: &[(usize, usize)],
+        skip_vertex: Option<usize>,
+        skip_edge: Option<usize>,
+    ) -> (usize, Vec<usize>) {
+        let mut label = vec![usize::MAX; n];
+        let mut count = 0;
+        for s in (0..n).filter(|&s| Some(s) != skip_vertex) {
+            if label[s] != usize::MAX {
+                continue;
+            }
+            label[s] = count;
+            let mut stack = vec![s];
+            while let Some(u) = stack.pop() {
+                for (id, &(a, b)) in edges.iter().enumerate() {
+                    if Some(id) == skip_edge || Some(a) == skip_vertex || Some(b) == skip_vertex {
+                        continue;
+                    }
+                    for (x, y) in [(a, b), (b, a)] {
+                        if x == u && label[y] == usize::MAX {
+                            label[y] = count;
+                            stack.push(y);
+                        }
+                    }
+                }
+            }
+            count += 1;
+        }
+        (count, label)
+    }
+
+    #[test]
+    fn test_connectivity_random() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..200 {
+            let n = rng.gen_range(1..10);
+            let m = rng.gen_range(0..15);
+            let edges: Vec<_> = (0..m)
+                .map(|_| (rng.gen_range(0..n), rng.gen_range(0..n)))
+                .collect();
+            let c = connectivity(n, &edges);
+            let (base, _) = components(n, &edges, None, None);
+
+            // 割点: 删除之后其余节点的连通分量变多
+            let cut: Vec<_> = (0..n)
+                .filter(|&u| components(n, &edges, Some(u), None).0 > base)
+                .collect();
+            assert_eq!(c.cut_vertices, cut);
+
+            // 桥: 删除之后连通分量变多
+            let bridges: Vec<_> = (0..m)
+                .filter(|&id| components(n, &edges, None, Some(id)).0 > base)
+                .collect();
+            assert_eq!(c.bridges, bridges);
+
+            // 边双连通: 删除任意一条边之后两个节点仍然连通
+            let labels: Vec<_> = (0..m)
+                .map(|id| components(n, &edges, None, Some(id)).1)
+                .collect();
+            let base_label = components(n, &edges, None, None).1;
+            let mut expect: Vec<Vec<usize>> = vec![];
+            for u in 0..n {
+                let same = |v: usize| {
+                    base_label[u] == base_label[v] && labels.iter().all(|l| l[u] == l[v])
+                };
+                if expect.iter().all(|comp| !same(comp[0])) {
+                    expect.push((0..n).filter(|&v| same(v)).collect());
+                }
+            }
+            assert_eq!(c.two_edge_components, expect);
+
+            // 块: 每条边恰好属于一个块, 属于多个块的节点恰好是割点
+            for &(u, v) in edges.iter().filter(|(u, v)| u != v) {
+                let count = c
+                    .blocks
+                    .iter()
+                    .filter(|b| b.contains(&u) && b.contains(&v))
+                    .count();
+                assert_eq!(count, 1);
+            }
+            for u in 0..n {
+                let count = c.blocks.iter().filter(|b| b.contains(&u)).count();
+                assert!(count >= 1);
+                assert_eq!(count > 1, c.cut_vertices.contains(&u));
+            }
+            for (i, a) in c.blocks.iter().enumerate() {
+                for b in &c.blocks[i + 1..] {
+                    assert!(a.iter().filter(|x| b.contains(x)).count() <= 1);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_multi_edges() {
+        // 重边不是桥
+        let c = connectivity(3, &[(0, 1), (0, 1), (1, 2)]);
+        assert_eq!(c.bridges, vec![2]);
+        assert_eq!(c.cut_vertices, vec![1]);
+        assert_eq!(c.blocks, vec![vec![1, 2], vec![0, 1]]);
+    }
+
+    #[test]
+    fn test_scc() {
+        use crate::graph_traversal::bfs;
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..50 {
+            let n = rng.gen_range(1..20);
+            let edges: Vec<_> = (0..rng.gen_range(0..40))
+                .map(|_| (rng.gen_range(0..n), rng.gen_range(0..n)))
+                .collect();
+            let g = Graph::from_edges(n, &edges, true);
+            let (count, comp) = strongly_connected_components(&g);
+
+            let reach: Vec<_> = (0..n).map(|u| bfs(&g, u)).collect();
+            for u in 0..n {
+                for v in 0..n {
+                    // 互相可达当且仅当在同一个分量中
+                    let mutual = reach[u].reached(v) && reach[v].reached(u);
+                    assert_eq!(mutual, comp[u] == comp[v]);
+                }
+            }
+            // 逆拓扑序: 边只会从编号大的分量指向编号小的分量
+            assert!(edges.iter().all(|&(u, v)| comp[u] >= comp[v]));
+            assert_eq!(comp.iter().max().map_or(0, |m| m + 1), count);
+        }
+    }
+}
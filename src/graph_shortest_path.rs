@@ -1,6 +1,9 @@
 //! 图最短路径
 
-use std::collections::BinaryHeap;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+
+use crate::binary_heap::IndexedBinaryHeap;
 
 /// Floyd 算法
 pub fn floyd(
@@ -12,9 +15,9 @@ pub fn floyd(
     // f[k][x][y] 表示在子图 1..k 内从 x 到 y 的最短路径
     let mut f = vec![vec![vec![None; n + 1]; n + 1]; n + 1];
 
-    // 如果两节点有直接连接则设置对应路径权重
+    // 如果两节点有直接连接则设置对应路径权重, 有重边时取最小的
     for &(u, v, w) in edges {
-        f[0][u][v] = Some(w);
+        f[0][u][v] = min_option_usize(f[0][u][v], Some(w));
     }
 
     // 每个节点和自己的连接路径权重为 0
@@ -49,6 +52,65 @@ impl PartialOrd for NodeDistance {
     }
 }
 
+/// 单源最短路的结果, 节点编号为 1..N
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShortestPaths {
+    /// 源节点
+    pub src: usize,
+    /// 到各个节点的最短距离, 不可达时为 None
+    pub dist: Vec<Option<usize>>,
+    /// 最短路径上的前驱节点, 源节点和不可达的节点为 None
+    pub prev: Vec<Option<usize>>,
+}
+
+impl ShortestPaths {
+    fn new(n: usize, src: usize) -> Self {
+        let mut dist = vec![None; n + 1];
+        dist[src] = Some(0);
+        Self {
+            src,
+            dist,
+            prev: vec![None; n + 1],
+        }
+    }
+
+    /// 从源节点到 dst 的最短路径, 沿着前驱节点往回走再反转
+    pub fn path_to(&self, dst: usize) -> Option<Vec<usize>> {
+        self.dist[dst]?;
+
+        let mut path = vec![dst];
+        let mut cur = dst;
+        while let Some(p) = self.prev[cur] {
+            path.push(p);
+            cur = p;
+        }
+        path.reverse();
+
+        Some(path)
+    }
+
+    /// 如果经过 u 到 v 更近则更新 v
+    fn relax(&mut self, u: usize, v: usize, w: usize) -> bool {
+        let d = self.dist[u].unwrap() + w;
+        if self.dist[v].is_some_and(|dv| dv <= d) {
+            return false;
+        }
+
+        self.dist[v] = Some(d);
+        self.prev[v] = Some(u);
+        true
+    }
+}
+
+/// 邻接表
+fn adjacency(n: usize, edges: &[(usize, usize, usize)]) -> Vec<Vec<(usize, usize)>> {
+    let mut g = vec![vec![]; n + 1];
+    for &(u, v, w) in edges {
+        g[u].push((v, w));
+    }
+    g
+}
+
 /// Dijkstra 算法
 pub fn dijkstra(
     n: usize,                        // 节点个数 编号为 1..N
@@ -56,34 +118,244 @@ pub fn dijkstra(
     src: usize,                      // 源节点
     dst: usize,                      // 目标节点
 ) -> Option<usize> {
-    let mut g = vec![vec![]; n + 1];
-    for &edge in edges {
-        let (u, v, w) = edge;
-        g[u].push((v, w));
-    }
+    dijkstra_all(n, edges, src).dist[dst]
+}
 
-    // distance 记录 src 到各个节点的最短路径
-    let mut distance = vec![None; n + 1];
+/// Dijkstra 算法求源节点到所有节点的最短路
+///
+/// 堆中保存目前已知的路径, 每次取出最短的一条 src -> u, 这一条路径必定是最短路径,
+/// 然后用它更新 u 的所有邻居, 同一个节点可能因为多次更新在堆中出现多次, 取出时距离比已知的更大就跳过
+pub fn dijkstra_all(n: usize, edges: &[(usize, usize, usize)], src: usize) -> ShortestPaths {
+    let g = adjacency(n, edges);
+    let mut res = ShortestPaths::new(n, src);
 
-    // 保存目前已知的路径
     let mut h = BinaryHeap::new();
     h.push(NodeDistance(src, 0));
-
-    // 在当前已知路径中取最短的那一条 src -> u
-    // 则这一条路径必定是最短路径
     while let Some(NodeDistance(u, dis)) = h.pop() {
-        if distance[u].is_none() {
-            distance[u] = Some(dis);
-            for &(v, w) in &g[u] {
-                // 把当前节点连通的节点加入堆
-                if distance[v].is_none() {
-                    h.push(NodeDistance(v, dis + w));
+        if res.dist[u].is_some_and(|d| d < dis) {
+            continue;
+        }
+
+        for &(v, w) in &g[u] {
+            if res.relax(u, v, w) {
+                h.push(NodeDistance(v, dis + w));
+            }
+        }
+    }
+
+    res
+}
+
+/// 使用可索引堆的 Dijkstra 算法, 结果和 [`dijkstra_all`] 相同
+///
+/// 更新节点距离时如果节点已经在堆中就直接修改它的 key(decrease-key), 每个节点最多入堆一次,
+/// 堆的大小不超过 n, 复杂度 O((n + m)logn)  
+/// decrease-key 的次数通常远多于弹出的次数, 这里使用向上调整更快的 4 叉堆
+pub fn dijkstra_indexed(n: usize, edges: &[(usize, usize, usize)], src: usize) -> ShortestPaths {
+    let g = adjacency(n, edges);
+    let mut res = ShortestPaths::new(n, src);
+
+    let mut handles = vec![None; n + 1];
+    let mut h = IndexedBinaryHeap::<_, 4>::new();
+    handles[src] = Some(h.push(Reverse((0, src))));
+    while let Some((_, Reverse((dis, u)))) = h.pop() {
+        for &(v, w) in &g[u] {
+            if res.relax(u, v, w) {
+                let key = Reverse((dis + w, v));
+                match handles[v] {
+                    Some(handle) if h.contains(handle) => {
+                        h.change_key(handle, key);
+                    }
+                    _ => handles[v] = Some(h.push(key)),
                 }
             }
         }
     }
 
-    distance[dst]
+    res
+}
+
+/// 使用 Dijkstra 算法求 src 到 dst 的最短路径经过的节点
+///
+/// ```
+/// use impx::graph_shortest_path::shortest_path;
+///
+/// let edges = [(1, 2, 4), (1, 3, 1), (3, 2, 1), (2, 4, 1)];
+/// assert_eq!(shortest_path(4, &edges, 1, 4), Some(vec![1, 3, 2, 4]));
+/// assert_eq!(shortest_path(4, &edges, 4, 1), None);
+/// ```
+pub fn shortest_path(
+    n: usize,
+    edges: &[(usize, usize, usize)],
+    src: usize,
+    dst: usize,
+) -> Option<Vec<usize>> {
+    dijkstra_all(n, edges, src).path_to(dst)
+}
+
+/// 边权只有 0 和 1 时的 0-1 BFS
+///
+/// 用双端队列代替堆: 经过权值为 0 的边到达的节点和当前节点距离相同, 放到队头; 权值为 1 的放到队尾  
+/// 这样队列中的距离始终是单调的并且最多只有两种取值, 和 Dijkstra 一样每次取出的都是距离最小的节点, 复杂度 O(n + m)
+pub fn zero_one_bfs(n: usize, edges: &[(usize, usize, usize)], src: usize) -> ShortestPaths {
+    assert!(
+        edges.iter().all(|e| e.2 <= 1),
+        "edge weights must be 0 or 1"
+    );
+
+    let g = adjacency(n, edges);
+    let mut res = ShortestPaths::new(n, src);
+    let mut done = vec![false; n + 1];
+
+    let mut queue = VecDeque::from([src]);
+    while let Some(u) = queue.pop_front() {
+        if std::mem::replace(&mut done[u], true) {
+            continue;
+        }
+
+        for &(v, w) in &g[u] {
+            if res.relax(u, v, w) {
+                if w == 0 {
+                    queue.push_front(v);
+                } else {
+                    queue.push_back(v);
+                }
+            }
+        }
+    }
+
+    res
+}
+
+/// A* 搜索, 返回 src 到 dst 的最短距离和路径
+///
+/// 和 Dijkstra 相比堆中按 `g(u) + h(u)` 排序, 其中 g(u) 是 src 到 u 的已知距离, h(u) 是估计的 u 到 dst 的距离  
+/// 启发函数 h 可容许(不超过真实距离)时找到的一定是最短路; h 满足一致性 `h(u) <= w(u, v) + h(v)` 时每个节点只会被取出一次  
+/// h 恒为 0 时就退化成了 Dijkstra, h 越接近真实距离搜索的节点越少
+///
+/// ```
+/// use impx::graph_shortest_path::a_star;
+///
+/// // 1 - 2 - 3 - 4 排成一条直线, 估计距离就是编号之差
+/// let edges = [(1, 2, 1), (2, 3, 1), (3, 4, 1), (1, 3, 5)];
+/// assert_eq!(a_star(4, &edges, 1, 4, |u| 4 - u), Some((3, vec![1, 2, 3, 4])));
+/// ```
+pub fn a_star<H>(
+    n: usize,
+    edges: &[(usize, usize, usize)],
+    src: usize,
+    dst: usize,
+    h: H,
+) -> Option<(usize, Vec<usize>)>
+where
+    H: Fn(usize) -> usize,
+{
+    let g = adjacency(n, edges);
+    let mut res = ShortestPaths::new(n, src);
+    let mut closed = vec![false; n + 1];
+
+    // NodeDistance 中保存的是 g + h
+    let mut heap = BinaryHeap::new();
+    heap.push(NodeDistance(src, h(src)));
+    while let Some(NodeDistance(u, _)) = heap.pop() {
+        if u == dst {
+            return Some((res.dist[dst].unwrap(), res.path_to(dst).unwrap()));
+        }
+        if std::mem::replace(&mut closed[u], true) {
+            continue;
+        }
+
+        for &(v, w) in &g[u] {
+            if res.relax(u, v, w) {
+                // 启发函数不一致时已经取出的节点也可能被更新, 需要重新处理
+                closed[v] = false;
+                heap.push(NodeDistance(v, res.dist[v].unwrap() + h(v)));
+            }
+        }
+    }
+
+    None
+}
+
+/// Bellman-Ford 算法, 边权可以为负, 存在从 src 可达的负环时返回 None
+///
+/// 最短路最多经过 n-1 条边, 所以对所有边松弛 n-1 轮就能得到最短路,
+/// 如果第 n 轮还能松弛说明存在负环
+///
+/// ```
+/// use impx::graph_shortest_path::bellman_ford;
+///
+/// let edges = [(1, 2, 4), (1, 3, 5), (3, 2, -3)];
+/// assert_eq!(bellman_ford(3, &edges, 1), Some(vec![None, Some(0), Some(2), Some(5)]));
+/// assert_eq!(bellman_ford(2, &[(1, 2, 1), (2, 1, -2)], 1), None);
+/// ```
+pub fn bellman_ford(
+    n: usize,
+    edges: &[(usize, usize, i64)],
+    src: usize,
+) -> Option<Vec<Option<i64>>> {
+    let mut dist = vec![None; n + 1];
+    dist[src] = Some(0);
+
+    for round in 0..n {
+        let mut updated = false;
+        for &(u, v, w) in edges {
+            let Some(du) = dist[u] else { continue };
+            if dist[v].is_none_or(|dv| du + w < dv) {
+                dist[v] = Some(du + w);
+                updated = true;
+            }
+        }
+
+        if !updated {
+            return Some(dist);
+        }
+        if round + 1 == n {
+            // 第 n 轮还能松弛
+            return None;
+        }
+    }
+
+    Some(dist)
+}
+
+/// SPFA(队列优化的 Bellman-Ford), 存在从 src 可达的负环时返回 None
+///
+/// 只有上一轮被更新过的节点才可能更新它的邻居, 所以用队列保存被更新过的节点  
+/// 同时记录每个节点最短路上的边数, 边数达到 n 说明路径上有重复的节点, 即存在负环  
+/// 最坏情况下复杂度仍然是 O(nm)
+pub fn spfa(n: usize, edges: &[(usize, usize, i64)], src: usize) -> Option<Vec<Option<i64>>> {
+    let mut g = vec![vec![]; n + 1];
+    for &(u, v, w) in edges {
+        g[u].push((v, w));
+    }
+
+    let mut dist = vec![None; n + 1];
+    let mut count = vec![0; n + 1];
+    let mut in_queue = vec![false; n + 1];
+    dist[src] = Some(0);
+
+    let mut queue = VecDeque::from([src]);
+    in_queue[src] = true;
+    while let Some(u) = queue.pop_front() {
+        in_queue[u] = false;
+        let du = dist[u].unwrap();
+        for &(v, w) in &g[u] {
+            if dist[v].is_none_or(|dv| du + w < dv) {
+                dist[v] = Some(du + w);
+                count[v] = count[u] + 1;
+                if count[v] >= n {
+                    return None;
+                }
+                if !in_queue[v] {
+                    in_queue[v] = true;
+                    queue.push_back(v);
+                }
+            }
+        }
+    }
+
+    Some(dist)
 }
 
 fn min_option_usize<T: std::cmp::Ord>(a: Option<T>, b: Option<T>) -> Option<T> {
@@ -146,8 +418,175 @@ mod tests {
         assert_shortest_path(floyd);
     }
 
+    #[test]
+    fn test_floyd_parallel_edges() {
+        // 较重的重边出现在后面时不能覆盖较轻的
+        let edges = [(1, 2, 1), (1, 2, 5), (2, 3, 7), (2, 3, 2)];
+        assert_eq!(floyd(3, &edges, 1, 2), Some(1));
+        assert_eq!(floyd(3, &edges, 2, 3), Some(2));
+        assert_eq!(floyd(3, &edges, 1, 3), Some(3));
+        assert_eq!(floyd(3, &edges, 1, 3), dijkstra(3, &edges, 1, 3));
+    }
+
     #[test]
     fn test_dijkstra() {
         assert_shortest_path(dijkstra);
     }
+
+    /// 检查路径确实存在并且长度等于最短距离
+    fn check_paths(n: usize, edges: &[(usize, usize, usize)], res: &ShortestPaths) {
+        for dst in 1..=n {
+            assert_eq!(res.dist[dst], floyd(n, edges, res.src, dst));
+            match res.path_to(dst) {
+                None => assert!(res.dist[dst].is_none()),
+                Some(path) => {
+                    assert_eq!(path[0], res.src);
+                    let len: usize = path
+                        .windows(2)
+                        .map(|p| {
+                            edges
+                                .iter()
+                                .filter(|e| e.0 == p[0] && e.1 == p[1])
+                                .map(|e| e.2)
+                                .min()
+                                .unwrap()
+                        })
+                        .sum();
+                    assert_eq!(Some(len), res.dist[dst]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_shortest_paths_random() {
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..30 {
+            let n = 12;
+            let edges: Vec<_> = (0..30)
+                .map(|_| {
+                    (
+                        rng.gen_range(1..=n),
+                        rng.gen_range(1..=n),
+                        rng.gen_range(0..10),
+                    )
+                })
+                .collect();
+            check_paths(n, &edges, &dijkstra_all(n, &edges, 1));
+
+            let res = dijkstra_indexed(n, &edges, 1);
+            assert_eq!(res.dist, dijkstra_all(n, &edges, 1).dist);
+            check_paths(n, &edges, &res);
+
+            let edges: Vec<_> = edges.iter().map(|&(u, v, w)| (u, v, w % 2)).collect();
+            let res = zero_one_bfs(n, &edges, 1);
+            assert_eq!(res.dist, dijkstra_all(n, &edges, 1).dist);
+            check_paths(n, &edges, &res);
+        }
+    }
+
+    #[test]
+    fn test_a_star_maze() {
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        let (rows, cols) = (20, 30);
+        let id = |r: usize, c: usize| r * cols + c + 1;
+
+        for _ in 0..20 {
+            // 随机生成的迷宫, 起点和终点一定是空地
+            let mut open = vec![vec![true; cols]; rows];
+            for row in open.iter_mut() {
+                for cell in row.iter_mut() {
+                    *cell = rng.gen_bool(0.7);
+                }
+            }
+            open[0][0] = true;
+            open[rows - 1][cols - 1] = true;
+
+            let mut edges = vec![];
+            for r in 0..rows {
+                for c in 0..cols {
+                    for (nr, nc) in [(r + 1, c), (r, c + 1)] {
+                        if nr < rows && nc < cols && open[r][c] && open[nr][nc] {
+                            edges.push((id(r, c), id(nr, nc), 1));
+                            edges.push((id(nr, nc), id(r, c), 1));
+                        }
+                    }
+                }
+            }
+
+            let n = rows * cols;
+            let (src, dst) = (id(0, 0), id(rows - 1, cols - 1));
+            let manhattan = |u: usize| {
+                let (r, c) = ((u - 1) / cols, (u - 1) % cols);
+                (rows - 1 - r) + (cols - 1 - c)
+            };
+
+            // 曼哈顿距离是可容许的: 不超过到终点的真实距离
+            let to_dst = dijkstra_all(n, &edges, dst);
+            for u in 1..=n {
+                if let Some(d) = to_dst.dist[u] {
+                    assert!(manhattan(u) <= d);
+                }
+            }
+
+            let expect = to_dst.dist[src];
+            let res = a_star(n, &edges, src, dst, manhattan);
+            assert_eq!(res.as_ref().map(|r| r.0), expect);
+            assert_eq!(a_star(n, &edges, src, dst, |_| 0).map(|r| r.0), expect);
+            if let Some((d, path)) = res {
+                assert_eq!(path.len() - 1, d);
+                assert_eq!((path[0], path[d]), (src, dst));
+                assert!(path.windows(2).all(|p| edges.contains(&(p[0], p[1], 1))));
+            }
+        }
+    }
+
+    #[test]
+    fn test_bellman_ford_and_spfa() {
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..200 {
+            let n = rng.gen_range(1..10);
+            let edges: Vec<_> = (0..rng.gen_range(0..20))
+                .map(|_| {
+                    (
+                        rng.gen_range(1..=n),
+                        rng.gen_range(1..=n),
+                        rng.gen_range(-3..10),
+                    )
+                })
+                .collect();
+
+            let bf = bellman_ford(n, &edges, 1);
+            assert_eq!(spfa(n, &edges, 1), bf);
+
+            // 没有负权边时和 Dijkstra 相同
+            let positive: Vec<_> = edges
+                .iter()
+                .map(|&(u, v, w)| (u, v, w.unsigned_abs() as usize))
+                .collect();
+            let expect: Vec<_> = dijkstra_all(n, &positive, 1)
+                .dist
+                .iter()
+                .map(|d| d.map(|d| d as i64))
+                .collect();
+            let positive: Vec<_> = positive.iter().map(|&(u, v, w)| (u, v, w as i64)).collect();
+            assert_eq!(bellman_ford(n, &positive, 1), Some(expect.clone()));
+            assert_eq!(spfa(n, &positive, 1), Some(expect));
+        }
+
+        // 负环不可达时不影响结果
+        let edges = [(1, 2, 1), (3, 4, -1), (4, 3, -1)];
+        assert_eq!(
+            bellman_ford(4, &edges, 1),
+            Some(vec![None, Some(0), Some(1), None, None])
+        );
+        assert_eq!(spfa(4, &edges, 1), bellman_ford(4, &edges, 1));
+        assert_eq!(spfa(4, &edges, 3), None);
+    }
 }
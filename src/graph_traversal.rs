@@ -0,0 +1,430 @@
+//! 图的遍历
+//!
+//! - [OI Wiki - BFS(图论)](https://oi-wiki.org/graph/bfs/)
+//! - [OI Wiki - DFS(图论)](https://oi-wiki.org/graph/dfs/)
+//! - [OI Wiki - 二分图](https://oi-wiki.org/graph/bi-graph/)
+//!
+//! 节点编号为 `0..n`, 图使用邻接表 [`Graph`] 保存
+//!
+//! - BFS: 用队列按层访问, 第一次访问到某个节点时经过的边数就是它到起点的最短距离(无权图)
+//! - DFS: 沿着一条路一直走到底再回溯, 这里用显式的栈代替递归, 栈中保存节点和下一个要访问的邻居的位置,
+//!   节点第一次入栈时是前序(pre visit), 所有邻居都访问完出栈时是后序(post visit)
+//!
+//! 遍历时记录每个节点是从哪个节点访问到的(parent), 就得到了一棵搜索树, 从终点沿着 parent 往回走就能还原路径
+//!
+//! 对每个还没有访问过的节点做一次遍历就能求出无向图的所有连通分量  
+//! 二分图判定: 遍历时给相邻节点染上不同的颜色, 出现两个相邻节点颜色相同说明存在奇环, 不是二分图
+
+use std::collections::VecDeque;
+
+use crate::visualize::{Dot, ToDot};
+
+/// 邻接表表示的图
+#[derive(Debug, Clone, Default)]
+pub struct Graph {
+    adj: Vec<Vec<usize>>,
+}
+
+impl Graph {
+    /// n 个节点没有边的图
+    pub fn new(n: usize) -> Self {
+        Self {
+            adj: vec![vec![]; n],
+        }
+    }
+
+    /// 从边列表创建, directed 为 false 时每条边都添加两个方向
+    pub fn from_edges(n: usize, edges: &[(usize, usize)], directed: bool) -> Self {
+        let mut g = Self::new(n);
+        for &(u, v) in edges {
+            if directed {
+                g.add_edge(u, v);
+            } else {
+                g.add_undirected_edge(u, v);
+            }
+        }
+        g
+    }
+
+    /// 节点个数
+    pub fn len(&self) -> usize {
+        self.adj.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.adj.is_empty()
+    }
+
+    /// 添加有向边 u -> v
+    pub fn add_edge(&mut self, u: usize, v: usize) {
+        self.adj[u].push(v);
+    }
+
+    /// 添加无向边 u - v
+    pub fn add_undirected_edge(&mut self, u: usize, v: usize) {
+        self.adj[u].push(v);
+        if u != v {
+            self.adj[v].push(u);
+        }
+    }
+
+    pub fn neighbors(&self, u: usize) -> &[usize] {
+        &self.adj[u]
+    }
+}
+
+/// 一次遍历得到的搜索树
+#[derive(Debug, Clone)]
+pub struct SearchTree {
+    /// 起点
+    pub src: usize,
+    /// 节点被访问(前序)的顺序
+    pub order: Vec<usize>,
+    /// 搜索树中的父节点, 起点和没有访问到的节点为 None
+    pub parent: Vec<Option<usize>>,
+    /// 搜索树中的深度, BFS 时就是到起点的最短距离, 没有访问到的节点为 None
+    pub depth: Vec<Option<usize>>,
+}
+
+impl SearchTree {
+    fn new(n: usize, src: usize) -> Self {
+        let mut depth = vec![None; n];
+        depth[src] = Some(0);
+        Self {
+            src,
+            order: vec![],
+            parent: vec![None; n],
+            depth,
+        }
+    }
+
+    /// 是否访问到了 v
+    pub fn reached(&self, v: usize) -> bool {
+        self.depth[v].is_some()
+    }
+
+    /// 搜索树中从起点到 v 的路径
+    pub fn path_to(&self, v: usize) -> Option<Vec<usize>> {
+        self.depth[v]?;
+
+        let mut path = vec![v];
+        let mut cur = v;
+        while let Some(p) = self.parent[cur] {
+            path.push(p);
+            cur = p;
+        }
+        path.reverse();
+
+        Some(path)
+    }
+}
+
+/// 从 src 开始 BFS
+///
+/// ```
+/// use impx::graph_traversal::{bfs, Graph};
+///
+/// let g = Graph::from_edges(5, &[(0, 1), (1, 2), (0, 3), (3, 2)], false);
+/// let tree = bfs(&g, 0);
+/// assert_eq!(tree.depth[2], Some(2));
+/// assert_eq!(tree.path_to(2), Some(vec![0, 1, 2]));
+/// assert_eq!(tree.path_to(4), None);
+/// ```
+pub fn bfs(g: &Graph, src: usize) -> SearchTree {
+    bfs_with(g, src, |_| {})
+}
+
+/// 从 src 开始 BFS, 每访问一个节点调用一次 visit
+pub fn bfs_with(g: &Graph, src: usize, mut visit: impl FnMut(usize)) -> SearchTree {
+    let mut tree = SearchTree::new(g.len(), src);
+    let mut queue = VecDeque::from([src]);
+    while let Some(u) = queue.pop_front() {
+        visit(u);
+        tree.order.push(u);
+
+        let d = tree.depth[u].unwrap();
+        for &v in g.neighbors(u) {
+            if tree.depth[v].is_none() {
+                tree.depth[v] = Some(d + 1);
+                tree.parent[v] = Some(u);
+                queue.push_back(v);
+            }
+        }
+    }
+
+    tree
+}
+
+/// 从 src 开始 DFS
+///
+/// ```
+/// use impx::graph_traversal::{dfs, Graph};
+///
+/// let g = Graph::from_edges(4, &[(0, 1), (1, 2), (0, 2), (2, 3)], true);
+/// let tree = dfs(&g, 0);
+/// assert_eq!(tree.order, vec![0, 1, 2, 3]);
+/// assert_eq!(tree.path_to(3), Some(vec![0, 1, 2, 3]));
+/// ```
+pub fn dfs(g: &Graph, src: usize) -> SearchTree {
+    dfs_with(g, src, |_| {}, |_| {})
+}
+
+/// 从 src 开始 DFS, 节点第一次访问时调用 pre, 所有邻居都访问完后调用 post
+pub fn dfs_with(
+    g: &Graph,
+    src: usize,
+    mut pre: impl FnMut(usize),
+    mut post: impl FnMut(usize),
+) -> SearchTree {
+    let mut tree = SearchTree::new(g.len(), src);
+    pre(src);
+    tree.order.push(src);
+
+    // (节点, 下一个要检查的邻居的位置)
+    let mut stack = vec![(src, 0)];
+    while let Some((u, i)) = stack.last_mut() {
+        let u = *u;
+        match g.neighbors(u).get(*i) {
+            Some(&v) => {
+                *i += 1;
+                if tree.depth[v].is_none() {
+                    tree.depth[v] = Some(tree.depth[u].unwrap() + 1);
+                    tree.parent[v] = Some(u);
+                    pre(v);
+                    tree.order.push(v);
+                    stack.push((v, 0));
+                }
+            }
+            None => {
+                post(u);
+                stack.pop();
+            }
+        }
+    }
+
+    tree
+}
+
+/// 无向图的连通分量, 返回分量个数和每个节点所属分量的编号
+///
+/// ```
+/// use impx::graph_traversal::{connected_components, Graph};
+///
+/// let g = Graph::from_edges(5, &[(0, 1), (3, 4)], false);
+/// assert_eq!(connected_components(&g), (3, vec![0, 0, 1, 2, 2]));
+/// ```
+pub fn connected_components(g: &Graph) -> (usize, Vec<usize>) {
+    let mut label = vec![usize::MAX; g.len()];
+    let mut count = 0;
+    for s in 0..g.len() {
+        if label[s] != usize::MAX {
+            continue;
+        }
+
+        label[s] = count;
+        let mut stack = vec![s];
+        while let Some(u) = stack.pop() {
+            for &v in g.neighbors(u) {
+                if label[v] == usize::MAX {
+                    label[v] = count;
+                    stack.push(v);
+                }
+            }
+        }
+        count += 1;
+    }
+
+    (count, label)
+}
+
+/// 二分图判定, 是二分图时返回每个节点的颜色
+///
+/// ```
+/// use impx::graph_traversal::{bipartite, Graph};
+///
+/// let square = Graph::from_edges(4, &[(0, 1), (1, 2), (2, 3), (3, 0)], false);
+/// assert_eq!(bipartite(&square), Some(vec![false, true, false, true]));
+///
+/// let triangle = Graph::from_edges(3, &[(0, 1), (1, 2), (2, 0)], false);
+/// assert_eq!(bipartite(&triangle), None);
+/// ```
+pub fn bipartite(g: &Graph) -> Option<Vec<bool>> {
+    let mut color = vec![None; g.len()];
+    for s in 0..g.len() {
+        if color[s].is_some() {
+            continue;
+        }
+
+        color[s] = Some(false);
+        let mut queue = VecDeque::from([s]);
+        while let Some(u) = queue.pop_front() {
+            let c = color[u].unwrap();
+            for &v in g.neighbors(u) {
+                match color[v] {
+                    None => {
+                        color[v] = Some(!c);
+                        queue.push_back(v);
+                    }
+                    Some(cv) if cv == c => return None,
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    Some(color.into_iter().map(Option::unwrap).collect())
+}
+
+impl ToDot for Graph {
+    /// 每条边都有对应的反向边时输出为无向图, 否则输出为有向图
+    fn to_dot(&self) -> String {
+        let mut count = std::collections::HashMap::new();
+        for u in 0..self.len() {
+            for &v in self.neighbors(u) {
+                *count.entry((u, v)).or_insert(0) += 1;
+            }
+        }
+        let undirected = count.iter().all(|(&(u, v), &c)| {
+            if u == v {
+                c % 2 == 0
+            } else {
+                count.get(&(v, u)) == Some(&c)
+            }
+        });
+
+        let mut dot = if undirected {
+            Dot::graph("Graph")
+        } else {
+            Dot::digraph("Graph")
+        };
+        dot.stmt("node [shape=circle]");
+        for u in 0..self.len() {
+            dot.node(&u.to_string(), &[]);
+        }
+
+        // 无向图的每条边在邻接表中出现两次, 只输出 u <= v 的一半
+        let mut emitted = std::collections::HashMap::new();
+        for u in 0..self.len() {
+            for &v in self.neighbors(u) {
+                if undirected {
+                    let seen = emitted.entry((u.min(v), u.max(v))).or_insert(0);
+                    *seen += 1;
+                    if *seen % 2 == 0 {
+                        continue;
+                    }
+                }
+                dot.edge(u, v, &[]);
+            }
+        }
+        dot.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    fn random_graph(n: usize, m: usize, directed: bool) -> Graph {
+        let mut rng = rand::thread_rng();
+        let edges: Vec<_> = (0..m)
+            .map(|_| (rng.gen_range(0..n), rng.gen_range(0..n)))
+            .collect();
+        Graph::from_edges(n, &edges, directed)
+    }
+
+    #[test]
+    fn test_bfs_shortest() {
+        for _ in 0..20 {
+            let g = random_graph(30, 60, true);
+            let tree = bfs(&g, 0);
+
+            // Bellman-Ford 风格的松弛求无权最短路
+            let mut dist = vec![None; g.len()];
+            dist[0] = Some(0);
+            for _ in 0..g.len() {
+                for u in 0..g.len() {
+                    let Some(d) = dist[u] else { continue };
+                    for &v in g.neighbors(u) {
+                        if dist[v].is_none_or(|dv| d + 1 < dv) {
+                            dist[v] = Some(d + 1);
+                        }
+                    }
+                }
+            }
+            assert_eq!(tree.depth, dist);
+
+            for (v, d) in dist.iter().enumerate() {
+                match tree.path_to(v) {
+                    None => assert!(!tree.reached(v)),
+                    Some(path) => {
+                        assert_eq!(Some(path.len() - 1), *d);
+                        assert!(path.windows(2).all(|w| g.neighbors(w[0]).contains(&w[1])));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_dfs_callbacks() {
+        let g = random_graph(50, 80, true);
+        let (mut pre, mut post) = (vec![], vec![]);
+        let tree = dfs_with(&g, 0, |u| pre.push(u), |u| post.push(u));
+
+        assert_eq!(pre, tree.order);
+        let mut sorted_pre = pre.clone();
+        let mut sorted_post = post.clone();
+        sorted_pre.sort();
+        sorted_post.sort();
+        assert_eq!(sorted_pre, sorted_post);
+
+        // 后序中子节点一定在父节点之前
+        let mut pos = vec![0; g.len()];
+        for (i, &u) in post.iter().enumerate() {
+            pos[u] = i;
+        }
+        for &u in &pre {
+            if let Some(p) = tree.parent[u] {
+                assert!(pos[u] < pos[p]);
+            }
+            if let Some(path) = tree.path_to(u) {
+                assert!(path.windows(2).all(|w| g.neighbors(w[0]).contains(&w[1])));
+            }
+        }
+
+        // 访问到的节点集合和 BFS 相同
+        let reached = bfs(&g, 0);
+        assert!((0..g.len()).all(|v| reached.reached(v) == tree.reached(v)));
+    }
+
+    #[test]
+    fn test_components_and_bipartite() {
+        for _ in 0..20 {
+            let g = random_graph(40, 30, false);
+            let (count, label) = connected_components(&g);
+            assert_eq!(label.iter().max().map_or(0, |&m| m + 1), count);
+            for u in 0..g.len() {
+                let tree = bfs(&g, u);
+                assert!((0..g.len()).all(|v| tree.reached(v) == (label[u] == label[v])));
+            }
+
+            // 二分图判定和暴力枚举染色的结果一致
+            let g = random_graph(10, 9, false);
+            let brute = (0..1u32 << 10).any(|mask| {
+                (0..10).all(|u| {
+                    g.neighbors(u)
+                        .iter()
+                        .all(|&v| (mask >> u & 1) != (mask >> v & 1))
+                })
+            });
+            match bipartite(&g) {
+                Some(color) => {
+                    assert!(brute);
+                    assert!((0..10).all(|u| g.neighbors(u).iter().all(|&v| color[u] != color[v])));
+                }
+                None => assert!(!brute),
+            }
+        }
+    }
+}
@@ -0,0 +1,619 @@
+//! 哈希表
+//!
+//! - [OI Wiki - 哈希表](https://oi-wiki.org/ds/hash/)
+//! - [wikipedia - Hash table](https://en.wikipedia.org/wiki/Hash_table)
+//! - [Robin Hood Hashing](https://programming.guide/robin-hood-hashing.html)
+//!
+//! 哈希表通过哈希函数把 key 映射到桶数组中的一个位置, 不同 key 映射到同一个位置时称为哈希冲突
+//! 解决冲突的方式主要有两种:
+//!
+//! - 拉链法 [`ChainedHashMap`]: 每个桶是一个链表(这里用 `Vec` 代替), 冲突的 key 都放在同一个桶中
+//! - 开放寻址法 [`RobinHoodHashMap`]: 所有数据直接放在桶数组中, 冲突时按照某种规则探测下一个空位
+//!
+//! 开放寻址使用线性探测, 并采用 Robin Hood 策略: 每个数据记录自己离理想位置的距离(探测距离)
+//! 插入时如果当前位置上数据的探测距离比待插入数据的小, 就"劫富济贫"交换两者, 继续为被换出的数据寻找位置
+//! 这样所有数据的探测距离会比较平均, 查找时如果遇到探测距离比自己小的数据就可以提前结束
+//! 删除时不使用墓碑标记, 而是把后面的数据依次向前移动一位(backward shift)
+//!
+//! 当负载因子(数据数量 / 桶数量)超过阈值时, 桶数组扩容为两倍并重新插入所有数据(rehash)
+//! 两种实现的桶数量都是 2 的幂, 可以用位运算代替取模
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+
+/// 初始桶数量
+const INITIAL_CAPACITY: usize = 8;
+
+/// 哈希表的负载统计
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    /// 数据数量
+    pub len: usize,
+    /// 桶数量
+    pub buckets: usize,
+    /// 负载因子
+    pub load_factor: f64,
+    /// 拉链法: 最长的链表长度; 开放寻址: 最大的探测距离
+    pub max_probe: usize,
+    /// 拉链法: 非空链表的平均长度; 开放寻址: 平均探测距离
+    pub avg_probe: f64,
+}
+
+/// 拉链法哈希表
+pub struct ChainedHashMap<K, V, S = RandomState> {
+    buckets: Vec<Vec<(K, V)>>,
+    len: usize,
+    hasher: S,
+}
+
+impl<K: Hash + Eq, V> ChainedHashMap<K, V> {
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+}
+
+impl<K: Hash + Eq, V> Default for ChainedHashMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> ChainedHashMap<K, V, S> {
+    /// 负载因子超过 3/4 时扩容
+    const MAX_LOAD: (usize, usize) = (3, 4);
+
+    pub fn with_hasher(hasher: S) -> Self {
+        Self {
+            buckets: (0..INITIAL_CAPACITY).map(|_| vec![]).collect(),
+            len: 0,
+            hasher,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// 负载因子
+    pub fn load_factor(&self) -> f64 {
+        self.len as f64 / self.buckets.len() as f64
+    }
+
+    fn bucket_index(&self, key: &K) -> usize {
+        let hash = self.hasher.hash_one(key);
+        hash as usize & (self.buckets.len() - 1)
+    }
+
+    /// 插入一个新数据前检查是否需要扩容
+    fn reserve_one(&mut self) {
+        let (num, den) = Self::MAX_LOAD;
+        if (self.len + 1) * den <= self.buckets.len() * num {
+            return;
+        }
+
+        let new_size = self.buckets.len() * 2;
+        let old = std::mem::replace(&mut self.buckets, (0..new_size).map(|_| vec![]).collect());
+        for (key, value) in old.into_iter().flatten() {
+            let index = self.bucket_index(&key);
+            self.buckets[index].push((key, value));
+        }
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let index = self.bucket_index(key);
+        self.buckets[index]
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v)
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let index = self.bucket_index(key);
+        self.buckets[index]
+            .iter_mut()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v)
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// 插入 key-value, 如果 key 已经存在则返回旧值
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(v) = self.get_mut(&key) {
+            return Some(std::mem::replace(v, value));
+        }
+
+        self.reserve_one();
+        let index = self.bucket_index(&key);
+        self.buckets[index].push((key, value));
+        self.len += 1;
+        None
+    }
+
+    /// 删除 key 并返回对应的值
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let index = self.bucket_index(key);
+        let bucket = &mut self.buckets[index];
+        let pos = bucket.iter().position(|(k, _)| k == key)?;
+        self.len -= 1;
+        Some(bucket.swap_remove(pos).1)
+    }
+
+    /// 获取 key 对应的 Entry 用于原地修改或插入
+    pub fn entry(&mut self, key: K) -> ChainedEntry<'_, K, V, S> {
+        let index = self.bucket_index(&key);
+        match self.buckets[index].iter().position(|(k, _)| k == &key) {
+            Some(pos) => ChainedEntry::Occupied(&mut self.buckets[index][pos].1),
+            None => ChainedEntry::Vacant(ChainedVacantEntry { map: self, key }),
+        }
+    }
+
+    /// 遍历所有数据, 顺序不确定
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.buckets.iter().flatten().map(|(k, v)| (k, v))
+    }
+
+    /// 负载统计
+    pub fn stats(&self) -> Stats {
+        let used = self.buckets.iter().filter(|b| !b.is_empty()).count();
+        Stats {
+            len: self.len,
+            buckets: self.buckets.len(),
+            load_factor: self.load_factor(),
+            max_probe: self.buckets.iter().map(|b| b.len()).max().unwrap_or(0),
+            avg_probe: if used == 0 {
+                0.0
+            } else {
+                self.len as f64 / used as f64
+            },
+        }
+    }
+}
+
+/// 拉链法哈希表的 Entry
+pub enum ChainedEntry<'a, K, V, S> {
+    Occupied(&'a mut V),
+    Vacant(ChainedVacantEntry<'a, K, V, S>),
+}
+
+/// 拉链法哈希表中不存在的 key
+pub struct ChainedVacantEntry<'a, K, V, S> {
+    map: &'a mut ChainedHashMap<K, V, S>,
+    key: K,
+}
+
+impl<'a, K: Hash + Eq, V, S: BuildHasher> ChainedVacantEntry<'a, K, V, S> {
+    pub fn insert(self, value: V) -> &'a mut V {
+        let map = self.map;
+        map.reserve_one();
+        let index = map.bucket_index(&self.key);
+        let bucket = &mut map.buckets[index];
+        bucket.push((self.key, value));
+        map.len += 1;
+        &mut bucket.last_mut().expect("bucket must not be empty").1
+    }
+}
+
+impl<'a, K: Hash + Eq, V, S: BuildHasher> ChainedEntry<'a, K, V, S> {
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    pub fn or_insert_with<F: FnOnce() -> V>(self, f: F) -> &'a mut V {
+        match self {
+            ChainedEntry::Occupied(v) => v,
+            ChainedEntry::Vacant(entry) => entry.insert(f()),
+        }
+    }
+
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Self {
+        match self {
+            ChainedEntry::Occupied(v) => {
+                f(v);
+                ChainedEntry::Occupied(v)
+            }
+            entry => entry,
+        }
+    }
+}
+
+/// 开放寻址哈希表中的一个数据
+struct Slot<K, V> {
+    hash: u64,
+    dist: usize, // 离理想位置的距离
+    key: K,
+    value: V,
+}
+
+/// Robin Hood 开放寻址哈希表
+pub struct RobinHoodHashMap<K, V, S = RandomState> {
+    slots: Vec<Option<Slot<K, V>>>,
+    len: usize,
+    hasher: S,
+}
+
+impl<K: Hash + Eq, V> RobinHoodHashMap<K, V> {
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+}
+
+impl<K: Hash + Eq, V> Default for RobinHoodHashMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> RobinHoodHashMap<K, V, S> {
+    /// 负载因子超过 7/8 时扩容, Robin Hood 策略在较高负载下探测距离依然比较短
+    const MAX_LOAD: (usize, usize) = (7, 8);
+
+    pub fn with_hasher(hasher: S) -> Self {
+        Self {
+            slots: (0..INITIAL_CAPACITY).map(|_| None).collect(),
+            len: 0,
+            hasher,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// 负载因子
+    pub fn load_factor(&self) -> f64 {
+        self.len as f64 / self.slots.len() as f64
+    }
+
+    fn mask(&self) -> usize {
+        self.slots.len() - 1
+    }
+
+    /// 查找 key 所在的位置
+    ///
+    /// 从理想位置开始线性探测, 遇到空位或者探测距离比当前距离小的数据时
+    /// 说明 key 不存在(如果存在的话它应该已经把这个数据换走了)
+    fn find(&self, hash: u64, key: &K) -> Option<usize> {
+        let mask = self.mask();
+        let mut index = hash as usize & mask;
+        let mut dist = 0;
+
+        while let Some(slot) = &self.slots[index] {
+            if slot.dist < dist {
+                return None;
+            }
+            if slot.hash == hash && &slot.key == key {
+                return Some(index);
+            }
+
+            index = (index + 1) & mask;
+            dist += 1;
+        }
+
+        None
+    }
+
+    /// 插入一个确定不存在的数据, 返回这个数据最终所在的位置
+    fn insert_new(&mut self, mut slot: Slot<K, V>) -> usize {
+        let mask = self.mask();
+        let mut index = slot.hash as usize & mask;
+        let mut placed = None;
+
+        loop {
+            match &mut self.slots[index] {
+                None => {
+                    self.slots[index] = Some(slot);
+                    self.len += 1;
+                    return placed.unwrap_or(index);
+                }
+                Some(curr) => {
+                    // 劫富济贫: 当前位置的数据比待插入的数据离理想位置更近, 交换两者
+                    if curr.dist < slot.dist {
+                        std::mem::swap(curr, &mut slot);
+                        placed.get_or_insert(index);
+                    }
+                }
+            }
+
+            index = (index + 1) & mask;
+            slot.dist += 1;
+        }
+    }
+
+    /// 插入一个新数据前检查是否需要扩容
+    fn reserve_one(&mut self) {
+        let (num, den) = Self::MAX_LOAD;
+        if (self.len + 1) * den <= self.slots.len() * num {
+            return;
+        }
+
+        let new_size = self.slots.len() * 2;
+        let old = std::mem::replace(&mut self.slots, (0..new_size).map(|_| None).collect());
+        self.len = 0;
+        for mut slot in old.into_iter().flatten() {
+            slot.dist = 0;
+            self.insert_new(slot);
+        }
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let hash = self.hasher.hash_one(key);
+        let index = self.find(hash, key)?;
+        self.slots[index].as_ref().map(|slot| &slot.value)
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let hash = self.hasher.hash_one(key);
+        let index = self.find(hash, key)?;
+        self.slots[index].as_mut().map(|slot| &mut slot.value)
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// 插入 key-value, 如果 key 已经存在则返回旧值
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(v) = self.get_mut(&key) {
+            return Some(std::mem::replace(v, value));
+        }
+
+        self.reserve_one();
+        let hash = self.hasher.hash_one(&key);
+        self.insert_new(Slot {
+            hash,
+            dist: 0,
+            key,
+            value,
+        });
+        None
+    }
+
+    /// 删除 key 并返回对应的值
+    ///
+    /// 删除后把后面探测距离大于 0 的数据依次向前移动一位
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let hash = self.hasher.hash_one(key);
+        let mut index = self.find(hash, key)?;
+        let removed = self.slots[index].take().expect("slot must exist");
+        self.len -= 1;
+
+        let mask = self.mask();
+        loop {
+            let next = (index + 1) & mask;
+            match &mut self.slots[next] {
+                Some(slot) if slot.dist > 0 => {
+                    slot.dist -= 1;
+                    self.slots[index] = self.slots[next].take();
+                    index = next;
+                }
+                _ => break,
+            }
+        }
+
+        Some(removed.value)
+    }
+
+    /// 获取 key 对应的 Entry 用于原地修改或插入
+    pub fn entry(&mut self, key: K) -> RobinHoodEntry<'_, K, V, S> {
+        let hash = self.hasher.hash_one(&key);
+        match self.find(hash, &key) {
+            Some(index) => {
+                let slot = self.slots[index].as_mut().expect("slot must exist");
+                RobinHoodEntry::Occupied(&mut slot.value)
+            }
+            None => RobinHoodEntry::Vacant(RobinHoodVacantEntry { map: self, key }),
+        }
+    }
+
+    /// 遍历所有数据, 顺序不确定
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.slots
+            .iter()
+            .flatten()
+            .map(|slot| (&slot.key, &slot.value))
+    }
+
+    /// 负载统计
+    pub fn stats(&self) -> Stats {
+        let dists = self.slots.iter().flatten().map(|slot| slot.dist);
+        Stats {
+            len: self.len,
+            buckets: self.slots.len(),
+            load_factor: self.load_factor(),
+            max_probe: dists.clone().max().unwrap_or(0),
+            avg_probe: if self.len == 0 {
+                0.0
+            } else {
+                dists.sum::<usize>() as f64 / self.len as f64
+            },
+        }
+    }
+}
+
+/// Robin Hood 哈希表的 Entry
+pub enum RobinHoodEntry<'a, K, V, S> {
+    Occupied(&'a mut V),
+    Vacant(RobinHoodVacantEntry<'a, K, V, S>),
+}
+
+/// Robin Hood 哈希表中不存在的 key
+pub struct RobinHoodVacantEntry<'a, K, V, S> {
+    map: &'a mut RobinHoodHashMap<K, V, S>,
+    key: K,
+}
+
+impl<'a, K: Hash + Eq, V, S: BuildHasher> RobinHoodVacantEntry<'a, K, V, S> {
+    pub fn insert(self, value: V) -> &'a mut V {
+        let map = self.map;
+        map.reserve_one();
+        let hash = map.hasher.hash_one(&self.key);
+        let index = map.insert_new(Slot {
+            hash,
+            dist: 0,
+            key: self.key,
+            value,
+        });
+
+        &mut map.slots[index].as_mut().expect("slot must exist").value
+    }
+}
+
+impl<'a, K: Hash + Eq, V, S: BuildHasher> RobinHoodEntry<'a, K, V, S> {
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    pub fn or_insert_with<F: FnOnce() -> V>(self, f: F) -> &'a mut V {
+        match self {
+            RobinHoodEntry::Occupied(v) => v,
+            RobinHoodEntry::Vacant(entry) => entry.insert(f()),
+        }
+    }
+
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Self {
+        match self {
+            RobinHoodEntry::Occupied(v) => {
+                f(v);
+                RobinHoodEntry::Occupied(v)
+            }
+            entry => entry,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+    use std::collections::HashMap;
+    use std::hash::{BuildHasherDefault, Hasher};
+
+    /// 把所有 key 都映射到少数几个哈希值的哈希函数, 用来制造大量冲突
+    #[derive(Default)]
+    struct BadHasher(u64);
+
+    impl Hasher for BadHasher {
+        fn finish(&self) -> u64 {
+            self.0 % 4
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            for &b in bytes {
+                self.0 = self.0.wrapping_mul(31).wrapping_add(b as u64);
+            }
+        }
+    }
+
+    type BadState = BuildHasherDefault<BadHasher>;
+
+    macro_rules! test_random {
+        ($map:expr) => {{
+            let mut rng = rand::thread_rng();
+            let mut map = $map;
+            let mut std_map = HashMap::new();
+
+            for i in 0..20000 {
+                let key = rng.gen_range(0..512);
+                match rng.gen_range(0..3) {
+                    0 => assert_eq!(map.insert(key, i), std_map.insert(key, i)),
+                    1 => assert_eq!(map.remove(&key), std_map.remove(&key)),
+                    _ => assert_eq!(map.get(&key), std_map.get(&key)),
+                }
+
+                assert_eq!(map.len(), std_map.len());
+            }
+
+            let mut items: Vec<_> = map.iter().map(|(&k, &v)| (k, v)).collect();
+            let mut expect: Vec<_> = std_map.into_iter().collect();
+            items.sort();
+            expect.sort();
+            assert_eq!(items, expect);
+        }};
+    }
+
+    macro_rules! test_entry {
+        ($map:expr) => {{
+            let mut map = $map;
+            let text = "the quick brown fox jumps over the lazy dog the end";
+            for word in text.split(' ') {
+                *map.entry(word).or_insert(0) += 1;
+            }
+
+            assert_eq!(map.get(&"the"), Some(&3));
+            assert_eq!(map.get(&"fox"), Some(&1));
+            assert_eq!(map.len(), 9);
+
+            map.entry("fox").and_modify(|v| *v += 10).or_default();
+            map.entry("cat").and_modify(|v| *v += 10).or_default();
+            assert_eq!(map.get(&"fox"), Some(&11));
+            assert_eq!(map.get(&"cat"), Some(&0));
+        }};
+    }
+
+    #[test]
+    fn test_chained_hash_map() {
+        test_random!(ChainedHashMap::new());
+        test_random!(ChainedHashMap::with_hasher(BadState::default()));
+        test_entry!(ChainedHashMap::new());
+
+        let mut map = ChainedHashMap::new();
+        for i in 0..1000 {
+            map.insert(i, i);
+        }
+
+        let stats = map.stats();
+        assert_eq!(stats.len, 1000);
+        assert_eq!(stats.buckets, 2048);
+        assert!(stats.load_factor <= 0.75);
+    }
+
+    #[test]
+    fn test_robin_hood_hash_map() {
+        test_random!(RobinHoodHashMap::new());
+        test_random!(RobinHoodHashMap::with_hasher(BadState::default()));
+        test_entry!(RobinHoodHashMap::new());
+
+        let mut map = RobinHoodHashMap::new();
+        for i in 0..1000 {
+            map.insert(i, i);
+        }
+
+        let stats = map.stats();
+        assert_eq!(stats.len, 1000);
+        assert_eq!(stats.buckets, 2048);
+        assert!(stats.load_factor <= 0.875);
+
+        // 删除后探测距离仍然满足 Robin Hood 的不变量: 查找所有剩余的 key 都能成功
+        for i in (0..1000).step_by(3) {
+            assert_eq!(map.remove(&i), Some(i));
+        }
+        for i in 0..1000 {
+            assert_eq!(map.get(&i).is_some(), i % 3 != 0);
+        }
+    }
+}
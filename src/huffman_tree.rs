@@ -23,9 +23,7 @@ where
             Some((node1, weight1)) => {
                 // 取权值最小的两个节点组成一棵新的树插入到序列中
                 let weight = weight0 + weight1;
-                let mut parent = BinaryTree::new(None);
-                parent.left = Some(Box::new(node0));
-                parent.right = Some(Box::new(node1));
+                let parent = BinaryTree::with_children(None, Some(node0), Some(node1));
 
                 let idx = nodes
                     .binary_search_by(|(_, probe)| weight.cmp(probe))
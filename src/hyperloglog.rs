@@ -0,0 +1,117 @@
+//! HyperLogLog
+//!
+//! - [wikipedia - HyperLogLog](https://en.wikipedia.org/wiki/HyperLogLog)
+//! - [HyperLogLog: the analysis of a near-optimal cardinality estimation algorithm](http://algo.inria.fr/flajolet/Publications/FlFuGaMe07.pdf)
+//!
+//! 用很少的内存估计一个数据流中不同元素的数量(基数)
+//!
+//! 对每个元素计算 64 位哈希值, 高 p 位作为寄存器下标, 剩余位中第一个 1 出现的位置记为 ρ
+//! 每个寄存器记录所有落入其中的元素的最大 ρ, 直观上看如果见过 ρ = k 的哈希值, 说明大约有 2^k 个不同的元素
+//!
+//! m = 2^p 个寄存器的调和平均数乘以修正系数 α_m * m^2 就是基数的估计值, 标准误差约为 1.04 / √m
+//! 估计值较小(不超过 2.5m)并且还有空寄存器时, 使用线性计数 m * ln(m / 空寄存器数量) 修正
+//! 使用 64 位哈希时基本不会出现大范围的哈希冲突, 所以省略了原论文中的大范围修正
+//!
+//! 两个 HyperLogLog 的寄存器逐个取最大值就得到两个数据流并集的 HyperLogLog
+
+use std::hash::Hash;
+
+use crate::cuckoo_filter::hash;
+
+pub struct HyperLogLog {
+    precision: u32,
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    /// 创建使用 2^precision 个寄存器的 HyperLogLog, precision 的范围是 4..=18
+    pub fn new(precision: u32) -> Self {
+        assert!((4..=18).contains(&precision), "precision must be in 4..=18");
+        Self {
+            precision,
+            registers: vec![0; 1 << precision],
+        }
+    }
+
+    pub fn precision(&self) -> u32 {
+        self.precision
+    }
+
+    /// 理论上的标准误差
+    pub fn standard_error(&self) -> f64 {
+        1.04 / (self.registers.len() as f64).sqrt()
+    }
+
+    pub fn add<T: Hash + ?Sized>(&mut self, key: &T) {
+        let hash = hash(key, 0);
+
+        let p = self.precision;
+        let index = (hash >> (64 - p)) as usize;
+        // 在最低位补一个 1 保证剩余位全为 0 时 ρ 不会超过 64 - p + 1
+        let rest = (hash << p) | (1 << (p - 1));
+        let rho = rest.leading_zeros() as u8 + 1;
+        self.registers[index] = self.registers[index].max(rho);
+    }
+
+    /// 估计不同元素的数量
+    pub fn count(&self) -> f64 {
+        let m = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+
+        let sum: f64 = self.registers.iter().map(|&r| 2f64.powi(-(r as i32))).sum();
+        let estimate = alpha * m * m / sum;
+
+        let zeros = self.registers.iter().filter(|&&r| r == 0).count();
+        if estimate <= 2.5 * m && zeros > 0 {
+            m * (m / zeros as f64).ln()
+        } else {
+            estimate
+        }
+    }
+
+    /// 合并另一个相同精度的 HyperLogLog
+    pub fn merge(&mut self, other: &HyperLogLog) {
+        assert_eq!(self.precision, other.precision, "precision mismatch");
+        for (a, &b) in self.registers.iter_mut().zip(&other.registers) {
+            *a = (*a).max(b);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hyperloglog() {
+        assert_eq!(HyperLogLog::new(12).count(), 0.0);
+
+        for n in [10, 1000, 100000] {
+            let mut hll = HyperLogLog::new(12);
+            // 重复元素不影响估计值
+            for i in 0..n * 3 {
+                hll.add(&(i % n));
+            }
+
+            let err = (hll.count() - n as f64).abs() / n as f64;
+            assert!(err < hll.standard_error() * 4.0, "n = {n} err = {err}");
+        }
+
+        let mut a = HyperLogLog::new(12);
+        let mut b = HyperLogLog::new(12);
+        for i in 0..60000 {
+            a.add(&i);
+        }
+        for i in 40000..100000 {
+            b.add(&i);
+        }
+        a.merge(&b);
+        let err = (a.count() - 100000.0).abs() / 100000.0;
+        assert!(err < a.standard_error() * 4.0);
+    }
+}
@@ -0,0 +1,157 @@
+//! 逆序对计数
+//!
+//! - [OI Wiki - 逆序对](https://oi-wiki.org/basic/merge-sort/#逆序对)
+//! - [wikipedia - Inversion (discrete mathematics)](https://en.wikipedia.org/wiki/Inversion_(discrete_mathematics))
+//!
+//! 满足 `i < j` 并且 `a[i] > a[j]` 的下标对 `(i, j)` 称为一个逆序对, 相等的元素不算逆序对
+//! 逆序对的个数等于冒泡排序或者插入排序需要交换相邻元素的次数, 最多为 n(n-1)/2
+//!
+//! 归并排序: 合并两个有序的半边时, 如果右边的 `b[j]` 比左边的 `a[i]` 小先被取出,
+//! 那么左边剩下的 `a[i..]` 都比 `b[j]` 大并且位置都在它前面, 一次就能数出 `len(a) - i` 个逆序对,
+//! 两个半边内部的逆序对由递归求出, O(nlogn)
+//!
+//! 树状数组: 从左到右扫描, 用树状数组记录已经出现过的每个值的个数,
+//! 扫描到 `a[j]` 时前面比它大的元素个数就是 `j - (前面不大于 a[j] 的元素个数)`, 一次前缀和查询 O(logn)
+//! 树状数组的下标是值, 值域很大或者不是整数时先离散化成 `0..n` 内的排名, 相等的值排名相同
+
+use crate::binary_indexed_tree;
+
+/// 逐对比较, O(n²)
+pub fn count_inversions_naive<T: Ord>(v: &[T]) -> usize {
+    (0..v.len())
+        .map(|i| v[i + 1..].iter().filter(|&x| *x < v[i]).count())
+        .sum()
+}
+
+/// 基于归并排序计数逆序对, O(nlogn) 时间 O(n) 额外空间
+///
+/// ```
+/// use impx::inversions::count_inversions;
+///
+/// assert_eq!(count_inversions(&[2, 4, 1, 3, 5]), 3);
+/// assert_eq!(count_inversions(&[5, 4, 3, 2, 1]), 10);
+/// assert_eq!(count_inversions(&[1, 1, 1]), 0);
+/// ```
+pub fn count_inversions<T: Ord + Clone>(v: &[T]) -> usize {
+    let mut v = v.to_vec();
+    let mut buf = Vec::with_capacity(v.len());
+    merge_count(&mut v, &mut buf)
+}
+
+/// 对 v 归并排序并返回其中的逆序对个数, buf 是合并时使用的临时空间
+fn merge_count<T: Ord + Clone>(v: &mut [T], buf: &mut Vec<T>) -> usize {
+    if v.len() <= 1 {
+        return 0;
+    }
+
+    let mid = v.len() / 2;
+    let mut count = merge_count(&mut v[..mid], buf) + merge_count(&mut v[mid..], buf);
+
+    buf.clear();
+    let (a, b) = v.split_at(mid);
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        // 相等时取左边的元素, 相等的元素不构成逆序对
+        if b[j] < a[i] {
+            count += a.len() - i;
+            buf.push(b[j].clone());
+            j += 1;
+        } else {
+            buf.push(a[i].clone());
+            i += 1;
+        }
+    }
+    buf.extend_from_slice(&a[i..]);
+    buf.extend_from_slice(&b[j..]);
+    v.clone_from_slice(buf);
+
+    count
+}
+
+/// 离散化后使用树状数组计数逆序对, O(nlogn)
+///
+/// ```
+/// use impx::inversions::count_inversions_bit;
+///
+/// assert_eq!(count_inversions_bit(&["c", "a", "b"]), 2);
+/// ```
+pub fn count_inversions_bit<T: Ord>(v: &[T]) -> usize {
+    let mut sorted: Vec<&T> = v.iter().collect();
+    sorted.sort_unstable();
+    sorted.dedup();
+
+    let mut t = vec![0; sorted.len()];
+    let mut count = 0;
+    for (j, x) in v.iter().enumerate() {
+        let rank = sorted.binary_search(&x).unwrap();
+        // 前面排名不超过 rank 的元素个数
+        let not_greater = binary_indexed_tree::sum(&t, rank + 1) as usize;
+        count += j - not_greater;
+        binary_indexed_tree::update(&mut t, rank, 1);
+    }
+
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    /// 冒泡排序交换相邻元素的次数等于逆序对个数
+    fn bubble_swaps(v: &[i32]) -> usize {
+        let mut v = v.to_vec();
+        let mut swaps = 0;
+        for i in 0..v.len() {
+            for j in 0..v.len() - 1 - i {
+                if v[j] > v[j + 1] {
+                    v.swap(j, j + 1);
+                    swaps += 1;
+                }
+            }
+        }
+        swaps
+    }
+
+    #[test]
+    fn test_count_inversions_cross_check() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..500 {
+            let n = rng.gen_range(0..60);
+            // 值域较小时有大量相等的元素
+            let max = rng.gen_range(1..100);
+            let v: Vec<i32> = (0..n).map(|_| rng.gen_range(-max..max)).collect();
+
+            let expect = count_inversions_naive(&v);
+            assert_eq!(count_inversions(&v), expect);
+            assert_eq!(count_inversions_bit(&v), expect);
+            assert_eq!(bubble_swaps(&v), expect);
+        }
+    }
+
+    #[test]
+    fn test_count_inversions_large() {
+        let n = 100_000;
+        let sorted: Vec<u64> = (0..n).collect();
+        let reversed: Vec<u64> = (0..n).rev().collect();
+        let total = (n * (n - 1) / 2) as usize;
+        assert_eq!(count_inversions(&sorted), 0);
+        assert_eq!(count_inversions_bit(&sorted), 0);
+        assert_eq!(count_inversions(&reversed), total);
+        assert_eq!(count_inversions_bit(&reversed), total);
+
+        let mut rng = rand::thread_rng();
+        let v: Vec<u64> = (0..n).map(|_| rng.gen()).collect();
+        assert_eq!(count_inversions(&v), count_inversions_bit(&v));
+
+        // 交换一对相邻的逆序元素恰好减少一个逆序对
+        let mut w = v.clone();
+        let i = (0..w.len() - 1).find(|&i| w[i] > w[i + 1]).unwrap();
+        w.swap(i, i + 1);
+        assert_eq!(count_inversions(&w) + 1, count_inversions(&v));
+
+        let empty: [u8; 0] = [];
+        assert_eq!(count_inversions(&empty), 0);
+        assert_eq!(count_inversions_bit(&empty), 0);
+    }
+}
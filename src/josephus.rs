@@ -18,6 +18,16 @@
 //! ```text
 //! J(n, k) = (J(n-1, k) + k) % n
 //! ```
+//!
+//! 当 n 很大而 k 很小时, 可以一次踢出 ⌊n/k⌋ 个人而不是一个:
+//! 第一轮踢出编号 k-1, 2k-1... 之后从编号 ⌊n/k⌋·k 开始重新计数, 剩下 n - ⌊n/k⌋ 个人,
+//! 递归求出的编号需要映射回原来的编号, 每踢出 k-1 个人就跳过一个被踢出的位置, 复杂度 O(k logn)
+//!
+//! k = 2 时有封闭形式: 记 n = 2^m + l (0 <= l < 2^m), 则 J(n, 2) = 2l
+//!
+//! 如果需要完整的出圈顺序, 可以用树状数组维护每个位置是否还在圈中,
+//! 每次用 [`crate::binary_indexed_tree::find_kth`] 找到剩下的人中的第 m 个, O(logn) 一次,
+//! 见 [`josephus_order`]
 
 /// 约瑟夫问题线性解法
 pub fn josephus_linear(n: usize, k: usize) -> usize {
@@ -29,6 +39,130 @@ pub fn josephus_linear(n: usize, k: usize) -> usize {
     idx
 }
 
+/// 约瑟夫问题递归解法, 和线性解法使用同一个递推式
+pub fn josephus_recursive(n: usize, k: usize) -> usize {
+    if n == 1 {
+        return 0;
+    }
+
+    (josephus_recursive(n - 1, k) + k) % n
+}
+
+/// 约瑟夫问题 O(k logn) 解法, 适用于 n 很大 k 很小的情况
+///
+/// 每一轮只把 n 缩小 ⌊n/k⌋, 递归深度可以达到 O(k logn), 所以先记录下每一轮的人数再倒着映射回去  
+/// 人数小于 k 之后一轮只能踢出一个人, 直接用线性递推求解
+pub fn josephus_fast(n: usize, k: usize) -> usize {
+    if k == 1 {
+        return n - 1;
+    }
+
+    let mut rounds = vec![];
+    let mut m = n;
+    while m >= k {
+        rounds.push(m);
+        m -= m / k;
+    }
+
+    let mut res = josephus_linear(m, k);
+    for m in rounds.into_iter().rev() {
+        // 一轮踢出 m / k 个人, 从 m / k * k 开始重新计数
+        res = match res.checked_sub(m % k) {
+            None => res + m - m % k,
+            Some(r) => r + r / (k - 1),
+        };
+    }
+
+    res
+}
+
+/// k = 2 时的封闭形式
+pub fn josephus_2(n: usize) -> usize {
+    assert!(n > 0);
+    let highest = 1 << n.ilog2();
+    2 * (n - highest)
+}
+
+/// 使用树状数组模拟求最后剩下的人
+pub fn josephus_bit(n: usize, k: usize) -> usize {
+    use crate::binary_indexed_tree::{find_kth, init, update};
+
+    assert!(n > 0 && k > 0, "n and k must be positive");
+    let mut t = init(&vec![1; n]);
+    // 当前位置在剩下的人中的排名(从 0 开始)
+    let mut rank = 0;
+    for remain in (2..=n).rev() {
+        rank = (rank + k - 1) % remain;
+        let pos = find_kth(&t, rank as isize + 1).expect("rank must be valid");
+        update(&mut t, pos, -1);
+    }
+
+    find_kth(&t, 1).expect("one person must remain")
+}
+
+/// 完整的出圈顺序, 使用树状数组模拟, 总共 O(nlogn)
+///
+/// 和 [`josephus_bit`] 一样维护当前位置在剩下的人中的排名, 只是记录下每次踢出的编号  
+/// n = 1e6 时也可以很快求出, 朴素的模拟见 [`elimination_order`]
+///
+/// ```
+/// use impx::josephus::josephus_order;
+///
+/// assert_eq!(josephus_order(7, 3), vec![2, 5, 1, 6, 4, 0, 3]);
+/// ```
+pub fn josephus_order(n: usize, k: usize) -> Vec<usize> {
+    use crate::binary_indexed_tree::{find_kth, init, update};
+
+    assert!(k > 0);
+    let mut t = init(&vec![1; n]);
+    let mut order = Vec::with_capacity(n);
+    let mut rank = 0;
+    for remain in (1..=n).rev() {
+        rank = (rank + k - 1) % remain;
+        let pos = find_kth(&t, rank as isize + 1).expect("rank must be valid");
+        update(&mut t, pos, -1);
+        order.push(pos);
+    }
+
+    order
+}
+
+/// 完整的出圈顺序, 直接在数组上删除模拟, 每次 O(n)
+pub fn elimination_order(n: usize, k: usize) -> EliminationOrder {
+    assert!(k > 0);
+    EliminationOrder {
+        people: (0..n).collect(),
+        k,
+        curr: 0,
+    }
+}
+
+/// 按出圈顺序返回编号的迭代器
+pub struct EliminationOrder {
+    people: Vec<usize>,
+    k: usize,
+    curr: usize,
+}
+
+impl Iterator for EliminationOrder {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.people.is_empty() {
+            return None;
+        }
+
+        self.curr = (self.curr + self.k - 1) % self.people.len();
+        Some(self.people.remove(self.curr))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.people.len(), Some(self.people.len()))
+    }
+}
+
+impl ExactSizeIterator for EliminationOrder {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -43,4 +177,67 @@ mod tests {
         assert_eq!(josephus_linear(66, 100), 6);
         assert_eq!(josephus_linear(1000, 123), 1);
     }
+
+    #[test]
+    fn test_josephus_variants() {
+        assert_eq!(
+            elimination_order(7, 3).collect::<Vec<_>>(),
+            vec![2, 5, 1, 6, 4, 0, 3]
+        );
+        assert_eq!(elimination_order(0, 3).count(), 0);
+
+        for n in 1..80 {
+            for k in 1..20 {
+                let expect = elimination_order(n, k).last().unwrap();
+                assert_eq!(josephus_linear(n, k), expect);
+                assert_eq!(josephus_recursive(n, k), expect);
+                assert_eq!(josephus_fast(n, k), expect);
+                assert_eq!(josephus_bit(n, k), expect);
+            }
+            assert_eq!(josephus_2(n), josephus_linear(n, 2));
+        }
+
+        for n in 0..80 {
+            for k in 1..20 {
+                assert!(josephus_order(n, k).into_iter().eq(elimination_order(n, k)));
+            }
+        }
+
+        // n = 1e6 时树状数组模拟出完整的出圈顺序
+        let n = 1_000_000;
+        for k in [2, 7, 1_000_003] {
+            let order = josephus_order(n, k);
+            assert_eq!(order.len(), n);
+            assert_eq!(*order.last().unwrap(), josephus_linear(n, k));
+            let mut seen = vec![false; n];
+            for &i in &order {
+                assert!(!std::mem::replace(&mut seen[i], true));
+            }
+        }
+
+        // n 很大时 O(k logn) 解法依然很快
+        assert_eq!(josephus_fast(10_000_000, 3), josephus_linear(10_000_000, 3));
+        // k 很大时每轮只缩小很少的人数, 不能递归
+        assert_eq!(
+            josephus_fast(10_000_000, 1_000_000),
+            josephus_linear(10_000_000, 1_000_000)
+        );
+        assert_eq!(
+            josephus_fast(1_000_000, 10_000_000),
+            josephus_linear(1_000_000, 10_000_000)
+        );
+        assert_eq!(josephus_2(1 << 40), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "n and k must be positive")]
+    fn test_josephus_bit_empty() {
+        josephus_bit(0, 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "n and k must be positive")]
+    fn test_josephus_bit_zero_step() {
+        josephus_bit(5, 0);
+    }
 }
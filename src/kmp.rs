@@ -1,6 +1,10 @@
 //! KMP 算法
 //!
 //! - [OI Wiki - 前缀函数与 KMP 算法](https://oi-wiki.org/string/kmp/)
+//!
+//! 所有算法都在字节序列 `&[u8]` 上进行, 返回的下标都是字节偏移, 字符串版本只是对字节版本的包装  
+//! UTF-8 中多字节字符的首字节(`0xC0..`)和后续字节(`0x80..0xC0`)的取值范围不重叠, 单字节字符都小于 `0x80`,
+//! 所以一个合法的 UTF-8 模式串在合法的 UTF-8 主串中只可能从字符边界开始匹配, 按字节匹配的结果可以直接用来切片
 
 /// 前缀函数
 ///
@@ -35,43 +39,17 @@
 /// - 即可得到 `j = p[len(sp)-1] = p[len(p)-1]`
 /// - 由此可以得到一个状态转移方程对于长度第 n 大的相同前后缀 `j(n) = p[j(n-1)-1] (j(n-1) > 0)`
 ///
+/// 朴素算法和优化算法1见 [`prefix_n_naive`], 这里只实现优化算法2, 时间复杂度 O(n):
+/// j 每次最多加 1, 每次回退至少减 1, 所以回退的总次数不超过 n
+///
 /// ```
 /// use impx::kmp::prefix_n;
 ///
-/// let p = prefix_n("abcab");
+/// let p = prefix_n(b"abcab");
 /// assert_eq!(2, p[4]); // 最大相同前后缀为 ab
 /// ```
-pub fn prefix_n(s: &str) -> Vec<usize> {
+pub fn prefix_n(s: &[u8]) -> Vec<usize> {
     let n = s.len();
-
-    // 朴素算法
-    let mut p = vec![0; n];
-    for i in 1..n {
-        // 字串为 S[..i] 长度为 i+1
-        for j in (0..(i + 1)).rev() {
-            // 在字串中分别从大到小取 j 个测试是否真前后缀相同
-            if s[..j] == s[(i + 1 - j)..(i + 1)] {
-                p[i] = j;
-                break;
-            }
-        }
-    }
-
-    // 优化算法1
-    let mut p = vec![0; n];
-    for i in 1..n {
-        // 字串为 S[..i] 长度为 i+1
-        for j in (0..=(p[i - 1] + 1)).rev() {
-            // p[i] <= p[i-1]+1 直接从 i-1 开始遍历
-            if s[..j] == s[(i + 1 - j)..(i + 1)] {
-                p[i] = j;
-                break;
-            }
-        }
-    }
-
-    // 优化算法2
-    let chars = s.as_bytes();
     let mut p = vec![0; n];
     for i in 1..n {
         // 先找到上一个子串的最大相同前后缀长度
@@ -79,16 +57,16 @@ pub fn prefix_n(s: &str) -> Vec<usize> {
 
         // 如果 s[i] != s[j] 则需要找到前一个子串的次级最大相同前后缀长度 j
         // 然后继续比较直到 j 为 0
-        // 或者当前子串的最后一个字符 chars[i] 与最大前缀的后一个字符 chars[j] 相等
-        while j > 0 && chars[i] != chars[j] {
+        // 或者当前子串的最后一个字符 s[i] 与最大前缀的后一个字符 s[j] 相等
+        while j > 0 && s[i] != s[j] {
             j = p[j - 1];
         }
 
         // 这里需要判断下是什么原因退出的循环
-        // 如果 chars[i] == chars[j] 直接将 j += 1 即可
+        // 如果 s[i] == s[j] 直接将 j += 1 即可
         // 即使此时 j == 0 也不影响
-        // 如果 chars[i] != chars[j] 则是因为 j == 0 退出的循环
-        if chars[i] == chars[j] {
+        // 如果 s[i] != s[j] 则是因为 j == 0 退出的循环
+        if s[i] == s[j] {
             j += 1;
         }
 
@@ -98,9 +76,37 @@ pub fn prefix_n(s: &str) -> Vec<usize> {
     p
 }
 
+/// 朴素算法计算前缀函数, 用于对照和测试
+///
+/// 对每个 i 从大到小枚举 j, 直接比较长度为 j 的前缀和后缀, j 从 i 开始枚举时是 O(n³)  
+/// 这里利用 `p[i] <= p[i-1]+1` 把 j 的上界缩小到 `p[i-1]+1`(优化算法1), 时间复杂度为 O(n²)
+///
+/// ```
+/// use impx::kmp::{prefix_n, prefix_n_naive};
+///
+/// assert_eq!(prefix_n_naive(b"abcabcd"), prefix_n(b"abcabcd"));
+/// ```
+pub fn prefix_n_naive(s: &[u8]) -> Vec<usize> {
+    let n = s.len();
+    let mut p = vec![0; n];
+    for i in 1..n {
+        // 字串为 S[..i] 长度为 i+1
+        for j in (0..=(p[i - 1] + 1)).rev() {
+            // 在字串中分别从大到小取 j 个测试是否真前后缀相同
+            // p[i] <= p[i-1]+1 直接从 p[i-1]+1 开始遍历
+            if s[..j] == s[(i + 1 - j)..(i + 1)] {
+                p[i] = j;
+                break;
+            }
+        }
+    }
+
+    p
+}
+
 /// Knuth-Morris-Pratt 算法
 ///
-/// 这里的 kmp 用来在字符串 s 中寻找第一次出现的子串 t  
+/// 这里的 kmp 用来在字符串 s 中寻找第一次出现的子串 t, 返回字节偏移  
 /// kmp 利用前缀函数的信息来减少重复的比较
 ///
 /// 假设有一个主串 `ababxxx...` 和一个子串 `ababcxxx`  
@@ -123,20 +129,79 @@ pub fn prefix_n(s: &str) -> Vec<usize> {
 ///
 /// - 先计算子串 t 的前缀函数 P
 /// - 遍历主串 s 并保存当前已经匹配的数量 k
-/// - 如果 `s[i] == p[k]` 那么将已经匹配的数量 +1
-/// - 如果 `s[i] == p[k]` 而且之前已经有匹配的部分即 `k > 0`
+/// - 如果 `s[i] == t[k]` 那么将已经匹配的数量 +1
+/// - 如果 `s[i] != t[k]` 而且之前已经有匹配的部分即 `k > 0`
 ///     - 通过子串的前缀函数 P[k-1] 可以得到跟当前已经匹配部分的前缀相同的最大后缀长度
 ///     - 将这个最大后缀替换为当前匹配的前缀即可, 即将已经匹配的长度 k 修改为最大后缀长度
+///     - 替换之后下一个字符仍然可能不匹配, 需要重复这个过程直到匹配或者 k 为 0
+///
+/// ```
+/// use impx::kmp::kmp;
+///
+/// assert_eq!(kmp("你好, 世界", "世界"), Some(8));
+/// assert_eq!(kmp("abc", ""), Some(0));
+/// assert_eq!(kmp("abc", "d"), None);
+/// ```
 pub fn kmp(s: &str, t: &str) -> Option<usize> {
-    let p = prefix_n(t);
-    let t = t.as_bytes();
+    kmp_bytes(s.as_bytes(), t.as_bytes())
+}
+
+/// 字节序列上的 [`kmp`]
+pub fn kmp_bytes(s: &[u8], t: &[u8]) -> Option<usize> {
+    let mut res = None;
+    kmp_search(s, t, |i| {
+        res = Some(i);
+        false
+    });
+    res
+}
+
+/// 字符串 s 中所有子串 t 出现的位置(字节偏移), 匹配的位置可以重叠
+///
+/// t 为空时和 [`str::match_indices`] 一样在每个字符边界上都匹配一次
+///
+/// ```
+/// use impx::kmp::kmp_find_all;
+///
+/// assert_eq!(kmp_find_all("aaaa", "aa"), [0, 1, 2]);
+/// assert_eq!(kmp_find_all("ééé", "é"), [0, 2, 4]);
+/// assert_eq!(kmp_find_all("aé", ""), [0, 1, 3]);
+/// ```
+pub fn kmp_find_all(s: &str, t: &str) -> Vec<usize> {
+    if t.is_empty() {
+        return s.char_indices().map(|(i, _)| i).chain([s.len()]).collect();
+    }
+    kmp_find_all_bytes(s.as_bytes(), t.as_bytes())
+}
+
+/// 字节序列上的 [`kmp_find_all`], t 为空时在每个位置上都匹配一次
+pub fn kmp_find_all_bytes(s: &[u8], t: &[u8]) -> Vec<usize> {
+    let mut res = vec![];
+    kmp_search(s, t, |i| {
+        res.push(i);
+        true
+    });
+    res
+}
+
+/// 依次把每个匹配的起点传给 found, found 返回 false 时停止查找
+fn kmp_search(s: &[u8], t: &[u8], mut found: impl FnMut(usize) -> bool) {
     let n = t.len();
+    if n == 0 {
+        for i in 0..=s.len() {
+            if !found(i) {
+                return;
+            }
+        }
+        return;
+    }
 
+    let p = prefix_n(t);
     let mut k = 0; // 已经匹配的数量
-    for (i, &v) in s.as_bytes().iter().enumerate() {
+    for (i, &v) in s.iter().enumerate() {
         // 已经有部分匹配, 但是下一个不匹配
         // 这个时候需要根据前缀函数将相同后缀作为新一轮匹配的前缀
-        if k > 0 && v != t[k] {
+        while k > 0 && v != t[k] {
             k = p[k - 1]
         }
 
@@ -145,11 +210,13 @@ pub fn kmp(s: &str, t: &str) -> Option<usize> {
         }
 
         if k == n {
-            return Some(i + 1 - k);
+            if !found(i + 1 - n) {
+                return;
+            }
+            // 完整匹配之后把最长的相同前后缀作为下一轮的前缀, 这样可以找到重叠的匹配
+            k = p[k - 1];
         }
     }
-
-    None
 }
 
 #[cfg(test)]
@@ -158,14 +225,25 @@ mod tests {
     fn test_prefix_n() {
         use super::*;
 
-        let p = prefix_n("abcabcd");
+        let p = prefix_n(b"abcabcd");
         assert_eq!(&p, &[0, 0, 0, 1, 2, 3, 0]);
 
-        let s = "abaabbbaabbaaaabaab";
+        let s = b"abaabbbaabbaaaabaab";
         let p = prefix_n(s);
         for (i, &v) in p.iter().enumerate() {
             assert_eq!(s[..v], s[(i + 1 - v)..(i + 1)])
         }
+        assert_eq!(prefix_n_naive(s), p);
+        assert!(prefix_n(b"").is_empty());
+
+        use rand::Rng;
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let s: Vec<u8> = (0..rng.gen_range(0..50))
+                .map(|_| rng.gen_range(b'a'..b'c'))
+                .collect();
+            assert_eq!(prefix_n(&s), prefix_n_naive(&s));
+        }
     }
 
     #[test]
@@ -177,6 +255,44 @@ mod tests {
         assert_eq!(Some(3), kmp("abababxxc", "babx"));
         assert_eq!(Some(5), kmp("klslapwosldkal", "pwo"));
         assert_eq!(Some(2), kmp("pqpsapspsp", "ps"));
-        assert_eq!(Some(6), kmp("bacbadababacamcaddababaca", "ababaca"),)
+        assert_eq!(Some(6), kmp("bacbadababacamcaddababaca", "ababaca"),);
+        // 失配后需要连续回退多次, 只回退一次会把 b 当作已经匹配的 a
+        assert_eq!(None, kmp("aabaa", "aaa"));
+        assert_eq!(Some(3), kmp("aabaaa", "aaa"));
+    }
+
+    #[test]
+    fn test_kmp_utf8() {
+        use super::*;
+
+        // 多字节字符内部不会产生错误的匹配, 下标是字节偏移
+        let s = "日本語の日本";
+        assert_eq!(kmp_find_all(s, "日本"), [0, 12]);
+        assert_eq!(&s[kmp(s, "の").unwrap()..], "の日本");
+        assert_eq!(prefix_n("ééé".as_bytes()), [0, 0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_kmp_find_all() {
+        use super::*;
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..200 {
+            // 小字母表更容易产生匹配和多次回退
+            let s: Vec<u8> = (0..rng.gen_range(0..60))
+                .map(|_| rng.gen_range(b'a'..b'c'))
+                .collect();
+            let t: Vec<u8> = (0..rng.gen_range(0..5))
+                .map(|_| rng.gen_range(b'a'..b'c'))
+                .collect();
+            let expect: Vec<_> = (0..=s.len()).filter(|&i| s[i..].starts_with(&t)).collect();
+            assert_eq!(kmp_find_all_bytes(&s, &t), expect);
+            assert_eq!(kmp_bytes(&s, &t), expect.first().copied());
+
+            let (s, t) = (String::from_utf8(s).unwrap(), String::from_utf8(t).unwrap());
+            let expect: Vec<_> = (0..=s.len()).filter(|&i| s[i..].starts_with(&t)).collect();
+            assert_eq!(kmp_find_all(&s, &t), expect);
+        }
     }
 }
@@ -0,0 +1,264 @@
+//! 背包问题和子集和
+//!
+//! - [OI Wiki - 背包 DP](https://oi-wiki.org/dp/knapsack/)
+//! - [wikipedia - Subset sum problem](https://en.wikipedia.org/wiki/Subset_sum_problem)
+//!
+//! n 个物品, 第 i 个重量为 `w[i]` 价值为 `v[i]`, 在总重量不超过 W 的前提下求最大价值
+//!
+//! - 0/1 背包: 每个物品最多选一次, `dp[c] = max(dp[c], dp[c - w[i]] + v[i])`,
+//!   只保留一行时 c 要从大到小遍历, 保证 `dp[c - w[i]]` 还是上一个物品的结果;
+//!   需要找回选了哪些物品时见 [`crate::dp::knapsack_01`]
+//! - 完全背包: 每个物品可以选任意次, 同样的转移但 c 从小到大遍历, `dp[c - w[i]]` 可能已经选过第 i 个物品
+//! - 多重背包: 第 i 个物品最多选 `k[i]` 次, 把 k 拆成 1, 2, 4, ..., 2^p 和剩下的部分,
+//!   任意 0..=k 都可以由其中若干份凑出, 于是转化为 O(Σlogk) 个物品的 0/1 背包
+//!
+//! 子集和: 判断能否从若干个数中选出一些使得和恰好为 t, 是价值只有 0/1 的 0/1 背包,
+//! 朴素做法用 bool 数组 `dp[s] |= dp[s - x]`, O(n·t)
+//!
+//! 注意到加入一个数 x 相当于把整个可达集合平移 x 再取并集, 用 [`BitSet`] 表示可达集合就是 `dp |= dp << x`,
+//! 每次处理 64 位, 复杂度 O(n·t/64)
+
+use crate::bitset::BitSet;
+
+/// 0/1 背包的最大价值, 滚动数组 O(n·W) 时间 O(W) 空间
+///
+/// ```
+/// use impx::knapsack::zero_one;
+///
+/// assert_eq!(zero_one(&[1, 3, 4, 5], &[1, 4, 5, 7], 7), 9);
+/// ```
+pub fn zero_one(weights: &[usize], values: &[u64], capacity: usize) -> u64 {
+    assert_eq!(
+        weights.len(),
+        values.len(),
+        "weights and values must have the same length"
+    );
+
+    let mut dp = vec![0u64; capacity + 1];
+    for (&w, &v) in weights.iter().zip(values) {
+        for c in (w..=capacity).rev() {
+            dp[c] = dp[c].max(dp[c - w] + v);
+        }
+    }
+
+    dp[capacity]
+}
+
+/// 完全背包, 返回最大价值和每个物品选择的次数
+///
+/// 记录每个容量最后一次更新时选择的物品, 从 W 开始不断减去该物品的重量就能找回方案
+///
+/// ```
+/// use impx::knapsack::unbounded;
+///
+/// let (best, counts) = unbounded(&[3, 4], &[4, 6], 10);
+/// assert_eq!(best, 14);
+/// assert_eq!(counts, vec![2, 1]);
+/// ```
+pub fn unbounded(weights: &[usize], values: &[u64], capacity: usize) -> (u64, Vec<usize>) {
+    assert_eq!(
+        weights.len(),
+        values.len(),
+        "weights and values must have the same length"
+    );
+
+    let mut dp = vec![0u64; capacity + 1];
+    let mut choice = vec![None; capacity + 1];
+    for (i, (&w, &v)) in weights.iter().zip(values).enumerate() {
+        // 重量为 0 的物品可以无限选, 这里忽略
+        if w == 0 {
+            continue;
+        }
+
+        for c in w..=capacity {
+            if dp[c - w] + v > dp[c] {
+                dp[c] = dp[c - w] + v;
+                choice[c] = Some(i);
+            }
+        }
+    }
+
+    // 最终的 dp 是最优解, 所以 dp[c] 最后一次更新时的 dp[c - w] 也已经是最优的
+    // choice 为 None 的容量 dp 为 0, 说明剩下的容量不需要再放任何物品
+    let mut counts = vec![0; weights.len()];
+    let mut c = capacity;
+    while let Some(i) = choice[c] {
+        counts[i] += 1;
+        c -= weights[i];
+    }
+
+    (dp[capacity], counts)
+}
+
+/// 多重背包, 第 i 个物品最多选 `counts[i]` 次, 二进制拆分后按 0/1 背包求解, O(W·Σlogk)
+///
+/// ```
+/// use impx::knapsack::bounded;
+///
+/// assert_eq!(bounded(&[3, 4], &[4, 6], &[3, 1], 10), 14);
+/// assert_eq!(bounded(&[3, 4], &[4, 6], &[3, 0], 10), 12);
+/// ```
+pub fn bounded(weights: &[usize], values: &[u64], counts: &[usize], capacity: usize) -> u64 {
+    assert!(
+        weights.len() == values.len() && weights.len() == counts.len(),
+        "weights, values and counts must have the same length"
+    );
+
+    let (mut ws, mut vs) = (vec![], vec![]);
+    for i in 0..weights.len() {
+        let mut rest = counts[i];
+        let mut k = 1;
+        while rest > 0 {
+            let take = k.min(rest);
+            ws.push(weights[i] * take);
+            vs.push(values[i] * take as u64);
+            rest -= take;
+            k <<= 1;
+        }
+    }
+
+    zero_one(&ws, &vs, capacity)
+}
+
+/// 朴素的子集和, O(n·t)
+pub fn subset_sum_naive(items: &[usize], target: usize) -> bool {
+    let mut dp = vec![false; target + 1];
+    dp[0] = true;
+    for &x in items {
+        for s in (x..=target).rev() {
+            dp[s] |= dp[s - x];
+        }
+    }
+
+    dp[target]
+}
+
+/// 用位图求出 `[0, limit]` 内所有能被凑出的子集和, O(n·limit/64)
+///
+/// 返回的位图中第 s 位为 1 表示 s 可以被凑出, 大于 limit 的位都是 0
+pub fn subset_sums(items: &[usize], limit: usize) -> BitSet {
+    let mut dp = BitSet::new(limit + 1);
+    dp.set(0);
+    for &x in items.iter().filter(|&&x| x <= limit) {
+        dp.or_shl(x);
+    }
+
+    // 位图大小按 64 对齐, 清除对齐部分中超过 limit 的和
+    for s in limit + 1..dp.len() {
+        dp.unset(s);
+    }
+
+    dp
+}
+
+/// 能否选出若干个数使得和恰好为 target
+///
+/// ```
+/// use impx::knapsack::subset_sum;
+///
+/// assert!(subset_sum(&[3, 34, 4, 12, 5, 2], 9));
+/// assert!(!subset_sum(&[3, 34, 4, 12, 5, 2], 30));
+/// ```
+pub fn subset_sum(items: &[usize], target: usize) -> bool {
+    subset_sums(items, target).test(target)
+}
+
+/// 把所有数分成两组, 两组和之差的最小值
+///
+/// 总和为 S 时, 找到不超过 S/2 的最大子集和 s, 答案就是 S - 2s
+///
+/// ```
+/// use impx::knapsack::min_partition_diff;
+///
+/// assert_eq!(min_partition_diff(&[1, 6, 11, 5]), 1);
+/// assert_eq!(min_partition_diff(&[3, 1, 4, 2, 2]), 0);
+/// ```
+pub fn min_partition_diff(items: &[usize]) -> usize {
+    let total: usize = items.iter().sum();
+    let sums = subset_sums(items, total / 2);
+    let best = (0..=total / 2).rev().find(|&s| sums.test(s)).unwrap();
+    total - 2 * best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    /// 枚举每个物品选择的次数
+    fn brute(weights: &[usize], values: &[u64], counts: &[usize], capacity: usize) -> u64 {
+        if weights.is_empty() {
+            return 0;
+        }
+
+        (0..=counts[0])
+            .take_while(|&k| k * weights[0] <= capacity)
+            .map(|k| {
+                k as u64 * values[0]
+                    + brute(
+                        &weights[1..],
+                        &values[1..],
+                        &counts[1..],
+                        capacity - k * weights[0],
+                    )
+            })
+            .max()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_knapsack_brute() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..200 {
+            let n = rng.gen_range(0..6);
+            let weights: Vec<usize> = (0..n).map(|_| rng.gen_range(1..10)).collect();
+            let values: Vec<u64> = (0..n).map(|_| rng.gen_range(0..20)).collect();
+            let counts: Vec<usize> = (0..n).map(|_| rng.gen_range(0..4)).collect();
+            let capacity = rng.gen_range(0..30);
+
+            let ones = vec![1; n];
+            assert_eq!(
+                zero_one(&weights, &values, capacity),
+                brute(&weights, &values, &ones, capacity)
+            );
+            assert_eq!(
+                zero_one(&weights, &values, capacity),
+                crate::dp::knapsack_01(&weights, &values, capacity).0
+            );
+            assert_eq!(
+                bounded(&weights, &values, &counts, capacity),
+                brute(&weights, &values, &counts, capacity)
+            );
+
+            let (best, chosen) = unbounded(&weights, &values, capacity);
+            let unlimited = vec![capacity; n];
+            assert_eq!(best, brute(&weights, &values, &unlimited, capacity));
+            let w: usize = chosen.iter().zip(&weights).map(|(k, w)| k * w).sum();
+            let v: u64 = chosen.iter().zip(&values).map(|(&k, v)| k as u64 * v).sum();
+            assert!(w <= capacity);
+            assert_eq!(v, best);
+        }
+    }
+
+    #[test]
+    fn test_subset_sum() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..50 {
+            let n = rng.gen_range(0..30);
+            let items: Vec<usize> = (0..n).map(|_| rng.gen_range(0..200)).collect();
+            let limit = rng.gen_range(0..1000);
+
+            let sums = subset_sums(&items, limit);
+            for s in 0..sums.len() {
+                assert_eq!(sums.test(s), s <= limit && subset_sum_naive(&items, s));
+            }
+            assert_eq!(subset_sum(&items, limit), subset_sum_naive(&items, limit));
+        }
+
+        assert!(subset_sum(&[], 0));
+        assert!(!subset_sum(&[], 1));
+        assert_eq!(min_partition_diff(&[]), 0);
+        assert_eq!(min_partition_diff(&[7]), 7);
+        assert_eq!(min_partition_diff(&[64, 1, 63]), 0);
+    }
+}
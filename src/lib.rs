@@ -7,10 +7,14 @@
 
 #![feature(is_sorted)]
 
+pub mod arc_cache;
+pub mod arena;
 pub mod array;
 pub mod avl_tree;
 pub mod b_plus_tree;
 pub mod b_tree;
+pub mod balanced_parens;
+pub mod bigint;
 pub mod binary_heap;
 pub mod binary_indexed_tree;
 pub mod binary_search;
@@ -19,26 +23,73 @@ pub mod binary_tree;
 pub mod bitset;
 pub mod bloom_filter;
 pub mod cartesian_tree;
+pub mod chtholly_tree;
+pub mod combinatorics;
+pub mod concurrent_skip_list;
 pub mod consistent_hashmap;
+pub mod count_min_sketch;
 pub mod crc32;
 pub mod cuckoo_filter;
+pub mod cuckoo_hash_map;
+pub mod difference_constraints;
 pub mod disjoint_set;
+pub mod dp;
+pub mod expression;
+pub mod fft;
+pub mod filter;
+pub mod graph_coloring;
+pub mod graph_connectivity;
 pub mod graph_shortest_path;
+pub mod graph_traversal;
+pub mod hash_map;
 pub mod huffman_tree;
+pub mod inversions;
+pub mod hyperloglog;
 pub mod josephus;
+pub mod knapsack;
 pub mod kmp;
 pub mod linked_list;
 pub mod linked_list_box;
 pub mod linked_list_rc;
 pub mod linked_list_refcell;
+pub mod lis;
+pub mod llrb_tree;
+pub mod lru_cache;
 pub mod matrix_exp;
+pub mod minhash;
 pub mod minimum_spanning_tree;
+pub mod mod_int;
+pub mod my_vec;
+pub mod number_theory;
+#[cfg(test)]
+mod oracle;
+pub mod persistent_vec;
+pub mod polynomial;
+#[cfg(test)]
+mod properties;
+pub mod quadtree;
 pub mod radix_tree;
 pub mod rb_tree;
+pub mod ring_buffer;
+pub mod rng;
+pub mod rope;
+pub mod running_median;
 pub mod segment_tree;
 pub mod skip_list;
+pub mod small_vec;
 pub mod sorting;
+#[cfg(all(test, feature = "soundness"))]
+mod soundness;
 pub mod sparse_table;
+pub mod sqrt_decomposition;
+pub mod stack_queue;
 pub mod string;
+pub mod sweep_line;
+pub mod top_k;
 pub mod treap;
 pub mod trie;
+pub mod tsp;
+pub mod two_sat;
+pub mod veb_tree;
+pub mod visualize;
+pub mod wavelet_matrix;
@@ -215,6 +215,124 @@ impl<T> LinkedList<T> {
     }
 }
 
+/// 节点句柄
+///
+/// 指向链表中某个节点, 可以在 O(1) 时间内访问, 删除或移动这个节点  
+/// 句柄不持有链表的借用, 所以需要调用方保证句柄属于这个链表且对应节点还没有被删除
+pub struct Handle<T> {
+    node: NonNull<Node<T>>,
+}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Handle<T> {}
+
+impl<T> LinkedList<T> {
+    /// 在头部插入并返回新节点的句柄
+    pub fn push_front_handle(&mut self, elem: T) -> Handle<T> {
+        self.push_front(elem);
+        Handle {
+            node: self.front.expect("front must exist after push"),
+        }
+    }
+
+    /// 在尾部插入并返回新节点的句柄
+    pub fn push_back_handle(&mut self, elem: T) -> Handle<T> {
+        self.push_back(elem);
+        Handle {
+            node: self.back.expect("back must exist after push"),
+        }
+    }
+
+    /// 头节点句柄
+    pub fn front_handle(&self) -> Option<Handle<T>> {
+        self.front.map(|node| Handle { node })
+    }
+
+    /// 尾节点句柄
+    pub fn back_handle(&self) -> Option<Handle<T>> {
+        self.back.map(|node| Handle { node })
+    }
+
+    /// 通过句柄访问节点元素
+    ///
+    /// # Safety
+    ///
+    /// 句柄必须属于当前链表且节点没有被删除
+    pub unsafe fn get(&self, handle: Handle<T>) -> &T {
+        &(*handle.node.as_ptr()).elem
+    }
+
+    /// 通过句柄访问节点元素的可变引用
+    ///
+    /// # Safety
+    ///
+    /// 句柄必须属于当前链表且节点没有被删除
+    pub unsafe fn get_mut(&mut self, handle: Handle<T>) -> &mut T {
+        &mut (*handle.node.as_ptr()).elem
+    }
+
+    /// 把节点从链表中摘下来, 修复前后节点以及 front/back 的指针
+    unsafe fn unlink(&mut self, node: NonNull<Node<T>>) {
+        let front = (*node.as_ptr()).front.take();
+        let back = (*node.as_ptr()).back.take();
+
+        match front {
+            Some(prev) => (*prev.as_ptr()).back = back,
+            None => self.front = back,
+        }
+
+        match back {
+            Some(next) => (*next.as_ptr()).front = front,
+            None => self.back = front,
+        }
+
+        self.len -= 1;
+    }
+
+    /// 通过句柄删除节点并返回元素
+    ///
+    /// # Safety
+    ///
+    /// 句柄必须属于当前链表且节点没有被删除, 删除后句柄失效
+    pub unsafe fn remove(&mut self, handle: Handle<T>) -> T {
+        self.unlink(handle.node);
+        Box::from_raw(handle.node.as_ptr()).elem
+    }
+
+    /// 把句柄对应的节点移动到链表头部
+    ///
+    /// # Safety
+    ///
+    /// 句柄必须属于当前链表且节点没有被删除
+    pub unsafe fn move_to_front(&mut self, handle: Handle<T>) {
+        let node = handle.node;
+        if self.front == Some(node) {
+            return;
+        }
+
+        self.unlink(node);
+        (*node.as_ptr()).back = self.front;
+        match self.front {
+            Some(old) => (*old.as_ptr()).front = Some(node),
+            None => self.back = Some(node),
+        }
+
+        self.front = Some(node);
+        self.len += 1;
+    }
+}
+
+impl<T> Drop for LinkedList<T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
 impl<T> Default for LinkedList<T> {
     fn default() -> Self {
         Self::new()
@@ -496,6 +614,35 @@ impl<'a, T> CursorMut<'a, T> {
         }
     }
 
+    /// 在游标之前插入一个元素, 处于 ghost 节点时插入到尾部
+    pub fn insert_before(&mut self, elem: T) {
+        let mut input = LinkedList::new();
+        input.push_back(elem);
+        self.splice_before(input);
+    }
+
+    /// 在游标之后插入一个元素, 处于 ghost 节点时插入到头部
+    pub fn insert_after(&mut self, elem: T) {
+        let mut input = LinkedList::new();
+        input.push_back(elem);
+        self.splice_after(input);
+    }
+
+    /// 删除当前节点并返回元素, 游标移动到下一个节点, 当前节点是尾节点时回到 ghost 节点
+    pub fn remove_current(&mut self) -> Option<T> {
+        let curr = self.curr?;
+        unsafe {
+            // 后面的节点接替当前位置, index 不变
+            self.curr = (*curr.as_ptr()).back;
+            if self.curr.is_none() {
+                self.index = None;
+            }
+
+            self.list.unlink(curr);
+            Some(Box::from_raw(curr.as_ptr()).elem)
+        }
+    }
+
     /// 按照当前位置将原始链表切割成两部分, 并返回前半部分, 当前位置属于后半部分
     pub fn split_before(&mut self) -> LinkedList<T> {
         // list.front -> A <-> B <-> C <-> D <- list.back
@@ -1183,7 +1330,7 @@ mod tests {
         cursor.move_next();
         cursor.move_prev();
         let tmp = cursor.split_before();
-        assert_eq!(m.into_iter().collect::<Vec<_>>(), &[]);
+        assert_eq!(m.into_iter().collect::<Vec<_>>(), &[] as &[u32]);
         m = tmp;
         let mut cursor = m.cursor_mut();
         cursor.move_next();
@@ -1204,4 +1351,78 @@ mod tests {
             &[200, 201, 202, 203, 1, 100, 101]
         );
     }
+
+    #[test]
+    fn test_cursor_insert_remove() {
+        let mut m: LinkedList<u32> = LinkedList::new();
+        let mut cursor = m.cursor_mut();
+        assert_eq!(cursor.remove_current(), None);
+        // ghost 节点上 insert_before 插入尾部, insert_after 插入头部
+        cursor.insert_before(2);
+        cursor.insert_after(1);
+        cursor.insert_before(3);
+        assert_eq!(cursor.index(), None);
+        check_links(&m);
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[1, 2, 3]);
+
+        let mut cursor = m.cursor_mut();
+        cursor.move_next();
+        cursor.insert_before(0);
+        cursor.insert_after(4);
+        assert_eq!(cursor.index(), Some(1));
+        assert_eq!(cursor.current(), Some(&mut 1));
+        assert_eq!(cursor.remove_current(), Some(1));
+        assert_eq!(cursor.index(), Some(1));
+        assert_eq!(cursor.current(), Some(&mut 4));
+        check_links(&m);
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[0, 4, 2, 3]);
+
+        // 删除尾节点后回到 ghost 节点
+        let mut cursor = m.cursor_mut();
+        cursor.move_prev();
+        assert_eq!(cursor.remove_current(), Some(3));
+        assert_eq!(cursor.index(), None);
+        cursor.move_next();
+        assert_eq!(cursor.remove_current(), Some(0));
+        assert_eq!(cursor.remove_current(), Some(4));
+        assert_eq!(cursor.remove_current(), Some(2));
+        assert_eq!(cursor.remove_current(), None);
+        assert!(m.is_empty());
+        assert!(m.front().is_none() && m.back().is_none());
+    }
+
+    #[test]
+    fn test_handle() {
+        let mut m = LinkedList::new();
+        let h1 = m.push_back_handle(1);
+        let h2 = m.push_back_handle(2);
+        let h3 = m.push_back_handle(3);
+        let h0 = m.push_front_handle(0);
+
+        unsafe {
+            assert_eq!(m.get(h2), &2);
+            *m.get_mut(h2) = 20;
+
+            m.move_to_front(h3);
+            check_links(&m);
+            assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[3, 0, 1, 20]);
+
+            m.move_to_front(h3);
+            m.move_to_front(h2);
+            check_links(&m);
+            assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[20, 3, 0, 1]);
+
+            assert_eq!(m.remove(h1), 1);
+            assert_eq!(m.remove(h2), 20);
+            check_links(&m);
+            assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[3, 0]);
+            assert_eq!(m.len(), 2);
+
+            assert_eq!(m.remove(h0), 0);
+            assert_eq!(m.remove(h3), 3);
+            assert!(m.is_empty());
+            assert!(m.front_handle().is_none());
+            assert!(m.back_handle().is_none());
+        }
+    }
 }
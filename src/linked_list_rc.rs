@@ -51,6 +51,15 @@ impl<T> List<T> {
     }
 }
 
+impl<T> Clone for List<T> {
+    /// 只增加头节点的引用计数, 新旧两个链表共享所有节点
+    fn clone(&self) -> Self {
+        List {
+            head: self.head.clone(),
+        }
+    }
+}
+
 impl<T> Drop for List<T> {
     fn drop(&mut self) {
         let mut curr = self.head.take();
@@ -85,6 +94,7 @@ impl<'a, T> Iterator for Iter<'a, T> {
 #[cfg(test)]
 mod tests {
     use super::List;
+    use std::rc::Rc;
 
     #[test]
     fn test_basic() {
@@ -117,4 +127,28 @@ mod tests {
         assert_eq!(iter.next(), Some(&2));
         assert_eq!(iter.next(), Some(&1));
     }
+
+    #[test]
+    fn test_sharing() {
+        let list = List::new().prepend(1).prepend(2);
+        let a = list.prepend(3);
+        let b = list.clone().prepend(4);
+        let shared = list.head.clone().unwrap();
+
+        // list, a 和 b 都持有节点 2
+        assert!(Rc::ptr_eq(
+            &shared,
+            a.head.as_ref().unwrap().next.as_ref().unwrap()
+        ));
+        assert!(Rc::ptr_eq(
+            &shared,
+            b.head.as_ref().unwrap().next.as_ref().unwrap()
+        ));
+        assert_eq!(Rc::strong_count(&shared), 4);
+
+        drop(list);
+        drop(a);
+        assert_eq!(Rc::strong_count(&shared), 2);
+        assert!(b.iter().eq(&[4, 2, 1]));
+    }
 }
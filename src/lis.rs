@@ -0,0 +1,170 @@
+//! 最长递增子序列
+//!
+//! - [OI Wiki - 最长不下降子序列](https://oi-wiki.org/dp/basic/#最长不下降子序列)
+//! - [wikipedia - Patience sorting](https://en.wikipedia.org/wiki/Patience_sorting)
+//!
+//! 朴素的 DP `dp[i] = max(dp[j] + 1) (j < i, a[j] < a[i])` 需要 O(n²), 耐心排序可以做到 O(nlogn):
+//!
+//! 把元素看作一张张牌, 从左到右依次发牌, 每张牌放到最左边的一个堆顶不小于它的牌堆上, 没有这样的牌堆时新开一堆
+//!
+//! ```text
+//! 发牌顺序: 10 9 2 5 3 7 101 18
+//!
+//! 堆 0  堆 1  堆 2  堆 3
+//!  10     5     7   101
+//!   9     3          18
+//!   2
+//! ```
+//!
+//! - 每个牌堆从底到顶是不增的, 所以任意递增子序列在每个牌堆中最多取一张牌, LIS 的长度不超过牌堆数
+//! - 一张牌放到第 k 堆时, 第 k-1 堆的堆顶比它小并且更早发出, 记录这张堆顶作为它的前驱,
+//!   从最后一堆的任意一张牌沿着前驱往回走, 每堆取一张就得到一个长度等于牌堆数的递增子序列
+//!
+//! 所以牌堆数就是 LIS 的长度, 同时也是把序列划分为不增子序列的最少个数(Dilworth 定理的特例)
+//! 所有堆顶从左到右是严格递增的, 查找放在哪一堆可以二分, 只求长度时只需要保存堆顶
+//!
+//! 下标版本的实现见 [`crate::array::longest_increasing_subsequence`]
+
+use crate::array::longest_increasing_subsequence;
+use crate::binary_search::binary_search_first;
+
+/// 最长严格递增子序列的长度, 只保存每个牌堆的堆顶, O(nlogn)
+///
+/// ```
+/// use impx::lis::lis_length;
+///
+/// assert_eq!(lis_length(&[10, 9, 2, 5, 3, 7, 101, 18]), 4);
+/// assert_eq!(lis_length(&[7, 7, 7]), 1);
+/// ```
+pub fn lis_length<T: Ord>(v: &[T]) -> usize {
+    let mut tops: Vec<&T> = vec![];
+    for x in v {
+        if tops.last().is_none_or(|&t| t < x) {
+            tops.push(x);
+        } else {
+            let k = binary_search_first(&tops, |&t| t >= x).unwrap();
+            tops[k] = x;
+        }
+    }
+
+    tops.len()
+}
+
+/// 一个最长严格递增子序列, O(nlogn)
+///
+/// ```
+/// use impx::lis::lis;
+///
+/// assert_eq!(lis(&[10, 9, 2, 5, 3, 7, 101, 18]), vec![2, 3, 7, 18]);
+/// assert_eq!(lis(&["b", "a", "c"]), vec!["a", "c"]);
+/// ```
+pub fn lis<T: Ord + Clone>(v: &[T]) -> Vec<T> {
+    longest_increasing_subsequence(v)
+        .into_iter()
+        .map(|i| v[i].clone())
+        .collect()
+}
+
+/// 一个最长不下降子序列
+///
+/// 把每个元素和它的下标组成 `(a[i], i)`, 值相等时下标更大的更大,
+/// 原序列的不下降子序列和新序列的严格递增子序列一一对应
+///
+/// ```
+/// use impx::lis::longest_non_decreasing;
+///
+/// assert_eq!(longest_non_decreasing(&[3, 1, 2, 2, 1, 3]), vec![1, 2, 2, 3]);
+/// ```
+pub fn longest_non_decreasing<T: Ord + Clone>(v: &[T]) -> Vec<T> {
+    let pairs: Vec<(&T, usize)> = v.iter().zip(0..).collect();
+    longest_increasing_subsequence(&pairs)
+        .into_iter()
+        .map(|i| v[i].clone())
+        .collect()
+}
+
+/// 耐心排序发牌之后的所有牌堆, 每个牌堆从底到顶不增, 牌堆数等于 LIS 的长度
+///
+/// ```
+/// use impx::lis::patience_piles;
+///
+/// let piles = patience_piles(&[10, 9, 2, 5, 3, 7, 101, 18]);
+/// assert_eq!(piles, vec![vec![10, 9, 2], vec![5, 3], vec![7], vec![101, 18]]);
+/// ```
+pub fn patience_piles<T: Ord + Clone>(v: &[T]) -> Vec<Vec<T>> {
+    let mut piles: Vec<Vec<T>> = vec![];
+    for x in v {
+        // 堆顶从左到右严格递增
+        if piles.last().is_none_or(|p| *p.last().unwrap() < *x) {
+            piles.push(vec![x.clone()]);
+        } else {
+            let k = binary_search_first(&piles, |p| *p.last().unwrap() >= *x).unwrap();
+            piles[k].push(x.clone());
+        }
+    }
+
+    piles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    /// O(n²) 动态规划, strict 为 false 时求最长不下降子序列的长度
+    fn dp_length(v: &[i32], strict: bool) -> usize {
+        let mut dp = vec![1; v.len()];
+        for i in 0..v.len() {
+            for j in 0..i {
+                if v[j] < v[i] || (!strict && v[j] == v[i]) {
+                    dp[i] = dp[i].max(dp[j] + 1);
+                }
+            }
+        }
+        dp.into_iter().max().unwrap_or(0)
+    }
+
+    /// sub 是否是 v 的子序列
+    fn is_subsequence(sub: &[i32], v: &[i32]) -> bool {
+        let mut it = v.iter();
+        sub.iter().all(|x| it.any(|y| y == x))
+    }
+
+    #[test]
+    fn test_lis_brute() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..500 {
+            let n = rng.gen_range(0..50);
+            let max = rng.gen_range(1..50);
+            let v: Vec<i32> = (0..n).map(|_| rng.gen_range(0..max)).collect();
+
+            let len = dp_length(&v, true);
+            assert_eq!(lis_length(&v), len);
+            let s = lis(&v);
+            assert_eq!(s.len(), len);
+            assert!(s.windows(2).all(|w| w[0] < w[1]));
+            assert!(is_subsequence(&s, &v));
+
+            let s = longest_non_decreasing(&v);
+            assert_eq!(s.len(), dp_length(&v, false));
+            assert!(s.windows(2).all(|w| w[0] <= w[1]));
+            assert!(is_subsequence(&s, &v));
+
+            let piles = patience_piles(&v);
+            assert_eq!(piles.len(), len);
+            assert!(piles.iter().all(|p| p.windows(2).all(|w| w[0] >= w[1])));
+            // 所有牌堆合起来就是原来的所有元素, 归并之后就完成了耐心排序
+            let mut all: Vec<i32> = piles.into_iter().flatten().collect();
+            all.sort_unstable();
+            let mut sorted = v.clone();
+            sorted.sort_unstable();
+            assert_eq!(all, sorted);
+        }
+
+        assert_eq!(lis_length::<i32>(&[]), 0);
+        assert!(lis::<i32>(&[]).is_empty());
+        assert!(patience_piles::<i32>(&[]).is_empty());
+        assert_eq!(lis(&[5, 4, 3, 2, 1]).len(), 1);
+        assert_eq!(longest_non_decreasing(&[2, 2, 2]), vec![2, 2, 2]);
+    }
+}
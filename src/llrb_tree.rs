@@ -0,0 +1,453 @@
+//! 左倾红黑树
+//!
+//! - [Left-leaning Red-Black Trees](https://sedgewick.io/wp-content/themes/sedgewick/papers/2008LLRB.pdf)
+//! - [wikipedia - Left-leaning red–black tree](https://en.wikipedia.org/wiki/Left-leaning_red%E2%80%93black_tree)
+//!
+//! 左倾红黑树在普通红黑树的性质之上额外要求: 红色节点只能是其父节点的左子节点
+//! 这样每个节点和它的红色左子节点可以看作 2-3 树中的一个 3-节点
+//! 红黑树和 2-3 树(2-3-4 树)之间是一一对应的, 所以插入删除只需要处理很少的几种情况
+//!
+//! 所有操作都是自顶向下递归, 在递归返回时通过 `fix_up` 自底向上修复三种情况:
+//!
+//! - 右子节点是红色而左子节点是黑色: 左旋
+//! - 左子节点和左子节点的左子节点都是红色: 右旋
+//! - 左右子节点都是红色: 颜色翻转, 相当于把 4-节点分裂并把中间节点上推到父节点
+//!
+//! 删除时在向下搜索的过程中保证当前节点或者其左子节点是红色(借助 `move_red_left` 和 `move_red_right`)
+//! 这样到达叶子节点时可以直接删除而不会破坏黑色平衡
+//!
+//! 相比 [`crate::rb_tree`] 中的实现不需要父指针也不需要处理大量对称情况, 但旋转次数更多
+
+use std::{cmp::Ordering, fmt::Debug};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Black,
+}
+
+impl Color {
+    fn flip(&mut self) {
+        *self = match self {
+            Color::Red => Color::Black,
+            Color::Black => Color::Red,
+        }
+    }
+}
+
+/// 左倾红黑树节点
+pub struct Node<T> {
+    color: Color,
+    value: T,
+    left: Link<T>,
+    right: Link<T>,
+}
+
+type Link<T> = Option<Box<Node<T>>>;
+
+impl<T> Node<T> {
+    fn new(value: T) -> Self {
+        Self {
+            color: Color::Red,
+            value,
+            left: None,
+            right: None,
+        }
+    }
+
+    /// 返回子树的黑色高度, 同时检查左倾红黑树的性质
+    pub fn depth(&self) -> usize {
+        debug_assert!(!is_red(&self.right), "right child must be black");
+        if self.color == Color::Red {
+            debug_assert!(!is_red(&self.left), "red node with red left child");
+        }
+
+        let left_depth = self.left.as_ref().map(|node| node.depth()).unwrap_or(1);
+        let right_depth = self.right.as_ref().map(|node| node.depth()).unwrap_or(1);
+
+        debug_assert_eq!(left_depth, right_depth, "black height not equal");
+        match self.color {
+            Color::Black => left_depth + 1,
+            Color::Red => left_depth,
+        }
+    }
+}
+
+fn is_red<T>(link: &Link<T>) -> bool {
+    link.as_ref()
+        .map(|node| node.color == Color::Red)
+        .unwrap_or(false)
+}
+
+/// 左子节点的左子节点是否为红色
+fn is_left_left_red<T>(link: &Link<T>) -> bool {
+    link.as_ref()
+        .map(|node| is_red(&node.left))
+        .unwrap_or(false)
+}
+
+/// 左旋, 新的根节点继承旧根节点的颜色, 旧根节点变成红色
+///
+/// ```text
+///     h              x
+///    / \            / \
+///   a   x(🔴) ->  h(🔴) c
+///      / \       / \
+///     b   c     a   b
+/// ```
+fn rotate_left<T>(mut h: Box<Node<T>>) -> Box<Node<T>> {
+    let mut x = h.right.take().expect("rotate left: right child must exist");
+    h.right = x.left.take();
+    x.color = h.color;
+    h.color = Color::Red;
+    x.left = Some(h);
+    x
+}
+
+/// 右旋, 新的根节点继承旧根节点的颜色, 旧根节点变成红色
+fn rotate_right<T>(mut h: Box<Node<T>>) -> Box<Node<T>> {
+    let mut x = h.left.take().expect("rotate right: left child must exist");
+    h.left = x.right.take();
+    x.color = h.color;
+    h.color = Color::Red;
+    x.right = Some(h);
+    x
+}
+
+/// 翻转节点及其两个子节点的颜色
+fn flip_colors<T>(h: &mut Node<T>) {
+    h.color.flip();
+    if let Some(left) = &mut h.left {
+        left.color.flip();
+    }
+    if let Some(right) = &mut h.right {
+        right.color.flip();
+    }
+}
+
+/// 递归返回时修复右倾的红色节点和连续的红色节点
+fn fix_up<T>(mut h: Box<Node<T>>) -> Box<Node<T>> {
+    if is_red(&h.right) && !is_red(&h.left) {
+        h = rotate_left(h);
+    }
+    if is_red(&h.left) && is_left_left_red(&h.left) {
+        h = rotate_right(h);
+    }
+    if is_red(&h.left) && is_red(&h.right) {
+        flip_colors(&mut h);
+    }
+
+    h
+}
+
+/// 假设 h 是红色且 h.left 和 h.left.left 都是黑色
+/// 把 h.left 或者 h.left 的某个子节点变成红色
+fn move_red_left<T>(mut h: Box<Node<T>>) -> Box<Node<T>> {
+    flip_colors(&mut h);
+    if h.right.as_ref().map(|r| is_red(&r.left)).unwrap_or(false) {
+        h.right = h.right.take().map(rotate_right);
+        h = rotate_left(h);
+        flip_colors(&mut h);
+    }
+
+    h
+}
+
+/// 假设 h 是红色且 h.right 和 h.right.left 都是黑色
+/// 把 h.right 或者 h.right 的某个子节点变成红色
+fn move_red_right<T>(mut h: Box<Node<T>>) -> Box<Node<T>> {
+    flip_colors(&mut h);
+    if is_left_left_red(&h.left) {
+        h = rotate_right(h);
+        flip_colors(&mut h);
+    }
+
+    h
+}
+
+fn insert<T: Ord>(h: Link<T>, value: T, old: &mut Option<T>) -> Box<Node<T>> {
+    let mut h = match h {
+        None => return Box::new(Node::new(value)),
+        Some(h) => h,
+    };
+
+    match value.cmp(&h.value) {
+        Ordering::Less => h.left = Some(insert(h.left.take(), value, old)),
+        Ordering::Greater => h.right = Some(insert(h.right.take(), value, old)),
+        Ordering::Equal => *old = Some(std::mem::replace(&mut h.value, value)),
+    }
+
+    fix_up(h)
+}
+
+/// 删除子树中的最小节点, 返回新的子树和最小值
+fn delete_min<T>(mut h: Box<Node<T>>) -> (Link<T>, T) {
+    // 左倾红黑树中没有左子节点的节点一定也没有右子节点
+    if h.left.is_none() {
+        return (None, h.value);
+    }
+
+    if !is_red(&h.left) && !is_left_left_red(&h.left) {
+        h = move_red_left(h);
+    }
+
+    let (left, min) = delete_min(h.left.take().expect("left child must exist"));
+    h.left = left;
+    (Some(fix_up(h)), min)
+}
+
+/// 删除子树中的 value, 调用前需要保证 value 一定存在
+fn delete<T: Ord>(mut h: Box<Node<T>>, value: &T, removed: &mut Option<T>) -> Link<T> {
+    if value < &h.value {
+        if !is_red(&h.left) && !is_left_left_red(&h.left) {
+            h = move_red_left(h);
+        }
+        let left = h.left.take().expect("value must exist in left subtree");
+        h.left = delete(left, value, removed);
+    } else {
+        if is_red(&h.left) {
+            h = rotate_right(h);
+        }
+
+        // 在底部找到了需要删除的节点直接删除
+        if value == &h.value && h.right.is_none() {
+            *removed = Some(h.value);
+            return None;
+        }
+
+        if !is_red(&h.right) && !h.right.as_ref().map(|r| is_red(&r.left)).unwrap_or(false) {
+            h = move_red_right(h);
+        }
+
+        let right = h.right.take().expect("value must exist in right subtree");
+        if value == &h.value {
+            // 用右子树的最小值替换当前节点
+            let (right, min) = delete_min(right);
+            h.right = right;
+            *removed = Some(std::mem::replace(&mut h.value, min));
+        } else {
+            h.right = delete(right, value, removed);
+        }
+    }
+
+    Some(fix_up(h))
+}
+
+/// 左倾红黑树
+pub struct LLRBTree<T> {
+    root: Link<T>,
+    length: usize,
+}
+
+impl<T: Ord> LLRBTree<T> {
+    pub fn new() -> Self {
+        Self {
+            root: None,
+            length: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// 查找树中是否存在 value
+    pub fn contains(&self, value: &T) -> bool {
+        let mut curr = &self.root;
+        while let Some(node) = curr {
+            match value.cmp(&node.value) {
+                Ordering::Less => curr = &node.left,
+                Ordering::Greater => curr = &node.right,
+                Ordering::Equal => return true,
+            }
+        }
+
+        false
+    }
+
+    /// 插入 value, 如果已经存在则替换并返回旧值
+    pub fn insert(&mut self, value: T) -> Option<T> {
+        let mut old = None;
+        let mut root = insert(self.root.take(), value, &mut old);
+        root.color = Color::Black;
+        self.root = Some(root);
+
+        if old.is_none() {
+            self.length += 1;
+        }
+
+        old
+    }
+
+    /// 删除 value, 如果存在则返回被删除的值
+    pub fn delete(&mut self, value: &T) -> Option<T> {
+        if !self.contains(value) {
+            return None;
+        }
+
+        let mut root = self.root.take().expect("root must exist");
+        if !is_red(&root.left) && !is_red(&root.right) {
+            root.color = Color::Red;
+        }
+
+        let mut removed = None;
+        self.root = delete(root, value, &mut removed);
+        if let Some(root) = &mut self.root {
+            root.color = Color::Black;
+        }
+
+        self.length -= 1;
+        removed
+    }
+
+    /// 中序遍历引用迭代器
+    pub fn iter(&self) -> Iter<'_, T> {
+        let mut iter = Iter { stack: vec![] };
+        iter.push_left(&self.root);
+        iter
+    }
+}
+
+impl<T> LLRBTree<T> {
+    /// 返回树的黑色高度, 同时检查左倾红黑树的性质
+    pub fn depth(&self) -> usize {
+        self.root.as_ref().map(|node| node.depth()).unwrap_or(0)
+    }
+}
+
+impl<T: Ord> Default for LLRBTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 中序遍历引用迭代器
+pub struct Iter<'a, T> {
+    stack: Vec<&'a Node<T>>,
+}
+
+impl<'a, T> Iter<'a, T> {
+    fn push_left(&mut self, mut link: &'a Link<T>) {
+        while let Some(node) = link {
+            self.stack.push(node);
+            link = &node.left;
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.push_left(&node.right);
+        Some(&node.value)
+    }
+}
+
+impl<T: Debug> Debug for LLRBTree<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut queue = match &self.root {
+            None => return writeln!(f, "NIL"),
+            Some(root) => vec![root],
+        };
+
+        while !queue.is_empty() {
+            let mut tmp = vec![];
+            let line = queue
+                .iter()
+                .map(|node| {
+                    if let Some(x) = &node.left {
+                        tmp.push(x);
+                    }
+                    if let Some(x) = &node.right {
+                        tmp.push(x);
+                    }
+
+                    format!("{:?}({:?})", node.value, node.color)
+                })
+                .collect::<Vec<String>>()
+                .join(" -> ");
+            writeln!(f, "{}", line)?;
+            queue = tmp;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_llrb_tree() {
+        let mut t = LLRBTree::new();
+        for i in 1..=1000 {
+            assert!(t.insert(i).is_none());
+            t.depth();
+        }
+
+        assert_eq!(t.len(), 1000);
+        assert_eq!(t.insert(500), Some(500));
+        assert!(t.iter().copied().eq(1..=1000));
+
+        for i in (1..=1000).step_by(2) {
+            assert_eq!(t.delete(&i), Some(i));
+            t.depth();
+        }
+
+        assert_eq!(t.delete(&1), None);
+        assert_eq!(t.len(), 500);
+        assert!(t.iter().copied().eq((2..=1000).step_by(2)));
+
+        for i in (2..=1000).rev().step_by(2) {
+            assert_eq!(t.delete(&i), Some(i));
+            t.depth();
+        }
+
+        assert!(t.is_empty());
+        assert_eq!(t.depth(), 0);
+    }
+
+    #[test]
+    fn test_llrb_tree_depth() {
+        // 按顺序插入 2^n - 1 个节点可以得到一颗全黑的满二叉树
+        for i in 2..15 {
+            let mut t = LLRBTree::new();
+            for v in 1..(1 << i) {
+                t.insert(v);
+            }
+
+            assert_eq!(t.depth(), i + 1);
+        }
+    }
+
+    #[test]
+    fn test_llrb_tree_random() {
+        let mut rng = rand::thread_rng();
+        let mut t = LLRBTree::new();
+        let mut set = BTreeSet::new();
+
+        for _ in 0..20000 {
+            let v = rng.gen_range(0..500);
+            if rng.gen_bool(0.5) {
+                assert_eq!(t.insert(v).is_none(), set.insert(v));
+            } else {
+                assert_eq!(t.delete(&v).is_some(), set.remove(&v));
+            }
+
+            assert_eq!(t.len(), set.len());
+            assert_eq!(t.contains(&v), set.contains(&v));
+            t.depth();
+        }
+
+        assert!(t.iter().eq(set.iter()));
+    }
+}
@@ -0,0 +1,223 @@
+//! LRU 缓存
+//!
+//! - [LeetCode 146. LRU 缓存](https://leetcode.cn/problems/lru-cache/)
+//! - [wikipedia - Cache replacement policies](https://en.wikipedia.org/wiki/Cache_replacement_policies#LRU)
+//!
+//! 最近最少使用(Least Recently Used)缓存在容量满时淘汰最久没有被访问过的数据
+//!
+//! 使用 [`crate::linked_list::LinkedList`] 按访问时间保存所有数据, 头部是最近访问的, 尾部是最久没有访问的
+//! 同时用 `HashMap` 保存 key 到链表节点句柄的映射, 这样:
+//!
+//! - 查找: 通过哈希表找到节点, 把节点移动到链表头部
+//! - 插入: 在链表头部插入新节点, 如果超过容量则删除链表尾部节点
+//!
+//! 所有操作都是 O(1) 的
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::linked_list::{Handle, LinkedList};
+
+/// 缓存淘汰策略的通用接口, 用于比较不同策略的命中率
+pub trait Cache<K, V> {
+    /// 查找 key 对应的值, 命中时更新缓存策略的内部状态
+    fn get(&mut self, key: &K) -> Option<&V>;
+
+    /// 写入 key-value, 如果 key 已经存在则返回旧值
+    fn put(&mut self, key: K, value: V) -> Option<V>;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn capacity(&self) -> usize;
+}
+
+/// LRU 缓存
+pub struct LruCache<K, V> {
+    capacity: usize,
+    map: HashMap<K, Handle<(K, V)>>,
+    list: LinkedList<(K, V)>,
+}
+
+impl<K: Hash + Eq + Clone, V> LruCache<K, V> {
+    /// 创建容量为 capacity 的缓存
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be positive");
+        Self {
+            capacity,
+            map: HashMap::with_capacity(capacity),
+            list: LinkedList::new(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.map.contains_key(key)
+    }
+
+    /// 查找 key 对应的值并标记为最近使用
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        let handle = *self.map.get(key)?;
+        unsafe {
+            self.list.move_to_front(handle);
+            Some(&self.list.get(handle).1)
+        }
+    }
+
+    /// 查找 key 对应值的可变引用并标记为最近使用
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let handle = *self.map.get(key)?;
+        unsafe {
+            self.list.move_to_front(handle);
+            Some(&mut self.list.get_mut(handle).1)
+        }
+    }
+
+    /// 查找 key 对应的值但不改变访问顺序
+    pub fn peek(&self, key: &K) -> Option<&V> {
+        let handle = *self.map.get(key)?;
+        unsafe { Some(&self.list.get(handle).1) }
+    }
+
+    /// 写入 key-value 并标记为最近使用, 如果 key 已经存在则返回旧值
+    ///
+    /// 写入新 key 时如果超过容量会淘汰最久没有使用的数据
+    pub fn put(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(&handle) = self.map.get(&key) {
+            unsafe {
+                self.list.move_to_front(handle);
+                let entry = self.list.get_mut(handle);
+                return Some(std::mem::replace(&mut entry.1, value));
+            }
+        }
+
+        if self.list.len() == self.capacity {
+            self.pop_lru();
+        }
+
+        let handle = self.list.push_front_handle((key.clone(), value));
+        self.map.insert(key, handle);
+        None
+    }
+
+    /// 删除 key 并返回对应的值
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let handle = self.map.remove(key)?;
+        unsafe { Some(self.list.remove(handle).1) }
+    }
+
+    /// 淘汰并返回最久没有使用的数据
+    pub fn pop_lru(&mut self) -> Option<(K, V)> {
+        let (key, value) = self.list.pop_back()?;
+        self.map.remove(&key);
+        Some((key, value))
+    }
+
+    /// 按照从最近使用到最久没有使用的顺序遍历
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.list.iter().map(|(k, v)| (k, v))
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> Cache<K, V> for LruCache<K, V> {
+    fn get(&mut self, key: &K) -> Option<&V> {
+        LruCache::get(self, key)
+    }
+
+    fn put(&mut self, key: K, value: V) -> Option<V> {
+        LruCache::put(self, key, value)
+    }
+
+    fn len(&self) -> usize {
+        LruCache::len(self)
+    }
+
+    fn capacity(&self) -> usize {
+        LruCache::capacity(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lru_cache() {
+        let mut cache = LruCache::new(2);
+        assert!(cache.is_empty());
+
+        assert_eq!(cache.put(1, 1), None);
+        assert_eq!(cache.put(2, 2), None);
+        assert_eq!(cache.get(&1), Some(&1));
+
+        // 容量满了淘汰最久没有访问的 2
+        assert_eq!(cache.put(3, 3), None);
+        assert_eq!(cache.get(&2), None);
+
+        assert_eq!(cache.put(4, 4), None);
+        assert_eq!(cache.get(&1), None);
+        assert_eq!(cache.get(&3), Some(&3));
+        assert_eq!(cache.get(&4), Some(&4));
+
+        assert_eq!(cache.put(3, 30), Some(3));
+        assert_eq!(cache.iter().collect::<Vec<_>>(), vec![(&3, &30), (&4, &4)]);
+
+        // peek 不改变访问顺序
+        assert_eq!(cache.peek(&4), Some(&4));
+        assert_eq!(cache.pop_lru(), Some((4, 4)));
+        assert_eq!(cache.len(), 1);
+
+        *cache.get_mut(&3).unwrap() += 1;
+        assert_eq!(cache.remove(&3), Some(31));
+        assert_eq!(cache.remove(&3), None);
+        assert!(cache.is_empty());
+        assert_eq!(cache.pop_lru(), None);
+    }
+
+    #[test]
+    fn test_lru_cache_random() {
+        use rand::Rng;
+
+        // 用 Vec 模拟 LRU 作为参照, 头部是最近使用的
+        let mut rng = rand::thread_rng();
+        let mut cache = LruCache::new(16);
+        let mut naive: Vec<(u32, u32)> = vec![];
+
+        for i in 0..10000 {
+            let key = rng.gen_range(0..32);
+            let pos = naive.iter().position(|&(k, _)| k == key);
+            if rng.gen_bool(0.5) {
+                let expect = pos.map(|p| naive.remove(p).1);
+                assert_eq!(cache.put(key, i), expect);
+                naive.insert(0, (key, i));
+                naive.truncate(16);
+            } else {
+                let expect = pos.map(|p| {
+                    let entry = naive.remove(p);
+                    naive.insert(0, entry);
+                    entry.1
+                });
+                assert_eq!(cache.get(&key).copied(), expect);
+            }
+
+            assert_eq!(cache.len(), naive.len());
+        }
+
+        let items: Vec<_> = cache.iter().map(|(&k, &v)| (k, v)).collect();
+        assert_eq!(items, naive);
+    }
+}
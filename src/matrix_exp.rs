@@ -35,12 +35,85 @@
 //! ```
 //!
 //! 则通过矩阵快速幂计算将斐波那契第 N 项的计算时间复杂度从 O(N) 降低至 O(logN)
+//!
+//! 除了定长矩阵 [`Matrix`] 之外还提供了运行时决定大小的 [`DynMatrix`],
+//! 以及基于高斯消元的行列式计算和线性方程组求解:
+//!
+//! - 对第 i 列选取绝对值最大的元素所在行作为主元行(列主元), 交换到第 i 行, 减小浮点误差
+//! - 用主元行消去下方所有行的第 i 列, 得到上三角矩阵
+//! - 行列式等于对角线元素之积, 每交换一次行变号一次
+//! - 解方程时对增广矩阵消元, 再从最后一行开始回代
+//!
+//! ## 图上的路径计数
+//!
+//! 设 A 为图的邻接矩阵, `A[i][j]` 是 i 到 j 的边数, 则 `A^k[i][j]` 就是 i 到 j 恰好经过 k 条边的路径(可以重复经过节点)数:
+//!
+//! ```text
+//! A^k[i][j] = Σ A^(k-1)[i][t]·A[t][j]
+//! ```
+//!
+//! 即先用 k-1 步走到 t 再走一条边到 j, 这正是按步数递推的 DP, 用矩阵快速幂可以在 O(n³ logk) 内求出, 见 [`count_paths`]
+//!
+//! ## 一般的线性递推
+//!
+//! k 阶线性递推 `a[n] = c[0]·a[n-1] + ... + c[k-1]·a[n-k]` 同样可以写成 k 阶的伴随矩阵:
+//!
+//! ```text
+//! | a[n]     |   | c[0] c[1] ... c[k-2] c[k-1] | | a[n-1] |
+//! | a[n-1]   |   | 1    0    ... 0      0      | | a[n-2] |
+//! | ...      | = | ...                         | | ...    |
+//! | a[n-k+1] |   | 0    0    ... 1      0      | | a[n-k] |
+//! ```
+//!
+//! 矩阵快速幂的复杂度是 O(k³ logn), 只需要某一项时可以用 O(k² logn) 的 Kitamasa 算法, 见 [`LinearRecurrence`]
 
-use std::ops::{AddAssign, Index, IndexMut, Mul};
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Sub};
 
-#[derive(Clone, Copy)]
+use crate::graph_traversal::Graph;
+
+/// 判断浮点数是否为 0 的精度
+const EPS: f64 = 1e-9;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Matrix<T, const M: usize, const N: usize>([[T; N]; M]);
 
+impl<T, const M: usize, const N: usize> Matrix<T, M, N> {
+    pub fn new(data: [[T; N]; M]) -> Self {
+        Self(data)
+    }
+}
+
+impl<T: Copy + Default, const M: usize, const N: usize> Matrix<T, M, N> {
+    /// 转置矩阵
+    pub fn transpose(&self) -> Matrix<T, N, M> {
+        let mut res = [[T::default(); M]; N];
+        for (i, row) in self.0.iter().enumerate() {
+            for (j, &v) in row.iter().enumerate() {
+                res[j][i] = v;
+            }
+        }
+        Matrix(res)
+    }
+}
+
+impl<T: Copy + Default + From<u8>, const M: usize> Matrix<T, M, M> {
+    /// 单位矩阵
+    pub fn identity() -> Self {
+        let mut res = [[T::default(); M]; M];
+        for (i, row) in res.iter_mut().enumerate() {
+            row[i] = T::from(1);
+        }
+        Matrix(res)
+    }
+}
+
+impl<const M: usize> Matrix<f64, M, M> {
+    /// 行列式
+    pub fn determinant(&self) -> f64 {
+        DynMatrix::from(*self).determinant()
+    }
+}
+
 impl<T, const M: usize, const N: usize> Index<usize> for Matrix<T, M, N> {
     type Output = [T; N];
 
@@ -77,26 +150,181 @@ where
     }
 }
 
-/// 计算正方矩阵的 n 次幂
-pub fn matrix_pow<const M: usize>(mut x: Matrix<i32, M, M>, mut n: usize) -> Matrix<i32, M, M> {
-    let mut res = Matrix([[0; M]; M]);
+/// 矩阵逐元素相加
+impl<T, const M: usize, const N: usize> Add for Matrix<T, M, N>
+where
+    T: Copy + Add<Output = T>,
+{
+    type Output = Matrix<T, M, N>;
+
+    fn add(mut self, rhs: Self) -> Self::Output {
+        for (a, b) in self.0.iter_mut().flatten().zip(rhs.0.iter().flatten()) {
+            *a = *a + *b;
+        }
+        self
+    }
+}
+
+/// 矩阵逐元素相减
+impl<T, const M: usize, const N: usize> Sub for Matrix<T, M, N>
+where
+    T: Copy + Sub<Output = T>,
+{
+    type Output = Matrix<T, M, N>;
+
+    fn sub(mut self, rhs: Self) -> Self::Output {
+        for (a, b) in self.0.iter_mut().flatten().zip(rhs.0.iter().flatten()) {
+            *a = *a - *b;
+        }
+        self
+    }
+}
+
+/// 模 m 意义下的矩阵乘法, 中间结果使用 u128 避免溢出
+fn mul_mod<const M: usize>(
+    a: &Matrix<u64, M, M>,
+    b: &Matrix<u64, M, M>,
+    m: u64,
+) -> Matrix<u64, M, M> {
+    let mut res = [[0; M]; M];
+    for (i, row) in res.iter_mut().enumerate() {
+        for (j, v) in row.iter_mut().enumerate() {
+            let sum: u128 = (0..M)
+                .map(|k| a[i][k] as u128 * b[k][j] as u128 % m as u128)
+                .sum();
+            *v = (sum % m as u128) as u64;
+        }
+    }
+    Matrix(res)
+}
+
+/// 模 m 意义下计算正方矩阵的 n 次幂
+pub fn matrix_pow_mod<const M: usize>(
+    mut x: Matrix<u64, M, M>,
+    mut n: usize,
+    m: u64,
+) -> Matrix<u64, M, M> {
+    let mut res = Matrix::identity();
+    for v in res.0.iter_mut().flatten() {
+        *v %= m;
+    }
+
+    while n > 0 {
+        if n & 1 == 1 {
+            res = mul_mod(&res, &x, m);
+        }
+        x = mul_mod(&x, &x, m);
+        n >>= 1;
+    }
+
+    res
+}
+
+/// 半环: 有加法和乘法, 加法有单位元 0, 乘法有单位元 1, 乘法对加法满足分配律
+///
+/// 矩阵乘法只依赖这些性质, 所以矩阵快速幂可以用在任意半环上,
+/// 比如把加法换成 min, 乘法换成加法的 [`MinPlus`] 半环可以求恰好经过 k 条边的最短路径
+pub trait Semiring: Copy {
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn add(self, rhs: Self) -> Self;
+    fn mul(self, rhs: Self) -> Self;
+}
+
+macro_rules! impl_semiring {
+    ($($t:ty),*) => {
+        $(
+            impl Semiring for $t {
+                fn zero() -> Self {
+                    0 as $t
+                }
+
+                fn one() -> Self {
+                    1 as $t
+                }
+
+                fn add(self, rhs: Self) -> Self {
+                    self + rhs
+                }
+
+                fn mul(self, rhs: Self) -> Self {
+                    self * rhs
+                }
+            }
+        )*
+    };
+}
+
+impl_semiring!(i32, i64, i128, u32, u64, u128, f64);
+
+/// (min, +) 半环, `None` 表示正无穷
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MinPlus(pub Option<i64>);
+
+impl Semiring for MinPlus {
+    fn zero() -> Self {
+        MinPlus(None)
+    }
+
+    fn one() -> Self {
+        MinPlus(Some(0))
+    }
+
+    fn add(self, rhs: Self) -> Self {
+        match (self.0, rhs.0) {
+            (Some(a), Some(b)) => MinPlus(Some(a.min(b))),
+            (a, b) => MinPlus(a.or(b)),
+        }
+    }
+
+    fn mul(self, rhs: Self) -> Self {
+        match (self.0, rhs.0) {
+            (Some(a), Some(b)) => MinPlus(Some(a + b)),
+            _ => MinPlus(None),
+        }
+    }
+}
+
+/// 半环上的矩阵乘法
+fn semiring_mul<T: Semiring, const M: usize>(
+    a: &Matrix<T, M, M>,
+    b: &Matrix<T, M, M>,
+) -> Matrix<T, M, M> {
+    let mut res = [[T::zero(); M]; M];
+    for (i, row) in res.iter_mut().enumerate() {
+        for (j, v) in row.iter_mut().enumerate() {
+            *v = (0..M).fold(T::zero(), |acc, k| acc.add(a[i][k].mul(b[k][j])));
+        }
+    }
+    Matrix(res)
+}
+
+/// 计算半环上正方矩阵的 n 次幂
+pub fn matrix_pow<T: Semiring, const M: usize>(
+    mut x: Matrix<T, M, M>,
+    mut n: usize,
+) -> Matrix<T, M, M> {
+    let mut res = Matrix([[T::zero(); M]; M]);
     for i in 0..M {
-        res[i][i] = 1;
+        res[i][i] = T::one();
     }
 
     while n > 0 {
         if n & 1 == 1 {
-            res = res * x;
+            res = semiring_mul(&res, &x);
         }
-        x = x * x;
         n >>= 1;
+        // 最后一次不需要再平方, 避免不必要的溢出
+        if n > 0 {
+            x = semiring_mul(&x, &x);
+        }
     }
 
     res
 }
 
-/// 使用矩阵快速幂求斐波那契数
-pub fn fib(n: usize) -> i32 {
+/// 使用矩阵快速幂求斐波那契数, u128 最多可以表示到 fib(186)
+pub fn fib(n: usize) -> u128 {
     if n == 0 {
         return 0;
     }
@@ -113,6 +341,464 @@ pub fn fib(n: usize) -> i32 {
     res[0][0]
 }
 
+/// 斐波那契数模 m
+pub fn fib_mod(n: usize, m: u64) -> u64 {
+    let x = Matrix([[1, 1], [1, 0]]);
+    matrix_pow_mod(x, n, m)[0][1]
+}
+
+/// 计算线性递推数列 `a[n] = c[0]·a[n-1] + c[1]·a[n-2] + ... + c[k-1]·a[n-k]` 模 m 的第 n 项
+///
+/// initial 是前 k 项 `a[0..k]`, 使用 Kitamasa 算法, 复杂度 O(k² logn):
+/// 由递推关系 x^k ≡ c[0]·x^(k-1) + ... + c[k-1], 求出 x^n 对特征多项式取模后的余式 r,
+/// 那么 `a[n] = Σ r[i]·a[i]`
+pub fn linear_recurrence(coeffs: &[u64], initial: &[u64], n: usize, m: u64) -> u64 {
+    let k = coeffs.len();
+    assert!(
+        k > 0 && initial.len() == k,
+        "need k coefficients and k initial terms"
+    );
+    if n < k {
+        return initial[n] % m;
+    }
+
+    let m128 = m as u128;
+    let coeffs: Vec<u128> = coeffs.iter().map(|&c| c as u128 % m128).collect();
+
+    // 两个余式相乘后对特征多项式取模
+    let mul = |a: &[u128], b: &[u128]| -> Vec<u128> {
+        let mut prod = vec![0; 2 * k - 1];
+        for (i, x) in a.iter().enumerate() {
+            for (j, y) in b.iter().enumerate() {
+                prod[i + j] = (prod[i + j] + x * y) % m128;
+            }
+        }
+        for i in (k..prod.len()).rev() {
+            let t = prod[i];
+            for (j, c) in coeffs.iter().enumerate() {
+                prod[i - 1 - j] = (prod[i - 1 - j] + t * c) % m128;
+            }
+        }
+        prod.truncate(k);
+        prod
+    };
+
+    // res = x^0, base = x
+    let mut res = vec![0; k];
+    res[0] = 1 % m128;
+    let mut base = vec![0; k];
+    if k > 1 {
+        base[1] = 1;
+    } else {
+        base[0] = coeffs[0];
+    }
+
+    let mut n = n;
+    while n > 0 {
+        if n & 1 == 1 {
+            res = mul(&res, &base);
+        }
+        base = mul(&base, &base);
+        n >>= 1;
+    }
+
+    let sum = res
+        .iter()
+        .zip(initial)
+        .fold(0, |acc, (r, &a)| (acc + r * (a as u128 % m128)) % m128);
+    sum as u64
+}
+
+/// k 阶线性递推数列 `a[n] = c[0]·a[n-1] + c[1]·a[n-2] + ... + c[k-1]·a[n-k]`
+///
+/// ```
+/// use impx::matrix_exp::LinearRecurrence;
+///
+/// // 斐波那契数列
+/// let fib = LinearRecurrence::new(&[1, 1], &[0, 1]);
+/// assert_eq!(fib.nth(10), 55);
+/// assert_eq!(fib.nth_mod(1_000_000_000_000_000_000, 1_000_000_007), 209783453);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinearRecurrence {
+    coeffs: Vec<u64>,
+    initial: Vec<u64>,
+}
+
+impl LinearRecurrence {
+    /// coeffs 是递推系数 `c[0..k]`, initial 是前 k 项 `a[0..k]`
+    pub fn new(coeffs: &[u64], initial: &[u64]) -> Self {
+        assert!(
+            !coeffs.is_empty() && initial.len() == coeffs.len(),
+            "need k coefficients and k initial terms"
+        );
+        Self {
+            coeffs: coeffs.to_vec(),
+            initial: initial.to_vec(),
+        }
+    }
+
+    /// 递推的阶数 k
+    pub fn order(&self) -> usize {
+        self.coeffs.len()
+    }
+
+    /// k 阶伴随矩阵, 第一行是递推系数, 次对角线上是 1
+    pub fn companion(&self) -> DynMatrix<u128> {
+        let k = self.order();
+        let mut c = DynMatrix::new(k, k);
+        for (j, &v) in self.coeffs.iter().enumerate() {
+            c[(0, j)] = v as u128;
+        }
+        for i in 1..k {
+            c[(i, i - 1)] = 1;
+        }
+        c
+    }
+
+    /// 第 n 项的精确值, 用伴随矩阵快速幂计算, O(k³ logn), 中间结果超出 u128 时溢出
+    pub fn nth(&self, n: usize) -> u128 {
+        let k = self.order();
+        if n < k {
+            return self.initial[n] as u128;
+        }
+
+        // [a[n], ..., a[n-k+1]] = C^(n-k+1) · [a[k-1], ..., a[0]], 取第一行
+        // 不使用 C^n 的最后一行, 因为 C^n 中会出现 a[n+k-1] 量级的元素, 更容易溢出
+        let p = self.companion().pow(n - k + 1);
+        (0..k)
+            .map(|j| p[(0, j)] * self.initial[k - 1 - j] as u128)
+            .sum()
+    }
+
+    /// 第 n 项模 m, 使用 Kitamasa 算法 O(k² logn), 见 [`linear_recurrence`]
+    pub fn nth_mod(&self, n: usize, m: u64) -> u64 {
+        linear_recurrence(&self.coeffs, &self.initial, n, m)
+    }
+}
+
+/// 图的邻接矩阵, `A[u][v]` 是 u 到 v 的边数, 无向边在两个方向上各算一次
+pub fn adjacency_matrix(g: &Graph) -> DynMatrix<u64> {
+    let mut a = DynMatrix::new(g.len(), g.len());
+    for u in 0..g.len() {
+        for &v in g.neighbors(u) {
+            a[(u, v)] += 1;
+        }
+    }
+    a
+}
+
+/// 模 m 意义下每对节点之间恰好经过 k 条边的路径数, 结果的第 (u, v) 项是 u 到 v 的路径数
+///
+/// ```
+/// use impx::graph_traversal::Graph;
+/// use impx::matrix_exp::count_paths;
+///
+/// // 三角形上从 0 出发走 3 步回到 0: 0-1-2-0 和 0-2-1-0
+/// let g = Graph::from_edges(3, &[(0, 1), (1, 2), (2, 0)], false);
+/// assert_eq!(count_paths(&g, 3, 1_000_000_007)[(0, 0)], 2);
+/// ```
+pub fn count_paths(g: &Graph, k: usize, m: u64) -> DynMatrix<u64> {
+    adjacency_matrix(g).pow_mod(k, m)
+}
+
+/// 运行时决定大小的矩阵, 按行优先保存在一维数组中
+#[derive(Debug, Clone, PartialEq)]
+pub struct DynMatrix<T> {
+    rows: usize,
+    cols: usize,
+    data: Vec<T>,
+}
+
+impl<T: Copy + Default> DynMatrix<T> {
+    /// 创建 rows 行 cols 列的零矩阵
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Self {
+            rows,
+            cols,
+            data: vec![T::default(); rows * cols],
+        }
+    }
+
+    /// 从二维数组创建矩阵, 每一行的长度必须相同
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let cols = rows.first().map_or(0, |r| r.len());
+        assert!(rows.iter().all(|r| r.len() == cols), "ragged rows");
+        Self {
+            rows: rows.len(),
+            cols,
+            data: rows.into_iter().flatten().collect(),
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// 转置矩阵
+    pub fn transpose(&self) -> Self {
+        let mut res = Self::new(self.cols, self.rows);
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                res[(j, i)] = self[(i, j)];
+            }
+        }
+        res
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        for j in 0..self.cols {
+            self.data.swap(a * self.cols + j, b * self.cols + j);
+        }
+    }
+}
+
+impl<T: Copy + Default + From<u8>> DynMatrix<T> {
+    /// n 阶单位矩阵
+    pub fn identity(n: usize) -> Self {
+        let mut res = Self::new(n, n);
+        for i in 0..n {
+            res[(i, i)] = T::from(1);
+        }
+        res
+    }
+}
+
+impl<T> DynMatrix<T>
+where
+    T: Copy + Default + From<u8> + Mul<Output = T> + AddAssign,
+{
+    /// 计算正方矩阵的 n 次幂
+    pub fn pow(&self, mut n: usize) -> Self {
+        assert_eq!(self.rows, self.cols, "matrix must be square");
+        let mut res = Self::identity(self.rows);
+        let mut x = self.clone();
+        while n > 0 {
+            if n & 1 == 1 {
+                res = &res * &x;
+            }
+            n >>= 1;
+            // 最后一次不需要再平方, 避免不必要的溢出
+            if n > 0 {
+                x = &x * &x;
+            }
+        }
+        res
+    }
+}
+
+impl DynMatrix<u64> {
+    /// 模 m 意义下的矩阵乘法, 中间结果使用 u128 避免溢出
+    fn mul_mod(&self, rhs: &Self, m: u64) -> Self {
+        assert_eq!(self.cols, rhs.rows, "dimension mismatch");
+        let m = m as u128;
+        let mut acc = vec![0u128; self.rows * rhs.cols];
+        for i in 0..self.rows {
+            for k in 0..self.cols {
+                let a = self[(i, k)] as u128;
+                for j in 0..rhs.cols {
+                    let v = &mut acc[i * rhs.cols + j];
+                    *v = (*v + a * rhs[(k, j)] as u128) % m;
+                }
+            }
+        }
+
+        DynMatrix {
+            rows: self.rows,
+            cols: rhs.cols,
+            data: acc.into_iter().map(|v| v as u64).collect(),
+        }
+    }
+
+    /// 模 m 意义下计算正方矩阵的 n 次幂
+    pub fn pow_mod(&self, mut n: usize, m: u64) -> Self {
+        assert_eq!(self.rows, self.cols, "matrix must be square");
+        let mut res = Self::identity(self.rows);
+        for v in res.data.iter_mut() {
+            *v %= m;
+        }
+
+        let mut x = self.clone();
+        while n > 0 {
+            if n & 1 == 1 {
+                res = res.mul_mod(&x, m);
+            }
+            x = x.mul_mod(&x, m);
+            n >>= 1;
+        }
+        res
+    }
+}
+
+impl DynMatrix<f64> {
+    /// 消元成上三角矩阵, 返回交换行的次数, 遇到全为 0 的列时返回 None
+    fn eliminate(&mut self, cols: usize) -> Option<usize> {
+        let mut swaps = 0;
+        for i in 0..cols.min(self.rows) {
+            // 选取绝对值最大的主元
+            let pivot = (i..self.rows)
+                .max_by(|&a, &b| self[(a, i)].abs().total_cmp(&self[(b, i)].abs()))
+                .expect("rows must not be empty");
+            if self[(pivot, i)].abs() < EPS {
+                return None;
+            }
+
+            if pivot != i {
+                self.swap_rows(pivot, i);
+                swaps += 1;
+            }
+
+            for r in i + 1..self.rows {
+                let factor = self[(r, i)] / self[(i, i)];
+                for c in i..self.cols {
+                    let v = self[(i, c)];
+                    self[(r, c)] -= factor * v;
+                }
+            }
+        }
+
+        Some(swaps)
+    }
+
+    /// 行列式
+    pub fn determinant(&self) -> f64 {
+        assert_eq!(self.rows, self.cols, "matrix must be square");
+        let mut m = self.clone();
+        match m.eliminate(self.cols) {
+            None => 0.0,
+            Some(swaps) => {
+                let det: f64 = (0..m.rows).map(|i| m[(i, i)]).product();
+                if swaps % 2 == 0 {
+                    det
+                } else {
+                    -det
+                }
+            }
+        }
+    }
+
+    /// 高斯消元解线性方程组 `self · x = b`, 没有唯一解时返回 None
+    pub fn solve(&self, b: &[f64]) -> Option<Vec<f64>> {
+        assert_eq!(self.rows, self.cols, "matrix must be square");
+        assert_eq!(self.rows, b.len(), "dimension mismatch");
+
+        // 构造增广矩阵
+        let n = self.rows;
+        let mut aug = DynMatrix::new(n, n + 1);
+        for i in 0..n {
+            for j in 0..n {
+                aug[(i, j)] = self[(i, j)];
+            }
+            aug[(i, n)] = b[i];
+        }
+        aug.eliminate(n)?;
+
+        // 从最后一行开始回代
+        let mut x = vec![0.0; n];
+        for i in (0..n).rev() {
+            let sum: f64 = (i + 1..n).map(|j| aug[(i, j)] * x[j]).sum();
+            x[i] = (aug[(i, n)] - sum) / aug[(i, i)];
+        }
+
+        Some(x)
+    }
+}
+
+impl<T, const M: usize, const N: usize> From<Matrix<T, M, N>> for DynMatrix<T> {
+    fn from(m: Matrix<T, M, N>) -> Self {
+        Self {
+            rows: M,
+            cols: N,
+            data: m.0.into_iter().flatten().collect(),
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for DynMatrix<T> {
+    type Output = T;
+
+    fn index(&self, (i, j): (usize, usize)) -> &Self::Output {
+        assert!(i < self.rows && j < self.cols, "index out of bounds");
+        &self.data[i * self.cols + j]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for DynMatrix<T> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut Self::Output {
+        assert!(i < self.rows && j < self.cols, "index out of bounds");
+        &mut self.data[i * self.cols + j]
+    }
+}
+
+impl<T: Copy + Add<Output = T>> Add for &DynMatrix<T> {
+    type Output = DynMatrix<T>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        assert_eq!(
+            (self.rows, self.cols),
+            (rhs.rows, rhs.cols),
+            "dimension mismatch"
+        );
+        DynMatrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self
+                .data
+                .iter()
+                .zip(&rhs.data)
+                .map(|(&a, &b)| a + b)
+                .collect(),
+        }
+    }
+}
+
+impl<T: Copy + Sub<Output = T>> Sub for &DynMatrix<T> {
+    type Output = DynMatrix<T>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        assert_eq!(
+            (self.rows, self.cols),
+            (rhs.rows, rhs.cols),
+            "dimension mismatch"
+        );
+        DynMatrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self
+                .data
+                .iter()
+                .zip(&rhs.data)
+                .map(|(&a, &b)| a - b)
+                .collect(),
+        }
+    }
+}
+
+impl<T> Mul for &DynMatrix<T>
+where
+    T: Copy + Default + Mul<Output = T> + AddAssign,
+{
+    type Output = DynMatrix<T>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        assert_eq!(self.cols, rhs.rows, "dimension mismatch");
+        let mut res = DynMatrix::new(self.rows, rhs.cols);
+        for i in 0..self.rows {
+            for k in 0..self.cols {
+                let a = self[(i, k)];
+                for j in 0..rhs.cols {
+                    res[(i, j)] += a * rhs[(k, j)];
+                }
+            }
+        }
+        res
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -123,5 +809,175 @@ mod tests {
         assert_eq!(fib(7), 13);
         assert_eq!(fib(17), 1597);
         assert_eq!(fib(19), 4181);
+        assert_eq!(fib(90), 2880067194370816120);
+        assert_eq!(fib(186), 332825110087067562321196029789634457848);
+    }
+
+    #[test]
+    fn test_semiring() {
+        let x = Matrix::new([[1i64, 1], [1, 0]]);
+        assert_eq!(matrix_pow(x, 10)[0][1], 55);
+
+        // 三个点的有向图, 求恰好经过 3 条边的最短路径
+        let inf = MinPlus(None);
+        let w = |v| MinPlus(Some(v));
+        let g = Matrix::new([[inf, w(1), w(10)], [inf, inf, w(1)], [w(1), inf, inf]]);
+        let res = matrix_pow(g, 3);
+        assert_eq!(res[0][0], w(3));
+        assert_eq!(res[0][2], w(21));
+        assert_eq!(res[1][1], w(3));
+        assert_eq!(matrix_pow(g, 0)[0][1], inf);
+    }
+
+    #[test]
+    fn test_fib_mod_and_recurrence() {
+        let m = 1_000_000_007;
+        for n in 0..=186 {
+            assert_eq!(fib_mod(n, m) as u128, fib(n) % m as u128);
+        }
+        // fib(10^18) mod 1e9+7
+        assert_eq!(fib_mod(1_000_000_000_000_000_000, m), 209783453);
+
+        // 斐波那契数列
+        for n in 0..100 {
+            assert_eq!(linear_recurrence(&[1, 1], &[0, 1], n, m), fib_mod(n, m));
+        }
+
+        // a[n] = 2a[n-1] 即 2 的幂
+        assert_eq!(linear_recurrence(&[2], &[1], 10, m), 1024);
+
+        // a[n] = a[n-1] + 2a[n-2] + 3a[n-3] 和暴力递推比较
+        let (c, init) = ([1u64, 2, 3], [1u64, 2, 3]);
+        let mut a = init.to_vec();
+        for n in 3..200 {
+            let v = (c[0] * a[n - 1] + c[1] * a[n - 2] + c[2] * a[n - 3]) % m;
+            a.push(v);
+        }
+        for (n, &v) in a.iter().enumerate() {
+            assert_eq!(linear_recurrence(&c, &init, n, m), v);
+        }
+    }
+
+    #[test]
+    fn test_matrix_ops() {
+        let a = Matrix::new([[1, 2, 3], [4, 5, 6]]);
+        let b = Matrix::new([[6, 5, 4], [3, 2, 1]]);
+        assert_eq!(a + b, Matrix::new([[7; 3]; 2]));
+        assert_eq!(a - a, Matrix::new([[0; 3]; 2]));
+        assert_eq!(a.transpose(), Matrix::new([[1, 4], [2, 5], [3, 6]]));
+        assert_eq!(a * Matrix::identity(), a);
+
+        let x = Matrix::new([[1, 1], [1, 0]]);
+        // fib(90) 模 1e9+7
+        let res = matrix_pow_mod(x, 90, 1_000_000_007);
+        assert_eq!(res[0][1], 2880067194370816120 % 1_000_000_007);
+        assert_eq!(matrix_pow_mod(x, 0, 1), Matrix::new([[0; 2]; 2]));
+    }
+
+    #[test]
+    fn test_determinant_and_solve() {
+        let m = Matrix::new([[2.0, -3.0, 1.0], [2.0, 0.0, -1.0], [1.0, 4.0, 5.0]]);
+        assert!((m.determinant() - 49.0).abs() < 1e-9);
+
+        let singular = DynMatrix::from_rows(vec![vec![1.0, 2.0], vec![2.0, 4.0]]);
+        assert_eq!(singular.determinant(), 0.0);
+        assert_eq!(singular.solve(&[1.0, 2.0]), None);
+
+        // 2x + y - z = 8, -3x - y + 2z = -11, -2x + y + 2z = -3 的解是 (2, 3, -1)
+        let a = DynMatrix::from_rows(vec![
+            vec![2.0, 1.0, -1.0],
+            vec![-3.0, -1.0, 2.0],
+            vec![-2.0, 1.0, 2.0],
+        ]);
+        let x = a.solve(&[8.0, -11.0, -3.0]).unwrap();
+        for (v, e) in x.iter().zip([2.0, 3.0, -1.0]) {
+            assert!((v - e).abs() < 1e-9);
+        }
+
+        // 需要交换行才能消元的情况
+        let p = DynMatrix::from_rows(vec![vec![0.0, 1.0], vec![1.0, 0.0]]);
+        assert_eq!(p.determinant(), -1.0);
+    }
+
+    #[test]
+    fn test_dyn_matrix() {
+        let a = DynMatrix::from_rows(vec![vec![1u64, 2], vec![3, 4], vec![5, 6]]);
+        assert_eq!((a.rows(), a.cols()), (3, 2));
+
+        let at = a.transpose();
+        let prod = &at * &a;
+        assert_eq!(prod, DynMatrix::from_rows(vec![vec![35, 44], vec![44, 56]]));
+        assert_eq!(&(&prod + &prod) - &prod, prod);
+
+        let fib = DynMatrix::from_rows(vec![vec![1u64, 1], vec![1, 0]]);
+        assert_eq!(fib.pow(90)[(0, 1)], 2880067194370816120);
+        assert_eq!(fib.pow(0), DynMatrix::identity(2));
+        assert_eq!(DynMatrix::from(Matrix::new([[1u64, 1], [1, 0]])), fib);
+    }
+
+    #[test]
+    fn test_linear_recurrence() {
+        let fib_rec = LinearRecurrence::new(&[1, 1], &[0, 1]);
+        assert_eq!(fib_rec.order(), 2);
+        for n in 0..=186 {
+            assert_eq!(fib_rec.nth(n), fib(n));
+        }
+
+        // a[n] = a[n-1] + 2a[n-2] + 3a[n-3], 精确值和取模结果都和暴力递推比较
+        let rec = LinearRecurrence::new(&[1, 2, 3], &[1, 2, 3]);
+        let mut a: Vec<u128> = vec![1, 2, 3];
+        for n in 3..60 {
+            a.push(a[n - 1] + 2 * a[n - 2] + 3 * a[n - 3]);
+        }
+        for (n, &v) in a.iter().enumerate() {
+            assert_eq!(rec.nth(n), v);
+            assert_eq!(rec.nth_mod(n, 998244353) as u128, v % 998244353);
+        }
+
+        // 一阶递推 a[n] = 3a[n-1]
+        let pow3 = LinearRecurrence::new(&[3], &[1]);
+        assert_eq!(pow3.nth(40), 3u128.pow(40));
+        assert_eq!(pow3.companion(), DynMatrix::from_rows(vec![vec![3u128]]));
+    }
+
+    #[test]
+    fn test_count_paths() {
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        let m = 1_000_000_007;
+        for _ in 0..20 {
+            let n = rng.gen_range(1..8);
+            let edges: Vec<_> = (0..rng.gen_range(0..20))
+                .map(|_| (rng.gen_range(0..n), rng.gen_range(0..n)))
+                .collect();
+            let g = Graph::from_edges(n, &edges, rng.gen());
+
+            // 按步数递推: dp[v] 为从 src 出发走 step 步到达 v 的路径数
+            for k in 0..12 {
+                let paths = count_paths(&g, k, m);
+                for src in 0..n {
+                    let mut dp = vec![0u64; n];
+                    dp[src] = 1;
+                    for _ in 0..k {
+                        let mut next = vec![0u64; n];
+                        for (u, &d) in dp.iter().enumerate() {
+                            for &v in g.neighbors(u) {
+                                next[v] = (next[v] + d) % m;
+                            }
+                        }
+                        dp = next;
+                    }
+                    assert!((0..n).all(|v| paths[(src, v)] == dp[v]));
+                }
+            }
+        }
+
+        // 有向环上只有走整圈的倍数才能回到起点
+        let cycle = Graph::from_edges(4, &[(0, 1), (1, 2), (2, 3), (3, 0)], true);
+        assert_eq!(count_paths(&cycle, 8, m)[(0, 0)], 1);
+        assert_eq!(count_paths(&cycle, 7, m)[(0, 0)], 0);
+        assert_eq!(count_paths(&cycle, 7, m)[(0, 3)], 1);
+        assert_eq!(count_paths(&cycle, 0, 1)[(0, 0)], 0);
     }
 }
@@ -0,0 +1,213 @@
+//! MinHash 和 SimHash
+//!
+//! - [wikipedia - MinHash](https://en.wikipedia.org/wiki/MinHash)
+//! - [wikipedia - SimHash](https://en.wikipedia.org/wiki/SimHash)
+//! - [Mining of Massive Datasets - Chapter 3](http://infolab.stanford.edu/~ullman/mmds/ch3n.pdf)
+//!
+//! 两者都是局部敏感哈希: 相似的输入得到相似的签名, 用来快速估计相似度和查找近似重复
+//!
+//! MinHash 用于估计两个集合的 Jaccard 相似度 |A ∩ B| / |A ∪ B|
+//! 对于一个随机哈希函数 h, min(h(A)) == min(h(B)) 的概率恰好等于 Jaccard 相似度
+//! 使用 k 个不同的哈希函数得到长度为 k 的签名, 签名中相同位置相等的比例就是相似度的估计值
+//!
+//! LSH 分桶(banding): 把签名分成 b 段, 每段 r 行, 只要有一段完全相同就认为是候选对
+//! 相似度为 s 的两个集合成为候选对的概率是 1 - (1 - s^r)^b, 是一条在 (1/b)^(1/r) 附近陡峭上升的 S 曲线
+//!
+//! SimHash 把带权重的特征集合压缩成一个 64 位指纹, 对每一位:
+//! 特征哈希值这一位是 1 则加上权重, 否则减去权重, 最后累加值为正的位设为 1
+//! 两个指纹的汉明距离越小说明越相似
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::cuckoo_filter::hash;
+
+/// MinHash 签名生成器
+pub struct MinHash {
+    seeds: Vec<u64>,
+}
+
+impl MinHash {
+    /// 使用 k 个哈希函数, 相同 seed 生成的签名可以互相比较
+    pub fn new(k: usize, seed: u64) -> Self {
+        assert!(k > 0, "k must be positive");
+        Self {
+            seeds: (0..k as u64).map(|i| hash(&i, seed)).collect(),
+        }
+    }
+
+    /// 签名长度
+    pub fn len(&self) -> usize {
+        self.seeds.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seeds.is_empty()
+    }
+
+    /// 计算集合的签名, 空集合的签名全是 `u64::MAX`
+    pub fn signature<'a, T, I>(&self, tokens: I) -> Vec<u64>
+    where
+        T: Hash + ?Sized + 'a,
+        I: IntoIterator<Item = &'a T>,
+    {
+        let mut sig = vec![u64::MAX; self.seeds.len()];
+        for token in tokens {
+            for (min, &seed) in sig.iter_mut().zip(&self.seeds) {
+                *min = (*min).min(hash(token, seed));
+            }
+        }
+
+        sig
+    }
+}
+
+/// 根据两个签名估计 Jaccard 相似度
+pub fn jaccard(a: &[u64], b: &[u64]) -> f64 {
+    assert_eq!(a.len(), b.len(), "signature length mismatch");
+    let same = a.iter().zip(b).filter(|(x, y)| x == y).count();
+    same as f64 / a.len() as f64
+}
+
+/// 基于 MinHash 签名分桶的 LSH 索引
+pub struct Lsh {
+    rows: usize,
+    bands: Vec<HashMap<u64, Vec<usize>>>,
+}
+
+impl Lsh {
+    /// 把长度为 bands * rows 的签名分成 bands 段, 每段 rows 行
+    pub fn new(bands: usize, rows: usize) -> Self {
+        assert!(bands > 0 && rows > 0, "bands and rows must be positive");
+        Self {
+            rows,
+            bands: (0..bands).map(|_| HashMap::new()).collect(),
+        }
+    }
+
+    /// 每一段签名的哈希值
+    fn band_hashes<'a>(&'a self, sig: &'a [u64]) -> impl Iterator<Item = u64> + 'a {
+        assert_eq!(
+            sig.len(),
+            self.bands.len() * self.rows,
+            "signature length mismatch"
+        );
+        sig.chunks(self.rows).map(|band| hash(band, 0))
+    }
+
+    /// 插入编号为 id 的签名
+    pub fn insert(&mut self, id: usize, sig: &[u64]) {
+        let hashes: Vec<_> = self.band_hashes(sig).collect();
+        for (bucket, h) in self.bands.iter_mut().zip(hashes) {
+            bucket.entry(h).or_default().push(id);
+        }
+    }
+
+    /// 查找至少有一段签名完全相同的候选编号, 按编号排序
+    pub fn query(&self, sig: &[u64]) -> Vec<usize> {
+        let mut res: Vec<_> = self
+            .band_hashes(sig)
+            .zip(&self.bands)
+            .filter_map(|(h, bucket)| bucket.get(&h))
+            .flatten()
+            .copied()
+            .collect();
+
+        res.sort_unstable();
+        res.dedup();
+        res
+    }
+}
+
+/// 计算带权重特征集合的 SimHash 指纹
+pub fn simhash<'a, T, I>(features: I) -> u64
+where
+    T: Hash + ?Sized + 'a,
+    I: IntoIterator<Item = (&'a T, i64)>,
+{
+    let mut acc = [0i64; 64];
+    for (feature, weight) in features {
+        let h = hash(feature, 0);
+        for (i, v) in acc.iter_mut().enumerate() {
+            if h >> i & 1 == 1 {
+                *v += weight;
+            } else {
+                *v -= weight;
+            }
+        }
+    }
+
+    acc.iter()
+        .enumerate()
+        .filter(|(_, &v)| v > 0)
+        .fold(0, |fp, (i, _)| fp | 1 << i)
+}
+
+/// 两个指纹的汉明距离
+pub fn hamming(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    /// 把文本切分成长度为 3 的字符片段(shingle)
+    fn shingles(text: &str) -> HashSet<String> {
+        let chars: Vec<char> = text.chars().collect();
+        chars.windows(3).map(|w| w.iter().collect()).collect()
+    }
+
+    #[test]
+    fn test_minhash() {
+        let a: HashSet<u32> = (0..1000).collect();
+        let b: HashSet<u32> = (500..1500).collect();
+        let exact = 500.0 / 1500.0;
+
+        let minhash = MinHash::new(256, 42);
+        let (sa, sb) = (minhash.signature(&a), minhash.signature(&b));
+        assert!((jaccard(&sa, &sb) - exact).abs() < 0.1);
+        assert_eq!(jaccard(&sa, &sa), 1.0);
+
+        let c: HashSet<u32> = (2000..3000).collect();
+        assert!(jaccard(&sa, &minhash.signature(&c)) < 0.05);
+    }
+
+    #[test]
+    fn test_lsh() {
+        let docs = [
+            "the quick brown fox jumps over the lazy dog",
+            "the quick brown fox jumped over the lazy dog",
+            "a completely different sentence about rust",
+            "the quick brown cat jumps over the lazy dog",
+        ];
+
+        let minhash = MinHash::new(64, 7);
+        let mut lsh = Lsh::new(16, 4);
+        let sigs: Vec<_> = docs
+            .iter()
+            .map(|d| minhash.signature(&shingles(d)))
+            .collect();
+        for (id, sig) in sigs.iter().enumerate() {
+            lsh.insert(id, sig);
+        }
+
+        let candidates = lsh.query(&sigs[0]);
+        assert!(candidates.contains(&0));
+        assert!(candidates.contains(&1));
+        assert!(!candidates.contains(&2));
+    }
+
+    #[test]
+    fn test_simhash() {
+        let words = |s: &'static str| s.split(' ').map(|w| (w, 1));
+        let a = simhash(words("the quick brown fox jumps over the lazy dog today"));
+        let b = simhash(words("the quick brown fox jumps over the lazy cat today"));
+        let c = simhash(words("rust is a systems programming language with no gc"));
+
+        assert_eq!(hamming(a, a), 0);
+        assert!(hamming(a, b) < hamming(a, c));
+        assert!(hamming(a, b) <= 16);
+    }
+}
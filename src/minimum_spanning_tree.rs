@@ -15,11 +15,52 @@
 //! - 如果相连，表示这两个节点都已经加入到同一颗树中，不做处理
 //! - 如果不相连，表示这两个节点目前还处于不同的树中，将两节点使用并查集合并即可
 //! - 遍历结束后，能够保证最终的权值是最小的(详细的归纳法证明见 oi-wiki 链接)
+//!
+//! Prim 算法从一个节点开始逐步扩展一棵树，每次选择连接树内和树外节点的边中权值最小的一条，把对应的树外节点加入树中  
+//! 用堆保存所有一端在树内的边，取出时另一端已经在树内的边直接丢弃，复杂度 O(mlogm)，适合稀疏图  
+//! 稠密图(m 接近 n²)上更适合不用堆的朴素版本：每次遍历所有树外节点到树的最短距离，复杂度 O(n²)
+//!
+//! Borůvka 算法每一轮为每个连通块找到连出去的权值最小的边，把这些边全部加入并合并连通块  
+//! 每一轮连通块的数量至少减半，所以最多 logn 轮，复杂度 O(mlogn)，每个连通块的查找互不影响，很适合并行  
+//! 边权相同时需要按统一的规则(比如边的编号)打破平局，否则可能同时选中构成环的几条等权边
+//!
+//! 图不连通时三种算法得到的都是最小生成森林
+//!
+//! 参考：[OI wiki - 次小生成树](https://oi-wiki.org/graph/mst/#次小生成树)
+//!
+//! 严格次小生成树：向最小生成树中加入一条非树边 (u, v, w) 会形成一个环，删去环上(即树上 u 到 v 的路径上)一条边就得到另一棵生成树  
+//! 为了让权值最小且严格大于最小生成树，应该删去路径上小于 w 的最大边，所以需要同时维护路径上的最大值和严格次大值  
+//! 用倍增求 LCA，同时维护每个节点向上 2^k 条边的最大值和严格次大值，每次查询 O(logn)
+//!
+//! 验证一个生成树是不是最小生成树利用的是环性质：生成树最小当且仅当每条非树边都不小于树上对应路径的最大边
+
+use std::cmp::Reverse;
 
-type Edge = (usize, usize, usize);
+use crate::binary_heap::build_heap;
+use crate::disjoint_set::UnionFind;
+
+/// 无向边 (u, v, w)，节点编号为 `0..n`
+pub type Edge = (usize, usize, usize);
+
+/// 最小生成树(森林)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpanningTree {
+    /// 所有边的权值之和
+    pub weight: usize,
+    pub edges: Vec<Edge>,
+}
+
+impl SpanningTree {
+    fn new(edges: Vec<Edge>) -> Self {
+        Self {
+            weight: edges.iter().map(|e| e.2).sum(),
+            edges,
+        }
+    }
+}
 
 /// Kruskal 算法构建最小生成树
-pub fn kruskal(mut g: Vec<Edge>, m: usize /* 表示节点数量 */) -> Vec<Edge> {
+pub fn kruskal(mut g: Vec<Edge>, m: usize /* 表示节点数量 */) -> SpanningTree {
     // 按边权逆序 sort 方面后面从尾部 pop 最小值
     g.sort_by(|a, b| b.2.cmp(&a.2));
 
@@ -28,48 +69,288 @@ pub fn kruskal(mut g: Vec<Edge>, m: usize /* 表示节点数量 */) -> Vec<Edge>
     let mut res = vec![];
     while let Some(edge) = g.pop() {
         let (a, b, _) = edge;
-        if !uf.connected(a, b) {
+        if uf.union(a, b) {
             res.push(edge);
-            uf.union(a, b);
         }
     }
 
-    res
+    SpanningTree::new(res)
 }
 
-struct UnionFind {
-    parent: Vec<usize>,
+/// Prim 算法构建最小生成树
+pub fn prim(g: &[Edge], n: usize /* 表示节点数量 */) -> SpanningTree {
+    let mut adj = vec![vec![]; n];
+    for &(u, v, w) in g {
+        adj[u].push((v, w));
+        adj[v].push((u, w));
+    }
+
+    let mut in_tree = vec![false; n];
+    let mut res = vec![];
+    // 图可能不连通，从每个还不在树中的节点开始扩展一棵树
+    for s in 0..n {
+        if in_tree[s] {
+            continue;
+        }
+
+        // 堆中保存 (w, v, u) 表示树内节点 u 到 v 的边，Reverse 变成小根堆
+        let mut heap = build_heap(&[]);
+        let mut next = Some((s, None));
+        while let Some((u, edge)) = next.take() {
+            in_tree[u] = true;
+            res.extend(edge);
+            for &(v, w) in &adj[u] {
+                if !in_tree[v] {
+                    heap.push(Reverse((w, v, u)));
+                }
+            }
+
+            // 丢弃另一端已经在树内的边
+            while let Some(Reverse((w, v, p))) = heap.pop() {
+                if !in_tree[v] {
+                    next = Some((v, Some((p, v, w))));
+                    break;
+                }
+            }
+        }
+    }
+
+    SpanningTree::new(res)
 }
 
-impl UnionFind {
-    fn new(n: usize) -> Self {
-        let parent = (0..n).collect();
-        Self { parent }
+/// Borůvka 算法构建最小生成树
+pub fn boruvka(g: &[Edge], n: usize /* 表示节点数量 */) -> SpanningTree {
+    let mut uf = UnionFind::new(n);
+    let mut res = vec![];
+
+    loop {
+        // cheapest[c] 是连通块 c 连出去的最小边的编号，按 (权值, 编号) 比较来打破平局
+        let mut cheapest: Vec<Option<usize>> = vec![None; n];
+        for (i, &(u, v, w)) in g.iter().enumerate() {
+            let (cu, cv) = (uf.find(u), uf.find(v));
+            if cu == cv {
+                continue;
+            }
+            for c in [cu, cv] {
+                if cheapest[c].is_none_or(|j| (w, i) < (g[j].2, j)) {
+                    cheapest[c] = Some(i);
+                }
+            }
+        }
+
+        let mut merged = false;
+        for i in cheapest.into_iter().flatten() {
+            let (u, v, _) = g[i];
+            // 两个连通块可能选中了同一条边
+            if !uf.connected(u, v) {
+                uf.union(u, v);
+                res.push(g[i]);
+                merged = true;
+            }
+        }
+
+        if !merged {
+            break;
+        }
     }
 
-    fn union(&mut self, a: usize, b: usize) {
-        let root_a = self.find(a);
-        let root_b = self.find(b);
-        if root_a != root_b {
-            self.parent[root_a] = root_b;
+    SpanningTree::new(res)
+}
+
+/// 树上路径的最大边和严格次大边, (权值, 树边编号)
+type Top2 = [Option<(usize, usize)>; 2];
+
+/// 合并两组最大值和严格次大值
+fn merge_top2(a: Top2, b: Top2) -> Top2 {
+    let mut all: Vec<_> = a.into_iter().chain(b).flatten().collect();
+    all.sort_by_key(|x| Reverse(x.0));
+    let first = all.first().copied();
+    let second = first.and_then(|f| all.iter().find(|x| x.0 < f.0).copied());
+    [first, second]
+}
+
+/// 在生成森林上用倍增查询路径上的最大边
+struct PathMax {
+    depth: Vec<usize>,
+    /// 节点所在树的编号
+    tree: Vec<usize>,
+    /// up[k][v] 是 v 向上 2^k 步的祖先
+    up: Vec<Vec<usize>>,
+    /// top[k][v] 是 v 向上 2^k 条边的最大值和严格次大值
+    top: Vec<Vec<Top2>>,
+}
+
+impl PathMax {
+    fn new(n: usize, edges: &[Edge]) -> Self {
+        let mut adj = vec![vec![]; n];
+        for (i, &(u, v, w)) in edges.iter().enumerate() {
+            adj[u].push((v, w, i));
+            adj[v].push((u, w, i));
+        }
+
+        let levels = (usize::BITS - n.max(1).leading_zeros()) as usize;
+        let mut up = vec![(0..n).collect::<Vec<_>>(); levels];
+        let mut top = vec![vec![[None, None]; n]; levels];
+        let mut depth = vec![0; n];
+        let mut tree = vec![usize::MAX; n];
+
+        // 从每棵树的根开始 DFS 求出父节点和深度
+        for root in 0..n {
+            if tree[root] != usize::MAX {
+                continue;
+            }
+            tree[root] = root;
+            let mut stack = vec![root];
+            while let Some(u) = stack.pop() {
+                for &(v, w, i) in &adj[u] {
+                    if tree[v] == usize::MAX {
+                        tree[v] = root;
+                        depth[v] = depth[u] + 1;
+                        up[0][v] = u;
+                        top[0][v] = [Some((w, i)), None];
+                        stack.push(v);
+                    }
+                }
+            }
+        }
+
+        for k in 1..levels {
+            for v in 0..n {
+                let mid = up[k - 1][v];
+                up[k][v] = up[k - 1][mid];
+                top[k][v] = merge_top2(top[k - 1][v], top[k - 1][mid]);
+            }
+        }
+
+        Self {
+            depth,
+            tree,
+            up,
+            top,
         }
     }
 
-    fn find(&self, mut a: usize) -> usize {
-        let mut root = self.parent[a];
-        while a != root {
-            a = root;
-            root = self.parent[a];
+    /// u 到 v 路径上的最大边和严格次大边, 不在同一棵树中时返回 None
+    fn query(&self, mut u: usize, mut v: usize) -> Option<Top2> {
+        if self.tree[u] != self.tree[v] {
+            return None;
+        }
+
+        let mut res = [None, None];
+        if self.depth[u] < self.depth[v] {
+            std::mem::swap(&mut u, &mut v);
+        }
+        let diff = self.depth[u] - self.depth[v];
+        for k in 0..self.up.len() {
+            if diff >> k & 1 == 1 {
+                res = merge_top2(res, self.top[k][u]);
+                u = self.up[k][u];
+            }
+        }
+        if u == v {
+            return Some(res);
+        }
+
+        for k in (0..self.up.len()).rev() {
+            if self.up[k][u] != self.up[k][v] {
+                res = merge_top2(res, merge_top2(self.top[k][u], self.top[k][v]));
+                u = self.up[k][u];
+                v = self.up[k][v];
+            }
         }
 
-        root
+        Some(merge_top2(res, merge_top2(self.top[0][u], self.top[0][v])))
     }
+}
 
-    fn connected(&self, a: usize, b: usize) -> bool {
-        let root_a = self.find(a);
-        let root_b = self.find(b);
-        root_a == root_b
+/// 不在 tree 中的边, 有重边时按数量扣除
+fn non_tree_edges(g: &[Edge], tree: &[Edge]) -> Vec<Edge> {
+    let mut count = std::collections::HashMap::new();
+    for &(u, v, w) in tree {
+        *count.entry((u.min(v), u.max(v), w)).or_insert(0) += 1;
     }
+
+    g.iter()
+        .filter(
+            |&&(u, v, w)| match count.get_mut(&(u.min(v), u.max(v), w)) {
+                Some(c) if *c > 0 => {
+                    *c -= 1;
+                    false
+                }
+                _ => true,
+            },
+        )
+        .copied()
+        .collect()
+}
+
+/// 严格次小生成树, 图不连通或者不存在严格次小生成树时返回 None
+///
+/// ```
+/// use impx::minimum_spanning_tree::second_best_mst;
+///
+/// let g = [(0, 1, 1), (1, 2, 2), (0, 2, 3), (2, 3, 4), (1, 3, 6)];
+/// assert_eq!(second_best_mst(&g, 4).unwrap().weight, 8);
+/// ```
+pub fn second_best_mst(g: &[Edge], n: usize) -> Option<SpanningTree> {
+    let mst = kruskal(g.to_vec(), n);
+    if mst.edges.len() + 1 != n {
+        return None;
+    }
+
+    let pm = PathMax::new(n, &mst.edges);
+    // (增加的权值, 加入的边, 删除的树边编号)
+    let mut best: Option<(usize, Edge, usize)> = None;
+    for e in non_tree_edges(g, &mst.edges) {
+        let (u, v, w) = e;
+        if u == v {
+            continue;
+        }
+
+        // 删除小于 w 的最大边
+        let [first, second] = pm.query(u, v).unwrap();
+        let removed = match first {
+            Some((fw, i)) if fw < w => Some((w - fw, i)),
+            _ => second.map(|(sw, i)| (w - sw, i)),
+        };
+        if let Some((delta, i)) = removed {
+            if best.is_none_or(|b| delta < b.0) {
+                best = Some((delta, e, i));
+            }
+        }
+    }
+
+    let (_, added, removed) = best?;
+    let mut edges = mst.edges;
+    edges[removed] = added;
+    Some(SpanningTree::new(edges))
+}
+
+/// 判断 tree 是否是图 g 的最小生成树(森林)
+///
+/// 先检查 tree 中的边都来自 g、没有环并且和 g 的连通性相同, 再检查每条非树边都不小于树上路径的最大边
+pub fn is_minimum_spanning_tree(g: &[Edge], n: usize, tree: &[Edge]) -> bool {
+    // tree 中的边必须都是 g 中的边
+    if non_tree_edges(g, tree).len() + tree.len() != g.len() {
+        return false;
+    }
+
+    let mut uf = UnionFind::new(n);
+    for &(u, v, _) in tree {
+        if uf.connected(u, v) {
+            return false;
+        }
+        uf.union(u, v);
+    }
+
+    let pm = PathMax::new(n, tree);
+    non_tree_edges(g, tree)
+        .into_iter()
+        .all(|(u, v, w)| match pm.query(u, v) {
+            // 非树边连接了两棵不同的树, 说明 tree 不是生成森林
+            None => false,
+            Some([first, _]) => first.is_none_or(|(fw, _)| fw <= w),
+        })
 }
 
 #[cfg(test)]
@@ -80,8 +361,9 @@ mod tests {
     fn test_kruskal() {
         let g = vec![(0, 1, 1), (1, 2, 1), (2, 0, 3)];
         let t = kruskal(g, 3);
-        let s = t.iter().fold(0, |acc, x| acc + x.2);
+        let s = t.edges.iter().fold(0, |acc, x| acc + x.2);
         assert_eq!(s, 2);
+        assert_eq!(t.weight, 2);
 
         let g = vec![
             (0, 1, 7),  // A -> B 7
@@ -96,8 +378,179 @@ mod tests {
             (4, 6, 9),  // E -> G 9
             (5, 6, 11), // F -> G 11
         ];
-        let t = kruskal(g, 7);
-        let s = t.iter().fold(0, |acc, x| acc + x.2);
+        let t = kruskal(g.clone(), 7);
+        let s = t.edges.iter().fold(0, |acc, x| acc + x.2);
         assert_eq!(s, 39);
+        assert_eq!(prim(&g, 7).weight, 39);
+        assert_eq!(boruvka(&g, 7).weight, 39);
+    }
+
+    #[test]
+    fn test_mst_random() {
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..50 {
+            let n = rng.gen_range(1..30);
+            let m = rng.gen_range(0..60);
+            // 权值范围很小, 有很多等权边
+            let g: Vec<Edge> = (0..m)
+                .map(|_| {
+                    (
+                        rng.gen_range(0..n),
+                        rng.gen_range(0..n),
+                        rng.gen_range(0..5),
+                    )
+                })
+                .collect();
+
+            let expect = kruskal(g.clone(), n);
+            for t in [prim(&g, n), boruvka(&g, n)] {
+                assert_eq!(t.weight, expect.weight);
+                assert_eq!(t.edges.len(), expect.edges.len());
+                assert_eq!(t.weight, t.edges.iter().map(|e| e.2).sum::<usize>());
+
+                // 边数正确并且没有环, 就是生成森林
+                let mut uf = UnionFind::new(n);
+                for &(u, v, _) in &t.edges {
+                    assert!(!uf.connected(u, v));
+                    uf.union(u, v);
+                }
+            }
+        }
+    }
+
+    /// 枚举所有 n-1 条边的子集求出所有生成树的权值
+    fn all_spanning_weights(g: &[Edge], n: usize) -> Vec<usize> {
+        let m = g.len();
+        let mut res = vec![];
+        for mask in 0..1u32 << m {
+            if mask.count_ones() as usize + 1 != n {
+                continue;
+            }
+            let edges: Vec<_> = (0..m)
+                .filter(|i| mask >> i & 1 == 1)
+                .map(|i| g[i])
+                .collect();
+            let mut uf = UnionFind::new(n);
+            if edges.iter().all(|&(u, v, _)| {
+                let ok = !uf.connected(u, v);
+                uf.union(u, v);
+                ok
+            }) {
+                res.push(edges.iter().map(|e| e.2).sum());
+            }
+        }
+        res.sort();
+        res.dedup();
+        res
+    }
+
+    #[test]
+    fn test_second_best_mst() {
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let n = rng.gen_range(1..7);
+            let m = rng.gen_range(0..11);
+            let g: Vec<Edge> = (0..m)
+                .map(|_| {
+                    (
+                        rng.gen_range(0..n),
+                        rng.gen_range(0..n),
+                        rng.gen_range(0..6),
+                    )
+                })
+                .collect();
+
+            let weights = all_spanning_weights(&g, n);
+            let second = second_best_mst(&g, n);
+            assert_eq!(second.as_ref().map(|t| t.weight), weights.get(1).copied());
+
+            if let Some(t) = second {
+                assert_eq!(t.edges.len() + 1, n);
+                assert!(!is_minimum_spanning_tree(&g, n, &t.edges));
+                assert_eq!(all_spanning_weights(&t.edges, n), vec![t.weight]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_is_minimum_spanning_tree() {
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let n = rng.gen_range(1..20);
+            let m = rng.gen_range(0..40);
+            let g: Vec<Edge> = (0..m)
+                .map(|_| {
+                    (
+                        rng.gen_range(0..n),
+                        rng.gen_range(0..n),
+                        rng.gen_range(0..10),
+                    )
+                })
+                .collect();
+
+            let mst = kruskal(g.clone(), n);
+            assert!(is_minimum_spanning_tree(&g, n, &mst.edges));
+
+            // 按随机顺序加边得到一个随机的生成森林, 最小当且仅当权值和最小生成树相同
+            let mut shuffled = g.clone();
+            for i in (1..shuffled.len()).rev() {
+                shuffled.swap(i, rng.gen_range(0..=i));
+            }
+            let mut uf = UnionFind::new(n);
+            let mut forest = vec![];
+            for &(u, v, w) in &shuffled {
+                if !uf.connected(u, v) {
+                    uf.union(u, v);
+                    forest.push((u, v, w));
+                }
+            }
+            let weight: usize = forest.iter().map(|e| e.2).sum();
+            assert_eq!(
+                is_minimum_spanning_tree(&g, n, &forest),
+                weight == mst.weight
+            );
+
+            // 少一条边就不再是生成森林
+            if let Some(e) = forest.pop() {
+                assert!(!is_minimum_spanning_tree(&g, n, &forest), "{e:?}");
+            }
+        }
+        assert!(!is_minimum_spanning_tree(&[(0, 1, 1)], 2, &[(0, 1, 2)]));
+    }
+
+    /// 大规模随机图, 以及不做按秩合并和路径压缩时查找会退化成 O(n) 的星形图
+    #[test]
+    fn test_mst_large() {
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        let (n, m) = (100_000, 400_000);
+        let mut g: Vec<Edge> = (0..m)
+            .map(|_| {
+                (
+                    rng.gen_range(0..n),
+                    rng.gen_range(0..n),
+                    rng.gen_range(0..1_000_000),
+                )
+            })
+            .collect();
+        // 再加一条链保证连通
+        g.extend((1..n).map(|i| (i - 1, i, 1_000_000)));
+
+        let t = kruskal(g.clone(), n);
+        assert_eq!(t.edges.len(), n - 1);
+        assert_eq!(t.weight, prim(&g, n).weight);
+        assert_eq!(t.weight, boruvka(&g, n).weight);
+
+        // 每条边都和 0 相连, 朴素的合并会把 0 所在的树不断挂到新节点下面形成一条长链
+        let star: Vec<Edge> = (1..n).map(|i| (0, i, i)).collect();
+        let t = kruskal(star, n);
+        assert_eq!(t.weight, (1..n).sum::<usize>());
     }
 }
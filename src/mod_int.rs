@@ -0,0 +1,297 @@
+//! 模整数
+//!
+//! - [OI Wiki - 模算术简介](https://oi-wiki.org/math/number-theory/mod-arith/)
+//! - [OI Wiki - 乘法逆元](https://oi-wiki.org/math/number-theory/inverse/)
+//!
+//! [`ModInt<M>`] 保存 `0..M` 内的一个值, 所有运算的结果都自动对 M 取模, 模数是类型的一部分,
+//! 不同模数的数不能混在一起运算, 也不会忘记取模
+//!
+//! - 加减法: 两个数都在 `0..M` 内, 相加后最多减一次 M, 相减时借位加一次 M, 不需要除法
+//! - 乘法: 两个小于 M 的数相乘可能超过 u64, 中间结果使用 u128
+//! - 除法: 乘以除数的乘法逆元, 逆元用扩展欧几里得算法求出, 所以 M 不一定是质数, 只要除数和 M 互质即可;
+//!   M 是质数时也可以用费马小定理 a^(M-2) ≡ a^(-1) 求逆元
+//!
+//! [`ModInt`] 实现了 [`Semiring`], 可以直接用于矩阵快速幂
+
+use std::fmt;
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::matrix_exp::Semiring;
+
+/// 模 M 的整数, M 必须大于 0
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ModInt<const M: u64>(u64);
+
+/// 模 998244353 的整数, 和 [`crate::fft::MOD`] 相同
+pub type ModInt998244353 = ModInt<998_244_353>;
+
+/// 模 10^9+7 的整数
+pub type ModInt1000000007 = ModInt<1_000_000_007>;
+
+impl<const M: u64> ModInt<M> {
+    /// 模数
+    pub const MODULUS: u64 = M;
+
+    pub const fn new(v: u64) -> Self {
+        Self(v % M)
+    }
+
+    /// `0..M` 内的值
+    pub fn value(self) -> u64 {
+        self.0
+    }
+
+    /// 快速幂
+    pub fn pow(self, mut exp: u64) -> Self {
+        let mut base = self;
+        let mut res = Self::new(1);
+        while exp > 0 {
+            if exp & 1 == 1 {
+                res *= base;
+            }
+            base *= base;
+            exp >>= 1;
+        }
+        res
+    }
+
+    /// 乘法逆元, 和 M 不互质时不存在
+    ///
+    /// [`crate::number_theory::mod_inv`] 使用 i64 计算, 这里用 i128 迭代求解, 支持所有 u64 的模数
+    pub fn inv(self) -> Option<Self> {
+        // 维护 r = a·x (mod M), 初始时 (r0, x0) = (M, 0), (r1, x1) = (a, 1)
+        let (mut r0, mut r1) = (M as i128, self.0 as i128);
+        let (mut x0, mut x1) = (0i128, 1i128);
+        while r1 != 0 {
+            let q = r0 / r1;
+            (r0, r1) = (r1, r0 - q * r1);
+            (x0, x1) = (x1, x0 - q * x1);
+        }
+
+        (r0 == 1).then(|| Self(x0.rem_euclid(M as i128) as u64))
+    }
+}
+
+impl<const M: u64> From<u64> for ModInt<M> {
+    fn from(v: u64) -> Self {
+        Self::new(v)
+    }
+}
+
+impl<const M: u64> From<u32> for ModInt<M> {
+    fn from(v: u32) -> Self {
+        Self::new(v as u64)
+    }
+}
+
+impl<const M: u64> From<u8> for ModInt<M> {
+    fn from(v: u8) -> Self {
+        Self::new(v as u64)
+    }
+}
+
+impl<const M: u64> From<usize> for ModInt<M> {
+    fn from(v: usize) -> Self {
+        Self::new(v as u64)
+    }
+}
+
+impl<const M: u64> From<i64> for ModInt<M> {
+    /// 负数取模后落在 `0..M` 内, 比如模 7 时 -1 是 6
+    fn from(v: i64) -> Self {
+        Self((v as i128).rem_euclid(M as i128) as u64)
+    }
+}
+
+impl<const M: u64> Add for ModInt<M> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        // M 超过 2^63 时相加可能溢出, 溢出时真实的和一定大于 M
+        let (s, overflow) = self.0.overflowing_add(rhs.0);
+        if overflow || s >= M {
+            Self(s.wrapping_sub(M))
+        } else {
+            Self(s)
+        }
+    }
+}
+
+impl<const M: u64> Sub for ModInt<M> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        if self.0 >= rhs.0 {
+            Self(self.0 - rhs.0)
+        } else {
+            Self(self.0.wrapping_sub(rhs.0).wrapping_add(M))
+        }
+    }
+}
+
+impl<const M: u64> Mul for ModInt<M> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self((self.0 as u128 * rhs.0 as u128 % M as u128) as u64)
+    }
+}
+
+impl<const M: u64> Div for ModInt<M> {
+    type Output = Self;
+
+    /// 除数和 M 不互质时 panic
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inv().expect("divisor is not invertible modulo M")
+    }
+}
+
+impl<const M: u64> Neg for ModInt<M> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::default() - self
+    }
+}
+
+macro_rules! impl_assign {
+    ($($trait:ident $method:ident $op:tt),*) => {
+        $(
+            impl<const M: u64> $trait for ModInt<M> {
+                fn $method(&mut self, rhs: Self) {
+                    *self = *self $op rhs;
+                }
+            }
+        )*
+    };
+}
+
+impl_assign!(
+    AddAssign add_assign +,
+    SubAssign sub_assign -,
+    MulAssign mul_assign *,
+    DivAssign div_assign /
+);
+
+impl<const M: u64> Sum for ModInt<M> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+impl<const M: u64> Product for ModInt<M> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::new(1), Mul::mul)
+    }
+}
+
+impl<const M: u64> Semiring for ModInt<M> {
+    fn zero() -> Self {
+        Self::default()
+    }
+
+    fn one() -> Self {
+        Self::new(1)
+    }
+
+    fn add(self, rhs: Self) -> Self {
+        self + rhs
+    }
+
+    fn mul(self, rhs: Self) -> Self {
+        self * rhs
+    }
+}
+
+impl<const M: u64> fmt::Debug for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl<const M: u64> fmt::Display for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix_exp::{fib_mod, matrix_pow, DynMatrix, Matrix};
+    use crate::number_theory::{mod_inv, pow_mod};
+    use rand::Rng;
+
+    type Mint = ModInt1000000007;
+
+    #[test]
+    fn test_mod_int_ops() {
+        let mut rng = rand::thread_rng();
+        let m = Mint::MODULUS;
+        for _ in 0..1000 {
+            let (x, y) = (rng.gen_range(0..m), rng.gen_range(1..m));
+            let (a, b) = (Mint::new(x), Mint::new(y));
+
+            assert_eq!((a + b).value(), (x + y) % m);
+            assert_eq!((a - b).value(), (x + m - y) % m);
+            assert_eq!((a * b).value(), (x as u128 * y as u128 % m as u128) as u64);
+            assert_eq!(a / b * b, a);
+            assert_eq!(-a + a, Mint::default());
+            assert_eq!(b.inv(), Some(b.pow(m - 2)));
+            assert_eq!(b.inv().map(Mint::value), mod_inv(y, m));
+            assert_eq!(a.pow(y).value(), pow_mod(x, y, m));
+
+            let mut c = a;
+            c += b;
+            c -= b;
+            c *= b;
+            c /= b;
+            assert_eq!(c, a);
+        }
+
+        assert_eq!(Mint::from(-1i64).value(), m - 1);
+        assert_eq!(Mint::from(m as usize + 3), Mint::new(3));
+        assert_eq!(Mint::new(0).inv(), None);
+        assert_eq!(
+            (1..=10u64).map(Mint::new).product::<Mint>(),
+            Mint::new(3628800)
+        );
+        assert_eq!((1..=100u64).map(Mint::new).sum::<Mint>(), Mint::new(5050));
+        assert_eq!(format!("{} {:?}", Mint::new(5), Mint::new(m + 6)), "5 6");
+    }
+
+    #[test]
+    fn test_mod_int_edge_modulus() {
+        // 非质数模数只有和模数互质的数才有逆元
+        type M12 = ModInt<12>;
+        assert_eq!(M12::new(5).inv(), Some(M12::new(5)));
+        assert_eq!(M12::new(4).inv(), None);
+        assert_eq!(M12::new(7) / M12::new(5), M12::new(11));
+
+        // 接近 2^64 的模数, 加法会溢出 u64
+        const BIG: u64 = u64::MAX - 58; // 2^64 - 59 是质数
+        type Big = ModInt<BIG>;
+        let a = Big::new(BIG - 1);
+        assert_eq!((a + a).value(), BIG - 2);
+        assert_eq!((Big::new(1) - a).value(), 2);
+        assert_eq!((a * a).value(), 1);
+        assert_eq!(a.inv(), Some(a));
+
+        assert_eq!(ModInt::<1>::new(5).value(), 0);
+    }
+
+    #[test]
+    fn test_mod_int_matrix() {
+        let x = Matrix::new([[Mint::new(1), Mint::new(1)], [Mint::new(1), Mint::new(0)]]);
+        let n = 1_000_000;
+        assert_eq!(matrix_pow(x, n)[0][1].value(), fib_mod(n, Mint::MODULUS));
+
+        let d = DynMatrix::from_rows(vec![
+            vec![Mint::new(1), Mint::new(1)],
+            vec![Mint::new(1), Mint::new(0)],
+        ]);
+        assert_eq!(d.pow(n)[(0, 1)], matrix_pow(x, n)[0][1]);
+    }
+}
@@ -0,0 +1,450 @@
+//! 从零实现的动态数组
+//!
+//! 参考 [The Rustonomicon - Implementing Vec](https://doc.rust-lang.org/nomicon/vec/vec.html)
+//!
+//! 分成两层:
+//!
+//! - [`RawVec`] 只负责管理一块可以容纳 cap 个 T 的内存: 分配、按两倍扩容和释放, 不关心其中哪些位置已经初始化
+//! - [`MyVec`] 在此之上记录已初始化的长度 len, 负责元素的写入、移动和析构
+//!
+//! 这样 [`IntoIter`] 可以直接接管 RawVec 的内存, 只析构还没有被取出的元素, 最后由 RawVec 释放内存
+//!
+//! 零大小类型(ZST)不需要分配内存, 容量直接视为 `usize::MAX`, 指针始终是悬垂的,
+//! 对它的读写和偏移都是零字节的操作, 所以其余代码不需要特殊处理
+
+use std::alloc::{self, Layout};
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::{Deref, DerefMut};
+use std::ptr::{self, NonNull};
+
+/// 第一次分配时的容量
+const MIN_CAP: usize = 4;
+
+/// 管理一块未初始化的内存
+pub struct RawVec<T> {
+    ptr: NonNull<T>,
+    cap: usize,
+    _marker: PhantomData<T>,
+}
+
+// RawVec 独占它指向的内存, 和 Box<[T]> 一样
+unsafe impl<T: Send> Send for RawVec<T> {}
+unsafe impl<T: Sync> Sync for RawVec<T> {}
+
+impl<T> RawVec<T> {
+    const IS_ZST: bool = mem::size_of::<T>() == 0;
+
+    pub fn new() -> Self {
+        Self {
+            ptr: NonNull::dangling(),
+            cap: if Self::IS_ZST { usize::MAX } else { 0 },
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn with_capacity(cap: usize) -> Self {
+        let mut buf = Self::new();
+        if !Self::IS_ZST && cap > 0 {
+            buf.resize(cap);
+        }
+        buf
+    }
+
+    pub fn ptr(&self) -> *mut T {
+        self.ptr.as_ptr()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    /// 容量翻倍, 第一次分配 [`MIN_CAP`] 个
+    pub fn grow(&mut self) {
+        // ZST 的容量已经是 usize::MAX, 还需要扩容说明长度溢出了
+        assert!(!Self::IS_ZST, "capacity overflow");
+
+        let new_cap = match self.cap {
+            0 => MIN_CAP,
+            cap => cap.checked_mul(2).expect("capacity overflow"),
+        };
+        self.resize(new_cap);
+    }
+
+    fn resize(&mut self, new_cap: usize) {
+        let new_layout = Layout::array::<T>(new_cap).expect("capacity overflow");
+        // 分配的大小不能超过 isize::MAX, 否则指针偏移会溢出
+        assert!(
+            new_layout.size() <= isize::MAX as usize,
+            "allocation too large"
+        );
+
+        let new_ptr = if self.cap == 0 {
+            unsafe { alloc::alloc(new_layout) }
+        } else {
+            let old_layout = Layout::array::<T>(self.cap).unwrap();
+            unsafe { alloc::realloc(self.ptr.as_ptr() as *mut u8, old_layout, new_layout.size()) }
+        };
+
+        self.ptr = match NonNull::new(new_ptr as *mut T) {
+            Some(ptr) => ptr,
+            None => alloc::handle_alloc_error(new_layout),
+        };
+        self.cap = new_cap;
+    }
+}
+
+impl<T> Default for RawVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for RawVec<T> {
+    /// 只释放内存, 元素的析构由持有者负责
+    fn drop(&mut self) {
+        if !Self::IS_ZST && self.cap != 0 {
+            let layout = Layout::array::<T>(self.cap).unwrap();
+            unsafe { alloc::dealloc(self.ptr.as_ptr() as *mut u8, layout) }
+        }
+    }
+}
+
+/// 动态数组, `[0, len)` 之内的元素已经初始化
+pub struct MyVec<T> {
+    buf: RawVec<T>,
+    len: usize,
+}
+
+impl<T> MyVec<T> {
+    pub fn new() -> Self {
+        Self {
+            buf: RawVec::new(),
+            len: 0,
+        }
+    }
+
+    pub fn with_capacity(cap: usize) -> Self {
+        Self {
+            buf: RawVec::with_capacity(cap),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    fn ptr(&self) -> *mut T {
+        self.buf.ptr()
+    }
+
+    /// 在末尾追加元素, 容量不足时翻倍
+    pub fn push(&mut self, value: T) {
+        if self.len == self.capacity() {
+            self.buf.grow();
+        }
+
+        unsafe { ptr::write(self.ptr().add(self.len), value) }
+        self.len += 1;
+    }
+
+    /// 取出末尾的元素, 只是把它读出来并减少长度, 原位置的内存视为未初始化
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+        Some(unsafe { ptr::read(self.ptr().add(self.len)) })
+    }
+
+    /// 在 index 处插入元素, 之后的元素整体向后移动一位
+    pub fn insert(&mut self, index: usize, value: T) {
+        assert!(index <= self.len, "index out of bounds");
+        if self.len == self.capacity() {
+            self.buf.grow();
+        }
+
+        unsafe {
+            // 区间可能重叠, 需要使用 copy 而不是 copy_nonoverlapping
+            ptr::copy(
+                self.ptr().add(index),
+                self.ptr().add(index + 1),
+                self.len - index,
+            );
+            ptr::write(self.ptr().add(index), value);
+        }
+        self.len += 1;
+    }
+
+    /// 删除 index 处的元素, 之后的元素整体向前移动一位
+    pub fn remove(&mut self, index: usize) -> T {
+        assert!(index < self.len, "index out of bounds");
+        unsafe {
+            self.len -= 1;
+            let value = ptr::read(self.ptr().add(index));
+            ptr::copy(
+                self.ptr().add(index + 1),
+                self.ptr().add(index),
+                self.len - index,
+            );
+            value
+        }
+    }
+
+    /// 析构所有元素, 保留容量
+    pub fn clear(&mut self) {
+        let elems: *mut [T] = self.as_mut_slice();
+        // 先把长度置零, 即使某个元素析构时 panic 也不会再次析构
+        self.len = 0;
+        unsafe { ptr::drop_in_place(elems) }
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { std::slice::from_raw_parts(self.ptr(), self.len) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr(), self.len) }
+    }
+}
+
+impl<T> Default for MyVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for MyVec<T> {
+    /// 先析构所有元素, 内存由 RawVec 释放
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T> Deref for MyVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T> DerefMut for MyVec<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T: Clone> Clone for MyVec<T> {
+    fn clone(&self) -> Self {
+        let mut v = MyVec::with_capacity(self.len);
+        v.extend(self.iter().cloned());
+        v
+    }
+}
+
+impl<T: Debug> Debug for MyVec<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> Extend<T> for MyVec<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|value| self.push(value));
+    }
+}
+
+impl<T> FromIterator<T> for MyVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut v = MyVec::new();
+        v.extend(iter);
+        v
+    }
+}
+
+/// 值迭代器, 接管 MyVec 的内存, `[start, end)` 之内是还没有取出的元素
+pub struct IntoIter<T> {
+    buf: RawVec<T>,
+    start: usize,
+    end: usize,
+}
+
+impl<T> IntoIterator for MyVec<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        // 把内存的所有权转移给迭代器, MyVec 本身不能再析构
+        let this = mem::ManuallyDrop::new(self);
+        let buf = unsafe { ptr::read(&this.buf) };
+        IntoIter {
+            buf,
+            start: 0,
+            end: this.len,
+        }
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.start == self.end {
+            return None;
+        }
+
+        self.start += 1;
+        Some(unsafe { ptr::read(self.buf.ptr().add(self.start - 1)) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.start;
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.start == self.end {
+            return None;
+        }
+
+        self.end -= 1;
+        Some(unsafe { ptr::read(self.buf.ptr().add(self.end)) })
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> Drop for IntoIter<T> {
+    /// 只析构还没有取出的元素, 内存由 RawVec 释放
+    fn drop(&mut self) {
+        let rest = ptr::slice_from_raw_parts_mut(
+            unsafe { self.buf.ptr().add(self.start) },
+            self.end - self.start,
+        );
+        self.start = self.end;
+        unsafe { ptr::drop_in_place(rest) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_my_vec() {
+        let mut v = MyVec::new();
+        assert!(v.is_empty());
+        assert_eq!(v.capacity(), 0);
+        assert_eq!(v.pop(), None);
+
+        // 按两倍扩容
+        let mut caps = vec![];
+        for i in 0..20 {
+            v.push(i);
+            caps.push(v.capacity());
+        }
+        caps.dedup();
+        assert_eq!(caps, vec![4, 8, 16, 32]);
+        assert_eq!(v.len(), 20);
+        assert_eq!(v[..5], [0, 1, 2, 3, 4]);
+
+        v.insert(0, -1);
+        v.insert(21, 20);
+        v.insert(10, 100);
+        assert_eq!(v.len(), 23);
+        assert_eq!(v[0], -1);
+        assert_eq!(v[10], 100);
+        assert_eq!(v[22], 20);
+
+        assert_eq!(v.remove(10), 100);
+        assert_eq!(v.remove(0), -1);
+        assert_eq!(v.pop(), Some(20));
+        assert!(v.iter().copied().eq(0..20));
+
+        // 通过 DerefMut 使用切片的方法
+        v.reverse();
+        v.sort_unstable();
+        assert!(v.iter().copied().eq(0..20));
+
+        let w = v.clone();
+        v.clear();
+        assert!(v.is_empty());
+        assert_eq!(v.capacity(), 32);
+        assert_eq!(w.len(), 20);
+        assert_eq!(format!("{:?}", MyVec::from_iter([1, 2])), "[1, 2]");
+    }
+
+    #[test]
+    fn test_my_vec_into_iter() {
+        let v: MyVec<_> = (0..10).map(|i| i.to_string()).collect();
+        let mut it = v.into_iter();
+        assert_eq!(it.len(), 10);
+        assert_eq!(it.next().as_deref(), Some("0"));
+        assert_eq!(it.next_back().as_deref(), Some("9"));
+        assert_eq!(it.len(), 8);
+        assert!(it.rev().eq((1..9).rev().map(|i| i.to_string())));
+    }
+
+    #[test]
+    fn test_my_vec_drop() {
+        let rc = Rc::new(());
+        let mut v = MyVec::new();
+        for _ in 0..10 {
+            v.push(rc.clone());
+        }
+        assert_eq!(Rc::strong_count(&rc), 11);
+
+        drop(v.pop());
+        drop(v.remove(3));
+        v.insert(0, rc.clone());
+        assert_eq!(Rc::strong_count(&rc), 10);
+
+        // 只取出一部分, 剩下的由迭代器析构
+        let mut it = v.into_iter();
+        drop(it.next());
+        drop(it.next_back());
+        assert_eq!(Rc::strong_count(&rc), 8);
+        drop(it);
+        assert_eq!(Rc::strong_count(&rc), 1);
+
+        let v: MyVec<_> = (0..5).map(|_| rc.clone()).collect();
+        drop(v);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[test]
+    fn test_my_vec_zst() {
+        let mut v = MyVec::new();
+        assert_eq!(v.capacity(), usize::MAX);
+        for _ in 0..100 {
+            v.push(());
+        }
+        v.insert(50, ());
+        assert_eq!(v.remove(0), ());
+        assert_eq!(v.len(), 100);
+        assert_eq!(v.into_iter().count(), 100);
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bounds")]
+    fn test_my_vec_insert_out_of_bounds() {
+        let mut v = MyVec::new();
+        v.push(1);
+        v.insert(2, 2);
+    }
+}
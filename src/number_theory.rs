@@ -0,0 +1,261 @@
+//! 数论基础
+//!
+//! - [OI Wiki - 筛法](https://oi-wiki.org/math/number-theory/sieve/)
+//! - [OI Wiki - 最大公约数](https://oi-wiki.org/math/number-theory/gcd/)
+//! - [OI Wiki - 欧拉函数](https://oi-wiki.org/math/number-theory/euler-totient/)
+//! - [OI Wiki - 乘法逆元](https://oi-wiki.org/math/number-theory/inverse/)
+//!
+//! 埃拉托斯特尼筛法: 从 2 开始, 每找到一个质数 p 就把 p², p² + p, p² + 2p... 都标记为合数
+//! 小于 p² 的 p 的倍数已经被更小的质因子标记过了, 复杂度 O(n loglogn)
+//! 这里用 [`BitSet`] 记录合数, 每个数只占一个比特
+//!
+//! 扩展欧几里得算法在求 gcd(a, b) 的同时求出 ax + by = gcd(a, b) 的一组解:
+//! 递归得到 bx' + (a mod b)y' = g 后, 因为 a mod b = a - ⌊a/b⌋b, 所以 x = y', y = x' - ⌊a/b⌋y'
+//! 当 gcd(a, m) = 1 时 ax ≡ 1 (mod m) 的解 x 就是 a 模 m 的乘法逆元
+//!
+//! 欧拉函数 φ(n) 是 1..=n 中与 n 互质的数的个数, 如果 n = ∏ pᵢ^kᵢ 则 φ(n) = n ∏ (1 - 1/pᵢ)
+
+use crate::bitset::BitSet;
+
+/// 基于位图的埃拉托斯特尼筛
+pub struct Sieve {
+    n: usize,
+    composite: BitSet,
+}
+
+impl Sieve {
+    /// 筛出 `0..=n` 中的所有质数
+    pub fn new(n: usize) -> Self {
+        let mut composite = BitSet::new(n + 1);
+        composite.set(0);
+        composite.set(1);
+
+        let mut p = 2;
+        while p * p <= n {
+            if !composite.test(p) {
+                for m in (p * p..=n).step_by(p) {
+                    composite.set(m);
+                }
+            }
+            p += 1;
+        }
+
+        Self { n, composite }
+    }
+
+    /// 筛的上界
+    pub fn limit(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_prime(&self, x: usize) -> bool {
+        assert!(x <= self.n, "{x} exceeds sieve limit {}", self.n);
+        !self.composite.test(x)
+    }
+
+    /// 按从小到大的顺序遍历所有质数
+    pub fn primes(&self) -> impl Iterator<Item = usize> + '_ {
+        (2..=self.n).filter(|&x| !self.composite.test(x))
+    }
+
+    /// 用筛出的质数分解质因数, 要求 x 不超过上界的平方
+    pub fn factorize(&self, mut x: u64) -> Vec<(u64, u32)> {
+        assert!(
+            x <= (self.n as u64).saturating_mul(self.n as u64),
+            "{x} exceeds sieve limit squared"
+        );
+
+        let mut res = vec![];
+        for p in self.primes().map(|p| p as u64) {
+            if p * p > x {
+                break;
+            }
+            let mut k = 0;
+            while x.is_multiple_of(p) {
+                x /= p;
+                k += 1;
+            }
+            if k > 0 {
+                res.push((p, k));
+            }
+        }
+
+        if x > 1 {
+            res.push((x, 1));
+        }
+        res
+    }
+}
+
+/// 试除法分解质因数, 返回从小到大的 `(质因子, 次数)`
+///
+/// 复杂度 O(√n), n 是接近 `u64::MAX` 的大质数时需要试除约 2^32 次
+pub fn factorize(mut n: u64) -> Vec<(u64, u32)> {
+    let mut res = vec![];
+    let mut p = 2;
+    // p 可能增长到 2^32, 写成除法避免 p * p 溢出
+    while p <= n / p {
+        let mut k = 0;
+        while n.is_multiple_of(p) {
+            n /= p;
+            k += 1;
+        }
+        if k > 0 {
+            res.push((p, k));
+        }
+        p += 1;
+    }
+
+    if n > 1 {
+        res.push((n, 1));
+    }
+    res
+}
+
+/// 欧拉函数
+pub fn phi(n: u64) -> u64 {
+    factorize(n)
+        .into_iter()
+        .fold(n, |acc, (p, _)| acc / p * (p - 1))
+}
+
+pub fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+pub fn lcm(a: u64, b: u64) -> u64 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    a / gcd(a, b) * b
+}
+
+/// 扩展欧几里得算法, 返回 `(g, x, y)` 满足 `ax + by = g = gcd(a, b)`
+pub fn ext_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    if b == 0 {
+        return (a, 1, 0);
+    }
+
+    let (g, x, y) = ext_gcd(b, a % b);
+    (g, y, x - a / b * y)
+}
+
+/// a 模 m 的乘法逆元, a 和 m 不互质或 m = 0 时不存在
+///
+/// m 可能超过 `i64::MAX`, 所以在 i128 上迭代地做扩展欧几里得, 只需要维护 a 的系数
+pub fn mod_inv(a: u64, m: u64) -> Option<u64> {
+    if m == 0 {
+        return None;
+    }
+
+    let (mut r0, mut r1) = (m as i128, (a % m) as i128);
+    let (mut x0, mut x1) = (0i128, 1i128);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (x0, x1) = (x1, x0 - q * x1);
+    }
+
+    (r0 == 1).then(|| x0.rem_euclid(m as i128) as u64)
+}
+
+/// 快速幂计算 base^exp mod m, 中间结果使用 u128 避免溢出
+pub fn pow_mod(base: u64, mut exp: u64, m: u64) -> u64 {
+    let m = m as u128;
+    let mut base = base as u128 % m;
+    let mut res = 1 % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            res = res * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+    res as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sieve() {
+        let sieve = Sieve::new(100);
+        let primes: Vec<_> = sieve.primes().collect();
+        assert_eq!(primes.len(), 25);
+        assert_eq!(&primes[..6], &[2, 3, 5, 7, 11, 13]);
+        assert!(sieve.is_prime(97));
+        assert!(!sieve.is_prime(91));
+        assert!(!sieve.is_prime(1));
+
+        let sieve = Sieve::new(100000);
+        assert_eq!(sieve.primes().count(), 9592);
+        assert_eq!(sieve.factorize(360), vec![(2, 3), (3, 2), (5, 1)]);
+        assert_eq!(sieve.factorize(999999937 * 2), vec![(2, 1), (999999937, 1)]);
+    }
+
+    #[test]
+    fn test_number_theory() {
+        assert_eq!(factorize(1), vec![]);
+        assert_eq!(factorize(84), vec![(2, 2), (3, 1), (7, 1)]);
+        assert_eq!(
+            factorize(u64::MAX),
+            vec![
+                (3, 1),
+                (5, 1),
+                (17, 1),
+                (257, 1),
+                (641, 1),
+                (65537, 1),
+                (6700417, 1)
+            ]
+        );
+        // 大于 2^32 的质因子最后剩下
+        assert_eq!(
+            factorize(6 * 1_000_000_000_039),
+            vec![(2, 1), (3, 1), (1_000_000_000_039, 1)]
+        );
+        for n in 1..500u64 {
+            let expect = (1..=n).filter(|&k| gcd(n, k) == 1).count() as u64;
+            assert_eq!(phi(n), expect);
+        }
+
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(0, 6), 0);
+
+        let (g, x, y) = ext_gcd(240, 46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+
+        let m = 1_000_000_007;
+        assert_eq!(mod_inv(3, m), Some(333333336));
+        assert_eq!(mod_inv(4, 8), None);
+        assert_eq!(mod_inv(3, 0), None);
+        assert_eq!(mod_inv(0, 1), Some(0));
+
+        // 模数超过 i64::MAX
+        for m in [u64::MAX - 58, 1 << 63, u64::MAX] {
+            for a in [2, 3, 7, 12345678901234567, m - 1] {
+                match mod_inv(a, m) {
+                    Some(x) => assert_eq!(a as u128 * x as u128 % m as u128, 1),
+                    None => assert_ne!(gcd(a, m), 1),
+                }
+            }
+        }
+        assert_eq!(mod_inv(2, u64::MAX - 58), Some((u64::MAX - 58) / 2 + 1));
+        for a in 1..100 {
+            assert_eq!(a * mod_inv(a, 101).unwrap() % 101, 1);
+        }
+
+        assert_eq!(pow_mod(2, 10, 1000), 24);
+        assert_eq!(pow_mod(3, 0, 1), 0);
+        // 费马小定理
+        assert_eq!(pow_mod(123456789, m - 1, m), 1);
+        assert_eq!(pow_mod(u64::MAX, u64::MAX, u64::MAX - 1), 1);
+    }
+}
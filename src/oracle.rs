@@ -0,0 +1,268 @@
+//! 有序容器差分测试
+//!
+//! 对多种有序容器同时执行相同的随机操作序列(插入/删除/查找)
+//! 以 [`std::collections::BTreeSet`] 作为参照, 检查每一步的可观察结果是否完全一致
+//!
+//! 操作序列由固定的种子生成, 出错时会打印种子、步数和操作方便复现  
+//! 树堆和跳表的结构也依赖随机数, 这里同样传入固定种子的 [`crate::rng`] 生成器, 保证每次运行的结构相同
+
+use std::collections::BTreeSet;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::avl_tree::AVLTree;
+use crate::b_tree::BTree;
+use crate::llrb_tree::LLRBTree;
+use crate::rb_tree::RBTree;
+use crate::skip_list::SkipList;
+use crate::treap::Treap;
+
+/// 随机操作
+#[derive(Debug, Clone, Copy)]
+pub enum Op {
+    Insert(i32),
+    Delete(i32),
+    Find(i32),
+}
+
+/// 统一各个容器的集合接口
+///
+/// 插入返回值是否为新值, 删除返回值是否存在
+pub trait OrderedSet {
+    fn insert(&mut self, value: i32) -> bool;
+    fn delete(&mut self, value: &i32) -> bool;
+    fn contains(&self, value: &i32) -> bool;
+
+    /// 没有实现 `len` 的容器返回 `None` 跳过检查
+    fn len(&self) -> Option<usize> {
+        None
+    }
+}
+
+impl OrderedSet for BTreeSet<i32> {
+    fn insert(&mut self, value: i32) -> bool {
+        BTreeSet::insert(self, value)
+    }
+
+    fn delete(&mut self, value: &i32) -> bool {
+        self.remove(value)
+    }
+
+    fn contains(&self, value: &i32) -> bool {
+        BTreeSet::contains(self, value)
+    }
+
+    fn len(&self) -> Option<usize> {
+        Some(BTreeSet::len(self))
+    }
+}
+
+impl OrderedSet for RBTree<i32> {
+    fn insert(&mut self, value: i32) -> bool {
+        RBTree::insert(self, value).is_none()
+    }
+
+    fn delete(&mut self, value: &i32) -> bool {
+        RBTree::delete(self, value).is_some()
+    }
+
+    fn contains(&self, value: &i32) -> bool {
+        RBTree::contains(self, value)
+    }
+
+    fn len(&self) -> Option<usize> {
+        Some(RBTree::len(self))
+    }
+}
+
+impl OrderedSet for LLRBTree<i32> {
+    fn insert(&mut self, value: i32) -> bool {
+        LLRBTree::insert(self, value).is_none()
+    }
+
+    fn delete(&mut self, value: &i32) -> bool {
+        LLRBTree::delete(self, value).is_some()
+    }
+
+    fn contains(&self, value: &i32) -> bool {
+        LLRBTree::contains(self, value)
+    }
+
+    fn len(&self) -> Option<usize> {
+        Some(LLRBTree::len(self))
+    }
+}
+
+impl OrderedSet for AVLTree<i32> {
+    fn insert(&mut self, value: i32) -> bool {
+        AVLTree::insert(self, value)
+    }
+
+    fn delete(&mut self, value: &i32) -> bool {
+        AVLTree::delete(self, value).is_some()
+    }
+
+    fn contains(&self, value: &i32) -> bool {
+        AVLTree::contains(self, value)
+    }
+
+    fn len(&self) -> Option<usize> {
+        Some(AVLTree::len(self))
+    }
+}
+
+impl<R: Rng> OrderedSet for Treap<i32, R> {
+    fn insert(&mut self, value: i32) -> bool {
+        let exist = Treap::contains(self, &value);
+        Treap::insert(self, value);
+        !exist
+    }
+
+    fn delete(&mut self, value: &i32) -> bool {
+        Treap::delete(self, value).is_some()
+    }
+
+    fn contains(&self, value: &i32) -> bool {
+        Treap::contains(self, value)
+    }
+
+    fn len(&self) -> Option<usize> {
+        Some(Treap::len(self))
+    }
+}
+
+impl<const N: usize, R: Rng> OrderedSet for SkipList<N, i32, (), R> {
+    fn insert(&mut self, value: i32) -> bool {
+        SkipList::insert(self, value, ()).is_none()
+    }
+
+    fn delete(&mut self, value: &i32) -> bool {
+        SkipList::delete(self, value).is_some()
+    }
+
+    fn contains(&self, value: &i32) -> bool {
+        SkipList::find(self, value).is_some()
+    }
+
+    fn len(&self) -> Option<usize> {
+        Some(SkipList::len(self))
+    }
+}
+
+impl OrderedSet for BTree<i32, ()> {
+    fn insert(&mut self, value: i32) -> bool {
+        BTree::insert(self, (value, ())).is_none()
+    }
+
+    fn delete(&mut self, value: &i32) -> bool {
+        BTree::delete(self, value).is_some()
+    }
+
+    fn contains(&self, value: &i32) -> bool {
+        BTree::get(self, value).is_some()
+    }
+
+    fn len(&self) -> Option<usize> {
+        Some(BTree::len(self))
+    }
+}
+
+/// 根据种子生成 n 个操作, key 的取值范围是 `0..range`
+///
+/// 较小的 range 可以让删除和重复插入更频繁地命中已有的 key
+pub fn gen_ops(seed: u64, n: usize, range: i32) -> Vec<Op> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..n)
+        .map(|_| {
+            let key = rng.gen_range(0..range);
+            match rng.gen_range(0..10) {
+                0..=4 => Op::Insert(key),
+                5..=7 => Op::Delete(key),
+                _ => Op::Find(key),
+            }
+        })
+        .collect()
+}
+
+/// 在被测容器和 `BTreeSet` 上同时执行操作序列并逐步对比结果
+pub fn check<S: OrderedSet>(name: &str, set: &mut S, ops: &[Op]) {
+    let mut oracle = BTreeSet::new();
+    for (step, &op) in ops.iter().enumerate() {
+        let (expect, actual) = match op {
+            Op::Insert(v) => (OrderedSet::insert(&mut oracle, v), set.insert(v)),
+            Op::Delete(v) => (OrderedSet::delete(&mut oracle, &v), set.delete(&v)),
+            Op::Find(v) => (OrderedSet::contains(&oracle, &v), set.contains(&v)),
+        };
+
+        assert_eq!(expect, actual, "{name}: step {step} {op:?}");
+        if let Some(len) = set.len() {
+            assert_eq!(oracle.len(), len, "{name}: step {step} {op:?} len");
+        }
+    }
+
+    // 最后检查参照集合中的每个值以及范围附近的值是否一致
+    for v in -1..=oracle.last().copied().unwrap_or_default() + 1 {
+        let expect = OrderedSet::contains(&oracle, &v);
+        assert_eq!(expect, set.contains(&v), "{name}: final contains {v}");
+    }
+}
+
+/// 用多组种子和 key 范围对同一种容器进行差分测试
+pub fn fuzz<S: OrderedSet, F: Fn() -> S>(name: &str, new: F) {
+    for seed in 0..32 {
+        for range in [8, 64, 1024] {
+            let ops = gen_ops(seed, 2000, range);
+            check(
+                &format!("{name}(seed={seed}, range={range})"),
+                &mut new(),
+                &ops,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::{Pcg32, XorShift64Star};
+
+    #[test]
+    fn test_oracle_btree_set() {
+        fuzz("BTreeSet", BTreeSet::new);
+    }
+
+    #[test]
+    fn test_oracle_rb_tree() {
+        fuzz("RBTree", RBTree::new);
+    }
+
+    #[test]
+    fn test_oracle_llrb_tree() {
+        fuzz("LLRBTree", LLRBTree::new);
+    }
+
+    #[test]
+    fn test_oracle_avl_tree() {
+        fuzz("AVLTree", AVLTree::new);
+    }
+
+    #[test]
+    fn test_oracle_treap() {
+        fuzz("Treap", || Treap::with_rng(Pcg32::seed_from_u64(0x5eed)));
+    }
+
+    #[test]
+    fn test_oracle_skip_list() {
+        fuzz("SkipList", || {
+            SkipList::<16, i32, (), _>::with_rng(XorShift64Star::seed_from_u64(0x5eed))
+        });
+    }
+
+    #[test]
+    fn test_oracle_b_tree() {
+        for order in [3, 4, 5, 8] {
+            fuzz(&format!("BTree(order={order})"), || BTree::new(order));
+        }
+    }
+}
@@ -0,0 +1,284 @@
+//! 持久化向量
+//!
+//! 参考 Clojure 的 PersistentVector, 使用 32 叉的 trie 存储元素, 下标的每 5 个比特决定一层的分支
+//!
+//! 所有修改操作都不改变原来的版本, 而是复制从根到目标叶子路径上的 O(log32 n) 个节点,
+//! 其余子树通过 `Rc` 在新旧版本之间共享, 参考 [`linked_list_rc`](crate::linked_list_rc) 的持久化链表
+//!
+//! 这里省略了 Clojure 实现中单独存放最后一个叶子的 tail 优化
+
+use std::rc::Rc;
+
+const BITS: u32 = 5;
+const WIDTH: usize = 1 << BITS;
+const MASK: usize = WIDTH - 1;
+
+enum Node<T> {
+    Branch(Vec<Rc<Node<T>>>),
+    Leaf(Vec<T>),
+}
+
+/// 持久化向量, 克隆只需要增加根节点的引用计数
+pub struct PersistentVec<T> {
+    root: Option<Rc<Node<T>>>,
+    len: usize,
+    /// 根节点所在层对应的下标偏移, 叶子层为 0
+    shift: u32,
+}
+
+impl<T> Clone for PersistentVec<T> {
+    fn clone(&self) -> Self {
+        Self {
+            root: self.root.clone(),
+            len: self.len,
+            shift: self.shift,
+        }
+    }
+}
+
+impl<T> Default for PersistentVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> PersistentVec<T> {
+    pub fn new() -> Self {
+        Self {
+            root: None,
+            len: 0,
+            shift: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len {
+            return None;
+        }
+
+        let mut node = self.root.as_deref()?;
+        let mut shift = self.shift;
+        loop {
+            match node {
+                Node::Branch(children) => {
+                    node = &children[(index >> shift) & MASK];
+                    shift -= BITS;
+                }
+                Node::Leaf(elems) => return elems.get(index & MASK),
+            }
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            stack: self
+                .root
+                .iter()
+                .map(|root| std::slice::from_ref(root).iter())
+                .collect(),
+            leaf: [].iter(),
+        }
+    }
+}
+
+impl<T: Clone> PersistentVec<T> {
+    /// 返回在末尾追加了 value 的新版本
+    pub fn push(&self, value: T) -> Self {
+        let (root, shift) = match &self.root {
+            None => (None, 0),
+            // 当前的树已满, 增加一层, 原来的根成为新根的第一个子节点
+            Some(root) if self.len == WIDTH << self.shift => (
+                Some(Rc::new(Node::Branch(vec![root.clone()]))),
+                self.shift + BITS,
+            ),
+            Some(root) => (Some(root.clone()), self.shift),
+        };
+
+        Self {
+            root: Some(Self::push_in(root.as_ref(), shift, self.len, value)),
+            len: self.len + 1,
+            shift,
+        }
+    }
+
+    /// 复制路径并把 value 放到下标 index 处, node 为 None 时创建新的子树
+    fn push_in(node: Option<&Rc<Node<T>>>, shift: u32, index: usize, value: T) -> Rc<Node<T>> {
+        if shift == 0 {
+            let mut elems = match node.map(|node| &**node) {
+                Some(Node::Leaf(elems)) => elems.clone(),
+                _ => Vec::with_capacity(WIDTH),
+            };
+            elems.push(value);
+            return Rc::new(Node::Leaf(elems));
+        }
+
+        let mut children = match node.map(|node| &**node) {
+            Some(Node::Branch(children)) => children.clone(),
+            _ => Vec::with_capacity(WIDTH),
+        };
+        let i = (index >> shift) & MASK;
+        if i < children.len() {
+            children[i] = Self::push_in(Some(&children[i]), shift - BITS, index, value);
+        } else {
+            children.push(Self::push_in(None, shift - BITS, index, value));
+        }
+        Rc::new(Node::Branch(children))
+    }
+
+    /// 返回把下标 index 处的元素替换为 value 的新版本
+    ///
+    /// 下标越界时 panic
+    pub fn update(&self, index: usize, value: T) -> Self {
+        assert!(index < self.len, "index out of bounds");
+        let root = self.root.as_ref().unwrap();
+        Self {
+            root: Some(Self::update_in(root, self.shift, index, value)),
+            len: self.len,
+            shift: self.shift,
+        }
+    }
+
+    fn update_in(node: &Rc<Node<T>>, shift: u32, index: usize, value: T) -> Rc<Node<T>> {
+        match &**node {
+            Node::Branch(children) => {
+                let mut children = children.clone();
+                let i = (index >> shift) & MASK;
+                children[i] = Self::update_in(&children[i], shift - BITS, index, value);
+                Rc::new(Node::Branch(children))
+            }
+            Node::Leaf(elems) => {
+                let mut elems = elems.clone();
+                elems[index & MASK] = value;
+                Rc::new(Node::Leaf(elems))
+            }
+        }
+    }
+}
+
+impl<T: Clone> FromIterator<T> for PersistentVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        iter.into_iter()
+            .fold(PersistentVec::new(), |v, value| v.push(value))
+    }
+}
+
+/// 引用迭代器, 按深度优先的顺序依次访问每个叶子
+pub struct Iter<'a, T> {
+    stack: Vec<std::slice::Iter<'a, Rc<Node<T>>>>,
+    leaf: std::slice::Iter<'a, T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(elem) = self.leaf.next() {
+                return Some(elem);
+            }
+
+            let node = loop {
+                match self.stack.last_mut()?.next() {
+                    Some(node) => break node,
+                    None => drop(self.stack.pop()),
+                }
+            };
+            match &**node {
+                Node::Branch(children) => self.stack.push(children.iter()),
+                Node::Leaf(elems) => self.leaf = elems.iter(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_persistent_vec() {
+        let empty = PersistentVec::new();
+        assert!(empty.is_empty());
+        assert_eq!(empty.get(0), None);
+        assert_eq!(empty.iter().next(), None);
+
+        // 跨过 32 和 32 * 32 两个增加层数的边界
+        let n = WIDTH * WIDTH + 100;
+        let mut versions = vec![empty];
+        for i in 0..n {
+            let v = versions.last().unwrap().push(i);
+            versions.push(v);
+        }
+
+        for (len, v) in versions.iter().enumerate().step_by(97) {
+            assert_eq!(v.len(), len);
+            assert!(v.iter().copied().eq(0..len));
+            assert_eq!(v.get(len), None);
+            if len > 0 {
+                assert_eq!(v.get(len - 1), Some(&(len - 1)));
+            }
+        }
+
+        let v = versions.pop().unwrap();
+        let w = v.update(0, 100).update(n - 1, 200).update(500, 300);
+        assert_eq!(w.get(0), Some(&100));
+        assert_eq!(w.get(500), Some(&300));
+        assert_eq!(w.get(n - 1), Some(&200));
+        // 旧版本不受影响
+        assert!(v.iter().copied().eq(0..n));
+
+        let v: PersistentVec<_> = "persistent".chars().collect();
+        assert_eq!(v.iter().collect::<String>(), "persistent");
+    }
+
+    #[test]
+    fn test_persistent_vec_sharing() {
+        let n = 1000;
+        let v: PersistentVec<_> = (0..n).map(Rc::new).collect();
+        let elems: Vec<_> = v.iter().cloned().collect();
+        let extra =
+            |elems: &[Rc<usize>]| -> usize { elems.iter().map(|x| Rc::strong_count(x) - 2).sum() };
+        assert_eq!(extra(&elems), 0);
+
+        // 克隆不复制任何节点
+        let cloned = v.clone();
+        assert_eq!(extra(&elems), 0);
+        drop(cloned);
+
+        // 修改只复制目标所在的叶子, 叶子中其余 31 个元素被新旧两个叶子共同持有
+        let w = v.update(500, Rc::new(0));
+        assert_eq!(extra(&elems), WIDTH - 1);
+        assert_eq!(Rc::strong_count(&elems[500]), 2);
+
+        // 追加也只复制最后一个叶子, 其中有 1000 % 32 个元素
+        let u = v.push(Rc::new(n));
+        assert_eq!(extra(&elems), WIDTH - 1 + n % WIDTH);
+
+        // 不在路径上的子树在版本之间共享
+        let (Node::Branch(a), Node::Branch(b)) =
+            (&**v.root.as_ref().unwrap(), &**w.root.as_ref().unwrap())
+        else {
+            panic!("expected branches");
+        };
+        assert!(!Rc::ptr_eq(&a[500 / WIDTH], &b[500 / WIDTH]));
+        assert!(Rc::ptr_eq(&a[0], &b[0]));
+
+        drop((v, w, u));
+        assert!(elems.iter().all(|x| Rc::strong_count(x) == 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bounds")]
+    fn test_persistent_vec_update_out_of_bounds() {
+        let v: PersistentVec<_> = (0..10).collect();
+        v.update(10, 0);
+    }
+}
@@ -0,0 +1,331 @@
+//! 稀疏多项式
+//!
+//! - [wikipedia - Polynomial arithmetic](https://en.wikipedia.org/wiki/Polynomial_arithmetic)
+//!
+//! 链表的经典应用: 用 [`crate::linked_list::LinkedList`] 按指数从高到低保存多项式中系数非 0 的项,
+//! 对于 `x^1000 + 1` 这种稀疏多项式只需要两个节点, 而用数组保存系数则需要 1001 个位置
+//!
+//! 加法是两个有序链表的归并, 用 [`crate::linked_list::CursorMut`] 在结果链表上只向后走一遍:
+//!
+//! - 游标上的指数比待加入的项大: 向后移动游标
+//! - 指数相等: 系数相加, 相加之后为 0 则用 `remove_current` 删掉这一项, 游标自动指向下一项
+//! - 指数更小或者已经走到 ghost 节点: 用 `insert_before` 插入到游标前面, 再把游标移动到新插入的项上
+//!
+//! 因为待加入的项同样按指数降序排列, 游标除了回到刚插入的项之外不会回退, 复杂度 O(n + m)
+//!
+//! 乘法把一个多项式的每一项分别乘以另一个多项式, 依次累加到结果上, 复杂度 O(n·m·(n + m))
+//! 稠密多项式的乘法可以使用 [`crate::fft`] 做到 O(n logn)
+
+use std::fmt;
+use std::ops::{Add, Mul};
+
+use crate::linked_list::{CursorMut, LinkedList};
+
+/// 多项式中的一项 `coef·x^exp`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Term {
+    pub coef: i64,
+    pub exp: u32,
+}
+
+/// 稀疏多项式
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Polynomial {
+    /// 按指数严格降序排列, 不包含系数为 0 的项
+    terms: LinkedList<Term>,
+}
+
+/// 把按指数降序排列的若干项累加到游标所在的链表上, 游标从 ghost 节点开始
+fn merge_terms(cursor: &mut CursorMut<Term>, terms: impl Iterator<Item = Term>) {
+    cursor.move_next();
+    for Term { coef, exp } in terms {
+        if coef == 0 {
+            continue;
+        }
+
+        while cursor.current().is_some_and(|t| t.exp > exp) {
+            cursor.move_next();
+        }
+
+        match cursor.current() {
+            Some(t) if t.exp == exp => {
+                t.coef += coef;
+                if t.coef == 0 {
+                    cursor.remove_current();
+                }
+            }
+            _ => {
+                // 游标回到新插入的项上, 后面指数相同的项可以直接合并
+                cursor.insert_before(Term { coef, exp });
+                cursor.move_prev();
+            }
+        }
+    }
+}
+
+impl Polynomial {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 常数多项式
+    pub fn constant(coef: i64) -> Self {
+        Self::monomial(coef, 0)
+    }
+
+    /// 单项式 `coef·x^exp`
+    pub fn monomial(coef: i64, exp: u32) -> Self {
+        let mut p = Self::new();
+        if coef != 0 {
+            p.terms.push_back(Term { coef, exp });
+        }
+
+        p
+    }
+
+    /// 是否零多项式
+    pub fn is_zero(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// 非 0 项的数量
+    pub fn len(&self) -> usize {
+        self.terms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.is_zero()
+    }
+
+    /// 最高次数, 零多项式返回 None
+    pub fn degree(&self) -> Option<u32> {
+        self.terms.front().map(|t| t.exp)
+    }
+
+    /// 指数为 exp 的项的系数
+    pub fn coef(&self, exp: u32) -> i64 {
+        self.terms
+            .iter()
+            .take_while(|t| t.exp >= exp)
+            .find(|t| t.exp == exp)
+            .map_or(0, |t| t.coef)
+    }
+
+    /// 按指数从高到低遍历所有非 0 项
+    pub fn terms(&self) -> impl Iterator<Item = &Term> {
+        self.terms.iter()
+    }
+
+    /// 加上一项 `coef·x^exp`, 需要从头找到插入位置, O(n)
+    pub fn add_term(&mut self, coef: i64, exp: u32) {
+        merge_terms(
+            &mut self.terms.cursor_mut(),
+            Some(Term { coef, exp }).into_iter(),
+        );
+    }
+
+    /// 在 x 处求值, 按指数从高到低使用秦九韶算法, 跳过的次数用快速幂补上
+    pub fn eval(&self, x: i64) -> i64 {
+        let mut acc = 0i64;
+        let mut iter = self.terms.iter().peekable();
+        while let Some(t) = iter.next() {
+            acc += t.coef;
+            let next_exp = iter.peek().map_or(0, |n| n.exp);
+            acc *= x.pow(t.exp - next_exp);
+        }
+
+        acc
+    }
+
+    /// 求导
+    pub fn derivative(&self) -> Self {
+        let terms = self
+            .terms
+            .iter()
+            .filter(|t| t.exp > 0)
+            .map(|t| Term {
+                coef: t.coef * t.exp as i64,
+                exp: t.exp - 1,
+            })
+            .collect();
+
+        Self { terms }
+    }
+}
+
+impl FromIterator<(i64, u32)> for Polynomial {
+    /// 从任意顺序的 (系数, 指数) 构建多项式, 相同指数的项会合并
+    fn from_iter<I: IntoIterator<Item = (i64, u32)>>(iter: I) -> Self {
+        let mut terms: Vec<_> = iter
+            .into_iter()
+            .map(|(coef, exp)| Term { coef, exp })
+            .collect();
+        terms.sort_by_key(|t| std::cmp::Reverse(t.exp));
+
+        let mut p = Self::new();
+        merge_terms(&mut p.terms.cursor_mut(), terms.into_iter());
+        p
+    }
+}
+
+impl Add<&Polynomial> for &Polynomial {
+    type Output = Polynomial;
+
+    fn add(self, rhs: &Polynomial) -> Polynomial {
+        let mut res = self.clone();
+        merge_terms(&mut res.terms.cursor_mut(), rhs.terms.iter().copied());
+        res
+    }
+}
+
+impl Mul<&Polynomial> for &Polynomial {
+    type Output = Polynomial;
+
+    fn mul(self, rhs: &Polynomial) -> Polynomial {
+        let mut res = Polynomial::new();
+        for a in self.terms.iter() {
+            // 乘以同一项之后指数依然保持降序
+            let scaled = rhs.terms.iter().map(|b| Term {
+                coef: a.coef * b.coef,
+                exp: a.exp + b.exp,
+            });
+            merge_terms(&mut res.terms.cursor_mut(), scaled);
+        }
+
+        res
+    }
+}
+
+impl fmt::Display for Polynomial {
+    /// 按指数从高到低输出, 例如 `3x^2 - x + 1`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        for (i, t) in self.terms.iter().enumerate() {
+            let abs = t.coef.unsigned_abs();
+            match (i, t.coef < 0) {
+                (0, true) => write!(f, "-")?,
+                (0, false) => {}
+                (_, true) => write!(f, " - ")?,
+                (_, false) => write!(f, " + ")?,
+            }
+
+            if abs != 1 || t.exp == 0 {
+                write!(f, "{}", abs)?;
+            }
+            match t.exp {
+                0 => {}
+                1 => write!(f, "x")?,
+                e => write!(f, "x^{}", e)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    /// 稠密系数表示, 下标为指数
+    fn dense(p: &Polynomial) -> Vec<i64> {
+        let mut v = vec![0; p.degree().map_or(0, |d| d as usize + 1)];
+        for t in p.terms() {
+            v[t.exp as usize] = t.coef;
+        }
+        v
+    }
+
+    fn random_poly(rng: &mut impl Rng) -> Polynomial {
+        (0..rng.gen_range(0..8))
+            .map(|_| (rng.gen_range(-3..=3), rng.gen_range(0..10)))
+            .collect()
+    }
+
+    #[test]
+    fn test_polynomial_basic() {
+        // (x + 1)(x - 1) = x^2 - 1
+        let a: Polynomial = [(1, 1), (1, 0)].into_iter().collect();
+        let b: Polynomial = [(-1, 0), (1, 1)].into_iter().collect();
+        let c = &a * &b;
+        assert_eq!(c.to_string(), "x^2 - 1");
+        assert_eq!(c.degree(), Some(2));
+        assert_eq!(c.len(), 2);
+        assert_eq!(c.coef(1), 0);
+        assert_eq!(c.eval(5), 24);
+
+        // 相加后系数抵消的项会被删除
+        let d = &c + &[(-1, 2), (3, 1000)].into_iter().collect();
+        assert_eq!(d.to_string(), "3x^1000 - 1");
+        assert_eq!(d.derivative().to_string(), "3000x^999");
+        assert_eq!(d.eval(1), 2);
+
+        let zero = &c + &[(1, 0), (-1, 2)].into_iter().collect();
+        assert!(zero.is_zero());
+        assert_eq!(zero.to_string(), "0");
+        assert_eq!(zero.degree(), None);
+        assert!((&zero * &a).is_zero());
+        assert_eq!(&zero + &a, a);
+
+        let mut e = Polynomial::constant(-2);
+        e.add_term(1, 3);
+        e.add_term(-1, 1);
+        e.add_term(0, 7);
+        e.add_term(2, 0);
+        assert_eq!(e.to_string(), "x^3 - x");
+        assert_eq!(
+            e,
+            &Polynomial::monomial(1, 3) + &Polynomial::monomial(-1, 1)
+        );
+        assert_eq!(Polynomial::monomial(0, 5), Polynomial::new());
+    }
+
+    #[test]
+    fn test_polynomial_against_dense() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..500 {
+            let (a, b) = (random_poly(&mut rng), random_poly(&mut rng));
+            let (da, db) = (dense(&a), dense(&b));
+
+            let sum = &a + &b;
+            let mut expect = vec![0; da.len().max(db.len())];
+            for (i, &c) in da.iter().enumerate() {
+                expect[i] += c;
+            }
+            for (i, &c) in db.iter().enumerate() {
+                expect[i] += c;
+            }
+            while expect.last() == Some(&0) {
+                expect.pop();
+            }
+            assert_eq!(dense(&sum), expect);
+
+            let prod = &a * &b;
+            let mut expect = vec![0; (da.len() + db.len()).saturating_sub(1)];
+            for (i, &x) in da.iter().enumerate() {
+                for (j, &y) in db.iter().enumerate() {
+                    expect[i + j] += x * y;
+                }
+            }
+            while expect.last() == Some(&0) {
+                expect.pop();
+            }
+            assert_eq!(dense(&prod), expect);
+            assert_eq!(prod, &b * &a);
+
+            // 链表中的项保持严格降序且没有 0 系数
+            assert!(prod.terms().all(|t| t.coef != 0));
+            assert!(prod
+                .terms()
+                .zip(prod.terms().skip(1))
+                .all(|(x, y)| x.exp > y.exp));
+
+            let x = rng.gen_range(-3..=3);
+            assert_eq!(prod.eval(x), a.eval(x) * b.eval(x));
+        }
+    }
+}
@@ -0,0 +1,299 @@
+//! 有序容器的性质测试
+//!
+//! - [proptest](https://proptest-rs.github.io/proptest/)
+//!
+//! 和 [`crate::oracle`] 使用固定种子的差分测试互补, 这里由 proptest 随机生成操作序列,
+//! 出错时会自动收缩(shrink)出最短的反例, 对每个容器检查以下性质:
+//!
+//! - 每一步插入/删除/查找的返回值和参照模型 [`BTreeMap`] 一致(成员关系等价)
+//! - 每一步之后长度和模型一致
+//! - 最后按顺序遍历的结果和模型一致(遍历有序)
+//!
+//! 只保存 key 的集合类容器(红黑树, AVL 树, Treap)把所有值看作 0, 和模型比较前也把模型的值清零
+
+use std::collections::BTreeMap;
+
+use proptest::prelude::*;
+
+use crate::avl_tree::AVLTree;
+use crate::b_plus_tree::BPlusTree;
+use crate::b_tree::BTree;
+use crate::radix_tree::RadixTree;
+use crate::rb_tree::RBTree;
+use crate::skip_list::SkipList;
+use crate::treap::Treap;
+
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    Insert(u16, u32),
+    Delete(u16),
+    Get(u16),
+}
+
+/// key 的范围较小, 保证有足够多的重复插入和删除已存在的 key
+fn op() -> impl Strategy<Value = Op> {
+    let key = 0..64u16;
+    prop_oneof![
+        (key.clone(), any::<u32>()).prop_map(|(k, v)| Op::Insert(k, v)),
+        key.clone().prop_map(Op::Delete),
+        key.prop_map(Op::Get),
+    ]
+}
+
+/// 统一各个容器的映射接口, 返回值都是操作前 key 对应的值
+trait OrderedMap {
+    /// 是否只保存 key
+    const SET: bool = false;
+
+    fn insert(&mut self, key: u16, value: u32) -> Option<u32>;
+    fn delete(&mut self, key: &u16) -> Option<u32>;
+    fn get(&self, key: &u16) -> Option<u32>;
+    fn len(&self) -> usize;
+    fn items(&self) -> Vec<(u16, u32)>;
+
+    /// 容器自身的结构检查
+    fn check(&self) {}
+}
+
+impl OrderedMap for RBTree<u16> {
+    const SET: bool = true;
+
+    fn insert(&mut self, key: u16, _: u32) -> Option<u32> {
+        RBTree::insert(self, key).map(|_| 0)
+    }
+
+    fn delete(&mut self, key: &u16) -> Option<u32> {
+        RBTree::delete(self, key).map(|_| 0)
+    }
+
+    fn get(&self, key: &u16) -> Option<u32> {
+        self.contains(key).then_some(0)
+    }
+
+    fn len(&self) -> usize {
+        RBTree::len(self)
+    }
+
+    fn items(&self) -> Vec<(u16, u32)> {
+        self.iter().map(|&k| (k, 0)).collect()
+    }
+
+    fn check(&self) {
+        self.validate();
+    }
+}
+
+impl OrderedMap for AVLTree<u16> {
+    const SET: bool = true;
+
+    fn insert(&mut self, key: u16, _: u32) -> Option<u32> {
+        (!AVLTree::insert(self, key)).then_some(0)
+    }
+
+    fn delete(&mut self, key: &u16) -> Option<u32> {
+        AVLTree::delete(self, key).map(|_| 0)
+    }
+
+    fn get(&self, key: &u16) -> Option<u32> {
+        self.contains(key).then_some(0)
+    }
+
+    fn len(&self) -> usize {
+        AVLTree::len(self)
+    }
+
+    fn items(&self) -> Vec<(u16, u32)> {
+        self.iter().map(|&k| (k, 0)).collect()
+    }
+}
+
+impl OrderedMap for Treap<u16> {
+    const SET: bool = true;
+
+    fn insert(&mut self, key: u16, _: u32) -> Option<u32> {
+        (!Treap::insert(self, key)).then_some(0)
+    }
+
+    fn delete(&mut self, key: &u16) -> Option<u32> {
+        Treap::delete(self, key).map(|_| 0)
+    }
+
+    fn get(&self, key: &u16) -> Option<u32> {
+        self.contains(key).then_some(0)
+    }
+
+    fn len(&self) -> usize {
+        Treap::len(self)
+    }
+
+    fn items(&self) -> Vec<(u16, u32)> {
+        self.iter().map(|&k| (k, 0)).collect()
+    }
+}
+
+impl OrderedMap for SkipList<8, u16, u32> {
+    fn insert(&mut self, key: u16, value: u32) -> Option<u32> {
+        SkipList::insert(self, key, value)
+    }
+
+    fn delete(&mut self, key: &u16) -> Option<u32> {
+        SkipList::delete(self, key)
+    }
+
+    fn get(&self, key: &u16) -> Option<u32> {
+        self.find(key).copied()
+    }
+
+    fn len(&self) -> usize {
+        SkipList::len(self)
+    }
+
+    fn items(&self) -> Vec<(u16, u32)> {
+        self.iter().map(|(&k, &v)| (k, v)).collect()
+    }
+
+    fn check(&self) {
+        SkipList::check(self);
+    }
+}
+
+impl OrderedMap for BTree<u16, u32> {
+    fn insert(&mut self, key: u16, value: u32) -> Option<u32> {
+        BTree::insert(self, (key, value)).map(|e| e.1)
+    }
+
+    fn delete(&mut self, key: &u16) -> Option<u32> {
+        BTree::delete(self, key).map(|e| e.1)
+    }
+
+    fn get(&self, key: &u16) -> Option<u32> {
+        BTree::get(self, key).map(|e| e.1)
+    }
+
+    fn len(&self) -> usize {
+        BTree::len(self)
+    }
+
+    fn items(&self) -> Vec<(u16, u32)> {
+        self.iter().map(|(&k, &v)| (k, v)).collect()
+    }
+
+    fn check(&self) {
+        self.validate();
+    }
+}
+
+impl OrderedMap for BPlusTree<u16, u32> {
+    fn insert(&mut self, key: u16, value: u32) -> Option<u32> {
+        BPlusTree::insert(self, (key, value)).map(|e| e.1)
+    }
+
+    fn delete(&mut self, key: &u16) -> Option<u32> {
+        BPlusTree::delete(self, key).map(|e| e.1)
+    }
+
+    fn get(&self, key: &u16) -> Option<u32> {
+        self.find(key).map(|e| *e.1)
+    }
+
+    fn len(&self) -> usize {
+        BPlusTree::len(self)
+    }
+
+    fn items(&self) -> Vec<(u16, u32)> {
+        self.iter().map(|(&k, &v)| (k, v)).collect()
+    }
+}
+
+/// 大端序的字节序列和数值的顺序一致, 遍历结果可以直接和模型比较
+impl OrderedMap for RadixTree<u32> {
+    fn insert(&mut self, key: u16, value: u32) -> Option<u32> {
+        RadixTree::insert(self, &key.to_be_bytes(), value)
+    }
+
+    fn delete(&mut self, key: &u16) -> Option<u32> {
+        RadixTree::delete(self, &key.to_be_bytes()).map(|e| e.1)
+    }
+
+    fn get(&self, key: &u16) -> Option<u32> {
+        self.find(&key.to_be_bytes()).copied()
+    }
+
+    fn len(&self) -> usize {
+        RadixTree::len(self)
+    }
+
+    fn items(&self) -> Vec<(u16, u32)> {
+        self.iter()
+            .map(|(k, &v)| (u16::from_be_bytes([k[0], k[1]]), v))
+            .collect()
+    }
+}
+
+/// 对容器和模型执行相同的操作序列并检查所有性质
+fn check<M: OrderedMap>(map: &mut M, ops: &[Op]) -> Result<(), TestCaseError> {
+    let mut model = BTreeMap::new();
+    for (step, &op) in ops.iter().enumerate() {
+        let (expect, actual) = match op {
+            Op::Insert(k, v) => {
+                let v = if M::SET { 0 } else { v };
+                (model.insert(k, v), map.insert(k, v))
+            }
+            Op::Delete(k) => (model.remove(&k), map.delete(&k)),
+            Op::Get(k) => (model.get(&k).copied(), map.get(&k)),
+        };
+
+        prop_assert_eq!(expect, actual, "step {} {:?}", step, op);
+        prop_assert_eq!(model.len(), map.len(), "step {} {:?} len", step, op);
+        map.check();
+    }
+
+    let items: Vec<_> = model.into_iter().collect();
+    prop_assert_eq!(items, map.items());
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn prop_rb_tree(ops in prop::collection::vec(op(), 0..300)) {
+        check(&mut RBTree::new(), &ops)?;
+    }
+
+    #[test]
+    fn prop_avl_tree(ops in prop::collection::vec(op(), 0..300)) {
+        check(&mut AVLTree::new(), &ops)?;
+    }
+
+    #[test]
+    fn prop_treap(ops in prop::collection::vec(op(), 0..300)) {
+        check(&mut Treap::new(), &ops)?;
+    }
+
+    #[test]
+    fn prop_skip_list(ops in prop::collection::vec(op(), 0..300)) {
+        check(&mut SkipList::<8, _, _>::new(), &ops)?;
+    }
+
+    #[test]
+    fn prop_b_tree(order in 3..8usize, ops in prop::collection::vec(op(), 0..300)) {
+        check(&mut BTree::new(order), &ops)?;
+    }
+
+    #[test]
+    fn prop_b_plus_tree(order in 3..8usize, ops in prop::collection::vec(op(), 0..300)) {
+        check(&mut BPlusTree::new(order), &ops)?;
+    }
+
+    #[test]
+    fn prop_radix_tree(ops in prop::collection::vec(op(), 0..300)) {
+        check(&mut RadixTree::new(), &ops)?;
+    }
+
+    /// 批量构建得到的树按顺序遍历就是输入的 key
+    #[test]
+    fn prop_b_plus_tree_from_sorted(order in 3..8usize, keys in prop::collection::btree_set(any::<u16>(), 0..300)) {
+        let t = BPlusTree::from_sorted_iter(keys.iter().map(|&k| (k, ())), order);
+        prop_assert_eq!(t.len(), keys.len());
+        prop_assert!(t.iter().map(|(k, _)| k).eq(keys.iter()));
+    }
+}
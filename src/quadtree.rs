@@ -0,0 +1,330 @@
+//! 四叉树
+//!
+//! - [wikipedia - Quadtree](https://en.wikipedia.org/wiki/Quadtree)
+//! - [OI Wiki - 四叉树](https://oi-wiki.org/ds/quad-tree/)
+//!
+//! 四叉树把二维平面上的一个矩形区域递归地平均分成四个子区域, 用来索引平面上的点
+//!
+//! - 插入: 找到点所在的叶子节点并加入, 叶子中的点数超过容量时把叶子分裂成四个子节点并把点重新分配下去
+//! - 删除: 从叶子中删除之后, 如果某个内部节点子树中的点数不超过容量, 就把整个子树合并回一个叶子
+//! - 区域查询: 只进入和查询矩形相交的子节点, 点分布比较均匀时远比遍历所有点快
+//!
+//! 大量重合的点会让分裂无法把它们分开, 所以限制了最大深度, 到达最大深度的叶子不再分裂
+
+/// 叶子节点不再分裂的深度
+const MAX_DEPTH: usize = 16;
+
+/// 默认的叶子节点容量
+const DEFAULT_CAPACITY: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    pub fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
+    }
+}
+
+/// 左闭右开的矩形 `[x0, x1) x [y0, y1)`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub x0: f64,
+    pub y0: f64,
+    pub x1: f64,
+    pub y1: f64,
+}
+
+impl Rect {
+    pub fn new(x0: f64, y0: f64, x1: f64, y1: f64) -> Self {
+        assert!(x0 <= x1 && y0 <= y1, "invalid rectangle");
+        Self { x0, y0, x1, y1 }
+    }
+
+    pub fn contains(&self, p: &Point) -> bool {
+        self.x0 <= p.x && p.x < self.x1 && self.y0 <= p.y && p.y < self.y1
+    }
+
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.x0 < other.x1 && other.x0 < self.x1 && self.y0 < other.y1 && other.y0 < self.y1
+    }
+
+    fn center(&self) -> Point {
+        Point::new((self.x0 + self.x1) / 2.0, (self.y0 + self.y1) / 2.0)
+    }
+
+    /// 点所在的象限, 依次是左下、右下、左上、右上
+    fn quadrant(&self, p: &Point) -> usize {
+        let c = self.center();
+        (p.x >= c.x) as usize | ((p.y >= c.y) as usize) << 1
+    }
+
+    /// 第 i 个象限对应的子矩形
+    fn child(&self, i: usize) -> Rect {
+        let c = self.center();
+        let (x0, x1) = if i & 1 == 0 {
+            (self.x0, c.x)
+        } else {
+            (c.x, self.x1)
+        };
+        let (y0, y1) = if i & 2 == 0 {
+            (self.y0, c.y)
+        } else {
+            (c.y, self.y1)
+        };
+        Rect { x0, y0, x1, y1 }
+    }
+}
+
+enum Node<T> {
+    Leaf(Vec<(Point, T)>),
+    Internal {
+        /// 子树中点的数量
+        count: usize,
+        children: Box<[Node<T>; 4]>,
+    },
+}
+
+impl<T> Node<T> {
+    fn empty_children() -> Box<[Node<T>; 4]> {
+        Box::new(std::array::from_fn(|_| Node::Leaf(vec![])))
+    }
+
+    fn insert(&mut self, bounds: Rect, depth: usize, capacity: usize, p: Point, v: T) {
+        match self {
+            Node::Leaf(items) => {
+                items.push((p, v));
+                if items.len() > capacity && depth < MAX_DEPTH {
+                    // 分裂成四个子节点
+                    let items = std::mem::take(items);
+                    let count = items.len();
+                    let mut children = Self::empty_children();
+                    for (p, v) in items {
+                        let i = bounds.quadrant(&p);
+                        children[i].insert(bounds.child(i), depth + 1, capacity, p, v);
+                    }
+                    *self = Node::Internal { count, children };
+                }
+            }
+            Node::Internal { count, children } => {
+                *count += 1;
+                let i = bounds.quadrant(&p);
+                children[i].insert(bounds.child(i), depth + 1, capacity, p, v);
+            }
+        }
+    }
+
+    fn remove(&mut self, bounds: Rect, capacity: usize, p: &Point) -> Option<T> {
+        match self {
+            Node::Leaf(items) => {
+                let i = items.iter().position(|(q, _)| q == p)?;
+                Some(items.swap_remove(i).1)
+            }
+            Node::Internal { count, children } => {
+                let i = bounds.quadrant(p);
+                let v = children[i].remove(bounds.child(i), capacity, p)?;
+                *count -= 1;
+                if *count <= capacity {
+                    // 子树中的点不多了, 合并回一个叶子
+                    let mut items = Vec::with_capacity(*count);
+                    children.iter_mut().for_each(|c| c.drain(&mut items));
+                    *self = Node::Leaf(items);
+                }
+                Some(v)
+            }
+        }
+    }
+
+    /// 取出子树中的所有点
+    fn drain(&mut self, out: &mut Vec<(Point, T)>) {
+        match self {
+            Node::Leaf(items) => out.append(items),
+            Node::Internal { children, .. } => children.iter_mut().for_each(|c| c.drain(out)),
+        }
+    }
+
+    fn query<'a>(&'a self, bounds: Rect, rect: &Rect, out: &mut Vec<(Point, &'a T)>) {
+        if !bounds.intersects(rect) {
+            return;
+        }
+
+        match self {
+            Node::Leaf(items) => out.extend(
+                items
+                    .iter()
+                    .filter(|(p, _)| rect.contains(p))
+                    .map(|(p, v)| (*p, v)),
+            ),
+            Node::Internal { children, .. } => {
+                for (i, c) in children.iter().enumerate() {
+                    c.query(bounds.child(i), rect, out);
+                }
+            }
+        }
+    }
+
+    fn depth(&self) -> usize {
+        match self {
+            Node::Leaf(_) => 0,
+            Node::Internal { children, .. } => {
+                1 + children.iter().map(|c| c.depth()).max().unwrap()
+            }
+        }
+    }
+}
+
+pub struct QuadTree<T> {
+    bounds: Rect,
+    capacity: usize,
+    len: usize,
+    root: Node<T>,
+}
+
+impl<T> QuadTree<T> {
+    /// 索引 bounds 范围内的点
+    pub fn new(bounds: Rect) -> Self {
+        Self::with_capacity(bounds, DEFAULT_CAPACITY)
+    }
+
+    /// 每个叶子节点最多保存 capacity 个点(到达最大深度时除外)
+    pub fn with_capacity(bounds: Rect, capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be positive");
+        Self {
+            bounds,
+            capacity,
+            len: 0,
+            root: Node::Leaf(vec![]),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// 树的深度, 只有一个叶子时为 0
+    pub fn depth(&self) -> usize {
+        self.root.depth()
+    }
+
+    /// 插入一个点, 点不在索引范围内时返回 false
+    pub fn insert(&mut self, p: Point, v: T) -> bool {
+        if !self.bounds.contains(&p) {
+            return false;
+        }
+
+        self.root.insert(self.bounds, 0, self.capacity, p, v);
+        self.len += 1;
+        true
+    }
+
+    /// 删除一个坐标为 p 的点并返回它的值, 有多个重合的点时只删除其中一个
+    pub fn remove(&mut self, p: &Point) -> Option<T> {
+        if !self.bounds.contains(p) {
+            return None;
+        }
+
+        let v = self.root.remove(self.bounds, self.capacity, p)?;
+        self.len -= 1;
+        Some(v)
+    }
+
+    /// 查询矩形 rect 中的所有点
+    pub fn query(&self, rect: &Rect) -> Vec<(Point, &T)> {
+        let mut res = vec![];
+        self.root.query(self.bounds, rect, &mut res);
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_quadtree() {
+        let mut tree = QuadTree::with_capacity(Rect::new(0.0, 0.0, 100.0, 100.0), 2);
+        assert!(tree.insert(Point::new(10.0, 10.0), 'a'));
+        assert!(tree.insert(Point::new(60.0, 10.0), 'b'));
+        assert!(tree.insert(Point::new(10.0, 60.0), 'c'));
+        assert!(tree.insert(Point::new(60.0, 60.0), 'd'));
+        assert!(!tree.insert(Point::new(100.0, 0.0), 'x'));
+        assert_eq!(tree.len(), 4);
+        assert_eq!(tree.depth(), 1);
+
+        let mut found: Vec<_> = tree
+            .query(&Rect::new(0.0, 0.0, 70.0, 50.0))
+            .into_iter()
+            .map(|(_, &v)| v)
+            .collect();
+        found.sort();
+        assert_eq!(found, vec!['a', 'b']);
+
+        assert_eq!(tree.remove(&Point::new(10.0, 10.0)), Some('a'));
+        assert_eq!(tree.remove(&Point::new(10.0, 10.0)), None);
+        assert_eq!(tree.remove(&Point::new(60.0, 10.0)), Some('b'));
+        // 只剩两个点, 合并回一个叶子
+        assert_eq!(tree.depth(), 0);
+        assert_eq!(tree.len(), 2);
+
+        // 重合的点不会无限分裂
+        for i in 0..100 {
+            tree.insert(Point::new(1.0, 1.0), char::from(b'0' + i % 10));
+        }
+        assert_eq!(tree.depth(), MAX_DEPTH);
+        assert_eq!(tree.query(&Rect::new(0.0, 0.0, 2.0, 2.0)).len(), 100);
+    }
+
+    #[test]
+    fn test_quadtree_random() {
+        let mut rng = rand::thread_rng();
+        let mut tree = QuadTree::new(Rect::new(-50.0, -50.0, 50.0, 50.0));
+        let mut points = vec![];
+
+        for i in 0..2000 {
+            if rng.gen_bool(0.7) || points.is_empty() {
+                let p = Point::new(rng.gen_range(-50.0..50.0), rng.gen_range(-50.0..50.0));
+                assert!(tree.insert(p, i));
+                points.push((p, i));
+            } else {
+                let (p, v) = points.swap_remove(rng.gen_range(0..points.len()));
+                assert_eq!(tree.remove(&p), Some(v));
+            }
+            assert_eq!(tree.len(), points.len());
+
+            if i % 100 == 0 {
+                let x0 = rng.gen_range(-60.0..40.0);
+                let y0 = rng.gen_range(-60.0..40.0);
+                let rect = Rect::new(
+                    x0,
+                    y0,
+                    x0 + rng.gen_range(0.0..30.0),
+                    y0 + rng.gen_range(0.0..30.0),
+                );
+
+                let mut found: Vec<_> = tree.query(&rect).into_iter().map(|(_, &v)| v).collect();
+                let mut expect: Vec<_> = points
+                    .iter()
+                    .filter(|(p, _)| rect.contains(p))
+                    .map(|&(_, v)| v)
+                    .collect();
+                found.sort();
+                expect.sort();
+                assert_eq!(found, expect);
+            }
+        }
+
+        for (p, v) in points {
+            assert_eq!(tree.remove(&p), Some(v));
+        }
+        assert!(tree.is_empty());
+        assert_eq!(tree.depth(), 0);
+    }
+}
@@ -1,17 +1,21 @@
 //! 基数树
 //!
 //! 参考 [armon/go-radix](https://github.com/armon/go-radix) 实现的 Rust 版本的 Radix-Tree
+//!
+//! [`RadixTree`] 以字节序列 `&[u8]` 作为 key, 边的标签是一个字节, 可以直接用于二进制数据  
+//! 同时不需要在搜索过程中解码 UTF-8 字符  
+//! [`StrRadixTree`] 是以 `&str` 作为 key 的一层简单包装
 
 /// 基数树节点之间相连的边
 pub struct Edge<T> {
-    label: char,
+    label: u8,
     node: Node<T>,
 }
 
 /// 基数树节点
 pub struct Node<T> {
-    value: Option<(String, T)>,
-    prefix: String,
+    value: Option<(Vec<u8>, T)>,
+    prefix: Vec<u8>,
     edges: Vec<Edge<T>>,
 }
 
@@ -19,35 +23,35 @@ impl<T> Node<T> {
     pub fn new() -> Self {
         Self {
             value: None,
-            prefix: String::new(),
+            prefix: vec![],
             edges: vec![],
         }
     }
 
-    pub fn new_prefix(s: &str) -> Self {
+    pub fn new_prefix(s: &[u8]) -> Self {
         Self {
             value: None,
-            prefix: String::from(s),
+            prefix: s.to_vec(),
             edges: vec![],
         }
     }
 
-    /// 二分查找以 target 作为首字符的子节点
-    pub fn find(&self, target: &char) -> Option<&Node<T>> {
+    /// 二分查找以 target 作为首字节的子节点
+    pub fn find(&self, target: &u8) -> Option<&Node<T>> {
         self.edges
             .binary_search_by(|edge| edge.label.cmp(target))
             .ok()
             .map(|idx| &self.edges[idx].node)
     }
 
-    /// 二分查找以 target 作为首字符的子节点所在边索引
-    pub fn find_index(&self, target: &char) -> Result<usize, usize> {
+    /// 二分查找以 target 作为首字节的子节点所在边索引
+    pub fn find_index(&self, target: &u8) -> Result<usize, usize> {
         self.edges.binary_search_by(|edge| edge.label.cmp(target))
     }
 
     /// 返回以当前节点作为数据节点的值
-    pub fn value(&self) -> Option<(&str, &T)> {
-        self.value.as_ref().map(|(k, v)| (k.as_str(), v))
+    pub fn value(&self) -> Option<(&[u8], &T)> {
+        self.value.as_ref().map(|(k, v)| (k.as_slice(), v))
     }
 
     /// 节点新增一条边
@@ -62,7 +66,7 @@ impl<T> Node<T> {
     fn merge_child(&mut self) {
         if self.edges.len() == 1 {
             let child = self.edges.remove(0).node;
-            self.prefix.push_str(&child.prefix);
+            self.prefix.extend_from_slice(&child.prefix);
             self.edges = child.edges;
             self.value = child.value;
         }
@@ -112,40 +116,35 @@ impl<T> RadixTree<T> {
     }
 
     /// 查找 key 对应基数树中的 value
-    pub fn find(&self, key: &str) -> Option<&T> {
+    pub fn find(&self, key: &[u8]) -> Option<&T> {
         let mut node = &self.root;
         let mut search = key;
 
-        while let Some(label) = search.chars().peekable().peek() {
-            match node.find(label) {
-                None => break,
-                Some(child) => {
-                    // 如果前缀与当前节点不匹配则结束搜索
-                    if !search.starts_with(&child.prefix) {
-                        return None;
-                    }
-
-                    // 去掉在当前节点上的前缀继续搜索
-                    search = &search[child.prefix.len()..];
-                    node = child;
-                }
+        while let Some(label) = search.first() {
+            let child = node.find(label)?;
+            match step(search, &child.prefix) {
+                // 去掉在当前节点上的前缀继续搜索
+                Step::Descend(rest) => search = rest,
+                // 前缀与当前节点不匹配则结束搜索
+                Step::Inside | Step::Diverge(_) => return None,
             }
+            node = child;
         }
 
         node.value().map(|(_, v)| v)
     }
 
     /// 在树中插入 key-value 对如果树中已经存在对应的 key 则更新其值并将旧值返回出来
-    pub fn insert(&mut self, key: &str, value: T) -> Option<T> {
+    pub fn insert(&mut self, key: &[u8], value: T) -> Option<T> {
         let mut node = &mut self.root;
         let mut search = key;
 
         loop {
-            match search.chars().peekable().peek() {
+            match search.first() {
                 None => match &mut node.value {
                     None => {
                         // 如果当前找到的节点不是数据节点, 则直接写入数据
-                        node.value = Some((String::from(key), value));
+                        node.value = Some((key.to_vec(), value));
                         self.size += 1;
                         return None;
                     }
@@ -159,8 +158,8 @@ impl<T> RadixTree<T> {
                         node.add_edge(Edge {
                             label,
                             node: Node {
-                                value: Some((String::from(key), value)),
-                                prefix: String::from(search),
+                                value: Some((key.to_vec(), value)),
+                                prefix: search.to_vec(),
                                 edges: vec![],
                             },
                         });
@@ -171,25 +170,26 @@ impl<T> RadixTree<T> {
                     Ok(index) => {
                         // 如果能找到对应字符的边首先查看此节点是不是 search 的前缀
                         // 如果是则在 search 上去掉前缀继续向下搜索即可
-                        if search.starts_with(&node.edges[index].node.prefix) {
-                            node = &mut node.edges[index].node;
-                            search = &search[node.prefix.len()..];
-                            continue;
-                        }
+                        // 否则计算出 search 和当前节点的最长公共前缀
+                        let size = match step(search, &node.edges[index].node.prefix) {
+                            Step::Descend(rest) => {
+                                node = &mut node.edges[index].node;
+                                search = rest;
+                                continue;
+                            }
+                            Step::Inside => search.len(),
+                            Step::Diverge(size) => size,
+                        };
 
                         // 如果不是前缀则需要分裂当前节点
-                        // 先把当前子节点移出父节点
+                        // 先把当前子节点移出父节点, 以公共前缀建立一个新的节点
                         let mut child = node.edges.remove(index).node;
-
-                        // 计算出 search 和当前节点的最长公共前缀
-                        // 以这个公共前缀建立一个新的节点
-                        let size = longest_commin_prefix(search, &child.prefix);
                         let mut new_parent = Node::new_prefix(&search[..size]);
 
                         // 子节点移除公共前缀, 添加到新节点的边上
                         child.prefix.drain(..size);
                         new_parent.add_edge(Edge {
-                            label: first_char(&child.prefix),
+                            label: child.prefix[0],
                             node: child,
                         });
 
@@ -198,14 +198,14 @@ impl<T> RadixTree<T> {
                         search = &search[size..];
                         if search.is_empty() {
                             // 如果 search 已经空了则直接把数据写到新的父节点
-                            new_parent.value = Some((String::from(key), value));
+                            new_parent.value = Some((key.to_vec(), value));
                         } else {
                             // 否则将 search 写入到父节点的一个新的子节点
                             new_parent.add_edge(Edge {
-                                label: first_char(search),
+                                label: search[0],
                                 node: Node {
-                                    value: Some((String::from(key), value)),
-                                    prefix: String::from(search),
+                                    value: Some((key.to_vec(), value)),
+                                    prefix: search.to_vec(),
                                     edges: vec![],
                                 },
                             });
@@ -239,22 +239,30 @@ impl<T> RadixTree<T> {
     ///
     /// 如基数树中有 `abc` 和 `acd` 两个数据则插入完成后应该是 root 节点有一个节点 a  
     /// 同时 a 有两个子节点 `bc` 和 `cd` 当 `abc` 被删除时 `a` 可以和 `cd` 合并成 `acd`
-    pub fn delete(&mut self, key: &str) -> Option<(String, T)> {
+    pub fn delete(&mut self, key: &[u8]) -> Option<(Vec<u8>, T)> {
+        if key.is_empty() {
+            // 空 key 直接存储在根节点上
+            let value = self.root.value.take();
+            if value.is_some() {
+                self.size -= 1;
+            }
+            return value;
+        }
+
         let mut node = &mut self.root;
         let mut search = key;
         let mut is_root = true;
 
-        while let Some(label) = search.chars().peekable().peek() {
+        while let Some(label) = search.first() {
             match node.find_index(label) {
                 // 没找到节点
                 Err(_) => return None,
                 Ok(index) => {
-                    let child = &node.edges[index].node;
-                    if !search.starts_with(&child.prefix) {
-                        return None;
+                    match step(search, &node.edges[index].node.prefix) {
+                        Step::Descend(rest) => search = rest,
+                        Step::Inside | Step::Diverge(_) => return None,
                     }
 
-                    search = &search[child.prefix.len()..];
                     if !search.is_empty() {
                         node = &mut node.edges[index].node;
                         is_root = false;
@@ -293,73 +301,69 @@ impl<T> RadixTree<T> {
         None
     }
 
-    /// 删除指定前缀
-    ///
+    /// 删除所有以 pre 为前缀的 key, 返回删除的数量
     ///
-    pub fn delete_prefix(&mut self, pre: &str) -> usize {
+    /// 向下查找到第一个完整路径以 pre 开头的节点, 即 pre 在这个节点的前缀上或恰好在节点处结束  
+    /// 直接删除这个节点及其所有子节点, 然后同 [`RadixTree::delete`] 一样尝试合并父节点
+    pub fn delete_prefix(&mut self, pre: &[u8]) -> usize {
+        if pre.is_empty() {
+            // 直接从根节点清理所有数据, 包括空 key
+            self.root.delete();
+            self.root.edges.clear();
+            return std::mem::take(&mut self.size);
+        }
+
         let mut parent = &mut self.root;
-        let mut child_index = None;
         let mut search = pre;
         let mut is_root = true;
 
-        // 找到前缀节点对应的父节点和索引前缀节点在父节点中的索引 child_index
-        while let Some(label) = search.chars().peekable().peek() {
-            if let Some(index) = child_index {
-                let edge: &mut Edge<T> = &mut parent.edges[index];
-                parent = &mut edge.node;
-                is_root = false;
-            }
-
-            match parent.find_index(label) {
-                Err(_) => return 0,
-                Ok(index) => {
-                    let child = &parent.edges[index].node;
-
-                    // 如果 search 和 child.prefix 都不是互相的前缀则不存在 pre 前缀的节点搜索结束
-                    if !search.starts_with(&child.prefix) && !child.prefix.starts_with(search) {
-                        return 0;
-                    }
-
-                    let size = std::cmp::max(child.prefix.len(), search.len());
-                    search = &search[size..];
-                    child_index = Some(index);
+        loop {
+            let Ok(index) = parent.find_index(&search[0]) else {
+                return 0;
+            };
+
+            match step(search, &parent.edges[index].node.prefix) {
+                Step::Diverge(_) => return 0,
+                Step::Descend(rest) if !rest.is_empty() => {
+                    search = rest;
+                    parent = &mut parent.edges[index].node;
+                    is_root = false;
                 }
-            }
-        }
+                Step::Descend(_) | Step::Inside => {
+                    let deleted = parent.edges.remove(index).node.delete();
 
-        match child_index {
-            None => {
-                // 直接从根节点清理所有数据
-                let size = self.size;
-                self.root.edges.clear();
-                self.size = 0;
-                size
-            }
-            Some(index) => {
-                let child = &mut parent.edges[index].node;
-                let deleted = child.delete();
-                parent.edges.remove(index);
+                    if !is_root && parent.edges.len() == 1 && parent.value.is_none() {
+                        parent.merge_child();
+                    }
 
-                if !is_root && parent.edges.len() == 1 && parent.value.is_none() {
-                    parent.merge_child();
+                    self.size -= deleted;
+                    return deleted;
                 }
-
-                deleted
             }
         }
     }
 
     /// 转换成引用迭代器
     pub fn iter(&self) -> Iter<'_, T> {
+        // 根节点上的空 key 最先返回, indexes 总是指向根节点之下的第一个数据节点
         let mut indexes = vec![];
         let mut node = &self.root;
-        while !node.edges.is_empty() && node.value.is_none() {
+        if !node.edges.is_empty() {
             indexes.push(0);
             node = &node.edges[0].node;
+            while node.value.is_none() {
+                indexes.push(0);
+                node = &node.edges[0].node;
+            }
         }
 
         let root = &self.root;
-        Iter { root, indexes }
+        let first = root.value();
+        Iter {
+            root,
+            first,
+            indexes,
+        }
     }
 }
 
@@ -372,13 +376,18 @@ impl<T> Default for RadixTree<T> {
 /// 引用迭代器
 pub struct Iter<'a, T> {
     root: &'a Node<T>,
+    first: Option<(&'a [u8], &'a T)>,
     indexes: Vec<usize>,
 }
 
 impl<'a, T> Iterator for Iter<'a, T> {
-    type Item = (&'a str, &'a T);
+    type Item = (&'a [u8], &'a T);
 
     fn next(&mut self) -> Option<Self::Item> {
+        if let Some(item) = self.first.take() {
+            return Some(item);
+        }
+
         if self.indexes.is_empty() {
             return None;
         }
@@ -433,7 +442,7 @@ impl<'a, T> Iterator for Iter<'a, T> {
 
             // 寻找兄弟节点 p2
             if let Some(edge) = pparent.edges.get(index + 1) {
-                self.indexes.push(curr_idx + 1);
+                self.indexes.push(index + 1);
                 node = &edge.node;
                 while node.value.is_none() {
                     self.indexes.push(0);
@@ -457,7 +466,7 @@ pub struct IntoIter<T> {
 }
 
 impl<T> IntoIterator for RadixTree<T> {
-    type Item = (String, T);
+    type Item = (Vec<u8>, T);
 
     type IntoIter = IntoIter<T>;
 
@@ -476,7 +485,7 @@ impl<T> IntoIterator for RadixTree<T> {
 }
 
 impl<T> Iterator for IntoIter<T> {
-    type Item = (String, T);
+    type Item = (Vec<u8>, T);
 
     fn next(&mut self) -> Option<Self::Item> {
         let indexes = &self.indexes;
@@ -490,8 +499,8 @@ impl<T> Iterator for IntoIter<T> {
             Some((key, _)) => {
                 // 这里为了避免处理不可变引用 key 和可变引用 self.tree 的冲突直接复制了 key 进行删除
                 // 正确的流程应该是和 delete 类似先删除子节点然后处理合并父节点
-                let key = String::from(key);
-                let res = self.tree.delete(key.as_str());
+                let key = key.clone();
+                let res = self.tree.delete(&key);
                 self.indexes.clear();
 
                 let mut node = &self.tree.root;
@@ -506,18 +515,173 @@ impl<T> Iterator for IntoIter<T> {
     }
 }
 
-/// 求两字符串的最长前缀长度
-pub fn longest_commin_prefix(a: &str, b: &str) -> usize {
-    a.chars()
-        .zip(b.chars())
-        .take_while(|x| x.0 == x.1)
-        .map(|x| x.0.len_utf8())
-        .sum()
+/// 以 `&str` 作为 key 的基数树
+///
+/// 内部直接使用字节序列作为 key, 所有 key 都由 `&str` 写入所以一定是合法的 UTF-8  
+/// 取出 key 时可以跳过 UTF-8 校验
+pub struct StrRadixTree<T> {
+    tree: RadixTree<T>,
+}
+
+impl<T> StrRadixTree<T> {
+    pub fn new() -> Self {
+        Self {
+            tree: RadixTree::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    /// 查找 key 对应基数树中的 value
+    pub fn find(&self, key: &str) -> Option<&T> {
+        self.tree.find(key.as_bytes())
+    }
+
+    /// 在树中插入 key-value 对如果树中已经存在对应的 key 则更新其值并将旧值返回出来
+    pub fn insert(&mut self, key: &str, value: T) -> Option<T> {
+        self.tree.insert(key.as_bytes(), value)
+    }
+
+    /// 在基数树中删除指定的 key
+    pub fn delete(&mut self, key: &str) -> Option<(String, T)> {
+        self.tree
+            .delete(key.as_bytes())
+            .map(|(k, v)| (unsafe { String::from_utf8_unchecked(k) }, v))
+    }
+
+    /// 删除指定前缀
+    pub fn delete_prefix(&mut self, pre: &str) -> usize {
+        self.tree.delete_prefix(pre.as_bytes())
+    }
+
+    /// 转换成引用迭代器
+    pub fn iter(&self) -> StrIter<'_, T> {
+        StrIter {
+            iter: self.tree.iter(),
+        }
+    }
+}
+
+impl<T> Default for StrRadixTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 以 `&str` 作为 key 的引用迭代器
+pub struct StrIter<'a, T> {
+    iter: Iter<'a, T>,
+}
+
+impl<'a, T> Iterator for StrIter<'a, T> {
+    type Item = (&'a str, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter
+            .next()
+            .map(|(k, v)| (unsafe { std::str::from_utf8_unchecked(k) }, v))
+    }
 }
 
-/// 获取字符串的首个字符
-fn first_char(s: &str) -> char {
-    s.chars().next().unwrap()
+/// 以 `String` 作为 key 的迭代器
+pub struct StrIntoIter<T> {
+    iter: IntoIter<T>,
+}
+
+impl<T> IntoIterator for StrRadixTree<T> {
+    type Item = (String, T);
+
+    type IntoIter = StrIntoIter<T>;
+
+    /// 转换成值迭代器
+    fn into_iter(self) -> Self::IntoIter {
+        StrIntoIter {
+            iter: self.tree.into_iter(),
+        }
+    }
+}
+
+impl<T> Iterator for StrIntoIter<T> {
+    type Item = (String, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter
+            .next()
+            .map(|(k, v)| (unsafe { String::from_utf8_unchecked(k) }, v))
+    }
+}
+
+/// 求两字节序列的最长公共前缀长度
+pub fn longest_commin_prefix(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|x| x.0 == x.1).count()
+}
+
+/// search 沿着一个节点的前缀向下匹配一步的结果
+#[derive(Debug, PartialEq, Eq)]
+enum Step<'a> {
+    /// 节点前缀是 search 的前缀, 返回去掉节点前缀之后剩余的部分
+    Descend(&'a [u8]),
+    /// search 在节点前缀内部结束, 即 search 是节点前缀的真前缀
+    Inside,
+    /// 两者在节点前缀内部出现不同的字节, 返回公共前缀的长度
+    Diverge(usize),
+}
+
+/// 所有操作共用的前缀匹配, 按字节比较所以节点可以在多字节字符内部分裂
+fn step<'a>(search: &'a [u8], prefix: &[u8]) -> Step<'a> {
+    let size = longest_commin_prefix(search, prefix);
+    if size == prefix.len() {
+        Step::Descend(&search[size..])
+    } else if size == search.len() {
+        Step::Inside
+    } else {
+        Step::Diverge(size)
+    }
+}
+
+/// 序列化为按 key 排序的 key-value 列表, 反序列化时逐个插入重新构建
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    impl<T: Serialize> Serialize for RadixTree<T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(self.iter())
+        }
+    }
+
+    impl<'de, T: Deserialize<'de>> Deserialize<'de> for RadixTree<T> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let mut tree = RadixTree::new();
+            for (key, value) in Vec::<(Vec<u8>, T)>::deserialize(deserializer)? {
+                tree.insert(&key, value);
+            }
+            Ok(tree)
+        }
+    }
+
+    impl<T: Serialize> Serialize for StrRadixTree<T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(self.iter())
+        }
+    }
+
+    impl<'de, T: Deserialize<'de>> Deserialize<'de> for StrRadixTree<T> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let mut tree = StrRadixTree::new();
+            for (key, value) in Vec::<(String, T)>::deserialize(deserializer)? {
+                tree.insert(&key, value);
+            }
+            Ok(tree)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -526,23 +690,109 @@ mod tests {
 
     #[test]
     fn test_longest_common_prefix() {
-        assert_eq!(2, longest_commin_prefix("abc", "abd"));
-        assert_eq!(0, longest_commin_prefix("abc", "bcd"));
+        assert_eq!(2, longest_commin_prefix(b"abc", b"abd"));
+        assert_eq!(0, longest_commin_prefix(b"abc", b"bcd"));
 
         let (a, b) = ("你们好", "你们");
-        let size = longest_commin_prefix(a, b);
+        let size = longest_commin_prefix(a.as_bytes(), b.as_bytes());
         assert_eq!(&a[size..], "好");
         assert_eq!(&b[size..], "");
 
         let (a, b) = ("好good好", "好golden");
-        let size = longest_commin_prefix(a, b);
+        let size = longest_commin_prefix(a.as_bytes(), b.as_bytes());
         assert_eq!(&a[size..], "od好");
         assert_eq!(&b[size..], "lden");
     }
 
     #[test]
-    fn test_radix_tree() {
+    fn test_step() {
+        assert_eq!(step(b"abcd", b"ab"), Step::Descend(b"cd"));
+        assert_eq!(step(b"ab", b"ab"), Step::Descend(b""));
+        assert_eq!(step(b"ab", b"abcd"), Step::Inside);
+        assert_eq!(step(b"", b"ab"), Step::Inside);
+        assert_eq!(step(b"abx", b"abcd"), Step::Diverge(2));
+
+        // "你" 和 "佬" 的 UTF-8 编码只有最后一个字节不同
+        let (a, b) = ("你".as_bytes(), "佬".as_bytes());
+        assert_eq!(step(a, b), Step::Diverge(2));
+        assert_eq!(step(&a[..2], b), Step::Inside);
+    }
+
+    #[test]
+    fn test_radix_tree_delete_prefix_inside_node() {
+        let mut t = StrRadixTree::new();
+        t.insert("abcd", 1);
+        t.insert("abce", 2);
+        t.insert("x", 3);
+
+        // 前缀在节点 "abc" 的内部结束
+        assert_eq!(t.delete_prefix("ab"), 2);
+        assert_eq!(t.len(), 1);
+        assert_eq!(t.find("x"), Some(&3));
+        assert_eq!(t.delete_prefix("xy"), 0);
+        assert_eq!(t.delete_prefix("abcd"), 0);
+
+        t.insert("", 0);
+        t.insert("上海", 1);
+        t.insert("上海市", 2);
+        assert_eq!(t.delete_prefix("上"), 2);
+        assert_eq!(t.delete_prefix(""), 2);
+        assert!(t.is_empty());
+        assert_eq!(t.find(""), None);
+
+        // 字节前缀可以在多字节字符内部结束
         let mut t = RadixTree::new();
+        t.insert("你好".as_bytes(), 1);
+        t.insert("佬".as_bytes(), 2);
+        t.insert("他".as_bytes(), 3);
+        assert_eq!(t.delete_prefix(&"你".as_bytes()[..2]), 2);
+        assert_eq!(t.len(), 1);
+        assert_eq!(t.find("他".as_bytes()), Some(&3));
+    }
+
+    #[test]
+    fn test_radix_tree_unicode_random() {
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+        use std::collections::BTreeMap;
+
+        // 中文字符之间大量共享 UTF-8 首字节, 混合 ASCII 让节点在字符内部和边界上都会分裂
+        let alphabet = ['a', 'b', '/', '你', '佬', '好', '中', '🦀'];
+        let mut rng = StdRng::seed_from_u64(0x5eed);
+        let gen = |rng: &mut StdRng, max: usize| -> String {
+            (0..rng.gen_range(0..max))
+                .map(|_| alphabet[rng.gen_range(0..alphabet.len())])
+                .collect()
+        };
+
+        let mut t = StrRadixTree::new();
+        let mut model = BTreeMap::new();
+        for i in 0..5000 {
+            let key = gen(&mut rng, 6);
+            match rng.gen_range(0..10) {
+                0..=4 => assert_eq!(t.insert(&key, i), model.insert(key, i)),
+                5..=6 => assert_eq!(t.delete(&key), model.remove(&key).map(|v| (key.clone(), v))),
+                7 => {
+                    let pre = gen(&mut rng, 3);
+                    let before = model.len();
+                    model.retain(|k, _| !k.starts_with(&pre));
+                    assert_eq!(t.delete_prefix(&pre), before - model.len(), "{pre}");
+                }
+                _ => assert_eq!(t.find(&key), model.get(&key)),
+            }
+
+            assert_eq!(t.len(), model.len());
+            if i % 100 == 0 {
+                assert!(t.iter().eq(model.iter().map(|(k, v)| (k.as_str(), v))));
+            }
+        }
+        assert!(t.iter().eq(model.iter().map(|(k, v)| (k.as_str(), v))));
+        assert!(t.into_iter().eq(model));
+    }
+
+    #[test]
+    fn test_radix_tree() {
+        let mut t = StrRadixTree::new();
 
         t.insert("a", 1);
         t.insert("ab", 2);
@@ -566,7 +816,7 @@ mod tests {
         assert_eq!(t.len(), 4);
         assert_eq!(t.delete_prefix("a"), 4);
 
-        let mut t = RadixTree::new();
+        let mut t = StrRadixTree::new();
         t.insert("aaa", 1);
         t.insert("aab", 2);
         t.insert("abb", 3);
@@ -580,7 +830,7 @@ mod tests {
         assert_eq!(t.delete("abb"), Some(("abb".into(), 3)));
         assert_eq!(t.delete("aab"), Some(("aab".into(), 2)));
 
-        let mut t = RadixTree::new();
+        let mut t = StrRadixTree::new();
         t.insert("/aaa", 1);
         t.insert("/bbb", 1);
 
@@ -597,7 +847,7 @@ mod tests {
 
     #[test]
     fn test_radix_tree_iter() {
-        let mut t = RadixTree::new();
+        let mut t = StrRadixTree::new();
 
         t.insert("a", 1);
         t.insert("ab", 2);
@@ -617,11 +867,21 @@ mod tests {
 
         t.delete_prefix("");
         assert!(t.iter().next().is_none());
+
+        // 回退到父节点 "a" 后应该取 "a" 的兄弟节点, 而不是 "ay" 的下一个索引
+        t.insert("ax", 1);
+        t.insert("ay", 2);
+        t.insert("b", 3);
+        let mut it = t.iter();
+        assert_eq!(it.next(), Some(("ax", &1)));
+        assert_eq!(it.next(), Some(("ay", &2)));
+        assert_eq!(it.next(), Some(("b", &3)));
+        assert_eq!(it.next(), None);
     }
 
     #[test]
     fn test_radix_tree_into_iter() {
-        let mut t = RadixTree::new();
+        let mut t = StrRadixTree::new();
 
         t.insert("a", 1);
         t.insert("ab", 2);
@@ -639,4 +899,89 @@ mod tests {
         assert_eq!(it.next(), Some(("b".into(), 6)));
         assert_eq!(it.next(), None);
     }
+
+    #[test]
+    fn test_radix_tree_bytes() {
+        let mut t = RadixTree::new();
+
+        t.insert(&[0x00, 0xff], 1);
+        t.insert(&[0x00, 0xff, 0x80], 2);
+        t.insert(&[0x00, 0xfe], 3);
+        t.insert(&[0xe4, 0xbd], 4);
+        t.insert(&[0xe4], 5);
+
+        assert_eq!(t.len(), 5);
+        assert_eq!(t.find(&[0x00, 0xff]), Some(&1));
+        assert_eq!(t.find(&[0x00, 0xff, 0x80]), Some(&2));
+        assert_eq!(t.find(&[0x00]), None);
+        assert_eq!(t.find(&[0xe4]), Some(&5));
+
+        let keys: Vec<_> = t.iter().map(|(k, v)| (k.to_vec(), *v)).collect();
+        assert_eq!(
+            keys,
+            vec![
+                (vec![0x00, 0xfe], 3),
+                (vec![0x00, 0xff], 1),
+                (vec![0x00, 0xff, 0x80], 2),
+                (vec![0xe4], 5),
+                (vec![0xe4, 0xbd], 4),
+            ]
+        );
+
+        assert_eq!(t.delete(&[0x00, 0xff]), Some((vec![0x00, 0xff], 1)));
+        assert_eq!(t.find(&[0x00, 0xff, 0x80]), Some(&2));
+        assert_eq!(t.delete_prefix(&[0x00]), 2);
+        assert_eq!(t.len(), 2);
+    }
+
+    #[test]
+    fn test_str_radix_tree_unicode() {
+        let mut t = StrRadixTree::new();
+
+        // "你" 和 "佬" 的 UTF-8 编码有相同的首字节, 节点会在字符内部分裂
+        t.insert("你好", 1);
+        t.insert("佬", 2);
+        t.insert("你们", 3);
+
+        assert_eq!(t.find("你好"), Some(&1));
+        assert_eq!(t.find("佬"), Some(&2));
+        assert_eq!(t.find("你"), None);
+
+        let mut it = t.iter();
+        assert_eq!(it.next(), Some(("你们", &3)));
+        assert_eq!(it.next(), Some(("你好", &1)));
+        assert_eq!(it.next(), Some(("佬", &2)));
+        assert_eq!(it.next(), None);
+
+        assert_eq!(t.delete("佬"), Some(("佬".into(), 2)));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_radix_tree_serde() {
+        let mut t = StrRadixTree::new();
+        for (i, key) in ["r", "ro", "rom", "roman", "romane", "ruby"]
+            .iter()
+            .enumerate()
+        {
+            t.insert(key, i);
+        }
+
+        let json = serde_json::to_string(&t).unwrap();
+        let t: StrRadixTree<usize> = serde_json::from_str(&json).unwrap();
+        assert_eq!(t.len(), 6);
+        assert_eq!(t.find("roman"), Some(&3));
+        assert_eq!(serde_json::to_string(&t).unwrap(), json);
+
+        let mut t = RadixTree::new();
+        t.insert(b"ab", 1);
+        t.insert(b"a", 2);
+        let json = serde_json::to_string(&t).unwrap();
+        assert_eq!(json, "[[[97],2],[[97,98],1]]");
+        let t: RadixTree<i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            t.iter().collect::<Vec<_>>(),
+            [(&b"a"[..], &2), (&b"ab"[..], &1)]
+        );
+    }
 }
@@ -11,7 +11,9 @@
 //! - 规则3: 红色节点的子节点都是黑色的
 //! - 规则4: 任意节点到叶结点上所有路径上黑色节点数量相等
 
-use std::{fmt::Debug, ptr::NonNull};
+use std::{fmt::Debug, marker::PhantomData, ptr::NonNull};
+
+use crate::visualize::{binary_tree, Dot, ToDot};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Color {
@@ -188,11 +190,38 @@ pub fn rotate<T>(mut node_ptr: NonNull<Node<T>>, dir: &Dir) -> NonNull<Node<T>>
 
 pub struct RBTree<T> {
     root: Option<NonNull<Node<T>>>,
+    length: usize,
 }
 
 impl<T: Ord> RBTree<T> {
     pub fn new() -> Self {
-        Self { root: None }
+        Self {
+            root: None,
+            length: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// 查找树中是否存在 value
+    pub fn contains(&self, value: &T) -> bool {
+        let mut curr_ptr = self.root;
+        while let Some(node_ptr) = curr_ptr {
+            let node = unsafe { node_ptr.as_ref() };
+            match value.cmp(&node.value) {
+                std::cmp::Ordering::Less => curr_ptr = node.left,
+                std::cmp::Ordering::Greater => curr_ptr = node.right,
+                std::cmp::Ordering::Equal => return true,
+            }
+        }
+
+        false
     }
 
     fn rotate2(&mut self, node_ptr: NodePtr<T>, dir: &Dir) {
@@ -209,6 +238,7 @@ impl<T: Ord> RBTree<T> {
                 let node = Box::new(Node::new(value, Color::Black));
                 let ptr = unsafe { NonNull::new_unchecked(Box::into_raw(node)) };
                 self.root = Some(ptr);
+                self.length = 1;
                 return None;
             }
             Some(node) => node,
@@ -253,6 +283,7 @@ impl<T: Ord> RBTree<T> {
         };
 
         self.balance(new_node_ptr);
+        self.length += 1;
         None
     }
 
@@ -263,78 +294,77 @@ impl<T: Ord> RBTree<T> {
         // G 表示 P 的父节点即 N 的祖父节点
         // U 表示 G 的另一个子节点即 P 的兄弟节点 N 的叔父节点
 
-        let mut node = unsafe { node_ptr.as_mut() };
-        while let Some((mut parent_ptr, node_dir)) = &node.parent {
-            let mut parent = unsafe { parent_ptr.as_mut() };
-
-            match parent.color {
-                // P 是黑色, 直接退出
-                Color::Black => return,
-                // P 是红色时, 继续观察 G
-                Color::Red => match &parent.parent {
-                    // 如果 G 为空表示 P 是根节点
-                    // 由于 N 是红色, 根据规则3需要将 P 改为黑色
-                    None => {
-                        parent.color = Color::Black;
-                        return;
-                    }
-                    // 如果 G 存在, 由于 P 是红色则则 G 一定是黑色
-                    Some((mut grand_ptr, parent_dir)) => {
-                        let grand = unsafe { grand_ptr.as_mut() };
-                        debug_assert_eq!(grand.color, Color::Black);
-
-                        // U 是红色, 则将 P 和 U 点改成黑色
-                        // G 改成红色即可然后将 N = G 继续向上修复
-                        //     G(⚫)           G(🔴)
-                        //      /  \            / \
-                        //  P(🔴) U(🔴) -->  P(⚫) U(⚫)
-                        //    /                /
-                        // N(🔴)            N(🔴)
-                        if let Some(mut uncle_ptr) = childof(grand, other(parent_dir)) {
-                            let uncle = unsafe { uncle_ptr.as_mut() };
-                            if uncle.color == Color::Red {
-                                uncle.color = Color::Black;
-                                parent.color = Color::Black;
-                                grand.color = Color::Red;
-                                node = grand;
-                                continue;
-                            }
-                        }
-
-                        // 叔父节点是黑色(可能存在, 也可能不存在)
-                        // 如果 N 和 P 的方向不同, 则需要将 P 左旋或右旋至相同的方向
-                        // 然后将 N 和 P 交换
-                        //     G(⚫)        G(⚫)
-                        //      /             /
-                        //  P(🔴)    -->  N(🔴)
-                        //     \            /
-                        //   N(🔴)       P(🔴)
-                        if node_dir != parent_dir {
-                            self.rotate2(parent_ptr, parent_dir);
-                            parent = node;
-                        }
-
-                        // 如果 N 和 P 都是左子节点: 将 G 右旋
-                        // 如果 N 和 P 都是右子节点: 将 G 左旋
-                        // 接着修改 P 和 G 的颜色即可 G 有可能是根节点旋转完后要重置
-                        //      G(⚫)          P(🔴)           P(⚫)
-                        //       / \            /  \            /   \
-                        //   P(🔴) U(⚫) -> N(🔴) G(⚫) -> N(🔴) G(🔴)
-                        //     /                      \               \
-                        //  N(🔴)                   U(⚫)            U(⚫)
-                        self.rotate2(grand_ptr, &other(parent_dir));
-                        parent.color = Color::Black;
-                        grand.color = Color::Red;
-                        return;
-                    }
-                },
+        // 旋转时会重新获取相关节点的可变引用, 所以循环中只保存指针
+        // 需要修改时再临时解引用, 不跨越旋转持有任何引用
+        while let Some((mut parent_ptr, node_dir)) = unsafe { node_ptr.as_ref() }.parent {
+            let parent = unsafe { parent_ptr.as_mut() };
+
+            // P 是黑色, 直接退出
+            if parent.color == Color::Black {
+                return;
             }
+
+            // P 是红色时, 继续观察 G
+            // 如果 G 为空表示 P 是根节点
+            // 由于 N 是红色, 根据规则3需要将 P 改为黑色
+            let Some((mut grand_ptr, parent_dir)) = parent.parent else {
+                parent.color = Color::Black;
+                return;
+            };
+
+            // 如果 G 存在, 由于 P 是红色则则 G 一定是黑色
+            let grand = unsafe { grand_ptr.as_mut() };
+            debug_assert_eq!(grand.color, Color::Black);
+
+            // U 是红色, 则将 P 和 U 点改成黑色
+            // G 改成红色即可然后将 N = G 继续向上修复
+            //     G(⚫)           G(🔴)
+            //      /  \            / \
+            //  P(🔴) U(🔴) -->  P(⚫) U(⚫)
+            //    /                /
+            // N(🔴)            N(🔴)
+            if let Some(mut uncle_ptr) = *childof(grand, other(&parent_dir)) {
+                let uncle = unsafe { uncle_ptr.as_mut() };
+                if uncle.color == Color::Red {
+                    uncle.color = Color::Black;
+                    parent.color = Color::Black;
+                    grand.color = Color::Red;
+                    node_ptr = grand_ptr;
+                    continue;
+                }
+            }
+
+            // 叔父节点是黑色(可能存在, 也可能不存在)
+            // 如果 N 和 P 的方向不同, 则需要将 P 左旋或右旋至相同的方向
+            // 然后将 N 和 P 交换
+            //     G(⚫)        G(⚫)
+            //      /             /
+            //  P(🔴)    -->  N(🔴)
+            //     \            /
+            //   N(🔴)       P(🔴)
+            if node_dir != parent_dir {
+                self.rotate2(parent_ptr, &parent_dir);
+                parent_ptr = node_ptr;
+            }
+
+            // 如果 N 和 P 都是左子节点: 将 G 右旋
+            // 如果 N 和 P 都是右子节点: 将 G 左旋
+            // 接着修改 P 和 G 的颜色即可 G 有可能是根节点旋转完后要重置
+            //      G(⚫)          P(🔴)           P(⚫)
+            //       / \            /  \            /   \
+            //   P(🔴) U(⚫) -> N(🔴) G(⚫) -> N(🔴) G(🔴)
+            //     /                      \               \
+            //  N(🔴)                   U(⚫)            U(⚫)
+            self.rotate2(grand_ptr, &other(&parent_dir));
+            unsafe { parent_ptr.as_mut() }.color = Color::Black;
+            unsafe { grand_ptr.as_mut() }.color = Color::Red;
+            return;
         }
     }
 
     /// 将一个新节点替换到指定节点的位置
-    fn replace_child(&mut self, mut node: NodePtr<T>, new: Option<NodePtr<T>>) {
-        match (unsafe { node.as_mut() }).parent {
+    fn replace_child(&mut self, node: NodePtr<T>, new: Option<NodePtr<T>>) {
+        match unsafe { node.as_ref() }.parent {
             None => {
                 self.root = new;
                 if let Some(mut new_ptr) = new {
@@ -359,10 +389,18 @@ impl<T: Ord> RBTree<T> {
 
     /// 删除节点
     pub fn delete(&mut self, value: &T) -> Option<T> {
+        let removed = self.delete_node(value);
+        if removed.is_some() {
+            self.length -= 1;
+        }
+        removed
+    }
+
+    fn delete_node(&mut self, value: &T) -> Option<T> {
         // 先在二叉搜索树上找到需要删除的节点
         let mut curr_ptr = self.root;
-        while let Some(mut node_ptr) = curr_ptr {
-            let node = unsafe { node_ptr.as_mut() };
+        while let Some(node_ptr) = curr_ptr {
+            let node = unsafe { node_ptr.as_ref() };
             match value.cmp(&node.value) {
                 std::cmp::Ordering::Less => curr_ptr = node.left,
                 std::cmp::Ordering::Greater => curr_ptr = node.right,
@@ -371,95 +409,81 @@ impl<T: Ord> RBTree<T> {
         }
 
         // 如果对应节点不存在直接返回
-        let node = match curr_ptr {
-            None => return None,
-            Some(mut ptr) => unsafe { ptr.as_mut() },
+        let node_ptr = curr_ptr?;
+
+        // 左右子节点都非空, 找到右子树的最小节点(最左节点)进行替换(可以直接替换数据)
+        // 此时整颗数在颜色上是平衡的, 转而删除这个最左节点
+        // 最左节点不可能有左子树, 则要么是叶子节点, 要么是只有一个红色节点
+        //     N()
+        //    /    \
+        //  ..     ..
+        //        /
+        //      l(🔴)
+        //      /    \
+        //   nil(⚫)  ...
+        let node = unsafe { node_ptr.as_ref() };
+        let target_ptr = match (node.left, node.right) {
+            (Some(_), Some(right_ptr)) => {
+                let mut succ_ptr = right_ptr;
+                while let Some(left_ptr) = unsafe { succ_ptr.as_ref() }.left {
+                    succ_ptr = left_ptr;
+                }
+
+                // 两个节点是不同的分配, 临时的可变引用互不重叠
+                unsafe {
+                    std::mem::swap(
+                        &mut (*node_ptr.as_ptr()).value,
+                        &mut (*succ_ptr.as_ptr()).value,
+                    );
+                }
+                succ_ptr
+            }
+            _ => node_ptr,
         };
 
-        // 经过和中间子节点的替换, 得到一个待删除的叶子节点
-        let leaf_node = match (node.left, node.right) {
-            // 对于叶子节点直接返回
-            (None, None) => node,
+        // 删除分成两个阶段: 先把节点从树上摘下来, 此后树中不再有指向它的指针
+        // 然后再取回节点的所有权并释放, 释放之后不会再访问这个节点
+        self.unlink(target_ptr);
+        let node = unsafe { Box::from_raw(target_ptr.as_ptr()) };
+        Some(node.value)
+    }
 
-            // 只有一个左子节点, 此子节点一定是红色的, 如果是黑色, 左右子树高度肯定会不一致
+    /// 把最多只有一个子节点的节点从树上摘下来并修复平衡, 不会释放节点
+    fn unlink(&mut self, node_ptr: NodePtr<T>) {
+        let node = unsafe { node_ptr.as_ref() };
+        let (color, is_root) = (node.color, node.parent.is_none());
+        debug_assert!(node.left.is_none() || node.right.is_none());
+
+        match node.left.or(node.right) {
+            // 只有一个子节点, 此子节点一定是红色的, 如果是黑色, 左右子树高度肯定会不一致
             // 因此当前节点也只能是黑色的, 则直接替换成子节点然后修改颜色即可(路径上总的黑色保持不变)
             //    N(⚫)           cl(⚫)
             //    /   \       ->   /  \
             // cl(🔴) nil(⚫)    ..   ..
-            (Some(mut left_ptr), None) => {
-                let left_node = unsafe { left_ptr.as_mut() };
-                debug_assert_eq!(node.color, Color::Black);
-                debug_assert_eq!(left_node.color, Color::Red);
-
-                left_node.color = Color::Black;
-                self.replace_child(node.into(), Some(left_ptr));
-                return Some(unsafe { Box::from_raw(node) }.value);
-            }
+            Some(mut child_ptr) => {
+                let child = unsafe { child_ptr.as_mut() };
+                debug_assert_eq!(color, Color::Black);
+                debug_assert_eq!(child.color, Color::Red);
 
-            // 只有一个右子节点此子节点一定是红色的, 如果是黑色, 左右子树高度肯定会不一致
-            // 因此当前节点也只能是黑色的, 则直接替换成子节点然后修改颜色即可(路径上总的黑色保持不变)
-            //     N(⚫)           cr(⚫)
-            //    /    \       ->   /  \
-            // nil(⚫) cr(🔴)     ..   ..
-            (None, Some(mut right_ptr)) => {
-                let right_node = unsafe { right_ptr.as_mut() };
-                debug_assert_eq!(node.color, Color::Black);
-                debug_assert_eq!(right_node.color, Color::Red);
-
-                right_node.color = Color::Black;
-                self.replace_child(node.into(), Some(right_ptr));
-                return Some(unsafe { Box::from_raw(node) }.value);
+                child.color = Color::Black;
+                self.replace_child(node_ptr, Some(child_ptr));
             }
 
-            // 左右子节点都非空, 找到右子树的最小节点(最左节点)进行替换(可以直接替换数据)
-            // 此时整颗数在颜色上是平衡的
-            // 最左节点不可能有左子树, 则要么是叶子节点, 要么是只有一个红色节点(原因同上)
-            // 判断如果右子节点不为空按照以上的步骤处理
-            // 如果右子节点为空则直接返回这个叶子节点
-            //     N()
-            //    /    \
-            //  ..     ..
-            //        /
-            //      l(🔴)
-            //      /    \
-            //   nil(⚫)  ...
-            (Some(_), Some(right_ptr)) => {
-                let mut succ_ptr = right_ptr;
-                while let Some(left_ptr) = unsafe { succ_ptr.as_mut() }.left {
-                    succ_ptr = left_ptr;
-                }
-
-                let succ = unsafe { succ_ptr.as_mut() };
-                std::mem::swap(&mut node.value, &mut succ.value);
-                debug_assert!(succ.left.is_none());
-
-                match succ.right {
-                    None => succ,
-                    Some(mut right_ptr) => {
-                        let right_node = unsafe { right_ptr.as_mut() };
-                        debug_assert_eq!(succ.color, Color::Black);
-                        debug_assert_eq!(right_node.color, Color::Red);
-
-                        right_node.color = Color::Black;
-                        self.replace_child(succ_ptr, Some(right_ptr));
-                        return Some(unsafe { Box::from_raw(succ) }.value);
-                    }
+            // 红色叶子节点或者是根节点直接摘下即可
+            // 黑色叶子节点需要先修复平衡, 修复过程中节点仍然在树上作为 N 参与旋转
+            None => {
+                if color == Color::Black && !is_root {
+                    self.fix_black_leaf(node_ptr);
                 }
+                self.replace_child(node_ptr, None);
             }
-        };
-
-        // 红色叶子节点或者是根节点直接删除即可
-        if leaf_node.color == Color::Red || leaf_node.parent.is_none() {
-            self.replace_child(leaf_node.into(), None);
-            return Some(unsafe { Box::from_raw(leaf_node) }.value);
         }
-
-        self.delete_black_leaf(leaf_node.into())
     }
 
-    /// 删除黑色叶子节点
-    fn delete_black_leaf(&mut self, mut delete_node: NodePtr<T>) -> Option<T> {
-        // 移除这个节点
+    /// 删除黑色叶子节点前修复平衡
+    ///
+    /// 循环中只保存节点指针, 修改颜色时临时解引用, 不跨越旋转持有任何引用
+    fn fix_black_leaf(&mut self, mut n: NodePtr<T>) {
         // N 表示当前节点
         // P 表示当前节点的父节点
         // S 表示当前节点的兄弟节点
@@ -471,10 +495,9 @@ impl<T: Ord> RBTree<T> {
         //    / \    / \
         //   C   D  D  C
 
-        let mut n = unsafe { delete_node.as_mut() };
-        while let Some((mut p, dir)) = n.parent {
+        while let Some((mut p, dir)) = unsafe { n.as_ref() }.parent {
             // N 在传入时是一个黑色叶子节点, 在循环内部 N 有可能会被替换, 但是被替换的也是黑色节点
-            let (mut s, mut c, mut d) = sibling(n);
+            let (mut s, mut c, mut d) = sibling(unsafe { n.as_ref() });
 
             if colorof(s) == Color::Red {
                 // 对应 wiki 中的 Case_D3
@@ -576,12 +599,56 @@ impl<T: Ord> RBTree<T> {
             //       /   \
             //    C(⚫) D(⚫)
             unsafe { s.unwrap().as_mut() }.color = Color::Red;
-            n = unsafe { p.as_mut() };
+            n = p;
         }
+    }
+}
+
+impl<T> RBTree<T> {
+    /// 释放所有节点
+    pub fn clear(&mut self) {
+        let mut stack: Vec<_> = self.root.take().into_iter().collect();
+        while let Some(ptr) = stack.pop() {
+            let node = unsafe { Box::from_raw(ptr.as_ptr()) };
+            stack.extend(node.left);
+            stack.extend(node.right);
+        }
+        self.length = 0;
+    }
+
+    /// 中序遍历引用迭代器
+    pub fn iter(&self) -> Iter<'_, T> {
+        let mut iter = Iter {
+            stack: vec![],
+            _marker: PhantomData,
+        };
+        iter.push_left(self.root);
+        iter
+    }
+}
+
+/// 中序遍历引用迭代器
+pub struct Iter<'a, T> {
+    stack: Vec<NodePtr<T>>,
+    _marker: PhantomData<&'a Node<T>>,
+}
+
+impl<T> Iter<'_, T> {
+    fn push_left(&mut self, mut link: Option<NodePtr<T>>) {
+        while let Some(ptr) = link {
+            self.stack.push(ptr);
+            link = unsafe { ptr.as_ref() }.left;
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
 
-        self.replace_child(delete_node, None);
-        let delete_node = unsafe { delete_node.as_mut() };
-        Some(unsafe { Box::from_raw(delete_node) }.value)
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = unsafe { self.stack.pop()?.as_ref() };
+        self.push_left(node.right);
+        Some(&node.value)
     }
 }
 
@@ -591,6 +658,47 @@ impl<T: Ord> Default for RBTree<T> {
     }
 }
 
+impl<T> Drop for RBTree<T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+/// 深拷贝, 保持原有的形状和颜色
+impl<T: Clone> Clone for RBTree<T> {
+    fn clone(&self) -> Self {
+        fn copy<T: Clone>(node_ptr: NodePtr<T>, parent: Option<(NodePtr<T>, Dir)>) -> NodePtr<T> {
+            let node = unsafe { node_ptr.as_ref() };
+            let mut new_node = Box::new(Node::new(node.value.clone(), node.color));
+            new_node.parent = parent;
+
+            let new_ptr = unsafe { NonNull::new_unchecked(Box::into_raw(new_node)) };
+            let left = node.left.map(|left| copy(left, Some((new_ptr, Dir::Left))));
+            let right = node
+                .right
+                .map(|right| copy(right, Some((new_ptr, Dir::Right))));
+
+            let new_node = unsafe { &mut *new_ptr.as_ptr() };
+            new_node.left = left;
+            new_node.right = right;
+            new_ptr
+        }
+
+        Self {
+            root: self.root.map(|root| copy(root, None)),
+            length: self.length,
+        }
+    }
+}
+
+// 树独占所有节点, 和 Box<T> 一样可以跨线程转移和共享
+unsafe impl<T: Send> Send for RBTree<T> {}
+unsafe impl<T: Sync> Sync for RBTree<T> {}
+
+// 迭代器只持有节点的共享引用, 等价于 &T
+unsafe impl<T: Sync> Send for Iter<'_, T> {}
+unsafe impl<T: Sync> Sync for Iter<'_, T> {}
+
 impl<T: Debug> RBTree<T> {
     pub fn depth(&self) -> usize {
         match self.root {
@@ -603,6 +711,83 @@ impl<T: Debug> RBTree<T> {
     }
 }
 
+impl<T: Ord + Debug> RBTree<T> {
+    /// 逐条检查红黑树的性质, 同时检查二叉搜索树的顺序, 父指针和长度, 不满足时 panic
+    ///
+    /// 返回黑高(空节点记为 1), 规则1 由 [`Color`] 类型保证, 规则2 通过把空节点的黑高记为 1 体现
+    pub fn validate(&self) -> usize {
+        let mut count = 0;
+        let height = match self.root {
+            None => 1,
+            Some(root) => {
+                assert!(unsafe { root.as_ref() }.parent.is_none(), "root has parent");
+                validate(root, None, None, &mut count)
+            }
+        };
+
+        assert_eq!(count, self.length, "length mismatch");
+        height
+    }
+}
+
+/// 检查以 node 为根的子树, 子树中的值都必须在 (lo, hi) 范围内
+fn validate<T: Ord + Debug>(
+    node_ptr: NodePtr<T>,
+    lo: Option<&T>,
+    hi: Option<&T>,
+    count: &mut usize,
+) -> usize {
+    let node = unsafe { node_ptr.as_ref() };
+    *count += 1;
+
+    assert!(
+        lo.is_none_or(|lo| lo < &node.value) && hi.is_none_or(|hi| &node.value < hi),
+        "{:?} out of order",
+        node.value
+    );
+
+    let mut heights = [1, 1];
+    for (i, dir) in [Dir::Left, Dir::Right].into_iter().enumerate() {
+        // 规则2: 空节点是黑色的
+        let Some(child_ptr) = *childof(node, dir) else {
+            continue;
+        };
+
+        let child = unsafe { child_ptr.as_ref() };
+        assert_eq!(
+            child.parent,
+            Some((node_ptr, dir)),
+            "parent of {:?} is broken",
+            child.value
+        );
+
+        // 规则3: 红色节点的子节点都是黑色的
+        assert!(
+            node.color == Color::Black || child.color == Color::Black,
+            "red node {:?} has red child {:?}",
+            node.value,
+            child.value
+        );
+
+        heights[i] = match dir {
+            Dir::Left => validate(child_ptr, lo, Some(&node.value), count),
+            Dir::Right => validate(child_ptr, Some(&node.value), hi, count),
+        };
+    }
+
+    // 规则4: 任意节点到叶结点上所有路径上黑色节点数量相等
+    assert_eq!(
+        heights[0], heights[1],
+        "black height differs at {:?}",
+        node.value
+    );
+
+    match node.color {
+        Color::Black => heights[0] + 1,
+        Color::Red => heights[0],
+    }
+}
+
 impl<T: Debug> Debug for RBTree<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut queue = match self.root {
@@ -635,6 +820,33 @@ impl<T: Debug> Debug for RBTree<T> {
     }
 }
 
+impl<T: Debug> ToDot for RBTree<T> {
+    /// 节点按红黑颜色填充
+    fn to_dot(&self) -> String {
+        let mut dot = Dot::digraph("RBTree");
+        dot.stmt("node [shape=circle, style=filled, fontcolor=white]");
+        if let Some(root) = self.root {
+            binary_tree(
+                &mut dot,
+                root,
+                &|node: NodePtr<T>| {
+                    let node = unsafe { node.as_ref() };
+                    (node.left, node.right)
+                },
+                &|dot: &mut Dot, node: NodePtr<T>| {
+                    let node = unsafe { node.as_ref() };
+                    let color = match node.color {
+                        Color::Red => "red",
+                        Color::Black => "black",
+                    };
+                    dot.node(&format!("{:?}", node.value), &[("fillcolor", color)])
+                },
+            );
+        }
+        dot.finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -678,13 +890,13 @@ mod tests {
         let mut t = RBTree::new();
         for i in 1..=10000 {
             assert!(t.insert(i).is_none());
-            t.depth();
+            t.validate();
         }
 
         let mut t = RBTree::new();
         for i in (1..1000).rev() {
             assert!(t.insert(i).is_none());
-            t.depth();
+            t.validate();
         }
 
         use rand::Rng;
@@ -693,7 +905,7 @@ mod tests {
         let mut t = RBTree::new();
         for _ in 1..1000 {
             t.insert(rng.gen_range(1..10000));
-            t.depth();
+            t.validate();
         }
     }
 
@@ -711,7 +923,7 @@ mod tests {
         t.insert(2848);
         t.insert(3339);
 
-        t.depth();
+        t.validate();
     }
 
     #[test]
@@ -739,29 +951,29 @@ mod tests {
 
         for i in 1..=1000 {
             assert!(t.insert(i).is_none());
-            t.depth();
+            t.validate();
         }
 
         for i in (1..=1000).rev() {
             assert!(t.delete(&i).is_some());
-            t.depth();
+            t.validate();
         }
 
         let mut t = RBTree::new();
 
         for i in 1..=10000 {
             t.insert(i);
-            t.depth();
+            t.validate();
         }
 
         for i in 1..=500 {
             t.delete(&(i * 10));
-            t.depth();
+            t.validate();
         }
 
         for i in (500..=1000).rev() {
             t.delete(&(i * 10));
-            t.depth();
+            t.validate();
         }
     }
 
@@ -787,4 +999,88 @@ mod tests {
             assert_eq!(t.depth(), i);
         }
     }
+
+    #[test]
+    fn test_rb_tree_iter() {
+        let mut t = RBTree::new();
+        for i in (0..100).rev() {
+            assert!(t.insert(i * 7 % 100).is_none());
+        }
+        assert!(t.insert(0).is_some());
+        assert_eq!(t.len(), 100);
+        assert!(t.iter().copied().eq(0..100));
+
+        for i in 0..50 {
+            assert!(t.delete(&(i * 2)).is_some());
+        }
+        assert_eq!(t.len(), 50);
+        assert!(t.iter().copied().eq((0..50).map(|i| i * 2 + 1)));
+
+        t.clear();
+        assert!(t.is_empty());
+        assert_eq!(t.iter().next(), None);
+    }
+
+    #[test]
+    fn test_rb_tree_validate() {
+        use rand::Rng;
+        let mut rng = rand::thread_rng();
+
+        let mut t = RBTree::new();
+        assert_eq!(t.validate(), 1);
+        for _ in 0..2000 {
+            let x = rng.gen_range(0..500);
+            if rng.gen_bool(0.6) {
+                t.insert(x);
+            } else {
+                t.delete(&x);
+            }
+            t.validate();
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "has red child")]
+    fn test_rb_tree_validate_red_red() {
+        let mut t = RBTree::new();
+        t.insert(2);
+        t.insert(1);
+        t.insert(3);
+
+        // 把根节点和左子节点都改成红色
+        let root = unsafe { t.root.unwrap().as_mut() };
+        root.color = Color::Red;
+        unsafe { root.left.unwrap().as_mut() }.color = Color::Red;
+        t.validate();
+    }
+
+    #[test]
+    fn test_rb_tree_clone() {
+        let mut t = RBTree::new();
+        for i in 0..100 {
+            t.insert(i * 7 % 100);
+        }
+        for i in 0..30 {
+            t.delete(&(i * 3));
+        }
+
+        let c = t.clone();
+        c.validate();
+        assert_eq!(c.len(), t.len());
+        assert_eq!(format!("{:?}", c), format!("{:?}", t));
+
+        // 修改原树不影响拷贝
+        t.clear();
+        assert!(c
+            .iter()
+            .copied()
+            .eq((0..100).filter(|i| i % 3 != 0 || *i >= 90)));
+    }
+
+    #[test]
+    fn test_rb_tree_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<RBTree<String>>();
+        assert_send_sync::<Iter<'_, String>>();
+    }
 }
@@ -0,0 +1,436 @@
+//! 环形缓冲区和双端队列
+//!
+//! - [wikipedia - Circular buffer](https://en.wikipedia.org/wiki/Circular_buffer)
+//! - [std::collections::VecDeque](https://doc.rust-lang.org/std/collections/struct.VecDeque.html)
+//!
+//! 用一段连续的数组首尾相接组成一个环, 记录头部下标 head 和元素数量 len
+//! 第 i 个元素保存在 `(head + i) % cap` 的位置, 这样在两端插入删除都是 O(1) 的
+//!
+//! - [`RingBuffer`]: 容量固定的环形缓冲区, 满了之后可以选择拒绝写入或者覆盖最旧的数据
+//! - [`ArrayDeque`]: 容量自动增长的双端队列, 直接使用 `std::alloc` 管理内存
+//!   扩容时把绕回到数组开头的部分和前半部分一起搬到新数组的开头
+
+use std::alloc::{self, Layout};
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr::{self, NonNull};
+
+/// 容量固定的环形缓冲区
+pub struct RingBuffer<T> {
+    buf: Box<[Option<T>]>,
+    head: usize,
+    len: usize,
+    overwrite: bool,
+}
+
+impl<T> RingBuffer<T> {
+    /// 创建容量为 capacity 的缓冲区
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be positive");
+        Self {
+            buf: (0..capacity).map(|_| None).collect(),
+            head: 0,
+            len: 0,
+            overwrite: false,
+        }
+    }
+
+    /// 开启覆盖模式后, 缓冲区满了再写入会覆盖最旧的数据
+    pub fn overwrite(mut self, enable: bool) -> Self {
+        self.overwrite = enable;
+        self
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == self.buf.len()
+    }
+
+    fn index(&self, i: usize) -> usize {
+        (self.head + i) % self.buf.len()
+    }
+
+    /// 在尾部写入数据
+    ///
+    /// 缓冲区没满时返回 `Ok(None)`, 满了时如果开启了覆盖模式则返回 `Ok(被覆盖的数据)`, 否则返回 `Err(value)`
+    pub fn push_back(&mut self, value: T) -> Result<Option<T>, T> {
+        if self.is_full() {
+            if !self.overwrite {
+                return Err(value);
+            }
+
+            // 最旧的数据在 head, 覆盖它之后 head 向后移动一位
+            let old = self.buf[self.head].replace(value);
+            self.head = self.index(1);
+            return Ok(old);
+        }
+
+        let tail = self.index(self.len);
+        self.buf[tail] = Some(value);
+        self.len += 1;
+        Ok(None)
+    }
+
+    /// 从头部取出最旧的数据
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let value = self.buf[self.head].take();
+        self.head = self.index(1);
+        self.len -= 1;
+        value
+    }
+
+    /// 第 i 个数据, 0 是最旧的
+    pub fn get(&self, i: usize) -> Option<&T> {
+        if i >= self.len {
+            return None;
+        }
+        self.buf[self.index(i)].as_ref()
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.len.checked_sub(1).and_then(|i| self.get(i))
+    }
+
+    /// 从最旧到最新遍历
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        (0..self.len).map(|i| self.buf[self.index(i)].as_ref().expect("slot must exist"))
+    }
+}
+
+/// 容量自动增长的双端队列
+pub struct ArrayDeque<T> {
+    ptr: NonNull<T>,
+    cap: usize,
+    head: usize,
+    len: usize,
+    _marker: PhantomData<T>,
+}
+
+unsafe impl<T: Send> Send for ArrayDeque<T> {}
+unsafe impl<T: Sync> Sync for ArrayDeque<T> {}
+
+impl<T> ArrayDeque<T> {
+    pub fn new() -> Self {
+        // 零大小类型不需要分配内存, 容量视为无限大
+        let cap = if mem::size_of::<T>() == 0 {
+            usize::MAX
+        } else {
+            0
+        };
+        Self {
+            ptr: NonNull::dangling(),
+            cap,
+            head: 0,
+            len: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut deque = Self::new();
+        if deque.cap < capacity {
+            deque.grow(capacity);
+        }
+        deque
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// 第 i 个元素在数组中的位置
+    fn index(&self, i: usize) -> usize {
+        let idx = self.head.wrapping_add(i);
+        if idx >= self.cap {
+            idx - self.cap
+        } else {
+            idx
+        }
+    }
+
+    /// 扩容到 new_cap 并把所有元素搬到新数组的开头
+    fn grow(&mut self, new_cap: usize) {
+        assert!(mem::size_of::<T>() != 0, "capacity overflow");
+        let layout = Layout::array::<T>(new_cap).expect("capacity overflow");
+        let new_ptr = unsafe { alloc::alloc(layout) as *mut T };
+        let new_ptr = match NonNull::new(new_ptr) {
+            Some(p) => p,
+            None => alloc::handle_alloc_error(layout),
+        };
+
+        if self.cap > 0 {
+            let (a, b) = self.as_slices();
+            let (a_len, b_len) = (a.len(), b.len());
+            unsafe {
+                ptr::copy_nonoverlapping(a.as_ptr(), new_ptr.as_ptr(), a_len);
+                ptr::copy_nonoverlapping(b.as_ptr(), new_ptr.as_ptr().add(a_len), b_len);
+                alloc::dealloc(
+                    self.ptr.as_ptr() as *mut u8,
+                    Layout::array::<T>(self.cap).unwrap(),
+                );
+            }
+        }
+
+        self.ptr = new_ptr;
+        self.cap = new_cap;
+        self.head = 0;
+    }
+
+    fn reserve_one(&mut self) {
+        if self.len == self.cap {
+            self.grow((self.cap * 2).max(4));
+        }
+    }
+
+    pub fn push_back(&mut self, value: T) {
+        self.reserve_one();
+        let tail = self.index(self.len);
+        unsafe { ptr::write(self.ptr.as_ptr().add(tail), value) };
+        self.len += 1;
+    }
+
+    pub fn push_front(&mut self, value: T) {
+        self.reserve_one();
+        self.head = self.index(self.cap - 1);
+        unsafe { ptr::write(self.ptr.as_ptr().add(self.head), value) };
+        self.len += 1;
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+
+        self.len -= 1;
+        let tail = self.index(self.len);
+        unsafe { Some(ptr::read(self.ptr.as_ptr().add(tail))) }
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let value = unsafe { ptr::read(self.ptr.as_ptr().add(self.head)) };
+        self.head = self.index(1);
+        self.len -= 1;
+        Some(value)
+    }
+
+    pub fn get(&self, i: usize) -> Option<&T> {
+        if i >= self.len {
+            return None;
+        }
+        unsafe { Some(&*self.ptr.as_ptr().add(self.index(i))) }
+    }
+
+    pub fn get_mut(&mut self, i: usize) -> Option<&mut T> {
+        if i >= self.len {
+            return None;
+        }
+        unsafe { Some(&mut *self.ptr.as_ptr().add(self.index(i))) }
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.len.checked_sub(1).and_then(|i| self.get(i))
+    }
+
+    /// 按顺序返回两段连续的内存, 第二段是绕回到数组开头的部分
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let first = self.len.min(self.cap - self.head);
+        unsafe {
+            let a = std::slice::from_raw_parts(self.ptr.as_ptr().add(self.head), first);
+            let b = std::slice::from_raw_parts(self.ptr.as_ptr(), self.len - first);
+            (a, b)
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        let (a, b) = self.as_slices();
+        Iter {
+            a: a.iter(),
+            b: b.iter(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        while self.pop_back().is_some() {}
+    }
+}
+
+impl<T> Default for ArrayDeque<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for ArrayDeque<T> {
+    fn drop(&mut self) {
+        self.clear();
+        if mem::size_of::<T>() != 0 && self.cap > 0 {
+            unsafe {
+                alloc::dealloc(
+                    self.ptr.as_ptr() as *mut u8,
+                    Layout::array::<T>(self.cap).unwrap(),
+                );
+            }
+        }
+    }
+}
+
+/// 双端队列迭代器, 依次遍历两段连续内存
+pub struct Iter<'a, T> {
+    a: std::slice::Iter<'a, T>,
+    b: std::slice::Iter<'a, T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.a.next().or_else(|| self.b.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.a.len() + self.b.len();
+        (n, Some(n))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.b.next_back().or_else(|| self.a.next_back())
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+impl<'a, T> IntoIterator for &'a ArrayDeque<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+    use std::collections::VecDeque;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_ring_buffer() {
+        let mut rb = RingBuffer::new(3);
+        assert_eq!(rb.push_back(1), Ok(None));
+        assert_eq!(rb.push_back(2), Ok(None));
+        assert_eq!(rb.push_back(3), Ok(None));
+        assert!(rb.is_full());
+        assert_eq!(rb.push_back(4), Err(4));
+
+        assert_eq!(rb.pop_front(), Some(1));
+        assert_eq!(rb.push_back(4), Ok(None));
+        assert_eq!(rb.iter().copied().collect::<Vec<_>>(), vec![2, 3, 4]);
+
+        let mut rb = rb.overwrite(true);
+        assert_eq!(rb.push_back(5), Ok(Some(2)));
+        assert_eq!(rb.push_back(6), Ok(Some(3)));
+        assert_eq!(rb.iter().rev().copied().collect::<Vec<_>>(), vec![6, 5, 4]);
+        assert_eq!((rb.front(), rb.back()), (Some(&4), Some(&6)));
+
+        while rb.pop_front().is_some() {}
+        assert!(rb.is_empty());
+        assert_eq!(rb.back(), None);
+    }
+
+    #[test]
+    fn test_array_deque() {
+        let mut rng = rand::thread_rng();
+        let mut deque = ArrayDeque::new();
+        let mut expect = VecDeque::new();
+
+        for i in 0..10000 {
+            match rng.gen_range(0..5) {
+                0 | 1 => {
+                    deque.push_back(i);
+                    expect.push_back(i);
+                }
+                2 => {
+                    deque.push_front(i);
+                    expect.push_front(i);
+                }
+                3 => assert_eq!(deque.pop_back(), expect.pop_back()),
+                _ => assert_eq!(deque.pop_front(), expect.pop_front()),
+            }
+
+            assert_eq!(deque.len(), expect.len());
+            assert_eq!(deque.front(), expect.front());
+            assert_eq!(deque.back(), expect.back());
+        }
+
+        assert!(deque.iter().eq(expect.iter()));
+        assert!(deque.iter().rev().eq(expect.iter().rev()));
+        let (a, b) = deque.as_slices();
+        assert_eq!(a.len() + b.len(), deque.len());
+
+        if let Some(v) = deque.get_mut(0) {
+            *v = -1;
+        }
+        assert_eq!(deque.get(0).copied(), expect.front().map(|_| -1));
+    }
+
+    #[test]
+    fn test_array_deque_drop() {
+        // 元素会在 pop 或者队列销毁时被正确释放
+        let rc = Rc::new(());
+        let mut deque = ArrayDeque::with_capacity(2);
+        for _ in 0..10 {
+            deque.push_front(rc.clone());
+            deque.push_back(rc.clone());
+        }
+        drop(deque.pop_front());
+        assert_eq!(Rc::strong_count(&rc), 20);
+        drop(deque);
+        assert_eq!(Rc::strong_count(&rc), 1);
+
+        let mut zst = ArrayDeque::new();
+        for _ in 0..100 {
+            zst.push_front(());
+        }
+        assert_eq!(zst.len(), 100);
+        assert_eq!(zst.iter().count(), 100);
+    }
+}
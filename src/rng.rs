@@ -0,0 +1,288 @@
+//! 伪随机数生成器
+//!
+//! - [wikipedia - Xorshift](https://en.wikipedia.org/wiki/Xorshift)
+//! - [PCG, A Family of Better Random Number Generators](https://www.pcg-random.org/)
+//! - [wikipedia - Permuted congruential generator](https://en.wikipedia.org/wiki/Permuted_congruential_generator)
+//!
+//! 树堆的优先级和跳表的层数都依赖随机数, 使用 `thread_rng` 时每次运行的结构都不同, 出错时很难复现
+//! 这里实现两个小巧的生成器, 都实现了 [`RngCore`] 和 [`SeedableRng`], 可以直接使用 [`rand::Rng`] 的所有方法,
+//! 相同的种子总是产生相同的序列, 传给 [`crate::treap::Treap::with_rng`] 和
+//! [`crate::skip_list::SkipList::with_rng`] 之后测试就是可以复现的
+//!
+//! ## xorshift64*
+//!
+//! 状态是一个非零的 u64, 每次通过三次移位异或得到下一个状态, 每一步都是 GF(2) 上的可逆线性变换,
+//! 选取合适的移位量 (12, 25, 27) 时非零状态会遍历全部 2^64-1 个值
+//! 线性变换的低位质量较差, 输出前再乘以一个奇数常数打乱, 所以叫做 xorshift*
+//!
+//! 状态为 0 时永远输出 0, 所以种子为 0 时替换成一个固定的非零值
+//!
+//! ## PCG32
+//!
+//! 状态是一个 64 位的线性同余生成器 `state = state * a + inc`, 周期为 2^64, inc 必须是奇数,
+//! 不同的 inc 对应不同的序列(stream)
+//! LCG 的高位质量好而低位周期很短, 输出时不直接返回状态, 而是对状态做一次置换(XSH RR):
+//!
+//! ```text
+//! x   = ((state >> 18) ^ state) >> 27   // 把高位异或到中间, 取 32 位
+//! rot = state >> 59                     // 最高 5 位决定旋转量
+//! out = x.rotate_right(rot)
+//! ```
+//!
+//! 旋转量由质量最好的最高位决定, 每个输出都只依赖于状态的高位部分
+
+use rand::{Error, RngCore, SeedableRng};
+
+/// splitmix64 的一步, 把相近的种子(比如 0, 1, 2)打散成看起来没有关系的值
+///
+/// ```
+/// use impx::rng::splitmix64;
+///
+/// assert_eq!(splitmix64(0), 0xe220a8397b1dcdaf);
+/// ```
+pub fn splitmix64(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+/// 用 next 生成的 u64 按小端序填充字节
+fn fill_bytes_via<F: FnMut() -> u64>(dest: &mut [u8], mut next: F) {
+    for chunk in dest.chunks_mut(8) {
+        let bytes = next().to_le_bytes();
+        chunk.copy_from_slice(&bytes[..chunk.len()]);
+    }
+}
+
+/// xorshift64* 生成器
+///
+/// ```
+/// use impx::rng::XorShift64Star;
+/// use rand::{Rng, SeedableRng};
+///
+/// let mut a = XorShift64Star::seed_from_u64(42);
+/// let mut b = XorShift64Star::seed_from_u64(42);
+/// let x: u32 = a.gen_range(0..100);
+/// assert_eq!(x, b.gen_range(0..100));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XorShift64Star {
+    state: u64,
+}
+
+impl XorShift64Star {
+    /// 种子为 0 时使用的状态
+    const ZERO_SEED: u64 = 0x853c49e6748fea9b;
+
+    /// 直接使用 seed 作为初始状态
+    pub fn new(seed: u64) -> Self {
+        let state = if seed == 0 { Self::ZERO_SEED } else { seed };
+        Self { state }
+    }
+}
+
+impl RngCore for XorShift64Star {
+    fn next_u32(&mut self) -> u32 {
+        // 高位质量更好
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.state = x;
+        x.wrapping_mul(0x2545f4914f6cdd1d)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        fill_bytes_via(dest, || self.next_u64());
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for XorShift64Star {
+    type Seed = [u8; 8];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::new(u64::from_le_bytes(seed))
+    }
+
+    /// 先经过 splitmix64 打散, 相邻的种子也会得到差别很大的初始状态
+    fn seed_from_u64(seed: u64) -> Self {
+        Self::new(splitmix64(seed))
+    }
+}
+
+/// PCG32 (XSH RR 64/32) 生成器
+///
+/// ```
+/// use impx::rng::Pcg32;
+/// use rand::RngCore;
+///
+/// // pcg32-demo 中种子 42, 序列 54 的前几个输出
+/// let mut rng = Pcg32::new(42, 54);
+/// assert_eq!(rng.next_u32(), 0xa15c02b7);
+/// assert_eq!(rng.next_u32(), 0x7b47f409);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pcg32 {
+    state: u64,
+    inc: u64,
+}
+
+impl Pcg32 {
+    const MULTIPLIER: u64 = 6364136223846793005;
+    /// [`SeedableRng::seed_from_u64`] 使用的序列
+    const DEFAULT_STREAM: u64 = 0xda3e39cb94b95bdb;
+
+    /// 指定初始状态和序列编号, 和 PCG 参考实现的 `pcg32_srandom_r` 相同
+    pub fn new(seed: u64, stream: u64) -> Self {
+        let mut rng = Self {
+            state: 0,
+            inc: (stream << 1) | 1,
+        };
+        rng.step();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.step();
+        rng
+    }
+
+    fn step(&mut self) {
+        self.state = self
+            .state
+            .wrapping_mul(Self::MULTIPLIER)
+            .wrapping_add(self.inc);
+    }
+}
+
+impl RngCore for Pcg32 {
+    fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.step();
+        let x = (((old >> 18) ^ old) >> 27) as u32;
+        x.rotate_right((old >> 59) as u32)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let lo = self.next_u32() as u64;
+        let hi = self.next_u32() as u64;
+        hi << 32 | lo
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        fill_bytes_via(dest, || self.next_u64());
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for Pcg32 {
+    /// 前 8 个字节是初始状态, 后 8 个字节是序列编号
+    type Seed = [u8; 16];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        let (state, stream) = seed.split_at(8);
+        Self::new(
+            u64::from_le_bytes(state.try_into().unwrap()),
+            u64::from_le_bytes(stream.try_into().unwrap()),
+        )
+    }
+
+    fn seed_from_u64(seed: u64) -> Self {
+        Self::new(seed, Self::DEFAULT_STREAM)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_reference_output() {
+        let mut rng = Pcg32::new(42, 54);
+        let out: Vec<u32> = (0..6).map(|_| rng.next_u32()).collect();
+        assert_eq!(
+            out,
+            [0xa15c02b7, 0x7b47f409, 0xba1d3330, 0x83d2f293, 0xbfa4784b, 0xcbed606e]
+        );
+
+        let mut rng = XorShift64Star::new(1);
+        let out: Vec<u64> = (0..4).map(|_| rng.next_u64()).collect();
+        assert_eq!(
+            out,
+            [
+                0x47e4ce4b896cdd1d,
+                0xabcfa6a8e079651d,
+                0xb9d10d8feb731f57,
+                0x4db418a0bb1b019d
+            ]
+        );
+
+        // 种子为 0 时不会卡在全 0 状态
+        let mut rng = XorShift64Star::new(0);
+        assert_ne!(rng.next_u64(), 0);
+        assert_eq!(XorShift64Star::from_seed([0; 8]), XorShift64Star::new(0));
+    }
+
+    #[test]
+    fn test_seeding() {
+        fn check<R: RngCore + SeedableRng + Clone>() {
+            let mut a = R::seed_from_u64(7);
+            let mut b = R::seed_from_u64(7);
+            let mut c = R::seed_from_u64(8);
+            let xs: Vec<u64> = (0..100).map(|_| a.next_u64()).collect();
+            let ys: Vec<u64> = (0..100).map(|_| b.next_u64()).collect();
+            let zs: Vec<u64> = (0..100).map(|_| c.next_u64()).collect();
+            assert_eq!(xs, ys);
+            assert_ne!(xs, zs);
+
+            // fill_bytes 和 next_u64 的小端序字节一致, 不是 8 的倍数时截断最后一个
+            let mut a = R::seed_from_u64(1);
+            let mut b = a.clone();
+            let mut buf = [0u8; 13];
+            a.fill_bytes(&mut buf);
+            let expect: Vec<u8> = (0..2).flat_map(|_| b.next_u64().to_le_bytes()).collect();
+            assert_eq!(buf[..], expect[..13]);
+        }
+
+        check::<XorShift64Star>();
+        check::<Pcg32>();
+
+        // 同一个种子的不同序列
+        assert_ne!(Pcg32::new(1, 1).next_u64(), Pcg32::new(1, 2).next_u64());
+        let seed: [u8; 16] = std::array::from_fn(|i| i as u8);
+        assert_eq!(
+            Pcg32::from_seed(seed),
+            Pcg32::new(0x0706050403020100, 0x0f0e0d0c0b0a0908)
+        );
+    }
+
+    #[test]
+    fn test_uniformity() {
+        // 每个桶的期望是 10000, 偏离超过 5% 的概率极小
+        fn check<R: Rng>(mut rng: R) {
+            let mut buckets = [0u32; 16];
+            for _ in 0..160000 {
+                buckets[rng.gen_range(0..16)] += 1;
+            }
+            assert!(buckets.iter().all(|&c| (9500..10500).contains(&c)));
+
+            let ones: u32 = (0..10000).map(|_| rng.next_u64().count_ones()).sum();
+            assert!((310000..330000).contains(&ones));
+        }
+
+        check(XorShift64Star::seed_from_u64(0x5eed));
+        check(Pcg32::seed_from_u64(0x5eed));
+    }
+}
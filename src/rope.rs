@@ -0,0 +1,434 @@
+//! 绳索(Rope)
+//!
+//! - [wikipedia - Rope](https://en.wikipedia.org/wiki/Rope_(data_structure))
+//! - [Ropes: an Alternative to Strings](https://www.cs.tufts.edu/comp/150FP/archive/hans-boehm/ropes.pdf)
+//!
+//! 用二叉树保存一个很长的字符串, 叶子节点保存一小段字符串(chunk), 中序遍历所有叶子就得到完整的字符串
+//! 每个内部节点记录子树中的字符数量, 按字符下标查找时可以像二叉搜索树一样向下查找
+//!
+//! 所有编辑操作都基于两个基本操作:
+//!
+//! - 拼接 concat: 创建一个新的内部节点, 左右子树分别是两个 rope, O(1)
+//! - 分割 split: 沿着下标向下查找, 把路径上的节点拆分成左右两部分, O(log n)
+//!
+//! 插入 = 分割 + 两次拼接, 删除 = 两次分割 + 拼接
+//!
+//! 反复拼接会让树变得不平衡, 当树的深度超过叶子数量对数的两倍时, 收集所有叶子重新构建一棵平衡的树
+//! 这里所有下标都是字符(char)下标而不是字节下标
+
+use std::fmt;
+use std::ops::Range;
+
+/// 每个叶子节点最多保存的字符数量
+const MAX_CHUNK: usize = 64;
+
+enum Node {
+    Leaf {
+        text: String,
+        chars: usize,
+    },
+    Branch {
+        left: Box<Node>,
+        right: Box<Node>,
+        chars: usize,
+        depth: usize,
+        leaves: usize,
+    },
+}
+
+type Link = Option<Box<Node>>;
+
+impl Node {
+    fn leaf(text: String) -> Node {
+        let chars = text.chars().count();
+        Node::Leaf { text, chars }
+    }
+
+    fn chars(&self) -> usize {
+        match self {
+            Node::Leaf { chars, .. } | Node::Branch { chars, .. } => *chars,
+        }
+    }
+
+    fn depth(&self) -> usize {
+        match self {
+            Node::Leaf { .. } => 0,
+            Node::Branch { depth, .. } => *depth,
+        }
+    }
+
+    fn leaves(&self) -> usize {
+        match self {
+            Node::Leaf { .. } => 1,
+            Node::Branch { leaves, .. } => *leaves,
+        }
+    }
+}
+
+/// 把字符串切分成若干个 chunk 并构建平衡的树
+fn build(s: &str) -> Link {
+    let mut chunks = vec![];
+    let mut chunk = String::new();
+    for (i, c) in s.chars().enumerate() {
+        if i > 0 && i % MAX_CHUNK == 0 {
+            chunks.push(std::mem::take(&mut chunk));
+        }
+        chunk.push(c);
+    }
+    if !chunk.is_empty() {
+        chunks.push(chunk);
+    }
+
+    build_balanced(chunks.into_iter().map(Node::leaf).collect())
+}
+
+/// 用有序的叶子节点构建平衡的树
+fn build_balanced(mut leaves: Vec<Node>) -> Link {
+    fn helper(leaves: &mut Vec<Node>, n: usize) -> Box<Node> {
+        if n == 1 {
+            return Box::new(leaves.pop().expect("leaves must not be empty"));
+        }
+
+        // 叶子是逆序弹出的, 所以先构建右子树
+        let right = helper(leaves, n - n / 2);
+        let left = helper(leaves, n / 2);
+        branch(left, right)
+    }
+
+    if leaves.is_empty() {
+        return None;
+    }
+
+    let n = leaves.len();
+    Some(helper(&mut leaves, n))
+}
+
+fn branch(left: Box<Node>, right: Box<Node>) -> Box<Node> {
+    Box::new(Node::Branch {
+        chars: left.chars() + right.chars(),
+        depth: left.depth().max(right.depth()) + 1,
+        leaves: left.leaves() + right.leaves(),
+        left,
+        right,
+    })
+}
+
+/// 拼接两棵树, 两个都是较短的叶子时直接合并成一个叶子
+fn join(left: Link, right: Link) -> Link {
+    let (left, right) = match (left, right) {
+        (None, r) => return r,
+        (l, None) => return l,
+        (Some(l), Some(r)) => (l, r),
+    };
+
+    match (*left, *right) {
+        (Node::Leaf { text: a, chars: x }, Node::Leaf { text: b, chars: y })
+            if x + y <= MAX_CHUNK =>
+        {
+            Some(Box::new(Node::Leaf {
+                text: a + &b,
+                chars: x + y,
+            }))
+        }
+        (l, r) => Some(branch(Box::new(l), Box::new(r))),
+    }
+}
+
+/// 在第 at 个字符之前把树分割成两部分
+fn split(node: Link, at: usize) -> (Link, Link) {
+    let node = match node {
+        None => return (None, None),
+        Some(node) => node,
+    };
+
+    if at == 0 {
+        return (None, Some(node));
+    }
+    if at >= node.chars() {
+        return (Some(node), None);
+    }
+
+    match *node {
+        Node::Leaf { text, .. } => {
+            let pos = text
+                .char_indices()
+                .nth(at)
+                .map_or(text.len(), |(pos, _)| pos);
+            let (a, b) = text.split_at(pos);
+            (
+                Some(Box::new(Node::leaf(a.to_string()))),
+                Some(Box::new(Node::leaf(b.to_string()))),
+            )
+        }
+        Node::Branch { left, right, .. } => {
+            let n = left.chars();
+            if at <= n {
+                let (ll, lr) = split(Some(left), at);
+                (ll, join(lr, Some(right)))
+            } else {
+                let (rl, rr) = split(Some(right), at - n);
+                (join(Some(left), rl), rr)
+            }
+        }
+    }
+}
+
+/// 绳索
+#[derive(Default)]
+pub struct Rope {
+    root: Link,
+}
+
+impl Rope {
+    pub fn new() -> Self {
+        Self { root: None }
+    }
+
+    /// 字符数量
+    pub fn len(&self) -> usize {
+        self.root.as_ref().map_or(0, |n| n.chars())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 树的深度, 只有一个叶子时深度为 0
+    pub fn depth(&self) -> usize {
+        self.root.as_ref().map_or(0, |n| n.depth())
+    }
+
+    fn from_link(root: Link) -> Self {
+        let mut rope = Self { root };
+        rope.rebalance();
+        rope
+    }
+
+    /// 树的深度超过叶子数量对数的两倍时重新构建平衡的树
+    fn rebalance(&mut self) {
+        let Some(root) = &self.root else { return };
+        let limit = 2 * (usize::BITS - root.leaves().leading_zeros()) as usize + 2;
+        if root.depth() <= limit {
+            return;
+        }
+
+        let mut leaves = vec![];
+        let mut stack = vec![self.root.take().expect("root must exist")];
+        while let Some(node) = stack.pop() {
+            match *node {
+                Node::Branch { left, right, .. } => {
+                    stack.push(right);
+                    stack.push(left);
+                }
+                leaf => leaves.push(leaf),
+            }
+        }
+
+        self.root = build_balanced(leaves);
+    }
+
+    /// 拼接两个 rope
+    pub fn concat(self, other: Rope) -> Rope {
+        Self::from_link(join(self.root, other.root))
+    }
+
+    /// 在第 at 个字符之前分割成两个 rope
+    pub fn split(self, at: usize) -> (Rope, Rope) {
+        assert!(at <= self.len(), "index out of bounds");
+        let (l, r) = split(self.root, at);
+        (Self::from_link(l), Self::from_link(r))
+    }
+
+    /// 在第 idx 个字符之前插入字符串
+    pub fn insert(&mut self, idx: usize, s: &str) {
+        assert!(idx <= self.len(), "index out of bounds");
+        let (l, r) = split(self.root.take(), idx);
+        self.root = join(join(l, build(s)), r);
+        self.rebalance();
+    }
+
+    /// 删除 range 范围内的字符
+    pub fn remove(&mut self, range: Range<usize>) {
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "range out of bounds"
+        );
+        let (rest, r) = split(self.root.take(), range.end);
+        let (l, _) = split(rest, range.start);
+        self.root = join(l, r);
+        self.rebalance();
+    }
+
+    /// 第 idx 个字符
+    pub fn char_at(&self, mut idx: usize) -> Option<char> {
+        let mut node = self.root.as_ref()?;
+        if idx >= node.chars() {
+            return None;
+        }
+
+        loop {
+            match node.as_ref() {
+                Node::Leaf { text, .. } => return text.chars().nth(idx),
+                Node::Branch { left, right, .. } => {
+                    if idx < left.chars() {
+                        node = left;
+                    } else {
+                        idx -= left.chars();
+                        node = right;
+                    }
+                }
+            }
+        }
+    }
+
+    /// 复制出 range 范围内的字符
+    pub fn slice(&self, range: Range<usize>) -> String {
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "range out of bounds"
+        );
+
+        let mut res = String::new();
+        let mut offset = 0;
+        for chunk in self.chunks() {
+            let n = chunk.chars().count();
+            if offset + n > range.start && offset < range.end {
+                let start = range.start.saturating_sub(offset);
+                let end = (range.end - offset).min(n);
+                res.extend(chunk.chars().skip(start).take(end - start));
+            }
+
+            offset += n;
+            if offset >= range.end {
+                break;
+            }
+        }
+
+        res
+    }
+
+    /// 按顺序遍历所有叶子节点中的字符串
+    pub fn chunks(&self) -> Chunks<'_> {
+        Chunks {
+            stack: self.root.as_deref().into_iter().collect(),
+        }
+    }
+}
+
+impl From<&str> for Rope {
+    fn from(s: &str) -> Self {
+        Self { root: build(s) }
+    }
+}
+
+impl fmt::Display for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for chunk in self.chunks() {
+            f.write_str(chunk)?;
+        }
+        Ok(())
+    }
+}
+
+/// 叶子节点迭代器
+pub struct Chunks<'a> {
+    stack: Vec<&'a Node>,
+}
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.stack.pop()? {
+                Node::Leaf { text, .. } => return Some(text),
+                Node::Branch { left, right, .. } => {
+                    self.stack.push(right);
+                    self.stack.push(left);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    /// String 的字符下标转换为字节下标
+    fn byte_index(s: &str, idx: usize) -> usize {
+        s.char_indices().nth(idx).map_or(s.len(), |(pos, _)| pos)
+    }
+
+    #[test]
+    fn test_rope() {
+        let mut rope = Rope::from("hello world");
+        rope.insert(5, ", 世界");
+        assert_eq!(rope.to_string(), "hello, 世界 world");
+        assert_eq!(rope.char_at(7), Some('世'));
+        assert_eq!(rope.char_at(100), None);
+        assert_eq!(rope.slice(7..9), "世界");
+
+        rope.remove(5..9);
+        assert_eq!(rope.to_string(), "hello world");
+
+        let (a, b) = rope.split(6);
+        assert_eq!(a.to_string(), "hello ");
+        assert_eq!(b.to_string(), "world");
+
+        let rope = b.concat(a);
+        assert_eq!(rope.to_string(), "worldhello ");
+        assert_eq!(rope.len(), 11);
+        assert!(Rope::new().is_empty());
+    }
+
+    #[test]
+    fn test_rope_random() {
+        let mut rng = rand::thread_rng();
+        let alphabet: Vec<char> = "abcdefg中文字符🦀".chars().collect();
+        let mut rope = Rope::new();
+        let mut expect = String::new();
+
+        for _ in 0..2000 {
+            let len = expect.chars().count();
+            match rng.gen_range(0..4) {
+                0 | 1 => {
+                    let idx = rng.gen_range(0..=len);
+                    let n = rng.gen_range(0..100);
+                    let s: String = (0..n)
+                        .map(|_| alphabet[rng.gen_range(0..alphabet.len())])
+                        .collect();
+                    rope.insert(idx, &s);
+                    expect.insert_str(byte_index(&expect, idx), &s);
+                }
+                2 => {
+                    let start = rng.gen_range(0..=len);
+                    let end = rng.gen_range(start..=len.min(start + 50));
+                    rope.remove(start..end);
+                    expect.replace_range(byte_index(&expect, start)..byte_index(&expect, end), "");
+                }
+                _ => {
+                    let start = rng.gen_range(0..=len);
+                    let end = rng.gen_range(start..=len);
+                    let s = &expect[byte_index(&expect, start)..byte_index(&expect, end)];
+                    assert_eq!(rope.slice(start..end), s);
+                    assert_eq!(rope.char_at(start), expect.chars().nth(start));
+                }
+            }
+
+            assert_eq!(rope.len(), expect.chars().count());
+        }
+
+        assert_eq!(rope.to_string(), expect);
+        assert!(rope.chunks().all(|c| c.chars().count() <= MAX_CHUNK));
+
+        // 在同一个位置反复插入, 树依然保持平衡
+        let mut rope = Rope::new();
+        for _ in 0..10000 {
+            rope.insert(0, "x");
+        }
+        assert_eq!(rope.len(), 10000);
+        assert!(rope.depth() <= 2 * 14 + 2);
+    }
+}
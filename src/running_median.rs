@@ -0,0 +1,274 @@
+//! 对顶堆维护动态中位数
+//!
+//! - [OI Wiki - 对顶堆](https://oi-wiki.org/ds/binary-heap/#对顶堆)
+//! - [LeetCode - 数据流的中位数](https://leetcode.cn/problems/find-median-from-data-stream/)
+//! - [LeetCode - 滑动窗口中位数](https://leetcode.cn/problems/sliding-window-median/)
+//!
+//! 把所有元素分成较小的一半和较大的一半, 较小的一半放在大根堆 low 中, 较大的一半放在小根堆 high 中:
+//!
+//! ```text
+//!          low (大根堆)         high (小根堆)
+//! [1 2 3 4 5]  <=  5   |   6  <=  [6 7 8 9]
+//!                  ^ 中位数
+//! ```
+//!
+//! 始终保持 low 的堆顶不大于 high 的堆顶, 并且 `|low| == |high|` 或者 `|low| == |high| + 1`,
+//! 这样 low 的堆顶就是(下)中位数, 查询 O(1)
+//!
+//! 插入时和 low 的堆顶比较决定放进哪一边, 之后如果两边的大小不满足要求就把多出来的一边的堆顶移到另一边, O(logn)
+//!
+//! ## 惰性删除
+//!
+//! 堆不支持删除任意元素, 删除 x 时只在"待删除"表中记一笔并修改这一边的有效元素个数,
+//! 等到 x 出现在堆顶时再真正弹出; 每次操作之后都清理两个堆顶, 保证堆顶总是有效元素
+//!
+//! 因为 low 中的元素都不大于 low 的堆顶, high 中的元素都不小于它, 所以 `x <= low 堆顶` 时 x 一定有一份在 low 中,
+//! 否则在 high 中; 两边分别记录待删除表, 避免值相同的元素在两边之间移动后删错了一边
+//!
+//! 每个元素最多被推入和弹出常数次, 均摊复杂度仍为 O(logn), 但堆中可能暂时留有已删除的元素
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::binary_heap::BinaryHeap;
+
+/// 支持插入, 删除和查询中位数的多重集合
+///
+/// ```
+/// use impx::running_median::RunningMedian;
+///
+/// let mut m = RunningMedian::new();
+/// m.extend([5, 1, 3]);
+/// assert_eq!(m.median(), Some(&3));
+/// m.insert(4);
+/// assert_eq!(m.median(), Some(&3));
+/// assert_eq!(m.upper_median(), Some(&4));
+/// assert!(m.remove(&1));
+/// assert!(!m.remove(&1));
+/// assert_eq!(m.median(), Some(&4));
+/// ```
+pub struct RunningMedian<T> {
+    /// 较小的一半, 大根堆
+    low: BinaryHeap<T>,
+    /// 较大的一半, 小根堆
+    high: BinaryHeap<Reverse<T>>,
+    /// 两边有效元素的个数, 不包括待删除的元素
+    low_len: usize,
+    high_len: usize,
+    /// 两边已经删除但还留在堆中的元素
+    low_deleted: HashMap<T, usize>,
+    high_deleted: HashMap<Reverse<T>, usize>,
+    /// 每个有效元素出现的次数, 用来判断删除的元素是否存在
+    counts: HashMap<T, usize>,
+}
+
+impl<T: Ord + Hash + Clone> Default for RunningMedian<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord + Hash + Clone> RunningMedian<T> {
+    pub fn new() -> Self {
+        Self {
+            low: BinaryHeap::new(),
+            high: BinaryHeap::new(),
+            low_len: 0,
+            high_len: 0,
+            low_deleted: HashMap::new(),
+            high_deleted: HashMap::new(),
+            counts: HashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.low_len + self.high_len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn contains(&self, x: &T) -> bool {
+        self.counts.contains_key(x)
+    }
+
+    /// 下中位数, 即从小到大第 ⌈n/2⌉ 个元素, O(1)
+    pub fn median(&self) -> Option<&T> {
+        self.low.peek()
+    }
+
+    /// 上中位数, 即从小到大第 ⌊n/2⌋+1 个元素, 元素个数为奇数时和下中位数相同, O(1)
+    pub fn upper_median(&self) -> Option<&T> {
+        if self.low_len > self.high_len {
+            self.low.peek()
+        } else {
+            self.high.peek().map(|Reverse(v)| v)
+        }
+    }
+
+    /// 插入一个元素, O(logn)
+    pub fn insert(&mut self, x: T) {
+        *self.counts.entry(x.clone()).or_insert(0) += 1;
+        if self.low.peek().is_none_or(|top| x <= *top) {
+            self.low.push(x);
+            self.low_len += 1;
+        } else {
+            self.high.push(Reverse(x));
+            self.high_len += 1;
+        }
+        self.rebalance();
+    }
+
+    /// 删除一个等于 x 的元素, 不存在时返回 false, 均摊 O(logn)
+    pub fn remove(&mut self, x: &T) -> bool {
+        match self.counts.get_mut(x) {
+            None => return false,
+            Some(c) if *c == 1 => {
+                self.counts.remove(x);
+            }
+            Some(c) => *c -= 1,
+        }
+
+        // 集合非空时 low 的堆顶一定是有效元素
+        if self.low.peek().is_some_and(|top| x <= top) {
+            *self.low_deleted.entry(x.clone()).or_insert(0) += 1;
+            self.low_len -= 1;
+            prune(&mut self.low, &mut self.low_deleted);
+        } else {
+            *self.high_deleted.entry(Reverse(x.clone())).or_insert(0) += 1;
+            self.high_len -= 1;
+            prune(&mut self.high, &mut self.high_deleted);
+        }
+        self.rebalance();
+        true
+    }
+
+    /// 每次插入或删除之后两边的大小最多相差 2, 移动一个元素就能恢复
+    fn rebalance(&mut self) {
+        if self.low_len > self.high_len + 1 {
+            let v = self.low.pop().unwrap();
+            self.low_len -= 1;
+            self.high.push(Reverse(v));
+            self.high_len += 1;
+            prune(&mut self.low, &mut self.low_deleted);
+        } else if self.low_len < self.high_len {
+            let Reverse(v) = self.high.pop().unwrap();
+            self.high_len -= 1;
+            self.low.push(v);
+            self.low_len += 1;
+            prune(&mut self.high, &mut self.high_deleted);
+        }
+    }
+}
+
+impl<T: Ord + Hash + Clone> Extend<T> for RunningMedian<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|v| self.insert(v));
+    }
+}
+
+/// 弹出堆顶所有待删除的元素
+fn prune<T: Ord + Hash>(heap: &mut BinaryHeap<T>, deleted: &mut HashMap<T, usize>) {
+    while let Some(top) = heap.peek() {
+        match deleted.get_mut(top) {
+            None => break,
+            Some(c) if *c == 1 => {
+                deleted.remove(top);
+            }
+            Some(c) => *c -= 1,
+        }
+        heap.pop();
+    }
+}
+
+/// 长度为 k 的滑动窗口的(下)中位数, O(nlogk)
+///
+/// ```
+/// use impx::running_median::sliding_window_median;
+///
+/// let v = [1, 3, -1, -3, 5, 3, 6, 7];
+/// assert_eq!(sliding_window_median(&v, 3), vec![1, -1, -1, 3, 5, 6]);
+/// ```
+pub fn sliding_window_median<T: Ord + Hash + Clone>(v: &[T], k: usize) -> Vec<T> {
+    assert!(k > 0, "window size must be positive");
+
+    let mut m = RunningMedian::new();
+    let mut res = Vec::with_capacity(v.len().saturating_sub(k - 1));
+    for (i, x) in v.iter().enumerate() {
+        m.insert(x.clone());
+        if i >= k {
+            m.remove(&v[i - k]);
+        }
+        if i + 1 >= k {
+            res.push(m.median().unwrap().clone());
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_running_median_brute() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..20 {
+            let mut m = RunningMedian::new();
+            let mut sorted: Vec<i32> = vec![];
+            // 值域较小, 有大量重复元素
+            for _ in 0..2000 {
+                let x = rng.gen_range(0..30);
+                if rng.gen_bool(0.6) {
+                    m.insert(x);
+                    let pos = sorted.partition_point(|&y| y < x);
+                    sorted.insert(pos, x);
+                } else {
+                    let pos = sorted.binary_search(&x).ok();
+                    assert_eq!(m.remove(&x), pos.is_some());
+                    if let Some(pos) = pos {
+                        sorted.remove(pos);
+                    }
+                }
+
+                let n = sorted.len();
+                assert_eq!(m.len(), n);
+                assert_eq!(m.contains(&x), sorted.binary_search(&x).is_ok());
+                if n == 0 {
+                    assert!(m.is_empty());
+                    assert_eq!(m.median(), None);
+                    assert_eq!(m.upper_median(), None);
+                } else {
+                    assert_eq!(m.median(), Some(&sorted[(n - 1) / 2]));
+                    assert_eq!(m.upper_median(), Some(&sorted[n / 2]));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_sliding_window_median() {
+        let mut rng = rand::thread_rng();
+        let v: Vec<u32> = (0..500).map(|_| rng.gen_range(0..50)).collect();
+        for k in [1, 2, 3, 10, 499, 500] {
+            let expect: Vec<u32> = v
+                .windows(k)
+                .map(|w| {
+                    let mut w = w.to_vec();
+                    w.sort_unstable();
+                    w[(k - 1) / 2]
+                })
+                .collect();
+            assert_eq!(sliding_window_median(&v, k), expect);
+        }
+
+        assert!(sliding_window_median(&v, 501).is_empty());
+        assert_eq!(
+            sliding_window_median(&["b", "a", "c", "a"], 2),
+            vec!["a", "a", "a"]
+        );
+    }
+}
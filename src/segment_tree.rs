@@ -26,15 +26,70 @@
 //! 当然可以简单的更新 `[s, t]` 的所有节点, 但是实际上对于 `[s, t]` 区间的求和子节点是不被读取的  
 //! 所以可以直接更新 `[s, t]` 区间所在节点的 sum 值然后添加一个懒标记  
 //! 直到需要计算 `[s, t]` 区间内的子节点时才有更新子节点的 sum 值
+//!
+//! 同时支持区间加和区间赋值两种懒标记, 一个节点上的标记总是表示"先赋值再加":
+//!
+//! - 在节点上赋值时会覆盖掉之前所有未下放的加法标记
+//! - 在节点上加时如果有未下放的赋值标记, 只需要累加到加法标记上
+//! - 下放时按照先赋值再加的顺序作用到子节点上, 保证和修改发生的顺序一致
+//!
+//! 加法标记可能是负数, 判断是否有未下放的标记要用 `!= 0` 而不是 `> 0`
+//!
+//! ## 非递归线段树(zkw 线段树)
+//!
+//! - [Efficient and easy segment trees](https://codeforces.com/blog/entry/18051)
+//!
+//! 如果只需要单点修改和区间查询, 可以把 n 个叶子直接放在数组的 `[n, 2n)` 上,
+//! 节点 i 的两个子节点是 `2i` 和 `2i+1`, 父节点是 `i/2`, 整棵树只占用 2n 个元素, 也不需要保存区间端点
+//!
+//! - 单点修改: 修改叶子 `i+n` 后沿着 `i/2` 一路向上重新合并即可
+//! - 区间查询: 把 `[l, r)` 的两个端点同时向上移动, 如果左端点是右子节点(奇数)说明它的父节点会超出区间,
+//!   先把它合并到结果里再右移一位, 右端点同理, 两端相遇时结束
+//!
+//! 左右两边分别累积结果, 最后再合并, 所以运算只需要满足结合律, 不需要交换律
+//!
+//! 和上面递归的版本相比没有函数调用和区间端点的比较, 数组大小也从 4n 降到了 2n,
+//! 对缓存更友好, 常数要小很多, 代价是不支持区间修改
+//!
+//! ## 区间覆盖长度
+//!
+//! 扫描线求矩形面积并时需要维护"被至少一个区间覆盖的总长度", 每个叶子是一个有长度的基本段,
+//! 修改是给一段叶子的覆盖次数加一或者减一, 见 [`CoverSegmentTree`]:
+//!
+//! - `cnt`: 恰好覆盖整个节点区间的修改次数, 修改时只在完整覆盖的节点上加减, 不需要下放
+//! - `len`: 节点区间内被覆盖的总长度, `cnt > 0` 时是整个区间的长度, 否则是两个子节点的 len 之和
+//!
+//! 要求每次减一都对应之前一次在相同区间上的加一, 这样减一时经过的节点和加一时完全相同, cnt 不会变成负数,
+//! 也就不需要懒标记下放, 根节点的 len 就是答案
 
 /// 线段树节点
 #[derive(Default, Clone, Copy)]
 pub struct Node {
-    pub low: usize,       // 节点管辖左区间
-    pub high: usize,      // 节点管辖的右区间
-    pub index: usize,     // 节点在线段树中的索引
-    pub sum: isize,       // 节点所管辖区间内元素和
-    pub lazy_mark: isize, // 懒标记表示此区间内有数据修改但是还没有更新到下方到子区间内
+    pub low: usize,              // 节点管辖左区间
+    pub high: usize,             // 节点管辖的右区间
+    pub index: usize,            // 节点在线段树中的索引
+    pub sum: isize,              // 节点所管辖区间内元素和
+    pub lazy_mark: isize,        // 懒标记表示此区间内有数据修改但是还没有更新到下方到子区间内
+    pub set_mark: Option<isize>, // 区间赋值的懒标记, 作用在 lazy_mark 之前
+}
+
+impl Node {
+    fn len(&self) -> isize {
+        (self.high - self.low + 1) as isize
+    }
+
+    /// 区间内每个元素加上 diff
+    fn apply_add(&mut self, diff: isize) {
+        self.sum += self.len() * diff;
+        self.lazy_mark += diff;
+    }
+
+    /// 区间内每个元素赋值为 value, 之前未下放的标记全部失效
+    fn apply_set(&mut self, value: isize) {
+        self.sum = self.len() * value;
+        self.set_mark = Some(value);
+        self.lazy_mark = 0;
+    }
 }
 
 /// 线段树
@@ -84,43 +139,76 @@ impl SegmentTree {
     }
 
     fn update_node(&mut self, index: usize, low: usize, high: usize, diff: isize) {
-        let mut node = self.tree[index];
+        let node = self.tree[index];
         if low <= node.low && node.high <= high {
-            node.sum += ((node.high - node.low + 1) as isize) * diff;
-            node.lazy_mark += diff;
-            self.tree[node.index] = node;
+            self.tree[index].apply_add(diff);
             return;
         }
 
         // 如有未更新的标记, 先更新到下一层
-        if node.low != node.high && node.lazy_mark > 0 {
-            let left = &mut self.tree[node.index * 2 + 1];
-            left.sum += ((left.high - left.low + 1) as isize) * node.lazy_mark;
-            left.lazy_mark += node.lazy_mark;
-
-            let right = &mut self.tree[node.index * 2 + 2];
-            right.sum += ((right.high - right.low + 1) as isize) * node.lazy_mark;
-            right.lazy_mark += node.lazy_mark;
-
-            node.lazy_mark = 0;
-        }
+        self.push_down(index);
 
         let mid = node.low + ((node.high - node.low) >> 1);
         let left_index = node.index * 2 + 1;
         let right_index = node.index * 2 + 2;
 
-        // 如果左节点在区间内
+        // 如果左节点和区间有交集, 查询区间原样传下去, 由子节点自己判断覆盖范围
         if low <= mid {
-            self.update_node(left_index, low, mid, diff);
+            self.update_node(left_index, low, high, diff);
         }
 
-        // 如果右节点在区间内
+        // 如果右节点和区间有交集
         if mid < high {
-            self.update_node(right_index, mid, high, diff);
+            self.update_node(right_index, low, high, diff);
+        }
+
+        self.tree[index].sum = self.tree[left_index].sum + self.tree[right_index].sum;
+    }
+
+    /// 线段树区间赋值, 区间 `[low, high]` 内的每个元素都修改为 value
+    pub fn range_set(&mut self, low: usize, high: usize, value: isize) {
+        self.set_node(0, low, high, value);
+    }
+
+    fn set_node(&mut self, index: usize, low: usize, high: usize, value: isize) {
+        let node = self.tree[index];
+        if high < node.low || node.high < low {
+            return;
+        }
+
+        if low <= node.low && node.high <= high {
+            self.tree[index].apply_set(value);
+            return;
+        }
+
+        self.push_down(index);
+
+        let (left_index, right_index) = (index * 2 + 1, index * 2 + 2);
+        self.set_node(left_index, low, high, value);
+        self.set_node(right_index, low, high, value);
+        self.tree[index].sum = self.tree[left_index].sum + self.tree[right_index].sum;
+    }
+
+    /// 把节点上未下放的标记按照先赋值再加的顺序作用到左右子节点上
+    fn push_down(&mut self, index: usize) {
+        let node = self.tree[index];
+        if node.low == node.high {
+            return;
+        }
+
+        for child in [index * 2 + 1, index * 2 + 2] {
+            let child = &mut self.tree[child];
+            if let Some(value) = node.set_mark {
+                child.apply_set(value);
+            }
+            if node.lazy_mark != 0 {
+                child.apply_add(node.lazy_mark);
+            }
         }
 
-        node.sum = self.tree[left_index].sum + self.tree[right_index].sum;
-        self.tree[node.index] = node;
+        let node = &mut self.tree[index];
+        node.set_mark = None;
+        node.lazy_mark = 0;
     }
 
     /// 线段树获取区间 `[low, high]` 内元素之和
@@ -129,23 +217,13 @@ impl SegmentTree {
     }
 
     fn sum_node(&mut self, index: usize, low: usize, high: usize) -> isize {
-        let mut node = self.tree[index];
+        let node = self.tree[index];
         if low <= node.low && node.high <= high {
             return node.sum;
         }
 
         // 如有未更新的标记, 先更新到下一层
-        if node.low != node.high && node.lazy_mark > 0 {
-            let left = &mut self.tree[node.index * 2 + 1];
-            left.sum += ((left.high - left.low + 1) as isize) * node.lazy_mark;
-            left.lazy_mark += node.lazy_mark;
-
-            let right = &mut self.tree[node.index * 2 + 2];
-            right.sum += ((right.high - right.low + 1) as isize) * node.lazy_mark;
-            right.lazy_mark += node.lazy_mark;
-
-            node.lazy_mark = 0;
-        }
+        self.push_down(index);
 
         let mut sum = 0;
         let mid = node.low + ((node.high - node.low) >> 1);
@@ -164,6 +242,178 @@ impl SegmentTree {
     }
 }
 
+/// 非递归线段树, `tree[n..2n]` 是叶子, `tree[i]` 是 `tree[2i]` 和 `tree[2i+1]` 的运算结果
+pub struct ZkwSegmentTree<T, F> {
+    n: usize,
+    tree: Vec<T>,
+    identity: T,
+    op: F,
+}
+
+impl<T: Clone, F: Fn(&T, &T) -> T> ZkwSegmentTree<T, F> {
+    /// 使用满足结合律的运算 op 和它的单位元 identity 初始化线段树
+    pub fn new(v: &[T], identity: T, op: F) -> Self {
+        let n = v.len();
+        let mut tree = vec![identity.clone(); n];
+        tree.extend_from_slice(v);
+
+        // 自底向上计算每个中间节点
+        for i in (1..n).rev() {
+            tree[i] = op(&tree[2 * i], &tree[2 * i + 1]);
+        }
+
+        Self {
+            n,
+            tree,
+            identity,
+            op,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// 获取第 i 个元素
+    pub fn get(&self, i: usize) -> &T {
+        assert!(i < self.n, "index out of range");
+        &self.tree[i + self.n]
+    }
+
+    /// 把第 i 个元素修改为 value
+    pub fn set(&mut self, i: usize, value: T) {
+        assert!(i < self.n, "index out of range");
+        let mut i = i + self.n;
+        self.tree[i] = value;
+        while i > 1 {
+            i /= 2;
+            self.tree[i] = (self.op)(&self.tree[2 * i], &self.tree[2 * i + 1]);
+        }
+    }
+
+    /// 查询区间 `[l, r]` 的运算结果, 区间不合法时返回 None
+    pub fn query(&self, l: usize, r: usize) -> Option<T> {
+        if l > r || r >= self.n {
+            return None;
+        }
+
+        let (mut l, mut r) = (l + self.n, r + self.n + 1);
+        let mut left = self.identity.clone();
+        let mut right = self.identity.clone();
+        while l < r {
+            if l & 1 == 1 {
+                left = (self.op)(&left, &self.tree[l]);
+                l += 1;
+            }
+            if r & 1 == 1 {
+                r -= 1;
+                right = (self.op)(&self.tree[r], &right);
+            }
+            l /= 2;
+            r /= 2;
+        }
+
+        Some((self.op)(&left, &right))
+    }
+}
+
+/// 区间求和的非递归线段树
+pub fn sum_tree(v: &[isize]) -> ZkwSegmentTree<isize, fn(&isize, &isize) -> isize> {
+    ZkwSegmentTree::new(v, 0, |a, b| a + b)
+}
+
+/// 区间最小值的非递归线段树
+pub fn min_tree(v: &[isize]) -> ZkwSegmentTree<isize, fn(&isize, &isize) -> isize> {
+    ZkwSegmentTree::new(v, isize::MAX, |&a, &b| a.min(b))
+}
+
+/// 维护区间覆盖长度的线段树, 节点 i 的子节点是 2i+1 和 2i+2
+pub struct CoverSegmentTree {
+    /// 基本段长度的前缀和, 叶子 `[l, r]` 的总长度是 `prefix[r+1] - prefix[l]`
+    prefix: Vec<i128>,
+    cnt: Vec<u32>,
+    len: Vec<i128>,
+}
+
+impl CoverSegmentTree {
+    /// 使用每个基本段的长度初始化, 初始时所有基本段都没有被覆盖
+    pub fn new(lens: &[i128]) -> Self {
+        let n = lens.len();
+        assert!(n > 0, "at least one segment");
+
+        let mut prefix = Vec::with_capacity(n + 1);
+        prefix.push(0);
+        for &len in lens {
+            prefix.push(prefix.last().unwrap() + len);
+        }
+
+        Self {
+            prefix,
+            cnt: vec![0; n * 4],
+            len: vec![0; n * 4],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.prefix.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 被覆盖至少一次的基本段的总长度
+    pub fn covered(&self) -> i128 {
+        self.len[0]
+    }
+
+    /// 基本段 `[low, high]` 的覆盖次数加一
+    pub fn cover(&mut self, low: usize, high: usize) {
+        self.update_node(0, 0, self.len() - 1, low, high, 1);
+    }
+
+    /// 撤销一次之前在相同区间 `[low, high]` 上的 [`CoverSegmentTree::cover`]
+    pub fn uncover(&mut self, low: usize, high: usize) {
+        self.update_node(0, 0, self.len() - 1, low, high, -1);
+    }
+
+    fn update_node(
+        &mut self,
+        index: usize,
+        l: usize,
+        r: usize,
+        low: usize,
+        high: usize,
+        delta: i32,
+    ) {
+        if low <= l && r <= high {
+            self.cnt[index] = self.cnt[index]
+                .checked_add_signed(delta)
+                .expect("uncover without matching cover");
+        } else {
+            let mid = l + ((r - l) >> 1);
+            if low <= mid {
+                self.update_node(index * 2 + 1, l, mid, low, high, delta);
+            }
+            if mid < high {
+                self.update_node(index * 2 + 2, mid + 1, r, low, high, delta);
+            }
+        }
+
+        self.len[index] = if self.cnt[index] > 0 {
+            self.prefix[r + 1] - self.prefix[l]
+        } else if l == r {
+            0
+        } else {
+            self.len[index * 2 + 1] + self.len[index * 2 + 2]
+        };
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -190,4 +440,165 @@ mod tests {
         assert_eq!(5, t.sum(0, 2));
         assert_eq!(21, t.sum(1, 5));
     }
+
+    #[test]
+    fn test_segment_tree_negative_lazy() {
+        use super::*;
+
+        let v = [1, 2, 3, 4, 5, 6];
+        let mut t = SegmentTree::new(&v);
+
+        // 整个区间减 2, 负数的懒标记停在根节点上
+        // [-1, 0, 1, 2, 3, 4]
+        t.update(0, 5, -2);
+        assert_eq!(1, t.sum(1, 2));
+        assert_eq!(-1, t.sum(0, 0));
+        assert_eq!(9, t.sum(3, 5));
+
+        // [-1, 0, 1, 1, 2, 3]
+        t.update(3, 5, -1);
+        assert_eq!(2, t.sum(4, 4));
+        assert_eq!(4, t.sum(2, 4));
+        assert_eq!(6, t.sum(0, 5));
+    }
+
+    #[test]
+    fn test_segment_tree_range_set() {
+        use super::*;
+
+        let v = [1, 2, 3, 4, 5, 6];
+        let mut t = SegmentTree::new(&v);
+
+        // [1, 1, 1, 1, 1, 1]
+        t.range_set(0, 5, 1);
+        assert_eq!(2, t.sum(2, 3));
+
+        // 赋值标记之后再加: [1, 1, 1, 3, 3, 3]
+        t.update(3, 5, 2);
+        assert_eq!(4, t.sum(2, 3));
+
+        // 赋值覆盖之前的加法标记: [-1, -1, -1, 3, 3, 3]
+        t.range_set(0, 2, -1);
+        assert_eq!(4, t.sum(1, 4));
+
+        // 不对齐节点边界的赋值: [-1, 5, 5, 5, 5, 3]
+        t.range_set(1, 4, 5);
+        assert_eq!(22, t.sum(0, 5));
+        assert_eq!(8, t.sum(4, 5));
+        assert_eq!(4, t.sum(0, 1));
+    }
+
+    /// 所有长度不超过 8 的数组上, 依次对每个区间做修改, 每次修改后和暴力计算对比所有区间的和
+    #[test]
+    fn test_segment_tree_exhaustive() {
+        use super::*;
+
+        for n in 1..=8 {
+            let mut v: Vec<isize> = (0..n as isize).map(|i| i * 3 - 7).collect();
+            let mut t = SegmentTree::new(&v);
+
+            let ranges: Vec<_> = (0..n).flat_map(|l| (l..n).map(move |h| (l, h))).collect();
+            for (i, &(low, high)) in ranges.iter().enumerate() {
+                let x = i as isize % 7 - 3;
+                if i % 3 == 2 {
+                    t.range_set(low, high, x);
+                    v[low..=high].iter_mut().for_each(|e| *e = x);
+                } else {
+                    t.update(low, high, x);
+                    v[low..=high].iter_mut().for_each(|e| *e += x);
+                }
+
+                for &(l, h) in &ranges {
+                    let expect: isize = v[l..=h].iter().sum();
+                    assert_eq!(t.sum(l, h), expect, "n={} [{}, {}] {:?}", n, l, h, v);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_zkw_segment_tree() {
+        use super::*;
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        for n in 1..=40 {
+            let mut v: Vec<isize> = (0..n).map(|_| rng.gen_range(-50..50)).collect();
+            let mut sum = sum_tree(&v);
+            let mut min = min_tree(&v);
+            assert_eq!(sum.len(), n);
+            assert_eq!(sum.query(0, n), None);
+
+            for _ in 0..50 {
+                let i = rng.gen_range(0..n);
+                let x = rng.gen_range(-50..50);
+                v[i] = x;
+                sum.set(i, x);
+                min.set(i, x);
+                assert_eq!(*sum.get(i), x);
+
+                let l = rng.gen_range(0..n);
+                let r = rng.gen_range(l..n);
+                assert_eq!(sum.query(l, r), Some(v[l..=r].iter().sum()));
+                assert_eq!(min.query(l, r), v[l..=r].iter().min().copied());
+            }
+        }
+    }
+
+    /// 字符串拼接不满足交换律, 检查结果的顺序
+    #[test]
+    fn test_zkw_segment_tree_non_commutative() {
+        use super::*;
+
+        let v: Vec<String> = "abcdefghijk".chars().map(String::from).collect();
+        let mut t = ZkwSegmentTree::new(&v, String::new(), |a, b| format!("{}{}", a, b));
+        for l in 0..v.len() {
+            for r in l..v.len() {
+                assert_eq!(t.query(l, r).unwrap(), v[l..=r].concat());
+            }
+        }
+
+        t.set(3, "XY".to_string());
+        assert_eq!(t.query(2, 5).unwrap(), "cXYef");
+        assert_eq!(t.query(0, 10).unwrap(), "abcXYefghijk");
+    }
+
+    /// 随机覆盖和撤销, 和逐个基本段统计覆盖次数对比
+    #[test]
+    fn test_cover_segment_tree() {
+        use super::*;
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        for n in 1..=20 {
+            let lens: Vec<i128> = (0..n).map(|_| rng.gen_range(0..10)).collect();
+            let mut t = CoverSegmentTree::new(&lens);
+            let mut cnt = vec![0; n];
+            let mut covered: Vec<(usize, usize)> = vec![];
+            assert_eq!(t.len(), n);
+            assert_eq!(t.covered(), 0);
+
+            for _ in 0..100 {
+                if covered.is_empty() || rng.gen_bool(0.6) {
+                    let l = rng.gen_range(0..n);
+                    let h = rng.gen_range(l..n);
+                    t.cover(l, h);
+                    cnt[l..=h].iter_mut().for_each(|c| *c += 1);
+                    covered.push((l, h));
+                } else {
+                    let (l, h) = covered.swap_remove(rng.gen_range(0..covered.len()));
+                    t.uncover(l, h);
+                    cnt[l..=h].iter_mut().for_each(|c| *c -= 1);
+                }
+
+                let expect: i128 = (0..n).filter(|&i| cnt[i] > 0).map(|i| lens[i]).sum();
+                assert_eq!(t.covered(), expect);
+            }
+        }
+
+        // 长度超过 i64 的范围
+        let mut t = CoverSegmentTree::new(&[i64::MAX as i128 + 1, i64::MAX as i128 + 1]);
+        t.cover(0, 1);
+        assert_eq!(t.covered(), 1 << 64);
+    }
 }
@@ -4,30 +4,61 @@
 //!
 //! 实现细节: 每个 key 只需要一个节点, 有多条指向其他层的链接
 //!
+//! ## 按排名索引
+//!
+//! 每条链接额外记录它在第 0 层上跨过的距离 span, 即目标节点与起点的排名之差,
+//! 头节点到每层第一个节点的 span 记录在跳表中, 空链接的 span 为 0  
+//! 查找时沿途累加 span 就能得到节点的排名, 按排名查找时只要累加的 span 不超过目标就向右走  
+//! 插入和删除时除了修改经过的链接, 还要把更高层跨过该节点的链接 span 加一或者减一,
+//! 于是 [`SkipList::rank`], [`SkipList::get_by_rank`] 以及按位置插入删除都是 O(logn)
+//!
+//! ## 节点池
+//!
+//! 默认每个节点单独通过 `Box` 分配和释放, 插入删除频繁交替时分配器的开销很明显  
+//! [`SkipList::with_pool_capacity`] 开启节点池: 删除节点时只把 key 和 value 移出,
+//! 节点的内存放进空闲链表, 之后插入时优先复用, 空闲链表的长度不超过给定的容量  
+//! 节点的大小只和 N 有关, 所以任意节点的内存都可以被复用
+//!
+//! ## 随机数生成器
+//!
+//! 节点的层数由随机数生成器 R 决定, 默认使用熵源初始化的 [`StdRng`],
+//! 需要可复现的结构时用 [`SkipList::with_rng`] 传入固定种子的生成器, 比如 [`crate::rng::XorShift64Star`]
+//!
 //! TODO: 没想清楚最左侧怎么做哨兵节点(最小值)所以很多代码在处理边界情况
 
 use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::mem::MaybeUninit;
 use std::ptr::NonNull;
 
-use rand::rngs::ThreadRng;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
 pub struct SkipListNode<const N: usize, K, V> {
     key: K,
     value: V,
     level: usize, // 节点最高层
     forward: [Link<N, K, V>; N],
+    span: [usize; N], // 每层链接在第 0 层上跨过的节点数
 }
 
 type Link<const N: usize, K, V> = Option<NonNull<SkipListNode<N, K, V>>>;
 
-pub struct SkipList<const N: usize, K, V> {
-    length: usize,             // 元素数量
-    level: usize,              // 最高层
-    rand: ThreadRng,           // 随机生成器
-    lists: [Link<N, K, V>; N], // 每层链表的头节点
+pub struct SkipList<const N: usize, K, V, R = StdRng> {
+    length: usize,                             // 元素数量
+    level: usize,                              // 最高层
+    rand: R,                                   // 随机生成器
+    lists: [Link<N, K, V>; N],                 // 每层链表的头节点
+    spans: [usize; N],                         // 每层头节点的排名加一
+    pool: Vec<NonNull<SkipListNode<N, K, V>>>, // 空闲节点, 其中的 key 和 value 已经移出
+    pool_capacity: usize,                      // 空闲节点数量的上限, 为 0 时不复用节点
 }
 
+// 跳表独占所有节点, 和 `Box` 一样只要 K 和 V 满足对应约束就可以跨线程转移和共享
+// 共享引用上只有只读操作, 所有修改都需要 `&mut self`
+unsafe impl<const N: usize, K: Send, V: Send, R: Send> Send for SkipList<N, K, V, R> {}
+unsafe impl<const N: usize, K: Sync, V: Sync, R: Sync> Sync for SkipList<N, K, V, R> {}
+
 impl<const N: usize, K, V> SkipListNode<N, K, V> {
     const NONE_NODE: Link<N, K, V> = None;
 
@@ -37,19 +68,37 @@ impl<const N: usize, K, V> SkipListNode<N, K, V> {
             value,
             level,
             forward: [Self::NONE_NODE; N],
+            span: [0; N],
         }
     }
 }
 
 impl<const N: usize, K: Ord, V> SkipList<N, K, V> {
+    pub fn new() -> Self {
+        Self::with_rng(StdRng::from_entropy())
+    }
+
+    /// 开启节点池, 最多保留 capacity 个空闲节点供之后的插入复用
+    pub fn with_pool_capacity(capacity: usize) -> Self {
+        let mut list = Self::new();
+        list.pool_capacity = capacity;
+        list
+    }
+}
+
+impl<const N: usize, K: Ord, V, R: Rng> SkipList<N, K, V, R> {
     const P: usize = 2;
 
-    pub fn new() -> Self {
+    /// 使用指定的随机数生成器, 种子相同时相同的操作序列得到相同的结构
+    pub fn with_rng(rng: R) -> Self {
         Self {
             length: 0,
             level: 0,
-            rand: rand::thread_rng(),
+            rand: rng,
             lists: [SkipListNode::NONE_NODE; N],
+            spans: [0; N],
+            pool: vec![],
+            pool_capacity: 0,
         }
     }
 
@@ -58,7 +107,7 @@ impl<const N: usize, K: Ord, V> SkipList<N, K, V> {
     }
 
     pub fn is_empty(&self) -> bool {
-        self.length > 0
+        self.length == 0
     }
 
     /// 随机层数
@@ -138,193 +187,492 @@ impl<const N: usize, K: Ord, V> SkipList<N, K, V> {
 
     /// 插入指定元素对, 如果 key 对应的节点存在则更新节点 value 把旧的 value 替换出来
     pub fn insert(&mut self, key: K, value: V) -> Option<V> {
-        let mut head = match self.find_start_node(&key) {
-            None => {
-                // 如果找不到比 key 小的头节点则此 key 是最小值
-                // 则执行插入当前最小值的特殊逻辑
-                self.insert_min(key, value);
-                return None;
-            }
-            Some(mut node) => {
-                let node_key = unsafe { &node.as_ref().key };
-                // 如果对应 key 相等则直接替换出来
-                if node_key == &key {
-                    let old = unsafe { &mut node.as_mut().value };
-                    return Some(std::mem::replace(old, value));
-                }
+        let (update, rank, next) = self.search(&key);
 
-                node
+        // 如果第 0 层的下一个节点等于 key 则直接替换
+        if let Some(mut node) = next {
+            let node = unsafe { node.as_mut() };
+            if node.key == key {
+                return Some(std::mem::replace(&mut node.value, value));
             }
+        }
+
+        self.link_node(&update, &rank, key, value);
+        None
+    }
+
+    /// 查找 key 对应的值, 如果不存在则用 f 生成的值插入, 返回值的可变引用
+    pub fn get_or_insert_with<F: FnOnce() -> V>(&mut self, key: K, f: F) -> &mut V {
+        let (update, rank, next) = self.search(&key);
+
+        let node = match next {
+            Some(node) if unsafe { &node.as_ref().key } == &key => node,
+            _ => self.link_node(&update, &rank, key, f()),
         };
 
-        // 从 head 节点开始, 先向右找到每一层小于 key 的最大节点
-        // 接着下降到下一层, 继续向右找小于 key 的最大节点
-        // 这里 update 记录每一层小于 key 的最大节点用于后续插入
-        let max_level = unsafe { head.as_ref().level };
+        unsafe { &mut (*node.as_ptr()).value }
+    }
+
+    /// 从最高层开始, 先向右找到每一层小于 key 的最大节点
+    /// 接着下降到下一层, 继续向右找小于 key 的最大节点
+    ///
+    /// 返回每一层小于 key 的最大节点(`None` 表示在链表头部)及其位置用于后续插入
+    /// 以及第 0 层上第一个大于等于 key 的节点
+    fn search(&self, key: &K) -> Path<N, K, V> {
+        self.search_by(|node_key, _| node_key < key)
+    }
+
+    /// 以排名代替 key 查找, 返回每一层排名小于 index 的最大节点, 第 0 层的下一个节点排名恰好是 index
+    fn search_rank(&self, index: usize) -> Path<N, K, V> {
+        self.search_by(|_, pos| pos <= index)
+    }
+
+    /// 从最高层开始只要 `right(下一个节点的 key, 下一个节点的位置)` 成立就向右走, 否则下降一层
+    ///
+    /// 位置从 1 开始, 0 表示链表头部, 所以节点的排名等于位置减一
+    fn search_by(&self, mut right: impl FnMut(&K, usize) -> bool) -> Path<N, K, V> {
         let mut update = [None; N];
-        for i in (0..=max_level).rev() {
-            let mut head_ref = unsafe { head.as_ref() };
-            while let Some(node) = head_ref.forward[i] {
-                let node_ref = unsafe { node.as_ref() };
-                if node_ref.key < key {
-                    head = node;
-                    head_ref = node_ref;
-                } else {
-                    break;
+        let mut rank = [0; N];
+        let mut prev: Link<N, K, V> = None;
+        let mut pos = 0;
+
+        for i in (0..=self.level).rev() {
+            loop {
+                let (next, span) = self.next_of(prev, i);
+                match next {
+                    Some(node) if right(unsafe { &node.as_ref().key }, pos + span) => {
+                        prev = Some(node);
+                        pos += span;
+                    }
+                    _ => break,
                 }
             }
 
-            update[i] = Some(head);
+            update[i] = prev;
+            rank[i] = pos;
         }
 
-        // 当前 head 是第 0 层小于 key 的最大节点
-        // 需要确认下个节点的值是否等于 key
-        // 如果等于则找到了相同 key 节点直接替换
-        let head_ref = unsafe { head.as_ref() };
-        if let Some(mut node) = head_ref.forward[0] {
-            let node_key = unsafe { &node.as_ref().key };
-            if node_key == &key {
-                let old = unsafe { &mut node.as_mut().value };
-                return Some(std::mem::replace(old, value));
-            }
-        }
+        (update, rank, self.next_of(prev, 0).0)
+    }
 
-        // 创建新节点随机 level 执行 0..level 层的插入
+    /// 创建新节点随机 level 执行 0..level 层的插入
+    ///
+    /// 如果 update 中对应层为空则新节点成为该层的头节点  
+    /// 更高层上跨过新节点的链接 span 加一
+    fn link_node(
+        &mut self,
+        update: &[Link<N, K, V>; N],
+        rank: &[usize; N],
+        key: K,
+        value: V,
+    ) -> NonNull<SkipListNode<N, K, V>> {
         let new_level = self.rand_lelve();
-        let new_node = SkipListNode::new(key, value, new_level);
-        let new_node = Box::new(new_node);
-        let new_node = unsafe { NonNull::new_unchecked(Box::into_raw(new_node)) };
-
-        for (i, item) in update.iter_mut().enumerate().take(new_level + 1) {
-            unsafe {
-                match item {
-                    None => {
-                        (*new_node.as_ptr()).forward[i] = self.lists[i].take();
-                        self.lists[i] = Some(new_node);
-                    }
-                    Some(mut node) => {
-                        let node = node.as_mut();
-                        let next = node.forward[i].take();
-                        (*new_node.as_ptr()).forward[i] = next;
-                        node.forward[i] = Some(new_node);
-                    }
+        let new_node = self.alloc_node(SkipListNode::new(key, value, new_level));
+
+        // 新节点的位置
+        let pos = rank[0] + 1;
+        for i in 0..N {
+            let (next, span) = self.slot(update[i], i);
+            if i <= new_level {
+                // 原来的下一个节点位置为 rank[i] + span, 插入后向后移动一位
+                let new_ref = unsafe { &mut *new_node.as_ptr() };
+                if next.is_some() {
+                    new_ref.span[i] = rank[i] + *span - rank[0];
                 }
+                new_ref.forward[i] = next.replace(new_node);
+                *span = pos - rank[i];
+            } else if next.is_some() {
+                *span += 1;
             }
         }
 
         self.length += 1;
         self.level = self.level.max(new_level);
-        None
+        new_node
     }
 
-    /// 插入最小值节点
+    /// key 的排名, 即跳表中严格小于 key 的元素个数
     ///
-    /// 随机出层数后添加到每层链表的头节点
-    fn insert_min(&mut self, key: K, value: V) {
-        let new_level = self.rand_lelve();
-        let new_node = SkipListNode::new(key, value, new_level);
-        let new_node = Box::new(new_node);
-        let new_node = unsafe { NonNull::new_unchecked(Box::into_raw(new_node)) };
-
-        for i in 0..=new_level {
-            unsafe {
-                (*new_node.as_ptr()).forward[i] = self.lists[i].take();
-                self.lists[i] = Some(new_node);
+    /// ```
+    /// use impx::skip_list::SkipList;
+    ///
+    /// let mut sl = SkipList::<8, _, _>::new();
+    /// for i in [10, 30, 20] {
+    ///     sl.insert(i, ());
+    /// }
+    /// assert_eq!(sl.rank(&20), 1);
+    /// assert_eq!(sl.rank(&25), 2);
+    /// assert_eq!(sl.get_by_rank(2), Some((&30, &())));
+    /// ```
+    pub fn rank(&self, key: &K) -> usize {
+        self.search(key).1[0]
+    }
+
+    /// 按排名查找元素, 排名从 0 开始
+    pub fn get_by_rank(&self, index: usize) -> Option<(&K, &V)> {
+        if index >= self.length {
+            return None;
+        }
+
+        self.search_rank(index).2.map(|node| {
+            let node = unsafe { node.as_ref() };
+            (&node.key, &node.value)
+        })
+    }
+
+    /// 在排名 index 处插入元素, 之后的元素排名加一
+    ///
+    /// 插入后仍然要保持 key 有序, 所以 key 必须严格大于前一个元素且严格小于原来排名为 index 的元素  
+    /// 已知插入位置时可以省去 key 的比较, 例如以递增的 key 把跳表当作序列使用
+    pub fn insert_at(&mut self, index: usize, key: K, value: V) {
+        assert!(index <= self.length, "index out of bounds");
+
+        let (update, rank, next) = self.search_rank(index);
+        let prev_ok = update[0].is_none_or(|node| unsafe { &node.as_ref().key } < &key);
+        let next_ok = next.is_none_or(|node| &key < unsafe { &node.as_ref().key });
+        assert!(prev_ok && next_ok, "key out of order at index");
+
+        self.link_node(&update, &rank, key, value);
+    }
+
+    /// 删除排名为 index 的元素
+    pub fn remove_at(&mut self, index: usize) -> Option<(K, V)> {
+        if index >= self.length {
+            return None;
+        }
+
+        let (update, _, next) = self.search_rank(index);
+        next.map(|node| self.unlink_node(&update, node))
+    }
+
+    /// 返回最小的元素对
+    pub fn first(&self) -> Option<(&K, &V)> {
+        self.lists[0].map(|node| {
+            let node = unsafe { node.as_ref() };
+            (&node.key, &node.value)
+        })
+    }
+
+    /// 返回最大的元素对
+    ///
+    /// 从最高层开始向右走到底再下降, 直到第 0 层的最后一个节点
+    pub fn last(&self) -> Option<(&K, &V)> {
+        let mut last: Link<N, K, V> = None;
+        for i in (0..=self.level).rev() {
+            let mut next = match last {
+                None => self.lists[i],
+                Some(node) => unsafe { node.as_ref().forward[i] },
+            };
+
+            while let Some(node) = next {
+                last = Some(node);
+                next = unsafe { node.as_ref().forward[i] };
             }
         }
 
-        self.length += 1;
-        self.level = self.level.max(new_level);
+        last.map(|node| {
+            let node = unsafe { node.as_ref() };
+            (&node.key, &node.value)
+        })
     }
 
     /// 删除指定 key 的节点
     ///
-    /// 需要从上至下找到 key 所在的节点或者前一个节点, 更新每层的链表, 最后 drop 堆内存
+    /// 需要从上至下找到每一层 key 所在节点的前一个节点, 更新每层的链表, 最后 drop 堆内存
     pub fn delete(&mut self, key: &K) -> Option<V> {
-        let mut prev = None;
-        let mut update = [None; N];
-        for i in (0..=self.level).rev() {
-            if prev.is_none() {
-                prev = self.lists[i];
+        let (update, _, next) = self.search(key);
+        match next {
+            Some(node) if unsafe { &node.as_ref().key } == key => {
+                Some(self.unlink_node(&update, node).1)
             }
+            _ => None,
+        }
+    }
+}
 
-            if let Some(mut head) = prev {
-                let mut head_ref = unsafe { head.as_ref() };
+/// 查找路径: 每一层的前一个节点, 它们的位置, 以及第 0 层上的下一个节点
+type Path<const N: usize, K, V> = ([Link<N, K, V>; N], [usize; N], Link<N, K, V>);
 
-                // 第 i 层的头节点都大于 key 说明 key 所在节点没有在第 i 层无需处理
-                if &head_ref.key > key {
-                    prev = None;
-                    continue;
-                }
-
-                // 第 i 层的头节点等于 key 添加到 update 等待后续替换掉头节点
-                if &head_ref.key == key {
-                    prev = None;
-                    update[i] = Some(head);
-                    continue;
-                }
+impl<const N: usize, K, V, R> SkipList<N, K, V, R> {
+    /// 第 i 层 prev 之后的链接及其 span, prev 为 None 时表示头节点
+    fn next_of(&self, prev: Link<N, K, V>, i: usize) -> (Link<N, K, V>, usize) {
+        match prev {
+            None => (self.lists[i], self.spans[i]),
+            Some(node) => {
+                let node = unsafe { node.as_ref() };
+                (node.forward[i], node.span[i])
+            }
+        }
+    }
 
-                // 第 i 层的头节点小于 key 则需要找到当前层小于 key 的最大节点
-                if &head_ref.key < key {
-                    while let Some(node) = head_ref.forward[i] {
-                        let node_ref = unsafe { node.as_ref() };
-                        if &node_ref.key < key {
-                            head_ref = node_ref;
-                            head = node;
-                        } else {
-                            break;
-                        }
-                    }
+    /// 同 [`SkipList::next_of`], 返回可修改的链接和 span
+    fn slot(&mut self, prev: Link<N, K, V>, i: usize) -> (&mut Link<N, K, V>, &mut usize) {
+        match prev {
+            None => (&mut self.lists[i], &mut self.spans[i]),
+            Some(node) => {
+                let node = unsafe { &mut *node.as_ptr() };
+                (&mut node.forward[i], &mut node.span[i])
+            }
+        }
+    }
 
-                    prev = Some(head);
-                    update[i] = Some(head);
-                }
+    /// 把第 0 层上 update 之后的节点从每一层摘下来并释放, 更高层跨过该节点的链接 span 减一
+    fn unlink_node(
+        &mut self,
+        update: &[Link<N, K, V>; N],
+        node: NonNull<SkipListNode<N, K, V>>,
+    ) -> (K, V) {
+        let node_ref = unsafe { node.as_ref() };
+        for (i, &prev) in update.iter().enumerate() {
+            let (next, span) = self.slot(prev, i);
+            if *next == Some(node) {
+                *next = node_ref.forward[i];
+                *span = match next {
+                    None => 0,
+                    Some(_) => *span + node_ref.span[i] - 1,
+                };
+            } else if next.is_some() {
+                *span -= 1;
             }
         }
 
-        let mut raw_ptr = None;
-        for i in (0..=self.level).rev() {
-            match update[i] {
-                None => continue,
-                Some(mut head) => {
-                    let head_ref = unsafe { head.as_ref() };
-                    let head_mut = unsafe { head.as_mut() };
-
-                    // 替换当前层的头节点
-                    if &head_ref.key == key {
-                        let head_next = head_mut.forward[i].take();
-                        self.lists[i] = head_next;
-                        raw_ptr = Some(head.as_ptr());
-                        continue;
-                    }
+        self.length -= 1;
+        self.level = (0..N).rev().find(|&i| self.lists[i].is_some()).unwrap_or(0);
 
-                    if let Some(mut node) = head_ref.forward[i] {
-                        let node_key = unsafe { &node.as_ref().key };
-                        if node_key == key {
-                            let node_next = unsafe { node.as_mut().forward[i] };
-                            head_mut.forward[i] = node_next;
-                            raw_ptr = Some(node.as_ptr());
-                        }
-                    }
-                }
+        let node = self.free_node(node);
+        (node.key, node.value)
+    }
+
+    /// 优先从节点池中取出空闲节点存放 node, 节点池为空时新分配
+    fn alloc_node(&mut self, node: SkipListNode<N, K, V>) -> NonNull<SkipListNode<N, K, V>> {
+        match self.pool.pop() {
+            Some(ptr) => {
+                unsafe { ptr.as_ptr().write(node) };
+                ptr
             }
+            None => unsafe { NonNull::new_unchecked(Box::into_raw(Box::new(node))) },
         }
+    }
 
-        // 清理原始指针对应的堆内存
-        if let Some(ptr) = raw_ptr {
-            self.length -= 1;
-            for i in (0..=self.level).rev() {
-                if self.lists[i].is_some() {
-                    self.level = i;
-                    break;
-                }
+    /// 把节点的内容移出, 节点池未满时保留节点的内存, 否则释放
+    fn free_node(&mut self, ptr: NonNull<SkipListNode<N, K, V>>) -> SkipListNode<N, K, V> {
+        let node = unsafe { ptr.as_ptr().read() };
+        if self.pool.len() < self.pool_capacity {
+            self.pool.push(ptr);
+        } else {
+            Self::dealloc_node(ptr);
+        }
+        node
+    }
+
+    /// 释放内容已经移出的节点, 通过 MaybeUninit 只释放内存而不析构内容
+    fn dealloc_node(ptr: NonNull<SkipListNode<N, K, V>>) {
+        drop(unsafe { Box::from_raw(ptr.as_ptr() as *mut MaybeUninit<SkipListNode<N, K, V>>) });
+    }
+
+    /// 修改节点池的容量, 多出的空闲节点会被释放, 容量为 0 时关闭节点池
+    pub fn set_pool_capacity(&mut self, capacity: usize) {
+        self.pool_capacity = capacity;
+        while self.pool.len() > capacity {
+            Self::dealloc_node(self.pool.pop().unwrap());
+        }
+    }
+
+    /// 节点池中的空闲节点数量
+    pub fn pool_len(&self) -> usize {
+        self.pool.len()
+    }
+
+    /// 清空跳表
+    ///
+    /// 所有节点都在第 0 层链表上, 沿第 0 层释放每个节点即可, 开启节点池时节点会放回池中
+    pub fn clear(&mut self) {
+        let mut curr = self.lists[0];
+        while let Some(node) = curr {
+            let node = self.free_node(node);
+            curr = node.forward[0];
+        }
+
+        self.lists = [SkipListNode::NONE_NODE; N];
+        self.spans = [0; N];
+        self.length = 0;
+        self.level = 0;
+    }
+
+    /// 按 key 从小到大遍历的引用迭代器
+    pub fn iter(&self) -> Iter<'_, N, K, V> {
+        Iter {
+            next: self.lists[0],
+            remain: self.length,
+            _marker: PhantomData,
+        }
+    }
+
+    /// 按 key 从小到大遍历的可变引用迭代器, 只能修改 value
+    pub fn iter_mut(&mut self) -> IterMut<'_, N, K, V> {
+        IterMut {
+            next: self.lists[0],
+            remain: self.length,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// 沿第 0 层链表遍历的引用迭代器
+pub struct Iter<'a, const N: usize, K, V> {
+    next: Link<N, K, V>,
+    remain: usize,
+    _marker: PhantomData<&'a SkipListNode<N, K, V>>,
+}
+
+impl<'a, const N: usize, K, V> Iterator for Iter<'a, N, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next.map(|node| {
+            let node = unsafe { node.as_ref() };
+            self.next = node.forward[0];
+            self.remain -= 1;
+            (&node.key, &node.value)
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remain, Some(self.remain))
+    }
+}
+
+impl<const N: usize, K, V> ExactSizeIterator for Iter<'_, N, K, V> {}
+
+/// 沿第 0 层链表遍历的可变引用迭代器
+pub struct IterMut<'a, const N: usize, K, V> {
+    next: Link<N, K, V>,
+    remain: usize,
+    _marker: PhantomData<&'a mut SkipListNode<N, K, V>>,
+}
+
+impl<'a, const N: usize, K, V> Iterator for IterMut<'a, N, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next.map(|node| {
+            let node = unsafe { &mut *node.as_ptr() };
+            self.next = node.forward[0];
+            self.remain -= 1;
+            (&node.key, &mut node.value)
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remain, Some(self.remain))
+    }
+}
+
+impl<const N: usize, K, V> ExactSizeIterator for IterMut<'_, N, K, V> {}
+
+/// 值迭代器, 每次从跳表头部取出最小节点
+pub struct IntoIter<const N: usize, K, V, R = StdRng> {
+    list: SkipList<N, K, V, R>,
+}
+
+impl<const N: usize, K, V, R> Iterator for IntoIter<N, K, V, R> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // 最小节点一定是它所在的每一层链表的头节点
+        let node = self.list.lists[0]?;
+        Some(self.list.unlink_node(&[None; N], node))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.length, Some(self.list.length))
+    }
+}
+
+impl<const N: usize, K, V, R> ExactSizeIterator for IntoIter<N, K, V, R> {}
+
+impl<const N: usize, K, V, R> IntoIterator for SkipList<N, K, V, R> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<N, K, V, R>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { list: self }
+    }
+}
+
+impl<'a, const N: usize, K, V, R> IntoIterator for &'a SkipList<N, K, V, R> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, N, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, const N: usize, K, V, R> IntoIterator for &'a mut SkipList<N, K, V, R> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, N, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<const N: usize, K: Ord, V, R> SkipList<N, K, V, R> {
+    /// 检查跳表的内部性质
+    ///
+    /// - 第 0 层链表的节点数量等于 length
+    /// - 每层链表都严格有序
+    /// - 每层链表上的节点都在第 0 层链表上, 且节点的最高层不低于当前层
+    /// - 最高层 level 以上的链表都为空
+    /// - 每条链接的 span 等于两端节点的位置之差, 空链接的 span 为 0
+    pub fn check(&self) {
+        let mut level0 = std::collections::HashMap::new();
+        let mut curr = self.lists[0];
+        while let Some(node) = curr {
+            level0.insert(node, level0.len() + 1);
+            curr = unsafe { node.as_ref() }.forward[0];
+        }
+        debug_assert_eq!(level0.len(), self.length, "level 0 length mismatch");
+
+        for i in 0..N {
+            if i > self.level {
+                debug_assert!(self.lists[i].is_none(), "level {i} above max level");
             }
 
-            let node = unsafe { Box::from_raw(ptr) };
-            return Some(node.value);
+            let mut curr = self.lists[i];
+            let mut prev: Option<&K> = None;
+            let (mut pos, mut span) = (0, self.spans[i]);
+            if curr.is_none() {
+                debug_assert_eq!(span, 0, "level {i} empty link with span");
+            }
+            while let Some(node) = curr {
+                let node_ref = unsafe { node.as_ref() };
+                debug_assert!(
+                    level0.contains_key(&node),
+                    "level {i} not subset of level 0"
+                );
+                debug_assert_eq!(pos + span, level0[&node], "level {i} span mismatch");
+                (pos, span) = (level0[&node], node_ref.span[i]);
+                if node_ref.forward[i].is_none() {
+                    debug_assert_eq!(span, 0, "level {i} empty link with span");
+                }
+                debug_assert!(node_ref.level >= i, "node level lower than list level {i}");
+                if let Some(prev) = prev {
+                    debug_assert!(prev < &node_ref.key, "level {i} not sorted");
+                }
+
+                prev = Some(&node_ref.key);
+                curr = node_ref.forward[i];
+            }
         }
+    }
+}
 
-        None
+impl<const N: usize, K, V, R> Drop for SkipList<N, K, V, R> {
+    fn drop(&mut self) {
+        self.clear();
+        self.set_pool_capacity(0);
     }
 }
 
@@ -334,7 +682,7 @@ impl<const N: usize, K: Ord, V> Default for SkipList<N, K, V> {
     }
 }
 
-impl<const N: usize, K: Ord + Debug, V: Debug> Debug for SkipList<N, K, V> {
+impl<const N: usize, K: Ord + Debug, V: Debug, R> Debug for SkipList<N, K, V, R> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         for i in (0..N).rev() {
             write!(f, "{i}: ")?;
@@ -355,6 +703,33 @@ impl<const N: usize, K: Ord + Debug, V: Debug> Debug for SkipList<N, K, V> {
     }
 }
 
+/// 序列化为按 key 升序的 key-value 列表, 反序列化时逐个插入重新构建
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    impl<const N: usize, K: Serialize, V: Serialize, R> Serialize for SkipList<N, K, V, R> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(self.iter())
+        }
+    }
+
+    impl<'de, const N: usize, K, V> Deserialize<'de> for SkipList<N, K, V>
+    where
+        K: Ord + Deserialize<'de>,
+        V: Deserialize<'de>,
+    {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let mut list = SkipList::new();
+            for (key, value) in Vec::<(K, V)>::deserialize(deserializer)? {
+                list.insert(key, value);
+            }
+            Ok(list)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -386,6 +761,7 @@ mod tests {
 
         assert!(sl.find(&0).is_none());
         assert!(sl.find(&9).is_none());
+        sl.check();
     }
 
     #[test]
@@ -418,4 +794,256 @@ mod tests {
 
         println!("{:?}", sl);
     }
+
+    #[test]
+    fn test_clear() {
+        use std::rc::Rc;
+
+        let value = Rc::new(());
+        let mut sl: SkipList<8, i32, Rc<()>> = SkipList::new();
+        assert!(sl.is_empty());
+
+        for i in 0..100 {
+            sl.insert(i, value.clone());
+            sl.check();
+        }
+
+        assert!(!sl.is_empty());
+        assert_eq!(Rc::strong_count(&value), 101);
+
+        sl.clear();
+        sl.check();
+        assert!(sl.is_empty());
+        assert_eq!(Rc::strong_count(&value), 1);
+
+        for i in (0..100).rev() {
+            sl.insert(i, value.clone());
+        }
+
+        for i in (0..100).step_by(2) {
+            assert!(sl.delete(&i).is_some());
+            sl.check();
+        }
+
+        assert_eq!(sl.len(), 50);
+        assert_eq!(Rc::strong_count(&value), 51);
+
+        drop(sl);
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+
+    #[test]
+    fn test_pool() {
+        use std::rc::Rc;
+
+        let value = Rc::new(());
+        let mut sl: SkipList<8, i32, Rc<()>> = SkipList::with_pool_capacity(16);
+        for round in 0..3 {
+            for i in 0..100 {
+                sl.insert(i, value.clone());
+            }
+            // 第一轮之后前 16 次插入复用池中的节点
+            assert_eq!(sl.pool_len(), 0, "round {round}");
+
+            for i in 0..40 {
+                assert!(sl.delete(&i).is_some());
+                sl.check();
+            }
+            // 节点池最多保留 16 个节点, 移出的 value 都已经析构
+            assert_eq!(sl.pool_len(), 16);
+            assert_eq!(Rc::strong_count(&value), 61);
+
+            sl.clear();
+            sl.check();
+            assert_eq!(Rc::strong_count(&value), 1);
+        }
+
+        for i in 0..10 {
+            sl.insert(i, value.clone());
+        }
+        assert_eq!(sl.pool_len(), 6);
+        assert!(sl.iter().map(|(k, _)| *k).eq(0..10));
+
+        sl.set_pool_capacity(2);
+        assert_eq!(sl.pool_len(), 2);
+        let values: Vec<_> = sl.into_iter().map(|(_, v)| v).collect();
+        assert_eq!(Rc::strong_count(&value), 11);
+        drop(values);
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut sl: SkipList<8, i32, i32> = SkipList::new();
+        assert!(sl.first().is_none());
+        assert!(sl.last().is_none());
+        assert!(sl.iter().next().is_none());
+
+        for i in [5, 3, 9, 1, 7] {
+            sl.insert(i, i * 10);
+        }
+
+        assert_eq!(sl.first(), Some((&1, &10)));
+        assert_eq!(sl.last(), Some((&9, &90)));
+
+        let keys: Vec<_> = sl.iter().map(|(k, _)| *k).collect();
+        assert_eq!(keys, vec![1, 3, 5, 7, 9]);
+        assert_eq!(sl.iter().len(), 5);
+
+        for (k, v) in &mut sl {
+            *v += k;
+        }
+
+        let items: Vec<_> = (&sl).into_iter().map(|(k, v)| (*k, *v)).collect();
+        assert_eq!(items, vec![(1, 11), (3, 33), (5, 55), (7, 77), (9, 99)]);
+
+        let mut it = sl.into_iter();
+        assert_eq!(it.next(), Some((1, 11)));
+        assert_eq!(it.next(), Some((3, 33)));
+        assert_eq!(it.len(), 3);
+    }
+
+    #[test]
+    fn test_get_or_insert_with() {
+        let mut sl: SkipList<8, &str, usize> = SkipList::new();
+        for word in ["b", "a", "c", "a", "b", "a"] {
+            *sl.get_or_insert_with(word, || 0) += 1;
+            sl.check();
+        }
+
+        assert_eq!(sl.len(), 3);
+        let counts: Vec<_> = sl.into_iter().collect();
+        assert_eq!(counts, vec![("a", 3), ("b", 2), ("c", 1)]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_skip_list_serde() {
+        let mut list = SkipList::<8, _, _>::new();
+        for i in [3, 1, 4, 1, 5, 9, 2, 6] {
+            list.insert(i, i * 10);
+        }
+
+        let json = serde_json::to_string(&list).unwrap();
+        assert_eq!(json, "[[1,10],[2,20],[3,30],[4,40],[5,50],[6,60],[9,90]]");
+
+        let list: SkipList<8, i32, i32> = serde_json::from_str(&json).unwrap();
+        list.check();
+        assert_eq!(list.len(), 7);
+        assert_eq!(list.find(&9), Some(&90));
+        assert_eq!(serde_json::to_string(&list).unwrap(), json);
+    }
+
+    #[test]
+    fn test_rank() {
+        let mut sl: SkipList<8, i32, i32> = SkipList::new();
+        assert_eq!(sl.rank(&0), 0);
+        assert!(sl.get_by_rank(0).is_none());
+        assert!(sl.remove_at(0).is_none());
+
+        for i in [50, 10, 40, 20, 30] {
+            sl.insert(i, i / 10);
+        }
+        sl.check();
+
+        assert_eq!(sl.rank(&10), 0);
+        assert_eq!(sl.rank(&35), 3);
+        assert_eq!(sl.rank(&60), 5);
+        assert_eq!(sl.get_by_rank(0), Some((&10, &1)));
+        assert_eq!(sl.get_by_rank(4), Some((&50, &5)));
+        assert!(sl.get_by_rank(5).is_none());
+
+        sl.insert_at(0, 0, 0);
+        sl.insert_at(6, 60, 6);
+        sl.insert_at(3, 25, 2);
+        sl.check();
+        let keys: Vec<_> = sl.iter().map(|(k, _)| *k).collect();
+        assert_eq!(keys, vec![0, 10, 20, 25, 30, 40, 50, 60]);
+
+        assert_eq!(sl.remove_at(3), Some((25, 2)));
+        assert_eq!(sl.remove_at(0), Some((0, 0)));
+        assert_eq!(sl.remove_at(5), Some((60, 6)));
+        assert!(sl.remove_at(5).is_none());
+        sl.check();
+        assert_eq!(sl.len(), 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "key out of order at index")]
+    fn test_insert_at_out_of_order() {
+        let mut sl: SkipList<8, i32, ()> = SkipList::new();
+        sl.insert(1, ());
+        sl.insert(3, ());
+        sl.insert_at(0, 2, ());
+    }
+
+    #[test]
+    fn test_rank_random() {
+        use rand::rngs::StdRng;
+        use std::collections::BTreeMap;
+
+        let mut rng = StdRng::seed_from_u64(0x5eed);
+        let mut sl: SkipList<8, u32, u32, _> = SkipList::with_rng(StdRng::seed_from_u64(1));
+        let mut model = BTreeMap::new();
+        for i in 0..3000 {
+            let key = rng.gen_range(0..500);
+            match rng.gen_range(0..6) {
+                0 | 1 => assert_eq!(sl.insert(key, i), model.insert(key, i)),
+                2 => assert_eq!(sl.delete(&key), model.remove(&key)),
+                3 => {
+                    let index = rng.gen_range(0..=model.len());
+                    let expect = model.keys().nth(index).copied();
+                    assert_eq!(sl.remove_at(index).map(|e| e.0), expect);
+                    if let Some(k) = expect {
+                        model.remove(&k);
+                    }
+                }
+                4 => assert_eq!(sl.rank(&key), model.range(..key).count()),
+                _ => {
+                    let index = rng.gen_range(0..=model.len());
+                    assert_eq!(sl.get_by_rank(index), model.iter().nth(index));
+                }
+            }
+
+            if i % 50 == 0 {
+                sl.check();
+            }
+        }
+
+        // 把跳表当作序列, 在尾部按位置追加
+        let mut sl: SkipList<8, usize, char> = SkipList::new();
+        for (i, c) in "skiplist".chars().enumerate() {
+            sl.insert_at(i, i, c);
+        }
+        sl.check();
+        let s: String = (0..sl.len())
+            .map(|i| *sl.get_by_rank(i).unwrap().1)
+            .collect();
+        assert_eq!(s, "skiplist");
+    }
+
+    #[test]
+    fn test_with_rng() {
+        use crate::rng::{Pcg32, XorShift64Star};
+
+        // 相同的种子得到相同的层数分布, Debug 输出包含每一层的所有节点
+        let build = |seed| {
+            let mut sl: SkipList<8, i32, (), _> = SkipList::with_rng(Pcg32::seed_from_u64(seed));
+            for i in 0..100 {
+                sl.insert(i, ());
+            }
+            sl.delete(&50);
+            format!("{sl:?}")
+        };
+        assert_eq!(build(7), build(7));
+        assert_ne!(build(7), build(8));
+
+        let mut sl: SkipList<8, i32, i32, _> = SkipList::with_rng(XorShift64Star::seed_from_u64(7));
+        for i in 0..1000 {
+            sl.insert(i, -i);
+        }
+        sl.check();
+        assert_eq!(sl.get_by_rank(500), Some((&500, &-500)));
+        assert!(sl.into_iter().map(|(k, v)| k + v).all(|x| x == 0));
+    }
 }
@@ -0,0 +1,408 @@
+//! 小数组优化的动态数组
+//!
+//! 元素数量不超过 N 时直接存放在结构体内部的数组中, 不需要堆分配;
+//! 超过 N 之后把全部元素搬到堆上的 [`MyVec`] 中, 之后即使元素减少也不再搬回来
+//!
+//! 内联数组使用 `[MaybeUninit<T>; N]`, 只有 `[0, len)` 之内的位置已经初始化,
+//! 所以需要自己负责这些元素的析构
+
+use std::fmt::Debug;
+use std::mem::{self, MaybeUninit};
+use std::ops::{Deref, DerefMut};
+use std::ptr;
+
+use crate::my_vec::{self, MyVec};
+
+enum Data<T, const N: usize> {
+    Inline {
+        buf: [MaybeUninit<T>; N],
+        len: usize,
+    },
+    Heap(MyVec<T>),
+}
+
+pub struct SmallVec<T, const N: usize> {
+    data: Data<T, N>,
+}
+
+impl<T, const N: usize> SmallVec<T, N> {
+    pub fn new() -> Self {
+        Self {
+            data: Data::Inline {
+                buf: [const { MaybeUninit::uninit() }; N],
+                len: 0,
+            },
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match &self.data {
+            Data::Inline { len, .. } => *len,
+            Data::Heap(v) => v.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        match &self.data {
+            Data::Inline { .. } => N,
+            Data::Heap(v) => v.capacity(),
+        }
+    }
+
+    /// 元素是否已经搬到堆上
+    pub fn spilled(&self) -> bool {
+        matches!(self.data, Data::Heap(_))
+    }
+
+    /// 内联数组已满时把所有元素搬到堆上, 容量取 2N
+    fn spill(&mut self) {
+        let Data::Inline { buf, len } = &mut self.data else {
+            return;
+        };
+
+        let mut heap = MyVec::with_capacity(2 * N);
+        for slot in &buf[..*len] {
+            heap.push(unsafe { slot.assume_init_read() });
+        }
+        // 元素已经全部移出, 先把长度置零避免重复析构
+        *len = 0;
+        self.data = Data::Heap(heap);
+    }
+
+    /// 在末尾追加元素, 内联数组放满后搬到堆上
+    pub fn push(&mut self, value: T) {
+        if self.len() == N {
+            self.spill();
+        }
+
+        match &mut self.data {
+            Data::Inline { buf, len } => {
+                buf[*len].write(value);
+                *len += 1;
+            }
+            Data::Heap(v) => v.push(value),
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        match &mut self.data {
+            Data::Inline { buf, len } => {
+                if *len == 0 {
+                    return None;
+                }
+                *len -= 1;
+                Some(unsafe { buf[*len].assume_init_read() })
+            }
+            Data::Heap(v) => v.pop(),
+        }
+    }
+
+    /// 在 index 处插入元素, 之后的元素整体向后移动一位
+    pub fn insert(&mut self, index: usize, value: T) {
+        assert!(index <= self.len(), "index out of bounds");
+        if self.len() == N {
+            self.spill();
+        }
+
+        match &mut self.data {
+            Data::Inline { buf, len } => {
+                unsafe {
+                    let p = buf.as_mut_ptr() as *mut T;
+                    ptr::copy(p.add(index), p.add(index + 1), *len - index);
+                    ptr::write(p.add(index), value);
+                }
+                *len += 1;
+            }
+            Data::Heap(v) => v.insert(index, value),
+        }
+    }
+
+    /// 删除 index 处的元素, 之后的元素整体向前移动一位
+    pub fn remove(&mut self, index: usize) -> T {
+        assert!(index < self.len(), "index out of bounds");
+        match &mut self.data {
+            Data::Inline { buf, len } => unsafe {
+                *len -= 1;
+                let p = buf.as_mut_ptr() as *mut T;
+                let value = ptr::read(p.add(index));
+                ptr::copy(p.add(index + 1), p.add(index), *len - index);
+                value
+            },
+            Data::Heap(v) => v.remove(index),
+        }
+    }
+
+    /// 析构所有元素, 已经搬到堆上的保留堆上的容量
+    pub fn clear(&mut self) {
+        match &mut self.data {
+            Data::Inline { buf, len } => {
+                let elems = ptr::slice_from_raw_parts_mut(buf.as_mut_ptr() as *mut T, *len);
+                // 先把长度置零, 即使某个元素析构时 panic 也不会再次析构
+                *len = 0;
+                unsafe { ptr::drop_in_place(elems) }
+            }
+            Data::Heap(v) => v.clear(),
+        }
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        match &self.data {
+            Data::Inline { buf, len } => unsafe {
+                std::slice::from_raw_parts(buf.as_ptr() as *const T, *len)
+            },
+            Data::Heap(v) => v,
+        }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        match &mut self.data {
+            Data::Inline { buf, len } => unsafe {
+                std::slice::from_raw_parts_mut(buf.as_mut_ptr() as *mut T, *len)
+            },
+            Data::Heap(v) => v,
+        }
+    }
+}
+
+impl<T, const N: usize> Default for SmallVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Drop for SmallVec<T, N> {
+    /// 内联的元素需要手动析构, 堆上的由 MyVec 负责
+    fn drop(&mut self) {
+        if let Data::Inline { .. } = self.data {
+            self.clear();
+        }
+    }
+}
+
+impl<T, const N: usize> Deref for SmallVec<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, const N: usize> DerefMut for SmallVec<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for SmallVec<T, N> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: Debug, const N: usize> Debug for SmallVec<T, N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, const N: usize> Extend<T> for SmallVec<T, N> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|value| self.push(value));
+    }
+}
+
+impl<T, const N: usize> FromIterator<T> for SmallVec<T, N> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut v = SmallVec::new();
+        v.extend(iter);
+        v
+    }
+}
+
+/// 值迭代器, 内联时 `[start, end)` 之内是还没有取出的元素
+pub enum IntoIter<T, const N: usize> {
+    Inline {
+        buf: [MaybeUninit<T>; N],
+        start: usize,
+        end: usize,
+    },
+    Heap(my_vec::IntoIter<T>),
+}
+
+impl<T, const N: usize> IntoIterator for SmallVec<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        // 把数据的所有权转移给迭代器, SmallVec 本身不能再析构
+        let this = mem::ManuallyDrop::new(self);
+        match unsafe { ptr::read(&this.data) } {
+            Data::Inline { buf, len } => IntoIter::Inline {
+                buf,
+                start: 0,
+                end: len,
+            },
+            Data::Heap(v) => IntoIter::Heap(v.into_iter()),
+        }
+    }
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        match self {
+            IntoIter::Inline { buf, start, end } => {
+                if start == end {
+                    return None;
+                }
+                *start += 1;
+                Some(unsafe { buf[*start - 1].assume_init_read() })
+            }
+            IntoIter::Heap(it) => it.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            IntoIter::Inline { start, end, .. } => (*end - *start, Some(*end - *start)),
+            IntoIter::Heap(it) => it.size_hint(),
+        }
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+    fn next_back(&mut self) -> Option<T> {
+        match self {
+            IntoIter::Inline { buf, start, end } => {
+                if start == end {
+                    return None;
+                }
+                *end -= 1;
+                Some(unsafe { buf[*end].assume_init_read() })
+            }
+            IntoIter::Heap(it) => it.next_back(),
+        }
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> Drop for IntoIter<T, N> {
+    /// 只析构还没有取出的内联元素
+    fn drop(&mut self) {
+        if let IntoIter::Inline { buf, start, end } = self {
+            let rest = ptr::slice_from_raw_parts_mut(
+                unsafe { (buf.as_mut_ptr() as *mut T).add(*start) },
+                *end - *start,
+            );
+            *start = *end;
+            unsafe { ptr::drop_in_place(rest) }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_small_vec() {
+        let mut v = SmallVec::<i32, 4>::new();
+        assert!(v.is_empty());
+        assert_eq!(v.capacity(), 4);
+        assert_eq!(v.pop(), None);
+
+        for i in 0..4 {
+            v.push(i);
+        }
+        assert!(!v.spilled());
+        v.insert(0, -1);
+        assert!(v.spilled());
+        assert_eq!(v.capacity(), 8);
+        assert_eq!(*v, [-1, 0, 1, 2, 3]);
+
+        assert_eq!(v.remove(0), -1);
+        assert_eq!(v.pop(), Some(3));
+        // 已经搬到堆上的不再搬回来
+        assert!(v.spilled());
+        assert_eq!(*v, [0, 1, 2]);
+
+        let mut v: SmallVec<i32, 5> = (0..3).collect();
+        v.insert(1, 10);
+        v.insert(4, 20);
+        assert!(!v.spilled());
+        assert_eq!(*v, [0, 10, 1, 2, 20]);
+        assert_eq!(v.remove(1), 10);
+        v.reverse();
+        assert_eq!(format!("{:?}", v.clone()), "[20, 2, 1, 0]");
+        v.clear();
+        assert!(v.is_empty());
+
+        // N = 0 时总是在堆上
+        let mut v = SmallVec::<i32, 0>::new();
+        v.push(1);
+        assert!(v.spilled());
+        assert_eq!(*v, [1]);
+    }
+
+    #[test]
+    fn test_small_vec_spill() {
+        for n in 0..12 {
+            let v: SmallVec<_, 5> = (0..n).map(|i| i.to_string()).collect();
+            assert_eq!(v.len(), n);
+            assert_eq!(v.spilled(), n > 5);
+            assert!(v.iter().map(|s| s.parse::<usize>().unwrap()).eq(0..n));
+
+            let mut it = v.into_iter();
+            if n > 0 {
+                assert_eq!(it.next_back(), Some((n - 1).to_string()));
+            }
+            assert!(it
+                .map(|s| s.parse::<usize>().unwrap())
+                .eq(0..n.saturating_sub(1)));
+        }
+    }
+
+    #[test]
+    fn test_small_vec_drop() {
+        let rc = Rc::new(());
+        for n in 0..8 {
+            let mut v = SmallVec::<_, 3>::new();
+            for _ in 0..n {
+                v.push(rc.clone());
+            }
+            assert_eq!(Rc::strong_count(&rc), n + 1);
+            drop(v.clone());
+            assert_eq!(Rc::strong_count(&rc), n + 1);
+
+            if n > 0 {
+                drop(v.remove(0));
+                v.insert(0, rc.clone());
+            }
+            assert_eq!(Rc::strong_count(&rc), n + 1);
+
+            // 只取出一部分, 剩下的由迭代器析构
+            let mut it = v.clone().into_iter();
+            drop(it.next());
+            drop(it);
+            assert_eq!(Rc::strong_count(&rc), n + 1);
+
+            drop(v);
+            assert_eq!(Rc::strong_count(&rc), 1);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bounds")]
+    fn test_small_vec_remove_out_of_bounds() {
+        let mut v = SmallVec::<i32, 2>::new();
+        v.push(1);
+        v.remove(1);
+    }
+}
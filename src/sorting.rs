@@ -20,6 +20,10 @@
 /// ```
 pub fn bubble_sort<T: PartialOrd>(v: &mut [T]) {
     let n = v.len();
+    if n <= 1 {
+        return;
+    }
+
     for i in 0..(n - 1) {
         for j in 0..(n - 1 - i) {
             if v[j] > v[j + 1] {
@@ -51,10 +55,15 @@ pub fn bubble_sort<T: PartialOrd>(v: &mut [T]) {
 /// assert!(a.is_sorted());
 /// ```
 pub fn cocktail_sort<T: PartialOrd>(v: &mut [T]) {
+    if v.len() <= 1 {
+        return;
+    }
+
     let (mut low, mut high) = (0, v.len() - 1);
     let mut swapped = true;
 
-    while swapped {
+    // 未排序部分只剩一个元素时结束, 否则 high 可能会减到 0 以下
+    while swapped && low < high {
         swapped = false;
         for i in (low + 1)..=high {
             if v[i] < v[i - 1] {
@@ -98,6 +107,10 @@ pub fn cocktail_sort<T: PartialOrd>(v: &mut [T]) {
 /// ```
 pub fn selection_sort<T: PartialOrd>(v: &mut [T]) {
     let n = v.len();
+    if n <= 1 {
+        return;
+    }
+
     for i in 0..(n - 1) {
         let mut min_idx = i;
         for j in (i + 1)..n {
@@ -192,7 +205,7 @@ pub fn shell_sort<T: PartialOrd>(v: &mut [T]) {
 /// <https://oi-wiki.org/basic/bucket-sort/>  
 /// <https://zh.wikipedia.org/wiki/桶排序>  
 /// 时间复杂度: 最优 O(n) 最坏 O(n^2) 平均 O(n+n^2/k+k)  
-/// 空间复杂度: 需要额外的桶空间 O(k)  
+/// 空间复杂度: 需要额外的桶空间 O(k) 这里取 k = n 即 O(n)  
 /// 稳定性: 依赖于内层排序算法, 如果用插入排序则为稳定
 ///
 /// 桶排序的思想是设置一些整体有序的桶, 通过把序列中的元素按照大小分布分散到各个桶之中  
@@ -204,32 +217,34 @@ pub fn shell_sort<T: PartialOrd>(v: &mut [T]) {
 /// - 遍历所有桶将桶内的数据排序, 可以使用插入排序, 也可以递归使用桶排序
 /// - 按顺序将桶中的元素放入原先的序列
 ///
+/// 桶的数量不能按照值域计算(比如每 8 个值一个桶), 否则值域很大时(比如包含 `usize::MAX`)需要分配的桶数量会爆炸  
+/// 这里固定使用 n 个桶, 把 `[min, max]` 均匀映射到 n 个桶上, 元素 x 放入第 `(x-min)*n/(max-min+1)` 个桶  
+/// 中间结果使用 u128 计算避免溢出, 数据分布均匀时每个桶内平均只有 1 个元素
+///
 /// ```
 /// #![feature(is_sorted)]
 /// use impx::sorting::bucket_sort;
 ///
-/// let mut a = [1, 10000, 88992, 92, 573, 8888];
+/// let mut a = [1, 10000, 88992, 92, 573, 8888, usize::MAX];
 /// bucket_sort(&mut a);
 /// assert!(a.is_sorted());
 /// ```
 pub fn bucket_sort(v: &mut [usize]) {
     let (min_val, max_val) = match (v.iter().min(), v.iter().max()) {
-        (Some(min), Some(max)) if min != max => (min, max),
+        (Some(&min), Some(&max)) if min != max => (min, max),
         _ => return,
     };
 
-    const BUCKET_SIZE: usize = 8;
-
-    let bucket_count = (max_val - min_val) / BUCKET_SIZE + 1;
-    let mut buckets = vec![vec![]; bucket_count];
-
-    for i in 0..v.len() {
-        buckets[(v[i] - min_val) / BUCKET_SIZE].push(v[i])
+    let n = v.len();
+    let range = (max_val - min_val) as u128 + 1;
+    let mut buckets = vec![vec![]; n];
+    for &val in v.iter() {
+        let index = (val - min_val) as u128 * n as u128 / range;
+        buckets[index as usize].push(val);
     }
 
     let mut index = 0;
-    for val in &mut buckets {
-        let bucket = val;
+    for bucket in &mut buckets {
         insertion_sort(bucket);
         for &mut val in bucket {
             v[index] = val;
@@ -238,6 +253,73 @@ pub fn bucket_sort(v: &mut [usize]) {
     }
 }
 
+/// 按照浮点数 key 进行桶排序
+///
+/// 稳定性: 是
+///
+/// 和 [`bucket_sort`] 一样使用 n 个桶, 先把 key 归一化到 `[0, 1]` 再映射到桶上  
+/// 为了不要求元素实现 Clone, 桶中只保存 `(key, 下标)`, 排序完成后按照得到的下标顺序原地重排元素  
+/// key 必须是有限值, NaN 和无穷大无法归一化
+///
+/// ```
+/// use impx::sorting::bucket_sort_by_key;
+///
+/// let mut a = [("b", 0.5), ("a", -1.5), ("c", 3.25), ("d", 0.5)];
+/// bucket_sort_by_key(&mut a, |x| x.1);
+/// assert_eq!(a, [("a", -1.5), ("b", 0.5), ("d", 0.5), ("c", 3.25)]);
+/// ```
+pub fn bucket_sort_by_key<T, F: Fn(&T) -> f64>(v: &mut [T], key: F) {
+    let n = v.len();
+    if n <= 1 {
+        return;
+    }
+
+    let keys: Vec<f64> = v.iter().map(&key).collect();
+    assert!(
+        keys.iter().all(|k| k.is_finite()),
+        "bucket sort keys must be finite"
+    );
+
+    let min = keys.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = keys.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if min == max {
+        return;
+    }
+
+    // max - min 超出 f64 的范围时先都除以 2 再相减
+    // 只在溢出时缩放, 次正规数除以 2 会丢失精度, 不同的 key 可能变成相同的值
+    let scale = if (max - min).is_finite() { 1.0 } else { 0.5 };
+    let (low, width) = (min * scale, max * scale - min * scale);
+
+    // key 相同时下标小的排在前面, 保证稳定
+    let mut buckets = vec![vec![]; n];
+    for (i, &k) in keys.iter().enumerate() {
+        let index = ((k * scale - low) / width * n as f64) as usize;
+        buckets[index.min(n - 1)].push((k, i));
+    }
+
+    let mut order = Vec::with_capacity(n);
+    for bucket in &mut buckets {
+        insertion_sort(bucket);
+        order.extend(bucket.iter().map(|&(_, i)| i));
+    }
+
+    // 排序后第 j 个位置应该放原来的第 order[j] 个元素
+    // 沿着置换的环依次交换, 处理过的位置标记为 order[j] = j
+    for i in 0..n {
+        let mut j = i;
+        loop {
+            let k = order[j];
+            order[j] = j;
+            if k == i {
+                break;
+            }
+            v.swap(j, k);
+            j = k;
+        }
+    }
+}
+
 /// 计数排序
 ///
 /// <https://oi-wiki.org/basic/counting-sort/>  
@@ -262,6 +344,10 @@ pub fn bucket_sort(v: &mut [usize]) {
 /// assert!(a.is_sorted());
 /// ```
 pub fn countint_sort(v: &mut [usize], up_bound: usize) {
+    if v.len() <= 1 {
+        return;
+    }
+
     let mut counter = vec![0; up_bound];
     for i in 0..v.len() {
         counter[v[i]] += 1;
@@ -302,6 +388,10 @@ pub fn countint_sort(v: &mut [usize], up_bound: usize) {
 /// assert!(a.is_sorted());
 /// ```
 pub fn radix_sort(v: &mut [usize]) {
+    if v.len() <= 1 {
+        return;
+    }
+
     let max_val = match v.iter().max() {
         Some(&val) => val,
         None => return,
@@ -330,7 +420,11 @@ pub fn radix_sort(v: &mut [usize]) {
             v[counter[(v_clone[i] / k) % SIZE]] = v_clone[i];
         }
 
-        k *= 10;
+        // max_val 接近 usize::MAX 时 k 乘 10 会溢出, 此时最高位已经处理完了
+        match k.checked_mul(10) {
+            Some(next) => k = next,
+            None => break,
+        }
     }
 }
 
@@ -379,12 +473,13 @@ pub fn merge_sort<T: PartialOrd + Copy>(v: &mut [T]) {
             continue;
         }
 
-        if a[i] < b[j] {
-            v[i + j] = a[i];
-            i += 1;
-        } else {
+        // 相等时取左边的元素, 保证稳定
+        if b[j] < a[i] {
             v[i + j] = b[j];
             j += 1;
+        } else {
+            v[i + j] = a[i];
+            i += 1;
         }
     }
 }
@@ -428,6 +523,10 @@ pub fn heap_sort<T: PartialOrd>(v: &mut [T]) {
         }
     }
 
+    if v.len() <= 1 {
+        return;
+    }
+
     // 从最后一个节点的父节点开始堆化
     for i in (0..=(v.len() - 1 - 1) / 2).rev() {
         down(v, i, v.len() - 1);
@@ -482,8 +581,139 @@ pub fn quick_sort<T: PartialOrd + Copy>(v: &mut [T]) {
     quick_sort(&mut v[pivot + 1..]);
 }
 
+/// Timsort
+///
+/// <https://en.wikipedia.org/wiki/Timsort>  
+/// <https://github.com/python/cpython/blob/main/Objects/listsort.txt>  
+/// 时间复杂度: 最优 O(n) 最坏 O(nlogn) 平均 O(nlogn)  
+/// 空间复杂度: 合并时需要 O(n) 的额外空间  
+/// 稳定性: 是
+///
+/// 现实中的数据往往包含很多已经有序的片段(run), Timsort 是归并排序和插入排序的结合, 充分利用这些片段:
+///
+/// - 从左往右找出自然有序的片段, 严格递减的片段直接翻转(严格递减才能保证翻转后依然稳定)
+/// - 片段长度小于 min_run 时用插入排序扩展到 min_run, min_run 在 32 到 64 之间,
+///   保证片段的数量恰好是或者略小于 2 的幂, 合并时更加平衡
+/// - 把片段依次压入栈中, 栈顶的三个片段长度 X Y Z 需要满足 `Z > Y + X` 和 `Y > X`,
+///   否则合并 Y 和 X/Z 中较短的一个, 这样栈的深度是 O(logn), 每次合并的两个片段长度也比较接近
+/// - 最后从栈顶开始合并所有剩余片段
+///
+/// 这里是简化版本, 省略了合并时的 galloping 模式
+///
+/// ```
+/// #![feature(is_sorted)]
+/// use impx::sorting::tim_sort;
+///
+/// let mut a: Vec<_> = (0..100).chain((0..100).rev()).chain(0..50).collect();
+/// tim_sort(&mut a);
+/// assert!(a.is_sorted());
+/// ```
+pub fn tim_sort<T: PartialOrd + Copy>(v: &mut [T]) {
+    let n = v.len();
+    if n <= 1 {
+        return;
+    }
+
+    let min_run = min_run_length(n);
+    let mut runs: Vec<(usize, usize)> = vec![]; // 每个片段的起点和长度
+    let mut start = 0;
+    while start < n {
+        let mut end = start + 1;
+        if end < n && v[end] < v[end - 1] {
+            while end < n && v[end] < v[end - 1] {
+                end += 1;
+            }
+            v[start..end].reverse();
+        } else {
+            while end < n && v[end] >= v[end - 1] {
+                end += 1;
+            }
+        }
+
+        // 前面 end - start 个元素已经有序, 插入排序只需要处理后面扩展的部分
+        let end = end.max((start + min_run).min(n));
+        insertion_sort(&mut v[start..end]);
+        runs.push((start, end - start));
+        start = end;
+
+        collapse_runs(v, &mut runs);
+    }
+
+    while runs.len() > 1 {
+        let i = runs.len() - 2;
+        merge_runs(v, &mut runs, i);
+    }
+}
+
+/// n 小于 64 时直接返回 n, 否则取 n 的最高 6 位, 如果剩下的位中有 1 则再加 1
+fn min_run_length(mut n: usize) -> usize {
+    let mut r = 0;
+    while n >= 64 {
+        r |= n & 1;
+        n >>= 1;
+    }
+    n + r
+}
+
+/// 合并栈顶的片段直到满足 `Z > Y + X` 和 `Y > X`
+fn collapse_runs<T: PartialOrd + Copy>(v: &mut [T], runs: &mut Vec<(usize, usize)>) {
+    while runs.len() > 1 {
+        let n = runs.len();
+        let (x, y) = (runs[n - 1].1, runs[n - 2].1);
+        if (n >= 3 && runs[n - 3].1 <= y + x) || (n >= 4 && runs[n - 4].1 <= runs[n - 3].1 + y) {
+            if runs[n - 3].1 < x {
+                merge_runs(v, runs, n - 3);
+            } else {
+                merge_runs(v, runs, n - 2);
+            }
+        } else if y <= x {
+            merge_runs(v, runs, n - 2);
+        } else {
+            break;
+        }
+    }
+}
+
+/// 合并第 i 和 i+1 个片段
+fn merge_runs<T: PartialOrd + Copy>(v: &mut [T], runs: &mut Vec<(usize, usize)>, i: usize) {
+    let (start, len1) = runs[i];
+    let (_, len2) = runs.remove(i + 1);
+    runs[i].1 = len1 + len2;
+
+    // 只需要把左边的片段复制出来, 合并结果从左往右写入时不会覆盖还没有读取的右边元素
+    let v = &mut v[start..start + len1 + len2];
+    let left = v[..len1].to_vec();
+    let (mut i, mut j, mut k) = (0, len1, 0);
+    while i < left.len() && j < v.len() {
+        // 相等时取左边的元素, 保证稳定
+        if v[j] < left[i] {
+            v[k] = v[j];
+            j += 1;
+        } else {
+            v[k] = left[i];
+            i += 1;
+        }
+        k += 1;
+    }
+
+    // 右边剩下的元素已经在正确的位置上
+    v[k..k + left.len() - i].copy_from_slice(&left[i..]);
+}
+
 /// 双调排序
+///
+/// <https://zh.wikipedia.org/wiki/双调排序>  
+/// 时间复杂度: O(nlog^2n)  
+/// 稳定性: 否 相隔很远的元素之间直接交换
+///
+/// 先把序列的前后两半分别排成升序和降序得到一个双调序列, 再不断对半比较交换合并成有序序列  
+/// 每一轮的比较交换互不依赖, 适合并行执行, 要求序列长度是 2 的幂
 pub fn bitonic_sort<T: PartialOrd>(v: &mut [T], up: bool) {
+    assert!(
+        v.is_empty() || v.len().is_power_of_two(),
+        "bitonic sort requires a power-of-two length"
+    );
+
     if v.len() > 1 {
         let mid = v.len() >> 1;
         bitonic_sort(&mut v[..mid], true);
@@ -531,6 +761,191 @@ mod tests {
         }
     }
 
+    /// 只按 key 比较的元素, index 记录原来的位置
+    #[derive(Debug, Clone, Copy)]
+    struct Item {
+        key: usize,
+        index: usize,
+    }
+
+    impl PartialEq for Item {
+        fn eq(&self, other: &Self) -> bool {
+            self.key == other.key
+        }
+    }
+
+    impl PartialOrd for Item {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            self.key.partial_cmp(&other.key)
+        }
+    }
+
+    /// key 的取值很少, 排序后相同 key 的元素必须保持原来的顺序
+    fn do_stability_test<F>(sorter: F)
+    where
+        F: Fn(&mut [Item]),
+    {
+        let mut rng = rand::thread_rng();
+        for _ in 0..32 {
+            let n = rng.gen_range(0..512);
+            let mut v: Vec<Item> = (0..n)
+                .map(|index| Item {
+                    key: rng.gen_range(0..8),
+                    index,
+                })
+                .collect();
+            sorter(&mut v);
+            for w in v.windows(2) {
+                assert!(
+                    w[0].key < w[1].key || (w[0].key == w[1].key && w[0].index < w[1].index),
+                    "{:?} {:?}",
+                    w[0],
+                    w[1]
+                );
+            }
+        }
+    }
+
+    /// 空序列和只有一两个元素的序列
+    fn do_tiny_test<F>(sorter: F)
+    where
+        F: Fn(&mut [usize]),
+    {
+        for v in [vec![], vec![1], vec![1, 2], vec![2, 1], vec![1, 1]] {
+            let mut expect = v.clone();
+            expect.sort();
+            let mut v = v;
+            sorter(&mut v);
+            assert_eq!(v, expect);
+        }
+    }
+
+    #[test]
+    fn test_stability() {
+        use super::{bubble_sort, cocktail_sort, insertion_sort, merge_sort, tim_sort};
+        do_stability_test(bubble_sort);
+        do_stability_test(cocktail_sort);
+        do_stability_test(insertion_sort);
+        do_stability_test(merge_sort);
+        do_stability_test(tim_sort);
+    }
+
+    #[test]
+    fn test_tiny_slice() {
+        use super::*;
+        do_tiny_test(bubble_sort);
+        do_tiny_test(cocktail_sort);
+        do_tiny_test(selection_sort);
+        do_tiny_test(insertion_sort);
+        do_tiny_test(heap_sort);
+        do_tiny_test(merge_sort);
+        do_tiny_test(tim_sort);
+    }
+
+    /// 空序列, 单个元素, 全部相等以及各种容易触发边界情况的输入
+    fn fuzz_inputs() -> Vec<Vec<usize>> {
+        let mut rng = rand::thread_rng();
+        let mut inputs = vec![vec![], vec![0], vec![usize::MAX]];
+        for n in [2, 3, 16, 64, 65, 1000, 1024] {
+            inputs.push(vec![7; n]);
+            inputs.push((0..n).collect());
+            inputs.push((0..n).rev().collect());
+            // 先升后降的管风琴序列
+            inputs.push((0..n).map(|i| i.min(n - i)).collect());
+            // 锯齿序列
+            inputs.push((0..n).map(|i| i % 5).collect());
+            // 只有头尾两个元素不在正确的位置
+            inputs.push((0..n).map(|i| (i + 1) % n).collect());
+            inputs.push((0..n).map(|_| rng.gen_range(0..2)).collect());
+            inputs.push((0..n).map(|_| rng.gen()).collect());
+            inputs.push((0..n).map(|i| [0, usize::MAX][i % 2]).collect());
+        }
+        inputs
+    }
+
+    /// 对所有导出的排序函数运行 [`fuzz_inputs`], 结果必须和标准库一致
+    #[test]
+    fn test_fuzz_all_sorters() {
+        use super::*;
+
+        type SortFn = fn(&mut [usize]);
+        let sorters: [(&str, SortFn); 15] = [
+            ("bubble_sort", bubble_sort),
+            ("cocktail_sort", cocktail_sort),
+            ("selection_sort", selection_sort),
+            ("insertion_sort", insertion_sort),
+            ("shell_sort", shell_sort),
+            ("bucket_sort", bucket_sort),
+            ("bucket_sort_by_key", |v| {
+                bucket_sort_by_key(v, |&x| x as f64)
+            }),
+            ("countint_sort", |v| {
+                let up_bound = v.iter().max().map_or(0, |&x| x + 1);
+                countint_sort(v, up_bound)
+            }),
+            ("radix_sort", radix_sort),
+            ("merge_sort", merge_sort),
+            ("heap_sort", heap_sort),
+            ("quick_sort", quick_sort),
+            ("tim_sort", tim_sort),
+            ("bitonic_sort", |v| bitonic_sort(v, true)),
+            ("bitonic_sort(down)", |v| {
+                bitonic_sort(v, false);
+                v.reverse()
+            }),
+        ];
+
+        for input in fuzz_inputs() {
+            let mut expect = input.clone();
+            expect.sort();
+            for (name, sorter) in sorters {
+                // 计数排序需要 O(max) 的空间, 双调排序要求长度是 2 的幂
+                if (name == "countint_sort" && expect.last() > Some(&(1 << 20)))
+                    || (name.starts_with("bitonic") && !input.len().is_power_of_two())
+                {
+                    continue;
+                }
+
+                let mut v = input.clone();
+                sorter(&mut v);
+                assert_eq!(v, expect, "{name} {input:?}");
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "power-of-two")]
+    fn test_bitonic_sort_len() {
+        use super::bitonic_sort;
+        bitonic_sort(&mut [3, 2, 1], true);
+    }
+
+    #[test]
+    fn test_tim_sort() {
+        use super::tim_sort;
+        do_rand_test(tim_sort);
+
+        // 多个有序和逆序片段拼接, 长度超过 min_run 时才会真正合并
+        let mut rng = rand::thread_rng();
+        for _ in 0..32 {
+            let mut v = vec![];
+            for _ in 0..rng.gen_range(1..16) {
+                let mut run: Vec<usize> = (0..rng.gen_range(1..300))
+                    .map(|_| rng.gen_range(0..1000))
+                    .collect();
+                run.sort();
+                if rng.gen() {
+                    run.reverse();
+                }
+                v.extend(run);
+            }
+            let mut expect = v.clone();
+            expect.sort();
+            tim_sort(&mut v);
+            assert_eq!(v, expect);
+        }
+    }
+
     #[test]
     fn test_bubble_sort() {
         use super::bubble_sort;
@@ -616,4 +1031,61 @@ mod tests {
             assert!(v.is_sorted());
         }
     }
+
+    #[test]
+    fn test_bucket_sort_wide_range() {
+        use super::bucket_sort;
+
+        // 按值域分桶时这里需要分配 usize::MAX / 8 个桶
+        let mut v = vec![usize::MAX, 0, usize::MAX / 2, 7, usize::MAX - 1, 7, 1 << 40];
+        bucket_sort(&mut v);
+        assert_eq!(
+            v,
+            [0, 7, 7, 1 << 40, usize::MAX / 2, usize::MAX - 1, usize::MAX]
+        );
+
+        let mut rng = rand::thread_rng();
+        let mut v: Vec<usize> = (0..1000).map(|_| rng.gen()).collect();
+        bucket_sort(&mut v);
+        assert!(v.is_sorted());
+    }
+
+    #[test]
+    fn test_bucket_sort_by_key() {
+        use super::bucket_sort_by_key;
+
+        let mut rng = rand::thread_rng();
+        for n in [0, 1, 2, 10, 1000] {
+            // 只有少量不同的 key, 检查相同 key 的元素保持原来的顺序
+            let mut v: Vec<(f64, usize)> = (0..n)
+                .map(|i| (rng.gen_range(-5..5) as f64 * 0.75, i))
+                .collect();
+            let mut expect = v.clone();
+            expect.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            bucket_sort_by_key(&mut v, |x| x.0);
+            assert_eq!(v, expect);
+        }
+
+        // 极端的值域
+        let mut v = [f64::MAX, -f64::MAX, 0.0, 1e-300, -1.0];
+        bucket_sort_by_key(&mut v, |&x| x);
+        assert_eq!(v, [-f64::MAX, -1.0, 0.0, 1e-300, f64::MAX]);
+
+        // 最小的次正规数除以 2 之后和 0 相同, 不能因此认为所有 key 都相等
+        let tiny = f64::from_bits(1);
+        let mut v = [tiny, 0.0];
+        bucket_sort_by_key(&mut v, |&x| x);
+        assert_eq!(v, [0.0, tiny]);
+        let mut v = [tiny, -tiny, 0.0, tiny * 3.0];
+        bucket_sort_by_key(&mut v, |&x| x);
+        assert_eq!(v, [-tiny, 0.0, tiny, tiny * 3.0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "must be finite")]
+    fn test_bucket_sort_by_key_nan() {
+        use super::bucket_sort_by_key;
+        bucket_sort_by_key(&mut [1.0, f64::NAN], |&x| x);
+    }
 }
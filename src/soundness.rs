@@ -0,0 +1,423 @@
+//! unsafe 模块的内存安全测试
+//!
+//! - [Miri](https://github.com/rust-lang/miri)
+//!
+//! 对链表, 红黑树, Treap, B 树, B+ 树, 跳表, 笛卡尔树, 动态数组和小数组执行小规模的随机操作,
+//! 元素使用会统计存活数量的 [`Tracked`] 类型:
+//!
+//! - 每一步之后存活的元素数量必须等于容器的长度, 删除时元素被移出或者析构恰好一次
+//! - 容器析构之后存活数量必须归零, 否则说明有节点泄漏
+//!
+//! 同时穿插一些别名相关的访问模式, 比如持有多个共享引用的同时遍历, 通过可变迭代器修改全部元素等
+//!
+//! 普通的 `cargo test` 只能发现泄漏, 悬垂指针和别名违规需要在 Miri 下运行才能检测出来:
+//!
+//! ```text
+//! cargo +nightly miri test --features soundness --lib soundness
+//! ```
+
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::b_plus_tree::BPlusTree;
+use crate::b_tree::BTree;
+use crate::cartesian_tree::build_cartesian_tree;
+use crate::linked_list::LinkedList;
+use crate::my_vec::MyVec;
+use crate::rb_tree::RBTree;
+use crate::skip_list::SkipList;
+use crate::small_vec::SmallVec;
+use crate::treap::Treap;
+
+/// Miri 比正常执行慢几个数量级, 操作数量保持很小
+const OPS: usize = if cfg!(miri) { 200 } else { 2000 };
+const KEYS: u16 = 32;
+
+/// 统计存活数量的元素, 只按 key 比较
+#[derive(Debug)]
+struct Tracked {
+    key: u16,
+    live: Rc<Cell<usize>>,
+}
+
+impl Tracked {
+    fn new(key: u16, live: &Rc<Cell<usize>>) -> Self {
+        live.set(live.get() + 1);
+        Self {
+            key,
+            live: live.clone(),
+        }
+    }
+}
+
+impl Clone for Tracked {
+    fn clone(&self) -> Self {
+        Self::new(self.key, &self.live)
+    }
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        let live = self.live.get();
+        assert!(live > 0, "element {} dropped twice", self.key);
+        self.live.set(live - 1);
+    }
+}
+
+impl PartialEq for Tracked {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for Tracked {}
+
+impl PartialOrd for Tracked {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Tracked {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+fn rng() -> StdRng {
+    StdRng::seed_from_u64(0x5eed)
+}
+
+#[test]
+fn soundness_linked_list() {
+    let live = Rc::new(Cell::new(0));
+    let mut rng = rng();
+    {
+        let mut list = LinkedList::new();
+        let mut handles = vec![];
+        for _ in 0..OPS {
+            let key = rng.gen_range(0..KEYS);
+            match rng.gen_range(0..8) {
+                0 => list.push_front(Tracked::new(key, &live)),
+                1 => list.push_back(Tracked::new(key, &live)),
+                // pop 可能弹出句柄对应的节点, 句柄随之失效
+                2 => {
+                    handles.clear();
+                    drop(list.pop_front());
+                }
+                3 => {
+                    handles.clear();
+                    drop(list.pop_back());
+                }
+                4 => handles.push(list.push_back_handle(Tracked::new(key, &live))),
+                5 if !handles.is_empty() => {
+                    // 句柄只在节点还在链表中时使用
+                    let handle = handles.swap_remove(rng.gen_range(0..handles.len()));
+                    unsafe {
+                        if rng.gen() {
+                            list.move_to_front(handle);
+                        } else {
+                            drop(list.remove(handle));
+                        }
+                    }
+                }
+                6 => {
+                    // 拆分再拼接回去
+                    let mut cursor = list.cursor_mut();
+                    for _ in 0..key % 4 {
+                        cursor.move_next();
+                    }
+                    let tail = cursor.split_after();
+                    cursor.splice_after(tail);
+                }
+                _ => {
+                    // 同时持有首尾的共享引用并遍历
+                    let (front, back) = (list.front(), list.back());
+                    let count = list.iter().count();
+                    assert_eq!(front.is_some(), count > 0);
+                    assert_eq!(back.is_some(), count > 0);
+                    for x in list.iter_mut() {
+                        x.key = (x.key + 1) % KEYS;
+                    }
+                }
+            }
+
+            assert_eq!(live.get(), list.len());
+        }
+
+        // 消费一半后丢弃剩余的迭代器
+        let half = list.len() / 2;
+        let mut iter = list.into_iter();
+        for _ in 0..half {
+            iter.next();
+        }
+    }
+    assert_eq!(live.get(), 0);
+}
+
+#[test]
+fn soundness_rb_tree() {
+    let live = Rc::new(Cell::new(0));
+    let mut rng = rng();
+    {
+        let mut tree = RBTree::new();
+        for _ in 0..OPS {
+            let key = rng.gen_range(0..KEYS);
+            if rng.gen_bool(0.6) {
+                drop(tree.insert(Tracked::new(key, &live)));
+            } else {
+                drop(tree.delete(&Tracked::new(key, &live)));
+            }
+
+            tree.validate();
+            assert_eq!(live.get(), tree.len());
+        }
+
+        tree.clear();
+        assert_eq!(live.get(), 0);
+        for key in 0..KEYS {
+            tree.insert(Tracked::new(key, &live));
+        }
+    }
+    assert_eq!(live.get(), 0);
+}
+
+#[test]
+fn soundness_treap() {
+    let live = Rc::new(Cell::new(0));
+    let mut rng = rng();
+    {
+        let mut treap = Treap::new();
+        for _ in 0..OPS {
+            let key = rng.gen_range(0..KEYS);
+            if rng.gen_bool(0.6) {
+                let value = Tracked::new(key, &live);
+                if !treap.contains(&value) {
+                    treap.insert(value);
+                }
+            } else {
+                drop(treap.delete(&Tracked::new(key, &live)));
+            }
+
+            assert!(treap.iter().zip(treap.iter().skip(1)).all(|(a, b)| a < b));
+            assert_eq!(live.get(), treap.len());
+        }
+
+        treap.clear();
+        assert_eq!(live.get(), 0);
+        for key in 0..KEYS {
+            treap.insert(Tracked::new(key, &live));
+        }
+
+        // 消费时释放节点
+        let values = treap.into_sorted_vec();
+        assert_eq!(live.get(), values.len());
+        assert!(values.iter().map(|v| v.key).eq(0..KEYS));
+
+        let mut treap = Treap::new();
+        for key in 0..KEYS {
+            treap.insert(Tracked::new(key, &live));
+        }
+    }
+    assert_eq!(live.get(), 0);
+}
+
+#[test]
+fn soundness_b_tree() {
+    let live = Rc::new(Cell::new(0));
+    let mut rng = rng();
+    for order in 3..6 {
+        let mut tree = BTree::new(order);
+        for _ in 0..OPS {
+            let key = rng.gen_range(0..KEYS);
+            if rng.gen_bool(0.6) {
+                let entry = (Tracked::new(key, &live), Tracked::new(key, &live));
+                drop(tree.insert(entry));
+            } else {
+                drop(tree.delete(&Tracked::new(key, &live)));
+            }
+
+            // 持有 min/max 的引用的同时遍历
+            let (min, max) = (tree.min(), tree.max());
+            let mut iter = tree.iter();
+            assert_eq!(iter.next().map(|(k, _)| k), min.map(|e| &e.0));
+            assert_eq!(
+                iter.last().map(|(k, _)| k).or(min.map(|e| &e.0)),
+                max.map(|e| &e.0)
+            );
+            assert_eq!(live.get(), tree.len() * 2);
+        }
+    }
+    assert_eq!(live.get(), 0);
+}
+
+/// B+ 树的 key 要求实现 Copy, 只统计 value
+#[test]
+fn soundness_b_plus_tree() {
+    let live = Rc::new(Cell::new(0));
+    let mut rng = rng();
+    for order in 3..6 {
+        let entries = (0..KEYS).map(|k| (k, Tracked::new(k, &live)));
+        let mut tree = BPlusTree::from_sorted_iter(entries, order);
+        for _ in 0..OPS {
+            let key = rng.gen_range(0..KEYS);
+            if rng.gen_bool(0.5) {
+                drop(tree.insert((key, Tracked::new(key, &live))));
+            } else {
+                drop(tree.delete(&key));
+            }
+
+            // 持有查找结果的同时沿叶子链表遍历
+            let found = tree.find(&key);
+            assert!(tree.iter().all(|(k, v)| *k == v.key));
+            assert!(found.is_none_or(|(_, v)| v.key == key));
+            assert_eq!(live.get(), tree.len());
+        }
+    }
+    assert_eq!(live.get(), 0);
+}
+
+#[test]
+fn soundness_skip_list() {
+    let live = Rc::new(Cell::new(0));
+    let mut rng = rng();
+    // 分别关闭和开启节点池, 池中节点的内容已经移出, 复用和释放时都不能再次析构
+    for pool in [0, 8] {
+        let mut list = SkipList::<4, _, _>::with_pool_capacity(pool);
+        for _ in 0..OPS {
+            let key = rng.gen_range(0..KEYS);
+            match rng.gen_range(0..4) {
+                0 => drop(list.insert(key, Tracked::new(key, &live))),
+                1 => drop(list.delete(&key)),
+                2 => {
+                    let value = list.get_or_insert_with(key, || Tracked::new(key, &live));
+                    assert_eq!(value.key, key);
+                }
+                _ => {
+                    let (first, last) = (list.first(), list.last());
+                    assert_eq!(first.is_some(), last.is_some());
+                    for (_, v) in list.iter_mut() {
+                        v.key += KEYS;
+                        v.key -= KEYS;
+                    }
+                }
+            }
+
+            list.check();
+            assert_eq!(live.get(), list.len());
+        }
+
+        list.clear();
+        assert_eq!(live.get(), 0);
+        for key in 0..KEYS {
+            list.insert(key, Tracked::new(key, &live));
+        }
+        drop(list);
+        assert_eq!(live.get(), 0);
+    }
+}
+
+/// 笛卡尔树要求元素实现 Copy, 这里只依靠 Miri 检查构建过程中的指针操作和泄漏
+#[test]
+fn soundness_cartesian_tree() {
+    let mut rng = rng();
+    for n in [0, 1, 2, 16, 64] {
+        let mut xs: Vec<(u16, u16)> = (0..n).map(|k| (k, rng.gen())).collect();
+        let tree = build_cartesian_tree(&mut xs);
+        assert_eq!(tree.root.is_some(), n > 0);
+    }
+}
+
+#[test]
+fn soundness_my_vec() {
+    let live = Rc::new(Cell::new(0));
+    let mut rng = rng();
+    {
+        let mut v = MyVec::new();
+        for _ in 0..OPS {
+            let key = rng.gen_range(0..KEYS);
+            match rng.gen_range(0..6) {
+                0 | 1 => v.push(Tracked::new(key, &live)),
+                2 => drop(v.pop()),
+                3 => {
+                    let index = rng.gen_range(0..=v.len());
+                    v.insert(index, Tracked::new(key, &live));
+                }
+                4 if !v.is_empty() => drop(v.remove(rng.gen_range(0..v.len()))),
+                _ => {
+                    // 同时持有首尾的共享引用, 再通过切片修改全部元素
+                    let (first, last) = (v.first(), v.last());
+                    assert_eq!(first.is_some(), last.is_some());
+                    v.sort();
+                    for x in v.iter_mut() {
+                        x.key = (x.key + 1) % KEYS;
+                    }
+                }
+            }
+
+            assert_eq!(live.get(), v.len());
+        }
+
+        let cloned = v.clone();
+        assert_eq!(live.get(), 2 * v.len());
+        drop(cloned);
+
+        // 从两端消费一部分后丢弃剩余的迭代器
+        let mut iter = v.into_iter();
+        drop(iter.next());
+        drop(iter.next_back());
+        assert_eq!(live.get(), iter.len());
+
+        let mut zst = MyVec::new();
+        for _ in 0..OPS {
+            zst.push(());
+        }
+        zst.insert(OPS / 2, ());
+        zst.remove(0);
+        assert_eq!(zst.into_iter().count(), OPS);
+    }
+    assert_eq!(live.get(), 0);
+}
+
+#[test]
+fn soundness_small_vec() {
+    let live = Rc::new(Cell::new(0));
+    let mut rng = rng();
+    // 长度在内联容量附近来回变化, 覆盖搬到堆上前后的各种操作
+    for _ in 0..OPS / 20 {
+        let mut v = SmallVec::<_, 4>::new();
+        for _ in 0..20 {
+            let key = rng.gen_range(0..KEYS);
+            match rng.gen_range(0..5) {
+                0 | 1 => v.push(Tracked::new(key, &live)),
+                2 => drop(v.pop()),
+                3 => {
+                    let index = rng.gen_range(0..=v.len());
+                    v.insert(index, Tracked::new(key, &live));
+                }
+                _ if !v.is_empty() => drop(v.remove(rng.gen_range(0..v.len()))),
+                _ => {}
+            }
+
+            assert_eq!(live.get(), v.len());
+        }
+
+        let cloned = v.clone();
+        assert_eq!(live.get(), 2 * v.len());
+        drop(cloned);
+
+        if rng.gen() {
+            let mut iter = v.into_iter();
+            drop(iter.next());
+            drop(iter.next_back());
+            assert_eq!(live.get(), iter.len());
+        } else {
+            drop(v);
+        }
+        assert_eq!(live.get(), 0);
+    }
+}
@@ -41,53 +41,373 @@
 //! 所以直接找到一个最大的 `n = log(r-l+1)` 得到 `s1 = l+2^n-1`  
 //! 同时这个步长也能被 s2 用到即 `r = s2 + 2^n-1` 那 s2 也可以算出来 `s2 = r - 2^n -1`  
 //! 通过步长最终可以得到结果 `max[l,r] = max(f[l][n], f[s2][n])`
+//!
+//! 只要运算满足结合律和幂等性(`op(x, x) = x`), 两个重叠的区间就不会影响结果,
+//! 所以除了最大值之外, 最小值、最大公约数、按位与/或等运算都可以用稀疏表 O(1) 查询
+//! [`SparseTable`] 对运算是泛型的, [`ArgSparseTable`] 返回最值所在的下标(比如欧拉序求 LCA 时需要)
+//!
+//! 不满足幂等性的运算(比如求和、乘积)不能使用重叠的区间, 可以使用猫树(Disjoint Sparse Table):
+//! 对第 h 层, 把数组按 2^(h+1) 分块, 每一块从中点向左预处理后缀结果、向右预处理前缀结果
+//! 查询 `[l, r]` 时 `l ^ r` 的最高位 h 说明 l 和 r 在第 h 层同一块中点的两侧, 结果就是两边预处理的值合并, 同样是 O(1)
+//!
+//! 相邻元素恰好相差 1 的数组(比如欧拉序的深度)上的区间最小值(±1 RMQ)可以做到 O(n) 预处理:
+//! 按 b = logn / 2 分块, 块间用稀疏表, 块内只有 2^(b-1) 种不同的差分形态, 可以预处理所有形态的块内答案
+//!
+//! 如果查询的区间是一个固定长度的滑动窗口, 用单调队列就可以 O(n) 求出所有窗口的最值
+
+/// 稀疏表, `table[j][i]` 保存区间 `[i, i + 2^j - 1]` 的运算结果
+pub struct SparseTable<T, F> {
+    table: Vec<Vec<T>>,
+    op: F,
+}
+
+impl<T: Clone, F: Fn(&T, &T) -> T> SparseTable<T, F> {
+    /// 使用满足结合律和幂等性的运算 op 初始化稀疏表
+    pub fn new(v: &[T], op: F) -> Self {
+        let mut table = vec![v.to_vec()];
+        let mut step = 1;
+        while step * 2 <= v.len() {
+            // 把步长切半, 合并两个小范围的结果
+            let prev = table.last().unwrap();
+            let next = (0..=v.len() - step * 2)
+                .map(|i| op(&prev[i], &prev[i + step]))
+                .collect();
+            table.push(next);
+            step *= 2;
+        }
+
+        Self { table, op }
+    }
+
+    pub fn len(&self) -> usize {
+        self.table[0].len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 查询区间 `[l, r]` 的运算结果, 区间不合法时返回 None
+    pub fn query(&self, l: usize, r: usize) -> Option<T> {
+        if l > r || r >= self.len() {
+            return None;
+        }
+
+        let n = (r - l + 1).ilog2() as usize;
+        let f = &self.table[n];
+        Some((self.op)(&f[l], &f[r + 1 - (1 << n)]))
+    }
+}
+
+/// 区间最大值稀疏表
+pub fn max_table<T: Ord + Clone>(v: &[T]) -> SparseTable<T, fn(&T, &T) -> T> {
+    SparseTable::new(v, |a, b| a.max(b).clone())
+}
+
+/// 区间最小值稀疏表
+pub fn min_table<T: Ord + Clone>(v: &[T]) -> SparseTable<T, fn(&T, &T) -> T> {
+    SparseTable::new(v, |a, b| a.min(b).clone())
+}
+
+/// 区间最大公约数稀疏表
+pub fn gcd_table(v: &[u64]) -> SparseTable<u64, fn(&u64, &u64) -> u64> {
+    SparseTable::new(v, |&a, &b| crate::number_theory::gcd(a, b))
+}
+
+/// 返回区间最值下标的稀疏表, 有多个最值时返回最靠左的下标
+pub struct ArgSparseTable<T> {
+    values: Vec<T>,
+    table: Vec<Vec<usize>>,
+    max: bool,
+}
+
+impl<T: Ord + Clone> ArgSparseTable<T> {
+    /// 查询区间最小值下标
+    pub fn argmin(v: &[T]) -> Self {
+        Self::new(v, false)
+    }
+
+    /// 查询区间最大值下标
+    pub fn argmax(v: &[T]) -> Self {
+        Self::new(v, true)
+    }
+
+    fn new(v: &[T], max: bool) -> Self {
+        let mut st = Self {
+            values: v.to_vec(),
+            table: vec![(0..v.len()).collect()],
+            max,
+        };
+
+        let mut step = 1;
+        while step * 2 <= v.len() {
+            let prev = st.table.last().unwrap();
+            let next = (0..=v.len() - step * 2)
+                .map(|i| st.pick(prev[i], prev[i + step]))
+                .collect();
+            st.table.push(next);
+            step *= 2;
+        }
 
-/// 初始化稀疏表
-pub fn init(v: &[isize]) -> Vec<Vec<isize>> {
-    let n = v.len();
-    // 先计算最大步长
-    let max_step = (n as f32).log2().ceil() as usize;
-    let mut f = vec![vec![0; max_step + 1]; n];
-
-    // 对于 0 步长最大值都是自己
-    for i in 0..n {
-        f[i][0] = v[i];
-    }
-
-    // 外层步长从 1 开始遍历到多一倍步长
-    for step in 1..=max_step {
-        // 内层起点从 0 开始
-        for start in 0..n {
-            // 如果范围在步长以内
-            // 把步长切半, 比较两个小范围的最大值
-            if start + (1 << step) - 1 < n {
-                f[start][step] = f[start][step - 1].max(f[start + (1 << (step - 1))][step - 1]);
+        st
+    }
+
+    /// 从两个下标中选出值更优的一个, 相等时选择更小的下标
+    fn pick(&self, a: usize, b: usize) -> usize {
+        let (va, vb) = (&self.values[a], &self.values[b]);
+        let better = if self.max { vb > va } else { vb < va };
+        if better || (vb == va && b < a) {
+            b
+        } else {
+            a
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// 查询区间 `[l, r]` 最值的下标, 区间不合法时返回 None
+    pub fn query(&self, l: usize, r: usize) -> Option<usize> {
+        if l > r || r >= self.len() {
+            return None;
+        }
+
+        let n = (r - l + 1).ilog2() as usize;
+        let f = &self.table[n];
+        Some(self.pick(f[l], f[r + 1 - (1 << n)]))
+    }
+}
+
+/// 猫树, 支持任意满足结合律的运算
+pub struct DisjointSparseTable<T, F> {
+    values: Vec<T>,
+    table: Vec<Vec<T>>,
+    op: F,
+}
+
+impl<T: Clone, F: Fn(&T, &T) -> T> DisjointSparseTable<T, F> {
+    pub fn new(v: &[T], op: F) -> Self {
+        let n = v.len();
+        let levels = if n <= 1 {
+            0
+        } else {
+            (n - 1).ilog2() as usize + 1
+        };
+        let mut table = vec![v.to_vec(); levels];
+
+        for (h, row) in table.iter_mut().enumerate() {
+            let len = 1 << h;
+            for mid in (len..n).step_by(len * 2) {
+                // 从中点向左计算后缀结果
+                for i in (mid - len..mid - 1).rev() {
+                    row[i] = op(&v[i], &row[i + 1]);
+                }
+                // 从中点向右计算前缀结果
+                for i in mid + 1..(mid + len).min(n) {
+                    row[i] = op(&row[i - 1], &v[i]);
+                }
             }
         }
+
+        Self {
+            values: v.to_vec(),
+            table,
+            op,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
     }
 
-    f
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// 查询区间 `[l, r]` 的运算结果, 区间不合法时返回 None
+    pub fn query(&self, l: usize, r: usize) -> Option<T> {
+        if l > r || r >= self.len() {
+            return None;
+        }
+        if l == r {
+            return Some(self.values[l].clone());
+        }
+
+        let h = (l ^ r).ilog2() as usize;
+        Some((self.op)(&self.table[h][l], &self.table[h][r]))
+    }
 }
 
-/// 计算稀疏表 f 所表示范围区间 `[l, r]` 内的最大值
-pub fn max(f: &[Vec<isize>], l: usize, r: usize) -> isize {
-    let n = ((r - l + 1) as f32).log2().floor() as usize;
-    f[l][n].max(f[r + 1 - (1 << n)][n])
+/// 相邻元素相差 1 的数组上的区间最小值下标查询
+pub struct PlusMinusOneRmq {
+    values: Vec<i64>,
+    block: usize,
+    /// 每一块的差分形态
+    masks: Vec<usize>,
+    /// 每一块最小值的下标
+    block_min: Vec<usize>,
+    /// 块间最小值
+    sparse: ArgSparseTable<i64>,
+    /// `inner[mask][i * block + j]` 是这种形态的块内 `[i, j]` 最小值的偏移
+    inner: Vec<Vec<usize>>,
+}
+
+impl PlusMinusOneRmq {
+    pub fn new(v: &[i64]) -> Self {
+        assert!(
+            v.windows(2).all(|w| (w[0] - w[1]).abs() == 1),
+            "adjacent values must differ by exactly 1"
+        );
+
+        let n = v.len();
+        let block = ((n.max(2).ilog2() / 2) as usize).max(1);
+
+        let mut masks = vec![];
+        let mut block_min = vec![];
+        for (b, chunk) in v.chunks(block).enumerate() {
+            let mask = chunk
+                .windows(2)
+                .enumerate()
+                .filter(|(_, w)| w[1] > w[0])
+                .fold(0, |m, (i, _)| m | 1 << i);
+            masks.push(mask);
+
+            let offset = (0..chunk.len()).min_by_key(|&i| (chunk[i], i)).unwrap();
+            block_min.push(b * block + offset);
+        }
+
+        // 预处理所有差分形态的块内答案
+        let inner = (0..1usize << (block - 1))
+            .map(|mask| {
+                let mut vals = vec![0i64; block];
+                for i in 1..block {
+                    vals[i] = vals[i - 1] + if mask >> (i - 1) & 1 == 1 { 1 } else { -1 };
+                }
+
+                let mut res = vec![0; block * block];
+                for i in 0..block {
+                    let mut best = i;
+                    for j in i..block {
+                        if vals[j] < vals[best] {
+                            best = j;
+                        }
+                        res[i * block + j] = best;
+                    }
+                }
+                res
+            })
+            .collect();
+
+        let mins: Vec<i64> = block_min.iter().map(|&i| v[i]).collect();
+        Self {
+            values: v.to_vec(),
+            block,
+            masks,
+            block_min,
+            sparse: ArgSparseTable::argmin(&mins),
+            inner,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// 第 b 块内 `[i, j]` 最小值的下标
+    fn in_block(&self, b: usize, i: usize, j: usize) -> usize {
+        b * self.block + self.inner[self.masks[b]][i * self.block + j]
+    }
+
+    /// 两个下标中值更小的一个, 相等时选择更小的下标
+    fn pick(&self, a: usize, b: usize) -> usize {
+        if (self.values[b], b) < (self.values[a], a) {
+            b
+        } else {
+            a
+        }
+    }
+
+    /// 查询区间 `[l, r]` 最小值的下标, 有多个最小值时返回最靠左的下标
+    pub fn query(&self, l: usize, r: usize) -> Option<usize> {
+        if l > r || r >= self.len() {
+            return None;
+        }
+
+        let (bl, br) = (l / self.block, r / self.block);
+        if bl == br {
+            return Some(self.in_block(bl, l % self.block, r % self.block));
+        }
+
+        let mut res = self.in_block(bl, l % self.block, self.block - 1);
+        if bl + 1 < br {
+            let b = self.sparse.query(bl + 1, br - 1).unwrap();
+            res = self.pick(res, self.block_min[b]);
+        }
+        Some(self.pick(res, self.in_block(br, 0, r % self.block)))
+    }
+}
+
+/// 单调队列求所有长度为 k 的滑动窗口的最值, better(a, b) 表示 a 比 b 更优
+fn sliding_window<T: Clone>(v: &[T], k: usize, better: impl Fn(&T, &T) -> bool) -> Vec<T> {
+    use crate::ring_buffer::ArrayDeque;
+
+    assert!(k > 0, "window size must be positive");
+    // 队列中保存下标, 对应的值单调, 队头就是当前窗口的最值
+    let mut deque = ArrayDeque::new();
+    let mut res = vec![];
+    for i in 0..v.len() {
+        while deque.back().is_some_and(|&j| !better(&v[j], &v[i])) {
+            deque.pop_back();
+        }
+        deque.push_back(i);
+
+        if deque.front().is_some_and(|&j| j + k <= i) {
+            deque.pop_front();
+        }
+        if i + 1 >= k {
+            res.push(v[*deque.front().unwrap()].clone());
+        }
+    }
+
+    res
+}
+
+/// 所有长度为 k 的滑动窗口的最小值
+pub fn sliding_window_min<T: Ord + Clone>(v: &[T], k: usize) -> Vec<T> {
+    sliding_window(v, k, |a, b| a < b)
+}
+
+/// 所有长度为 k 的滑动窗口的最大值
+pub fn sliding_window_max<T: Ord + Clone>(v: &[T], k: usize) -> Vec<T> {
+    sliding_window(v, k, |a, b| a > b)
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use crate::number_theory::gcd;
+
     #[test]
     fn test_sparse_table_max() {
-        use super::*;
-
         let a = vec![3, 5, 7, 2, 1, 9];
-        let f = init(&a);
+        let f = max_table(&a);
 
-        assert_eq!(max(&f, 0, 3), 7);
-        assert_eq!(max(&f, 3, 4), 2);
-        assert_eq!(max(&f, 4, 4), 1);
-        assert_eq!(max(&f, 0, 5), 9);
+        assert_eq!(f.query(0, 3), Some(7));
+        assert_eq!(f.query(3, 4), Some(2));
+        assert_eq!(f.query(4, 4), Some(1));
+        assert_eq!(f.query(0, 5), Some(9));
+        assert_eq!(f.query(0, 6), None);
+        assert_eq!(f.query(3, 2), None);
+        assert!(max_table::<isize>(&[]).query(0, 0).is_none());
     }
 
     fn rand_slice(n: i32) -> Vec<isize> {
@@ -99,14 +419,102 @@ mod tests {
 
     #[test]
     fn test_sparse_table_rand() {
-        use super::*;
-
         let a = rand_slice(64);
-        let f = init(&a);
+        let f = max_table(&a);
+
+        assert_eq!(f.query(20, 60), a[20..61].iter().max().copied());
+        assert_eq!(f.query(10, 60), a[10..61].iter().max().copied());
+        assert_eq!(f.query(0, 63), a[0..].iter().max().copied());
+        assert_eq!(f.query(60, 63), a[60..].iter().max().copied());
+        assert_eq!(f.query(60, 64), None);
 
-        assert_eq!(&max(&f, 20, 60), a[20..61].iter().max().unwrap());
-        assert_eq!(&max(&f, 10, 60), a[10..61].iter().max().unwrap());
-        assert_eq!(&max(&f, 0, 64), a[0..].iter().max().unwrap());
-        assert_eq!(&max(&f, 60, 64), a[60..].iter().max().unwrap());
+        let min = min_table(&a);
+        let argmin = ArgSparseTable::argmin(&a);
+        let argmax = ArgSparseTable::argmax(&a);
+        let g: Vec<u64> = a.iter().map(|&x| x as u64 * 6).collect();
+        let gt = gcd_table(&g);
+        for l in 0..a.len() {
+            for r in l..a.len() {
+                let s = &a[l..=r];
+                assert_eq!(min.query(l, r), s.iter().min().copied());
+
+                let lo = s.iter().min().unwrap();
+                let hi = s.iter().max().unwrap();
+                assert_eq!(
+                    argmin.query(l, r),
+                    s.iter().position(|x| x == lo).map(|i| i + l)
+                );
+                assert_eq!(
+                    argmax.query(l, r),
+                    s.iter().position(|x| x == hi).map(|i| i + l)
+                );
+                assert_eq!(gt.query(l, r), g[l..=r].iter().copied().reduce(gcd));
+            }
+        }
+    }
+
+    #[test]
+    fn test_sparse_table_custom_op() {
+        // 按位或也是幂等的
+        let a = [1u32, 2, 4, 8, 16];
+        let st = SparseTable::new(&a, |x, y| x | y);
+        assert_eq!(st.query(1, 3), Some(14));
+        assert_eq!(st.len(), 5);
+    }
+
+    #[test]
+    fn test_disjoint_sparse_table() {
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        for n in 0..70 {
+            let a: Vec<i64> = (0..n).map(|_| rng.gen_range(-100..100)).collect();
+            let sum = DisjointSparseTable::new(&a, |x, y| x + y);
+            // 字符串拼接不满足交换律, 可以检查运算顺序
+            let s: Vec<String> = a.iter().map(|x| x.to_string()).collect();
+            let concat = DisjointSparseTable::new(&s, |x, y| format!("{x},{y}"));
+
+            for l in 0..n {
+                for r in l..n {
+                    assert_eq!(sum.query(l, r), Some(a[l..=r].iter().sum()));
+                    assert_eq!(concat.query(l, r), Some(s[l..=r].join(",")));
+                }
+            }
+            assert_eq!(sum.query(0, n), None);
+        }
+    }
+
+    #[test]
+    fn test_plus_minus_one_rmq() {
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        for n in [1, 2, 3, 10, 100, 300] {
+            let mut a = vec![0i64];
+            for _ in 1..n {
+                let d = if rng.gen_bool(0.5) { 1 } else { -1 };
+                a.push(a.last().unwrap() + d);
+            }
+
+            let rmq = PlusMinusOneRmq::new(&a);
+            for l in 0..n {
+                for r in l..n {
+                    let s = &a[l..=r];
+                    let min = s.iter().min().unwrap();
+                    let expect = s.iter().position(|x| x == min).unwrap() + l;
+                    assert_eq!(rmq.query(l, r), Some(expect));
+                }
+            }
+            assert_eq!(rmq.query(0, n), None);
+        }
+    }
+
+    #[test]
+    fn test_sliding_window() {
+        let a = [1, 3, -1, -3, 5, 3, 6, 7];
+        assert_eq!(sliding_window_max(&a, 3), vec![3, 3, 5, 5, 6, 7]);
+        assert_eq!(sliding_window_min(&a, 3), vec![-1, -3, -3, -3, 3, 3]);
+        assert_eq!(sliding_window_min(&a, 1), a.to_vec());
+        assert!(sliding_window_min(&a, 9).is_empty());
     }
 }
@@ -0,0 +1,262 @@
+//! 分块和莫队算法
+//!
+//! - [OI Wiki - 分块思想](https://oi-wiki.org/ds/decompose/)
+//! - [OI Wiki - 普通莫队算法](https://oi-wiki.org/misc/mo-algo/)
+//!
+//! 分块把长度为 n 的数组切分成大小为 b ≈ √n 的若干块, 预处理每一块的区间和与最小值
+//!
+//! - 单点修改: 修改元素后重新计算所在块的信息, 区间和可以 O(1) 更新, 最小值需要 O(√n) 重新计算
+//! - 区间查询: 两端不完整的块逐个元素计算, 中间完整的块直接使用预处理的信息, O(√n)
+//!
+//! 和 [`crate::segment_tree`] 与 [`crate::binary_indexed_tree`] 的 O(logn) 相比复杂度更高,
+//! 但是实现简单, 并且很多不满足区间可合并性质的问题也可以用分块解决
+//!
+//! 莫队算法用于离线处理区间查询: 如果已知区间 `[l, r)` 的答案, 能够 O(1) 得到 `[l±1, r)` 和 `[l, r±1)` 的答案,
+//! 就可以把所有查询按照左端点所在的块排序, 同一块内按右端点排序, 然后依次移动左右端点
+//! 左端点每次查询最多移动 O(√n), 右端点在每一块内最多移动 O(n), 总复杂度 O((n + q)√n)
+//! 这里块的下标从 0 开始, 下标为偶数的块右端点升序, 下标为奇数的块右端点降序, 可以减少右端点来回移动的距离
+
+use std::ops::Range;
+
+/// 分块数组, 支持单点修改、区间求和与区间最小值
+pub struct BlockArray {
+    values: Vec<isize>,
+    block: usize,
+    sums: Vec<isize>,
+    mins: Vec<isize>,
+}
+
+impl BlockArray {
+    pub fn new(values: &[isize]) -> Self {
+        let block = ((values.len() as f64).sqrt() as usize).max(1);
+        let sums = values.chunks(block).map(|c| c.iter().sum()).collect();
+        let mins = values
+            .chunks(block)
+            .map(|c| *c.iter().min().expect("chunk must not be empty"))
+            .collect();
+
+        Self {
+            values: values.to_vec(),
+            block,
+            sums,
+            mins,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn get(&self, i: usize) -> isize {
+        self.values[i]
+    }
+
+    /// 把第 i 个元素修改为 v
+    pub fn update(&mut self, i: usize, v: isize) {
+        let b = i / self.block;
+        self.sums[b] += v - self.values[i];
+        self.values[i] = v;
+
+        let start = b * self.block;
+        let end = (start + self.block).min(self.values.len());
+        self.mins[b] = *self.values[start..end].iter().min().unwrap();
+    }
+
+    /// 把查询区间分成左边不完整的块、中间完整的块和右边不完整的块
+    fn query<T>(
+        &self,
+        range: Range<usize>,
+        init: T,
+        value: impl Fn(T, isize) -> T,
+        block: impl Fn(T, usize) -> T,
+    ) -> T {
+        assert!(range.start <= range.end && range.end <= self.values.len());
+        let (mut i, end) = (range.start, range.end);
+        let mut acc = init;
+
+        while i < end {
+            if i.is_multiple_of(self.block) && i + self.block <= end {
+                acc = block(acc, i / self.block);
+                i += self.block;
+            } else {
+                acc = value(acc, self.values[i]);
+                i += 1;
+            }
+        }
+
+        acc
+    }
+
+    /// 区间 `[l, r)` 之和
+    pub fn sum(&self, range: Range<usize>) -> isize {
+        self.query(range, 0, |acc, v| acc + v, |acc, b| acc + self.sums[b])
+    }
+
+    /// 区间 `[l, r)` 的最小值, 空区间返回 None
+    pub fn min(&self, range: Range<usize>) -> Option<isize> {
+        let min = |acc: Option<isize>, v: isize| Some(acc.map_or(v, |a| a.min(v)));
+        self.query(range, None, min, |acc, b| min(acc, self.mins[b]))
+    }
+}
+
+/// 莫队算法维护的区间状态
+pub trait MoState {
+    type Answer;
+
+    /// 把第 i 个元素加入当前区间
+    fn add(&mut self, i: usize);
+
+    /// 把第 i 个元素移出当前区间
+    fn remove(&mut self, i: usize);
+
+    /// 当前区间的答案
+    fn answer(&self) -> Self::Answer;
+}
+
+/// 离线处理长度为 n 的数组上的所有区间查询, 按照查询的顺序返回答案, 有区间越界时 panic
+pub fn mo<S: MoState>(n: usize, queries: &[Range<usize>], state: &mut S) -> Vec<S::Answer> {
+    assert!(
+        queries.iter().all(|q| q.start <= q.end && q.end <= n),
+        "query range out of bounds"
+    );
+
+    let block = ((n as f64).sqrt() as usize).max(1);
+    let mut order: Vec<usize> = (0..queries.len()).collect();
+    order.sort_by_key(|&i| {
+        let q = &queries[i];
+        let b = q.start / block;
+        // 下标为偶数的块右端点升序, 下标为奇数的块右端点降序
+        let end = if b.is_multiple_of(2) {
+            q.end
+        } else {
+            n - q.end
+        };
+        (b, end)
+    });
+
+    let mut answers: Vec<Option<S::Answer>> = (0..queries.len()).map(|_| None).collect();
+    let (mut l, mut r) = (0, 0);
+    for i in order {
+        let q = &queries[i];
+
+        // 先扩大区间再缩小区间, 保证区间不会出现左端点大于右端点的情况
+        while r < q.end {
+            state.add(r);
+            r += 1;
+        }
+        while l > q.start {
+            l -= 1;
+            state.add(l);
+        }
+        while r > q.end {
+            r -= 1;
+            state.remove(r);
+        }
+        while l < q.start {
+            state.remove(l);
+            l += 1;
+        }
+
+        answers[i] = Some(state.answer());
+    }
+
+    answers.into_iter().map(|a| a.unwrap()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_block_array() {
+        let mut rng = rand::thread_rng();
+        let mut values: Vec<isize> = (0..200).map(|_| rng.gen_range(-100..100)).collect();
+        let mut arr = BlockArray::new(&values);
+        assert_eq!(arr.len(), 200);
+
+        for _ in 0..2000 {
+            let i = rng.gen_range(0..values.len());
+            let v = rng.gen_range(-100..100);
+            values[i] = v;
+            arr.update(i, v);
+
+            let l = rng.gen_range(0..=values.len());
+            let r = rng.gen_range(l..=values.len());
+            assert_eq!(arr.sum(l..r), values[l..r].iter().sum::<isize>());
+            assert_eq!(arr.min(l..r), values[l..r].iter().min().copied());
+        }
+    }
+
+    /// 区间内不同元素的数量
+    struct Distinct<'a> {
+        values: &'a [usize],
+        count: Vec<usize>,
+        distinct: usize,
+    }
+
+    impl<'a> MoState for Distinct<'a> {
+        type Answer = usize;
+
+        fn add(&mut self, i: usize) {
+            let c = &mut self.count[self.values[i]];
+            if *c == 0 {
+                self.distinct += 1;
+            }
+            *c += 1;
+        }
+
+        fn remove(&mut self, i: usize) {
+            let c = &mut self.count[self.values[i]];
+            *c -= 1;
+            if *c == 0 {
+                self.distinct -= 1;
+            }
+        }
+
+        fn answer(&self) -> usize {
+            self.distinct
+        }
+    }
+
+    #[test]
+    fn test_mo() {
+        let mut rng = rand::thread_rng();
+        let values: Vec<usize> = (0..500).map(|_| rng.gen_range(0..50)).collect();
+        let queries: Vec<_> = (0..1000)
+            .map(|_| {
+                let l = rng.gen_range(0..=values.len());
+                l..rng.gen_range(l..=values.len())
+            })
+            .collect();
+
+        let mut state = Distinct {
+            values: &values,
+            count: vec![0; 50],
+            distinct: 0,
+        };
+        let answers = mo(values.len(), &queries, &mut state);
+        for (q, ans) in queries.iter().zip(answers) {
+            let expect = values[q.clone()].iter().collect::<HashSet<_>>().len();
+            assert_eq!(ans, expect);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "query range out of bounds")]
+    fn test_mo_out_of_bounds() {
+        let values = [0; 10];
+        let mut state = Distinct {
+            values: &values,
+            count: vec![0; 1],
+            distinct: 0,
+        };
+        // 5..11 在下标为 1 的块, 排序时会计算 n - end
+        mo(values.len(), &[0..3, 5..11], &mut state);
+    }
+}
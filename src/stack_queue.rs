@@ -0,0 +1,244 @@
+//! 栈和队列
+//!
+//! - [`Queue`]: 用两个栈实现队列, 入队压入 input, 出队时 output 为空才把 input 整个倒进 output, 均摊 O(1)
+//! - [`MinStack`]: 额外记录每个前缀最小值的位置, O(1) 查询最小值
+//! - [`MinQueue`]: 单调队列, 只保留可能成为最小值的元素位置, O(1) 查询最小值, 常用于滑动窗口最小值
+
+use std::collections::VecDeque;
+
+/// 两个栈实现的队列
+#[derive(Debug, Default)]
+pub struct Queue<T> {
+    input: Vec<T>,
+    output: Vec<T>,
+}
+
+impl<T> Queue<T> {
+    pub fn new() -> Self {
+        Self {
+            input: vec![],
+            output: vec![],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.input.len() + self.output.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn push(&mut self, value: T) {
+        self.input.push(value);
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.output.is_empty() {
+            // 倒过来之后 input 的栈底变成 output 的栈顶
+            self.output.extend(self.input.drain(..).rev());
+        }
+        self.output.pop()
+    }
+
+    /// 队首元素, output 为空时队首是 input 的栈底
+    pub fn peek(&self) -> Option<&T> {
+        self.output.last().or_else(|| self.input.first())
+    }
+}
+
+/// 可以 O(1) 查询最小值的栈
+#[derive(Debug, Default)]
+pub struct MinStack<T> {
+    data: Vec<T>,
+    /// 每次出现新的最小值(包括相等)时记录它的位置, 栈顶是当前最小值的位置
+    mins: Vec<usize>,
+}
+
+impl<T: Ord> MinStack<T> {
+    pub fn new() -> Self {
+        Self {
+            data: vec![],
+            mins: vec![],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn push(&mut self, value: T) {
+        if self.min().is_none_or(|min| value <= *min) {
+            self.mins.push(self.data.len());
+        }
+        self.data.push(value);
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        let value = self.data.pop()?;
+        if self.mins.last() == Some(&self.data.len()) {
+            self.mins.pop();
+        }
+        Some(value)
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.data.last()
+    }
+
+    pub fn min(&self) -> Option<&T> {
+        self.mins.last().map(|&i| &self.data[i])
+    }
+}
+
+/// 可以 O(1) 查询最小值的单调队列
+#[derive(Debug, Default)]
+pub struct MinQueue<T> {
+    data: VecDeque<T>,
+    /// 可能成为最小值的元素的绝对位置, 对应的元素单调不减
+    mins: VecDeque<usize>,
+    /// 队首元素的绝对位置, 即已经出队的元素数量
+    head: usize,
+}
+
+impl<T: Ord> MinQueue<T> {
+    pub fn new() -> Self {
+        Self {
+            data: VecDeque::new(),
+            mins: VecDeque::new(),
+            head: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// 入队时弹出所有比它大的元素, 它们比新元素更早出队, 不可能再成为最小值
+    pub fn push(&mut self, value: T) {
+        while let Some(&i) = self.mins.back() {
+            if self.data[i - self.head] <= value {
+                break;
+            }
+            self.mins.pop_back();
+        }
+        self.mins.push_back(self.head + self.data.len());
+        self.data.push_back(value);
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        let value = self.data.pop_front()?;
+        if self.mins.front() == Some(&self.head) {
+            self.mins.pop_front();
+        }
+        self.head += 1;
+        Some(value)
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.data.front()
+    }
+
+    pub fn min(&self) -> Option<&T> {
+        self.mins.front().map(|&i| &self.data[i - self.head])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn test_queue() {
+        let mut q = Queue::new();
+        assert_eq!(q.pop(), None);
+        assert_eq!(q.peek(), None);
+
+        q.push(1);
+        q.push(2);
+        assert_eq!(q.peek(), Some(&1));
+        assert_eq!(q.pop(), Some(1));
+        q.push(3);
+        assert_eq!(q.peek(), Some(&2));
+        assert_eq!(q.len(), 2);
+        assert_eq!(q.pop(), Some(2));
+        assert_eq!(q.pop(), Some(3));
+        assert!(q.is_empty());
+    }
+
+    #[test]
+    fn test_min_stack() {
+        let mut s = MinStack::new();
+        assert_eq!(s.min(), None);
+        for x in [3, 5, 2, 2, 4] {
+            s.push(x);
+        }
+        assert_eq!(s.min(), Some(&2));
+        assert_eq!(s.pop(), Some(4));
+        assert_eq!(s.pop(), Some(2));
+        // 相等的最小值还剩一个
+        assert_eq!(s.min(), Some(&2));
+        assert_eq!(s.pop(), Some(2));
+        assert_eq!(s.min(), Some(&3));
+        assert_eq!(s.peek(), Some(&5));
+    }
+
+    #[test]
+    fn test_min_queue_sliding_window() {
+        let xs = [1, 3, -1, -3, 5, 3, 6, 7];
+        let mut q = MinQueue::new();
+        let mut mins = vec![];
+        for (i, &x) in xs.iter().enumerate() {
+            q.push(x);
+            if i >= 3 {
+                q.pop();
+            }
+            if i >= 2 {
+                mins.push(*q.min().unwrap());
+            }
+        }
+        assert_eq!(mins, [-1, -3, -3, -3, 3, 3]);
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0x5eed);
+        let mut queue = Queue::new();
+        let mut stack = MinStack::new();
+        let mut min_queue = MinQueue::new();
+        let mut deque = VecDeque::new();
+        let mut vec = vec![];
+
+        for _ in 0..10000 {
+            if rng.gen_bool(0.55) {
+                let x = rng.gen_range(0..100);
+                queue.push(x);
+                min_queue.push(x);
+                stack.push(x);
+                deque.push_back(x);
+                vec.push(x);
+            } else {
+                assert_eq!(queue.pop(), deque.front().copied());
+                assert_eq!(min_queue.pop(), deque.pop_front());
+                assert_eq!(stack.pop(), vec.pop());
+            }
+
+            assert_eq!(queue.len(), deque.len());
+            assert_eq!(queue.peek(), deque.front());
+            assert_eq!(min_queue.peek(), deque.front());
+            assert_eq!(min_queue.min(), deque.iter().min());
+            assert_eq!(stack.len(), vec.len());
+            assert_eq!(stack.peek(), vec.last());
+            assert_eq!(stack.min(), vec.iter().min());
+        }
+    }
+}
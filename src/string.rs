@@ -68,12 +68,32 @@ pub fn minimal_string(s: &str) -> usize {
 /// - 如果找到了一个回文串将其臂长 `d[i]` 记录下来, 并记录当前臂长最大的那个回文串的左右索引 `[l, r]`
 /// - 对于最长回文串右臂内的一个索引 j 查找与其对称的左臂内的索引的回文串臂长 `d[l + r - j]`
 /// - 根据对称性 j 可以从此臂长开始再向外扩展查找回文串
+///
+/// 返回最长回文子串在字节序列上的闭区间, 多字节字符请使用 [`longest_palindrome`]
 pub fn manacher(s: &str) -> (usize, usize) {
-    let s = s.as_bytes();
-    let mut t = vec![b'#'];
-    for &c in s {
-        t.push(c);
-        t.push(b'#');
+    let d = radii(s.as_bytes());
+
+    // 查找最长臂长及其对应的索引
+    let (mut max_mid, mut max_r) = (0, 0);
+    for (i, &v) in d.iter().enumerate() {
+        if v > max_r {
+            (max_mid, max_r) = (i, v);
+        }
+    }
+
+    // 计算出原始字符串中的索引
+    ((max_mid - max_r) / 2, (max_mid + max_r) / 2 - 1)
+}
+
+/// 插入特殊值之后每个中心的回文臂长 `d`, 长度为 `2n+1`
+///
+/// 插入的特殊值用 None 表示, 这样对任意元素类型都不需要挑选一个不会出现的值  
+/// 插入后以 i 为中心的臂长恰好等于原序列中以它为中心的最长回文串的长度
+fn radii<T: Eq>(s: &[T]) -> Vec<usize> {
+    let mut t = vec![None];
+    for c in s {
+        t.push(Some(c));
+        t.push(None);
     }
 
     let n = t.len();
@@ -107,16 +127,392 @@ pub fn manacher(s: &str) -> (usize, usize) {
         }
     }
 
-    // 查找最长臂长及其对应的索引
-    let (mut max_mid, mut max_r) = (0, 0);
-    for (i, &v) in d.iter().enumerate() {
-        if v > max_r {
-            (max_mid, max_r) = (i, v);
+    d
+}
+
+/// 最长回文子串, 按字符而不是字节判断回文, 有多个时返回最靠左的一个
+///
+/// ```
+/// use impx::string::longest_palindrome;
+///
+/// assert_eq!(longest_palindrome("xabbac"), "abba");
+/// assert_eq!(longest_palindrome("上海自来水来自海上!"), "上海自来水来自海上");
+/// assert_eq!(longest_palindrome(""), "");
+/// ```
+pub fn longest_palindrome(s: &str) -> &str {
+    let chars: Vec<char> = s.chars().collect();
+    let d = radii(&chars);
+    let (mid, len) =
+        d.iter().copied().enumerate().fold(
+            (0, 0),
+            |best, (i, v)| if v > best.1 { (i, v) } else { best },
+        );
+
+    // 字符下标转换为字节偏移
+    let offsets: Vec<usize> = s.char_indices().map(|(i, _)| i).chain([s.len()]).collect();
+    let start = (mid - len) / 2;
+    &s[offsets[start]..offsets[start + len]]
+}
+
+/// 回文子串的个数, 位置不同的相同子串分别计数
+///
+/// 以 i 为中心的最长回文串长度为 `d[i]` 时, 长度为 `d[i], d[i]-2, ...` 的子串也都是回文串,
+/// 一共有 `(d[i]+1)/2` 个
+///
+/// ```
+/// use impx::string::count_palindromic_substrings;
+///
+/// assert_eq!(count_palindromic_substrings("aaa"), 6);
+/// assert_eq!(count_palindromic_substrings("abc"), 3);
+/// ```
+pub fn count_palindromic_substrings(s: &str) -> usize {
+    let chars: Vec<char> = s.chars().collect();
+    radii(&chars).iter().map(|&d| d.div_ceil(2)).sum()
+}
+
+/// O(n) 预处理之后 O(1) 判断任意区间是否是回文串
+///
+/// 区间 `[l, r]` 在插入特殊值后的中心是 `l + r + 1`, 它是回文串当且仅当这个中心的臂长不小于区间长度
+///
+/// ```
+/// use impx::string::PalindromeQuery;
+///
+/// let q = PalindromeQuery::new("abacaba".as_bytes());
+/// assert!(q.is_palindrome(0, 6));
+/// assert!(q.is_palindrome(4, 6));
+/// assert!(!q.is_palindrome(0, 1));
+/// ```
+pub struct PalindromeQuery {
+    d: Vec<usize>,
+}
+
+impl PalindromeQuery {
+    pub fn new<T: Eq>(s: &[T]) -> Self {
+        Self { d: radii(s) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.d.len() / 2
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 区间 `[l, r]` 是否是回文串, 区间不合法时返回 false
+    pub fn is_palindrome(&self, l: usize, r: usize) -> bool {
+        l <= r && r < self.len() && self.d[l + r + 1] > r - l
+    }
+}
+
+/// 编辑脚本中的一步
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditOp {
+    /// 保留 a 中的字符
+    Keep(char),
+    /// 插入 b 中的字符
+    Insert(char),
+    /// 删除 a 中的字符
+    Delete(char),
+    /// 把 a 中的字符替换为 b 中的字符
+    Replace(char, char),
+}
+
+/// 用 Hirschberg 算法求出把 a 变成 b 的一个最短编辑脚本, 除 Keep 之外的步数等于编辑距离
+///
+/// 直接回溯需要保存整个 `O(mn)` 的 dp 表, Hirschberg 算法用分治只需要 `O(m + n)` 的空间,
+/// 其中 dp 只保存 b 上的一行, 另外还有两个输入反转后的副本:
+///
+/// - 把 a 从中间切成 `a1 = a[..mid]` 和 `a2 = a[mid..]`
+/// - 正向计算 a1 到 b 每个前缀的编辑距离, 反向计算 a2 到 b 每个后缀的编辑距离
+/// - 取使两者之和最小的切分点 k, 最优脚本一定可以拆成 `a1 -> b[..k]` 和 `a2 -> b[k..]` 两段, 分别递归
+///
+/// 每一层递归计算量是上一层的一半, 时间复杂度仍然是 `O(mn)`
+///
+/// ```
+/// use impx::string::{edit_script, EditOp};
+///
+/// let ops = edit_script("kitten", "sitting");
+/// assert_eq!(ops.iter().filter(|op| !matches!(op, EditOp::Keep(_))).count(), 3);
+/// ```
+pub fn edit_script(a: &str, b: &str) -> Vec<EditOp> {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let mut ops = vec![];
+    hirschberg(&a, &b, &mut ops);
+    ops
+}
+
+/// a 到 b 的每个前缀的编辑距离, 即 dp 表的最后一行
+fn last_row(a: &[char], b: &[char]) -> Vec<usize> {
+    let mut dp: Vec<usize> = (0..=b.len()).collect();
+    for (i, &x) in a.iter().enumerate() {
+        let mut prev = dp[0];
+        dp[0] = i + 1;
+        for (j, &y) in b.iter().enumerate() {
+            let cur = if x == y {
+                prev
+            } else {
+                1 + prev.min(dp[j]).min(dp[j + 1])
+            };
+            prev = dp[j + 1];
+            dp[j + 1] = cur;
         }
     }
+    dp
+}
 
-    // 计算出原始字符串中的索引
-    ((max_mid - max_r) / 2, (max_mid + max_r) / 2 - 1)
+fn hirschberg(a: &[char], b: &[char], ops: &mut Vec<EditOp>) {
+    if a.is_empty() {
+        ops.extend(b.iter().map(|&c| EditOp::Insert(c)));
+        return;
+    }
+    if b.is_empty() {
+        ops.extend(a.iter().map(|&c| EditOp::Delete(c)));
+        return;
+    }
+    if a.len() == 1 {
+        // 只有一个字符时: b 中包含它就保留, 否则替换掉 b 的第一个字符
+        let c = a[0];
+        match b.iter().position(|&x| x == c) {
+            Some(j) => {
+                ops.extend(b[..j].iter().map(|&x| EditOp::Insert(x)));
+                ops.push(EditOp::Keep(c));
+                ops.extend(b[j + 1..].iter().map(|&x| EditOp::Insert(x)));
+            }
+            None => {
+                ops.push(EditOp::Replace(c, b[0]));
+                ops.extend(b[1..].iter().map(|&x| EditOp::Insert(x)));
+            }
+        }
+        return;
+    }
+
+    let mid = a.len() / 2;
+    let left = last_row(&a[..mid], b);
+    let (ra, rb): (Vec<char>, Vec<char>) = (
+        a[mid..].iter().rev().copied().collect(),
+        b.iter().rev().copied().collect(),
+    );
+    let right = last_row(&ra, &rb);
+
+    let k = (0..=b.len())
+        .min_by_key(|&k| left[k] + right[b.len() - k])
+        .unwrap();
+    hirschberg(&a[..mid], &b[..k], ops);
+    hirschberg(&a[mid..], &b[k..], ops);
+}
+
+/// Damerau-Levenshtein 距离, 在编辑距离的基础上允许交换相邻的两个字符
+///
+/// 这里是不受限制的版本: 交换过的字符之间还可以继续插入删除, 例如 `"ca" -> "ac" -> "abc"` 距离为 2  
+/// (只允许每个子串编辑一次的 OSA 版本会得到 3)
+///
+/// 状态 `dp[i][j]` 同编辑距离, 额外记录每个字符最后出现在 a 中的行 `last[c]`:
+/// 对于 `b[j]`, 设 a 中最后一次出现 `b[j]` 的位置为 k, b 中 j 之前最后一次出现 `a[i]` 的位置为 l,
+/// 那么可以把 `a[k..i]` 变成 `b[l..j]`: 删除中间 `i-k-1` 个字符, 交换一次, 再插入 `j-l-1` 个字符
+///
+/// ```
+/// use impx::string::damerau_levenshtein;
+///
+/// assert_eq!(damerau_levenshtein("ca", "abc"), 2);
+/// assert_eq!(damerau_levenshtein("abcdef", "abdcef"), 1);
+/// ```
+pub fn damerau_levenshtein(a: &str, b: &str) -> usize {
+    use std::collections::HashMap;
+
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let (n, m) = (a.len(), b.len());
+    let inf = n + m;
+
+    // 外面多加一圈无穷大, dp[i+1][j+1] 对应 a 的前 i 个字符和 b 的前 j 个字符
+    let mut dp = vec![vec![0; m + 2]; n + 2];
+    dp[0][0] = inf;
+    for i in 0..=n {
+        dp[i + 1][0] = inf;
+        dp[i + 1][1] = i;
+    }
+    for j in 0..=m {
+        dp[0][j + 1] = inf;
+        dp[1][j + 1] = j;
+    }
+
+    let mut last: HashMap<char, usize> = HashMap::new();
+    for i in 1..=n {
+        // b 中 j 之前最后一次和 a[i] 相同的位置
+        let mut l = 0;
+        for j in 1..=m {
+            let k = last.get(&b[j - 1]).copied().unwrap_or(0);
+            let cost = (a[i - 1] != b[j - 1]) as usize;
+            dp[i + 1][j + 1] = (dp[i][j] + cost)
+                .min(dp[i + 1][j] + 1)
+                .min(dp[i][j + 1] + 1)
+                .min(dp[k][l] + (i - k - 1) + 1 + (j - l - 1));
+            if cost == 0 {
+                l = j;
+            }
+        }
+        last.insert(a[i - 1], i);
+    }
+
+    dp[n + 1][m + 1]
+}
+
+/// 多项式滚动哈希, O(n) 预处理后 O(1) 求任意子串的哈希值
+///
+/// `h[i]` 是前 i 个元素的哈希值 `s[0]*B^(i-1) + ... + s[i-1]`, 那么子串 `s[l..r]` 的哈希值为
+/// `h[r] - h[l] * B^(r-l)`
+///
+/// 模数取梅森素数 `2^61-1`, 乘法在 u128 中进行, 取模只需要移位和加法  
+/// 哈希值相同并不保证子串相同, 需要精确结果时应当再比较一次
+///
+/// ```
+/// use impx::string::RollingHash;
+///
+/// let s: Vec<char> = "abcabc".chars().collect();
+/// let h = RollingHash::new(&s);
+/// assert_eq!(h.hash(0..3), h.hash(3..6));
+/// assert_ne!(h.hash(0..3), h.hash(1..4));
+/// ```
+pub struct RollingHash {
+    h: Vec<u64>,
+    p: Vec<u64>,
+}
+
+impl RollingHash {
+    const MOD: u64 = (1 << 61) - 1;
+    const BASE: u64 = 0x1f2e_3d4c_5b6a_7988;
+
+    fn mul(a: u64, b: u64) -> u64 {
+        let t = a as u128 * b as u128;
+        let t = (t >> 61) as u64 + (t as u64 & Self::MOD);
+        if t >= Self::MOD {
+            t - Self::MOD
+        } else {
+            t
+        }
+    }
+
+    pub fn new<T: Copy + Into<u32>>(s: &[T]) -> Self {
+        let mut h = vec![0; s.len() + 1];
+        let mut p = vec![1; s.len() + 1];
+        for (i, &c) in s.iter().enumerate() {
+            // 加一避免 0 值元素的前导 0 不影响哈希
+            h[i + 1] = (Self::mul(h[i], Self::BASE) + c.into() as u64 + 1) % Self::MOD;
+            p[i + 1] = Self::mul(p[i], Self::BASE);
+        }
+        Self { h, p }
+    }
+
+    pub fn len(&self) -> usize {
+        self.h.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 子串 `s[range]` 的哈希值
+    pub fn hash(&self, range: std::ops::Range<usize>) -> u64 {
+        let (l, r) = (range.start, range.end);
+        assert!(l <= r && r <= self.len(), "range out of bounds");
+        (self.h[r] + Self::MOD - Self::mul(self.h[l], self.p[r - l])) % Self::MOD
+    }
+}
+
+/// 二分最大的长度 len 使 `check(len)` 返回 Some, 要求 check 对长度单调
+fn binary_search_len(max: usize, check: impl Fn(usize) -> Option<usize>) -> (usize, usize) {
+    let (mut lo, mut hi) = (0, max);
+    let mut best = (0, 0);
+    while lo < hi {
+        let mid = lo + (hi - lo).div_ceil(2);
+        match check(mid) {
+            Some(start) => {
+                best = (start, mid);
+                lo = mid;
+            }
+            None => hi = mid - 1,
+        }
+    }
+    best
+}
+
+/// 字符下标区间 `[start, start+len)` 转换为字节切片
+fn char_slice(s: &str, start: usize, len: usize) -> &str {
+    let mut offsets = s.char_indices().map(|(i, _)| i).chain([s.len()]);
+    let begin = offsets.nth(start).unwrap();
+    let end = if len == 0 {
+        begin
+    } else {
+        offsets.nth(len - 1).unwrap()
+    };
+    &s[begin..end]
+}
+
+/// 最长公共子串, 返回它在 a 中的位置, 有多个时返回任意一个
+///
+/// 如果存在长度为 len 的公共子串那么一定存在长度为 len-1 的公共子串, 所以可以二分长度:
+/// 把 a 中所有长度为 len 的子串的哈希放入哈希表, 再检查 b 中是否有相同的子串  
+/// 哈希相同的子串会再逐个字符比较, 所以哈希冲突只影响效率不影响结果
+///
+/// 时间复杂度期望 `O((m+n)log(min(m, n)))`
+///
+/// ```
+/// use impx::string::longest_common_substring;
+///
+/// assert_eq!(longest_common_substring("xabcdey", "zzbcdezz"), "bcde");
+/// assert_eq!(longest_common_substring("abc", "xyz"), "");
+/// ```
+pub fn longest_common_substring<'a>(a: &'a str, b: &str) -> &'a str {
+    use std::collections::HashMap;
+
+    let (ca, cb): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let (ha, hb) = (RollingHash::new(&ca), RollingHash::new(&cb));
+
+    let (start, len) = binary_search_len(ca.len().min(cb.len()), |len| {
+        let mut seen: HashMap<u64, Vec<usize>> = HashMap::new();
+        for i in 0..=ca.len() - len {
+            seen.entry(ha.hash(i..i + len)).or_default().push(i);
+        }
+        (0..=cb.len() - len).find_map(|j| {
+            seen.get(&hb.hash(j..j + len))?
+                .iter()
+                .copied()
+                .find(|&i| ca[i..i + len] == cb[j..j + len])
+        })
+    });
+
+    char_slice(a, start, len)
+}
+
+/// 最长重复子串, 即至少出现两次(可以重叠)的最长子串, 有多个时返回任意一个
+///
+/// 同 [`longest_common_substring`] 二分长度, 检查同一个字符串中是否有两个不同位置的子串相同
+///
+/// ```
+/// use impx::string::longest_repeated_substring;
+///
+/// assert_eq!(longest_repeated_substring("banana"), "ana");
+/// assert_eq!(longest_repeated_substring("abc"), "");
+/// ```
+pub fn longest_repeated_substring(s: &str) -> &str {
+    use std::collections::HashMap;
+
+    let cs: Vec<char> = s.chars().collect();
+    let h = RollingHash::new(&cs);
+
+    let (start, len) = binary_search_len(cs.len().saturating_sub(1), |len| {
+        let mut seen: HashMap<u64, Vec<usize>> = HashMap::new();
+        for j in 0..=cs.len() - len {
+            let starts = seen.entry(h.hash(j..j + len)).or_default();
+            if let Some(&i) = starts.iter().find(|&&i| cs[i..i + len] == cs[j..j + len]) {
+                return Some(i);
+            }
+            starts.push(j);
+        }
+        None
+    });
+
+    char_slice(s, start, len)
 }
 
 #[cfg(test)]
@@ -138,4 +534,254 @@ mod tests {
         assert_eq!(manacher("xabcbac"), (1, 5));
         assert_eq!(manacher("aacaabbacabb"), (5, 11));
     }
+
+    fn is_palindrome<T: Eq>(s: &[T]) -> bool {
+        s.iter().eq(s.iter().rev())
+    }
+
+    #[test]
+    fn test_palindromes() {
+        use super::*;
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let chars: Vec<char> = (0..rng.gen_range(0..30))
+                .map(|_| ['a', 'b', 'é'][rng.gen_range(0..3)])
+                .collect();
+            let s: String = chars.iter().collect();
+            let n = chars.len();
+
+            let q = PalindromeQuery::new(&chars);
+            let mut count = 0;
+            let mut longest = 0;
+            for l in 0..n {
+                for r in l..n {
+                    let expect = is_palindrome(&chars[l..=r]);
+                    assert_eq!(q.is_palindrome(l, r), expect, "{s} [{l}, {r}]");
+                    count += expect as usize;
+                    if expect {
+                        longest = longest.max(r - l + 1);
+                    }
+                }
+            }
+            assert!(!q.is_palindrome(0, n));
+
+            assert_eq!(count_palindromic_substrings(&s), count);
+            let p = longest_palindrome(&s);
+            assert_eq!(p.chars().count(), longest);
+            assert!(is_palindrome(&p.chars().collect::<Vec<_>>()));
+        }
+    }
+
+    fn apply(a: &str, ops: &[super::EditOp]) -> String {
+        use super::EditOp::*;
+
+        let mut it = a.chars();
+        let mut res = String::new();
+        for &op in ops {
+            match op {
+                Keep(c) => {
+                    assert_eq!(it.next(), Some(c));
+                    res.push(c);
+                }
+                Insert(c) => res.push(c),
+                Delete(c) => assert_eq!(it.next(), Some(c)),
+                Replace(c, d) => {
+                    assert_eq!(it.next(), Some(c));
+                    assert_ne!(c, d);
+                    res.push(d);
+                }
+            }
+        }
+        assert_eq!(it.next(), None);
+        res
+    }
+
+    #[test]
+    fn test_edit_script() {
+        use super::*;
+        use crate::dp::edit_distance;
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..200 {
+            let a: String = (0..rng.gen_range(0..20))
+                .map(|_| ['a', 'b', 'c', '中'][rng.gen_range(0..4)])
+                .collect();
+            let b: String = (0..rng.gen_range(0..20))
+                .map(|_| ['a', 'b', 'c', '中'][rng.gen_range(0..4)])
+                .collect();
+
+            let ops = edit_script(&a, &b);
+            assert_eq!(apply(&a, &ops), b);
+            let cost = ops
+                .iter()
+                .filter(|op| !matches!(op, EditOp::Keep(_)))
+                .count();
+            assert_eq!(cost, edit_distance(&a, &b), "{a} -> {b}");
+            assert_eq!(edit_distance(&a, &b), edit_distance(&b, &a));
+        }
+    }
+
+    /// 在长度不超过上限的字符串上 BFS 求出允许相邻交换的最短编辑距离
+    fn damerau_bfs(a: &str, b: &str, alphabet: &[char]) -> usize {
+        use std::collections::{HashMap, VecDeque};
+
+        let limit = a.chars().count().max(b.chars().count()) + 1;
+        let mut dist = HashMap::from([(a.to_string(), 0)]);
+        let mut queue = VecDeque::from([a.to_string()]);
+        while let Some(s) = queue.pop_front() {
+            let d = dist[&s];
+            if s == b {
+                return d;
+            }
+            let s: Vec<char> = s.chars().collect();
+            let mut next = vec![];
+            for i in 0..=s.len() {
+                for &c in alphabet {
+                    if s.len() < limit {
+                        let mut t = s.clone();
+                        t.insert(i, c);
+                        next.push(t);
+                    }
+                    if i < s.len() {
+                        let mut t = s.clone();
+                        t[i] = c;
+                        next.push(t);
+                    }
+                }
+                if i < s.len() {
+                    let mut t = s.clone();
+                    t.remove(i);
+                    next.push(t);
+                }
+                if i + 1 < s.len() {
+                    let mut t = s.clone();
+                    t.swap(i, i + 1);
+                    next.push(t);
+                }
+            }
+            for t in next {
+                let t: String = t.into_iter().collect();
+                if !dist.contains_key(&t) {
+                    dist.insert(t.clone(), d + 1);
+                    queue.push_back(t);
+                }
+            }
+        }
+        unreachable!()
+    }
+
+    #[test]
+    fn test_damerau_levenshtein() {
+        use super::*;
+        use crate::dp::edit_distance;
+        use rand::Rng;
+
+        assert_eq!(damerau_levenshtein("", ""), 0);
+        assert_eq!(damerau_levenshtein("", "ab"), 2);
+        assert_eq!(damerau_levenshtein("ab", "ba"), 1);
+        assert_eq!(damerau_levenshtein("ca", "abc"), 2);
+        assert_eq!(damerau_levenshtein("a cat", "an act"), 2);
+
+        let alphabet = ['a', 'b', 'c'];
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let a: String = (0..rng.gen_range(0..5))
+                .map(|_| alphabet[rng.gen_range(0..3)])
+                .collect();
+            let b: String = (0..rng.gen_range(0..5))
+                .map(|_| alphabet[rng.gen_range(0..3)])
+                .collect();
+
+            let d = damerau_levenshtein(&a, &b);
+            assert_eq!(d, damerau_bfs(&a, &b, &alphabet), "{a} -> {b}");
+            assert!(d <= edit_distance(&a, &b));
+        }
+    }
+
+    fn brute_common(a: &[char], b: &[char]) -> usize {
+        let mut best = 0;
+        for i in 0..a.len() {
+            for j in 0..b.len() {
+                let k = a[i..]
+                    .iter()
+                    .zip(&b[j..])
+                    .take_while(|(x, y)| x == y)
+                    .count();
+                best = best.max(k);
+            }
+        }
+        best
+    }
+
+    #[test]
+    fn test_rolling_hash() {
+        use super::*;
+
+        let h = RollingHash::new("aaaa".as_bytes());
+        assert_eq!(h.len(), 4);
+        assert_eq!(h.hash(0..2), h.hash(2..4));
+        assert_eq!(h.hash(1..1), h.hash(3..3));
+        assert_ne!(h.hash(0..1), h.hash(0..2));
+
+        // 0 值元素也会影响哈希
+        let h = RollingHash::new(&[0u8, 0, 1]);
+        assert_ne!(h.hash(0..3), h.hash(1..3));
+    }
+
+    #[test]
+    fn test_longest_common_substring() {
+        use super::*;
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+
+        assert_eq!(longest_common_substring("", "abc"), "");
+        assert_eq!(longest_common_substring("abc", "abc"), "abc");
+        assert_eq!(
+            longest_common_substring("你好世界hello", "hello世界"),
+            "hello"
+        );
+        assert_eq!(
+            longest_common_substring("上海自来水", "北京自来水厂"),
+            "自来水"
+        );
+        assert_eq!(longest_repeated_substring(""), "");
+        assert_eq!(longest_repeated_substring("aaaa"), "aaa");
+        assert_eq!(longest_repeated_substring("中文中文字"), "中文");
+
+        let mut rng = StdRng::seed_from_u64(0x5eed);
+        for _ in 0..200 {
+            let alphabet = ['a', 'b', 'c', '字'];
+            let a: Vec<char> = (0..rng.gen_range(0..40))
+                .map(|_| alphabet[rng.gen_range(0..alphabet.len())])
+                .collect();
+            let b: Vec<char> = (0..rng.gen_range(0..40))
+                .map(|_| alphabet[rng.gen_range(0..alphabet.len())])
+                .collect();
+            let (sa, sb): (String, String) = (a.iter().collect(), b.iter().collect());
+
+            let res = longest_common_substring(&sa, &sb);
+            assert_eq!(res.chars().count(), brute_common(&a, &b), "{sa} {sb}");
+            assert!(sa.contains(res) && sb.contains(res));
+
+            let res = longest_repeated_substring(&sa);
+            let mut expect = 0;
+            for i in 0..a.len() {
+                for j in i + 1..a.len() {
+                    let k = a[i..]
+                        .iter()
+                        .zip(&a[j..])
+                        .take_while(|(x, y)| x == y)
+                        .count();
+                    expect = expect.max(k);
+                }
+            }
+            assert_eq!(res.chars().count(), expect, "{sa}");
+            let first = sa.find(res).unwrap();
+            let next = first + res.chars().next().map_or(0, char::len_utf8);
+            assert!(res.is_empty() || sa[next..].contains(res));
+        }
+    }
 }
@@ -0,0 +1,386 @@
+//! 区间调度和扫描线
+//!
+//! - [OI Wiki - 扫描线](https://oi-wiki.org/geometry/scanning/)
+//! - [wikipedia - Interval scheduling](https://en.wikipedia.org/wiki/Interval_scheduling)
+//!
+//! 所有区间都是左闭右开的 `[start, end)`, 首尾相接的两个区间 `[1, 3)` 和 `[3, 5)` 不重叠
+//!
+//! - 区间合并: 按左端点排序后依次扫描, 当前区间的左端点不超过已合并区间的右端点时延长右端点, 否则开始新的区间
+//! - 最大重叠数(最少会议室): 把每个区间拆成 `(start, +1)` 和 `(end, -1)` 两个事件, 按坐标排序后扫描并累加,
+//!   坐标相同时先处理 -1, 前一个会议结束的时刻下一个会议可以使用同一间会议室
+//! - 区间调度: 选出最多的互不重叠的区间, 贪心地每次选择结束最早并且和已选区间不重叠的区间,
+//!   交换论证: 任意最优解的第一个区间都可以替换成结束最早的区间而不产生重叠
+//!
+//! ## 矩形面积并
+//!
+//! 一条水平线从下往上扫过所有矩形, 矩形的下边是 +1 事件, 上边是 -1 事件,
+//! 相邻两个事件之间扫描线被覆盖的长度不变, 面积就是覆盖长度乘以两个事件的高度差:
+//!
+//! ```text
+//! y
+//! 4 |     +-------+
+//! 3 | +---|---+   |   y ∈ [3, 4): 覆盖 [3, 7), 长度 4
+//! 2 | |   +---|---+   y ∈ [2, 3): 覆盖 [1, 5) ∪ [3, 7) = [1, 7), 长度 6
+//! 1 | +-------+       y ∈ [1, 2): 覆盖 [1, 5), 长度 4
+//!   +--1---3---5---7-- x
+//! ```
+//!
+//! 把所有 x 坐标离散化之后, 第 i 个基本段是 `[xs[i], xs[i+1])`, 长度是 `xs[i+1] - xs[i]`,
+//! 下边加入时把矩形覆盖的基本段的覆盖次数加一, 上边离开时减一, 扫描线上被覆盖的长度
+//! 由 [`crate::segment_tree::CoverSegmentTree`] 维护, 每次修改 O(logn), 总共 O(nlogn)
+//!
+//! 坐标差都在 i128 中计算, 坐标接近 i64 的边界时也不会溢出, 但面积本身要能用 i128 表示
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::ops::Range;
+
+use crate::segment_tree::CoverSegmentTree;
+
+/// 合并所有重叠或者首尾相接的区间, 返回按左端点排序的不相交区间, 空区间会被忽略
+///
+/// ```
+/// use impx::sweep_line::merge_intervals;
+///
+/// assert_eq!(merge_intervals(&[1..3, 8..10, 2..6, 6..7]), vec![1..7, 8..10]);
+/// ```
+pub fn merge_intervals(intervals: &[Range<i64>]) -> Vec<Range<i64>> {
+    let mut sorted: Vec<_> = intervals
+        .iter()
+        .filter(|r| !r.is_empty())
+        .cloned()
+        .collect();
+    sorted.sort_by_key(|r| r.start);
+
+    let mut res: Vec<Range<i64>> = vec![];
+    for r in sorted {
+        match res.last_mut() {
+            Some(last) if r.start <= last.end => last.end = last.end.max(r.end),
+            _ => res.push(r),
+        }
+    }
+    res
+}
+
+/// 同一时刻最多有多少个区间重叠, 也就是安排所有会议最少需要的会议室数量, O(nlogn)
+///
+/// ```
+/// use impx::sweep_line::max_overlap;
+///
+/// assert_eq!(max_overlap(&[0..30, 5..10, 15..20]), 2);
+/// assert_eq!(max_overlap(&[1..3, 3..5]), 1);
+/// ```
+pub fn max_overlap(intervals: &[Range<i64>]) -> usize {
+    let mut events: Vec<(i64, i32)> = intervals
+        .iter()
+        .filter(|r| !r.is_empty())
+        .flat_map(|r| [(r.start, 1), (r.end, -1)])
+        .collect();
+    // 坐标相同时 -1 排在 +1 前面
+    events.sort_unstable();
+
+    let (mut curr, mut best) = (0, 0);
+    for (_, delta) in events {
+        curr += delta;
+        best = best.max(curr);
+    }
+    best as usize
+}
+
+/// 给每个区间分配一间会议室, 同一间会议室中的区间互不重叠, 使用的会议室数量等于 [`max_overlap`]
+///
+/// 按开始时间处理区间, 小根堆中保存每间会议室最后一个会议的结束时间, 堆顶已经空闲时复用, 否则新开一间
+/// 返回每个区间的会议室编号, 空区间不占用会议室, 编号记为 0
+///
+/// ```
+/// use impx::sweep_line::assign_rooms;
+///
+/// assert_eq!(assign_rooms(&[0..30, 5..10, 15..20]), vec![0, 1, 1]);
+/// ```
+pub fn assign_rooms(intervals: &[Range<i64>]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..intervals.len()).collect();
+    order.sort_by_key(|&i| intervals[i].start);
+
+    let mut rooms = vec![0; intervals.len()];
+    // (结束时间, 会议室编号)
+    let mut heap: BinaryHeap<Reverse<(i64, usize)>> = BinaryHeap::new();
+    let mut count = 0;
+    for i in order {
+        let r = &intervals[i];
+        if r.is_empty() {
+            continue;
+        }
+
+        let room = match heap.peek() {
+            Some(&Reverse((end, room))) if end <= r.start => {
+                heap.pop();
+                room
+            }
+            _ => {
+                count += 1;
+                count - 1
+            }
+        };
+        rooms[i] = room;
+        heap.push(Reverse((r.end, room)));
+    }
+    rooms
+}
+
+/// 区间调度, 选出最多的互不重叠的区间, 返回按结束时间排序的区间下标, O(nlogn)
+///
+/// ```
+/// use impx::sweep_line::max_non_overlapping;
+///
+/// assert_eq!(max_non_overlapping(&[1..4, 3..5, 0..6, 5..7, 3..9, 5..9, 6..10, 8..11]), vec![0, 3, 7]);
+/// ```
+pub fn max_non_overlapping(intervals: &[Range<i64>]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..intervals.len()).collect();
+    order.sort_by_key(|&i| intervals[i].end);
+
+    let mut res = vec![];
+    let mut last_end = i64::MIN;
+    for i in order {
+        let r = &intervals[i];
+        if !r.is_empty() && r.start >= last_end {
+            res.push(i);
+            last_end = r.end;
+        }
+    }
+    res
+}
+
+/// 平面上左闭右开的矩形 `[x1, x2) × [y1, y2)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub x1: i64,
+    pub y1: i64,
+    pub x2: i64,
+    pub y2: i64,
+}
+
+impl Rect {
+    pub fn new(x1: i64, y1: i64, x2: i64, y2: i64) -> Self {
+        Self { x1, y1, x2, y2 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.x1 >= self.x2 || self.y1 >= self.y2
+    }
+
+    pub fn area(&self) -> i128 {
+        if self.is_empty() {
+            return 0;
+        }
+        (self.x2 as i128 - self.x1 as i128) * (self.y2 as i128 - self.y1 as i128)
+    }
+}
+
+/// 矩形面积并, 重叠的部分只计算一次, O(nlogn)
+///
+/// ```
+/// use impx::sweep_line::{union_area, Rect};
+///
+/// let rects = [Rect::new(1, 1, 5, 3), Rect::new(3, 2, 7, 4)];
+/// assert_eq!(union_area(&rects), 8 + 8 - 2);
+/// ```
+pub fn union_area(rects: &[Rect]) -> i128 {
+    let rects: Vec<_> = rects.iter().filter(|r| !r.is_empty()).collect();
+    if rects.is_empty() {
+        return 0;
+    }
+
+    let mut xs: Vec<i64> = rects.iter().flat_map(|r| [r.x1, r.x2]).collect();
+    xs.sort_unstable();
+    xs.dedup();
+    let index = |x: i64| xs.binary_search(&x).unwrap();
+
+    // (y, 是否是上边, 覆盖的第一个基本段, 最后一个基本段)
+    let mut events: Vec<(i64, bool, usize, usize)> = rects
+        .iter()
+        .flat_map(|r| {
+            let (l, h) = (index(r.x1), index(r.x2) - 1);
+            [(r.y1, false, l, h), (r.y2, true, l, h)]
+        })
+        .collect();
+    events.sort_unstable_by_key(|e| e.0);
+
+    let lens: Vec<i128> = xs.windows(2).map(|w| w[1] as i128 - w[0] as i128).collect();
+    let mut tree = CoverSegmentTree::new(&lens);
+    let mut area = 0;
+    let mut prev_y = events[0].0;
+    for (y, top, l, h) in events {
+        area += tree.covered() * (y as i128 - prev_y as i128);
+        if top {
+            tree.uncover(l, h);
+        } else {
+            tree.cover(l, h);
+        }
+        prev_y = y;
+    }
+    area
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::segment_tree::SegmentTree;
+    use rand::Rng;
+
+    fn random_intervals(rng: &mut impl Rng, n: usize, max: i64) -> Vec<Range<i64>> {
+        (0..n)
+            .map(|_| {
+                let (a, b) = (rng.gen_range(0..max), rng.gen_range(0..max));
+                a.min(b)..a.max(b)
+            })
+            .collect()
+    }
+
+    /// 离散化后在线段树上对每个区间覆盖的点区间加一, 再逐个查询点的覆盖次数
+    fn max_overlap_segment_tree(intervals: &[Range<i64>]) -> usize {
+        let mut xs: Vec<i64> = intervals.iter().flat_map(|r| [r.start, r.end]).collect();
+        xs.sort_unstable();
+        xs.dedup();
+        if xs.is_empty() {
+            return 0;
+        }
+
+        let mut tree = SegmentTree::new(&vec![0; xs.len()]);
+        for r in intervals.iter().filter(|r| !r.is_empty()) {
+            let l = xs.binary_search(&r.start).unwrap();
+            let h = xs.binary_search(&r.end).unwrap() - 1;
+            tree.update(l, h, 1);
+        }
+        (0..xs.len())
+            .map(|i| tree.sum(i, i) as usize)
+            .max()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_intervals_brute() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..200 {
+            let n = rng.gen_range(0..20);
+            let intervals = random_intervals(&mut rng, n, 50);
+
+            // 逐个整数点统计覆盖次数
+            let cover: Vec<usize> = (0..50)
+                .map(|x| intervals.iter().filter(|r| r.contains(&x)).count())
+                .collect();
+            let best = cover.iter().copied().max().unwrap();
+            assert_eq!(max_overlap(&intervals), best);
+            assert_eq!(max_overlap_segment_tree(&intervals), best);
+
+            let merged = merge_intervals(&intervals);
+            for w in merged.windows(2) {
+                assert!(w[0].end < w[1].start);
+            }
+            for x in 0..50 {
+                assert_eq!(merged.iter().any(|r| r.contains(&x)), cover[x as usize] > 0);
+            }
+
+            let rooms = assign_rooms(&intervals);
+            let used = (0..n)
+                .filter(|&i| !intervals[i].is_empty())
+                .map(|i| rooms[i] + 1)
+                .max()
+                .unwrap_or(0);
+            assert_eq!(used, best);
+            for i in 0..n {
+                for j in i + 1..n {
+                    let (a, b) = (&intervals[i], &intervals[j]);
+                    if rooms[i] == rooms[j] && !a.is_empty() && !b.is_empty() {
+                        assert!(a.end <= b.start || b.end <= a.start);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_non_overlapping_brute() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..200 {
+            let n = rng.gen_range(0..12);
+            let intervals = random_intervals(&mut rng, n, 30);
+
+            let chosen = max_non_overlapping(&intervals);
+            for w in chosen.windows(2) {
+                assert!(intervals[w[0]].end <= intervals[w[1]].start);
+            }
+
+            // 枚举所有子集
+            let best = (0..1u32 << n)
+                .filter(|&mask| {
+                    let mut set: Vec<_> = (0..n)
+                        .filter(|&i| mask >> i & 1 == 1)
+                        .map(|i| intervals[i].clone())
+                        .collect();
+                    set.sort_by_key(|r| r.start);
+                    set.iter().all(|r| !r.is_empty())
+                        && set.windows(2).all(|w| w[0].end <= w[1].start)
+                })
+                .map(u32::count_ones)
+                .max()
+                .unwrap();
+            assert_eq!(chosen.len(), best as usize);
+        }
+    }
+
+    #[test]
+    fn test_union_area_brute() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..200 {
+            let n = rng.gen_range(0..10);
+            let rects: Vec<Rect> = (0..n)
+                .map(|_| {
+                    let (x1, x2) = (rng.gen_range(-20..20), rng.gen_range(-20..20));
+                    let (y1, y2) = (rng.gen_range(-20..20), rng.gen_range(-20..20));
+                    Rect::new(x1.min(x2), y1.min(y2), x1.max(x2), y1.max(y2))
+                })
+                .collect();
+
+            // 逐个单位格子判断是否被覆盖
+            let mut brute = 0;
+            for x in -20..20 {
+                for y in -20..20 {
+                    if rects
+                        .iter()
+                        .any(|r| r.x1 <= x && x < r.x2 && r.y1 <= y && y < r.y2)
+                    {
+                        brute += 1;
+                    }
+                }
+            }
+            assert_eq!(union_area(&rects), brute);
+        }
+
+        // 不相交时是面积之和, 完全包含时是大矩形的面积
+        let big = 1_000_000_000;
+        let rects = [
+            Rect::new(0, 0, big, big),
+            Rect::new(big, big, 2 * big, 2 * big),
+        ];
+        assert_eq!(union_area(&rects), 2 * big as i128 * big as i128);
+        let rects = [
+            Rect::new(0, 0, 10, 10),
+            Rect::new(2, 2, 5, 5),
+            Rect::new(0, 0, 10, 10),
+        ];
+        assert_eq!(union_area(&rects), rects[0].area());
+        assert_eq!(union_area(&[]), 0);
+
+        // 坐标接近 i64 的边界, 坐标差超过 i64 的范围
+        let (min, max) = (i64::MIN, i64::MAX);
+        let wide = Rect::new(min, 0, max, 2);
+        assert_eq!(wide.area(), (u64::MAX as i128) * 2);
+        let rects = [
+            wide,
+            Rect::new(0, min, 1, max),
+            Rect::new(min, -3, min + 1, -1),
+        ];
+        let expect = wide.area() + u64::MAX as i128 - 2 + 2;
+        assert_eq!(union_area(&rects), expect);
+    }
+}
@@ -0,0 +1,308 @@
+//! Top-K 与数据流中的高频元素
+//!
+//! - [wikipedia - Misra–Gries heavy hitters algorithm](https://en.wikipedia.org/wiki/Misra%E2%80%93Gries_heavy_hitters_algorithm)
+//! - [Efficient Computation of Frequent and Top-k Elements in Data Streams](https://www.cs.ucsb.edu/sites/default/files/documents/2005-23.pdf)
+//!
+//! 从 n 个元素中选出最大的 k 个只需要维护一个大小为 k 的小根堆, 堆顶是目前第 k 大的元素  
+//! 新元素比堆顶大时替换堆顶, 时间复杂度 O(nlogk), 空间复杂度 O(k)
+//!
+//! 如果要找的是数据流中出现次数最多的元素, 精确统计需要保存所有不同的元素, 使用固定的 k 个计数器只能近似:
+//!
+//! - Misra-Gries: 元素已有计数器时加一, 否则有空闲计数器时分配一个, 都没有时所有计数器同时减一并删除归零的计数器
+//!   每次集体减一都相当于丢弃了 k+1 个不同的元素, 所以估计值不大于真实值且最多少 N / (k+1)
+//! - Space-Saving: 没有空闲计数器时把最小的计数器 min 让给新元素并记为 min + 1, 同时记录误差 min
+//!   估计值不小于真实值且最多多出 N / k, 出现次数超过 N / k 的元素一定在计数器中
+//!
+//! 其中 N 是数据流的长度, 这里 Space-Saving 每次用 O(k) 的时间找最小计数器, 论文中的 Stream-Summary 结构可以做到 O(1)
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+/// 保留最大的 k 个元素
+pub struct TopK<T> {
+    k: usize,
+    heap: BinaryHeap<Reverse<T>>,
+}
+
+impl<T: Ord> TopK<T> {
+    pub fn new(k: usize) -> Self {
+        Self {
+            k,
+            heap: BinaryHeap::with_capacity(k + 1),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// 目前保留的元素中最小的一个, 即第 k 大的元素
+    pub fn min(&self) -> Option<&T> {
+        self.heap.peek().map(|Reverse(v)| v)
+    }
+
+    pub fn push(&mut self, v: T) {
+        if self.heap.len() < self.k {
+            self.heap.push(Reverse(v));
+        } else if self.min().is_some_and(|min| v > *min) {
+            self.heap.pop();
+            self.heap.push(Reverse(v));
+        }
+    }
+
+    /// 按从大到小的顺序返回保留的元素
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        // Reverse 的升序就是原始值的降序
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(v)| v)
+            .collect()
+    }
+}
+
+impl<T: Ord> Extend<T> for TopK<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|v| self.push(v));
+    }
+}
+
+/// 从大到小返回迭代器中最大的 k 个元素
+///
+/// ```
+/// use impx::top_k::top_k;
+///
+/// assert_eq!(top_k([3, 1, 4, 1, 5, 9, 2, 6], 3), vec![9, 6, 5]);
+/// assert_eq!(top_k([2, 1], 3), vec![2, 1]);
+/// ```
+pub fn top_k<T: Ord, I: IntoIterator<Item = T>>(iter: I, k: usize) -> Vec<T> {
+    let mut top = TopK::new(k);
+    top.extend(iter);
+    top.into_sorted_vec()
+}
+
+/// Misra-Gries 高频元素统计, 估计值偏小
+pub struct MisraGries<T> {
+    k: usize,
+    n: u64,
+    counters: HashMap<T, u64>,
+}
+
+impl<T: Hash + Eq + Clone> MisraGries<T> {
+    /// 使用 k 个计数器
+    pub fn new(k: usize) -> Self {
+        assert!(k > 0, "k must be positive");
+        Self {
+            k,
+            n: 0,
+            counters: HashMap::with_capacity(k + 1),
+        }
+    }
+
+    /// 已经处理的元素个数
+    pub fn total(&self) -> u64 {
+        self.n
+    }
+
+    pub fn insert(&mut self, item: T) {
+        self.n += 1;
+        if let Some(c) = self.counters.get_mut(&item) {
+            *c += 1;
+        } else if self.counters.len() < self.k {
+            self.counters.insert(item, 1);
+        } else {
+            // 新元素和所有计数器中的元素各抵消一次
+            self.counters.retain(|_, c| {
+                *c -= 1;
+                *c > 0
+            });
+        }
+    }
+
+    /// 估计出现次数, 不大于真实值且最多少 N / (k+1)
+    pub fn estimate(&self, item: &T) -> u64 {
+        self.counters.get(item).copied().unwrap_or(0)
+    }
+
+    /// 按估计值从大到小返回所有候选元素
+    pub fn candidates(&self) -> Vec<(T, u64)> {
+        let mut res: Vec<_> = self.counters.iter().map(|(k, &c)| (k.clone(), c)).collect();
+        res.sort_by_key(|&(_, c)| Reverse(c));
+        res
+    }
+}
+
+/// Space-Saving 计数器
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Counter {
+    /// 估计的出现次数
+    pub count: u64,
+    /// 接手计数器时继承的计数, 真实值不小于 count - error
+    pub error: u64,
+}
+
+/// Space-Saving 高频元素统计, 估计值偏大
+pub struct SpaceSaving<T> {
+    k: usize,
+    n: u64,
+    counters: HashMap<T, Counter>,
+}
+
+impl<T: Hash + Eq + Clone> SpaceSaving<T> {
+    /// 使用 k 个计数器
+    pub fn new(k: usize) -> Self {
+        assert!(k > 0, "k must be positive");
+        Self {
+            k,
+            n: 0,
+            counters: HashMap::with_capacity(k),
+        }
+    }
+
+    /// 已经处理的元素个数
+    pub fn total(&self) -> u64 {
+        self.n
+    }
+
+    pub fn insert(&mut self, item: T) {
+        self.n += 1;
+        if let Some(c) = self.counters.get_mut(&item) {
+            c.count += 1;
+            return;
+        }
+
+        let counter = if self.counters.len() < self.k {
+            Counter { count: 1, error: 0 }
+        } else {
+            // 把最小的计数器让给新元素
+            let (victim, min) = self
+                .counters
+                .iter()
+                .min_by_key(|(_, c)| c.count)
+                .map(|(k, c)| (k.clone(), c.count))
+                .unwrap();
+            self.counters.remove(&victim);
+            Counter {
+                count: min + 1,
+                error: min,
+            }
+        };
+        self.counters.insert(item, counter);
+    }
+
+    /// 估计出现次数, 不在计数器中时返回 0
+    pub fn estimate(&self, item: &T) -> u64 {
+        self.counters.get(item).map_or(0, |c| c.count)
+    }
+
+    /// 元素对应的计数器
+    pub fn counter(&self, item: &T) -> Option<Counter> {
+        self.counters.get(item).copied()
+    }
+
+    /// 按估计值从大到小返回前 n 个元素
+    pub fn top(&self, n: usize) -> Vec<(T, Counter)> {
+        let mut res: Vec<_> = self.counters.iter().map(|(k, &c)| (k.clone(), c)).collect();
+        // 估计值相同时误差小的更可靠
+        res.sort_by_key(|&(_, c)| (Reverse(c.count), c.error));
+        res.truncate(n);
+        res
+    }
+
+    /// 一定是出现次数超过 threshold 的元素, 即 `count - error > threshold`
+    pub fn guaranteed(&self, threshold: u64) -> Vec<T> {
+        self.counters
+            .iter()
+            .filter(|(_, c)| c.count - c.error > threshold)
+            .map(|(k, _)| k.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arc_cache::zipf_trace;
+
+    fn frequencies(trace: &[u64]) -> HashMap<u64, u64> {
+        let mut freq = HashMap::new();
+        for &key in trace {
+            *freq.entry(key).or_insert(0) += 1;
+        }
+        freq
+    }
+
+    #[test]
+    fn test_top_k() {
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        let v: Vec<i32> = (0..1000).map(|_| rng.gen_range(0..500)).collect();
+        let mut sorted = v.clone();
+        sorted.sort_by(|a, b| b.cmp(a));
+
+        for k in [0, 1, 10, 999, 1000, 2000] {
+            assert_eq!(top_k(v.iter().copied(), k), sorted[..k.min(v.len())]);
+        }
+
+        let mut top = TopK::new(2);
+        assert!(top.is_empty());
+        top.extend([5, 1, 7]);
+        assert_eq!(top.min(), Some(&5));
+        assert_eq!(top.len(), 2);
+    }
+
+    #[test]
+    fn test_misra_gries() {
+        let trace = zipf_trace(1000, 1.1, 50000, 1);
+        let freq = frequencies(&trace);
+        let k = 50;
+
+        let mut mg = MisraGries::new(k);
+        trace.iter().for_each(|&key| mg.insert(key));
+        assert_eq!(mg.total(), trace.len() as u64);
+
+        let bound = trace.len() as u64 / (k as u64 + 1);
+        for (key, &f) in &freq {
+            let est = mg.estimate(key);
+            assert!(est <= f && f <= est + bound);
+        }
+
+        // Zipf 分布中出现最多的几个元素就是排名靠前的 key
+        let top: Vec<_> = mg.candidates().iter().take(3).map(|&(k, _)| k).collect();
+        assert_eq!(top, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_space_saving() {
+        let trace = zipf_trace(1000, 1.1, 50000, 2);
+        let freq = frequencies(&trace);
+        let k = 50;
+
+        let mut ss = SpaceSaving::new(k);
+        trace.iter().for_each(|&key| ss.insert(key));
+
+        let bound = trace.len() as u64 / k as u64;
+        for (key, &f) in &freq {
+            if let Some(c) = ss.counter(key) {
+                assert!(c.count >= f && c.count <= f + bound);
+                assert!(c.count - c.error <= f);
+            } else {
+                // 不在计数器中的元素出现次数不超过 N / k
+                assert!(f <= bound);
+            }
+        }
+
+        let top: Vec<_> = ss.top(3).iter().map(|&(k, _)| k).collect();
+        assert_eq!(top, vec![0, 1, 2]);
+        for key in ss.guaranteed(bound) {
+            assert!(freq[&key] > bound);
+        }
+        assert!(ss.guaranteed(bound).contains(&0));
+    }
+}
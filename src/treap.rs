@@ -19,16 +19,24 @@
 //! - 比较新节点及其父节点的 priority 如果新节点的 priority 比父节点小则通过旋转更新父节点
 //!
 //! - 删除时，首先按照正常 BST 的方式找到需要删除的值
-//! - 通过旋转的方式，将待删除的值旋转到叶子节点
-//! - 直接删除叶子节点即可
+//! - 通过旋转的方式，将待删除的值向下旋转, 每次把两个子节点中 priority 较小的一个旋转上来, 这样才能保持堆的性质
+//! - 只剩一个子节点(或者没有子节点)时直接用子节点替换它
 //!
 //! 实际上树堆依赖了 BST 的一个性质即左旋和右旋任意节点后仍会是一棵合法的 BST  
 //! 利用此性质可以很方便的执行堆化(堆化也就是节点上浮和下沉两种操作对应旋转)
+//!
+//! 优先级由随机数生成器 R 产生, 默认使用 `thread_rng`, 需要可复现的树形时用 [`Treap::with_rng`]
+//! 传入固定种子的生成器, 比如 [`crate::rng::Pcg32`]
 
+use rand::rngs::ThreadRng;
 use rand::Rng;
 
+use std::fmt::Debug;
+use std::marker::PhantomData;
 use std::ptr::NonNull;
 
+use crate::visualize::{binary_tree, Dot, ToDot};
+
 /// Treap 节点
 pub struct Node<T> {
     value: T,      // 树上值
@@ -69,41 +77,74 @@ enum Dir {
     Right,
 }
 
-/// Treap 树堆
+/// Treap 树堆, R 是生成优先级的随机数生成器
 #[derive(Default)]
-pub struct Treap<T> {
-    rng: rand::rngs::ThreadRng,
+pub struct Treap<T, R = ThreadRng> {
+    rng: R,
     root: Option<NonNull<Node<T>>>,
+    length: usize,
 }
 
 impl<T: Ord + Eq> Treap<T> {
     /// 创建新的树堆
     pub fn new() -> Self {
+        Self::with_rng(rand::thread_rng())
+    }
+}
+
+impl<T: Ord + Eq, R: Rng> Treap<T, R> {
+    /// 使用指定的随机数生成器创建树堆, 种子相同时相同的操作序列得到相同的树
+    pub fn with_rng(rng: R) -> Self {
         Self {
-            rng: rand::thread_rng(),
+            rng,
             root: None,
+            length: 0,
         }
     }
 
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
     /// 返回树深度(高度)
     pub fn depth(&self) -> usize {
         self.root
             .map_or(0, |root_ptr| unsafe { root_ptr.as_ref() }.depth())
     }
 
+    /// 查找树中是否存在 value
+    pub fn contains(&self, value: &T) -> bool {
+        let mut curr_ptr = self.root;
+        while let Some(node_ptr) = curr_ptr {
+            let node = unsafe { node_ptr.as_ref() };
+            match node.value.cmp(value) {
+                std::cmp::Ordering::Greater => curr_ptr = node.left,
+                std::cmp::Ordering::Less => curr_ptr = node.right,
+                std::cmp::Ordering::Equal => return true,
+            }
+        }
+
+        false
+    }
+
     fn new_node(&mut self, value: T) -> NonNull<Node<T>> {
         let priority = self.rng.gen();
         let new_node = Box::new(Node::new(value, priority));
+        self.length += 1;
         unsafe { NonNull::new_unchecked(Box::into_raw(new_node)) }
     }
 
-    /// Treap 插入值
-    pub fn insert(&mut self, value: T) {
+    /// Treap 插入值, 返回是否是新插入的值, 值已经存在时不做任何修改
+    pub fn insert(&mut self, value: T) -> bool {
         let mut root_ptr = match self.root {
             None => {
                 let new_node_ptr = self.new_node(value);
                 self.root = Some(new_node_ptr);
-                return;
+                return true;
             }
             Some(root_ptr) => root_ptr,
         };
@@ -114,8 +155,8 @@ impl<T: Ord + Eq> Treap<T> {
 
         loop {
             match curr_node.value.cmp(&value) {
-                // 树上已经有重复值
-                std::cmp::Ordering::Equal => return,
+                // 树上已经有重复值, 还没有分配新节点也没有修改任何节点
+                std::cmp::Ordering::Equal => return false,
                 std::cmp::Ordering::Less => match curr_node.right {
                     None => {
                         let new_node_ptr = self.new_node(value);
@@ -154,7 +195,7 @@ impl<T: Ord + Eq> Treap<T> {
                     let child_ptr = parent_node.left.unwrap();
                     let child_node = unsafe { child_ptr.as_ref() };
                     if child_node.priority >= parent_node.priority {
-                        return;
+                        return true;
                     } else {
                         rotate_right(parent_ptr)
                     }
@@ -163,7 +204,7 @@ impl<T: Ord + Eq> Treap<T> {
                     let child_ptr = parent_node.right.unwrap();
                     let child_node = unsafe { child_ptr.as_ref() };
                     if child_node.priority >= parent_node.priority {
-                        return;
+                        return true;
                     } else {
                         rotate_left(parent_ptr)
                     }
@@ -173,7 +214,7 @@ impl<T: Ord + Eq> Treap<T> {
             match parents.pop() {
                 None => {
                     self.root = Some(new_node_ptr);
-                    return;
+                    return true;
                 }
                 Some((mut pp_ptr, pp_dir)) => {
                     let pp_node = unsafe { pp_ptr.as_mut() };
@@ -242,14 +283,33 @@ impl<T: Ord + Eq> Treap<T> {
                             }
 
                             let node = unsafe { Box::from_raw(curr_node) };
+                            self.length -= 1;
                             return Some(node.value);
                         }
 
-                        // 右旋到下一层更新父节点
-                        let new_parent = rotate_right(curr_ptr);
-                        curr_ptr = unsafe { new_parent.as_ref() }.right.unwrap();
+                        // 把 priority 较小的子节点旋转上来, 当前节点下沉一层
+                        // 旋转后的新节点需要重新挂到原先的父节点上
+                        let left = unsafe { curr_node.left.unwrap().as_ref() };
+                        let right = unsafe { curr_node.right.unwrap().as_ref() };
+                        let (new_parent, down) = if left.priority < right.priority {
+                            (rotate_right(curr_ptr), Dir::Right)
+                        } else {
+                            (rotate_left(curr_ptr), Dir::Left)
+                        };
+                        match parent {
+                            None => self.root = Some(new_parent),
+                            Some((mut p_ptr, dir)) => {
+                                let parent_node = unsafe { p_ptr.as_mut() };
+                                match dir {
+                                    Dir::Left => parent_node.left = Some(new_parent),
+                                    Dir::Right => parent_node.right = Some(new_parent),
+                                }
+                            }
+                        }
+
+                        // 当前节点还是 curr_ptr, 只是换了父节点
                         curr_node = unsafe { curr_ptr.as_mut() };
-                        parent = Some((new_parent, Dir::Right));
+                        parent = Some((new_parent, down));
                     }
                 }
             }
@@ -257,6 +317,84 @@ impl<T: Ord + Eq> Treap<T> {
     }
 }
 
+impl<T, R> Treap<T, R> {
+    /// 释放所有节点
+    pub fn clear(&mut self) {
+        let mut stack: Vec<_> = self.root.take().into_iter().collect();
+        while let Some(ptr) = stack.pop() {
+            let node = unsafe { Box::from_raw(ptr.as_ptr()) };
+            stack.extend(node.left);
+            stack.extend(node.right);
+        }
+        self.length = 0;
+    }
+
+    /// 消费树堆, 按从小到大的顺序返回所有值
+    ///
+    /// 中序遍历的同时释放节点, 弹出一个节点时它的左子树已经全部处理完了
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let mut values = Vec::with_capacity(self.length);
+        let mut stack = vec![];
+        let mut link = self.root.take();
+        self.length = 0;
+        loop {
+            while let Some(ptr) = link {
+                stack.push(ptr);
+                link = unsafe { ptr.as_ref() }.left;
+            }
+
+            let Some(ptr) = stack.pop() else {
+                break;
+            };
+            let node = unsafe { Box::from_raw(ptr.as_ptr()) };
+            link = node.right;
+            values.push(node.value);
+        }
+        values
+    }
+
+    /// 中序遍历引用迭代器
+    pub fn iter(&self) -> Iter<'_, T> {
+        let mut iter = Iter {
+            stack: vec![],
+            _marker: PhantomData,
+        };
+        iter.push_left(self.root);
+        iter
+    }
+}
+
+impl<T, R> Drop for Treap<T, R> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+/// 中序遍历引用迭代器
+pub struct Iter<'a, T> {
+    stack: Vec<NonNull<Node<T>>>,
+    _marker: PhantomData<&'a Node<T>>,
+}
+
+impl<T> Iter<'_, T> {
+    fn push_left(&mut self, mut link: Option<NonNull<Node<T>>>) {
+        while let Some(ptr) = link {
+            self.stack.push(ptr);
+            link = unsafe { ptr.as_ref() }.left;
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = unsafe { self.stack.pop()?.as_ref() };
+        self.push_left(node.right);
+        Some(&node.value)
+    }
+}
+
 /// 节点左旋
 pub fn rotate_left<T>(mut old_root_ptr: NonNull<Node<T>>) -> NonNull<Node<T>> {
     let old_root = unsafe { old_root_ptr.as_mut() };
@@ -287,6 +425,28 @@ pub fn rotate_right<T>(mut old_root_ptr: NonNull<Node<T>>) -> NonNull<Node<T>> {
     new_root_ptr
 }
 
+impl<T: Debug, R> ToDot for Treap<T, R> {
+    /// 节点上同时显示值和优先级
+    fn to_dot(&self) -> String {
+        let mut dot = Dot::digraph("Treap");
+        if let Some(root) = self.root {
+            binary_tree(
+                &mut dot,
+                root,
+                &|node: NonNull<Node<T>>| {
+                    let node = unsafe { node.as_ref() };
+                    (node.left, node.right)
+                },
+                &|dot: &mut Dot, node: NonNull<Node<T>>| {
+                    let node = unsafe { node.as_ref() };
+                    dot.node(&format!("{:?}\n{}", node.value, node.priority), &[])
+                },
+            );
+        }
+        dot.finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -320,4 +480,126 @@ mod tests {
 
         assert_eq!(t.depth(), 0);
     }
+
+    #[test]
+    fn test_treap_iter() {
+        let mut t = Treap::new();
+        for i in [5, 1, 4, 1, 3, 9, 2, 6] {
+            t.insert(i);
+        }
+        assert_eq!(t.len(), 7);
+        assert_eq!(t.iter().copied().collect::<Vec<_>>(), [1, 2, 3, 4, 5, 6, 9]);
+
+        t.delete(&4);
+        t.delete(&4);
+        assert_eq!(t.len(), 6);
+
+        t.clear();
+        assert!(t.is_empty());
+        assert_eq!(t.iter().next(), None);
+    }
+
+    /// 检查二叉搜索树和小根堆的性质, 返回节点数
+    fn check<T: Ord, R: Rng>(t: &Treap<T, R>) -> usize {
+        fn visit<T: Ord>(ptr: Option<NonNull<Node<T>>>, lo: Option<&T>, hi: Option<&T>) -> usize {
+            let Some(ptr) = ptr else {
+                return 0;
+            };
+            let node = unsafe { ptr.as_ref() };
+            assert!(lo.is_none_or(|lo| lo < &node.value), "bst order");
+            assert!(hi.is_none_or(|hi| &node.value < hi), "bst order");
+            for child in [node.left, node.right].into_iter().flatten() {
+                assert!(
+                    unsafe { child.as_ref() }.priority >= node.priority,
+                    "heap order"
+                );
+            }
+
+            1 + visit(node.left, lo, Some(&node.value)) + visit(node.right, Some(&node.value), hi)
+        }
+
+        let count = visit(t.root, None, None);
+        assert_eq!(count, t.len());
+        count
+    }
+
+    #[test]
+    fn test_treap_insert_duplicate() {
+        let mut t = Treap::new();
+        assert!(t.insert(1));
+        assert!(t.insert(2));
+        assert!(!t.insert(1));
+        assert!(!t.insert(2));
+        assert_eq!(t.len(), 2);
+        check(&t);
+    }
+
+    #[test]
+    fn test_treap_stress() {
+        use crate::rng::Pcg32;
+        use rand::{Rng, SeedableRng};
+        use std::collections::BTreeSet;
+
+        // 操作序列和树的结构都由固定的种子决定, 出错时可以复现
+        let mut rng = Pcg32::seed_from_u64(0x5eed);
+        let mut t = Treap::with_rng(Pcg32::seed_from_u64(1));
+        let mut model = BTreeSet::new();
+        for _ in 0..20000 {
+            let v = rng.gen_range(0..500);
+            if rng.gen_bool(0.5) {
+                assert_eq!(t.insert(v), model.insert(v));
+            } else {
+                assert_eq!(t.delete(&v), model.take(&v));
+            }
+        }
+        check(&t);
+        assert!(t.iter().eq(model.iter()));
+
+        // 删除所有节点, 每次删除后检查一次
+        let mut values: Vec<_> = model.into_iter().collect();
+        while !values.is_empty() {
+            let v = values.swap_remove(rng.gen_range(0..values.len()));
+            assert_eq!(t.delete(&v), Some(v));
+            check(&t);
+        }
+        assert!(t.is_empty());
+        assert_eq!(t.depth(), 0);
+    }
+
+    #[test]
+    fn test_treap_with_rng() {
+        use crate::rng::{Pcg32, XorShift64Star};
+        use rand::SeedableRng;
+
+        // 相同的种子得到完全相同的优先级和树形
+        let build = |seed| {
+            let mut t = Treap::with_rng(Pcg32::seed_from_u64(seed));
+            for i in 0..100 {
+                t.insert(i);
+            }
+            t.delete(&50);
+            t
+        };
+        assert_eq!(build(7).to_dot(), build(7).to_dot());
+        assert_ne!(build(7).to_dot(), build(8).to_dot());
+
+        let mut t = Treap::with_rng(XorShift64Star::seed_from_u64(7));
+        for i in 0..1000 {
+            t.insert(i);
+        }
+        check(&t);
+        // 期望深度约为 2ln(n) ≈ 14, 顺序插入也不会退化成链表
+        assert!(t.depth() < 50);
+    }
+
+    #[test]
+    fn test_treap_into_sorted_vec() {
+        assert!(Treap::<i32>::new().into_sorted_vec().is_empty());
+
+        let mut t = Treap::new();
+        for i in [5, 1, 4, 1, 3, 9, 2, 6] {
+            t.insert(i.to_string());
+        }
+        assert_eq!(t.into_sorted_vec(), ["1", "2", "3", "4", "5", "6", "9"]);
+    }
 }
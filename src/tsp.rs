@@ -0,0 +1,338 @@
+//! 旅行商问题
+//!
+//! - [OI Wiki - 状压 DP](https://oi-wiki.org/dp/state/)
+//! - [wikipedia - Held–Karp algorithm](https://en.wikipedia.org/wiki/Held%E2%80%93Karp_algorithm)
+//! - [wikipedia - 2-opt](https://en.wikipedia.org/wiki/2-opt)
+//!
+//! 给出 n 个城市两两之间的距离 `dist[u][v]`, 求从城市 0 出发经过每个城市恰好一次再回到城市 0 的最短回路
+//!
+//! Held-Karp 状压 DP: 用一个整数的二进制位表示访问过的城市集合,
+//! 状态 `dp[S][j]` 表示从 0 出发, 恰好经过集合 S 中的城市(不含 0)并且停在 j ∈ S 的最短路径长度
+//!
+//! ```text
+//! dp[{j}][j] = dist[0][j]
+//! dp[S][j]   = min(dp[S - {j}][k] + dist[k][j]) k ∈ S - {j}
+//! 答案       = min(dp[全集][j] + dist[j][0])
+//! ```
+//!
+//! 城市 0 固定为起点不需要放进集合, 一共 2^(n-1)·(n-1) 个状态, 每个状态 O(n) 转移, 复杂度 O(n²·2ⁿ),
+//! n = 20 时大约需要几亿次运算和上百 MB 内存, 所以限制 n <= 20
+//!
+//! 更大的规模只能使用启发式算法:
+//!
+//! - 最近邻: 每次走到离当前城市最近的没有访问过的城市, O(n²)
+//! - 2-opt: 对称距离下, 如果回路中的两条边 (a, b) (c, d) 满足 `dist[a][c] + dist[b][d] < dist[a][b] + dist[c][d]`,
+//!   就把 b..=c 这一段翻转, 两条边替换成 (a, c) (b, d), 回路变短; 反复执行直到找不到可以改进的边对(局部最优)
+
+/// Held-Karp 算法支持的最大城市数
+pub const HELD_KARP_MAX: usize = 20;
+
+/// 一条回路, 从城市 0 出发按 order 的顺序访问, 最后回到城市 0
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tour {
+    /// 回路总长度
+    pub cost: u64,
+    /// 所有城市的一个排列, 第一个是 0
+    pub order: Vec<usize>,
+}
+
+/// 按 order 的顺序访问并回到起点的总长度
+pub fn tour_cost(dist: &[Vec<u64>], order: &[usize]) -> u64 {
+    if order.is_empty() {
+        return 0;
+    }
+
+    let n = order.len();
+    (0..n).map(|i| dist[order[i]][order[(i + 1) % n]]).sum()
+}
+
+/// Held-Karp 算法求最短回路, 距离可以不对称, O(n²·2ⁿ)
+///
+/// ```
+/// use impx::tsp::held_karp;
+///
+/// let dist = vec![
+///     vec![0, 10, 15, 20],
+///     vec![10, 0, 35, 25],
+///     vec![15, 35, 0, 30],
+///     vec![20, 25, 30, 0],
+/// ];
+/// let tour = held_karp(&dist);
+/// assert_eq!(tour.cost, 80);
+/// assert_eq!(tour.order, vec![0, 2, 3, 1]);
+/// ```
+pub fn held_karp(dist: &[Vec<u64>]) -> Tour {
+    let n = dist.len();
+    assert!(n <= HELD_KARP_MAX, "held_karp supports at most 20 cities");
+    if n <= 1 {
+        return Tour {
+            cost: 0,
+            order: (0..n).collect(),
+        };
+    }
+
+    // 城市 1..n 对应二进制的第 0..m 位
+    let m = n - 1;
+    let full = (1usize << m) - 1;
+    let idx = |mask: usize, j: usize| mask * m + j;
+    let mut dp = vec![u64::MAX; (full + 1) * m];
+    // 到达 dp[S][j] 之前所在的城市, 用来还原路径
+    let mut prev = vec![0u8; (full + 1) * m];
+
+    for j in 0..m {
+        dp[idx(1 << j, j)] = dist[0][j + 1];
+    }
+
+    // S + {k} 的二进制一定比 S 大, 所以按数值从小到大遍历集合时 dp[S] 已经全部算完
+    for mask in 1..=full {
+        for j in 0..m {
+            let d = dp[idx(mask, j)];
+            if mask >> j & 1 == 0 || d == u64::MAX {
+                continue;
+            }
+
+            // 用 dp[S][j] 向外更新 dp[S + {k}][k]
+            for k in 0..m {
+                if mask >> k & 1 == 1 {
+                    continue;
+                }
+
+                let next = idx(mask | 1 << k, k);
+                let cost = d + dist[j + 1][k + 1];
+                if cost < dp[next] {
+                    dp[next] = cost;
+                    prev[next] = j as u8;
+                }
+            }
+        }
+    }
+
+    let (cost, mut last) = (0..m)
+        .map(|j| (dp[idx(full, j)] + dist[j + 1][0], j))
+        .min()
+        .expect("at least one city besides 0");
+
+    // 从终点沿着 prev 往回走
+    let mut order = vec![];
+    let mut mask = full;
+    while mask != 0 {
+        order.push(last + 1);
+        let p = prev[idx(mask, last)] as usize;
+        mask ^= 1 << last;
+        last = p;
+    }
+    order.push(0);
+    order.reverse();
+
+    Tour { cost, order }
+}
+
+/// 最近邻启发式, 从 start 出发每次走到最近的未访问城市, 返回的回路从城市 0 开始
+pub fn nearest_neighbor(dist: &[Vec<u64>], start: usize) -> Tour {
+    let n = dist.len();
+    if n == 0 {
+        return Tour {
+            cost: 0,
+            order: vec![],
+        };
+    }
+
+    let mut visited = vec![false; n];
+    let mut order = Vec::with_capacity(n);
+    let mut curr = start;
+    visited[curr] = true;
+    order.push(curr);
+    for _ in 1..n {
+        curr = (0..n)
+            .filter(|&v| !visited[v])
+            .min_by_key(|&v| dist[curr][v])
+            .unwrap();
+        visited[curr] = true;
+        order.push(curr);
+    }
+
+    // 回路的起点不影响总长度, 旋转到城市 0 开头
+    let zero = order.iter().position(|&u| u == 0).unwrap();
+    order.rotate_left(zero);
+    Tour {
+        cost: tour_cost(dist, &order),
+        order,
+    }
+}
+
+/// 2-opt 局部搜索, 要求距离对称, 返回的回路不会比输入更长
+pub fn two_opt(dist: &[Vec<u64>], mut tour: Tour) -> Tour {
+    let order = &mut tour.order;
+    let n = order.len();
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for i in 0..n.saturating_sub(2) {
+            for j in i + 2..n {
+                // 两条边相邻时翻转不会改变回路
+                if i == 0 && j == n - 1 {
+                    continue;
+                }
+
+                let (a, b) = (order[i], order[i + 1]);
+                let (c, d) = (order[j], order[(j + 1) % n]);
+                if dist[a][c] + dist[b][d] < dist[a][b] + dist[c][d] {
+                    order[i + 1..=j].reverse();
+                    improved = true;
+                }
+            }
+        }
+    }
+
+    tour.cost = tour_cost(dist, &tour.order);
+    tour
+}
+
+/// 最近邻得到初始回路之后用 2-opt 改进, 适用于对称距离下 n 较大的情况
+///
+/// ```
+/// use impx::tsp::approximate;
+///
+/// // 一条直线上的 4 个点, 最短回路是从一端走到另一端再回来
+/// let x = [0i64, 3, 1, 2];
+/// let dist: Vec<Vec<u64>> = x
+///     .iter()
+///     .map(|a| x.iter().map(|b| a.abs_diff(*b)).collect())
+///     .collect();
+/// assert_eq!(approximate(&dist).cost, 6);
+/// ```
+pub fn approximate(dist: &[Vec<u64>]) -> Tour {
+    two_opt(dist, nearest_neighbor(dist, 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    /// 平面上整数坐标的点, 欧几里得距离乘以 100 后取整
+    fn euclidean(points: &[(i64, i64)]) -> Vec<Vec<u64>> {
+        points
+            .iter()
+            .map(|&(x1, y1)| {
+                points
+                    .iter()
+                    .map(|&(x2, y2)| {
+                        let (dx, dy) = ((x1 - x2) as f64, (y1 - y2) as f64);
+                        ((dx * dx + dy * dy).sqrt() * 100.0).round() as u64
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn random_points(rng: &mut impl Rng, n: usize) -> Vec<(i64, i64)> {
+        (0..n)
+            .map(|_| (rng.gen_range(0..100), rng.gen_range(0..100)))
+            .collect()
+    }
+
+    /// 固定起点 0, 枚举其余城市的所有排列
+    fn brute_force(dist: &[Vec<u64>]) -> u64 {
+        fn search(dist: &[Vec<u64>], order: &mut Vec<usize>, used: &mut [bool]) -> u64 {
+            if order.len() == dist.len() {
+                return tour_cost(dist, order);
+            }
+
+            let mut best = u64::MAX;
+            for v in 1..dist.len() {
+                if !used[v] {
+                    used[v] = true;
+                    order.push(v);
+                    best = best.min(search(dist, order, used));
+                    order.pop();
+                    used[v] = false;
+                }
+            }
+            best
+        }
+
+        let mut used = vec![false; dist.len()];
+        used[0] = true;
+        search(dist, &mut vec![0], &mut used)
+    }
+
+    fn assert_valid(dist: &[Vec<u64>], tour: &Tour) {
+        let mut sorted = tour.order.clone();
+        sorted.sort_unstable();
+        assert!(sorted.into_iter().eq(0..dist.len()));
+        assert_eq!(tour.order.first().copied(), (!dist.is_empty()).then_some(0));
+        assert_eq!(tour.cost, tour_cost(dist, &tour.order));
+    }
+
+    #[test]
+    fn test_held_karp_brute() {
+        let mut rng = rand::thread_rng();
+        for n in 0..=8 {
+            for _ in 0..10 {
+                // 不对称的随机距离
+                let dist: Vec<Vec<u64>> = (0..n)
+                    .map(|u| {
+                        (0..n)
+                            .map(|v| if u == v { 0 } else { rng.gen_range(1..100) })
+                            .collect()
+                    })
+                    .collect();
+                let tour = held_karp(&dist);
+                assert_valid(&dist, &tour);
+                if n > 0 {
+                    assert_eq!(tour.cost, brute_force(&dist));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_known_optimum() {
+        // 4x4 的网格, 存在一条只走相邻格点的哈密顿回路, 最优解为 16 条长度 100 的边
+        let grid: Vec<_> = (0..16).map(|i| (i / 4, i % 4)).collect();
+        let dist = euclidean(&grid);
+        let tour = held_karp(&dist);
+        assert_valid(&dist, &tour);
+        assert_eq!(tour.cost, 1600);
+
+        // 圆上的点, 最优解是按角度顺序访问
+        let circle: Vec<_> = [0, 5, 2, 7, 1, 4, 6, 3]
+            .iter()
+            .map(|&k| {
+                let t = std::f64::consts::TAU * k as f64 / 8.0;
+                (
+                    (t.cos() * 50.0).round() as i64,
+                    (t.sin() * 50.0).round() as i64,
+                )
+            })
+            .collect();
+        let dist = euclidean(&circle);
+        let tour = held_karp(&dist);
+        assert_eq!(tour.order, vec![0, 4, 2, 7, 5, 1, 6, 3]);
+        assert_eq!(approximate(&dist).cost, tour.cost);
+    }
+
+    #[test]
+    fn test_heuristic() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..20 {
+            let n = rng.gen_range(1..=10);
+            let dist = euclidean(&random_points(&mut rng, n));
+            let best = held_karp(&dist).cost;
+
+            let nn = nearest_neighbor(&dist, rng.gen_range(0..n));
+            assert_valid(&dist, &nn);
+            let opt = two_opt(&dist, nn.clone());
+            assert_valid(&dist, &opt);
+            assert!(best <= opt.cost && opt.cost <= nn.cost);
+        }
+
+        // 规模较大时只检查回路合法且 2-opt 不会变差
+        let dist = euclidean(&random_points(&mut rng, 200));
+        let nn = nearest_neighbor(&dist, 0);
+        let opt = approximate(&dist);
+        assert_valid(&dist, &opt);
+        assert!(opt.cost <= nn.cost);
+    }
+}
@@ -0,0 +1,194 @@
+//! 2-SAT
+//!
+//! - [OI Wiki - 2-SAT](https://oi-wiki.org/graph/2-sat/)
+//! - [wikipedia - 2-satisfiability](https://en.wikipedia.org/wiki/2-satisfiability)
+//!
+//! 2-SAT 问题: 有 n 个布尔变量, 给出若干个形如 `a ∨ b` 的子句(a, b 是变量或者变量的否定), 求一组让所有子句都成立的赋值
+//!
+//! 子句 `a ∨ b` 等价于两个蕴含式 `¬a → b` 和 `¬b → a`, 把每个文字(x 和 ¬x)作为节点、蕴含式作为有向边就得到了蕴含图  
+//! 如果 x 和 ¬x 在同一个强连通分量中, 说明 x 和 ¬x 必须同时成立, 无解  
+//! 否则在缩点后的 DAG 中, 取拓扑序靠后的那个文字为真就一定不会推出矛盾  
+//! [`strongly_connected_components`] 给出的分量编号是逆拓扑序, 所以编号小的拓扑序靠后, `comp[x] < comp[¬x]` 时 x 为真
+
+use std::ops::Not;
+
+use crate::graph_connectivity::strongly_connected_components;
+use crate::graph_traversal::Graph;
+
+/// 文字: 变量或者变量的否定
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Lit(usize);
+
+impl Lit {
+    /// 变量 x
+    pub fn pos(x: usize) -> Self {
+        Lit(x << 1)
+    }
+
+    /// 变量 x 的否定
+    pub fn neg(x: usize) -> Self {
+        Lit(x << 1 | 1)
+    }
+
+    /// 对应的变量
+    pub fn var(self) -> usize {
+        self.0 >> 1
+    }
+
+    /// 在蕴含图中的节点编号
+    fn node(self) -> usize {
+        self.0
+    }
+}
+
+impl Not for Lit {
+    type Output = Lit;
+
+    fn not(self) -> Lit {
+        Lit(self.0 ^ 1)
+    }
+}
+
+/// 2-SAT 求解器
+///
+/// ```
+/// use impx::two_sat::{Lit, TwoSat};
+///
+/// let (x, y) = (Lit::pos(0), Lit::pos(1));
+/// let mut sat = TwoSat::new(2);
+/// sat.add_or(x, !y);
+/// sat.add_or(!x, !y);
+/// sat.add_or(x, y);
+/// assert_eq!(sat.solve(), Some(vec![true, false]));
+///
+/// sat.add_true(y);
+/// assert_eq!(sat.solve(), None);
+/// ```
+pub struct TwoSat {
+    n: usize,
+    g: Graph,
+}
+
+impl TwoSat {
+    /// n 个变量
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            g: Graph::new(n * 2),
+        }
+    }
+
+    /// 变量个数
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// 子句 a ∨ b
+    pub fn add_or(&mut self, a: Lit, b: Lit) {
+        assert!(
+            a.var() < self.n && b.var() < self.n,
+            "variable out of range"
+        );
+        self.g.add_edge((!a).node(), b.node());
+        self.g.add_edge((!b).node(), a.node());
+    }
+
+    /// a → b, 即 ¬a ∨ b
+    pub fn add_implies(&mut self, a: Lit, b: Lit) {
+        self.add_or(!a, b);
+    }
+
+    /// a 必须成立
+    pub fn add_true(&mut self, a: Lit) {
+        self.add_or(a, a);
+    }
+
+    /// a 和 b 不能同时成立
+    pub fn add_nand(&mut self, a: Lit, b: Lit) {
+        self.add_or(!a, !b);
+    }
+
+    /// a 和 b 恰好有一个成立
+    pub fn add_xor(&mut self, a: Lit, b: Lit) {
+        self.add_or(a, b);
+        self.add_nand(a, b);
+    }
+
+    /// 求一组满足所有子句的赋值, 无解时返回 None
+    pub fn solve(&self) -> Option<Vec<bool>> {
+        let (_, comp) = strongly_connected_components(&self.g);
+        (0..self.n)
+            .map(|x| {
+                let (p, q) = (comp[Lit::pos(x).node()], comp[Lit::neg(x).node()]);
+                (p != q).then_some(p < q)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_two_sat_random() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..300 {
+            let n = rng.gen_range(1..8);
+            let mut sat = TwoSat::new(n);
+            let mut clauses = vec![];
+            for _ in 0..rng.gen_range(0..3 * n) {
+                let lit = |rng: &mut rand::rngs::ThreadRng| {
+                    let x = rng.gen_range(0..n);
+                    if rng.gen_bool(0.5) {
+                        Lit::pos(x)
+                    } else {
+                        Lit::neg(x)
+                    }
+                };
+                let (a, b) = (lit(&mut rng), lit(&mut rng));
+                sat.add_or(a, b);
+                clauses.push((a, b));
+            }
+
+            let eval = |assign: &[bool], l: Lit| assign[l.var()] == (l == Lit::pos(l.var()));
+            let satisfies = |assign: &[bool]| {
+                clauses
+                    .iter()
+                    .all(|&(a, b)| eval(assign, a) || eval(assign, b))
+            };
+            let brute = (0..1u32 << n)
+                .any(|mask| satisfies(&(0..n).map(|i| mask >> i & 1 == 1).collect::<Vec<_>>()));
+
+            match sat.solve() {
+                Some(assign) => assert!(satisfies(&assign)),
+                None => assert!(!brute),
+            }
+        }
+    }
+
+    #[test]
+    fn test_two_sat_helpers() {
+        // 三个变量两两异或不可能同时成立
+        let mut sat = TwoSat::new(3);
+        let [a, b, c] = [0, 1, 2].map(Lit::pos);
+        sat.add_xor(a, b);
+        sat.add_xor(b, c);
+        assert!(sat.solve().is_some());
+        sat.add_xor(a, c);
+        assert_eq!(sat.solve(), None);
+
+        let mut sat = TwoSat::new(2);
+        sat.add_implies(a, b);
+        sat.add_true(a);
+        assert_eq!(sat.solve(), Some(vec![true, true]));
+        sat.add_nand(a, b);
+        assert_eq!(sat.solve(), None);
+        assert_eq!(sat.len(), 2);
+    }
+}
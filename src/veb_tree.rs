@@ -0,0 +1,476 @@
+//! van Emde Boas 树
+//!
+//! - [Wikipedia - Van Emde Boas tree](https://en.wikipedia.org/wiki/Van_Emde_Boas_tree)
+//! - 算法导论第 20 章
+//!
+//! 用于 u32 整数集合, 插入、删除、前驱、后继都是 O(loglogU), U = 2^32
+//!
+//! 把 k 位的整数 x 拆成高 `k - k/2` 位 `high(x)` 和低 `k/2` 位 `low(x)`,
+//! 每个高位对应一个管理低位的子树(cluster), 另外用一棵摘要树(summary)记录哪些 cluster 非空
+//! 每个节点单独保存最小值和最大值, 并且最小值不放进 cluster:
+//!
+//! - 查询后继时先看 x 所在 cluster 的最大值, 如果比 low(x) 大就只在这个 cluster 中递归,
+//!   否则在 summary 中找下一个非空 cluster 并直接取它的最小值, 每层只递归一次
+//! - 插入时如果 cluster 为空则新建它并在 summary 中插入高位, 新建的 cluster 只需要设置最小值 O(1),
+//!   否则只在 cluster 中递归, 同样每层只递归一次
+//!
+//! 于是每层位数减半, 一共 loglogU 层
+//! 完整的 cluster 数组需要 O(U) 空间, 这里用哈希表只保存非空的 cluster, 空间为 O(n loglogU)
+//! 位数不超过 6 时直接用一个 u64 作为叶子节点  
+//! 哈希表的 key 只是一个整数, 用乘法哈希代替默认的 SipHash
+
+use std::collections::HashMap;
+use std::hash::{BuildHasherDefault, Hasher};
+
+/// 整数 key 的乘法哈希(Fibonacci hashing)
+#[derive(Default)]
+struct IntHasher(u64);
+
+impl Hasher for IntHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.write_u64(self.0 << 8 | b as u64);
+        }
+    }
+
+    fn write_u32(&mut self, n: u32) {
+        self.write_u64(n as u64);
+    }
+
+    fn write_u64(&mut self, n: u64) {
+        self.0 = n.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    }
+}
+
+type Clusters = HashMap<u32, Node, BuildHasherDefault<IntHasher>>;
+
+/// 叶子节点的最大位数
+const LEAF_BITS: u32 = 6;
+
+/// 非空的子树
+enum Node {
+    /// 位数不超过 6, 每一位表示一个元素
+    Leaf(u64),
+    Inner(Box<Inner>),
+}
+
+struct Inner {
+    bits: u32,             // 管理的整数位数
+    min: u32,              // 最小值, 不放在 cluster 中
+    max: u32,              // 最大值
+    summary: Option<Node>, // 非空 cluster 的高位集合
+    clusters: Clusters,    // 高位到 cluster 的映射
+}
+
+/// 删除的结果
+#[derive(PartialEq, Eq)]
+enum Removal {
+    NotFound,
+    Removed,
+    /// 删除后子树为空, 需要由父节点移除
+    Emptied,
+}
+
+impl Inner {
+    fn low_bits(&self) -> u32 {
+        self.bits / 2
+    }
+
+    fn high_bits(&self) -> u32 {
+        self.bits - self.bits / 2
+    }
+
+    fn split(&self, x: u32) -> (u32, u32) {
+        let lo = self.low_bits();
+        (x >> lo, x & ((1 << lo) - 1))
+    }
+
+    fn join(&self, high: u32, low: u32) -> u32 {
+        (high << self.low_bits()) | low
+    }
+}
+
+impl Node {
+    /// 只包含 x 的子树
+    fn new(bits: u32, x: u32) -> Self {
+        if bits <= LEAF_BITS {
+            Node::Leaf(1 << x)
+        } else {
+            Node::Inner(Box::new(Inner {
+                bits,
+                min: x,
+                max: x,
+                summary: None,
+                clusters: Clusters::default(),
+            }))
+        }
+    }
+
+    fn min(&self) -> u32 {
+        match self {
+            Node::Leaf(mask) => mask.trailing_zeros(),
+            Node::Inner(node) => node.min,
+        }
+    }
+
+    fn max(&self) -> u32 {
+        match self {
+            Node::Leaf(mask) => 63 - mask.leading_zeros(),
+            Node::Inner(node) => node.max,
+        }
+    }
+
+    fn contains(&self, x: u32) -> bool {
+        match self {
+            Node::Leaf(mask) => mask & (1 << x) != 0,
+            Node::Inner(node) => {
+                if x == node.min || x == node.max {
+                    return true;
+                }
+
+                let (h, l) = node.split(x);
+                node.clusters.get(&h).is_some_and(|c| c.contains(l))
+            }
+        }
+    }
+
+    /// 插入 x, 返回 x 之前是否不存在
+    fn insert(&mut self, mut x: u32) -> bool {
+        let node = match self {
+            Node::Leaf(mask) => {
+                let absent = *mask & (1 << x) == 0;
+                *mask |= 1 << x;
+                return absent;
+            }
+            Node::Inner(node) => node,
+        };
+
+        // 比最小值小时和最小值交换, 把原来的最小值放进 cluster
+        if x < node.min {
+            std::mem::swap(&mut x, &mut node.min);
+        }
+        if x == node.min {
+            return false;
+        }
+
+        let (h, l) = node.split(x);
+        let inserted = match node.clusters.get_mut(&h) {
+            Some(cluster) => cluster.insert(l),
+            None => {
+                // cluster 为空时新建只需要 O(1), 递归只发生在 summary 上
+                node.clusters.insert(h, Node::new(node.low_bits(), l));
+                match &mut node.summary {
+                    Some(summary) => {
+                        summary.insert(h);
+                    }
+                    None => node.summary = Some(Node::new(node.high_bits(), h)),
+                }
+                true
+            }
+        };
+
+        node.max = node.max.max(x);
+        inserted
+    }
+
+    /// 删除 x
+    fn delete(&mut self, mut x: u32) -> Removal {
+        let node = match self {
+            Node::Leaf(mask) => {
+                if *mask & (1 << x) == 0 {
+                    return Removal::NotFound;
+                }
+
+                *mask &= !(1 << x);
+                return match mask {
+                    0 => Removal::Emptied,
+                    _ => Removal::Removed,
+                };
+            }
+            Node::Inner(node) => node,
+        };
+
+        if node.min == node.max {
+            return match x == node.min {
+                true => Removal::Emptied,
+                false => Removal::NotFound,
+            };
+        }
+
+        // 删除最小值时把 cluster 中最小的元素提上来作为新的最小值, 然后从 cluster 中删除它
+        if x == node.min {
+            let h = node.summary.as_ref().unwrap().min();
+            x = node.join(h, node.clusters[&h].min());
+            node.min = x;
+        }
+
+        let (h, l) = node.split(x);
+        let Some(cluster) = node.clusters.get_mut(&h) else {
+            return Removal::NotFound;
+        };
+        match cluster.delete(l) {
+            Removal::NotFound => return Removal::NotFound,
+            Removal::Removed => {}
+            Removal::Emptied => {
+                node.clusters.remove(&h);
+                if node.summary.as_mut().unwrap().delete(h) == Removal::Emptied {
+                    node.summary = None;
+                }
+            }
+        }
+
+        if x == node.max {
+            node.max = match &node.summary {
+                None => node.min,
+                Some(summary) => {
+                    let h = summary.max();
+                    node.join(h, node.clusters[&h].max())
+                }
+            };
+        }
+
+        Removal::Removed
+    }
+
+    /// 大于 x 的最小元素
+    fn successor(&self, x: u32) -> Option<u32> {
+        let node = match self {
+            Node::Leaf(mask) => {
+                let rest = match x {
+                    63.. => 0,
+                    _ => mask >> (x + 1) << (x + 1),
+                };
+                return (rest != 0).then(|| rest.trailing_zeros());
+            }
+            Node::Inner(node) => node,
+        };
+
+        if x < node.min {
+            return Some(node.min);
+        }
+
+        let (h, l) = node.split(x);
+        if let Some(cluster) = node.clusters.get(&h) {
+            if l < cluster.max() {
+                return Some(node.join(h, cluster.successor(l).unwrap()));
+            }
+        }
+
+        let h = node.summary.as_ref()?.successor(h)?;
+        Some(node.join(h, node.clusters[&h].min()))
+    }
+
+    /// 小于 x 的最大元素
+    fn predecessor(&self, x: u32) -> Option<u32> {
+        let node = match self {
+            Node::Leaf(mask) => {
+                let rest = mask & ((1 << x) - 1);
+                return (rest != 0).then(|| 63 - rest.leading_zeros());
+            }
+            Node::Inner(node) => node,
+        };
+
+        if x > node.max {
+            return Some(node.max);
+        }
+
+        let (h, l) = node.split(x);
+        if let Some(cluster) = node.clusters.get(&h) {
+            if l > cluster.min() {
+                return Some(node.join(h, cluster.predecessor(l).unwrap()));
+            }
+        }
+
+        // 最小值不在 cluster 中, 找不到前一个 cluster 时还要检查最小值
+        match node.summary.as_ref().and_then(|s| s.predecessor(h)) {
+            Some(h) => Some(node.join(h, node.clusters[&h].max())),
+            None => (x > node.min).then_some(node.min),
+        }
+    }
+}
+
+/// u32 整数集合上的 van Emde Boas 树
+pub struct VebTree {
+    root: Option<Node>,
+    len: usize,
+}
+
+impl VebTree {
+    pub fn new() -> Self {
+        Self { root: None, len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn min(&self) -> Option<u32> {
+        self.root.as_ref().map(Node::min)
+    }
+
+    pub fn max(&self) -> Option<u32> {
+        self.root.as_ref().map(Node::max)
+    }
+
+    pub fn contains(&self, x: u32) -> bool {
+        self.root.as_ref().is_some_and(|root| root.contains(x))
+    }
+
+    /// 插入 x, 返回 x 之前是否不存在
+    pub fn insert(&mut self, x: u32) -> bool {
+        let inserted = match &mut self.root {
+            Some(root) => root.insert(x),
+            None => {
+                self.root = Some(Node::new(u32::BITS, x));
+                true
+            }
+        };
+
+        self.len += inserted as usize;
+        inserted
+    }
+
+    /// 删除 x, 返回 x 是否存在
+    pub fn delete(&mut self, x: u32) -> bool {
+        let Some(root) = &mut self.root else {
+            return false;
+        };
+
+        let removed = match root.delete(x) {
+            Removal::NotFound => false,
+            Removal::Removed => true,
+            Removal::Emptied => {
+                self.root = None;
+                true
+            }
+        };
+
+        self.len -= removed as usize;
+        removed
+    }
+
+    /// 大于 x 的最小元素
+    pub fn successor(&self, x: u32) -> Option<u32> {
+        self.root.as_ref()?.successor(x)
+    }
+
+    /// 小于 x 的最大元素
+    pub fn predecessor(&self, x: u32) -> Option<u32> {
+        self.root.as_ref()?.predecessor(x)
+    }
+
+    /// 从小到大遍历, 每一步都是一次后继查询
+    pub fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+        std::iter::successors(self.min(), |&x| self.successor(x))
+    }
+}
+
+impl Default for VebTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FromIterator<u32> for VebTree {
+    fn from_iter<I: IntoIterator<Item = u32>>(iter: I) -> Self {
+        let mut tree = VebTree::new();
+        iter.into_iter().for_each(|x| {
+            tree.insert(x);
+        });
+        tree
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_veb_tree() {
+        let mut t = VebTree::new();
+        assert!(t.is_empty());
+        assert_eq!(t.min(), None);
+        assert_eq!(t.successor(0), None);
+        assert_eq!(t.predecessor(u32::MAX), None);
+        assert!(!t.delete(1));
+
+        for x in [0, u32::MAX, 5, 64, 63, 1 << 16, (1 << 16) - 1] {
+            assert!(t.insert(x));
+        }
+        assert!(!t.insert(5));
+        assert_eq!(t.len(), 7);
+        assert_eq!(t.min(), Some(0));
+        assert_eq!(t.max(), Some(u32::MAX));
+
+        assert_eq!(t.successor(0), Some(5));
+        assert_eq!(t.successor(5), Some(63));
+        assert_eq!(t.successor(64), Some((1 << 16) - 1));
+        assert_eq!(t.successor(1 << 16), Some(u32::MAX));
+        assert_eq!(t.successor(u32::MAX), None);
+        assert_eq!(t.predecessor(0), None);
+        assert_eq!(t.predecessor(63), Some(5));
+        assert_eq!(t.predecessor(u32::MAX), Some(1 << 16));
+
+        assert!(t.delete(0));
+        assert!(t.delete(u32::MAX));
+        assert!(!t.delete(0));
+        assert_eq!(t.min(), Some(5));
+        assert_eq!(t.max(), Some(1 << 16));
+        assert_eq!(
+            t.iter().collect::<Vec<_>>(),
+            vec![5, 63, 64, (1 << 16) - 1, 1 << 16]
+        );
+
+        for x in [5, 63, 64, (1 << 16) - 1, 1 << 16] {
+            assert!(t.delete(x));
+        }
+        assert!(t.is_empty());
+        assert_eq!(t.min(), None);
+    }
+
+    fn check_random(range: u32, ops: usize, seed: u64) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut t = VebTree::new();
+        let mut model = BTreeSet::new();
+        let gen = |rng: &mut StdRng| match range {
+            u32::MAX => rng.gen(),
+            _ => rng.gen_range(0..range),
+        };
+
+        for _ in 0..ops {
+            let x = gen(&mut rng);
+            match rng.gen_range(0..5) {
+                0 | 1 => assert_eq!(t.insert(x), model.insert(x)),
+                2 => assert_eq!(t.delete(x), model.remove(&x)),
+                3 => assert_eq!(t.successor(x), model.range(x..).find(|&&y| y > x).copied()),
+                _ => assert_eq!(t.predecessor(x), model.range(..x).next_back().copied()),
+            }
+
+            assert_eq!(t.len(), model.len());
+            assert_eq!(t.min(), model.first().copied());
+            assert_eq!(t.max(), model.last().copied());
+        }
+
+        assert!(t.iter().eq(model.iter().copied()));
+        assert!(model.iter().all(|&x| t.contains(x)));
+    }
+
+    #[test]
+    fn test_veb_tree_random() {
+        // 小范围内大量重复的插入删除, 以及整个 u32 范围内的稀疏元素
+        check_random(100, 20_000, 1);
+        check_random(1 << 12, 20_000, 2);
+        check_random(u32::MAX, 20_000, 3);
+    }
+}
@@ -0,0 +1,284 @@
+//! Graphviz DOT 导出
+//!
+//! - [The DOT Language](https://graphviz.org/doc/info/lang.html)
+//! - [Graphviz Online](https://dreampuf.github.io/GraphvizOnline/)
+//!
+//! 实现了 [`ToDot`] 的结构可以导出为 DOT 格式, 再用 graphviz 渲染成图片:
+//!
+//! ```text
+//! dot -Tsvg tree.dot -o tree.svg
+//! ```
+//!
+//! 各个结构在自己的模块中实现 [`ToDot`] (需要访问私有的节点), 这里只提供拼接 DOT 文本的 [`Dot`]
+//!
+//! - 二叉树的节点只有一个孩子时, 另一侧会补一个不可见的节点, 这样渲染出来能区分左右孩子
+//! - B 树和 B+ 树的节点使用 record 形状, 一个节点内的多个 key 用竖线分隔
+//! - 红黑树节点按颜色填充, Treap 节点同时显示值和优先级
+//! - 无向图(每条边都存在反向边)输出为 `graph`, 否则输出为 `digraph`
+
+use std::fmt::Write;
+
+/// 可以导出为 Graphviz DOT 格式的结构
+pub trait ToDot {
+    fn to_dot(&self) -> String;
+}
+
+/// DOT 文本构建器, 节点编号由构建器分配
+pub struct Dot {
+    out: String,
+    directed: bool,
+    nodes: usize,
+}
+
+impl Dot {
+    /// 有向图
+    pub fn digraph(name: &str) -> Self {
+        Self::new(name, true)
+    }
+
+    /// 无向图
+    pub fn graph(name: &str) -> Self {
+        Self::new(name, false)
+    }
+
+    fn new(name: &str, directed: bool) -> Self {
+        let kind = if directed { "digraph" } else { "graph" };
+        Self {
+            out: format!("{} \"{}\" {{\n", kind, escape(name)),
+            directed,
+            nodes: 0,
+        }
+    }
+
+    /// 添加一行属性或其他语句, 例如 `node [shape=circle]`
+    pub fn stmt(&mut self, stmt: &str) {
+        writeln!(self.out, "    {};", stmt).unwrap();
+    }
+
+    /// 添加一个节点, 返回节点编号, label 会被转义
+    pub fn node(&mut self, label: &str, attrs: &[(&str, &str)]) -> usize {
+        self.add_node(format!("label=\"{}\"", escape(label)), attrs)
+    }
+
+    /// 添加一个 record 形状的节点, 每个字段之间用竖线分隔
+    pub fn record(&mut self, fields: &[String], attrs: &[(&str, &str)]) -> usize {
+        let label = fields
+            .iter()
+            .map(|f| escape_record(f))
+            .collect::<Vec<_>>()
+            .join("|");
+        self.add_node(format!("shape=record, label=\"{}\"", label), attrs)
+    }
+
+    /// 添加一个不可见的占位节点
+    pub fn invisible(&mut self) -> usize {
+        self.add_node("style=invis, label=\"\"".to_string(), &[])
+    }
+
+    fn add_node(&mut self, mut head: String, attrs: &[(&str, &str)]) -> usize {
+        if !attrs.is_empty() {
+            head.push_str(", ");
+            head.push_str(&attr_list(attrs));
+        }
+
+        let id = self.nodes;
+        self.nodes += 1;
+        writeln!(self.out, "    n{} [{}];", id, head).unwrap();
+        id
+    }
+
+    /// 添加一条边
+    pub fn edge(&mut self, from: usize, to: usize, attrs: &[(&str, &str)]) {
+        let op = if self.directed { "->" } else { "--" };
+        write!(self.out, "    n{} {} n{}", from, op, to).unwrap();
+        if !attrs.is_empty() {
+            write!(self.out, " [{}]", attr_list(attrs)).unwrap();
+        }
+        self.out.push_str(";\n");
+    }
+
+    /// 结束并返回 DOT 文本
+    pub fn finish(mut self) -> String {
+        self.out.push_str("}\n");
+        self.out
+    }
+}
+
+/// `key="value", ...`
+fn attr_list(attrs: &[(&str, &str)]) -> String {
+    attrs
+        .iter()
+        .map(|(key, value)| format!("{}=\"{}\"", key, escape(value)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// 转义双引号字符串中的字符, 换行转换成 `\n`
+pub fn escape(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' | '\\' => {
+                res.push('\\');
+                res.push(c);
+            }
+            '\n' => res.push_str("\\n"),
+            _ => res.push(c),
+        }
+    }
+    res
+}
+
+/// record 的 label 中 `|{}<>` 和空格也有特殊含义
+fn escape_record(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    for c in escape(s).chars() {
+        if matches!(c, '|' | '{' | '}' | '<' | '>' | ' ') {
+            res.push('\\');
+        }
+        res.push(c);
+    }
+    res
+}
+
+/// 递归导出一棵二叉树, 返回根节点编号
+///
+/// children 返回节点的左右孩子, node 在 DOT 中添加节点本身并返回编号
+pub(crate) fn binary_tree<N: Copy>(
+    dot: &mut Dot,
+    root: N,
+    children: &impl Fn(N) -> (Option<N>, Option<N>),
+    node: &impl Fn(&mut Dot, N) -> usize,
+) -> usize {
+    let id = node(dot, root);
+    let (left, right) = children(root);
+    if left.is_none() && right.is_none() {
+        return id;
+    }
+
+    for child in [left, right] {
+        match child {
+            Some(child) => {
+                let child = binary_tree(dot, child, children, node);
+                dot.edge(id, child, &[]);
+            }
+            None => {
+                let nil = dot.invisible();
+                dot.edge(id, nil, &[("style", "invis")]);
+            }
+        }
+    }
+
+    id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dot_builder() {
+        let mut dot = Dot::digraph("t");
+        dot.stmt("node [shape=circle]");
+        let a = dot.node("say \"hi\"\nnext", &[]);
+        let b = dot.record(&["1".to_string(), "a|b".to_string()], &[("color", "red")]);
+        dot.edge(a, b, &[]);
+        dot.edge(b, a, &[("style", "dashed")]);
+
+        assert_eq!(
+            dot.finish(),
+            r#"digraph "t" {
+    node [shape=circle];
+    n0 [label="say \"hi\"\nnext"];
+    n1 [shape=record, label="1|a\|b", color="red"];
+    n0 -> n1;
+    n1 -> n0 [style="dashed"];
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_binary_trees_to_dot() {
+        use crate::{avl_tree::AVLTree, rb_tree::RBTree, treap::Treap};
+
+        let mut t = RBTree::new();
+        for v in [2, 1, 3, 4] {
+            t.insert(v);
+        }
+        let dot = t.to_dot();
+        assert!(dot.starts_with("digraph \"RBTree\" {"));
+        assert!(dot.contains("n1 [label=\"1\", fillcolor=\"black\"];"));
+        assert!(dot.contains("[label=\"4\", fillcolor=\"red\"];"));
+        // 3 只有右孩子, 左侧补一个不可见节点
+        assert_eq!(dot.matches("invis").count(), 2);
+
+        let mut t = AVLTree::new();
+        for v in [1, 2, 3] {
+            t.insert(v);
+        }
+        assert_eq!(
+            t.to_dot(),
+            r#"digraph "AVLTree" {
+    node [shape=circle];
+    n0 [label="2", xlabel="2"];
+    n1 [label="1", xlabel="1"];
+    n0 -> n1;
+    n2 [label="3", xlabel="1"];
+    n0 -> n2;
+}
+"#
+        );
+
+        let mut t = Treap::new();
+        for v in 0..10 {
+            t.insert(v);
+        }
+        let dot = t.to_dot();
+        assert_eq!(dot.matches("\\n").count(), 10);
+        assert_eq!(
+            dot.matches(" -> ").count() - dot.matches("invis\"]").count(),
+            9
+        );
+    }
+
+    #[test]
+    fn test_b_trees_to_dot() {
+        use crate::{b_plus_tree::BPlusTree, b_tree::BTree};
+
+        let mut t = BTree::new(3);
+        assert_eq!(t.to_dot(), "digraph \"BTree\" {\n}\n");
+        for i in 1..=5 {
+            t.insert((i, ()));
+        }
+        let dot = t.to_dot();
+        assert_eq!(
+            dot.matches("shape=record").count(),
+            dot.matches(" -> ").count() + 1
+        );
+
+        let t = BPlusTree::from_sorted_iter((1..=6).map(|i| (i, ())), 3);
+        let dot = t.to_dot();
+        assert!(dot.contains("n0 [shape=record, label=\"3|5\"];"));
+        assert!(dot.contains("label=\"1|2\""));
+        assert_eq!(dot.matches("style=\"dashed\"").count(), 2);
+        assert!(dot.contains("{ rank=same; "));
+    }
+
+    #[test]
+    fn test_graph_to_dot() {
+        use crate::graph_traversal::Graph;
+
+        let g = Graph::from_edges(3, &[(0, 1), (1, 2), (1, 2)], false);
+        let dot = g.to_dot();
+        assert!(dot.starts_with("graph "));
+        assert_eq!(dot.matches(" -- ").count(), 3);
+
+        let g = Graph::from_edges(3, &[(0, 1), (1, 0), (1, 2)], true);
+        let dot = g.to_dot();
+        assert!(dot.starts_with("digraph "));
+        assert!(
+            dot.contains("n0 -> n1;") && dot.contains("n1 -> n0;") && dot.contains("n1 -> n2;")
+        );
+    }
+}
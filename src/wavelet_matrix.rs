@@ -0,0 +1,178 @@
+//! 小波矩阵
+//!
+//! - [OI Wiki - 小波树](https://oi-wiki.org/ds/wavelet-tree/)
+//! - [The Wavelet Matrix](https://users.dcc.uchile.cl/~gnavarro/ps/spire12.4.pdf)
+//!
+//! 用于静态数组上的区间第 k 小、区间内小于 x 的元素个数等查询, 每次查询 O(logσ), σ 是不同值的数量
+//!
+//! 先把所有值离散化成排名, 排名的二进制位数为 L, 然后从最高位开始逐层处理:
+//!
+//! - 记录当前数组中每个元素这一位是 0 还是 1, 以及前缀中 1 的个数
+//! - 把这一位是 0 的元素稳定地放到前面, 1 的元素放到后面, 作为下一层的数组
+//!
+//! 查询区间 `[l, r)` 时, 这一层中 0 的元素在下一层会落到 `[rank0(l), rank0(r))`,
+//! 1 的元素会落到 `[zeros + rank1(l), zeros + rank1(r))`, 其中 zeros 是这一层 0 的总数
+//!
+//! 求第 k 小时, 如果区间中这一位是 0 的元素数量 c > k, 说明答案这一位是 0, 进入 0 的区间,
+//! 否则答案这一位是 1, k 减去 c 后进入 1 的区间, 逐层确定答案的每一位
+//!
+//! 这里每层直接保存前缀和数组而不是使用简洁位向量, 空间是 O(n logσ) 个整数
+
+/// 小波矩阵
+pub struct WaveletMatrix {
+    /// 离散化后的所有不同值
+    values: Vec<i64>,
+    /// 每一层前 i 个元素中这一位为 1 的个数
+    ones: Vec<Vec<usize>>,
+    /// 每一层这一位为 0 的元素个数
+    zeros: Vec<usize>,
+}
+
+impl WaveletMatrix {
+    pub fn new(arr: &[i64]) -> Self {
+        let mut values = arr.to_vec();
+        values.sort_unstable();
+        values.dedup();
+
+        let bits = (usize::BITS - values.len().leading_zeros()) as usize;
+        let mut curr: Vec<usize> = arr
+            .iter()
+            .map(|v| values.binary_search(v).unwrap())
+            .collect();
+
+        let mut ones = Vec::with_capacity(bits);
+        let mut zeros = Vec::with_capacity(bits);
+        for level in (0..bits).rev() {
+            let mut prefix = Vec::with_capacity(curr.len() + 1);
+            prefix.push(0);
+            for &x in &curr {
+                prefix.push(prefix.last().unwrap() + (x >> level & 1));
+            }
+
+            let (zero, one): (Vec<usize>, Vec<usize>) =
+                curr.iter().partition(|&&x| x >> level & 1 == 0);
+            zeros.push(zero.len());
+            ones.push(prefix);
+            curr = zero.into_iter().chain(one).collect();
+        }
+
+        Self {
+            values,
+            ones,
+            zeros,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.ones.first().map_or(0, |p| p.len() - 1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 区间 `[l, r]` 在某一层中 0 的元素和 1 的元素分别落到下一层的区间
+    fn next(&self, depth: usize, l: usize, r: usize, bit: usize) -> (usize, usize) {
+        let ones = &self.ones[depth];
+        if bit == 0 {
+            (l - ones[l], r - ones[r])
+        } else {
+            (self.zeros[depth] + ones[l], self.zeros[depth] + ones[r])
+        }
+    }
+
+    /// 区间 `[l, r]` 中第 k 小的值, k 从 0 开始
+    pub fn kth(&self, l: usize, r: usize, mut k: usize) -> Option<i64> {
+        assert!(l <= r && r < self.len(), "range out of bounds");
+        let (mut lo, mut hi) = (l, r + 1);
+        if k >= hi - lo {
+            return None;
+        }
+
+        let mut rank = 0;
+        for depth in 0..self.ones.len() {
+            let ones = &self.ones[depth];
+            let c = (hi - lo) - (ones[hi] - ones[lo]);
+            let bit = if k < c {
+                0
+            } else {
+                k -= c;
+                1
+            };
+
+            rank = rank << 1 | bit;
+            (lo, hi) = self.next(depth, lo, hi, bit);
+        }
+
+        Some(self.values[rank])
+    }
+
+    /// 区间 `[l, r]` 中小于 x 的元素个数
+    pub fn count_less(&self, l: usize, r: usize, x: i64) -> usize {
+        assert!(l <= r && r < self.len(), "range out of bounds");
+        // 小于 x 等价于排名小于 x 的离散化位置
+        let target = self.values.partition_point(|&v| v < x);
+        let bits = self.ones.len();
+        let (mut lo, mut hi) = (l, r + 1);
+        let mut count = 0;
+
+        for depth in 0..bits {
+            let bit = target >> (bits - 1 - depth) & 1;
+            if bit == 1 {
+                // 这一位是 0 的元素都小于 target
+                let ones = &self.ones[depth];
+                count += (hi - lo) - (ones[hi] - ones[lo]);
+            }
+            (lo, hi) = self.next(depth, lo, hi, bit);
+        }
+
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_wavelet_matrix() {
+        let wm = WaveletMatrix::new(&[5, 1, 4, 1, 3, 9, 2, 6]);
+        assert_eq!(wm.len(), 8);
+        assert_eq!(wm.kth(0, 7, 0), Some(1));
+        assert_eq!(wm.kth(0, 7, 7), Some(9));
+        assert_eq!(wm.kth(2, 5, 1), Some(3));
+        assert_eq!(wm.kth(2, 5, 4), None);
+        assert_eq!(wm.count_less(0, 7, 4), 4);
+        assert_eq!(wm.count_less(0, 7, 100), 8);
+        assert_eq!(wm.count_less(0, 7, -100), 0);
+
+        let wm = WaveletMatrix::new(&[7, 7, 7]);
+        assert_eq!(wm.kth(0, 2, 2), Some(7));
+        assert_eq!(wm.count_less(0, 2, 8), 3);
+        assert!(WaveletMatrix::new(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_wavelet_matrix_random() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..20 {
+            let n = rng.gen_range(1..200);
+            let arr: Vec<i64> = (0..n).map(|_| rng.gen_range(-50..50)).collect();
+            let wm = WaveletMatrix::new(&arr);
+
+            for _ in 0..200 {
+                let l = rng.gen_range(0..n);
+                let r = rng.gen_range(l..n);
+                let mut sorted = arr[l..=r].to_vec();
+                sorted.sort();
+
+                let k = rng.gen_range(0..sorted.len());
+                assert_eq!(wm.kth(l, r, k), Some(sorted[k]));
+
+                let x = rng.gen_range(-60..60);
+                assert_eq!(wm.count_less(l, r, x), sorted.partition_point(|&v| v < x));
+            }
+        }
+    }
+}